@@ -0,0 +1,59 @@
+//! Block execution benchmarks: `cargo bench --bench execution`.
+//!
+//! Applies a large synthetic block of payments on a `ChainState` one
+//! transaction after the other and with `parallel::apply_parallel`. With
+//! independent payments (distinct senders and receivers) the groups spread over
+//! every core; with every payment going to the same receiver they form a single
+//! group and run in order, which shows the cost of planning.
+
+use std::hint::black_box;
+use std::thread;
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use blockc::entity::entity::Entity;
+use blockc::state::parallel;
+use blockc::state::state::ChainState;
+use blockc::transactions::transactions::{Transaction, TransactionExt};
+
+/// Payments in the synthetic block.
+const BLOCK_TRANSACTIONS: usize = 20_000;
+
+// Funded senders paying `receiver(i)`, and the state they are applied on
+fn synthetic_block(receiver: impl Fn(usize) -> String) -> (ChainState, Vec<Transaction>) {
+    let mut state = ChainState::new();
+    let mut transactions = Vec::with_capacity(BLOCK_TRANSACTIONS);
+    for i in 0..BLOCK_TRANSACTIONS {
+        let name = format!("sender{}", i);
+        state.credit(&name, 10.0);
+        let mut sender = Entity::new(name, 10.0, Vec::new(), "pub".to_string(), "priv".to_string());
+        transactions.push(Transaction::create_payment(&mut sender, &receiver(i), 1.0, 0.1).unwrap());
+    }
+    (state, transactions)
+}
+
+fn execute(c: &mut Criterion) {
+    let threads = thread::available_parallelism().map(|threads| threads.get()).unwrap_or(1);
+    let blocks = [
+        ("independent", synthetic_block(|i| format!("receiver{}", i))),
+        ("one_receiver", synthetic_block(|_| "receiver".to_string())),
+    ];
+
+    let mut group = c.benchmark_group("execute_block");
+    group.sample_size(20);
+    group.throughput(Throughput::Elements(BLOCK_TRANSACTIONS as u64));
+    for (name, (state, transactions)) in &blocks {
+        group.bench_function(format!("{}/sequential", name), |b| b.iter_batched(
+            || state.clone(),
+            |mut state| parallel::apply_parallel(&mut state, black_box(transactions), 1).unwrap(),
+            BatchSize::LargeInput,
+        ));
+        group.bench_function(format!("{}/parallel", name), |b| b.iter_batched(
+            || state.clone(),
+            |mut state| parallel::apply_parallel(&mut state, black_box(transactions), threads).unwrap(),
+            BatchSize::LargeInput,
+        ));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, execute);
+criterion_main!(benches);
@@ -0,0 +1,94 @@
+//! Block hash algorithm benchmarks: `cargo bench --bench hash_algorithms`.
+//!
+//! Compares every `HashAlgorithm` on what a chain does with it: hashing one
+//! header, mining (many headers, one nonce each), validating a long chain
+//! (every block hash and Merkle root recomputed) and computing transaction
+//! ids, plus hashing 64 KiB to show long inputs. SHA-256 is the default and
+//! the baseline. Transaction ids (the Merkle leaves) are SHA-256 on every
+//! chain, so validation depends less on the header algorithm than mining.
+
+use std::hint::black_box;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use blockc::block::block::{Block, HashAlgorithm};
+use blockc::blockchain::blockchain::Blockchain;
+use blockc::entity::entity::Entity;
+use blockc::transactions::transactions::{Transaction, TransactionExt};
+use blockc_core::hash::sha256_hex;
+
+/// Blocks of the chain validated by `validate_chain`.
+const CHAIN_LENGTH: usize = 1_000;
+
+fn unmined_block() -> Block {
+    let root = sha256_hex("state");
+    Block::new_at(Vec::new(), sha256_hex("previous"), 0.1, root, 1_700_000_000)
+}
+
+// Empty blocks (two coinbases each) mined at difficulty 1
+fn long_chain(algorithm: HashAlgorithm) -> Blockchain {
+    let mut chain = Blockchain::with_hash_algorithm(1, 50.0, algorithm);
+    for _ in 1..CHAIN_LENGTH {
+        chain.add_block(Vec::new(), "Miner").unwrap();
+    }
+    chain
+}
+
+fn header_hash(c: &mut Criterion) {
+    let block = unmined_block();
+    let mut group = c.benchmark_group("header_hash");
+    for algorithm in HashAlgorithm::ALL {
+        group.bench_function(algorithm.name(), |b| b.iter(|| black_box(&block).calculate_hash_with(algorithm)));
+    }
+    group.finish();
+}
+
+fn mine(c: &mut Criterion) {
+    // Nonces tried per iteration (mining is this loop until a hash is good enough)
+    let attempts = 10_000;
+    let block = unmined_block();
+    let mut group = c.benchmark_group("mine_nonces");
+    group.throughput(Throughput::Elements(attempts as u64));
+    for algorithm in HashAlgorithm::ALL {
+        let hasher = block.header_hasher_with(algorithm);
+        group.bench_function(algorithm.name(), |b| b.iter(|| {
+            for nonce in 0..attempts {
+                black_box(hasher.hash(nonce));
+            }
+        }));
+    }
+    group.finish();
+}
+
+fn validate_chain(c: &mut Criterion) {
+    let mut group = c.benchmark_group("validate_chain");
+    group.sample_size(20);
+    group.throughput(Throughput::Elements(CHAIN_LENGTH as u64));
+    for algorithm in HashAlgorithm::ALL {
+        let chain = long_chain(algorithm);
+        group.bench_with_input(BenchmarkId::from_parameter(algorithm.name()), &chain, |b, chain| b.iter(|| chain.validate().unwrap()));
+    }
+    group.finish();
+}
+
+fn transaction_id(c: &mut Criterion) {
+    let mut alice = Entity::new("Alice".to_string(), 1_000.0, Vec::new(), "pub".to_string(), sha256_hex("key"));
+    let payment = Transaction::create_payment(&mut alice, "Bob", 10.0, 0.1).unwrap();
+    let mut group = c.benchmark_group("transaction_id");
+    for algorithm in [HashAlgorithm::Sha256, HashAlgorithm::Blake3] {
+        group.bench_function(algorithm.name(), |b| b.iter(|| black_box(&payment).id_with(algorithm)));
+    }
+    group.finish();
+}
+
+// Long inputs, where BLAKE3's parallel chunks pay off
+fn bulk(c: &mut Criterion) {
+    let data = "a".repeat(64 * 1024);
+    let mut group = c.benchmark_group("bulk_64kib");
+    group.throughput(Throughput::Bytes(data.len() as u64));
+    for algorithm in HashAlgorithm::ALL {
+        group.bench_function(algorithm.name(), |b| b.iter(|| algorithm.hash_hex(black_box(&data))));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, header_hash, mine, validate_chain, transaction_id, bulk);
+criterion_main!(benches);
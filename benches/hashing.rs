@@ -0,0 +1,91 @@
+//! Hashing benchmarks: `cargo bench --bench hashing`.
+//!
+//! Each group compares the current hashing path with the one it replaced
+//! (formatting every hash byte with `format!("{:02x}")` and building the whole
+//! preimage string before hashing), kept here as the baseline. Mining also
+//! compares hashing the whole header per attempt with `Block::mine`, which
+//! only hashes the nonce and the rest of the header after the cached prefix.
+
+use std::hint::black_box;
+use criterion::{criterion_group, criterion_main, Criterion};
+use sha2::{Digest, Sha256};
+use blockc::block::block::{Block, BlockExt};
+use blockc::entity::entity::Entity;
+use blockc_core::hash::{sha256_hex, Domain};
+
+fn legacy_sha256_hex(data: &str) -> String {
+    let result = Sha256::digest(data.as_bytes());
+    result.iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>()
+}
+
+fn legacy_block_hash(block: &Block) -> String {
+    legacy_sha256_hex(&format!(
+        "{}{}{}{}{}{}{}",
+        Domain::BlockHeader.tag(),
+        block.previous_block_hash, block.time_stamp, block.nonce, block.base_fee, block.merkle_root, block.state_root
+    ))
+}
+
+fn legacy_mine(block: &mut Block, difficulty: u32) {
+    loop {
+        let hash = legacy_block_hash(block);
+        if Block::hash_meets_difficulty(&hash, difficulty) {
+            block.block_hash = hash;
+            return;
+        }
+        block.nonce += 1;
+    }
+}
+
+// Rebuilds and hashes the whole header on every attempt
+fn streamed_mine(block: &mut Block, difficulty: u32) {
+    while !Block::hash_meets_difficulty(&block.calculate_hash(), difficulty) {
+        block.nonce += 1;
+    }
+    block.block_hash = block.calculate_hash();
+}
+
+fn unmined_block() -> Block {
+    let root = sha256_hex("state");
+    Block::new_at(Vec::new(), sha256_hex("previous"), 0.1, root, 1_700_000_000)
+}
+
+fn sha256(c: &mut Criterion) {
+    let data = "a".repeat(200);
+    let mut group = c.benchmark_group("sha256_hex");
+    group.bench_function("format_per_byte", |b| b.iter(|| legacy_sha256_hex(black_box(&data))));
+    group.bench_function("hex_encode", |b| b.iter(|| sha256_hex(black_box(&data))));
+    group.finish();
+}
+
+fn block_hash(c: &mut Criterion) {
+    let block = unmined_block();
+    let mut group = c.benchmark_group("block_hash");
+    group.bench_function("format_then_hash", |b| b.iter(|| legacy_block_hash(black_box(&block))));
+    group.bench_function("streamed", |b| b.iter(|| black_box(&block).calculate_hash()));
+    group.finish();
+}
+
+fn sign(c: &mut Criterion) {
+    let alice = Entity::new("Alice".to_string(), 0.0, Vec::new(), "pub".to_string(), sha256_hex("key"));
+    let data = "transfer|Alice|Bob|10|0.1|0|1700000000";
+    let mut group = c.benchmark_group("sign");
+    group.bench_function("format_then_hash", |b| b.iter(|| legacy_sha256_hex(&format!("{}{}{}", Domain::Signature.tag(), black_box(data), alice.private_key))));
+    group.bench_function("streamed", |b| b.iter(|| alice.sign(black_box(data))));
+    group.finish();
+}
+
+fn mine(c: &mut Criterion) {
+    // The same block needs the same nonces either way: only the hashing differs
+    let mut group = c.benchmark_group("mine_difficulty_3");
+    group.sample_size(20);
+    group.bench_function("format_then_hash", |b| b.iter(|| legacy_mine(&mut unmined_block(), 3)));
+    group.bench_function("streamed", |b| b.iter(|| streamed_mine(&mut unmined_block(), 3)));
+    group.bench_function("incremental", |b| b.iter(|| unmined_block().mine(3)));
+    group.finish();
+}
+
+criterion_group!(benches, sha256, block_hash, sign, mine);
+criterion_main!(benches);
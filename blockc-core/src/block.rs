@@ -0,0 +1,291 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::hash::HashAlgorithm;
+use crate::header::{self, BlockHeader, HeaderHasher};
+use crate::merkle::{build_proof, merkle_root, MerkleProof};
+use crate::ordering::is_canonical;
+use crate::transaction::Transaction;
+
+/// Represents a block in the blockchain.
+/// 
+/// Each block contains:
+/// - A hash of its own data
+/// - A hash of the previous block (linking blocks together)
+/// - A list of transactions
+/// - The Merkle root of those transactions
+/// - The state root (commitment to all account balances and nonces after the block)
+/// - A timestamp
+/// - A nonce (used for proof-of-work mining)
+/// 
+/// # Fields
+/// 
+/// * `block_hash` - Hash of this block's data (SHA-256 unless the chain uses another `HashAlgorithm`)
+/// * `previous_block_hash` - Hash of the previous block in the chain
+/// * `transaction` - Vector of transactions included in this block
+/// * `time_stamp` - Unix timestamp when block was created (u32, valid until 2106)
+/// * `nonce` - Proof-of-work value (will be used for mining)
+/// * `base_fee` - Minimum fee every transaction in this block had to pay (burned)
+/// * `merkle_root` - Merkle root of the transaction ids
+/// * `state_root` - Hash of the account state after applying this block (see `ChainState::state_root`)
+/// 
+/// # Hash Calculation
+/// 
+/// The block hash is calculated from:
+/// - Previous block hash
+/// - Timestamp
+/// - Nonce
+/// - Base fee
+/// - Merkle root (covers all transaction data, signatures included)
+/// - State root
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block{
+
+    pub block_hash: String,
+    pub previous_block_hash: String,
+    pub transaction: Vec<Transaction>,
+    pub time_stamp: u32, // small because of project size, good until 2106
+    pub nonce: u32,
+    pub base_fee: f64,
+    pub merkle_root: String,
+    pub state_root: String,
+
+}
+
+impl Block {
+    /// Creates an unmined block stamped with `time_stamp` instead of the current time.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc_core::block::Block;
+    /// 
+    /// let block = Block::new_at(Vec::new(), "0".to_string(), 0.0, String::new(), 1_700_000_000);
+    /// assert_eq!(block.time_stamp, 1_700_000_000);
+    /// assert_eq!(block.block_hash, block.calculate_hash());
+    /// ```
+    pub fn new_at(transaction: Vec<Transaction>, previous_block_hash: String, base_fee: f64, state_root: String, time_stamp: u32) -> Self
+    {
+        let nonce = 0;
+        let merkle_root = Block::merkle_root_of(&transaction);
+
+        // create hash using helper fn
+        let block_hash = Block::hash(
+        &previous_block_hash,
+        time_stamp,
+        nonce,
+        base_fee,
+        &merkle_root,
+        &state_root
+        );
+
+        // create block
+        Block {
+            block_hash: block_hash,
+            previous_block_hash: previous_block_hash,
+            transaction: transaction,
+            time_stamp: time_stamp,
+            nonce: nonce,
+            base_fee: base_fee,
+            merkle_root: merkle_root,
+            state_root: state_root,
+        }
+    }
+
+    /// Returns the header of this block.
+    pub fn header(&self) -> BlockHeader {
+        BlockHeader {
+            block_hash: self.block_hash.clone(),
+            previous_block_hash: self.previous_block_hash.clone(),
+            time_stamp: self.time_stamp,
+            nonce: self.nonce,
+            base_fee: self.base_fee,
+            merkle_root: self.merkle_root.clone(),
+            state_root: self.state_root.clone(),
+        }
+    }
+
+    /// Calculates the Merkle root of a list of transactions (leaves are transaction ids).
+    pub fn merkle_root_of(transaction: &[Transaction]) -> String {
+        let ids: Vec<String> = transaction.iter().map(|t| t.id()).collect();
+        merkle_root(&ids)
+    }
+
+    /// Recalculates the Merkle root from this block's transactions.
+    /// 
+    /// It must match `merkle_root`, otherwise a transaction was changed after the
+    /// block was created.
+    pub fn calculate_merkle_root(&self) -> String {
+        Block::merkle_root_of(&self.transaction)
+    }
+
+    /// Builds a Merkle proof that the transaction with id `transaction_id` is in this block.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Some(MerkleProof)` - Proof to verify against `merkle_root`
+    /// * `None` - If the transaction is not in this block
+    pub fn prove_transaction(&self, transaction_id: &str) -> Option<MerkleProof> {
+        let ids: Vec<String> = self.transaction.iter().map(|t| t.id()).collect();
+        let index = ids.iter().position(|id| id == transaction_id)?;
+        build_proof(&ids, index)
+    }
+
+    /// Calculates the SHA-256 hash of a block's data.
+    /// 
+    /// This is a static function that can be called without a Block instance,
+    /// which is useful when creating a new block (before `self` exists).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `previous_hash` - Hash of the previous block
+    /// * `time_stamp` - Block creation timestamp
+    /// * `nonce` - Proof-of-work nonce value
+    /// * `base_fee` - Base fee of the block
+    /// * `merkle_root` - Merkle root of the block's transactions
+    /// * `state_root` - State root after the block
+    /// 
+    /// # Returns
+    /// 
+    /// A hexadecimal string representing the SHA-256 hash.
+    pub fn hash(previous_hash: &str, time_stamp: u32, nonce: u32, base_fee: f64, merkle_root: &str, state_root: &str) -> String
+    {
+        header::block_hash(previous_hash, time_stamp, nonce, base_fee, merkle_root, state_root)
+    }
+
+    /// Calculates the hash of this block instance.
+    /// 
+    /// Convenience method that calls the static `hash()` function with
+    /// this block's data.
+    /// 
+    /// # Returns
+    /// 
+    /// A hexadecimal string representing the SHA-256 hash of this block.
+    pub fn calculate_hash(&self) -> String {
+        Block::hash(
+            &self.previous_block_hash,
+            self.time_stamp,
+            self.nonce,
+            self.base_fee,
+            &self.merkle_root,
+            &self.state_root
+        )
+    }
+
+    /// Calculates the hash of this block with the hash function of its chain.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc_core::block::Block;
+    /// use blockc_core::hash::HashAlgorithm;
+    /// 
+    /// let block = Block::new_at(Vec::new(), "0".to_string(), 0.0, String::new(), 1_700_000_000);
+    /// assert_eq!(block.calculate_hash_with(HashAlgorithm::Sha256), block.calculate_hash());
+    /// assert_ne!(block.calculate_hash_with(HashAlgorithm::DoubleSha256), block.calculate_hash());
+    /// ```
+    pub fn calculate_hash_with(&self, algorithm: HashAlgorithm) -> String {
+        header::block_hash_with(
+            algorithm,
+            &self.previous_block_hash,
+            self.time_stamp,
+            self.nonce,
+            self.base_fee,
+            &self.merkle_root,
+            &self.state_root
+        )
+    }
+
+    /// Returns `true` if `hash` starts with `difficulty` zeros.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `hash` - Hexadecimal block hash
+    /// * `difficulty` - Number of leading zeros required
+    pub fn hash_meets_difficulty(hash: &str, difficulty: u32) -> bool {
+        header::hash_meets_difficulty(hash, difficulty)
+    }
+
+    /// Returns `true` if this block's hash satisfies the proof-of-work `difficulty`.
+    pub fn meets_difficulty(&self, difficulty: u32) -> bool {
+        Block::hash_meets_difficulty(&self.block_hash, difficulty)
+    }
+
+    /// Returns a hasher of this block's header for any nonce (see `HeaderHasher`).
+    /// 
+    /// The header's other fields are read once: build a new hasher after
+    /// changing them.
+    pub fn header_hasher(&self) -> HeaderHasher {
+        self.header_hasher_with(HashAlgorithm::Sha256)
+    }
+
+    /// Like `header_hasher`, for a chain hashing its headers with `algorithm`.
+    pub fn header_hasher_with(&self, algorithm: HashAlgorithm) -> HeaderHasher {
+        HeaderHasher::with_algorithm(algorithm, &self.previous_block_hash, self.time_stamp, self.base_fee, &self.merkle_root, &self.state_root)
+    }
+}
+
+/// Checks the block at `height` on its own and against the block before it
+/// (`None` for the genesis): everything that needs no chain state (hash, Merkle
+/// root, proof of work, link, timestamp, base fee and canonical order).
+///
+/// # Example
+///
+/// ```
+/// use blockc_core::block::{check_block, Block};
+/// use blockc_core::hash::HashAlgorithm;
+///
+/// let genesis = Block::new_at(Vec::new(), "0".to_string(), 0.0, String::new(), 1_700_000_000);
+/// assert!(check_block(&genesis, None, 0, 1, HashAlgorithm::Sha256, "treasury").is_ok());
+///
+/// let next = Block::new_at(Vec::new(), genesis.block_hash.clone(), 0.0, String::new(), 1_600_000_000);
+/// assert!(check_block(&next, Some(&genesis), 1, 0, HashAlgorithm::Sha256, "treasury").unwrap_err().contains("timestamp"));
+/// ```
+pub fn check_block(current: &Block, previous: Option<&Block>, height: usize, difficulty: u32, hash_algorithm: HashAlgorithm, treasury_address: &str) -> Result<(), String> {
+    // Check if the block's hash matches its calculated hash
+    if current.block_hash != current.calculate_hash_with(hash_algorithm) {
+        return Err("hash does not match its data".to_string());
+    }
+
+    // Check the transactions still match the Merkle root in the header
+    if current.merkle_root != current.calculate_merkle_root() {
+        return Err("Merkle root does not match its transactions".to_string());
+    }
+
+    // Validate genesis block
+    let Some(previous) = previous else {
+        if current.previous_block_hash != "0" {
+            return Err("genesis does not point to \"0\"".to_string());
+        }
+        return Ok(());
+    };
+
+    // Check the proof of work
+    if !current.meets_difficulty(difficulty) {
+        return Err(format!("hash does not meet difficulty {}", difficulty));
+    }
+
+    // Check if current block's previous_hash matches previous block's hash
+    if current.previous_block_hash != previous.block_hash {
+        return Err(format!("previous hash does not match block {}", height - 1));
+    }
+
+    if current.time_stamp < previous.time_stamp {
+        return Err(format!("timestamp is before block {}", height - 1));
+    }
+
+    // Check every user transaction met the block's base fee
+    let underpaid = current.transaction.iter()
+        .filter(|t| !t.is_coinbase() && t.sender_address != treasury_address)
+        .any(|t| t.fee < current.base_fee);
+    if underpaid {
+        return Err("a transaction paid less than the base fee".to_string());
+    }
+
+    // Check the user transactions are in the order every node applies them in
+    let coinbase_count = current.transaction.iter().take_while(|t| t.is_coinbase()).count();
+    if !is_canonical(&current.transaction[coinbase_count..]) {
+        return Err("transactions are not in canonical order".to_string());
+    }
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use crate::transaction::TransactionKind;
+
+/// Gas every transaction costs, whatever it does (like a plain transfer on Ethereum).
+pub const TRANSACTION_GAS: u64 = 21_000;
+
+/// Extra gas per output of a multi-recipient payment, per input or output of a UTXO
+/// (CoinJoin or confidential) transaction and per member of a withdrawal's ring.
+pub const OUTPUT_GAS: u64 = 1_000;
+
+/// Extra gas per instruction of deployed contract code.
+pub const CODE_INSTRUCTION_GAS: u64 = 200;
+
+/// Extra gas per argument passed to a contract call.
+pub const INPUT_GAS: u64 = 10;
+
+/// Gas a transaction of the given kind costs before any contract code runs.
+///
+/// A transaction's gas limit must cover at least this amount. Everything above it
+/// is available to the contract code of a call.
+///
+/// # Example
+///
+/// ```
+/// use blockc_core::gas::{intrinsic_gas, TRANSACTION_GAS};
+/// use blockc_core::transaction::TransactionKind;
+///
+/// assert_eq!(intrinsic_gas(&TransactionKind::Transfer), TRANSACTION_GAS);
+/// ```
+pub fn intrinsic_gas(kind: &TransactionKind) -> u64 {
+    let extra = match kind {
+        TransactionKind::MultiPay { outputs } => outputs.len() as u64 * OUTPUT_GAS,
+        TransactionKind::Utxo { inputs, outputs } => (inputs.len() + outputs.len()) as u64 * OUTPUT_GAS,
+        TransactionKind::CoinJoin { inputs, outputs } => (inputs.len() + outputs.len()) as u64 * OUTPUT_GAS,
+        TransactionKind::Deploy { code } => code.len() as u64 * CODE_INSTRUCTION_GAS,
+        TransactionKind::Call { input, .. } => input.len() as u64 * INPUT_GAS,
+        #[cfg(feature = "privacy")]
+        TransactionKind::ConfidentialTransfer { inputs, outputs, .. } => (inputs.len() + outputs.len()) as u64 * OUTPUT_GAS,
+        #[cfg(feature = "privacy")]
+        TransactionKind::RingWithdraw { ring, .. } => ring.len() as u64 * OUTPUT_GAS,
+        _ => 0,
+    };
+    TRANSACTION_GAS + extra
+}
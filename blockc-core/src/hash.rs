@@ -0,0 +1,374 @@
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+
+/// Hashes `data` with SHA-256.
+///
+/// # Returns
+///
+/// The hash as a hexadecimal string.
+///
+/// # Example
+///
+/// ```
+/// use blockc_core::hash::sha256_hex;
+///
+/// assert_eq!(sha256_hex("abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+/// ```
+pub fn sha256_hex(data: &str) -> String {
+    sha256_parts_hex(&[data])
+}
+
+/// Hashes the concatenation of `parts` with SHA-256, without building it.
+///
+/// # Returns
+///
+/// The hash as a hexadecimal string, the same as `sha256_hex(&parts.concat())`.
+///
+/// # Example
+///
+/// ```
+/// use blockc_core::hash::{sha256_hex, sha256_parts_hex};
+///
+/// assert_eq!(sha256_parts_hex(&["ab", "c"]), sha256_hex("abc"));
+/// ```
+pub fn sha256_parts_hex(parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Hashes formatted text with SHA-256, streaming it into the hasher instead of
+/// building the string first (see `format_args!`).
+///
+/// # Returns
+///
+/// The hash as a hexadecimal string, the same as `sha256_hex(&format!(...))`.
+///
+/// # Example
+///
+/// ```
+/// use blockc_core::hash::{sha256_fmt_hex, sha256_hex};
+///
+/// assert_eq!(sha256_fmt_hex(format_args!("{}{}", "block", 7)), sha256_hex("block7"));
+/// ```
+pub fn sha256_fmt_hex(args: fmt::Arguments<'_>) -> String {
+    let mut writer = HashWriter(Sha256::new());
+    // Writing to a hasher never fails
+    let _ = fmt::write(&mut writer, args);
+    hex::encode(writer.0.finalize())
+}
+
+/// Feeds formatted text to a hasher.
+struct HashWriter<H>(H);
+
+impl fmt::Write for HashWriter<Sha256> {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        self.0.update(text.as_bytes());
+        Ok(())
+    }
+}
+
+impl fmt::Write for HashWriter<AlgorithmHasher> {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        Hasher::update(&mut self.0, text.as_bytes());
+        Ok(())
+    }
+}
+
+/// Hashes two Merkle nodes together (left first), tagged as a Merkle node.
+pub fn hash_pair(left: &str, right: &str) -> String {
+    HashAlgorithm::Sha256.tagged_parts_hex(Domain::MerkleNode, &[left, right])
+}
+
+/// What a hash is computed for.
+///
+/// The input of every hash the chain commits to starts with the tag of its
+/// domain, so a hash computed for one purpose can never be taken for another:
+/// a signature is not a transaction id over the same bytes, and a Merkle node
+/// is not a transaction id (which would let a proof pass off an inner node as
+/// a leaf). Tags end with a NUL byte, so none is a prefix of another.
+///
+/// Hashes that must match other systems (HTLC hash locks) and the state trie
+/// keep their own formats.
+///
+/// * `TransactionId` - Transaction ids (`sign::transaction_id`)
+/// * `BlockHeader` - Block hashes (`header::block_hash`)
+/// * `Signature` - Signatures (`sign::sign`)
+/// * `MerkleNode` - Inner nodes of transaction Merkle trees (`hash_pair`)
+/// * `Address` - Addresses derived from public keys (`sign::address`)
+///
+/// # Example
+///
+/// ```
+/// use blockc_core::hash::{sha256_parts_hex, Domain, HashAlgorithm};
+///
+/// let id = HashAlgorithm::Sha256.tagged_parts_hex(Domain::TransactionId, &["data", "sig"]);
+/// assert_eq!(id, sha256_parts_hex(&["blockc:transaction-id\0", "data", "sig"]));
+/// assert_ne!(id, HashAlgorithm::Sha256.tagged_parts_hex(Domain::Signature, &["data", "sig"]));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Domain {
+    TransactionId,
+    BlockHeader,
+    Signature,
+    MerkleNode,
+    Address,
+}
+
+impl Domain {
+    /// Returns the bytes hashed before the input.
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Domain::TransactionId => "blockc:transaction-id\0",
+            Domain::BlockHeader => "blockc:block-header\0",
+            Domain::Signature => "blockc:signature\0",
+            Domain::MerkleNode => "blockc:merkle-node\0",
+            Domain::Address => "blockc:address\0",
+        }
+    }
+}
+
+/// A hash function with 32-byte digests, fed incrementally.
+///
+/// Implementations are cheap to clone, so a hasher fed a common prefix can be
+/// cloned for each message sharing it (see `HeaderHasher`).
+///
+/// # Example
+///
+/// ```
+/// use blockc_core::hash::{Hasher, Sha256Hasher};
+///
+/// let mut hasher = Sha256Hasher::default();
+/// hasher.update(b"ab");
+/// hasher.update(b"c");
+/// assert_eq!(hex::encode(hasher.finalize()), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+/// ```
+pub trait Hasher: Clone {
+    /// Feeds `data` to the hash.
+    fn update(&mut self, data: &[u8]);
+
+    /// Returns the digest of everything fed so far.
+    fn finalize(self) -> [u8; 32];
+}
+
+/// SHA-256, as BlockC hashes by default.
+#[derive(Clone, Default)]
+pub struct Sha256Hasher(Sha256);
+
+impl Hasher for Sha256Hasher {
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    fn finalize(self) -> [u8; 32] {
+        self.0.finalize().into()
+    }
+}
+
+/// SHA-256 of the SHA-256 digest, as Bitcoin hashes its block headers.
+#[derive(Clone, Default)]
+pub struct DoubleSha256Hasher(Sha256);
+
+impl Hasher for DoubleSha256Hasher {
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    fn finalize(self) -> [u8; 32] {
+        Sha256::digest(self.0.finalize()).into()
+    }
+}
+
+/// Keccak-256 (the original padding, not NIST SHA3-256), as Ethereum hashes.
+#[derive(Clone, Default)]
+pub struct Keccak256Hasher(Keccak256);
+
+impl Hasher for Keccak256Hasher {
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    fn finalize(self) -> [u8; 32] {
+        self.0.finalize().into()
+    }
+}
+
+/// BLAKE3: several times faster than SHA-256 on long inputs, about as fast on
+/// block headers (see `cargo bench --bench hash_algorithms`).
+#[derive(Clone, Default)]
+pub struct Blake3Hasher(blake3::Hasher);
+
+impl Hasher for Blake3Hasher {
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    fn finalize(self) -> [u8; 32] {
+        self.0.finalize().into()
+    }
+}
+
+/// The hash function a chain uses for its block headers.
+///
+/// Chosen in the chain's parameters (the genesis definition), so a network can
+/// mimic the hashing of a real chain. The hash is always 32 bytes, shown as 64
+/// hex digits, so difficulties mean the same for every algorithm.
+///
+/// * `Sha256` - SHA-256 (the default)
+/// * `DoubleSha256` - SHA-256 applied twice, like Bitcoin
+/// * `Keccak256` - Keccak-256, like Ethereum
+/// * `Blake3` - BLAKE3, for speed
+///
+/// # Example
+///
+/// ```
+/// use blockc_core::hash::{sha256_hex, HashAlgorithm};
+///
+/// assert_eq!(HashAlgorithm::default().hash_hex("abc"), sha256_hex("abc"));
+/// assert_eq!(
+///     HashAlgorithm::Keccak256.hash_hex(""),
+///     "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+/// );
+/// assert_eq!(
+///     HashAlgorithm::DoubleSha256.hash_hex("hello"),
+///     "9595c9df90075148eb06860365df33584b75bff782a510c6cd4883a419833d50"
+/// );
+///
+/// assert_eq!(
+///     HashAlgorithm::Blake3.hash_hex(""),
+///     "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+/// );
+///
+/// assert_eq!(HashAlgorithm::parse("double_sha256").unwrap(), HashAlgorithm::DoubleSha256);
+/// assert!(HashAlgorithm::parse("md5").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    DoubleSha256,
+    Keccak256,
+    Blake3,
+}
+
+impl HashAlgorithm {
+    /// Every algorithm, in the order of their names in error messages.
+    pub const ALL: [HashAlgorithm; 4] = [HashAlgorithm::Sha256, HashAlgorithm::DoubleSha256, HashAlgorithm::Keccak256, HashAlgorithm::Blake3];
+
+    /// Returns the name used in chain parameters ("sha256", "double_sha256",
+    /// "keccak256" or "blake3").
+    pub fn name(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::DoubleSha256 => "double_sha256",
+            HashAlgorithm::Keccak256 => "keccak256",
+            HashAlgorithm::Blake3 => "blake3",
+        }
+    }
+
+    /// Reads an algorithm from its name (see `name`).
+    pub fn parse(name: &str) -> Result<Self, String> {
+        HashAlgorithm::ALL.into_iter()
+            .find(|algorithm| algorithm.name() == name)
+            .ok_or_else(|| format!("Unknown hash algorithm \"{}\" (expected sha256, double_sha256, keccak256 or blake3)", name))
+    }
+
+    /// Returns a new hasher for this algorithm.
+    pub fn hasher(&self) -> AlgorithmHasher {
+        match self {
+            HashAlgorithm::Sha256 => AlgorithmHasher::Sha256(Sha256Hasher::default()),
+            HashAlgorithm::DoubleSha256 => AlgorithmHasher::DoubleSha256(DoubleSha256Hasher::default()),
+            HashAlgorithm::Keccak256 => AlgorithmHasher::Keccak256(Keccak256Hasher::default()),
+            HashAlgorithm::Blake3 => AlgorithmHasher::Blake3(Blake3Hasher::default()),
+        }
+    }
+
+    /// Hashes `data` and returns the hash as a hexadecimal string.
+    pub fn hash_hex(&self, data: &str) -> String {
+        self.hash_parts_hex(&[data])
+    }
+
+    /// Hashes the concatenation of `parts` without building it (see `sha256_parts_hex`).
+    pub fn hash_parts_hex(&self, parts: &[&str]) -> String {
+        let mut hasher = self.hasher();
+        for part in parts {
+            hasher.update(part.as_bytes());
+        }
+        hex::encode(hasher.finalize())
+    }
+
+    /// Hashes formatted text without building the string first (see `sha256_fmt_hex`).
+    pub fn hash_fmt_hex(&self, args: fmt::Arguments<'_>) -> String {
+        fmt_hex(self.hasher(), args)
+    }
+
+    /// Returns a hasher for this algorithm already fed the tag of `domain`.
+    pub fn tagged_hasher(&self, domain: Domain) -> AlgorithmHasher {
+        let mut hasher = self.hasher();
+        hasher.update(domain.tag().as_bytes());
+        hasher
+    }
+
+    /// Hashes the concatenation of `parts` after the tag of `domain`.
+    pub fn tagged_parts_hex(&self, domain: Domain, parts: &[&str]) -> String {
+        let mut hasher = self.tagged_hasher(domain);
+        for part in parts {
+            hasher.update(part.as_bytes());
+        }
+        hex::encode(hasher.finalize())
+    }
+
+    /// Hashes formatted text after the tag of `domain`.
+    pub fn tagged_fmt_hex(&self, domain: Domain, args: fmt::Arguments<'_>) -> String {
+        fmt_hex(self.tagged_hasher(domain), args)
+    }
+}
+
+fn fmt_hex(hasher: AlgorithmHasher, args: fmt::Arguments<'_>) -> String {
+    let mut writer = HashWriter(hasher);
+    // Writing to a hasher never fails
+    let _ = fmt::write(&mut writer, args);
+    hex::encode(writer.0.finalize())
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A hasher of whichever algorithm a chain was configured with.
+// Mining clones one per nonce: boxing the Keccak state would allocate each time
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone)]
+pub enum AlgorithmHasher {
+    Sha256(Sha256Hasher),
+    DoubleSha256(DoubleSha256Hasher),
+    Keccak256(Keccak256Hasher),
+    Blake3(Blake3Hasher),
+}
+
+impl Hasher for AlgorithmHasher {
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            AlgorithmHasher::Sha256(hasher) => hasher.update(data),
+            AlgorithmHasher::DoubleSha256(hasher) => hasher.update(data),
+            AlgorithmHasher::Keccak256(hasher) => hasher.update(data),
+            AlgorithmHasher::Blake3(hasher) => hasher.update(data),
+        }
+    }
+
+    fn finalize(self) -> [u8; 32] {
+        match self {
+            AlgorithmHasher::Sha256(hasher) => hasher.finalize(),
+            AlgorithmHasher::DoubleSha256(hasher) => hasher.finalize(),
+            AlgorithmHasher::Keccak256(hasher) => hasher.finalize(),
+            AlgorithmHasher::Blake3(hasher) => hasher.finalize(),
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use alloc::format;
+use alloc::string::String;
+use crate::hash::{AlgorithmHasher, Domain, HashAlgorithm, Hasher};
+
+/// The header of a block: everything its hash covers, without the transactions.
+///
+/// Headers are enough to follow a chain's proof of work and to check Merkle
+/// proofs of its transactions, which is what light clients (and bridges) do.
+///
+/// # Fields
+///
+/// * `block_hash` - Hash of the block
+/// * `previous_block_hash` - Hash of the previous block
+/// * `time_stamp` - Block timestamp
+/// * `nonce` - Proof-of-work nonce
+/// * `base_fee` - Base fee of the block
+/// * `merkle_root` - Merkle root of the block's transaction ids
+/// * `state_root` - State root after the block
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockHeader {
+    pub block_hash: String,
+    pub previous_block_hash: String,
+    pub time_stamp: u32,
+    pub nonce: u32,
+    pub base_fee: f64,
+    pub merkle_root: String,
+    pub state_root: String,
+}
+
+impl BlockHeader {
+    /// Recalculates the block hash from the header's data (it must equal `block_hash`).
+    pub fn calculate_hash(&self) -> String {
+        self.calculate_hash_with(HashAlgorithm::Sha256)
+    }
+
+    /// Recalculates the block hash with the hash function of the header's chain.
+    pub fn calculate_hash_with(&self, algorithm: HashAlgorithm) -> String {
+        block_hash_with(
+            algorithm,
+            &self.previous_block_hash,
+            self.time_stamp,
+            self.nonce,
+            self.base_fee,
+            &self.merkle_root,
+            &self.state_root
+        )
+    }
+
+    /// Returns `true` if `block_hash` is the hash of the header and meets `difficulty`.
+    pub fn is_valid(&self, difficulty: u32) -> bool {
+        self.is_valid_with(difficulty, HashAlgorithm::Sha256)
+    }
+
+    /// Like `is_valid`, for a chain hashing its headers with `algorithm`.
+    pub fn is_valid_with(&self, difficulty: u32, algorithm: HashAlgorithm) -> bool {
+        self.calculate_hash_with(algorithm) == self.block_hash && hash_meets_difficulty(&self.block_hash, difficulty)
+    }
+}
+
+/// Calculates the SHA-256 hash of a block from its header fields, tagged as a
+/// block header (see `Domain` and `block_hash_with`).
+///
+/// # Arguments
+///
+/// * `previous_hash` - Hash of the previous block
+/// * `time_stamp` - Block timestamp
+/// * `nonce` - Proof-of-work nonce value
+/// * `base_fee` - Base fee of the block
+/// * `merkle_root` - Merkle root of the block's transactions
+/// * `state_root` - State root after the block
+///
+/// # Returns
+///
+/// A hexadecimal string representing the SHA-256 hash.
+pub fn block_hash(previous_hash: &str, time_stamp: u32, nonce: u32, base_fee: f64, merkle_root: &str, state_root: &str) -> String {
+    block_hash_with(HashAlgorithm::Sha256, previous_hash, time_stamp, nonce, base_fee, merkle_root, state_root)
+}
+
+/// Calculates the hash of a block from its header fields with `algorithm`.
+///
+/// The preimage is the same for every algorithm, so with `HashAlgorithm::Sha256`
+/// this is `block_hash`.
+///
+/// # Example
+///
+/// ```
+/// use blockc_core::hash::HashAlgorithm;
+/// use blockc_core::header::{block_hash, block_hash_with};
+///
+/// assert_eq!(block_hash_with(HashAlgorithm::Sha256, "0", 1, 2, 0.1, "m", "s"), block_hash("0", 1, 2, 0.1, "m", "s"));
+/// assert_ne!(block_hash_with(HashAlgorithm::Keccak256, "0", 1, 2, 0.1, "m", "s"), block_hash("0", 1, 2, 0.1, "m", "s"));
+/// ```
+pub fn block_hash_with(algorithm: HashAlgorithm, previous_hash: &str, time_stamp: u32, nonce: u32, base_fee: f64, merkle_root: &str, state_root: &str) -> String {
+    algorithm.tagged_fmt_hex(Domain::BlockHeader, format_args!("{}{}{}{}{}{}", previous_hash, time_stamp, nonce, base_fee, merkle_root, state_root))
+}
+
+/// Hashes one block header for many nonces, as mining does.
+///
+/// Everything but the nonce is fixed while mining, so the hasher is fed the
+/// part of the preimage before the nonce once and keeps that state; each
+/// attempt only hashes the nonce and the (preformatted) rest. Digests are
+/// checked against the difficulty before being hex-encoded, so only the
+/// winning hash is turned into a string. Hashes are the same as `block_hash`
+/// (or `block_hash_with` for the algorithm given to `with_algorithm`).
+///
+/// # Example
+///
+/// ```
+/// use blockc_core::hash::HashAlgorithm;
+/// use blockc_core::header::{block_hash, block_hash_with, HeaderHasher};
+///
+/// let hasher = HeaderHasher::new("0", 1_000, 0.1, "root", "state");
+/// assert_eq!(hasher.hash_hex(42), block_hash("0", 1_000, 42, 0.1, "root", "state"));
+///
+/// let (nonce, hash) = hasher.find_nonce(0, 2).unwrap();
+/// assert!(hash.starts_with("00"));
+/// assert_eq!(hash, block_hash("0", 1_000, nonce, 0.1, "root", "state"));
+/// // No nonce before the one found works
+/// assert!((0..nonce).all(|nonce| !hasher.hash_hex(nonce).starts_with("00")));
+///
+/// let keccak = HeaderHasher::with_algorithm(HashAlgorithm::Keccak256, "0", 1_000, 0.1, "root", "state");
+/// assert_eq!(keccak.hash_hex(42), block_hash_with(HashAlgorithm::Keccak256, "0", 1_000, 42, 0.1, "root", "state"));
+/// ```
+#[derive(Clone)]
+pub struct HeaderHasher {
+    prefix: AlgorithmHasher, // State after the previous hash and the timestamp
+    suffix: String,          // Base fee, Merkle root and state root, formatted once
+}
+
+impl HeaderHasher {
+    /// Prepares the hashing of a header with these fields (see `block_hash`).
+    pub fn new(previous_hash: &str, time_stamp: u32, base_fee: f64, merkle_root: &str, state_root: &str) -> Self {
+        HeaderHasher::with_algorithm(HashAlgorithm::Sha256, previous_hash, time_stamp, base_fee, merkle_root, state_root)
+    }
+
+    /// Prepares the hashing of a header with `algorithm` (see `block_hash_with`).
+    pub fn with_algorithm(algorithm: HashAlgorithm, previous_hash: &str, time_stamp: u32, base_fee: f64, merkle_root: &str, state_root: &str) -> Self {
+        let mut prefix = algorithm.tagged_hasher(Domain::BlockHeader);
+        prefix.update(previous_hash.as_bytes());
+        prefix.update(format!("{}", time_stamp).as_bytes());
+        HeaderHasher {
+            prefix: prefix,
+            suffix: format!("{}{}{}", base_fee, merkle_root, state_root),
+        }
+    }
+
+    /// Returns the digest of the header with `nonce`.
+    pub fn hash(&self, nonce: u32) -> [u8; 32] {
+        let mut digits = [0u8; 10];
+        let mut start = digits.len();
+        let mut rest = nonce;
+        loop {
+            start -= 1;
+            digits[start] = b'0' + (rest % 10) as u8;
+            rest /= 10;
+            if rest == 0 {
+                break;
+            }
+        }
+        // Clone the algorithm's own state, not the (largest-variant-sized) enum
+        let rest = [&digits[start..], self.suffix.as_bytes()];
+        match &self.prefix {
+            AlgorithmHasher::Sha256(prefix) => finish(prefix, rest),
+            AlgorithmHasher::DoubleSha256(prefix) => finish(prefix, rest),
+            AlgorithmHasher::Keccak256(prefix) => finish(prefix, rest),
+            AlgorithmHasher::Blake3(prefix) => finish(prefix, rest),
+        }
+    }
+
+    /// Returns the hash of the header with `nonce`, as `block_hash` would.
+    pub fn hash_hex(&self, nonce: u32) -> String {
+        hex::encode(self.hash(nonce))
+    }
+
+    /// Tries the nonces from `start` up to `u32::MAX` and returns the first one
+    /// whose hash meets `difficulty`, with that hash.
+    ///
+    /// Returns `None` if none does: the caller changes the header (e.g. its
+    /// timestamp) and starts again.
+    pub fn find_nonce(&self, start: u32, difficulty: u32) -> Option<(u32, String)> {
+        (start..=u32::MAX)
+            .find(|nonce| digest_meets_difficulty(&self.hash(*nonce), difficulty))
+            .map(|nonce| (nonce, self.hash_hex(nonce)))
+    }
+}
+
+// Hashes `rest` after the state in `prefix`
+fn finish<H: Hasher>(prefix: &H, rest: [&[u8]; 2]) -> [u8; 32] {
+    let mut hasher = prefix.clone();
+    for part in rest {
+        hasher.update(part);
+    }
+    hasher.finalize()
+}
+
+/// Returns `true` if the hex form of `digest` starts with `difficulty` zeros,
+/// without encoding it (see `hash_meets_difficulty`).
+pub fn digest_meets_difficulty(digest: &[u8], difficulty: u32) -> bool {
+    let difficulty = difficulty as usize;
+    if difficulty > digest.len() * 2 {
+        return false;
+    }
+    let (full, half) = (difficulty / 2, difficulty % 2);
+    digest[..full].iter().all(|byte| *byte == 0) && (half == 0 || digest[full] >> 4 == 0)
+}
+
+/// Returns `true` if `hash` starts with `difficulty` zeros.
+///
+/// # Arguments
+///
+/// * `hash` - Hexadecimal block hash
+/// * `difficulty` - Number of leading zeros required
+pub fn hash_meets_difficulty(hash: &str, difficulty: u32) -> bool {
+    hash.chars().take_while(|c| *c == '0').count() >= difficulty as usize
+}
+
+/// Checks a line of headers, oldest first: each one must be valid for
+/// `difficulty` and point to the one before it.
+///
+/// The first header is only checked on its own; the caller decides whether it
+/// trusts what it extends (e.g. a genesis header it already knows).
+///
+/// # Returns
+///
+/// * `Ok(())` - If the headers form a valid line
+/// * `Err(String)` - The first header that doesn't
+///
+/// # Example
+///
+/// ```
+/// use blockc_core::header::{block_hash, verify_headers, BlockHeader};
+///
+/// fn mine(previous: &str, time_stamp: u32) -> BlockHeader {
+///     let mut header = BlockHeader {
+///         block_hash: String::new(),
+///         previous_block_hash: previous.to_string(),
+///         time_stamp: time_stamp,
+///         nonce: 0,
+///         base_fee: 0.1,
+///         merkle_root: String::new(),
+///         state_root: String::new(),
+///     };
+///     while !header.is_valid(1) {
+///         header.nonce += 1;
+///         header.block_hash = header.calculate_hash();
+///     }
+///     header
+/// }
+///
+/// let genesis = mine("0", 1);
+/// let next = mine(&genesis.block_hash, 2);
+/// assert!(verify_headers(&[genesis.clone(), next.clone()], 1).is_ok());
+/// assert!(verify_headers(&[next, genesis], 1).is_err());
+/// assert_eq!(block_hash("0", 1, 0, 0.1, "", "").len(), 64);
+/// ```
+pub fn verify_headers(headers: &[BlockHeader], difficulty: u32) -> Result<(), String> {
+    verify_headers_with(headers, difficulty, HashAlgorithm::Sha256)
+}
+
+/// Like `verify_headers`, for a chain hashing its headers with `algorithm`.
+pub fn verify_headers_with(headers: &[BlockHeader], difficulty: u32, algorithm: HashAlgorithm) -> Result<(), String> {
+    for (index, header) in headers.iter().enumerate() {
+        if !header.is_valid_with(difficulty, algorithm) {
+            return Err(format!("Header {} has an invalid hash or proof of work", header.block_hash));
+        }
+        if index > 0 && header.previous_block_hash != headers[index - 1].block_hash {
+            return Err(format!("Header {} does not extend {}", header.block_hash, headers[index - 1].block_hash));
+        }
+    }
+    Ok(())
+}
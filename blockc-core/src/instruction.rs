@@ -0,0 +1,218 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Maximum number of arguments a call can pass.
+pub const MAX_INPUTS: usize = 16;
+
+/// Maximum number of instructions in a contract.
+pub const MAX_CODE_LENGTH: usize = 1024;
+
+/// One instruction of the contract VM.
+///
+/// The VM works on a stack of `i64` values (no floating point, so every node
+/// computes exactly the same result). "Pops a, b" means `b` is the top of the
+/// stack and `a` the value below it.
+///
+/// * `Push(n)` - Pushes `n`
+/// * `Pop` - Drops the top value
+/// * `Dup` - Duplicates the top value
+/// * `Swap` - Swaps the two top values
+/// * `Add`, `Sub`, `Mul`, `Div`, `Mod` - Pops a, b; pushes a op b (errors on overflow or division by zero)
+/// * `Eq`, `Lt`, `Gt` - Pops a, b; pushes 1 if a op b, else 0
+/// * `Not` - Pops a; pushes 1 if a is 0, else 0
+/// * `Jump(target)` - Continues at instruction `target`
+/// * `JumpIf(target)` - Pops a; continues at `target` if a is not 0
+/// * `Load` - Pops key; pushes the stored value (0 if never written)
+/// * `Store` - Pops key, value; writes value at key
+/// * `CallValue` - Pushes the coins sent with the call (whole coins)
+/// * `Balance` - Pushes the contract's balance (whole coins)
+/// * `Transfer(address)` - Pops amount; sends that many coins from the contract to `address`
+/// * `TransferToCaller` - Pops amount; sends that many coins from the contract to the caller
+/// * `Stop` - Ends execution; the top of the stack (if any) is the return value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Instruction {
+    Push(i64),
+    Pop,
+    Dup,
+    Swap,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Eq,
+    Lt,
+    Gt,
+    Not,
+    Jump(usize),
+    JumpIf(usize),
+    Load,
+    Store,
+    CallValue,
+    Balance,
+    Transfer(String),
+    TransferToCaller,
+    Stop,
+}
+
+impl Instruction {
+    /// Gas charged for executing the instruction.
+    ///
+    /// Stack and arithmetic instructions are cheap; reading storage, writing
+    /// storage and moving coins cost more because every node has to keep the result.
+    pub fn gas_cost(&self) -> u64 {
+        match self {
+            Instruction::Push(_) | Instruction::Pop | Instruction::Dup | Instruction::Swap => 2,
+            Instruction::Add | Instruction::Sub | Instruction::Eq | Instruction::Lt | Instruction::Gt | Instruction::Not => 3,
+            Instruction::Mul | Instruction::Div | Instruction::Mod => 5,
+            Instruction::Jump(_) => 8,
+            Instruction::JumpIf(_) => 10,
+            Instruction::CallValue => 2,
+            Instruction::Balance => 100,
+            Instruction::Load => 200,
+            Instruction::Store => 5_000,
+            Instruction::Transfer(_) | Instruction::TransferToCaller => 9_000,
+            Instruction::Stop => 0,
+        }
+    }
+
+    /// Text form of the instruction (e.g. `PUSH 5`), as read by `parse`.
+    pub fn encode(&self) -> String {
+        match self {
+            Instruction::Push(value) => format!("PUSH {}", value),
+            Instruction::Pop => "POP".to_string(),
+            Instruction::Dup => "DUP".to_string(),
+            Instruction::Swap => "SWAP".to_string(),
+            Instruction::Add => "ADD".to_string(),
+            Instruction::Sub => "SUB".to_string(),
+            Instruction::Mul => "MUL".to_string(),
+            Instruction::Div => "DIV".to_string(),
+            Instruction::Mod => "MOD".to_string(),
+            Instruction::Eq => "EQ".to_string(),
+            Instruction::Lt => "LT".to_string(),
+            Instruction::Gt => "GT".to_string(),
+            Instruction::Not => "NOT".to_string(),
+            Instruction::Jump(target) => format!("JUMP {}", target),
+            Instruction::JumpIf(target) => format!("JUMPIF {}", target),
+            Instruction::Load => "LOAD".to_string(),
+            Instruction::Store => "STORE".to_string(),
+            Instruction::CallValue => "CALLVALUE".to_string(),
+            Instruction::Balance => "BALANCE".to_string(),
+            Instruction::Transfer(address) => format!("TRANSFER {}", address),
+            Instruction::TransferToCaller => "TRANSFERCALLER".to_string(),
+            Instruction::Stop => "STOP".to_string(),
+        }
+    }
+}
+
+/// Encodes a program as text, one instruction per line.
+pub fn encode(code: &[Instruction]) -> String {
+    code.iter().map(|instruction| instruction.encode()).collect::<Vec<String>>().join("\n")
+}
+
+/// Checks contract code before it is deployed.
+///
+/// Code is rejected if it is empty, longer than `MAX_CODE_LENGTH`, jumps outside
+/// itself or transfers to an empty address. Errors that depend on the data (stack
+/// underflow, division by zero, ...) can only be found when the code runs.
+///
+/// # Returns
+///
+/// * `Ok(())` - The code can be deployed
+/// * `Err(String)` - The first problem found
+///
+/// # Example
+///
+/// ```
+/// use blockc_core::instruction::{parse, validate};
+///
+/// assert!(validate(&parse("PUSH 1; JUMPIF 0").unwrap()).is_ok());
+/// assert!(validate(&parse("JUMP 7").unwrap()).is_err());
+/// ```
+pub fn validate(code: &[Instruction]) -> Result<(), String> {
+    if code.is_empty() {
+        return Err("A contract needs code".to_string());
+    }
+    if code.len() > MAX_CODE_LENGTH {
+        return Err(format!("Contract has {} instructions, limit is {}", code.len(), MAX_CODE_LENGTH));
+    }
+    for (index, instruction) in code.iter().enumerate() {
+        match instruction {
+            Instruction::Jump(target) | Instruction::JumpIf(target) if *target >= code.len() => {
+                return Err(format!("Instruction {} jumps to {}, outside the code", index, target));
+            }
+            Instruction::Transfer(address) if address.is_empty() => {
+                return Err(format!("Instruction {} transfers to an empty address", index));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Parses a program written as text: one instruction per line (or separated by
+/// `;`), case-insensitive, `#` starts a comment.
+///
+/// # Returns
+///
+/// * `Ok(Vec<Instruction>)` - The program
+/// * `Err(String)` - The first line that could not be parsed
+///
+/// # Example
+///
+/// ```
+/// use blockc_core::instruction::{parse, Instruction};
+///
+/// let code = parse("PUSH 2; PUSH 3 # operands\nADD").unwrap();
+/// assert_eq!(code, vec![Instruction::Push(2), Instruction::Push(3), Instruction::Add]);
+/// assert!(parse("PUSH x").is_err());
+/// ```
+pub fn parse(source: &str) -> Result<Vec<Instruction>, String> {
+    let mut code = Vec::new();
+    for line in source.lines() {
+        let line = line.split('#').next().unwrap_or("");
+        for statement in line.split(';') {
+            let mut parts = statement.split_whitespace();
+            let Some(name) = parts.next() else { continue };
+            let argument = parts.next();
+            code.push(parse_instruction(&name.to_uppercase(), argument)?);
+        }
+    }
+    Ok(code)
+}
+
+fn parse_instruction(name: &str, argument: Option<&str>) -> Result<Instruction, String> {
+    let number = |argument: Option<&str>| -> Result<i64, String> {
+        argument.and_then(|a| a.parse().ok()).ok_or(format!("{} needs a number", name))
+    };
+    let target = |argument: Option<&str>| -> Result<usize, String> {
+        argument.and_then(|a| a.parse().ok()).ok_or(format!("{} needs a position", name))
+    };
+    let instruction = match name {
+        "PUSH" => Instruction::Push(number(argument)?),
+        "POP" => Instruction::Pop,
+        "DUP" => Instruction::Dup,
+        "SWAP" => Instruction::Swap,
+        "ADD" => Instruction::Add,
+        "SUB" => Instruction::Sub,
+        "MUL" => Instruction::Mul,
+        "DIV" => Instruction::Div,
+        "MOD" => Instruction::Mod,
+        "EQ" => Instruction::Eq,
+        "LT" => Instruction::Lt,
+        "GT" => Instruction::Gt,
+        "NOT" => Instruction::Not,
+        "JUMP" => Instruction::Jump(target(argument)?),
+        "JUMPIF" => Instruction::JumpIf(target(argument)?),
+        "LOAD" => Instruction::Load,
+        "STORE" => Instruction::Store,
+        "CALLVALUE" => Instruction::CallValue,
+        "BALANCE" => Instruction::Balance,
+        "TRANSFER" => Instruction::Transfer(argument.ok_or("TRANSFER needs an address".to_string())?.to_string()),
+        "TRANSFERCALLER" => Instruction::TransferToCaller,
+        "STOP" => Instruction::Stop,
+        _ => return Err(format!("Unknown instruction {}", name)),
+    };
+    Ok(instruction)
+}
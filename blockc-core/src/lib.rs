@@ -0,0 +1,30 @@
+//! The parts of BlockC that need neither an operating system nor a clock:
+//! hashing, signing, Merkle proofs, block headers, the transaction and block
+//! types with their `signing_data`, and stateless validation (`check_payload`,
+//! `check_block`, canonical ordering).
+//!
+//! The crate is `no_std` and only needs `alloc`, so a device can sign
+//! transactions and check blocks, headers and Merkle proofs (a light client)
+//! without the full node. Times are always passed in by the caller. The `std`
+//! feature only turns on `std` in the hash implementation; `privacy` adds the
+//! confidential and ring-signature transaction kinds, and `testing` proptest
+//! strategies (with `Arbitrary`) for transactions and blocks.
+#![no_std]
+#![allow(clippy::redundant_field_names)]
+
+extern crate alloc;
+
+pub mod hash;
+pub mod sign;
+pub mod merkle;
+pub mod header;
+pub mod instruction;
+pub mod gas;
+pub mod payload;
+#[cfg(feature = "privacy")]
+pub mod privacy;
+pub mod transaction;
+pub mod ordering;
+pub mod block;
+#[cfg(feature = "testing")]
+pub mod testing;
@@ -0,0 +1,119 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::hash::{hash_pair, sha256_hex};
+
+/// One step of a Merkle proof: the sibling hash and which side it is on.
+///
+/// # Fields
+///
+/// * `hash` - Hash of the sibling node
+/// * `is_left` - `true` if the sibling is the left node of the pair
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProofStep {
+    pub hash: String,
+    pub is_left: bool,
+}
+
+/// Proof that a leaf is part of a Merkle tree.
+///
+/// Starting from the leaf, hash it with each sibling in turn; the result must be
+/// the Merkle root.
+///
+/// # Fields
+///
+/// * `leaf` - The leaf being proven (e.g. a transaction id)
+/// * `steps` - Siblings from the bottom of the tree to the top
+#[derive(Debug, Clone, PartialEq)]
+pub struct MerkleProof {
+    pub leaf: String,
+    pub steps: Vec<ProofStep>,
+}
+
+impl MerkleProof {
+    /// Returns `true` if this proof links `leaf` to `root`.
+    pub fn verify(&self, root: &str) -> bool {
+        let mut current = self.leaf.clone();
+        for step in &self.steps {
+            current = if step.is_left {
+                hash_pair(&step.hash, &current)
+            } else {
+                hash_pair(&current, &step.hash)
+            };
+        }
+        current == root
+    }
+}
+
+/// Calculates the Merkle root of a list of leaves.
+///
+/// Leaves are hashed pair by pair (tagged as Merkle nodes, see `Domain`), level
+/// by level, until one hash is left. When a level has an odd number of nodes,
+/// the last one is paired with itself (like Bitcoin).
+///
+/// # Arguments
+///
+/// * `leaves` - Leaf hashes (e.g. transaction ids), in order
+///
+/// # Returns
+///
+/// The Merkle root, or the hash of an empty string if there are no leaves.
+///
+/// # Example
+///
+/// ```
+/// use blockc_core::merkle::{merkle_root, build_proof};
+///
+/// let leaves = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+/// let root = merkle_root(&leaves);
+///
+/// let proof = build_proof(&leaves, 2).unwrap();
+/// assert!(proof.verify(&root));
+/// ```
+pub fn merkle_root(leaves: &[String]) -> String {
+    if leaves.is_empty() {
+        return sha256_hex("");
+    }
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level.remove(0)
+}
+
+/// Builds the proof that `leaves[index]` is part of the tree.
+///
+/// # Returns
+///
+/// * `Some(MerkleProof)` - The proof
+/// * `None` - If `index` is out of range
+pub fn build_proof(leaves: &[String], index: usize) -> Option<MerkleProof> {
+    if index >= leaves.len() {
+        return None;
+    }
+    let mut steps = Vec::new();
+    let mut level = leaves.to_vec();
+    let mut position = index;
+
+    while level.len() > 1 {
+        let sibling = if position.is_multiple_of(2) {
+            // Right sibling, or itself when it is the last odd node
+            ProofStep { hash: level.get(position + 1).unwrap_or(&level[position]).clone(), is_left: false }
+        } else {
+            ProofStep { hash: level[position - 1].clone(), is_left: true }
+        };
+        steps.push(sibling);
+        level = next_level(&level);
+        position /= 2;
+    }
+
+    Some(MerkleProof {
+        leaf: leaves[index].to_string(),
+        steps: steps,
+    })
+}
+
+fn next_level(level: &[String]) -> Vec<String> {
+    level.chunks(2)
+        .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+        .collect()
+}
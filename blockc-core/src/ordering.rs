@@ -0,0 +1,86 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::transaction::{Transaction, TransactionKind};
+
+/// Returns `true` if `child` can only be applied after `parent`: it is from the
+/// same sender with a higher nonce, or it spends one of `parent`'s UTXO outputs.
+pub fn must_follow(child: &Transaction, parent: &Transaction) -> bool {
+    if child.sender_address == parent.sender_address && parent.nonce < child.nonce {
+        return true;
+    }
+    match &child.kind {
+        TransactionKind::Utxo { inputs, .. } if !inputs.is_empty() => {
+            let parent_id = parent.id();
+            inputs.iter().any(|outpoint| outpoint.transaction_id == parent_id)
+        }
+        _ => false,
+    }
+}
+
+/// Returns `true` if `parent` pays `child`'s sender, who may need it to afford `child`.
+pub fn funds(parent: &Transaction, child: &Transaction) -> bool {
+    parent.sender_address != child.sender_address && parent.pays(&child.sender_address)
+}
+
+/// Returns `true` if `child` depends on `parent` (`must_follow` or `funds`).
+pub fn depends_on(child: &Transaction, parent: &Transaction) -> bool {
+    must_follow(child, parent) || funds(parent, child)
+}
+
+/// Puts the user transactions of a block in canonical order.
+///
+/// Transactions are placed one at a time. The next one is the transaction with
+/// the highest fee (lowest id on ties) among those whose dependencies are all
+/// placed; if a funding cycle leaves none, the ones only waiting for funding
+/// (see `funds`) are considered, and at worst the next by fee. The result only
+/// depends on the set of transactions, so every node orders a block the same way.
+///
+/// # Example
+///
+/// ```
+/// use blockc_core::ordering;
+/// use blockc_core::transaction::Transaction;
+///
+/// let transfer = |from: &str, to: &str, amount: f64, fee: f64| {
+///     Transaction::new(from.to_string(), to.to_string(), amount, fee, 0, String::new(), 1_700_000_000)
+/// };
+/// let parent = transfer("Alice", "Bob", 20.0, 0.1);
+/// let child = transfer("Bob", "Carol", 5.0, 3.0);
+/// let other = transfer("Dave", "Carol", 5.0, 1.0);
+///
+/// // Bob's payment pays the most but needs Alice's first
+/// let ordered = ordering::canonical_order(vec![child.clone(), other.clone(), parent.clone()]);
+/// assert_eq!(ordered, vec![other.clone(), parent.clone(), child.clone()]);
+/// assert!(ordering::is_canonical(&ordered));
+/// assert!(!ordering::is_canonical(&[child, parent, other]));
+/// ```
+pub fn canonical_order(transactions: Vec<Transaction>) -> Vec<Transaction> {
+    let ids: Vec<String> = transactions.iter().map(|t| t.id()).collect();
+    let mut left: Vec<usize> = (0..transactions.len()).collect();
+    left.sort_by(|&a, &b| transactions[b].fee.total_cmp(&transactions[a].fee).then_with(|| ids[a].cmp(&ids[b])));
+
+    let mut order: Vec<usize> = Vec::with_capacity(left.len());
+    while !left.is_empty() {
+        let ready = |i: usize, left: &[usize]| !left.iter().any(|&j| {
+            j != i && must_follow(&transactions[i], &transactions[j])
+        });
+        let funded = |i: usize, left: &[usize]| !left.iter().any(|&j| {
+            j != i && funds(&transactions[j], &transactions[i])
+        });
+
+        let position = left.iter().position(|&i| ready(i, &left) && funded(i, &left))
+            .or_else(|| left.iter().position(|&i| ready(i, &left)))
+            .unwrap_or(0);
+        order.push(left.remove(position));
+    }
+
+    let mut slots: Vec<Option<Transaction>> = transactions.into_iter().map(Some).collect();
+    order.into_iter().filter_map(|i| slots[i].take()).collect()
+}
+
+/// Returns `true` if `transactions` are already in canonical order (see `canonical_order`).
+pub fn is_canonical(transactions: &[Transaction]) -> bool {
+    let ids: Vec<String> = transactions.iter().map(|t| t.id()).collect();
+    let canonical: Vec<String> = canonical_order(transactions.to_vec()).iter().map(|t| t.id()).collect();
+    ids == canonical
+}
@@ -0,0 +1,241 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::block::Block;
+use crate::merkle::MerkleProof;
+use crate::transaction::Transaction;
+
+/// Balances of a payment channel at one point in time, signed off chain.
+///
+/// Every payment through the channel produces a new state with a higher
+/// `sequence`. Both parties sign each state; when the channel closes, the chain
+/// pays out the state with the highest sequence it has seen.
+///
+/// # Fields
+///
+/// * `channel_id` - Id of the channel (id of its funding transaction)
+/// * `sequence` - Version of the state (the initial state is 0)
+/// * `balance_a` - Amount owned by the channel's opener
+/// * `balance_b` - Amount owned by the counterparty
+/// * `signature_a` - Opener's signature (empty if not signed yet)
+/// * `signature_b` - Counterparty's signature (empty if not signed yet)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelState {
+    pub channel_id: String,
+    pub sequence: u64,
+    pub balance_a: f64,
+    pub balance_b: f64,
+    pub signature_a: String,
+    pub signature_b: String,
+}
+
+impl ChannelState {
+    /// Data signed by both parties (everything but the signatures).
+    pub fn signing_data(&self) -> String {
+        format!("{}{}{}{}", self.channel_id, self.sequence, self.balance_a, self.balance_b)
+    }
+
+    /// Returns `true` if both parties signed this state.
+    pub fn is_fully_signed(&self) -> bool {
+        !self.signature_a.is_empty() && !self.signature_b.is_empty()
+    }
+}
+
+/// What an escrow vote asks for.
+///
+/// * `Release` - Pay the seller
+/// * `Refund` - Give the funds back to the buyer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EscrowOutcome {
+    Release,
+    Refund,
+}
+
+/// How a vesting grant unlocks, by block height.
+///
+/// * `Linear` - Nothing before `start_height`, everything from `end_height`, linear in between
+/// * `Cliffs` - Each `(height, amount)` unlocks `amount` at once from `height`
+#[derive(Debug, Clone, PartialEq)]
+pub enum VestingSchedule {
+    Linear { start_height: u64, end_height: u64 },
+    Cliffs { unlocks: Vec<(u64, f64)> },
+}
+
+impl VestingSchedule {
+    /// Part of the signed data describing the schedule.
+    pub fn signing_data(&self) -> String {
+        match self {
+            VestingSchedule::Linear { start_height, end_height } => format!("linear:{}:{}", start_height, end_height),
+            VestingSchedule::Cliffs { unlocks } => {
+                let parts: Vec<String> = unlocks.iter()
+                    .map(|(height, amount)| format!("{}:{}", height, amount))
+                    .collect();
+                format!("cliffs:{}", parts.join(","))
+            }
+        }
+    }
+
+    /// Checks that the schedule can unlock exactly `amount`.
+    pub fn check(&self, amount: f64) -> Result<(), String> {
+        match self {
+            VestingSchedule::Linear { start_height, end_height } => {
+                if end_height <= start_height {
+                    return Err("Vesting must end after it starts".to_string());
+                }
+            }
+            VestingSchedule::Cliffs { unlocks } => {
+                if unlocks.is_empty() || unlocks.iter().any(|(_, unlocked)| *unlocked < 0.0) {
+                    return Err("Vesting cliffs must be non-empty and positive".to_string());
+                }
+                let total: f64 = unlocks.iter().map(|(_, unlocked)| unlocked).sum();
+                if total != amount {
+                    return Err(format!("Vesting cliffs unlock {}, grant is {}", total, amount));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reference to one output of a transaction.
+///
+/// # Fields
+///
+/// * `transaction_id` - Id of the transaction that created the output
+/// * `index` - Position of the output in that transaction
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OutPoint {
+    pub transaction_id: String,
+    pub index: usize,
+}
+
+impl OutPoint {
+    /// Creates a reference to output `index` of `transaction_id`.
+    pub fn new(transaction_id: String, index: usize) -> Self {
+        OutPoint {
+            transaction_id: transaction_id,
+            index: index,
+        }
+    }
+}
+
+/// One input of a CoinJoin, with its owner's signature over the whole join.
+///
+/// # Fields
+///
+/// * `outpoint` - Output spent
+/// * `signature` - Signature of the output's owner over `join_message` (empty until signed)
+#[derive(Debug, Clone, PartialEq)]
+pub struct JoinInput {
+    pub outpoint: OutPoint,
+    pub signature: String,
+}
+
+impl JoinInput {
+    /// Returns the data of the input covered by the transaction's signature.
+    pub fn signing_data(&self) -> String {
+        format!("{}:{}:{}", self.outpoint.transaction_id, self.outpoint.index, self.signature)
+    }
+}
+
+/// A service the DID subject can be reached at (e.g. a messaging inbox).
+///
+/// # Fields
+///
+/// * `id` - Name of the service within the document
+/// * `kind` - Type of service (e.g. "Inbox")
+/// * `endpoint` - URL of the service
+#[derive(Debug, Clone, PartialEq)]
+pub struct Service {
+    pub id: String,
+    pub kind: String,
+    pub endpoint: String,
+}
+
+impl Service {
+    /// Creates a service entry.
+    pub fn new(id: &str, kind: &str, endpoint: &str) -> Self {
+        Service {
+            id: id.to_string(),
+            kind: kind.to_string(),
+            endpoint: endpoint.to_string(),
+        }
+    }
+
+    /// Text form included in the signed transaction data.
+    pub fn signing_data(&self) -> String {
+        format!("{}|{}|{}", self.id, self.kind, self.endpoint)
+    }
+}
+
+/// How much each vote counts.
+///
+/// * `OnePerAddress` - Every address has one vote
+/// * `Balance` - A vote weighs the voter's spendable balance when voting opened
+///   (see `Ballot::snapshot`), so coins moved to another address during the
+///   vote can't be voted twice
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VoteWeighting {
+    OnePerAddress,
+    Balance,
+}
+
+/// Side of a limit order.
+///
+/// * `Buy` - Buys the base asset, paying with the quote asset
+/// * `Sell` - Sells the base asset for the quote asset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+impl Side {
+    /// Returns the name used in signing payloads ("buy" or "sell").
+    pub fn name(&self) -> &'static str {
+        match self {
+            Side::Buy => "buy",
+            Side::Sell => "sell",
+        }
+    }
+}
+
+/// Proof that a transaction is part of a block of another chain.
+///
+/// # Fields
+///
+/// * `block_hash` - Hash of the block containing the transaction
+/// * `transaction` - The transaction
+/// * `proof` - Merkle proof linking the transaction id to the block's Merkle root
+#[derive(Debug, Clone, PartialEq)]
+pub struct BridgeProof {
+    pub block_hash: String,
+    pub transaction: Transaction,
+    pub proof: MerkleProof,
+}
+
+impl BridgeProof {
+    /// Builds the proof for the transaction `transaction_id` of a chain's
+    /// `blocks`.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(BridgeProof)` - The proof
+    /// * `None` - If none of the blocks contains the transaction
+    pub fn new<'a>(blocks: impl IntoIterator<Item = &'a Block>, transaction_id: &str) -> Option<Self> {
+        let block = blocks.into_iter().find(|block| block.transaction.iter().any(|t| t.id() == transaction_id))?;
+        let transaction = block.transaction.iter().find(|t| t.id() == transaction_id)?;
+        Some(BridgeProof {
+            block_hash: block.block_hash.clone(),
+            transaction: transaction.clone(),
+            proof: block.prove_transaction(transaction_id)?,
+        })
+    }
+
+    /// Returns the data signed by a transaction carrying this proof.
+    pub fn signing_data(&self) -> String {
+        let steps: Vec<&str> = self.proof.steps.iter().map(|step| step.hash.as_str()).collect();
+        format!("{}:{}:{}", self.block_hash, self.transaction.id(), steps.join(","))
+    }
+}
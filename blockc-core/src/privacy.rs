@@ -0,0 +1,479 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
+use curve25519_dalek::scalar::Scalar;
+use sha2::{Digest, Sha256, Sha512};
+use crate::payload::OutPoint;
+
+/// Hashes `parts` to a scalar, under a `domain` tag so hashes made for one
+/// purpose can never be reused for another.
+///
+/// Each part is length-prefixed, so `["ab", "c"]` and `["a", "bc"]` hash differently.
+pub fn hash_to_scalar(domain: &str, parts: &[&[u8]]) -> Scalar {
+    let mut hasher = Sha512::new();
+    hasher.update((domain.len() as u64).to_le_bytes());
+    hasher.update(domain.as_bytes());
+    for part in parts {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part);
+    }
+    Scalar::from_bytes_mod_order_wide(&hasher.finalize().into())
+}
+
+/// Derives a secret key for `purpose` from an entity's private key.
+///
+/// Entities only have a private key string; every key used by the privacy
+/// features is derived from it, so one backup restores them all.
+pub fn secret_key(private_key: &str, purpose: &str) -> Scalar {
+    hash_to_scalar(purpose, &[private_key.as_bytes()])
+}
+
+/// Returns the public key (`secret * G`) of a secret key.
+pub fn public_key(secret: &Scalar) -> RistrettoPoint {
+    RistrettoPoint::mul_base(secret)
+}
+
+/// Encodes bytes as lowercase hexadecimal.
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Decodes hexadecimal (either case) into bytes.
+pub fn from_hex(value: &str) -> Result<Vec<u8>, String> {
+    if !value.len().is_multiple_of(2) {
+        return Err(format!("Hex value {:?} has an odd length", value));
+    }
+    (0..value.len()).step_by(2)
+        .map(|i| u8::from_str_radix(value.get(i..i + 2).unwrap_or(""), 16).map_err(|_| format!("Invalid hex value {:?}", value)))
+        .collect()
+}
+
+/// Encodes a point as 64 hex characters.
+pub fn point_to_hex(point: &RistrettoPoint) -> String {
+    to_hex(point.compress().as_bytes())
+}
+
+/// Decodes a point encoded with `point_to_hex`.
+pub fn point_from_hex(value: &str) -> Result<RistrettoPoint, String> {
+    let bytes: [u8; 32] = from_hex(value)?.try_into().map_err(|_| format!("Point {:?} must be 32 bytes", value))?;
+    CompressedRistretto(bytes).decompress().ok_or(format!("{:?} is not a valid point", value))
+}
+
+/// Encodes a scalar as 64 hex characters.
+pub fn scalar_to_hex(scalar: &Scalar) -> String {
+    to_hex(scalar.as_bytes())
+}
+
+/// Decodes a scalar encoded with `scalar_to_hex`.
+pub fn scalar_from_hex(value: &str) -> Result<Scalar, String> {
+    let bytes: [u8; 32] = from_hex(value)?.try_into().map_err(|_| format!("Scalar {:?} must be 32 bytes", value))?;
+    Option::from(Scalar::from_canonical_bytes(bytes)).ok_or(format!("{:?} is not a canonical scalar", value))
+}
+
+/// Bits covered by a range proof: hidden amounts are below 2^40 units (about 1.1 million coins).
+pub const RANGE_BITS: usize = 40;
+
+/// Second generator `H` of the commitments, with no known relation to `G`.
+///
+/// It is hashed from a fixed tag, so nobody knows `x` with `H = x * G`; that is
+/// what keeps a commitment from being opened to two different values.
+pub fn value_generator() -> RistrettoPoint {
+    let digest: [u8; 64] = Sha512::digest(b"blockc/confidential/value-generator").into();
+    RistrettoPoint::from_uniform_bytes(&digest)
+}
+
+/// Pedersen commitment to `value`: `value * H + blinding * G`.
+///
+/// The blinding factor hides the value; commitments add up, so the sum of two
+/// commitments commits to the sum of their values.
+///
+/// # Example
+///
+/// ```
+/// use blockc_core::privacy::commit;
+/// use curve25519_dalek::scalar::Scalar;
+///
+/// let (r1, r2) = (Scalar::from(11u64), Scalar::from(22u64));
+/// assert_eq!(commit(3, &r1) + commit(4, &r2), commit(7, &(r1 + r2)));
+/// ```
+pub fn commit(value: u64, blinding: &Scalar) -> RistrettoPoint {
+    Scalar::from(value) * value_generator() + public_key(blinding)
+}
+
+/// Proof that a commitment `C` opens to 0 or 1, without telling which.
+///
+/// An OR of two Schnorr proofs: one for "`C = r * G`" (the bit is 0) and one for
+/// "`C - H = r * G`" (the bit is 1). The prover only knows the key of one
+/// statement and simulates the other; the two challenges must add up to the hash
+/// of the transcript.
+///
+/// # Fields
+///
+/// * `commitment` - Commitment to the bit
+/// * `challenges` - Challenge of each statement
+/// * `responses` - Response of each statement
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BitProof {
+    pub commitment: RistrettoPoint,
+    pub challenges: [Scalar; 2],
+    pub responses: [Scalar; 2],
+}
+
+/// Proof that a commitment hides a value between 0 and 2^`RANGE_BITS` - 1.
+///
+/// Without it a sender could create an output committing to a "negative" amount
+/// (a value wrapping around the group order) and mint coins while still
+/// balancing. The value is split into bits, each committed to and proven to be 0
+/// or 1; the bit commitments, weighted by powers of two, must add up to the
+/// commitment.
+///
+/// # Fields
+///
+/// * `bits` - One proof per bit, least significant first
+///
+/// # Example
+///
+/// ```
+/// use blockc_core::privacy::{commit, RangeProof};
+/// use curve25519_dalek::scalar::Scalar;
+///
+/// let blinding = Scalar::from(42u64);
+/// let proof = RangeProof::prove(1_000, &blinding).unwrap();
+/// assert!(proof.verify(&commit(1_000, &blinding)).is_ok());
+/// assert!(proof.verify(&commit(1_001, &blinding)).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeProof {
+    pub bits: Vec<BitProof>,
+}
+
+impl RangeProof {
+    /// Proves that `commit(value, blinding)` is in range.
+    ///
+    /// The proof's nonces are derived from the blinding factor, so no random
+    /// number generator is needed; blinding factors must never be reused.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(RangeProof)` - The proof
+    /// * `Err(String)` - If `value` needs more than `RANGE_BITS` bits
+    pub fn prove(value: u64, blinding: &Scalar) -> Result<Self, String> {
+        if value >> RANGE_BITS != 0 {
+            return Err(format!("Value {} needs more than {} bits", value, RANGE_BITS));
+        }
+        let commitment = commit(value, blinding);
+        let h = value_generator();
+        let seed = blinding.as_bytes();
+
+        // Bit blindings weighted by powers of two add up to the blinding factor
+        let mut bit_blindings: Vec<Scalar> = (0..RANGE_BITS - 1)
+            .map(|i| hash_to_scalar("blockc/confidential/bit-blinding", &[seed, &i.to_le_bytes()]))
+            .collect();
+        let weighted: Scalar = bit_blindings.iter().enumerate().map(|(i, r)| power_of_two(i) * r).sum();
+        bit_blindings.push((blinding - weighted) * power_of_two(RANGE_BITS - 1).invert());
+
+        let mut bits = Vec::with_capacity(RANGE_BITS);
+        for (i, r) in bit_blindings.iter().enumerate() {
+            let bit = ((value >> i) & 1) as usize;
+            let bit_commitment = Scalar::from(bit as u64) * h + public_key(r);
+            let statements = [bit_commitment, bit_commitment - h];
+            let index = i.to_le_bytes();
+
+            // Simulate the statement that is false, prove the true one
+            let fake = 1 - bit;
+            let mut challenges = [Scalar::ZERO; 2];
+            let mut responses = [Scalar::ZERO; 2];
+            challenges[fake] = hash_to_scalar("blockc/confidential/bit-challenge", &[seed, &index]);
+            responses[fake] = hash_to_scalar("blockc/confidential/bit-response", &[seed, &index]);
+            let nonce = hash_to_scalar("blockc/confidential/bit-nonce", &[seed, &index]);
+            let mut announcements = [public_key(&nonce); 2];
+            announcements[fake] = public_key(&responses[fake]) - challenges[fake] * statements[fake];
+
+            let challenge = bit_challenge(&commitment, &bit_commitment, &announcements, i);
+            challenges[bit] = challenge - challenges[fake];
+            responses[bit] = nonce + challenges[bit] * r;
+            bits.push(BitProof {
+                commitment: bit_commitment,
+                challenges: challenges,
+                responses: responses,
+            });
+        }
+        Ok(RangeProof { bits: bits })
+    }
+
+    /// Checks the proof against `commitment`.
+    pub fn verify(&self, commitment: &RistrettoPoint) -> Result<(), String> {
+        if self.bits.len() != RANGE_BITS {
+            return Err(format!("A range proof needs {} bits, got {}", RANGE_BITS, self.bits.len()));
+        }
+        let h = value_generator();
+        let weighted: RistrettoPoint = self.bits.iter().enumerate()
+            .map(|(i, bit)| power_of_two(i) * bit.commitment)
+            .sum();
+        if weighted != *commitment {
+            return Err("Range proof bits do not add up to the commitment".to_string());
+        }
+        for (i, bit) in self.bits.iter().enumerate() {
+            let statements = [bit.commitment, bit.commitment - h];
+            let announcements = [0, 1].map(|j| public_key(&bit.responses[j]) - bit.challenges[j] * statements[j]);
+            if bit.challenges[0] + bit.challenges[1] != bit_challenge(commitment, &bit.commitment, &announcements, i) {
+                return Err(format!("Range proof bit {} is not 0 or 1", i));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the SHA-256 hash of the whole proof (hex), used in signing payloads.
+    pub fn digest(&self) -> String {
+        let mut hasher = Sha256::new();
+        for bit in &self.bits {
+            hasher.update(bit.commitment.compress().as_bytes());
+            for scalar in bit.challenges.iter().chain(bit.responses.iter()) {
+                hasher.update(scalar.as_bytes());
+            }
+        }
+        to_hex(&hasher.finalize())
+    }
+}
+
+fn power_of_two(exponent: usize) -> Scalar {
+    Scalar::from(1u64 << exponent)
+}
+
+fn bit_challenge(commitment: &RistrettoPoint, bit_commitment: &RistrettoPoint, announcements: &[RistrettoPoint; 2], index: usize) -> Scalar {
+    hash_to_scalar("blockc/confidential/bit", &[
+        commitment.compress().as_bytes(),
+        bit_commitment.compress().as_bytes(),
+        announcements[0].compress().as_bytes(),
+        announcements[1].compress().as_bytes(),
+        &index.to_le_bytes(),
+    ])
+}
+
+/// A new hidden output of a confidential transfer.
+///
+/// # Fields
+///
+/// * `owner` - Address that can spend the output (visible)
+/// * `commitment` - Commitment to the amount
+/// * `range_proof` - Proof that the amount is in range
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfidentialOutput {
+    pub owner: String,
+    pub commitment: RistrettoPoint,
+    pub range_proof: RangeProof,
+}
+
+impl ConfidentialOutput {
+    /// Returns the data signed by a transaction creating this output.
+    pub fn signing_data(&self) -> String {
+        format!("{}={}/{}", self.owner, point_to_hex(&self.commitment), self.range_proof.digest())
+    }
+}
+
+/// Proof that a confidential transfer creates no coins.
+///
+/// The chain computes the excess
+/// `E = inputs + deposit * H - outputs - withdrawal * H`.
+/// If the amounts balance, the `H` terms cancel and `E = x * G`, where `x` is
+/// the input blindings minus the output blindings. The proof is a Schnorr
+/// signature by `x` over the transfer: only someone who knows `x` can make it,
+/// and nobody can know `x` unless the amounts balance (the relation between `H`
+/// and `G` is unknown).
+///
+/// # Fields
+///
+/// * `nonce` - Schnorr nonce commitment (`k * G`)
+/// * `response` - Schnorr response (`k + e * x`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BalanceProof {
+    pub nonce: RistrettoPoint,
+    pub response: Scalar,
+}
+
+impl BalanceProof {
+    /// Signs `message` with the excess blinding factor `excess`.
+    pub fn prove(excess: &Scalar, message: &str) -> Self {
+        let nonce = hash_to_scalar("blockc/confidential/balance-nonce", &[excess.as_bytes(), message.as_bytes()]);
+        let nonce_point = public_key(&nonce);
+        let challenge = balance_challenge(&public_key(excess), &nonce_point, message);
+        BalanceProof {
+            nonce: nonce_point,
+            response: nonce + challenge * excess,
+        }
+    }
+
+    /// Checks the proof against the excess point the chain computed.
+    pub fn verify(&self, excess: &RistrettoPoint, message: &str) -> Result<(), String> {
+        let challenge = balance_challenge(excess, &self.nonce, message);
+        if public_key(&self.response) != self.nonce + challenge * excess {
+            return Err("Confidential inputs and outputs do not balance".to_string());
+        }
+        Ok(())
+    }
+
+    /// Returns the data signed by a transaction carrying this proof.
+    pub fn signing_data(&self) -> String {
+        format!("{}/{}", point_to_hex(&self.nonce), scalar_to_hex(&self.response))
+    }
+}
+
+fn balance_challenge(excess: &RistrettoPoint, nonce: &RistrettoPoint, message: &str) -> Scalar {
+    hash_to_scalar("blockc/confidential/balance", &[
+        excess.compress().as_bytes(),
+        nonce.compress().as_bytes(),
+        message.as_bytes(),
+    ])
+}
+
+/// Excess of a transfer: `inputs + deposit * H - outputs - withdrawal * H`.
+pub fn excess(inputs: &[RistrettoPoint], outputs: &[RistrettoPoint], deposit: u64, withdrawal: u64) -> RistrettoPoint {
+    let h = value_generator();
+    inputs.iter().sum::<RistrettoPoint>() + Scalar::from(deposit) * h
+        - outputs.iter().sum::<RistrettoPoint>() - Scalar::from(withdrawal) * h
+}
+
+/// Message signed by the balance proof of a transfer: everything but the proof.
+pub fn balance_message(sender: &str, inputs: &[OutPoint], outputs: &[ConfidentialOutput], deposit: u64, withdrawal: u64) -> String {
+    let inputs: Vec<String> = inputs.iter().map(|input| format!("{}:{}", input.transaction_id, input.index)).collect();
+    let outputs: Vec<String> = outputs.iter().map(|output| output.signing_data()).collect();
+    format!("{}|{}|{}|{}|{}", sender, inputs.join(","), outputs.join(","), deposit, withdrawal)
+}
+
+/// Hashes a public key to a point nobody knows the discrete logarithm of.
+pub fn hash_to_point(key: &RistrettoPoint) -> RistrettoPoint {
+    let mut hasher = Sha512::new();
+    hasher.update(b"blockc/ring/hash-to-point");
+    hasher.update(key.compress().as_bytes());
+    RistrettoPoint::from_uniform_bytes(&hasher.finalize().into())
+}
+
+/// Key image of a secret key: `x * Hp(x * G)`.
+///
+/// It is the same in every signature made with the key, whatever the ring, and
+/// reveals nothing about which ring member the key belongs to.
+pub fn key_image(secret: &Scalar) -> RistrettoPoint {
+    secret * hash_to_point(&public_key(secret))
+}
+
+/// Linkable ring signature (LSAG).
+///
+/// Proves that the message was signed by the owner of one of the ring's public
+/// keys, without revealing which. Two signatures by the same key carry the same
+/// key image, so the chain can refuse a second one (a double spend) without
+/// learning who signed either.
+///
+/// The signer's position is hidden by a chain of challenges around the ring:
+/// each member's response links one challenge to the next, and only the real
+/// signer can close the loop.
+///
+/// # Fields
+///
+/// * `key_image` - Key image of the signing key
+/// * `challenge` - Challenge of the first ring member
+/// * `responses` - One response per ring member
+///
+/// # Example
+///
+/// ```
+/// use blockc_core::privacy::{public_key, secret_key, RingSignature};
+///
+/// let secrets: Vec<_> = ["a", "b", "c"].iter().map(|key| secret_key(key, "demo")).collect();
+/// let ring: Vec<_> = secrets.iter().map(public_key).collect();
+///
+/// let signature = RingSignature::sign("pay Dave", &ring, &secrets[1]).unwrap();
+/// assert!(signature.verify("pay Dave", &ring).is_ok());
+/// assert!(signature.verify("pay Eve", &ring).is_err());
+///
+/// // Another signature by the same key, even in another ring, is linked
+/// let other = RingSignature::sign("pay Eve", &ring[1..], &secrets[1]).unwrap();
+/// assert!(signature.is_linked(&other));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RingSignature {
+    pub key_image: RistrettoPoint,
+    pub challenge: Scalar,
+    pub responses: Vec<Scalar>,
+}
+
+impl RingSignature {
+    /// Signs `message` with `secret`, whose public key must be in `ring`.
+    ///
+    /// The nonces are derived from the secret and the message, so no random
+    /// number generator is needed.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(RingSignature)` - The signature
+    /// * `Err(String)` - If the ring is smaller than 2 members or the key is not in it
+    pub fn sign(message: &str, ring: &[RistrettoPoint], secret: &Scalar) -> Result<Self, String> {
+        if ring.len() < 2 {
+            return Err("A ring needs at least 2 members".to_string());
+        }
+        let signer = ring.iter().position(|key| *key == public_key(secret))
+            .ok_or("The signing key is not in the ring".to_string())?;
+        let image = key_image(secret);
+        let seed = |purpose: &str, index: usize| {
+            hash_to_scalar(purpose, &[secret.as_bytes(), message.as_bytes(), &index.to_le_bytes()])
+        };
+
+        let n = ring.len();
+        let mut challenges = vec![Scalar::ZERO; n];
+        let mut responses: Vec<Scalar> = (0..n).map(|i| seed("blockc/ring/response", i)).collect();
+        let nonce = seed("blockc/ring/nonce", signer);
+        challenges[(signer + 1) % n] = ring_challenge(message, ring, &image, &public_key(&nonce), &(nonce * hash_to_point(&ring[signer])));
+        for step in 1..n {
+            let i = (signer + step) % n;
+            let (left, right) = announcements(&ring[i], &image, &challenges[i], &responses[i]);
+            challenges[(i + 1) % n] = ring_challenge(message, ring, &image, &left, &right);
+        }
+        responses[signer] = nonce - challenges[signer] * secret;
+        Ok(RingSignature {
+            key_image: image,
+            challenge: challenges[0],
+            responses: responses,
+        })
+    }
+
+    /// Checks the signature of `message` by a member of `ring`.
+    pub fn verify(&self, message: &str, ring: &[RistrettoPoint]) -> Result<(), String> {
+        if ring.len() < 2 || self.responses.len() != ring.len() {
+            return Err(format!("Ring signature has {} responses for {} ring members", self.responses.len(), ring.len()));
+        }
+        let mut challenge = self.challenge;
+        for (key, response) in ring.iter().zip(&self.responses) {
+            let (left, right) = announcements(key, &self.key_image, &challenge, response);
+            challenge = ring_challenge(message, ring, &self.key_image, &left, &right);
+        }
+        if challenge != self.challenge {
+            return Err("Ring signature is not valid for this ring".to_string());
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if both signatures were made with the same key.
+    pub fn is_linked(&self, other: &RingSignature) -> bool {
+        self.key_image == other.key_image
+    }
+
+    /// Returns the data signed by a transaction carrying this signature.
+    pub fn signing_data(&self) -> String {
+        let responses: Vec<String> = self.responses.iter().map(scalar_to_hex).collect();
+        format!("{}/{}/{}", point_to_hex(&self.key_image), scalar_to_hex(&self.challenge), responses.join(","))
+    }
+}
+
+/// Announcements of a ring member: `s * G + c * P` and `s * Hp(P) + c * I`.
+fn announcements(key: &RistrettoPoint, image: &RistrettoPoint, challenge: &Scalar, response: &Scalar) -> (RistrettoPoint, RistrettoPoint) {
+    (public_key(response) + challenge * key, response * hash_to_point(key) + challenge * image)
+}
+
+fn ring_challenge(message: &str, ring: &[RistrettoPoint], image: &RistrettoPoint, left: &RistrettoPoint, right: &RistrettoPoint) -> Scalar {
+    let mut parts: Vec<[u8; 32]> = ring.iter().map(|key| key.compress().to_bytes()).collect();
+    parts.extend([image, left, right].iter().map(|point| point.compress().to_bytes()));
+    let mut data: Vec<&[u8]> = vec![message.as_bytes()];
+    data.extend(parts.iter().map(|part| part.as_slice()));
+    hash_to_scalar("blockc/ring/challenge", &data)
+}
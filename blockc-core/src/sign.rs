@@ -0,0 +1,88 @@
+use alloc::string::String;
+use crate::hash::{sha256_hex, Domain, HashAlgorithm};
+
+/// Returns the public key matching `private_key` (its SHA-256 hash).
+pub fn public_key(private_key: &str) -> String {
+    sha256_hex(private_key)
+}
+
+/// Start of the addresses derived by `address`.
+pub const ADDRESS_PREFIX: &str = "bc";
+
+/// Number of hexadecimal characters after `ADDRESS_PREFIX` in a derived address.
+pub const ADDRESS_HASH_LENGTH: usize = 40;
+
+/// Derives the address of a public key: `ADDRESS_PREFIX` followed by the
+/// first 160 bits of its SHA-256 hash, tagged as an address (see `Domain`).
+///
+/// Accounts may still use any string as their address; a derived one can't
+/// be claimed by someone else without the matching key, and the only way to
+/// choose what it looks like is to try keys until one fits.
+///
+/// # Example
+///
+/// ```
+/// use blockc_core::sign::{address, public_key, ADDRESS_HASH_LENGTH, ADDRESS_PREFIX};
+///
+/// let alice = address(&public_key("alice-secret"));
+/// assert!(alice.starts_with(ADDRESS_PREFIX));
+/// assert_eq!(alice.len(), ADDRESS_PREFIX.len() + ADDRESS_HASH_LENGTH);
+/// assert_ne!(alice, address(&public_key("bob-secret")));
+/// ```
+pub fn address(public_key: &str) -> String {
+    let mut hash = HashAlgorithm::Sha256.tagged_parts_hex(Domain::Address, &[public_key]);
+    hash.truncate(ADDRESS_HASH_LENGTH);
+    hash.insert_str(0, ADDRESS_PREFIX);
+    hash
+}
+
+/// Signs `data` with `private_key`.
+///
+/// The signature is the SHA-256 hash of the data followed by the key, tagged
+/// as a signature (see `Domain`). This is
+/// a simplified scheme for learning purposes: real blockchains use public-key
+/// signatures (ECDSA, Ed25519, ...).
+///
+/// # Arguments
+///
+/// * `data` - The data to sign (e.g. `Transaction::signing_data`)
+/// * `private_key` - Key of the signer
+///
+/// # Returns
+///
+/// A hexadecimal string representing the signature.
+///
+/// # Example
+///
+/// ```
+/// use blockc_core::sign::{sign, transaction_id};
+///
+/// let signature = sign("transfer|AliceBob", "secret");
+/// assert_eq!(signature.len(), 64);
+/// assert_ne!(signature, sign("transfer|AliceBob", "other"));
+/// assert_ne!(transaction_id("transfer|AliceBob", &signature), signature);
+/// ```
+pub fn sign(data: &str, private_key: &str) -> String {
+    HashAlgorithm::Sha256.tagged_parts_hex(Domain::Signature, &[data, private_key])
+}
+
+/// Returns the id of a transaction: the SHA-256 hash of its signing data
+/// followed by its signature, tagged as a transaction id (see `Domain`).
+pub fn transaction_id(signing_data: &str, signature: &str) -> String {
+    transaction_id_with(HashAlgorithm::Sha256, signing_data, signature)
+}
+
+/// Returns the id of a transaction hashed with `algorithm` instead of SHA-256.
+///
+/// # Example
+///
+/// ```
+/// use blockc_core::hash::HashAlgorithm;
+/// use blockc_core::sign::{transaction_id, transaction_id_with};
+///
+/// assert_eq!(transaction_id_with(HashAlgorithm::Sha256, "data", "sig"), transaction_id("data", "sig"));
+/// assert_ne!(transaction_id_with(HashAlgorithm::Blake3, "data", "sig"), transaction_id("data", "sig"));
+/// ```
+pub fn transaction_id_with(algorithm: HashAlgorithm, signing_data: &str, signature: &str) -> String {
+    algorithm.tagged_parts_hex(Domain::TransactionId, &[signing_data, signature])
+}
@@ -0,0 +1,83 @@
+use alloc::format;
+use alloc::string::String;
+use proptest::prelude::*;
+use crate::block::Block;
+use crate::sign;
+use crate::transaction::{Transaction, ENCRYPTED_MEMO_PREFIX, MAX_MEMO_BYTES};
+
+/// Timestamp of generated payments, and of every block in generated chains
+/// (their clock is stopped).
+pub const CHAIN_TIME: u32 = 1_700_000_000;
+
+/// Addresses like "Alice" (a capital letter and a few lowercase ones).
+pub fn address() -> impl Strategy<Value = String> {
+    "[A-Z][a-z]{2,7}"
+}
+
+/// Amounts from 0.01 to 10000, in whole cents.
+pub fn amount() -> impl Strategy<Value = f64> {
+    (1u64..1_000_000).prop_map(|cents| cents as f64 / 100.0)
+}
+
+/// Plain memos within `MAX_MEMO_BYTES` (never mistaken for encrypted ones).
+pub fn memo() -> impl Strategy<Value = Option<String>> {
+    let text = proptest::string::string_regex(&format!("[ -~]{{0,{}}}", MAX_MEMO_BYTES)).expect("valid memo pattern");
+    proptest::option::of(text)
+        .prop_filter("plain memo", |memo| !memo.as_deref().unwrap_or("").starts_with(ENCRYPTED_MEMO_PREFIX))
+}
+
+/// Signed payments between two addresses, with a fee, an optional memo and any nonce.
+///
+/// # Example
+///
+/// ```
+/// use proptest::test_runner::TestRunner;
+/// use blockc_core::testing::transaction;
+///
+/// // Every generated payment is well formed and its id is stable
+/// TestRunner::default().run(&transaction(), |tx| {
+///     assert!(tx.check_memo().is_ok());
+///     assert_eq!(tx.id(), tx.clone().id());
+///     Ok(())
+/// }).unwrap();
+/// ```
+pub fn transaction() -> impl Strategy<Value = Transaction> {
+    (address(), address(), amount(), 0u64..100, memo(), 0u64..1_000)
+        .prop_map(|(sender, receiver, amount, fee_cents, memo, nonce)| {
+            // The sender's address doubles as its secret key
+            let mut transaction = Transaction::new(sender.clone(), receiver, amount, fee_cents as f64 / 100.0, nonce, String::new(), CHAIN_TIME);
+            transaction.memo = memo;
+            transaction.attach_signature(sign::sign(&transaction.signing_data(), &sender))
+                .expect("generated signatures are well formed");
+            transaction
+        })
+}
+
+/// Unmined blocks of up to 8 payments, on any previous hash and state root.
+///
+/// They are internally consistent (hash and Merkle root match the data) but
+/// not connected to any chain.
+pub fn block() -> impl Strategy<Value = Block> {
+    (prop::collection::vec(transaction(), 0..8), "[0-9a-f]{64}", 0u64..1_000, "[0-9a-f]{64}", any::<u32>())
+        .prop_map(|(transactions, previous_block_hash, base_fee_cents, state_root, time_stamp)| {
+            Block::new_at(transactions, previous_block_hash, base_fee_cents as f64 / 100.0, state_root, time_stamp)
+        })
+}
+
+impl Arbitrary for Transaction {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        transaction().boxed()
+    }
+}
+
+impl Arbitrary for Block {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        block().boxed()
+    }
+}
@@ -0,0 +1,681 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::gas::{self, TRANSACTION_GAS};
+use crate::hash::HashAlgorithm;
+use crate::header::BlockHeader;
+use crate::instruction::{self, Instruction, MAX_INPUTS};
+use crate::payload::{BridgeProof, ChannelState, EscrowOutcome, JoinInput, OutPoint, Service, Side, VestingSchedule, VoteWeighting};
+#[cfg(feature = "privacy")]
+use crate::privacy::{BalanceProof, ConfidentialOutput, RingSignature};
+use crate::sign;
+
+/// Sender address used for block reward (coinbase) transactions.
+///
+/// Coinbase transactions create new coins, so there is no real sender to debit.
+pub const COINBASE_ADDRESS: &str = "COINBASE";
+
+/// Maximum size of a transaction memo, in bytes (policy limit).
+pub const MAX_MEMO_BYTES: usize = 80;
+
+/// Every memo encrypted to its receiver starts with this prefix; any other memo is plaintext.
+pub const ENCRYPTED_MEMO_PREFIX: &str = "enc:";
+
+/// Bytes an encrypted memo adds to its plaintext (32-byte ephemeral key and 16-byte tag).
+pub const ENCRYPTED_MEMO_OVERHEAD: usize = 48;
+
+/// Maximum size of an encrypted memo on chain: the hex encoding of a
+/// `MAX_MEMO_BYTES` plaintext with its overhead, after the prefix.
+pub const MAX_ENCRYPTED_MEMO_BYTES: usize = ENCRYPTED_MEMO_PREFIX.len() + 2 * (MAX_MEMO_BYTES + ENCRYPTED_MEMO_OVERHEAD);
+
+/// One payment of a multi-recipient transaction.
+/// 
+/// # Fields
+/// 
+/// * `address` - Address receiving the funds
+/// * `amount` - Amount paid to `address`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Output {
+    pub address: String,
+    pub amount: f64,
+}
+
+impl Output {
+    /// Creates an output paying `amount` to `address`.
+    pub fn new(address: String, amount: f64) -> Self {
+        Output {
+            address: address,
+            amount: amount,
+        }
+    }
+}
+
+/// Earliest point at which a transaction can be included in a block.
+/// 
+/// Time locks are compared with the timestamp of the latest block (not the clock
+/// of whoever validates), so every node agrees on whether a lock has expired.
+/// Relative locks count from the block that confirmed another transaction.
+/// 
+/// * `None` - No lock, the transaction can be included right away
+/// * `Height` - The block including it must be at least at this height
+/// * `Timestamp` - The latest block must be at least this recent (Unix time)
+/// * `RelativeHeight` - At least `blocks` blocks after the block confirming `transaction_id`
+/// * `RelativeTime` - At least `seconds` after the block confirming `transaction_id`
+#[derive(Debug, Clone, PartialEq)]
+pub enum LockTime {
+    None,
+    Height(u64),
+    Timestamp(u32),
+    RelativeHeight { transaction_id: String, blocks: u64 },
+    RelativeTime { transaction_id: String, seconds: u32 },
+}
+
+impl LockTime {
+    /// Part of the signed data describing the lock (empty when there is none).
+    pub fn signing_data(&self) -> String {
+        match self {
+            LockTime::None => String::new(),
+            LockTime::Height(height) => format!("lock:height:{}", height),
+            LockTime::Timestamp(time) => format!("lock:time:{}", time),
+            LockTime::RelativeHeight { transaction_id, blocks } => format!("lock:after:{}:{}blocks", transaction_id, blocks),
+            LockTime::RelativeTime { transaction_id, seconds } => format!("lock:after:{}:{}s", transaction_id, seconds),
+        }
+    }
+}
+
+/// What a transaction does.
+/// 
+/// * `Transfer` - Moves `amount` from the sender to the receiver
+/// * `Anchor` - Records a document hash on chain (proof of existence); nothing is transferred
+/// * `MultiPay` - Pays several outputs at once; `amount` is the sum of the outputs
+/// * `HtlcLock` - Locks `amount` in a hash time-locked contract for the receiver
+/// * `HtlcClaim` - Receiver claims an HTLC by revealing the secret, before expiry
+/// * `HtlcRefund` - Sender takes an expired HTLC back
+/// * `ChannelOpen` - Locks `amount` in a payment channel with the receiver
+/// * `ChannelClose` - Submits a signed channel state, starting (or updating) the dispute window
+/// * `ChannelSettle` - Pays out the channel's final state once the dispute window is over
+/// * `EscrowOpen` - Locks `amount` for the receiver (seller) under a 2-of-3 escrow with an arbiter
+/// * `EscrowVote` - A party of an escrow votes to release or refund it
+/// * `VestingGrant` - Pays the receiver coins that unlock over time
+/// * `StreamOpen` - Deposits `amount` that accrues to the receiver at `rate_per_block`
+/// * `StreamWithdraw` - The receiver of a stream withdraws what has accrued
+/// * `StreamCancel` - The sender stops a stream: accrued coins go to the receiver, the rest is refunded
+/// * `Utxo` - Spends unspent outputs owned by the sender and creates new ones (UTXO model);
+///   `amount` is the sum of the new outputs
+/// * `CoinJoin` - Spends outputs of several owners, each signing the whole join, and
+///   creates new ones; the inputs are released to the sender (the coordinator),
+///   which pays the outputs (`amount`) and the fee
+/// * `Deploy` - Creates a contract account running `code`, funded with `amount`
+/// * `Call` - Sends `amount` to a contract and runs its code with `input`
+/// * `AssetIssue` - Creates a fungible asset and gives `initial_supply` units to the sender
+/// * `AssetMint` - The issuer of an asset creates more units (up to its max supply)
+/// * `AssetTransfer` - Sends `quantity` units of an asset to the receiver
+/// * `NftMint` - Creates a non-fungible token owned by the sender
+/// * `NftTransfer` - Gives a token the sender owns to the receiver
+/// * `NftBurn` - Destroys a token the sender owns
+/// * `NameRegister` - Registers a name pointing to `target` (or updates the target of a name the sender owns)
+/// * `NameRenew` - Extends a name the sender owns
+/// * `DidRegister` - Registers the DID document of the sender's address
+/// * `DidUpdate` - Replaces the keys and services of the sender's DID document
+/// * `BallotCreate` - Opens a ballot with options and a voting window
+/// * `BallotVote` - Votes for one option of a ballot
+/// * `AuctionCreate` - Opens an auction (sealed-bid if it has a reveal deadline)
+/// * `AuctionBid` - Bids `amount` in an open auction (the coins are locked)
+/// * `AuctionCommit` - Locks `amount` as the deposit of a hidden bid in a sealed auction
+/// * `AuctionReveal` - Reveals the bid behind a commitment
+/// * `AuctionSettle` - Pays the seller the winning bid and refunds everything else
+/// * `PoolCreate` - Opens a constant-product pool for two assets with its first liquidity
+/// * `PoolDeposit` - Adds liquidity to a pool in exchange for shares
+/// * `PoolWithdraw` - Burns pool shares for their part of the reserves
+/// * `PoolSwap` - Sells one asset of a pool for the other
+/// * `OrderPlace` - Places a limit order, matched against the order book when the block is applied
+/// * `OrderCancel` - Cancels a resting order and unlocks what is left of it
+/// * `OracleCreate` - Creates a data feed with its designated reporters
+/// * `OracleReport` - Publishes a reporter's latest value on a feed
+/// * `BridgeLock` - Locks asset units in escrow for a recipient on another chain
+/// * `BridgeRelay` - Relays block headers of another chain to its bridge
+/// * `BridgeMint` - Mints wrapped units for a proven lock of another chain
+/// * `BridgeBurn` - Burns wrapped units to release the original asset on its chain
+/// * `BridgeRelease` - Releases escrowed units for a proven burn of another chain
+/// * `StealthTransfer` - Pays a one-time stealth address, with the ephemeral key the
+///   receiver needs to find and spend the payment
+/// * `ConfidentialTransfer` - Spends hidden outputs of the sender and creates new ones
+///   whose amounts are only committed to; `amount` is deposited from the sender's
+///   public balance and `withdrawal` released back to it (`privacy` feature)
+/// * `RingDeposit` - Deposits `amount` under a one-time key that a ring signature can
+///   later withdraw from (`privacy` feature)
+/// * `RingWithdraw` - Withdraws one of the ring's deposits to the sender without
+///   revealing which (`privacy` feature)
+///
+/// Asset, NFT, name, DID, ballot, pool, order, oracle and bridge transactions, and
+/// auction transactions other than bids and commitments, move no native coins: their
+/// `amount` must be 0.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransactionKind {
+    Transfer,
+    Anchor { doc_hash: String },
+    MultiPay { outputs: Vec<Output> },
+    HtlcLock { hash_lock: String, expiry_height: u64 },
+    HtlcClaim { htlc_id: String, preimage: String },
+    HtlcRefund { htlc_id: String },
+    ChannelOpen,
+    ChannelClose { state: ChannelState },
+    ChannelSettle { channel_id: String },
+    EscrowOpen { arbiter: String },
+    EscrowVote { escrow_id: String, outcome: EscrowOutcome },
+    VestingGrant { schedule: VestingSchedule },
+    StreamOpen { rate_per_block: f64 },
+    StreamWithdraw { stream_id: String },
+    StreamCancel { stream_id: String },
+    Utxo { inputs: Vec<OutPoint>, outputs: Vec<Output> },
+    CoinJoin { inputs: Vec<JoinInput>, outputs: Vec<Output> },
+    Deploy { code: Vec<Instruction> },
+    Call { contract: String, input: Vec<i64> },
+    AssetIssue { ticker: String, decimals: u8, max_supply: u64, initial_supply: u64 },
+    AssetMint { asset_id: String, quantity: u64 },
+    AssetTransfer { asset_id: String, quantity: u64 },
+    NftMint { token_id: String, metadata_hash: Option<String> },
+    NftTransfer { token_id: String },
+    NftBurn { token_id: String },
+    NameRegister { name: String, target: String },
+    NameRenew { name: String },
+    DidRegister { public_keys: Vec<String>, services: Vec<Service> },
+    DidUpdate { public_keys: Vec<String>, services: Vec<Service> },
+    BallotCreate { question: String, options: Vec<String>, start_height: u64, end_height: u64, weighting: VoteWeighting },
+    BallotVote { ballot_id: String, option: usize },
+    AuctionCreate { item: String, reserve_price: f64, bidding_end: u64, reveal_end: Option<u64> },
+    AuctionBid { auction_id: String },
+    AuctionCommit { auction_id: String, commitment: String },
+    AuctionReveal { auction_id: String, bid: f64, salt: String },
+    AuctionSettle { auction_id: String },
+    PoolCreate { asset_a: String, asset_b: String, amount_a: u64, amount_b: u64, fee_bps: u32 },
+    PoolDeposit { pool_id: String, amount_a: u64, amount_b: u64 },
+    PoolWithdraw { pool_id: String, shares: u64 },
+    PoolSwap { pool_id: String, asset_in: String, amount_in: u64, min_out: u64 },
+    OrderPlace { base: String, quote: String, side: Side, price: u64, quantity: u64 },
+    OrderCancel { order_id: String },
+    OracleCreate { feed: String, reporters: Vec<String>, quorum: usize, max_age: u64 },
+    OracleReport { feed: String, value: f64 },
+    BridgeLock { chain_id: String, asset_id: String, ticker: String, decimals: u8, quantity: u64, recipient: String },
+    BridgeRelay { chain_id: String, headers: Vec<BlockHeader> },
+    BridgeMint { chain_id: String, proof: Box<BridgeProof> },
+    BridgeBurn { chain_id: String, asset_id: String, quantity: u64, recipient: String },
+    BridgeRelease { chain_id: String, proof: Box<BridgeProof> },
+    StealthTransfer { ephemeral_key: String },
+    #[cfg(feature = "privacy")]
+    ConfidentialTransfer { inputs: Vec<OutPoint>, outputs: Vec<ConfidentialOutput>, withdrawal: f64, proof: BalanceProof },
+    #[cfg(feature = "privacy")]
+    RingDeposit { key: String },
+    #[cfg(feature = "privacy")]
+    RingWithdraw { ring: Vec<String>, signature: RingSignature },
+}
+
+impl TransactionKind {
+    /// Short name of the kind; the signed data of every transaction starts with it.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TransactionKind::Transfer => "transfer",
+            TransactionKind::Anchor { .. } => "anchor",
+            TransactionKind::MultiPay { .. } => "multipay",
+            TransactionKind::HtlcLock { .. } => "htlc",
+            TransactionKind::HtlcClaim { .. } => "htlc_claim",
+            TransactionKind::HtlcRefund { .. } => "htlc_refund",
+            TransactionKind::ChannelOpen => "channel_open",
+            TransactionKind::ChannelClose { .. } => "channel_close",
+            TransactionKind::ChannelSettle { .. } => "channel_settle",
+            TransactionKind::EscrowOpen { .. } => "escrow",
+            TransactionKind::EscrowVote { .. } => "escrow_vote",
+            TransactionKind::VestingGrant { .. } => "vesting",
+            TransactionKind::StreamOpen { .. } => "stream",
+            TransactionKind::StreamWithdraw { .. } => "stream_withdraw",
+            TransactionKind::StreamCancel { .. } => "stream_cancel",
+            TransactionKind::Utxo { .. } => "utxo",
+            TransactionKind::CoinJoin { .. } => "coinjoin",
+            TransactionKind::Deploy { .. } => "deploy",
+            TransactionKind::Call { .. } => "call",
+            TransactionKind::AssetIssue { .. } => "asset_issue",
+            TransactionKind::AssetMint { .. } => "asset_mint",
+            TransactionKind::AssetTransfer { .. } => "asset_transfer",
+            TransactionKind::NftMint { .. } => "nft_mint",
+            TransactionKind::NftTransfer { .. } => "nft_transfer",
+            TransactionKind::NftBurn { .. } => "nft_burn",
+            TransactionKind::NameRegister { .. } => "name_register",
+            TransactionKind::NameRenew { .. } => "name_renew",
+            TransactionKind::DidRegister { .. } => "did_register",
+            TransactionKind::DidUpdate { .. } => "did_update",
+            TransactionKind::BallotCreate { .. } => "ballot_create",
+            TransactionKind::BallotVote { .. } => "ballot_vote",
+            TransactionKind::AuctionCreate { .. } => "auction_create",
+            TransactionKind::AuctionBid { .. } => "auction_bid",
+            TransactionKind::AuctionCommit { .. } => "auction_commit",
+            TransactionKind::AuctionReveal { .. } => "auction_reveal",
+            TransactionKind::AuctionSettle { .. } => "auction_settle",
+            TransactionKind::PoolCreate { .. } => "pool_create",
+            TransactionKind::PoolDeposit { .. } => "pool_deposit",
+            TransactionKind::PoolWithdraw { .. } => "pool_withdraw",
+            TransactionKind::PoolSwap { .. } => "pool_swap",
+            TransactionKind::OrderPlace { .. } => "order_place",
+            TransactionKind::OrderCancel { .. } => "order_cancel",
+            TransactionKind::OracleCreate { .. } => "oracle_create",
+            TransactionKind::OracleReport { .. } => "oracle_report",
+            TransactionKind::BridgeLock { .. } => "bridge_lock",
+            TransactionKind::BridgeRelay { .. } => "bridge_relay",
+            TransactionKind::BridgeMint { .. } => "bridge_mint",
+            TransactionKind::BridgeBurn { .. } => "bridge_burn",
+            TransactionKind::BridgeRelease { .. } => "bridge_release",
+            TransactionKind::StealthTransfer { .. } => "stealth_transfer",
+            #[cfg(feature = "privacy")]
+            TransactionKind::ConfidentialTransfer { .. } => "confidential_transfer",
+            #[cfg(feature = "privacy")]
+            TransactionKind::RingDeposit { .. } => "ring_deposit",
+            #[cfg(feature = "privacy")]
+            TransactionKind::RingWithdraw { .. } => "ring_withdraw",
+        }
+    }
+
+    /// Part of the signed data describing the kind (empty for plain transfers).
+    pub fn signing_data(&self) -> String {
+        match self {
+            TransactionKind::Transfer => String::new(),
+            TransactionKind::Anchor { doc_hash } => format!("anchor:{}", doc_hash),
+            TransactionKind::MultiPay { outputs } => {
+                let parts: Vec<String> = outputs.iter()
+                    .map(|output| format!("{}:{}", output.address, output.amount))
+                    .collect();
+                format!("multipay:{}", parts.join(","))
+            }
+            TransactionKind::HtlcLock { hash_lock, expiry_height } => format!("htlc:{}:{}", hash_lock, expiry_height),
+            TransactionKind::HtlcClaim { htlc_id, preimage } => format!("htlc_claim:{}:{}", htlc_id, preimage),
+            TransactionKind::HtlcRefund { htlc_id } => format!("htlc_refund:{}", htlc_id),
+            TransactionKind::ChannelOpen => "channel_open".to_string(),
+            TransactionKind::ChannelClose { state } => {
+                format!("channel_close:{}{}{}", state.signing_data(), state.signature_a, state.signature_b)
+            }
+            TransactionKind::ChannelSettle { channel_id } => format!("channel_settle:{}", channel_id),
+            TransactionKind::EscrowOpen { arbiter } => format!("escrow:{}", arbiter),
+            TransactionKind::EscrowVote { escrow_id, outcome } => format!("escrow_vote:{}:{:?}", escrow_id, outcome),
+            TransactionKind::VestingGrant { schedule } => format!("vesting:{}", schedule.signing_data()),
+            TransactionKind::StreamOpen { rate_per_block } => format!("stream:{}", rate_per_block),
+            TransactionKind::StreamWithdraw { stream_id } => format!("stream_withdraw:{}", stream_id),
+            TransactionKind::StreamCancel { stream_id } => format!("stream_cancel:{}", stream_id),
+            TransactionKind::Utxo { inputs, outputs } => {
+                let ins: Vec<String> = inputs.iter()
+                    .map(|input| format!("{}:{}", input.transaction_id, input.index))
+                    .collect();
+                let outs: Vec<String> = outputs.iter()
+                    .map(|output| format!("{}:{}", output.address, output.amount))
+                    .collect();
+                format!("utxo:{}->{}", ins.join(","), outs.join(","))
+            }
+            TransactionKind::CoinJoin { inputs, outputs } => {
+                let ins: Vec<String> = inputs.iter().map(|input| input.signing_data()).collect();
+                let outs: Vec<String> = outputs.iter()
+                    .map(|output| format!("{}:{}", output.address, output.amount))
+                    .collect();
+                format!("coinjoin:{}->{}", ins.join(","), outs.join(","))
+            }
+            TransactionKind::Deploy { code } => format!("deploy:{}", instruction::encode(code).replace('\n', ";")),
+            TransactionKind::Call { contract, input } => {
+                let args: Vec<String> = input.iter().map(|value| value.to_string()).collect();
+                format!("call:{}:{}", contract, args.join(","))
+            }
+            TransactionKind::AssetIssue { ticker, decimals, max_supply, initial_supply } => {
+                format!("asset_issue:{}:{}:{}:{}", ticker, decimals, max_supply, initial_supply)
+            }
+            TransactionKind::AssetMint { asset_id, quantity } => format!("asset_mint:{}:{}", asset_id, quantity),
+            TransactionKind::AssetTransfer { asset_id, quantity } => format!("asset_transfer:{}:{}", asset_id, quantity),
+            TransactionKind::NftMint { token_id, metadata_hash } => {
+                format!("nft_mint:{}:{}", token_id, metadata_hash.as_deref().unwrap_or(""))
+            }
+            TransactionKind::NftTransfer { token_id } => format!("nft_transfer:{}", token_id),
+            TransactionKind::NftBurn { token_id } => format!("nft_burn:{}", token_id),
+            TransactionKind::NameRegister { name, target } => format!("name_register:{}:{}", name, target),
+            TransactionKind::NameRenew { name } => format!("name_renew:{}", name),
+            TransactionKind::DidRegister { public_keys, services } | TransactionKind::DidUpdate { public_keys, services } => {
+                let services: Vec<String> = services.iter().map(|service| service.signing_data()).collect();
+                format!("{}:{}:{}", self.name(), public_keys.join(","), services.join(","))
+            }
+            TransactionKind::BallotCreate { question, options, start_height, end_height, weighting } => {
+                format!("ballot_create:{}:{}:{}:{}:{:?}", question, options.join(","), start_height, end_height, weighting)
+            }
+            TransactionKind::BallotVote { ballot_id, option } => format!("ballot_vote:{}:{}", ballot_id, option),
+            TransactionKind::AuctionCreate { item, reserve_price, bidding_end, reveal_end } => {
+                format!("auction_create:{}:{}:{}:{:?}", item, reserve_price, bidding_end, reveal_end)
+            }
+            TransactionKind::AuctionBid { auction_id } => format!("auction_bid:{}", auction_id),
+            TransactionKind::AuctionCommit { auction_id, commitment } => format!("auction_commit:{}:{}", auction_id, commitment),
+            TransactionKind::AuctionReveal { auction_id, bid, salt } => format!("auction_reveal:{}:{}:{}", auction_id, bid, salt),
+            TransactionKind::AuctionSettle { auction_id } => format!("auction_settle:{}", auction_id),
+            TransactionKind::PoolCreate { asset_a, asset_b, amount_a, amount_b, fee_bps } => {
+                format!("pool_create:{}:{}:{}:{}:{}", asset_a, asset_b, amount_a, amount_b, fee_bps)
+            }
+            TransactionKind::PoolDeposit { pool_id, amount_a, amount_b } => format!("pool_deposit:{}:{}:{}", pool_id, amount_a, amount_b),
+            TransactionKind::PoolWithdraw { pool_id, shares } => format!("pool_withdraw:{}:{}", pool_id, shares),
+            TransactionKind::PoolSwap { pool_id, asset_in, amount_in, min_out } => {
+                format!("pool_swap:{}:{}:{}:{}", pool_id, asset_in, amount_in, min_out)
+            }
+            TransactionKind::OrderPlace { base, quote, side, price, quantity } => {
+                format!("order_place:{}:{}:{}:{}:{}", base, quote, side.name(), price, quantity)
+            }
+            TransactionKind::OrderCancel { order_id } => format!("order_cancel:{}", order_id),
+            TransactionKind::OracleCreate { feed, reporters, quorum, max_age } => {
+                format!("oracle_create:{}:{}:{}:{}", feed, reporters.join(","), quorum, max_age)
+            }
+            TransactionKind::OracleReport { feed, value } => format!("oracle_report:{}:{}", feed, value),
+            TransactionKind::BridgeLock { chain_id, asset_id, ticker, decimals, quantity, recipient } => {
+                format!("bridge_lock:{}:{}:{}:{}:{}:{}", chain_id, asset_id, ticker, decimals, quantity, recipient)
+            }
+            TransactionKind::BridgeRelay { chain_id, headers } => {
+                let hashes: Vec<&str> = headers.iter().map(|header| header.block_hash.as_str()).collect();
+                format!("bridge_relay:{}:{}", chain_id, hashes.join(","))
+            }
+            TransactionKind::BridgeMint { chain_id, proof } => format!("bridge_mint:{}:{}", chain_id, proof.signing_data()),
+            TransactionKind::BridgeBurn { chain_id, asset_id, quantity, recipient } => {
+                format!("bridge_burn:{}:{}:{}:{}", chain_id, asset_id, quantity, recipient)
+            }
+            TransactionKind::BridgeRelease { chain_id, proof } => format!("bridge_release:{}:{}", chain_id, proof.signing_data()),
+            TransactionKind::StealthTransfer { ephemeral_key } => format!("stealth:{}", ephemeral_key),
+            #[cfg(feature = "privacy")]
+            TransactionKind::ConfidentialTransfer { inputs, outputs, withdrawal, proof } => {
+                let inputs: Vec<String> = inputs.iter().map(|input| format!("{}:{}", input.transaction_id, input.index)).collect();
+                let outputs: Vec<String> = outputs.iter().map(|output| output.signing_data()).collect();
+                format!("confidential:{}:{}:{}:{}", inputs.join(","), outputs.join(","), withdrawal, proof.signing_data())
+            }
+            #[cfg(feature = "privacy")]
+            TransactionKind::RingDeposit { key } => format!("ring_deposit:{}", key),
+            #[cfg(feature = "privacy")]
+            TransactionKind::RingWithdraw { ring, signature } => format!("ring_withdraw:{}:{}", ring.join(","), signature.signing_data()),
+        }
+    }
+}
+
+/// Represents a transaction between two entities in the blockchain.
+/// 
+/// Transactions store only addresses (not full Entity objects) to:
+/// - Avoid stale data issues
+/// - Match real blockchain design
+/// - Reduce memory usage
+/// - Improve data consistency
+/// 
+/// # Fields
+/// 
+/// * `sender_address` - Address of the entity sending funds
+/// * `receiver_address` - Address of the entity receiving funds
+/// * `amount` - Amount being transferred
+/// * `fee` - Fee paid by the sender to the block producer
+/// * `nonce` - Sequence number of the sender's transactions (0, 1, 2, ...)
+/// * `timestamp` - Unix timestamp when transaction was created
+/// * `signature` - Digital signature created by the sender
+/// * `memo` - Optional note (e.g. an invoice number), covered by the signature;
+///   plaintext, or encrypted to the receiver if it starts with `ENCRYPTED_MEMO_PREFIX`
+/// * `kind` - What the transaction does (transfer, document anchor, ...)
+/// * `lock_time` - Earliest block the transaction can be included in
+/// * `gas_limit` - Most gas the transaction can use (at least its intrinsic gas)
+/// * `gas_price` - Coins paid per unit of gas used, on top of `fee` (0 by default)
+/// * `script` - Optional validation script (`scripting` feature): the transaction is
+///   only valid while it returns `true`
+/// 
+/// # Example
+/// 
+/// ```
+/// use blockc_core::sign;
+/// use blockc_core::transaction::Transaction;
+/// 
+/// // Build and sign a payment without a chain (or a clock)
+/// let mut transaction = Transaction::new("Alice".to_string(), "Bob".to_string(), 50.0, 0.1, 0, String::new(), 1_700_000_000);
+/// let signature = sign::sign(&transaction.signing_data(), "alice-secret");
+/// transaction.attach_signature(signature).unwrap();
+/// assert!(transaction.is_signed());
+/// assert!(transaction.check_memo().is_ok());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transaction 
+{
+    pub sender_address: String,
+    pub receiver_address: String,
+    pub amount: f64,
+    pub fee: f64,
+    pub nonce: u64,
+    pub timestamp: u32,
+    pub signature: String,
+    pub memo: Option<String>,
+    pub kind: TransactionKind,
+    pub lock_time: LockTime,
+    pub gas_limit: u64,
+    pub gas_price: f64,
+    pub script: Option<String>,
+}
+
+impl Transaction {
+    /// Creates an unsigned transfer stamped with `time_stamp` (see `attach_signature`).
+    pub fn new(sender_address: String, receiver_address: String, amount_tx: f64, fee: f64, nonce: u64, signature: String, time_stamp: u32) -> Self
+    {
+        Transaction
+        {
+            sender_address: sender_address,
+            receiver_address: receiver_address,
+            amount: amount_tx,
+            fee: fee,
+            nonce: nonce,
+            timestamp: time_stamp,
+            signature: signature,
+            memo: None,
+            kind: TransactionKind::Transfer,
+            lock_time: LockTime::None,
+            gas_limit: TRANSACTION_GAS,
+            gas_price: 0.0,
+            script: None,
+        }
+
+    }
+
+    /// Creates a coinbase transaction stamped with `time_stamp`, which pays
+    /// `amount` of newly created coins.
+    ///
+    /// Coinbase transactions are built by the chain itself when a block is added
+    /// (block reward, fees, treasury share), so they carry no signature.
+    pub fn coinbase_at(receiver_address: String, amount: f64, time_stamp: u32) -> Self {
+        Transaction::new(COINBASE_ADDRESS.to_string(), receiver_address, amount, 0.0, 0, String::new(), time_stamp)
+    }
+
+    /// Returns `true` if this is a coinbase (block reward) transaction.
+    pub fn is_coinbase(&self) -> bool {
+        self.sender_address == COINBASE_ADDRESS
+    }
+
+    /// Returns `true` if the transaction carries a signature.
+    /// 
+    /// Only signed transactions are accepted by `Blockchain::submit_transaction`.
+    pub fn is_signed(&self) -> bool {
+        !self.signature.is_empty()
+    }
+
+    /// Attaches a signature made elsewhere (e.g. by a `TransactionSigner`) to an
+    /// unsigned transaction.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(())` - The transaction is signed
+    /// * `Err(String)` - If the transaction is already signed, or the signature is
+    ///   not 64 hexadecimal characters
+    pub fn attach_signature(&mut self, signature: String) -> Result<(), String> {
+        if self.is_signed() {
+            return Err("Transaction is already signed".to_string());
+        }
+        if signature.len() != 64 || !signature.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err("A signature is 64 hexadecimal characters".to_string());
+        }
+        self.signature = signature;
+        Ok(())
+    }
+
+    /// Returns the id of this transaction: the SHA-256 hash of all its data,
+    /// signature included.
+    /// 
+    /// # Returns
+    /// 
+    /// A hexadecimal string identifying the transaction.
+    pub fn id(&self) -> String {
+        sign::transaction_id(&self.signing_data(), &self.signature)
+    }
+
+    /// Returns the id of this transaction hashed with `algorithm` (e.g. BLAKE3
+    /// for an indexer that hashes many transactions).
+    /// 
+    /// Chains always identify transactions by `id`: HTLCs, channels, outpoints
+    /// and Merkle proofs refer to those ids, whatever hashes the block headers.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc_core::hash::HashAlgorithm;
+    /// use blockc_core::transaction::Transaction;
+    /// 
+    /// let coinbase = Transaction::coinbase_at("Miner".to_string(), 50.0, 1_700_000_000);
+    /// assert_eq!(coinbase.id_with(HashAlgorithm::Sha256), coinbase.id());
+    /// assert_ne!(coinbase.id_with(HashAlgorithm::Blake3), coinbase.id());
+    /// ```
+    pub fn id_with(&self, algorithm: HashAlgorithm) -> String {
+        sign::transaction_id_with(algorithm, &self.signing_data(), &self.signature)
+    }
+
+    /// Returns `true` if this transaction credits `address` (the receiver of a
+    /// transfer, or any output of a multi-recipient payment).
+    pub fn pays(&self, address: &str) -> bool {
+        match &self.kind {
+            TransactionKind::Transfer | TransactionKind::VestingGrant { .. } | TransactionKind::StealthTransfer { .. } => {
+                self.receiver_address == address
+            }
+            TransactionKind::MultiPay { outputs } | TransactionKind::Utxo { outputs, .. } | TransactionKind::CoinJoin { outputs, .. } => {
+                outputs.iter().any(|output| output.address == address)
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns how much this transaction credits `address` (see `pays`).
+    pub fn amount_paid_to(&self, address: &str) -> f64 {
+        match &self.kind {
+            TransactionKind::Transfer | TransactionKind::VestingGrant { .. } | TransactionKind::StealthTransfer { .. }
+                if self.receiver_address == address => self.amount,
+            TransactionKind::MultiPay { outputs } | TransactionKind::Utxo { outputs, .. } | TransactionKind::CoinJoin { outputs, .. } => {
+                outputs.iter().filter(|output| output.address == address).map(|output| output.amount).sum()
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Most the sender can pay for this transaction (amount + fee + all its gas).
+    ///
+    /// The sender must afford this up front; the price of unused gas is refunded
+    /// once the transaction is applied.
+    pub fn total_cost(&self) -> f64 {
+        self.amount + self.fee + self.max_gas_fee()
+    }
+
+    /// Gas this transaction costs before any contract code runs (see `gas::intrinsic_gas`).
+    pub fn intrinsic_gas(&self) -> u64 {
+        gas::intrinsic_gas(&self.kind)
+    }
+
+    /// Price of the whole gas limit (`gas_limit * gas_price`).
+    pub fn max_gas_fee(&self) -> f64 {
+        self.gas_limit as f64 * self.gas_price
+    }
+
+    /// Returns the data covered by the sender's signature.
+    /// 
+    /// Everything except the signature itself: changing any field invalidates it.
+    /// It starts with the kind's name, so a signature for one kind of transaction
+    /// (e.g. a transfer whose memo looks like contract code) can never be reused
+    /// for another kind.
+    pub fn signing_data(&self) -> String {
+        format!("{}|{}{}{}{}{}{}{}{}{}{}:{}{}", 
+            self.kind.name(),
+            self.sender_address,      
+            self.receiver_address,   
+            self.amount,          
+            self.fee,
+            self.nonce,
+            self.timestamp,
+            self.memo.as_deref().unwrap_or(""),
+            self.kind.signing_data(),
+            self.lock_time.signing_data(),
+            self.gas_limit,
+            self.gas_price,
+            self.script.as_deref().unwrap_or("")
+        )
+    }
+
+    /// Checks the parts of a contract transaction that don't depend on the chain.
+    ///
+    /// * `Deploy` - No receiver, and the code passes `instruction::validate`
+    /// * `Call` - The receiver is the contract, with at most `MAX_INPUTS` arguments
+    ///
+    /// Other kinds always pass.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the transaction is well formed
+    /// * `Err(String)` - What is wrong with it
+    pub fn check_payload(&self) -> Result<(), String> {
+        match &self.kind {
+            TransactionKind::Deploy { code } => {
+                if !self.receiver_address.is_empty() {
+                    return Err("A deploy transaction has no receiver".to_string());
+                }
+                instruction::validate(code)
+            }
+            TransactionKind::Call { contract, input } => {
+                if self.receiver_address != *contract {
+                    return Err(format!("Call receiver {} is not the contract {}", self.receiver_address, contract));
+                }
+                if input.len() > MAX_INPUTS {
+                    return Err(format!("Call has {} arguments, limit is {}", input.len(), MAX_INPUTS));
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns `true` if the memo is encrypted to the receiver (it starts with
+    /// `ENCRYPTED_MEMO_PREFIX`).
+    pub fn has_encrypted_memo(&self) -> bool {
+        self.memo.as_deref().is_some_and(|memo| memo.starts_with(ENCRYPTED_MEMO_PREFIX))
+    }
+
+    /// Checks the memo against the `MAX_MEMO_BYTES` policy limit.
+    /// 
+    /// Encrypted memos can't be read by the chain, so only their format is
+    /// checked: hex after the prefix, with the overhead and at most
+    /// `MAX_MEMO_BYTES` of ciphertext (`MAX_ENCRYPTED_MEMO_BYTES` in all).
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(())` - If there is no memo or it is small enough
+    /// * `Err(String)` - If the memo is too long, or starts with the encrypted
+    ///   prefix but is malformed
+    pub fn check_memo(&self) -> Result<(), String> {
+        match &self.memo {
+            Some(memo) if memo.starts_with(ENCRYPTED_MEMO_PREFIX) => {
+                let body = &memo[ENCRYPTED_MEMO_PREFIX.len()..];
+                if !body.len().is_multiple_of(2) || !body.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+                    return Err("Encrypted memo is not hex".to_string());
+                }
+                if body.len() < 2 * ENCRYPTED_MEMO_OVERHEAD {
+                    return Err("Encrypted memo is too short".to_string());
+                }
+                if memo.len() > MAX_ENCRYPTED_MEMO_BYTES {
+                    return Err(format!("Encrypted memo is {} bytes, limit is {}", memo.len(), MAX_ENCRYPTED_MEMO_BYTES));
+                }
+                Ok(())
+            }
+            Some(memo) if memo.len() > MAX_MEMO_BYTES => {
+                Err(format!("Memo is {} bytes, limit is {}", memo.len(), MAX_MEMO_BYTES))
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,12 @@
+#![no_main]
+
+use blockc::block::block::{Block, BlockExt};
+use libfuzzer_sys::fuzz_target;
+
+// Any input decodes to a block or an error, and decoded blocks round-trip
+fuzz_target!(|data: &[u8]| {
+    if let Ok(block) = Block::from_bytes(data) {
+        let again = Block::from_bytes(&block.to_bytes()).expect("an encoded block decodes");
+        assert_eq!(again, block);
+    }
+});
@@ -0,0 +1,12 @@
+#![no_main]
+
+use blockc::block::block::{Block, BlockExt};
+use libfuzzer_sys::fuzz_target;
+
+// Any input decodes to a block or an error, and a decoded block has no other
+// encoding than the input
+fuzz_target!(|data: &[u8]| {
+    if let Ok(block) = Block::from_wire(data) {
+        assert_eq!(block.to_wire(), data);
+    }
+});
@@ -0,0 +1,15 @@
+#![no_main]
+
+use blockc::block::block::{Block, BlockExt};
+use blockc::codec::limits::MessageLimits;
+use libfuzzer_sys::fuzz_target;
+
+// The limits never accept a block the plain decoder rejects, and what they
+// accept is within them
+fuzz_target!(|data: &[u8]| {
+    let limits = MessageLimits::default();
+    if let Ok(block) = limits.decode_block_wire(data) {
+        assert!(block.transaction.len() <= limits.max_block_transactions);
+        assert_eq!(Block::from_wire(data).unwrap(), block);
+    }
+});
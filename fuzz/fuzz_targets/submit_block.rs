@@ -0,0 +1,23 @@
+#![no_main]
+
+use std::sync::{Arc, OnceLock};
+use blockc::block::block::{Block, BlockExt};
+use blockc::blockchain::blockchain::Blockchain;
+use blockc::helpers::clock::ManualClock;
+use libfuzzer_sys::fuzz_target;
+
+/// Chain the blocks are submitted to: a fixed genesis at difficulty 0, so the
+/// fuzzer can reach the checks past proof of work.
+fn chain() -> &'static Blockchain {
+    static CHAIN: OnceLock<Blockchain> = OnceLock::new();
+    CHAIN.get_or_init(|| Blockchain::with_clock(0, 50.0, Arc::new(ManualClock::new(1_700_000_000))))
+}
+
+// Validation rejects any decoded block with an error rather than a panic
+fuzz_target!(|data: &[u8]| {
+    if let Ok(block) = Block::from_bytes(data) {
+        let mut chain = chain().clone();
+        let _ = chain.submit_block(block);
+        let _ = chain.validate();
+    }
+});
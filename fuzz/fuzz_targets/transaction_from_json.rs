@@ -0,0 +1,16 @@
+#![no_main]
+
+use blockc::blockchain::blockchain::Blockchain;
+use blockc::transactions::transactions::{Transaction, TransactionExt};
+use libfuzzer_sys::fuzz_target;
+
+// Decoded transactions round-trip, and the mempool rejects bad ones without panicking
+fuzz_target!(|data: &str| {
+    if let Ok(transaction) = Transaction::from_json(data) {
+        let again = Transaction::from_json(&transaction.to_json()).expect("an encoded transaction decodes");
+        assert_eq!(again, transaction);
+
+        let mut chain = Blockchain::with_settings(1, 50.0);
+        let _ = chain.submit_transaction(transaction);
+    }
+});
@@ -0,0 +1,12 @@
+#![no_main]
+
+use blockc::vm::instruction::{encode, parse, validate};
+use libfuzzer_sys::fuzz_target;
+
+// Contract source parses or fails cleanly, and parsed code re-parses the same
+fuzz_target!(|data: &str| {
+    if let Ok(code) = parse(data) {
+        let _ = validate(&code);
+        assert_eq!(parse(&encode(&code)), Ok(code));
+    }
+});
@@ -0,0 +1,179 @@
+#ifndef BLOCKC_H
+#define BLOCKC_H
+
+/* Generated by cbindgen from src/ffi/ffi.rs; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Result of every `blockc_*` function that can fail.
+//
+// When it is not `Ok`, `blockc_last_error` describes what went wrong.
+typedef enum BlockcStatus {
+  // Success
+  BLOCKC_STATUS_OK = 0,
+  // A pointer argument was null
+  BLOCKC_STATUS_NULL_ARGUMENT = 1,
+  // A string argument was not UTF-8
+  BLOCKC_STATUS_INVALID_UTF8 = 2,
+  // The chain or the wallet refused the operation (see `blockc_last_error`)
+  BLOCKC_STATUS_REJECTED = 3,
+  // The library panicked; the handles involved should not be used again
+  BLOCKC_STATUS_PANIC = 4,
+} BlockcStatus;
+
+// Opaque handle of a chain, created by `blockc_chain_new` and freed by `blockc_chain_free`.
+typedef struct BlockcChain BlockcChain;
+
+// Opaque handle of a wallet, created by `blockc_wallet_new` and freed by `blockc_wallet_free`.
+typedef struct BlockcWallet BlockcWallet;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Returns the message of the last failed call on this thread, or null.
+//
+// The string belongs to the library and stays valid until the next
+// `blockc_*` call on the same thread.
+const char *blockc_last_error(void);
+
+// Frees a string returned by the library.
+//
+// # Safety
+//
+// `text` must be null or a string returned through an `out` argument, not freed yet.
+void blockc_string_free(char *text);
+
+// Creates a chain with its genesis block. Free it with `blockc_chain_free`.
+struct BlockcChain *blockc_chain_new(uint32_t difficulty, double block_reward);
+
+// Frees a chain.
+//
+// # Safety
+//
+// `chain` must be null or a handle from `blockc_chain_new`, not freed yet.
+void blockc_chain_free(struct BlockcChain *chain);
+
+// Returns the height of the tip (the genesis block is 0), or 0 if `chain` is null.
+//
+// # Safety
+//
+// `chain` must be null or a live chain handle.
+uint64_t blockc_chain_height(const struct BlockcChain *chain);
+
+// Writes the balance of `address` to `*balance`.
+//
+// # Safety
+//
+// `chain` must be a live chain handle, `address` a NUL-terminated string and
+// `balance` valid for writes.
+enum BlockcStatus blockc_chain_balance(const struct BlockcChain *chain,
+                                       const char *address,
+                                       double *balance);
+
+// Writes the hash of the tip to `*hash`; free it with `blockc_string_free`.
+//
+// # Safety
+//
+// `chain` must be a live chain handle and `hash` valid for writes.
+enum BlockcStatus blockc_chain_latest_hash(const struct BlockcChain *chain, char **hash);
+
+// Submits a signed transaction, given as JSON, to the mempool.
+//
+// # Safety
+//
+// `chain` must be a live chain handle and `transaction_json` a NUL-terminated string.
+enum BlockcStatus blockc_chain_submit(struct BlockcChain *chain, const char *transaction_json);
+
+// Mines a block from the mempool for `miner`.
+//
+// # Safety
+//
+// `chain` must be a live chain handle and `miner` a NUL-terminated string.
+enum BlockcStatus blockc_chain_mine(struct BlockcChain *chain, const char *miner);
+
+// Checks the whole chain; on failure `blockc_last_error` names the first problem.
+//
+// # Safety
+//
+// `chain` must be a live chain handle.
+enum BlockcStatus blockc_chain_validate(const struct BlockcChain *chain);
+
+// Creates a wallet for `address`; the same non-zero `seed` always gives the
+// same keys, 0 picks a fresh seed. Free it with `blockc_wallet_free`.
+//
+// Returns null if `address` is null or not UTF-8.
+//
+// # Safety
+//
+// `address` must be null or a NUL-terminated string.
+struct BlockcWallet *blockc_wallet_new(const char *address, uint64_t seed);
+
+// Frees a wallet.
+//
+// # Safety
+//
+// `wallet` must be null or a handle from `blockc_wallet_new`, not freed yet.
+void blockc_wallet_free(struct BlockcWallet *wallet);
+
+// Signs a payment of `amount` to a name or an address and writes it to
+// `*transaction_json`, ready for `blockc_chain_submit`; free it with
+// `blockc_string_free`.
+//
+// The wallet syncs with `chain` first. A negative `fee` means twice the base fee.
+//
+// # Safety
+//
+// `wallet` and `chain` must be live handles, `recipient` a NUL-terminated
+// string and `transaction_json` valid for writes.
+//
+// # Example
+//
+// ```
+// use std::ffi::{CStr, CString};
+// use std::ptr;
+// use blockc::ffi::ffi::*;
+//
+// unsafe {
+//     let chain = blockc_chain_new(1, 50.0);
+//     let alice = CString::new("Alice").unwrap();
+//     assert_eq!(blockc_chain_mine(chain, alice.as_ptr()), BlockcStatus::Ok);
+//
+//     let wallet = blockc_wallet_new(alice.as_ptr(), 7);
+//     let bob = CString::new("Bob").unwrap();
+//     let mut json = ptr::null_mut();
+//     assert_eq!(blockc_wallet_pay(wallet, chain, bob.as_ptr(), 10.0, -1.0, &mut json), BlockcStatus::Ok);
+//     assert_eq!(blockc_chain_submit(chain, json), BlockcStatus::Ok);
+//
+//     // The same transaction again is a replay
+//     assert_eq!(blockc_chain_submit(chain, json), BlockcStatus::Rejected);
+//     assert!(!CStr::from_ptr(blockc_last_error()).to_str().unwrap().is_empty());
+//     blockc_string_free(json);
+//
+//     assert_eq!(blockc_chain_mine(chain, alice.as_ptr()), BlockcStatus::Ok);
+//     let mut balance = 0.0;
+//     assert_eq!(blockc_chain_balance(chain, bob.as_ptr(), &mut balance), BlockcStatus::Ok);
+//     assert_eq!(balance, 10.0);
+//     assert_eq!(blockc_chain_height(chain), 2);
+//     assert_eq!(blockc_chain_validate(chain), BlockcStatus::Ok);
+//     assert_eq!(blockc_chain_validate(ptr::null()), BlockcStatus::NullArgument);
+//
+//     blockc_wallet_free(wallet);
+//     blockc_chain_free(chain);
+// }
+// ```
+enum BlockcStatus blockc_wallet_pay(struct BlockcWallet *wallet,
+                                    const struct BlockcChain *chain,
+                                    const char *recipient,
+                                    double amount,
+                                    double fee,
+                                    char **transaction_json);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* BLOCKC_H */
@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+
+/// Maximum number of decimals an asset can have.
+pub const MAX_DECIMALS: u8 = 18;
+
+/// Maximum length of an asset ticker.
+pub const MAX_TICKER_LENGTH: usize = 8;
+
+/// A fungible token issued on the chain, next to the native coin.
+///
+/// Quantities are whole numbers of the smallest unit: with 2 decimals, a
+/// quantity of 150 is displayed as "1.50".
+///
+/// # Fields
+///
+/// * `id` - Id of the transaction that issued the asset
+/// * `ticker` - Short unique name (e.g. "USD")
+/// * `decimals` - Number of decimals used to display quantities
+/// * `max_supply` - Most units that can ever exist
+/// * `supply` - Units issued so far
+/// * `issuer` - Address allowed to mint more units
+///
+/// # Example
+///
+/// ```
+/// use blockc::assets::asset::Asset;
+///
+/// let asset = Asset {
+///     id: "usd".to_string(),
+///     ticker: "USD".to_string(),
+///     decimals: 2,
+///     max_supply: 1_000_000,
+///     supply: 150,
+///     issuer: "Bank".to_string(),
+/// };
+/// assert_eq!(asset.format_quantity(150), "1.50");
+/// assert_eq!(asset.remaining_supply(), 999_850);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Asset {
+    pub id: String,
+    pub ticker: String,
+    pub decimals: u8,
+    pub max_supply: u64,
+    pub supply: u64,
+    pub issuer: String,
+}
+
+impl Asset {
+    /// Units that can still be minted.
+    pub fn remaining_supply(&self) -> u64 {
+        self.max_supply - self.supply
+    }
+
+    /// Displays `quantity` with the asset's decimals.
+    pub fn format_quantity(&self, quantity: u64) -> String {
+        if self.decimals == 0 {
+            return quantity.to_string();
+        }
+        let scale = 10u128.pow(self.decimals as u32);
+        let quantity = quantity as u128;
+        format!("{}.{:0width$}", quantity / scale, quantity % scale, width = self.decimals as usize)
+    }
+}
+
+/// Checks the ticker and decimals of a new asset.
+///
+/// A ticker is 1 to `MAX_TICKER_LENGTH` uppercase letters or digits.
+pub fn check_definition(ticker: &str, decimals: u8) -> Result<(), String> {
+    if ticker.is_empty() || ticker.len() > MAX_TICKER_LENGTH || !ticker.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()) {
+        return Err(format!("Ticker {:?} must be 1 to {} uppercase letters or digits", ticker, MAX_TICKER_LENGTH));
+    }
+    if decimals > MAX_DECIMALS {
+        return Err(format!("An asset can have at most {} decimals", MAX_DECIMALS));
+    }
+    Ok(())
+}
+
+/// Registry of issued assets and of who holds how much of each.
+///
+/// # Fields
+///
+/// * `assets` - Issued assets, by id
+/// * `balances` - Units held, by asset id then address
+///
+/// # Example
+///
+/// ```
+/// use blockc::assets::asset::AssetLedger;
+///
+/// let mut ledger = AssetLedger::new();
+/// ledger.issue("gold", "GOLD", 0, 100, "Mine", 60).unwrap();
+/// ledger.transfer("gold", "Mine", "Alice", 25).unwrap();
+/// ledger.mint("gold", "Mine", 40).unwrap();
+///
+/// assert_eq!(ledger.balance_of("Alice", "gold"), 25);
+/// assert_eq!(ledger.balance_of("Mine", "gold"), 75);
+/// assert!(ledger.mint("gold", "Mine", 1).is_err()); // max supply reached
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AssetLedger {
+    assets: HashMap<String, Asset>,
+    balances: HashMap<String, HashMap<String, u64>>,
+}
+
+impl AssetLedger {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        AssetLedger {
+            assets: HashMap::new(),
+            balances: HashMap::new(),
+        }
+    }
+
+    /// Returns the asset with the given id.
+    pub fn get(&self, id: &str) -> Option<&Asset> {
+        self.assets.get(id)
+    }
+
+    /// Returns the asset with the given ticker.
+    pub fn by_ticker(&self, ticker: &str) -> Option<&Asset> {
+        self.assets.values().find(|asset| asset.ticker == ticker)
+    }
+
+    /// Returns every issued asset, sorted by ticker.
+    pub fn all(&self) -> Vec<&Asset> {
+        let mut assets: Vec<&Asset> = self.assets.values().collect();
+        assets.sort_by(|a, b| a.ticker.cmp(&b.ticker));
+        assets
+    }
+
+    /// Returns how many units of `asset_id` `address` holds.
+    pub fn balance_of(&self, address: &str, asset_id: &str) -> u64 {
+        self.balances.get(asset_id).and_then(|holders| holders.get(address)).copied().unwrap_or(0)
+    }
+
+    /// Checks that a new asset can be issued (valid definition, unused id and
+    /// ticker, initial supply within the max supply).
+    pub fn check_issue(&self, id: &str, ticker: &str, decimals: u8, max_supply: u64, initial_supply: u64) -> Result<(), String> {
+        check_definition(ticker, decimals)?;
+        if self.assets.contains_key(id) {
+            return Err(format!("Asset {} already exists", id));
+        }
+        if self.by_ticker(ticker).is_some() {
+            return Err(format!("Ticker {} is already used", ticker));
+        }
+        if initial_supply > max_supply {
+            return Err(format!("Initial supply {} is above the max supply {}", initial_supply, max_supply));
+        }
+        Ok(())
+    }
+
+    /// Checks that `issuer` can mint `quantity` more units of `asset_id`.
+    pub fn check_mint(&self, asset_id: &str, issuer: &str, quantity: u64) -> Result<(), String> {
+        let asset = self.asset(asset_id)?;
+        if asset.issuer != issuer {
+            return Err(format!("Only {} can mint {}", asset.issuer, asset.ticker));
+        }
+        if quantity > asset.remaining_supply() {
+            return Err(format!("Minting {} {} would exceed the max supply {}", quantity, asset.ticker, asset.max_supply));
+        }
+        Ok(())
+    }
+
+    /// Checks that `sender` holds at least `quantity` units of `asset_id`.
+    pub fn check_transfer(&self, asset_id: &str, sender: &str, quantity: u64) -> Result<(), String> {
+        let asset = self.asset(asset_id)?;
+        let balance = self.balance_of(sender, asset_id);
+        if balance < quantity {
+            return Err(format!("{} holds {} {}, cannot send {}", sender, balance, asset.ticker, quantity));
+        }
+        Ok(())
+    }
+
+    /// Issues a new asset and credits `initial_supply` units to `issuer`.
+    pub fn issue(&mut self, id: &str, ticker: &str, decimals: u8, max_supply: u64, issuer: &str, initial_supply: u64) -> Result<(), String> {
+        self.check_issue(id, ticker, decimals, max_supply, initial_supply)?;
+        self.assets.insert(id.to_string(), Asset {
+            id: id.to_string(),
+            ticker: ticker.to_string(),
+            decimals: decimals,
+            max_supply: max_supply,
+            supply: initial_supply,
+            issuer: issuer.to_string(),
+        });
+        self.credit(id, issuer, initial_supply);
+        Ok(())
+    }
+
+    /// Mints `quantity` more units of `asset_id` to its issuer.
+    pub fn mint(&mut self, asset_id: &str, issuer: &str, quantity: u64) -> Result<(), String> {
+        self.check_mint(asset_id, issuer, quantity)?;
+        if let Some(asset) = self.assets.get_mut(asset_id) {
+            asset.supply += quantity;
+        }
+        self.credit(asset_id, issuer, quantity);
+        Ok(())
+    }
+
+    /// Moves `quantity` units of `asset_id` from `sender` to `receiver`.
+    pub fn transfer(&mut self, asset_id: &str, sender: &str, receiver: &str, quantity: u64) -> Result<(), String> {
+        self.check_transfer(asset_id, sender, quantity)?;
+        if let Some(holders) = self.balances.get_mut(asset_id)
+            && let Some(balance) = holders.get_mut(sender) {
+            *balance -= quantity;
+        }
+        self.credit(asset_id, receiver, quantity);
+        Ok(())
+    }
+
+    /// Destroys `quantity` units of `asset_id` held by `holder`, lowering the supply.
+    pub fn burn(&mut self, asset_id: &str, holder: &str, quantity: u64) -> Result<(), String> {
+        self.check_transfer(asset_id, holder, quantity)?;
+        if let Some(holders) = self.balances.get_mut(asset_id)
+            && let Some(balance) = holders.get_mut(holder) {
+            *balance -= quantity;
+        }
+        if let Some(asset) = self.assets.get_mut(asset_id) {
+            asset.supply -= quantity;
+        }
+        Ok(())
+    }
+
+    fn credit(&mut self, asset_id: &str, address: &str, quantity: u64) {
+        *self.balances.entry(asset_id.to_string()).or_default().entry(address.to_string()).or_insert(0) += quantity;
+    }
+
+    fn asset(&self, id: &str) -> Result<&Asset, String> {
+        self.assets.get(id).ok_or(format!("No asset {}", id))
+    }
+}
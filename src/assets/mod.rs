@@ -0,0 +1,2 @@
+pub mod asset;
+pub mod nft;
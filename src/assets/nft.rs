@@ -0,0 +1,130 @@
+use std::collections::{HashMap, HashSet};
+
+/// Maximum length of a token id.
+pub const MAX_TOKEN_ID_LENGTH: usize = 64;
+
+/// A non-fungible token: one unique item with a single owner.
+///
+/// # Fields
+///
+/// * `id` - Unique token id, chosen by the creator
+/// * `creator` - Address that minted it
+/// * `owner` - Current owner
+/// * `metadata_hash` - Optional hash of the item's metadata (image, description, ...) kept off chain
+#[derive(Debug, Clone, PartialEq)]
+pub struct Nft {
+    pub id: String,
+    pub creator: String,
+    pub owner: String,
+    pub metadata_hash: Option<String>,
+}
+
+/// Registry of non-fungible tokens and their owners.
+///
+/// Ids of burned tokens are remembered and can never be minted again, so an id
+/// always refers to the same item.
+///
+/// # Fields
+///
+/// * `tokens` - Existing tokens, by id
+/// * `burned` - Ids of burned tokens
+///
+/// # Example
+///
+/// ```
+/// use blockc::assets::nft::NftRegistry;
+///
+/// let mut registry = NftRegistry::new();
+/// registry.mint("punk-1", "Artist", None).unwrap();
+/// registry.transfer("punk-1", "Artist", "Alice").unwrap();
+/// assert_eq!(registry.owner_of("punk-1"), Some("Alice"));
+///
+/// registry.burn("punk-1", "Alice").unwrap();
+/// assert!(registry.mint("punk-1", "Artist", None).is_err());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct NftRegistry {
+    tokens: HashMap<String, Nft>,
+    burned: HashSet<String>,
+}
+
+impl NftRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        NftRegistry {
+            tokens: HashMap::new(),
+            burned: HashSet::new(),
+        }
+    }
+
+    /// Returns the token with the given id.
+    pub fn get(&self, token_id: &str) -> Option<&Nft> {
+        self.tokens.get(token_id)
+    }
+
+    /// Returns the owner of a token (`None` if it doesn't exist or was burned).
+    pub fn owner_of(&self, token_id: &str) -> Option<&str> {
+        self.tokens.get(token_id).map(|token| token.owner.as_str())
+    }
+
+    /// Returns the tokens owned by `address`, sorted by id.
+    pub fn tokens_of(&self, address: &str) -> Vec<&Nft> {
+        let mut owned: Vec<&Nft> = self.tokens.values().filter(|token| token.owner == address).collect();
+        owned.sort_by(|a, b| a.id.cmp(&b.id));
+        owned
+    }
+
+    /// Returns `true` if the token was burned.
+    pub fn is_burned(&self, token_id: &str) -> bool {
+        self.burned.contains(token_id)
+    }
+
+    /// Checks that `token_id` is valid and was never used.
+    pub fn check_mint(&self, token_id: &str) -> Result<(), String> {
+        if token_id.is_empty() || token_id.len() > MAX_TOKEN_ID_LENGTH {
+            return Err(format!("Token id must be 1 to {} bytes", MAX_TOKEN_ID_LENGTH));
+        }
+        if self.tokens.contains_key(token_id) || self.burned.contains(token_id) {
+            return Err(format!("Token {} already exists", token_id));
+        }
+        Ok(())
+    }
+
+    /// Checks that `owner` owns `token_id`.
+    pub fn check_owner(&self, token_id: &str, owner: &str) -> Result<(), String> {
+        match self.owner_of(token_id) {
+            Some(current) if current == owner => Ok(()),
+            Some(current) => Err(format!("Token {} is owned by {}, not {}", token_id, current, owner)),
+            None => Err(format!("No token {}", token_id)),
+        }
+    }
+
+    /// Mints a new token owned by its creator.
+    pub fn mint(&mut self, token_id: &str, creator: &str, metadata_hash: Option<String>) -> Result<(), String> {
+        self.check_mint(token_id)?;
+        self.tokens.insert(token_id.to_string(), Nft {
+            id: token_id.to_string(),
+            creator: creator.to_string(),
+            owner: creator.to_string(),
+            metadata_hash: metadata_hash,
+        });
+        Ok(())
+    }
+
+    /// Gives a token owned by `owner` to `receiver`.
+    pub fn transfer(&mut self, token_id: &str, owner: &str, receiver: &str) -> Result<(), String> {
+        self.check_owner(token_id, owner)?;
+        if let Some(token) = self.tokens.get_mut(token_id) {
+            token.owner = receiver.to_string();
+        }
+        Ok(())
+    }
+
+    /// Destroys a token owned by `owner`; its id can never be minted again.
+    pub fn burn(&mut self, token_id: &str, owner: &str) -> Result<(), String> {
+        self.check_owner(token_id, owner)?;
+        self.tokens.remove(token_id);
+        self.burned.insert(token_id.to_string());
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::BTreeSet;
+use crate::blockchain::blockchain::Blockchain;
+use crate::entity::entity::Entity;
+use crate::merkle::patricia::{verify_proof, TrieProof};
+use crate::merkle::summation::{build_sum_proof, sum_root, SumNode, SumProof};
+
+/// Message an address signs to show it is controlled by the reporting party.
+///
+/// It names the auditor's challenge and the block the report is for, so an old
+/// signature can't be replayed in another audit.
+pub fn ownership_message(challenge: &str, height: u64, block_hash: &str, address: &str) -> String {
+    format!("reserves:{}:{}:{}:{}", challenge, height, block_hash, address)
+}
+
+/// One address counted in a reserves report.
+///
+/// # Fields
+///
+/// * `address` - Address holding the funds
+/// * `balance` - Its balance at the report's height
+/// * `account_proof` - Proof of its account against the block's state root
+/// * `signature` - Its signature over `ownership_message`
+#[derive(Debug, Clone)]
+pub struct ReserveEntry {
+    pub address: String,
+    pub balance: f64,
+    pub account_proof: TrieProof,
+    pub signature: String,
+}
+
+impl ReserveEntry {
+    /// Returns the entry's leaf in the summation tree.
+    pub fn leaf(&self) -> SumNode {
+        SumNode::leaf(&self.address, self.balance)
+    }
+}
+
+/// Proof that a set of addresses held at least some amount at a given height.
+///
+/// For every address, the report carries a signature over the auditor's
+/// challenge (ownership) and a proof of its account against the state root of
+/// the block (balance). The balances are the leaves of a Merkle summation tree
+/// whose root commits to the total, so each address can also be shown to be
+/// counted with `leaf_proof` without revealing the others.
+///
+/// # Fields
+///
+/// * `height` - Height of the block the balances are taken at
+/// * `block_hash` - Hash of that block
+/// * `state_root` - State root of that block
+/// * `challenge` - Challenge chosen by the auditor
+/// * `root` - Root of the summation tree over the entries (its sum is the total)
+/// * `entries` - Addresses counted, sorted by address
+///
+/// # Example
+///
+/// ```
+/// use blockc::audit::reserves::ReservesReport;
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::entity::entity::Entity;
+///
+/// let mut chain = Blockchain::new();
+/// chain.add_block(Vec::new(), "Cold").unwrap();
+/// chain.add_block(Vec::new(), "Hot").unwrap();
+/// let cold = Entity::new("Cold".to_string(), 0.0, Vec::new(), "c".to_string(), "c".to_string());
+/// let hot = Entity::new("Hot".to_string(), 0.0, Vec::new(), "h".to_string(), "h".to_string());
+///
+/// // The exchange answers the auditor's challenge for both of its addresses
+/// let report = ReservesReport::generate(&chain, &[cold, hot], "audit-2026-q3").unwrap();
+/// assert_eq!(report.total(), 90.0);
+///
+/// // Anyone can check it against the chain, even after more blocks
+/// chain.add_block(Vec::new(), "Miner").unwrap();
+/// assert_eq!(report.verify(&chain, 80.0).unwrap(), 90.0);
+/// assert!(report.verify(&chain, 100.0).is_err());
+///
+/// // An address can be shown to be counted on its own
+/// assert!(report.leaf_proof("Hot").unwrap().verify(&report.root));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReservesReport {
+    pub height: u64,
+    pub block_hash: String,
+    pub state_root: String,
+    pub challenge: String,
+    pub root: SumNode,
+    pub entries: Vec<ReserveEntry>,
+}
+
+impl ReservesReport {
+    /// Builds a report for the addresses of `owners` at the tip of `chain`.
+    ///
+    /// # Arguments
+    ///
+    /// * `chain` - The chain, whose latest block the balances are taken at
+    /// * `owners` - Entities controlling the addresses (they sign the challenge)
+    /// * `challenge` - Challenge chosen by the auditor
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ReservesReport)` - The report
+    /// * `Err(String)` - If there are no owners or an address appears twice
+    pub fn generate(chain: &Blockchain, owners: &[Entity], challenge: &str) -> Result<Self, String> {
+        if owners.is_empty() {
+            return Err("A reserves report needs at least one address".to_string());
+        }
+        let block = chain.get_latest_block();
+        let height = chain.get_blocks().len() as u64 - 1;
+        let mut owners: Vec<&Entity> = owners.iter().collect();
+        owners.sort_by(|a, b| a.address.cmp(&b.address));
+
+        let mut entries: Vec<ReserveEntry> = Vec::new();
+        for owner in owners {
+            if entries.iter().any(|entry| entry.address == owner.address) {
+                return Err(format!("Address {} appears twice", owner.address));
+            }
+            entries.push(ReserveEntry {
+                address: owner.address.clone(),
+                balance: chain.get_balance(&owner.address),
+                account_proof: chain.state().prove(&owner.address),
+                signature: owner.sign(&ownership_message(challenge, height, &block.block_hash, &owner.address)),
+            });
+        }
+        let leaves: Vec<SumNode> = entries.iter().map(|entry| entry.leaf()).collect();
+
+        Ok(ReservesReport {
+            height: height,
+            block_hash: block.block_hash.clone(),
+            state_root: block.state_root.clone(),
+            challenge: challenge.to_string(),
+            root: sum_root(&leaves),
+            entries: entries,
+        })
+    }
+
+    /// Total the report claims (the sum of the summation tree's root).
+    pub fn total(&self) -> f64 {
+        self.root.sum
+    }
+
+    /// Returns the proof that `address` is counted in the report's total.
+    pub fn leaf_proof(&self, address: &str) -> Option<SumProof> {
+        let leaves: Vec<SumNode> = self.entries.iter().map(|entry| entry.leaf()).collect();
+        let index = self.entries.iter().position(|entry| entry.address == address)?;
+        build_sum_proof(&leaves, index)
+    }
+
+    /// Checks the report against `chain`:
+    /// 1. The block at `height` has `block_hash` and `state_root`
+    /// 2. Every address appears once and signed the challenge for that block
+    /// 3. Every balance matches its account proof against the state root
+    /// 4. The summation tree over the entries has `root`
+    /// 5. The total is at least `minimum`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(f64)` - The proven total
+    /// * `Err(String)` - The first check that failed
+    pub fn verify(&self, chain: &Blockchain, minimum: f64) -> Result<f64, String> {
+        let block = chain.get_blocks().get(self.height as usize)
+            .ok_or(format!("The chain has no block at height {}", self.height))?;
+        if block.block_hash != self.block_hash || block.state_root != self.state_root {
+            return Err(format!("Block {} does not match the report", self.height));
+        }
+
+        let mut seen = BTreeSet::new();
+        for entry in &self.entries {
+            if !seen.insert(&entry.address) {
+                return Err(format!("Address {} appears twice", entry.address));
+            }
+            if entry.signature.is_empty() {
+                return Err(format!("{} did not sign the challenge", entry.address));
+            }
+            let proof = &entry.account_proof;
+            if proof.key != entry.address || !verify_proof(&self.state_root, proof) {
+                return Err(format!("Account proof of {} is invalid", entry.address));
+            }
+            let proven = proof.value.as_deref()
+                .and_then(|value| value.split(':').next())
+                .and_then(|balance| balance.parse::<f64>().ok())
+                .unwrap_or(0.0);
+            if proven != entry.balance {
+                return Err(format!("{} held {} at height {}, not {}", entry.address, proven, self.height, entry.balance));
+            }
+        }
+
+        let leaves: Vec<SumNode> = self.entries.iter().map(|entry| entry.leaf()).collect();
+        if sum_root(&leaves) != self.root {
+            return Err("Summation tree root does not match the entries".to_string());
+        }
+        if self.total() < minimum {
+            return Err(format!("Reserves are {}, less than {}", self.total(), minimum));
+        }
+        Ok(self.total())
+    }
+}
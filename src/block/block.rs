@@ -1,123 +1,179 @@
 use crate::helpers::{self};
-use sha2::{Sha256, Digest}; // hashing
 use crate::transactions::transactions::Transaction;
+use crate::codec::json::{self, JsonCodec};
+use crate::codec::wire;
 
-/// Represents a block in the blockchain.
-/// 
-/// Each block contains:
-/// - A hash of its own data
-/// - A hash of the previous block (linking blocks together)
-/// - A list of transactions
-/// - A timestamp
-/// - A nonce (used for proof-of-work mining)
-/// 
-/// # Fields
+// Blocks, their headers and the checks that need no chain state live in
+// `blockc_core`, so light clients can use them without `std`.
+pub use blockc_core::block::{check_block, Block};
+pub use blockc_core::header::{BlockHeader, HeaderHasher};
+pub use blockc_core::hash::HashAlgorithm;
+
+/// Largest encoded block `Block::from_bytes` accepts.
+pub const MAX_ENCODED_BLOCK_BYTES: usize = 8 * 1024 * 1024;
+
+/// What the node does with blocks on top of `blockc_core`: stamping them with
+/// the clock, mining them and encoding them.
 /// 
-/// * `block_hash` - SHA-256 hash of this block's data
-/// * `previous_block_hash` - Hash of the previous block in the chain
-/// * `transaction` - Vector of transactions included in this block
-/// * `time_stamp` - Unix timestamp when block was created (u32, valid until 2106)
-/// * `nonce` - Proof-of-work value (will be used for mining)
+/// # Example
 /// 
-/// # Hash Calculation
+/// ```
+/// use blockc::block::block::{Block, BlockExt};
 /// 
-/// The block hash is calculated from:
-/// - Previous block hash
-/// - Timestamp
-/// - Nonce
-/// - All transaction data (sender, receiver, amount, timestamp)
-pub struct Block{
-
-    pub block_hash: String,
-    pub previous_block_hash: String,
-    pub transaction: Vec<Transaction>,
-    pub time_stamp: u32, // small because of project size, good until 2106
-    pub nonce: u32,
+/// let mut block = Block::new(Vec::new(), "0".to_string(), 0.0, String::new());
+/// block.mine(1);
+/// assert!(block.meets_difficulty(1));
+/// assert_eq!(Block::from_bytes(&block.to_bytes()).unwrap(), block);
+/// ```
+pub trait BlockExt: Sized {
+    /// Creates an unmined block stamped with the current time (see `Block::new_at`).
+    fn new(transaction: Vec<Transaction>, previous_block_hash: String, base_fee: f64, state_root: String) -> Self;
 
-}
+    /// Encodes the block (header fields and transactions) as UTF-8 JSON.
+    fn to_bytes(&self) -> Vec<u8>;
 
+    /// Decodes a block received from the network or read from storage.
+    /// 
+    /// Malformed input gives an error, never a panic. The block is only decoded:
+    /// `Blockchain::submit_block` checks whether it is valid.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Block)` - The decoded block
+    /// * `Err(String)` - If the input is over `MAX_ENCODED_BLOCK_BYTES`, is not
+    ///   UTF-8 JSON or does not describe a block
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::block::block::{Block, BlockExt};
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// 
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.add_block(Vec::new(), "Miner").unwrap();
+    /// let block = chain.get_latest_block();
+    /// 
+    /// let bytes = block.to_bytes();
+    /// assert_eq!(&Block::from_bytes(&bytes).unwrap(), block);
+    /// 
+    /// assert!(Block::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    /// assert!(Block::from_bytes(&[0xff, 0xfe]).is_err());
+    /// ```
+    fn from_bytes(bytes: &[u8]) -> Result<Self, String>;
 
-impl Block{
-    pub fn new(transaction: Vec<Transaction>, previous_block_hash: String) -> Self
-    {
-        let time_stamp = helpers::helper_functions::get_time();
-        let nonce = 0;
-
-        // create hash using helper fn
-        let block_hash = Block::hash(
-        &previous_block_hash,
-        time_stamp,
-        nonce,
-        &transaction
-        );
-
-        // create block
-        Block {
-            block_hash: block_hash,
-            previous_block_hash: previous_block_hash,
-            transaction: transaction,
-            time_stamp: time_stamp,
-            nonce: nonce,
-        }
-    }
+    /// Encodes the block in the compact wire format (see `WireCodec`), about
+    /// a tenth of the size of `to_bytes`.
+    fn to_wire(&self) -> Vec<u8>;
 
+    /// Decodes a block encoded with `to_wire`.
+    /// 
+    /// Like `from_bytes`, malformed input gives an error, never a panic, and
+    /// the block is only decoded, not validated.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Block)` - The decoded block
+    /// * `Err(String)` - If the input is over `MAX_ENCODED_BLOCK_BYTES`, is
+    ///   truncated or malformed, or has bytes after the block
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::block::block::{Block, BlockExt};
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// 
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// let mut alice = Entity::new("Alice".to_string(), 50.0, Vec::new(), "pub".to_string(), "priv".to_string());
+    /// let payments = (0..20)
+    ///     .map(|_| Transaction::create_payment(&mut alice, "Bob", 1.5, 0.1).unwrap())
+    ///     .collect();
+    /// chain.add_block(payments, "Miner").unwrap();
+    /// let block = chain.get_latest_block();
+    /// 
+    /// let bytes = block.to_wire();
+    /// assert_eq!(&Block::from_wire(&bytes).unwrap(), block);
+    /// assert!(bytes.len() * 4 < block.to_bytes().len());
+    /// 
+    /// assert!(Block::from_wire(&bytes[..bytes.len() - 1]).is_err());
+    /// assert!(Block::from_wire(&[bytes.as_slice(), &[0]].concat()).is_err());
+    /// ```
+    fn from_wire(bytes: &[u8]) -> Result<Self, String>;
 
-    /// Calculates the SHA-256 hash of a block's data.
+    /// Mines this block (proof of work).
     /// 
-    /// This is a static function that can be called without a Block instance,
-    /// which is useful when creating a new block (before `self` exists).
+    /// Increments the nonce until the block hash starts with `difficulty` zeros,
+    /// then stores the winning hash in `block_hash`. The header is serialized
+    /// once per timestamp; each attempt only hashes the new nonce.
     /// 
     /// # Arguments
     /// 
-    /// * `previous_hash` - Hash of the previous block
-    /// * `time_stamp` - Block creation timestamp
-    /// * `nonce` - Proof-of-work nonce value
-    /// * `transaction` - Vector of transactions in the block
+    /// * `difficulty` - Number of leading zeros required
     /// 
-    /// # Returns
+    /// # Example
     /// 
-    /// A hexadecimal string representing the SHA-256 hash.
-    pub fn hash(previous_hash: &str, time_stamp: u32, nonce: u32, transaction: &Vec<Transaction>) -> String
+    /// ```
+    /// use blockc::block::block::{Block, BlockExt};
+    /// 
+    /// let mut block = Block::new(Vec::new(), "0".to_string(), 0.0, String::new());
+    /// block.mine(2);
+    /// assert!(block.block_hash.starts_with("00"));
+    /// assert_eq!(block.block_hash, block.calculate_hash());
+    /// ```
+    fn mine(&mut self, difficulty: u32);
+
+    /// Mines this block for a chain hashing its headers with `algorithm`
+    /// (see `mine`).
+    fn mine_with(&mut self, difficulty: u32, algorithm: HashAlgorithm);
+}
+
+impl BlockExt for Block {
+    fn new(transaction: Vec<Transaction>, previous_block_hash: String, base_fee: f64, state_root: String) -> Self
     {
-        let mut hasher = Sha256::new();
-        
-        // Convert transaction vector to a string representation
-        let transaction_str: String = transaction.iter()
-            .map(|t| format!("{}{}{}{}", 
-                t.sender_address, 
-                t.receiver_address, 
-                t.amount, 
-                t.timestamp
-            ))
-            .collect::<Vec<String>>()
-            .join(",");
-        
-        let data_to_hash = format!("{}{}{}{}",
-                                previous_hash,
-                                time_stamp,
-                                nonce,
-                                transaction_str);
-        hasher.update(data_to_hash.as_bytes());
-        let result = hasher.finalize();
-        result.iter()
-            .map(|byte| format!("{:02x}", byte))
-            .collect::<String>()
+        let time_stamp = helpers::helper_functions::get_time();
+        Block::new_at(transaction, previous_block_hash, base_fee, state_root, time_stamp)
     }
 
-    /// Calculates the hash of this block instance.
-    /// 
-    /// Convenience method that calls the static `hash()` function with
-    /// this block's data.
-    /// 
-    /// # Returns
-    /// 
-    /// A hexadecimal string representing the SHA-256 hash of this block.
-    pub fn calculate_hash(&self) -> String {
-        Block::hash(
-            &self.previous_block_hash,
-            self.time_stamp,
-            self.nonce,
-            &self.transaction
-        )
+    fn to_bytes(&self) -> Vec<u8> {
+        JsonCodec::to_json(self).to_string().into_bytes()
     }
-}
\ No newline at end of file
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() > MAX_ENCODED_BLOCK_BYTES {
+            return Err(format!("Encoded block is over {} bytes", MAX_ENCODED_BLOCK_BYTES));
+        }
+        let text = std::str::from_utf8(bytes).map_err(|_| "Encoded block is not UTF-8".to_string())?;
+        <Block as JsonCodec>::from_json(&json::parse(text)?)
+    }
+
+    fn to_wire(&self) -> Vec<u8> {
+        wire::encode(self)
+    }
+
+    fn from_wire(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() > MAX_ENCODED_BLOCK_BYTES {
+            return Err(format!("Encoded block is over {} bytes", MAX_ENCODED_BLOCK_BYTES));
+        }
+        wire::decode(bytes)
+    }
+
+    fn mine(&mut self, difficulty: u32) {
+        self.mine_with(difficulty, HashAlgorithm::Sha256)
+    }
+
+    fn mine_with(&mut self, difficulty: u32, algorithm: HashAlgorithm) {
+        loop {
+            if let Some((nonce, hash)) = self.header_hasher_with(algorithm).find_nonce(self.nonce, difficulty) {
+                self.nonce = nonce;
+                self.block_hash = hash;
+                return;
+            }
+            // All nonces tried: refresh the timestamp to get new hashes (move it
+            // forward even if the clock is stopped)
+            self.nonce = 0;
+            self.time_stamp = helpers::helper_functions::get_time().max(self.time_stamp.wrapping_add(1));
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use sha2::{Digest, Sha256};
+use crate::block::block::Block;
+use crate::transactions::transactions::TransactionKind;
+
+/// Bits of an address filter by default (256 bytes per block).
+pub const DEFAULT_FILTER_BITS: usize = 2048;
+
+/// Hash functions of an address filter by default.
+pub const DEFAULT_FILTER_HASHES: u32 = 4;
+
+/// Size of the address filters a chain keeps for its blocks.
+///
+/// More bits and a number of hashes suited to the addresses per block mean
+/// fewer false positives (blocks scanned for nothing), at the cost of memory.
+///
+/// # Fields
+///
+/// * `bits` - Bits per filter (rounded up to a multiple of 64)
+/// * `hashes` - Bits set per address
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FilterParams {
+    pub bits: usize,
+    pub hashes: u32,
+}
+
+impl FilterParams {
+    /// Checks the parameters can make a filter.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.bits == 0 || self.hashes == 0 {
+            return Err("Address filters need at least one bit and one hash".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl Default for FilterParams {
+    fn default() -> Self {
+        FilterParams {
+            bits: DEFAULT_FILTER_BITS,
+            hashes: DEFAULT_FILTER_HASHES,
+        }
+    }
+}
+
+/// A Bloom filter of the addresses a block touches.
+///
+/// `contains` never misses an address that was inserted, but may answer `true`
+/// for one that wasn't: a `false` means the block can be skipped for sure.
+///
+/// # Example
+///
+/// ```
+/// use blockc::block::bloom::{AddressFilter, FilterParams};
+/// use blockc::blockchain::blockchain::Blockchain;
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// chain.add_block(Vec::new(), "Miner").unwrap();
+///
+/// let filter = AddressFilter::for_block(&chain.get_blocks()[1], FilterParams::default());
+/// assert!(filter.contains("Miner"));
+/// assert!(!filter.contains("Nobody"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddressFilter {
+    bits: Vec<u64>,
+    hashes: u32,
+}
+
+impl AddressFilter {
+    /// Creates an empty filter.
+    pub fn new(params: FilterParams) -> Self {
+        AddressFilter {
+            bits: vec![0; params.bits.div_ceil(64).max(1)],
+            hashes: params.hashes.max(1),
+        }
+    }
+
+    /// Builds the filter of every address `block` touches: senders, receivers
+    /// and the outputs of multi-output payments.
+    pub fn for_block(block: &Block, params: FilterParams) -> Self {
+        let mut filter = AddressFilter::new(params);
+        for transaction in &block.transaction {
+            filter.insert(&transaction.sender_address);
+            filter.insert(&transaction.receiver_address);
+            if let TransactionKind::MultiPay { outputs } | TransactionKind::Utxo { outputs, .. } | TransactionKind::CoinJoin { outputs, .. } = &transaction.kind {
+                for output in outputs {
+                    filter.insert(&output.address);
+                }
+            }
+        }
+        filter
+    }
+
+    /// Adds `address`.
+    pub fn insert(&mut self, address: &str) {
+        for bit in self.positions(address) {
+            self.bits[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+
+    /// Returns `false` if `address` was certainly never inserted.
+    pub fn contains(&self, address: &str) -> bool {
+        self.positions(address).all(|bit| self.bits[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    // Double hashing: two 64-bit halves of the address's SHA-256 make every position
+    fn positions(&self, address: &str) -> impl Iterator<Item = usize> + use<> {
+        let digest = Sha256::digest(address.as_bytes());
+        let first = u64::from_le_bytes(digest[..8].try_into().unwrap());
+        let second = u64::from_le_bytes(digest[8..16].try_into().unwrap()) | 1;
+        let bits = self.bits.len() as u64 * 64;
+        (0..self.hashes as u64).map(move |index| (first.wrapping_add(index.wrapping_mul(second)) % bits) as usize)
+    }
+}
@@ -0,0 +1,3 @@
+// Canonical ordering is part of stateless block validation, so it lives in
+// `blockc_core`.
+pub use blockc_core::ordering::{canonical_order, depends_on, funds, is_canonical, must_follow};
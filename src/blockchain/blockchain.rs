@@ -1,8 +1,111 @@
-use crate::block::block::Block;
-use crate::transactions::transactions::Transaction;
+use crate::block::block::{check_block, Block, BlockHeader, HashAlgorithm};
+use crate::transactions::transactions::{Transaction, TransactionExt};
+use crate::state::state::ChainState;
+use crate::state::parallel;
+use crate::state::cache::StateCache;
+use crate::state::rules::LocalRules;
+use crate::treasury::treasury::{Treasury, TREASURY_ADDRESS};
+use crate::block::bloom::{AddressFilter, FilterParams};
+use crate::block::ordering;
+use crate::blockchain::blocks::{BlockList, SharedVec};
+use crate::blockchain::memory::{self, MemoryLimits, MemoryUsage};
+use crate::mempool::mempool::Mempool;
+use crate::mining::template::BlockTemplate;
+use crate::merkle::merkle::ExistenceProof;
+use crate::export::{dot, mermaid};
+#[cfg(feature = "fs")]
+use crate::export::csv::{self, LedgerFilter};
+use crate::transactions::transactions::TransactionKind;
+use crate::fees::base_fee::{self, INITIAL_BASE_FEE, MAX_BLOCK_SIZE, TARGET_BLOCK_SIZE};
+use crate::fees::gas::BLOCK_GAS_LIMIT;
+#[cfg(feature = "tamper")]
+use crate::blockchain::tamper::Tamper;
+use crate::helpers::clock::{Clock, SystemClock};
+use std::fmt;
+use std::thread;
+#[cfg(feature = "fs")]
+use std::path::Path;
+use std::sync::Arc;
+
+/// Coins created by every new block, before fees.
+pub const DEFAULT_BLOCK_REWARD: f64 = 50.0;
+
+/// Percentage of each block reward and fees that goes to the treasury by default.
+pub const DEFAULT_TREASURY_SHARE: f64 = 10.0;
+
+/// How far (in seconds) a block's timestamp may be ahead of the chain's clock.
+pub const MAX_FUTURE_BLOCK_TIME: u32 = 2 * 60 * 60;
+
+/// A block that passed every check of `Blockchain::submit_block`, with the state
+/// it leads to (see `Blockchain::verify_block`).
+#[derive(Debug, Clone)]
+pub struct VerifiedBlock {
+    block: Block,
+    state: ChainState,
+    treasury: Treasury,
+    burned: f64,
+    local_changes: u64,
+}
+
+impl VerifiedBlock {
+    /// Returns the block.
+    pub fn block(&self) -> &Block {
+        &self.block
+    }
+}
+
+/// The first block of a chain that can't be connected, found while replaying it.
+///
+/// # Fields
+///
+/// * `height` - Height of the block; every block below it is valid
+/// * `reason` - Why it was rejected
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainDamage {
+    pub height: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for ChainDamage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Block {}: {}", self.height, self.reason)
+    }
+}
+
+/// What `Blockchain::reindex` rebuilt.
+///
+/// # Fields
+///
+/// * `blocks` - Blocks replayed, the genesis included
+/// * `transactions` - Transaction ids in the transaction index
+/// * `accounts` - Accounts in the state cache
+/// * `stale` - Indexes that differed from the rebuilt ones ("transaction index",
+///   "address filters", "state cache")
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ReindexReport {
+    pub blocks: usize,
+    pub transactions: usize,
+    pub accounts: usize,
+    pub stale: Vec<&'static str>,
+}
+
+#[derive(Clone)]
 pub struct Blockchain {
-    chain: Vec<Block>,        // The chain of blocks
+    chain: BlockList,         // The chain of blocks (shared between copies)
+    filters: SharedVec<AddressFilter>, // Addresses touched by each block
+    filter_params: FilterParams, // Size of the address filters
     difficulty: u32,          // Proof-of-work difficulty (number of leading zeros)
+    hash_algorithm: HashAlgorithm, // Hash function of the block headers
+    block_reward: f64,        // Coins created per block
+    treasury: Treasury,       // Governance-owned treasury, funded by every block
+    state: ChainState,        // On-chain balances after the last block
+    state_cache: StateCache,  // Balances and nonces, with undo records per block
+    base_fee: f64,            // Base fee the next block must charge (burned)
+    total_burned: f64,        // Sum of all base fees burned so far
+    mempool: Mempool,         // Transactions waiting for a block
+    clock: Arc<dyn Clock>,    // Source of block timestamps
+    local_changes: u64,       // Changes of the local rules or the treasury by the operator
+    execution_threads: usize, // Threads block transactions are applied on
 }
 
 
@@ -17,16 +120,946 @@ impl Blockchain {
     /// 
     /// A new `Blockchain` instance with a genesis block and default difficulty of 3.
     pub fn new() -> Self {
+        Blockchain::with_settings(3, DEFAULT_BLOCK_REWARD)
+    }
+
+    /// Creates a new blockchain with its own difficulty and block reward.
+    /// 
+    /// Useful to run several independent chains side by side (e.g. two coins in an
+    /// atomic swap).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `difficulty` - Number of leading zeros a block hash needs
+    /// * `block_reward` - Coins created by every new block
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// 
+    /// let chain = Blockchain::with_settings(2, 100.0);
+    /// assert_eq!(chain.get_difficulty(), 2);
+    /// assert_eq!(chain.get_block_reward(), 100.0);
+    /// ```
+    pub fn with_settings(difficulty: u32, block_reward: f64) -> Self {
+        Blockchain::with_clock(difficulty, block_reward, Arc::new(SystemClock))
+    }
+
+    /// Creates a new blockchain whose timestamps come from `clock`.
+    /// 
+    /// The genesis block and every block template are stamped with the clock's
+    /// time, and blocks too far ahead of it are rejected (see `submit_block`), so
+    /// a `ManualClock` makes time-dependent rules testable.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use std::sync::Arc;
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::helpers::clock::ManualClock;
+    /// use blockc::transactions::transactions::{LockTime, Transaction, TransactionExt};
+    /// 
+    /// let clock = ManualClock::new(1_000);
+    /// let mut chain = Blockchain::with_clock(1, 50.0, Arc::new(clock.clone()));
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// assert_eq!(chain.get_latest_block().time_stamp, 1_000);
+    /// 
+    /// // Locked until time 2000: rejected while the last block is older
+    /// let fee = chain.get_base_fee() * 2.0;
+    /// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+    /// let locked = Transaction::create_timelocked_payment(&mut alice, "Bob", 5.0, fee, LockTime::Timestamp(2_000)).unwrap();
+    /// assert!(chain.add_block(vec![locked.clone()], "Alice").is_err());
+    /// 
+    /// clock.advance(1_000);
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// chain.add_block(vec![locked], "Alice").unwrap();
+    /// assert_eq!(chain.get_balance("Bob"), 5.0);
+    /// ```
+    pub fn with_clock(difficulty: u32, block_reward: f64, clock: Arc<dyn Clock>) -> Self {
+        Blockchain::with_params(difficulty, block_reward, HashAlgorithm::Sha256, clock)
+    }
+
+    /// Creates a new blockchain whose block headers are hashed with
+    /// `hash_algorithm` instead of SHA-256, to mimic another chain's hashing
+    /// (e.g. double SHA-256 like Bitcoin or Keccak-256 like Ethereum).
+    /// 
+    /// The algorithm is fixed for the life of the chain: the genesis block and
+    /// every block mined or accepted afterwards are hashed with it.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::block::block::{HashAlgorithm, BlockExt};
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// 
+    /// let mut chain = Blockchain::with_hash_algorithm(1, 50.0, HashAlgorithm::DoubleSha256);
+    /// chain.add_block(Vec::new(), "Miner").unwrap();
+    /// assert!(chain.is_valid());
+    /// 
+    /// let block = chain.get_latest_block();
+    /// assert_eq!(block.block_hash, block.calculate_hash_with(HashAlgorithm::DoubleSha256));
+    /// assert_ne!(block.block_hash, block.calculate_hash());
+    /// 
+    /// // A block mined with SHA-256 is not valid on this chain
+    /// let mut template = chain.get_block_template("Miner").unwrap();
+    /// template.hash_algorithm = HashAlgorithm::Sha256;
+    /// assert!(chain.submit_block(template.mine()).is_err());
+    /// ```
+    pub fn with_hash_algorithm(difficulty: u32, block_reward: f64, hash_algorithm: HashAlgorithm) -> Self {
+        Blockchain::with_params(difficulty, block_reward, hash_algorithm, Arc::new(SystemClock))
+    }
+
+    fn with_params(difficulty: u32, block_reward: f64, hash_algorithm: HashAlgorithm, clock: Arc<dyn Clock>) -> Self {
         // Create genesis block (first block in the chain)
-        let genesis = Block::new(
+        let mut genesis = Block::new_at(
             Vec::new(),              // No transactions in genesis block
-            "0".to_string()          // Previous hash is "0" for genesis
+            "0".to_string(),         // Previous hash is "0" for genesis
+            0.0,                     // Nothing to burn in genesis
+            ChainState::new().state_root(), // Empty state
+            clock.now()
         );
-        
+        genesis.block_hash = genesis.calculate_hash_with(hash_algorithm);
+        let mut state = ChainState::new();
+        state.record_block(0, genesis.time_stamp, &genesis.transaction);
+        Blockchain::with_genesis(difficulty, block_reward, hash_algorithm, genesis, state, clock)
+    }
+
+    /// Rebuilds a chain from its blocks (genesis first), e.g. read from storage.
+    /// 
+    /// The genesis block must point to "0", burn nothing, hold only coinbase
+    /// transactions (the initial allocations, see `GenesisConfig`) and commit to
+    /// the state they create; every other block is checked and connected with
+    /// `submit_block`.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Blockchain)` - The rebuilt chain
+    /// * `Err(String)` - The first block that is not valid, and why
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// 
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.add_block(Vec::new(), "Miner").unwrap();
+    /// 
+    /// let copy = Blockchain::from_blocks(1, 50.0, chain.get_blocks().to_vec()).unwrap();
+    /// assert_eq!(copy.get_latest_hash(), chain.get_latest_hash());
+    /// assert_eq!(copy.get_balance("Miner"), 45.0);
+    /// 
+    /// // A chain with another reward does not accept the same blocks
+    /// assert!(Blockchain::from_blocks(1, 10.0, chain.get_blocks().to_vec()).is_err());
+    /// ```
+    pub fn from_blocks(difficulty: u32, block_reward: f64, blocks: Vec<Block>) -> Result<Self, String> {
+        Blockchain::from_blocks_with(difficulty, block_reward, HashAlgorithm::Sha256, blocks)
+    }
+
+    /// Rebuilds a chain whose block headers are hashed with `hash_algorithm`
+    /// (see `from_blocks`).
+    /// 
+    /// Every block hash, the genesis one included, must be the hash of its
+    /// header with that algorithm, and so must the blocks mined on the chain.
+    /// A chain picks its algorithm once, in its genesis definition (see
+    /// `GenesisConfig`).
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::block::block::HashAlgorithm;
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::blockchain::genesis::GenesisConfig;
+    /// 
+    /// let genesis = GenesisConfig::from_json(r#"{ "timestamp": 1, "difficulty": 1, "hash_algorithm": "keccak256" }"#).unwrap();
+    /// let mut chain = genesis.build().unwrap();
+    /// chain.add_block(Vec::new(), "Miner").unwrap();
+    /// assert_eq!(chain.get_latest_hash(), chain.get_latest_block().calculate_hash_with(HashAlgorithm::Keccak256));
+    /// 
+    /// let blocks = chain.get_blocks().to_vec();
+    /// assert!(Blockchain::from_blocks_with(1, 50.0, HashAlgorithm::Keccak256, blocks.clone()).is_ok());
+    /// assert!(Blockchain::from_blocks(1, 50.0, blocks).is_err());
+    /// ```
+    pub fn from_blocks_with(difficulty: u32, block_reward: f64, hash_algorithm: HashAlgorithm, blocks: Vec<Block>) -> Result<Self, String> {
+        Blockchain::from_blocks_assume_valid(difficulty, block_reward, hash_algorithm, blocks, None)
+    }
+
+    /// Rebuilds a chain like `from_blocks_with`, trusting the scripts and ring
+    /// signatures of the blocks up to the one hashed `assume_valid` (like
+    /// Bitcoin's `assumevalid`), which makes re-validating a long chain faster.
+    ///
+    /// Only those checks are skipped: hashes, hash links, proof of work, Merkle
+    /// roots, balances, nonces and the state roots are still checked, so a block
+    /// under the checkpoint can't be changed without breaking the links up to it.
+    /// If no block has the `assume_valid` hash, nothing is trusted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::block::block::HashAlgorithm;
+    /// use blockc::blockchain::blockchain::Blockchain;
+    ///
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.add_block(Vec::new(), "Miner").unwrap();
+    /// chain.add_block(Vec::new(), "Miner").unwrap();
+    /// let checkpoint = chain.get_blocks()[1].block_hash.clone();
+    ///
+    /// let blocks = chain.get_blocks().to_vec();
+    /// let loaded = Blockchain::from_blocks_assume_valid(1, 50.0, HashAlgorithm::Sha256, blocks.clone(), Some(&checkpoint)).unwrap();
+    /// assert_eq!(loaded.get_latest_hash(), chain.get_latest_hash());
+    ///
+    /// // Block hashes and links are still checked below the checkpoint
+    /// let mut forged = blocks;
+    /// forged[1].time_stamp += 1;
+    /// assert!(Blockchain::from_blocks_assume_valid(1, 50.0, HashAlgorithm::Sha256, forged, Some(&checkpoint)).is_err());
+    /// ```
+    pub fn from_blocks_assume_valid(difficulty: u32, block_reward: f64, hash_algorithm: HashAlgorithm, blocks: Vec<Block>, assume_valid: Option<&str>) -> Result<Self, String> {
+        match Blockchain::from_blocks_until_invalid(difficulty, block_reward, hash_algorithm, blocks, assume_valid, &LocalRules::default())? {
+            (_, Some(damage)) => Err(damage.to_string()),
+            (chain, None) => Ok(chain),
+        }
+    }
+
+    /// Rebuilds a chain like `from_blocks_assume_valid`, with the node operator's
+    /// `rules` installed before the blocks are replayed, stopping at the first
+    /// block that can't be connected instead of failing.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((Blockchain, Option<ChainDamage>))` - The chain of the valid blocks,
+    ///   and the first invalid one (`None` if every block was connected)
+    /// * `Err(String)` - If there is no valid genesis block to start from
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::block::block::HashAlgorithm;
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::state::rules::LocalRules;
+    ///
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// for _ in 0..3 {
+    ///     chain.add_block(Vec::new(), "Miner").unwrap();
+    /// }
+    /// let mut blocks = chain.get_blocks().to_vec();
+    /// blocks[2].transaction[0].amount = 1_000.0;
+    ///
+    /// let (valid, damage) = Blockchain::from_blocks_until_invalid(1, 50.0, HashAlgorithm::Sha256, blocks, None, &LocalRules::default()).unwrap();
+    /// let damage = damage.unwrap();
+    /// assert_eq!(damage.height, 2);
+    /// assert!(damage.to_string().starts_with("Block 2:"));
+    /// assert_eq!(valid.get_latest_hash(), chain.get_blocks()[1].block_hash);
+    /// ```
+    pub fn from_blocks_until_invalid(difficulty: u32, block_reward: f64, hash_algorithm: HashAlgorithm, blocks: Vec<Block>, assume_valid: Option<&str>, rules: &LocalRules) -> Result<(Self, Option<ChainDamage>), String> {
+        let trusted = assume_valid
+            .and_then(|hash| blocks.iter().position(|block| block.block_hash == hash))
+            .unwrap_or(0);
+        let mut blocks = blocks.into_iter();
+        let genesis = blocks.next().ok_or("No genesis block".to_string())?;
+        let mut state = Blockchain::genesis_state(&genesis, hash_algorithm)
+            .map_err(|error| format!("Block 0: not a valid genesis block ({})", error))?;
+        state.install_rules(rules)?;
+
+        let mut chain = Blockchain::with_genesis(difficulty, block_reward, hash_algorithm, genesis, state, Arc::new(SystemClock));
+        if let Some(treasury) = &rules.treasury {
+            chain.treasury = treasury.clone();
+        }
+        let mut damage = None;
+        for (index, block) in blocks.enumerate() {
+            chain.state.set_assume_valid(index < trusted);
+            if let Err(reason) = chain.submit_block(block) {
+                damage = Some(ChainDamage {
+                    height: index + 1,
+                    reason: reason,
+                });
+                break;
+            }
+        }
+        chain.state.set_assume_valid(false);
+        Ok((chain, damage))
+    }
+
+    /// Checks a genesis block and returns the state its allocations create.
+    pub(crate) fn genesis_state(genesis: &Block, hash_algorithm: HashAlgorithm) -> Result<ChainState, String> {
+        if genesis.previous_block_hash != "0" || genesis.base_fee != 0.0 {
+            return Err("it must point to \"0\" and burn nothing".to_string());
+        }
+        if genesis.merkle_root != genesis.calculate_merkle_root() || genesis.block_hash != genesis.calculate_hash_with(hash_algorithm) {
+            return Err("its hash does not match its data".to_string());
+        }
+        let mut state = ChainState::new();
+        for transaction in &genesis.transaction {
+            if !transaction.is_coinbase() {
+                return Err("it may only hold coinbase allocations".to_string());
+            }
+            state.apply_transaction(transaction)?;
+        }
+        if genesis.state_root != state.state_root() {
+            return Err("its state root does not match its allocations".to_string());
+        }
+        state.record_block(0, genesis.time_stamp, &genesis.transaction);
+        Ok(state)
+    }
+
+    fn with_genesis(difficulty: u32, block_reward: f64, hash_algorithm: HashAlgorithm, genesis: Block, mut state: ChainState, clock: Arc<dyn Clock>) -> Self {
+        let filter_params = FilterParams::default();
+        state.take_touched();
         Blockchain {
-            chain: vec![genesis],    // Initialize chain with genesis block
-            difficulty: 3,            // Default difficulty (3 leading zeros)
+            filters: SharedVec::from(vec![AddressFilter::for_block(&genesis, filter_params)]),
+            filter_params: filter_params,
+            chain: BlockList::from(vec![genesis]), // Initialize chain with genesis block
+            difficulty: difficulty,
+            hash_algorithm: hash_algorithm,
+            block_reward: block_reward,
+            treasury: Treasury::new(TREASURY_ADDRESS.to_string(), DEFAULT_TREASURY_SHARE),
+            state_cache: StateCache::from_state(&state),
+            state: state,
+            base_fee: INITIAL_BASE_FEE,
+            total_burned: 0.0,
+            mempool: Mempool::new(),
+            clock: clock,
+            local_changes: 0,
+            execution_threads: thread::available_parallelism().map(|threads| threads.get()).unwrap_or(1),
+        }
+    }
+
+    /// Returns the clock block timestamps come from.
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
+
+    /// Replaces the chain's clock (e.g. to drive a simulation's time).
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Returns the size of the address filters kept for each block.
+    pub fn filter_params(&self) -> FilterParams {
+        self.filter_params
+    }
+
+    /// Changes the size of the address filters and rebuilds those of every block.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The filters are rebuilt
+    /// * `Err(String)` - If the parameters are invalid (nothing is changed)
+    pub fn set_filter_params(&mut self, params: FilterParams) -> Result<(), String> {
+        params.validate()?;
+        self.filter_params = params;
+        self.filters = self.chain.iter().map(|block| AddressFilter::for_block(block, params)).collect();
+        Ok(())
+    }
+
+    /// Rebuilds the indexes derived from the blocks by replaying every block from
+    /// the genesis: the transaction index (`find_transaction`, relative lock
+    /// times), the address filters (`get_history`, the address index) and the
+    /// state cache (balances and nonces, with the undo records of the latest
+    /// blocks).
+    ///
+    /// Replaying checks every block again, scripts and ring signatures included
+    /// (nothing is assumed valid). The mempool, the treasury, the filter
+    /// parameters and the memory limits are kept.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ReindexReport)` - What was rebuilt, and which indexes were stale
+    /// * `Err(String)` - The first block that can't be replayed (nothing is changed)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    ///
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+    /// let payment = Transaction::create_payment(&mut alice, "Bob", 10.0, chain.get_base_fee()).unwrap();
+    /// let id = payment.id();
+    /// chain.add_block(vec![payment], "Miner").unwrap();
+    ///
+    /// let report = chain.reindex().unwrap();
+    /// assert_eq!(report.blocks, 3);
+    /// assert_eq!(report.accounts, chain.state().accounts().len());
+    /// assert!(report.stale.is_empty());
+    /// assert_eq!(chain.find_transaction(&id).unwrap().0, 2);
+    /// assert_eq!(chain.get_history("Bob").len(), 1);
+    /// assert_eq!(chain.state_cache().depth(), 2);
+    /// ```
+    pub fn reindex(&mut self) -> Result<ReindexReport, String> {
+        let (mut replayed, damage) = Blockchain::from_blocks_until_invalid(self.difficulty, self.block_reward, self.hash_algorithm, self.chain.to_vec(), None, &self.local_rules())?;
+        if let Some(damage) = damage {
+            return Err(damage.to_string());
+        }
+        let filters: SharedVec<AddressFilter> = self.chain.iter()
+            .map(|block| AddressFilter::for_block(block, self.filter_params))
+            .collect();
+
+        let mut stale = Vec::new();
+        if self.state.replace_confirmations(&mut replayed.state) {
+            stale.push("transaction index");
+        }
+        if self.filters != filters {
+            stale.push("address filters");
+        }
+        if self.state_cache.verify(&replayed.state).is_err() {
+            stale.push("state cache");
+        }
+
+        self.filters = filters;
+        replayed.state_cache.set_max_bytes(self.state_cache.max_bytes());
+        self.state_cache = replayed.state_cache;
+        Ok(ReindexReport {
+            blocks: self.chain.len(),
+            transactions: self.state.transactions_confirmed(),
+            accounts: self.state.accounts().len(),
+            stale: stale,
+        })
+    }
+
+    /// Returns the address filter of the block at `height`.
+    pub fn get_filter(&self, height: usize) -> Option<&AddressFilter> {
+        self.filters.get(height)
+    }
+
+    /// Returns the blocks that may touch `address`, with their height.
+    ///
+    /// Blocks whose address filter rules `address` out are skipped without
+    /// looking at their transactions; the others may still not involve it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::block::bloom::FilterParams;
+    /// use blockc::blockchain::blockchain::Blockchain;
+    ///
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// chain.add_block(Vec::new(), "Bob").unwrap();
+    ///
+    /// let heights: Vec<u64> = chain.blocks_touching("Bob").map(|(height, _)| height).collect();
+    /// assert_eq!(heights, vec![2]);
+    ///
+    /// // Smaller filters (more false positives) give the same history
+    /// chain.set_filter_params(FilterParams { bits: 64, hashes: 2 }).unwrap();
+    /// assert_eq!(chain.get_history("Bob").len(), 1);
+    /// assert!(chain.set_filter_params(FilterParams { bits: 0, hashes: 2 }).is_err());
+    /// ```
+    pub fn blocks_touching<'a>(&'a self, address: &str) -> impl Iterator<Item = (u64, &'a Block)> {
+        self.chain.iter().zip(self.filters.iter()).enumerate()
+            .filter(move |(_, (_, filter))| filter.contains(address))
+            .map(|(height, (block, _))| (height as u64, block))
+    }
+
+    /// Registers a chain-level policy script every transaction must pass.
+    ///
+    /// Policies are checked with the chain's other rules: in the mempool, during
+    /// block assembly and when a block is validated, from the next block on. They
+    /// are part of the chain's `LocalRules`, saved with it and applied again when
+    /// its blocks are replayed.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The policy is active
+    /// * `Err(String)` - The script is too large or does not compile
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    ///
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// chain.register_policy("max_amount", "amount <= 10.0").unwrap();
+    ///
+    /// let fee = chain.get_base_fee() * 2.0;
+    /// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+    /// let big = Transaction::create_payment(&mut alice, "Bob", 20.0, fee).unwrap();
+    /// assert!(chain.submit_transaction(big).unwrap_err().contains("max_amount"));
+    /// ```
+    #[cfg(feature = "scripting")]
+    pub fn register_policy(&mut self, name: &str, script: &str) -> Result<(), String> {
+        self.local_changes += 1;
+        self.state.register_policy(name, script)
+    }
+
+    /// Returns the rules added by the node operator (see `LocalRules`), the
+    /// treasury rewound to before its spends included unless it is the default one.
+    pub fn local_rules(&self) -> LocalRules {
+        let treasury = self.treasury.rewound();
+        let default = Treasury::new(TREASURY_ADDRESS.to_string(), DEFAULT_TREASURY_SHARE);
+        LocalRules {
+            treasury: (treasury != default).then_some(treasury),
+            ..self.state.rules().clone()
+        }
+    }
+
+    /// Removes a policy script; returns `true` if it was registered.
+    #[cfg(feature = "scripting")]
+    pub fn remove_policy(&mut self, name: &str) -> bool {
+        self.local_changes += 1;
+        self.state.remove_policy(name)
+    }
+
+    /// Opens a bridge to another chain (see `BridgeLink`).
+    ///
+    /// Like policies, bridges are configured by the node operator rather than by a
+    /// transaction: the opening is kept in the chain's `LocalRules`, saved with it
+    /// and installed again before its blocks are replayed. The remote chain is
+    /// followed from its genesis header, and its blocks must meet `remote_difficulty`.
+    ///
+    /// # Arguments
+    ///
+    /// * `local_chain` - Name of this chain, as the remote chain knows it
+    /// * `remote_chain` - Name of the other chain
+    /// * `remote_genesis` - Genesis header of the other chain (trusted)
+    /// * `remote_difficulty` - Proof-of-work difficulty of the other chain
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The bridge is open
+    /// * `Err(String)` - If a bridge to `remote_chain` is already open
+    pub fn open_bridge(&mut self, local_chain: &str, remote_chain: &str, remote_genesis: BlockHeader, remote_difficulty: u32) -> Result<(), String> {
+        self.local_changes += 1;
+        self.state.open_bridge(local_chain, remote_chain, remote_genesis, remote_difficulty)
+    }
+
+    /// Returns the proof-of-work difficulty (number of leading zeros).
+    pub fn get_difficulty(&self) -> u32 {
+        self.difficulty
+    }
+
+    /// Returns the hash function of the chain's block headers.
+    pub fn hash_algorithm(&self) -> HashAlgorithm {
+        self.hash_algorithm
+    }
+
+    /// Returns the coins created by every new block.
+    pub fn get_block_reward(&self) -> f64 {
+        self.block_reward
+    }
+
+    /// Returns the base fee the next block charges per transaction.
+    /// 
+    /// The base fee is burned, so a transaction must offer at least this fee to be
+    /// included. Anything above it is a tip for the miner.
+    pub fn get_base_fee(&self) -> f64 {
+        self.base_fee
+    }
+
+    /// Returns the total amount of coins burned through base fees.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// 
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Alice").unwrap(); // Alice mines 45.0
+    /// 
+    /// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "pub".to_string(), "priv".to_string());
+    /// let mut bob = Entity::new("Bob".to_string(), 0.0, Vec::new(), "pub2".to_string(), "priv2".to_string());
+    /// let base_fee = chain.get_base_fee();
+    /// let tx = Transaction::create_and_execute_with_fee(&mut alice, &mut bob, 10.0, base_fee + 1.0).unwrap();
+    /// 
+    /// chain.add_block(vec![tx], "Miner").unwrap();
+    /// assert_eq!(chain.get_total_burned(), base_fee);
+    /// assert_eq!(chain.get_balance("Bob"), 10.0);
+    /// ```
+    pub fn get_total_burned(&self) -> f64 {
+        self.total_burned
+    }
+
+    /// Returns all blocks, genesis first.
+    pub fn get_blocks(&self) -> &BlockList {
+        &self.chain
+    }
+
+    /// Returns the headers of the blocks from `from_height` to the tip (what a relayer
+    /// sends to a light client).
+    pub fn get_headers(&self, from_height: usize) -> Vec<BlockHeader> {
+        self.chain.iter().skip(from_height).map(|block| block.header()).collect()
+    }
+
+    /// Returns the pool of transactions waiting for a block.
+    pub fn mempool(&self) -> &Mempool {
+        &self.mempool
+    }
+
+    /// Returns the approximate memory used by the blocks, their address filters, the
+    /// state cache and the mempool (see `MemoryUsage`).
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let filter_bytes = std::mem::size_of::<AddressFilter>() + self.filter_params.bits.div_ceil(64) * 8;
+        MemoryUsage {
+            blocks: self.chain.iter().map(memory::block_bytes).sum(),
+            filters: self.filters.len() * filter_bytes,
+            state_cache: self.state_cache.memory_bytes(),
+            mempool: self.mempool.memory_bytes(),
+        }
+    }
+
+    /// Returns the memory ceilings of the mempool and the state cache.
+    pub fn memory_limits(&self) -> MemoryLimits {
+        MemoryLimits {
+            mempool: self.mempool.max_bytes(),
+            state_cache: self.state_cache.max_bytes(),
+        }
+    }
+
+    /// Sets the memory ceilings of the mempool and the state cache, so the node can
+    /// run within a fixed budget.
+    ///
+    /// Nothing is dropped that the chain needs: past its ceiling the mempool evicts
+    /// the lowest-fee transactions as new ones come (or rejects the new ones), and
+    /// the state cache forgets its oldest undo records.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::blockchain::memory::MemoryLimits;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    ///
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// chain.set_memory_limits(MemoryLimits { mempool: Some(0), state_cache: Some(0) });
+    /// assert_eq!(chain.state_cache().depth(), 0);
+    ///
+    /// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+    /// let payment = Transaction::create_payment(&mut alice, "Bob", 1.0, 0.5).unwrap();
+    /// assert!(chain.submit_transaction(payment).is_err());
+    /// assert_eq!(chain.memory_usage().mempool, 0);
+    /// ```
+    pub fn set_memory_limits(&mut self, limits: MemoryLimits) {
+        self.mempool.set_max_bytes(limits.mempool);
+        self.state_cache.set_max_bytes(limits.state_cache);
+    }
+
+    /// Returns the number of threads block transactions are applied on.
+    pub fn execution_threads(&self) -> usize {
+        self.execution_threads
+    }
+
+    /// Sets the number of threads block transactions are applied on (the number of
+    /// cores by default; 1 applies them in order).
+    ///
+    /// Blocks of at least `parallel::PARALLEL_THRESHOLD` user transactions go
+    /// through `parallel::apply_parallel`: independent payments are applied on
+    /// several threads, with the same resulting state as in order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::fees::base_fee::MAX_BLOCK_SIZE;
+    /// use blockc::state::parallel;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    ///
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.set_execution_threads(4);
+    /// let mut senders = Vec::new();
+    /// for i in 0..MAX_BLOCK_SIZE {
+    ///     let name = format!("sender{}", i);
+    ///     chain.add_block(Vec::new(), &name).unwrap();
+    ///     senders.push(Entity::new(name, 45.0, Vec::new(), "s".to_string(), "s".to_string()));
+    /// }
+    /// let fee = chain.get_base_fee() * 2.0;
+    /// let payments: Vec<Transaction> = senders.iter_mut().enumerate()
+    ///     .map(|(i, sender)| Transaction::create_payment(sender, &format!("receiver{}", i), 1.0, fee).unwrap())
+    ///     .collect();
+    ///
+    /// // A full block of independent payments is spread over threads
+    /// let before = chain.state().clone();
+    /// let stages = parallel::parallel_stages();
+    /// chain.add_block(payments, "Miner").unwrap();
+    /// assert!(parallel::parallel_stages() > stages);
+    ///
+    /// // Its state root is the one of applying it in order
+    /// let block = chain.get_latest_block();
+    /// assert_eq!(block.transaction.len(), MAX_BLOCK_SIZE + 2);
+    /// let (coinbase, user): (Vec<&Transaction>, Vec<&Transaction>) = block.transaction.iter().partition(|t| t.is_coinbase());
+    /// let mut sequential = before;
+    /// for transaction in user.into_iter().chain(coinbase) {
+    ///     sequential.apply_transaction(transaction).unwrap();
+    /// }
+    /// assert_eq!(sequential.state_root(), block.state_root);
+    /// ```
+    pub fn set_execution_threads(&mut self, threads: usize) {
+        self.execution_threads = threads.max(1);
+    }
+
+    /// Returns the pool of transactions waiting for a block, e.g. to change its
+    /// replace-by-fee policy or collect its events.
+    pub fn mempool_mut(&mut self) -> &mut Mempool {
+        &mut self.mempool
+    }
+
+    /// Submits a transaction to the mempool.
+    /// 
+    /// The transaction must be signed and pay at least the current base fee (treasury spends excepted),
+    /// its sender must be able to afford it on chain (without locked vesting), its nonce must not be used yet
+    /// and its lock time must allow it in the next block. Its gas limit must cover its
+    /// intrinsic gas and fit in a block (`BLOCK_GAS_LIMIT`), and its own script and the
+    /// policy scripts must accept it.
+    /// Submitting a transaction with the same nonce as a pending one replaces it if
+    /// the fee bump is high enough (see `RbfPolicy`).
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(())` - If the transaction is now pending
+    /// * `Err(String)` - If the transaction is rejected
+    pub fn submit_transaction(&mut self, transaction: Transaction) -> Result<(), String> {
+        if !transaction.is_signed() {
+            return Err(format!("Transaction {} is not signed", transaction.id()));
         }
+        if transaction.gas_limit < transaction.intrinsic_gas() || transaction.gas_limit > BLOCK_GAS_LIMIT {
+            return Err(format!("Gas limit {} must be between the intrinsic gas {} and the block gas limit {}", transaction.gas_limit, transaction.intrinsic_gas(), BLOCK_GAS_LIMIT));
+        }
+        if transaction.sender_address != self.treasury.address {
+            if transaction.fee < self.base_fee {
+                return Err(format!("Transaction fee {} is below the base fee {}", transaction.fee, self.base_fee));
+            }
+            let balance = self.state.get_spendable_balance(&transaction.sender_address);
+            if balance < transaction.total_cost() {
+                return Err(format!("Insufficient on-chain balance for {}. Have: {}, Need: {}", transaction.sender_address, balance, transaction.total_cost()));
+            }
+        }
+        if transaction.nonce < self.state.get_nonce(&transaction.sender_address) {
+            return Err(format!("Nonce {} was already used by {}", transaction.nonce, transaction.sender_address));
+        }
+        self.state.check_lock_time(&transaction)?;
+        self.state.check_scripts(&transaction)?;
+        self.mempool.add(transaction)
+    }
+
+    /// Builds a block from the mempool (highest fees first) and adds it to the chain.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `miner_address` - Address receiving the block reward and tips
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(usize)` - Number of mempool transactions included
+    /// * `Err(String)` - If the block could not be added
+    pub fn add_block_from_mempool(&mut self, miner_address: &str) -> Result<usize, String> {
+        let transactions = self.mempool.select(MAX_BLOCK_SIZE, self.base_fee, &self.state);
+        let count = transactions.len();
+        self.add_block(transactions, miner_address)?;
+        Ok(count)
+    }
+
+    /// Returns the on-chain state (balances and nonces) after the last block.
+    pub fn state(&self) -> &ChainState {
+        &self.state
+    }
+
+    /// Returns the nonce the next transaction from `address` must use.
+    pub fn get_nonce(&self, address: &str) -> u64 {
+        self.state_cache.get_nonce(address)
+    }
+
+    /// Returns the cache of balances and nonces, updated with every connected block.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// 
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+    /// let payment = Transaction::create_payment(&mut alice, "Bob", 10.0, chain.get_base_fee()).unwrap();
+    /// chain.add_block(vec![payment], "Miner").unwrap();
+    /// assert_eq!(chain.state_cache().get_balance("Bob"), 10.0);
+    /// assert_eq!(chain.state_cache().get_nonce("Alice"), 1);
+    /// 
+    /// // The cache matches the state rebuilt by replaying every block
+    /// let replayed = Blockchain::from_blocks(1, 50.0, chain.get_blocks().to_vec()).unwrap();
+    /// assert!(chain.state_cache().verify(replayed.state()).is_ok());
+    /// ```
+    pub fn state_cache(&self) -> &StateCache {
+        &self.state_cache
+    }
+
+    /// Proves that a document hash was anchored on chain.
+    /// 
+    /// Looks for the first anchor transaction carrying `doc_hash` and returns the
+    /// block height and timestamp it was included at, with a Merkle proof linking the
+    /// transaction to the block header.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Some(ExistenceProof)` - The proof (check it with `ExistenceProof::verify`)
+    /// * `None` - If the document was never anchored
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// 
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// 
+    /// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "pub".to_string(), "priv".to_string());
+    /// let anchor = Transaction::create_anchor(&mut alice, "d0c5".to_string(), chain.get_base_fee()).unwrap();
+    /// chain.add_block(vec![anchor], "Miner").unwrap();
+    /// 
+    /// let proof = chain.prove_existence("d0c5").unwrap();
+    /// assert_eq!(proof.height, 2);
+    /// assert!(proof.verify());
+    /// ```
+    pub fn prove_existence(&self, doc_hash: &str) -> Option<ExistenceProof> {
+        for (height, block) in self.chain.iter().enumerate() {
+            let found = block.transaction.iter().find(|t| {
+                matches!(&t.kind, TransactionKind::Anchor { doc_hash: anchored } if anchored == doc_hash)
+            });
+            if let Some(transaction) = found {
+                return Some(ExistenceProof {
+                    doc_hash: doc_hash.to_string(),
+                    height: height as u64,
+                    timestamp: block.time_stamp,
+                    block_hash: block.block_hash.clone(),
+                    merkle_root: block.merkle_root.clone(),
+                    transaction: transaction.clone(),
+                    proof: block.prove_transaction(&transaction.id())?,
+                });
+            }
+        }
+        None
+    }
+
+    /// Returns the confirmed transactions sent by or paying `address`, with the
+    /// height of their block (oldest first).
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// 
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.add_block(Vec::new(), "Miner").unwrap();
+    /// 
+    /// let history = chain.get_history("Miner");
+    /// assert_eq!(history.len(), 1);
+    /// assert_eq!(history[0].0, 1);
+    /// assert_eq!(history[0].1.amount, 45.0);
+    /// ```
+    pub fn get_history(&self, address: &str) -> Vec<(u64, &Transaction)> {
+        self.blocks_touching(address)
+            .flat_map(|(height, block)| block.transaction.iter().map(move |transaction| (height, transaction)))
+            .filter(|(_, transaction)| transaction.sender_address == address || transaction.pays(address))
+            .collect()
+    }
+
+    /// Renders the chain as a Graphviz DOT graph (see `export::dot::blocks_to_dot`).
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// 
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.add_block(Vec::new(), "Miner").unwrap();
+    /// 
+    /// let dot = chain.to_dot();
+    /// assert!(dot.contains("#1\\n"));
+    /// assert!(dot.contains(&format!("\"{}\" -> \"{}\"", chain.get_latest_hash(), chain.get_blocks()[0].block_hash)));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        dot::blocks_to_dot(&self.chain)
+    }
+
+    /// Renders the chain as a Mermaid flowchart (see `export::mermaid`).
+    pub fn to_mermaid(&self) -> String {
+        mermaid::chain_to_mermaid(&self.chain)
+    }
+
+    /// Writes the confirmed transactions kept by `filter` to a CSV file (see
+    /// `export::csv::write_csv` for the columns).
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(usize)` - Number of transaction rows written
+    /// * `Err(String)` - If the file can't be written
+    #[cfg(feature = "fs")]
+    pub fn export_csv(&self, path: &Path, filter: &LedgerFilter) -> Result<usize, String> {
+        csv::export_csv(self, path, filter)
+    }
+
+    /// Finds a confirmed transaction by id, through the transaction index.
+    /// 
+    /// # Returns
+    /// 
+    /// The height of its block and the transaction, or `None` if no block has it.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// 
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.add_block(Vec::new(), "Miner").unwrap();
+    /// let coinbase = chain.get_latest_block().transaction[0].id();
+    /// 
+    /// assert_eq!(chain.find_transaction(&coinbase).unwrap().0, 1);
+    /// assert!(chain.find_transaction("unknown").is_none());
+    /// ```
+    pub fn find_transaction(&self, id: &str) -> Option<(u64, &Transaction)> {
+        let (height, _) = self.state.get_confirmation(id)?;
+        self.chain.get(height as usize)?.transaction.iter()
+            .find(|transaction| transaction.id() == id)
+            .map(|transaction| (height, transaction))
+    }
+
+    /// Returns the on-chain balance of `address`.
+    pub fn get_balance(&self, address: &str) -> f64 {
+        self.state_cache.get_balance(address)
+    }
+
+    /// Returns the protocol treasury.
+    pub fn treasury(&self) -> &Treasury {
+        &self.treasury
+    }
+
+    /// Returns the protocol treasury so governance can create and approve proposals.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// 
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Miner").unwrap(); // treasury receives 5.0
+    /// 
+    /// let treasury = chain.treasury_mut();
+    /// treasury.add_governor("Alice".to_string());
+    /// let id = treasury.propose("Bob".to_string(), 3.0, "Grant".to_string()).unwrap();
+    /// treasury.approve(id, "Alice").unwrap();
+    /// 
+    /// let spend = chain.treasury().create_spend(id).unwrap();
+    /// chain.add_block(vec![spend], "Miner").unwrap();
+    /// assert_eq!(chain.get_balance("Bob"), 3.0);
+    ///
+    /// // Proposals and approvals are local rules: replaying the spend still works
+    /// assert!(chain.reindex().unwrap().stale.is_empty());
+    /// ```
+    pub fn treasury_mut(&mut self) -> &mut Treasury {
+        self.local_changes += 1;
+        &mut self.treasury
     }
     
     /// Returns a reference to the latest block in the chain.
@@ -56,24 +1089,347 @@ impl Blockchain {
     /// Adds a new block to the blockchain.
     /// 
     /// This method:
-    /// 1. Gets the previous block's hash
-    /// 2. Creates a new block with the given transactions
-    /// 3. Adds it to the chain
+    /// 1. Builds a block template with the given transactions (see `build_template`)
+    /// 2. Mines it (proof of work at the chain's difficulty)
+    /// 3. Submits it (see `submit_block`)
     /// 
-    /// Note: Proof-of-work mining is not yet implemented, so blocks are added immediately.
+    /// Nothing is changed if any transaction is invalid.
     /// 
     /// # Arguments
     /// 
     /// * `transactions` - Vector of transactions to include in the new block
-    pub fn add_block(&mut self, transactions: Vec<Transaction>) {
-        // Get previous block's hash
-        let previous_hash = self.get_latest_hash();
-        
-        // Create new block
-        let new_block = Block::new(transactions, previous_hash);
-        
-        // Add to chain
-        self.chain.push(new_block);
+    /// * `miner_address` - Address receiving the block reward and fees
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(())` - If the block was added
+    /// * `Err(String)` - If the block is too big or a transaction is invalid
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// 
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Miner").unwrap();
+    /// 
+    /// // 10% of the reward goes to the treasury by default
+    /// assert_eq!(chain.get_balance("Miner"), 45.0);
+    /// assert_eq!(chain.get_balance(&chain.treasury().address), 5.0);
+    /// 
+    /// // An empty block lowers the base fee
+    /// assert!(chain.get_base_fee() < 0.1);
+    /// ```
+    pub fn add_block(&mut self, transactions: Vec<Transaction>, miner_address: &str) -> Result<(), String> {
+        let template = self.build_template(transactions, miner_address)?;
+        let block = template.mine();
+        self.submit_block(block)
+    }
+
+    /// Returns a block template for an external miner.
+    /// 
+    /// The template contains the coinbase transactions paying `miner_address`, the
+    /// best transactions from the mempool (see `Mempool::select`), the previous
+    /// block hash and the difficulty. Mine it and hand it back with `submit_block`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `miner_address` - Address receiving the block reward and tips
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(BlockTemplate)` - The candidate block
+    /// * `Err(String)` - If the selected transactions cannot be applied
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::block::block::BlockExt;
+    /// 
+    /// let mut chain = Blockchain::new();
+    /// let template = chain.get_block_template("Miner").unwrap();
+    /// assert_eq!(template.height, 1);
+    /// 
+    /// let block = template.mine();
+    /// chain.submit_block(block).unwrap();
+    /// assert_eq!(chain.get_balance("Miner"), 45.0);
+    /// ```
+    pub fn get_block_template(&self, miner_address: &str) -> Result<BlockTemplate, String> {
+        let transactions = self.mempool.select(MAX_BLOCK_SIZE, self.base_fee, &self.state);
+        self.build_template(transactions, miner_address)
+    }
+
+    /// Builds a block template containing `transactions`.
+    /// 
+    /// The coinbase transactions come first:
+    /// 1. The block reward plus tips, minus the treasury share, to `miner_address`
+    /// 2. The treasury share to the treasury (if not 0)
+    /// 
+    /// The user transactions follow in canonical order (see `ordering::canonical_order`),
+    /// whatever order they were given in.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(BlockTemplate)` - The unmined block
+    /// * `Err(String)` - If the block is too big or a transaction is invalid
+    pub fn build_template(&self, transactions: Vec<Transaction>, miner_address: &str) -> Result<BlockTemplate, String> {
+        let transactions = ordering::canonical_order(transactions);
+        let mut state = self.state.clone();
+        let mut treasury = self.treasury.clone();
+        let (tips, _) = self.apply_transactions(&transactions, &mut state, &mut treasury)?;
+
+        let mut block_transactions = self.coinbase_transactions(miner_address, tips);
+        for coinbase in &block_transactions {
+            state.apply_transaction(coinbase)?;
+        }
+        block_transactions.extend(transactions);
+
+        Ok(BlockTemplate {
+            block: Block::new_at(block_transactions, self.get_latest_hash(), self.base_fee, state.state_root(), self.next_block_time()),
+            height: self.chain.len() as u64,
+            difficulty: self.difficulty,
+            hash_algorithm: self.hash_algorithm,
+        })
+    }
+
+    /// Timestamp of the next block: the clock's time, but never before the latest block.
+    fn next_block_time(&self) -> u32 {
+        self.clock.now().max(self.get_latest_block().time_stamp)
+    }
+
+    /// Validates a mined block and connects it to the chain.
+    /// 
+    /// This method checks:
+    /// 1. The block builds on the latest block and uses the current base fee; its
+    ///    timestamp is not before the latest block nor more than
+    ///    `MAX_FUTURE_BLOCK_TIME` ahead of the chain's clock
+    /// 2. Its Merkle root and hash are correct and the hash meets the difficulty (proof of work)
+    /// 3. It is not over `MAX_BLOCK_SIZE` transactions (coinbase excluded) and the
+    ///    gas limits of its transactions add up to at most `BLOCK_GAS_LIMIT`
+    /// 4. Every transaction can be applied (senders afford it, nonces in order)
+    /// 5. Every transaction pays at least the base fee, which is burned
+    /// 6. Treasury spends point to an approved proposal (they don't pay the base fee)
+    /// 7. The coinbase pays exactly the reward plus tips (gas paid included), with the treasury's share
+    /// 8. Memos respect the `MAX_MEMO_BYTES` policy limit (encrypted memos are well formed)
+    /// 9. Lock times have expired (see `ChainState::check_lock_time`)
+    /// 10. The state root matches the account state after the block
+    /// 11. Transaction scripts and policy scripts accept every transaction
+    /// 12. The user transactions are in canonical order (see `ordering::canonical_order`),
+    ///     so every node applies them the same way
+    /// 
+    /// Then the block is added and the base fee is adjusted for the next block
+    /// depending on how full this one was. Nothing is changed if a check fails.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(())` - If the block was connected
+    /// * `Err(String)` - The reason the block was rejected
+    pub fn submit_block(&mut self, block: Block) -> Result<(), String> {
+        let verified = self.verify_block(block)?;
+        self.connect_block(verified)
+    }
+
+    /// Runs every check of `submit_block` without changing the chain, and returns
+    /// the block with the state it leads to, ready to be connected.
+    ///
+    /// This is the expensive part of connecting a block: it can run on a copy of
+    /// the chain, away from its lock (see `verifier::Verifier`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::block::ordering;
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// use blockc::block::block::BlockExt;
+    ///
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// let block = chain.get_block_template("Miner").unwrap().mine();
+    ///
+    /// let verified = chain.verify_block(block.clone()).unwrap();
+    /// assert_eq!(verified.block(), &block);
+    /// assert_eq!(chain.get_blocks().len(), 1);
+    ///
+    /// // Gas limits that would overflow when added up are refused
+    /// let mut template = chain.get_block_template("Miner").unwrap();
+    /// let greedy: Vec<Transaction> = ["Alice", "Bob"].iter().map(|sender| {
+    ///     let mut transaction = Transaction::create_unsigned_payment(sender, "Carol", 1.0, 1.0, 0).unwrap();
+    ///     transaction.gas_limit = u64::MAX;
+    ///     transaction
+    /// }).collect();
+    /// template.block.transaction.extend(ordering::canonical_order(greedy));
+    /// template.block.merkle_root = template.block.calculate_merkle_root();
+    /// assert!(chain.verify_block(template.mine()).unwrap_err().starts_with("Block gas"));
+    /// ```
+    pub fn verify_block(&self, block: Block) -> Result<VerifiedBlock, String> {
+        if block.previous_block_hash != self.get_latest_hash() {
+            return Err("Block does not build on the latest block".to_string());
+        }
+        if block.base_fee != self.base_fee {
+            return Err(format!("Block base fee {} does not match {}", block.base_fee, self.base_fee));
+        }
+        if block.time_stamp < self.get_latest_block().time_stamp {
+            return Err("Block timestamp is before the latest block".to_string());
+        }
+        if block.time_stamp as u64 > self.clock.now() as u64 + MAX_FUTURE_BLOCK_TIME as u64 {
+            return Err(format!("Block timestamp {} is too far in the future", block.time_stamp));
+        }
+        if block.merkle_root != block.calculate_merkle_root() {
+            return Err("Block Merkle root does not match its transactions".to_string());
+        }
+        if block.block_hash != block.calculate_hash_with(self.hash_algorithm) {
+            return Err("Block hash does not match its data".to_string());
+        }
+        if !block.meets_difficulty(self.difficulty) {
+            return Err(format!("Block hash does not meet difficulty {}", self.difficulty));
+        }
+
+        // Coinbase transactions come first, everything after is a user transaction
+        let coinbase_count = block.transaction.iter().take_while(|t| t.is_coinbase()).count();
+        let (coinbase, transactions) = block.transaction.split_at(coinbase_count);
+        if coinbase.is_empty() {
+            return Err("Block has no coinbase transaction".to_string());
+        }
+        if !ordering::is_canonical(transactions) {
+            return Err("Block transactions are not in canonical order".to_string());
+        }
+
+        // Work on copies so a bad block leaves the chain untouched
+        let mut state = self.state.clone();
+        let mut treasury = self.treasury.clone();
+        let (tips, burned) = self.apply_transactions(transactions, &mut state, &mut treasury)?;
+
+        let expected = self.coinbase_transactions(&coinbase[0].receiver_address, tips);
+        let matches = expected.len() == coinbase.len() && expected.iter().zip(coinbase)
+            .all(|(e, c)| e.receiver_address == c.receiver_address && e.amount == c.amount);
+        if !matches {
+            return Err("Coinbase does not pay the expected reward".to_string());
+        }
+        for transaction in coinbase {
+            state.apply_transaction(transaction)?;
+        }
+        if block.state_root != state.state_root() {
+            return Err("Block state root does not match the state after it".to_string());
+        }
+        state.record_block(self.chain.len() as u64, block.time_stamp, &block.transaction);
+        Ok(VerifiedBlock {
+            block: block,
+            state: state,
+            treasury: treasury,
+            burned: burned,
+            local_changes: self.local_changes,
+        })
+    }
+
+    /// Adds a block checked by `verify_block` and adjusts the base fee.
+    ///
+    /// The state verified replaces the chain's. If the operator changed the local
+    /// rules or the treasury since (`register_policy`, `remove_policy`,
+    /// `open_bridge`, `treasury_mut`), the block is verified again against them
+    /// first, so the changes are neither lost nor bypassed.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the block was connected
+    /// * `Err(String)` - If another block was connected since it was verified, or
+    ///   the block breaks the rules changed since
+    pub(crate) fn connect_block(&mut self, verified: VerifiedBlock) -> Result<(), String> {
+        let verified = match verified.local_changes == self.local_changes {
+            true => verified,
+            false => self.verify_block(verified.block)?,
+        };
+        let VerifiedBlock { block, mut state, treasury, burned, .. } = verified;
+        if block.previous_block_hash != self.get_latest_hash() || block.base_fee != self.base_fee {
+            return Err("Block does not build on the latest block".to_string());
+        }
+        let coinbase_count = block.transaction.iter().take_while(|t| t.is_coinbase()).count();
+        let transactions = &block.transaction[coinbase_count..];
+
+        self.mempool.remove_confirmed(transactions);
+        self.base_fee = base_fee::next_base_fee(self.base_fee, transactions.len(), TARGET_BLOCK_SIZE);
+        self.total_burned += burned;
+        let touched = state.take_touched();
+        self.state_cache.connect(&state, &touched);
+        self.state = state;
+        self.treasury = treasury;
+        self.filters.push(AddressFilter::for_block(&block, self.filter_params));
+        self.chain.push(block);
+        Ok(())
+    }
+
+    /// Applies user transactions on `state`, checking block size, block gas, base fee
+    /// and treasury spends.
+    /// 
+    /// Transactions go through `parallel::apply_parallel` on `execution_threads`
+    /// threads, whose result and error are the same as applying them in order.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok((tips, burned))` - Fees above the base fee plus gas paid, and base fees burned
+    /// * `Err(String)` - The first problem found
+    fn apply_transactions(&self, transactions: &[Transaction], state: &mut ChainState, treasury: &mut Treasury) -> Result<(f64, f64), String> {
+        let mut tips = 0.0;
+        let mut burned = 0.0;
+
+        if transactions.len() > MAX_BLOCK_SIZE {
+            return Err(format!("Block has {} transactions, limit is {}", transactions.len(), MAX_BLOCK_SIZE));
+        }
+        // Gas limits come from the block: saturate rather than overflow
+        let block_gas = transactions.iter().fold(0u64, |gas, t| gas.saturating_add(t.gas_limit));
+        if block_gas > BLOCK_GAS_LIMIT {
+            return Err(format!("Block gas {} is over the limit {}", block_gas, BLOCK_GAS_LIMIT));
+        }
+
+        // Checks that don't need the state, up to the first failure; transactions
+        // before it are applied first so an earlier state error still wins
+        let mut checked = transactions.len();
+        let mut check_error = None;
+        for (index, transaction) in transactions.iter().enumerate() {
+            if let Err(error) = self.check_block_transaction(transaction, treasury) {
+                checked = index;
+                check_error = Some(error);
+                break;
+            }
+        }
+        let gas_used = parallel::apply_parallel(state, &transactions[..checked], self.execution_threads)?;
+        if let Some(error) = check_error {
+            return Err(error);
+        }
+
+        for (transaction, gas_used) in transactions.iter().zip(gas_used) {
+            // The base fee part is burned, the rest and the gas paid are a tip for the miner
+            let base_part = transaction.fee.min(self.base_fee);
+            burned += base_part;
+            tips += transaction.fee - base_part + gas_used as f64 * transaction.gas_price;
+        }
+        Ok((tips, burned))
+    }
+
+    /// Checks a user transaction of a block against the rules that don't depend on
+    /// the account state: no coinbase, memo size, base fee and treasury spends.
+    fn check_block_transaction(&self, transaction: &Transaction, treasury: &mut Treasury) -> Result<(), String> {
+        if transaction.is_coinbase() {
+            return Err("Coinbase transactions are created by the chain".to_string());
+        }
+        transaction.check_memo()?;
+        if transaction.sender_address == treasury.address {
+            treasury.authorize_spend(transaction)?;
+        } else if transaction.fee < self.base_fee {
+            return Err(format!("Transaction fee {} is below the base fee {}", transaction.fee, self.base_fee));
+        }
+        Ok(())
+    }
+
+    /// Coinbase transactions paying the block reward plus `tips`, split between
+    /// the miner and the treasury.
+    fn coinbase_transactions(&self, miner_address: &str, tips: f64) -> Vec<Transaction> {
+        let (treasury_part, miner_part) = self.treasury.split(self.block_reward + tips);
+        let mut coinbase = vec![Transaction::coinbase(miner_address.to_string(), miner_part)];
+        if treasury_part > 0.0 {
+            coinbase.push(Transaction::coinbase(self.treasury.address.clone(), treasury_part));
+        }
+        coinbase
     }
     
     /// Validates the integrity of the entire blockchain.
@@ -82,45 +1438,202 @@ impl Blockchain {
     /// 1. Each block's stored hash matches its calculated hash
     /// 2. Each block's `previous_hash` matches the previous block's hash
     /// 3. Genesis block has the correct previous hash ("0")
+    /// 4. Every transaction paid at least the base fee of its block
+    /// 5. Every block after genesis meets the proof-of-work difficulty
+    /// 6. Each block's Merkle root matches its transactions
+    /// 7. No block is older than the block before it
+    /// 8. Every block's user transactions are in canonical order
     /// 
     /// # Returns
     /// 
     /// * `true` - If the chain is valid
-    /// * `false` - If any validation check fails
+    /// * `false` - If any validation check fails (see `validate` for which one)
     pub fn is_valid(&self) -> bool {
-        // Check if chain is empty
+        self.validate().is_ok()
+    }
+
+    /// Validates the chain like `is_valid` and says where it is broken.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(())` - If the chain is valid
+    /// * `Err(String)` - The first failed check, starting with the height of the block
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// 
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.add_block(Vec::new(), "Miner").unwrap();
+    /// assert!(chain.validate().is_ok());
+    /// assert_eq!(chain.first_invalid_block(), None);
+    /// ```
+    pub fn validate(&self) -> Result<(), String> {
         if self.chain.is_empty() {
-            return false;
-        }
-        
-        // Validate genesis block
-        let genesis = &self.chain[0];
-        if genesis.previous_block_hash != "0" {
-            return false;
-        }
-        
-        // Check if genesis block's hash matches its calculated hash
-        if genesis.block_hash != genesis.calculate_hash() {
-            return false;
-        }
-        
-        // Validate the rest of the chain
-        // Use windows(2) to get pairs of consecutive blocks
-        for window in self.chain.windows(2) {
-            let previous = &window[0];
-            let current = &window[1];
-            
-            // Check if current block's hash matches its calculated hash
-            if current.block_hash != current.calculate_hash() {
-                return false;
-            }
-            
-            // Check if current block's previous_hash matches previous block's hash
-            if current.previous_block_hash != previous.block_hash {
-                return false;
-            }
+            return Err("Chain has no genesis block".to_string());
+        }
+        for height in 0..self.chain.len() {
+            self.validate_block(height).map_err(|reason| format!("Block {}: {}", height, reason))?;
         }
-        
-        true
+        Ok(())
     }
-}
\ No newline at end of file
+
+    /// Truncates the chain to its last valid block, so the chain can be mined or
+    /// synced again from there.
+    ///
+    /// The blocks are replayed with the chain's local rules, like when it is
+    /// loaded, up to the first one that fails the checks of `validate` (see
+    /// `first_damaged_block`) or that is rejected on replay; that block and every
+    /// block after it are removed. The clock, the address filter parameters, the
+    /// memory limits and the mempool settings are kept, and proposals spent by
+    /// the removed blocks are approved again. The user transactions of the removed
+    /// blocks go back to the mempool with the pending ones; those the shorter
+    /// chain no longer accepts are dropped.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(ChainDamage))` - The block the chain was cut at, with why
+    /// * `Ok(None)` - Every block is valid (nothing is changed)
+    /// * `Err(String)` - If the genesis block itself is damaged (nothing is changed)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    ///
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+    /// let payment = Transaction::create_payment(&mut alice, "Bob", 10.0, chain.get_base_fee() * 2.0).unwrap();
+    /// chain.add_block(vec![payment], "Miner").unwrap();
+    ///
+    /// // A sound chain is left alone
+    /// assert_eq!(chain.repair(), Ok(None));
+    /// assert_eq!(chain.get_blocks().len(), 3);
+    ///
+    /// // A spend whose proposal was lost no longer replays: the chain is cut there
+    /// chain.treasury_mut().add_governor("Alice".to_string());
+    /// let id = chain.treasury_mut().propose("Carol".to_string(), 1.0, "Grant".to_string()).unwrap();
+    /// chain.treasury_mut().approve(id, "Alice").unwrap();
+    /// chain.add_block(vec![chain.treasury().create_spend(id).unwrap()], "Miner").unwrap();
+    /// chain.treasury_mut().proposals.clear();
+    /// let damage = chain.repair().unwrap().unwrap();
+    /// assert_eq!(damage.height, 3);
+    /// assert!(damage.reason.contains("does not reference a proposal"), "{}", damage.reason);
+    /// assert_eq!(chain.get_blocks().len(), 3);
+    /// ```
+    pub fn repair(&mut self) -> Result<Option<ChainDamage>, String> {
+        let damaged = self.first_invalid_block().map(|height| ChainDamage {
+            height: height,
+            reason: self.validate_block(height).unwrap_err(),
+        });
+        if let Some(damage) = damaged.as_ref().filter(|damage| damage.height == 0) {
+            return Err(format!("Cannot repair the chain: {}", damage));
+        }
+
+        // Blocks are replayed up to the first damaged one, and cut at the first one rejected
+        let kept = damaged.as_ref().map_or(self.chain.len(), |damage| damage.height);
+        let blocks = self.chain.iter().take(kept).cloned().collect();
+        let (mut repaired, rejected) = Blockchain::from_blocks_until_invalid(self.difficulty, self.block_reward, self.hash_algorithm, blocks, None, &self.local_rules())?;
+        let Some(damage) = rejected.or(damaged) else {
+            return Ok(None);
+        };
+        let mut pending: Vec<Transaction> = self.chain.iter().skip(damage.height)
+            .flat_map(|block| block.transaction.iter().filter(|transaction| !transaction.is_coinbase()).cloned())
+            .collect();
+        pending.extend(self.mempool.transactions().iter().cloned());
+
+        repaired.set_filter_params(self.filter_params)?;
+        repaired.clock = self.clock.clone();
+        repaired.mempool = std::mem::take(&mut self.mempool);
+        repaired.mempool.clear();
+        repaired.set_memory_limits(self.memory_limits());
+        repaired.execution_threads = self.execution_threads;
+        // The treasury was replayed too: blocks verified before must be checked again
+        repaired.local_changes = self.local_changes + 1;
+        *self = repaired;
+        for transaction in pending {
+            // Transactions that depended on the removed blocks are dropped
+            let _ = self.submit_transaction(transaction);
+        }
+        Ok(Some(damage))
+    }
+
+    /// Returns a handle to damage blocks in place (see `Tamper`), to demonstrate
+    /// what `validate` catches.
+    #[cfg(feature = "tamper")]
+    pub fn tamper(&mut self) -> Tamper<'_> {
+        Tamper::new(&mut self.chain, self.difficulty, self.hash_algorithm)
+    }
+
+    /// Returns the height of the first block that fails validation, if any.
+    pub fn first_invalid_block(&self) -> Option<usize> {
+        (0..self.chain.len()).find(|height| self.validate_block(*height).is_err())
+    }
+
+    /// Returns the first stored block that is damaged: one that fails the checks of
+    /// `validate` on its own or against the block before it, whatever the chain's
+    /// state and local rules.
+    ///
+    /// `ChainStore::repair` only truncates a chain there.
+    ///
+    /// # Arguments
+    ///
+    /// * `difficulty` - The chain's proof-of-work difficulty
+    /// * `hash_algorithm` - The algorithm the blocks are hashed with
+    /// * `blocks` - The blocks, from the genesis, as stored
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::block::block::HashAlgorithm;
+    /// use blockc::blockchain::blockchain::Blockchain;
+    ///
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// chain.add_block(Vec::new(), "Bob").unwrap();
+    /// let mut blocks = chain.get_blocks().to_vec();
+    /// assert_eq!(Blockchain::first_damaged_block(1, HashAlgorithm::Sha256, &blocks), None);
+    ///
+    /// blocks[1].previous_block_hash = "f00d".to_string();
+    /// let damage = Blockchain::first_damaged_block(1, HashAlgorithm::Sha256, &blocks).unwrap();
+    /// assert_eq!(damage.height, 1);
+    /// assert_eq!(damage.reason, "hash does not match its data");
+    /// ```
+    pub fn first_damaged_block(difficulty: u32, hash_algorithm: HashAlgorithm, blocks: &[Block]) -> Option<ChainDamage> {
+        (0..blocks.len()).find_map(|height| {
+            let previous = height.checked_sub(1).map(|previous| &blocks[previous]);
+            check_block(&blocks[height], previous, height, difficulty, hash_algorithm, TREASURY_ADDRESS)
+                .err()
+                .map(|reason| ChainDamage { height: height, reason: reason })
+        })
+    }
+
+    /// Checks one block of the chain on its own and against the block before it.
+    fn validate_block(&self, height: usize) -> Result<(), String> {
+        let previous = height.checked_sub(1).map(|previous| &self.chain[previous]);
+        check_block(&self.chain[height], previous, height, self.difficulty, self.hash_algorithm, &self.treasury.address)
+    }
+}
+
+impl fmt::Debug for Blockchain {
+    // The clock has no useful representation: show the chain's summary instead
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Blockchain")
+            .field("height", &(self.chain.len() - 1))
+            .field("tip", &self.get_latest_hash())
+            .field("difficulty", &self.difficulty)
+            .field("base_fee", &self.base_fee)
+            .field("state_root", &self.state.state_root())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for Blockchain {
+    fn default() -> Self {
+        Blockchain::new()
+    }
+}
@@ -0,0 +1,257 @@
+use std::fmt;
+use std::iter::FusedIterator;
+use std::ops::Index;
+use std::sync::Arc;
+use crate::block::block::Block;
+
+/// Number of items per chunk of a `SharedVec`.
+const CHUNK_SIZE: usize = 64;
+
+/// The blocks of a chain (see `SharedVec`).
+pub type BlockList = SharedVec<Block>;
+
+/// A list of items (the blocks of a chain, genesis first, or data kept per
+/// block) shared between copies of the chain.
+///
+/// Items are kept behind `Arc`s, in chunks of 64 that are themselves shared,
+/// so cloning a chain copies one pointer per chunk instead of every block:
+/// snapshots for reorg trials, read-only views and simulations are cheap. A
+/// copy that grows only copies the pointers of its last chunk, and the items
+/// are never copied unless one is changed in place (see `Tamper`).
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use blockc::blockchain::blockchain::Blockchain;
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// chain.add_block(Vec::new(), "Miner").unwrap();
+///
+/// // The copy shares the blocks it has in common with the chain
+/// let mut trial = chain.clone();
+/// trial.add_block(Vec::new(), "Miner").unwrap();
+/// let (blocks, trial_blocks) = (chain.get_blocks(), trial.get_blocks());
+/// assert!(Arc::ptr_eq(&blocks.get_shared(1).unwrap(), &trial_blocks.get_shared(1).unwrap()));
+/// assert_eq!((blocks.len(), trial_blocks.len()), (2, 3));
+///
+/// // Read like a slice
+/// assert_eq!(blocks[0].previous_block_hash, "0");
+/// assert_eq!(blocks.last().unwrap().block_hash, chain.get_latest_hash());
+/// assert_eq!(trial_blocks.iter().rev().nth(1), Some(&blocks[1]));
+/// ```
+pub struct SharedVec<T> {
+    chunks: Vec<Arc<Vec<Arc<T>>>>, // Full chunks, then the last one
+    len: usize,
+}
+
+// Derives would require `T: Clone` / `T: Default`, which sharing doesn't need
+impl<T> Clone for SharedVec<T> {
+    fn clone(&self) -> Self {
+        SharedVec {
+            chunks: self.chunks.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<T> Default for SharedVec<T> {
+    fn default() -> Self {
+        SharedVec {
+            chunks: Vec::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<T> SharedVec<T> {
+    /// Creates an empty list.
+    pub fn new() -> Self {
+        SharedVec::default()
+    }
+
+    /// Number of items.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether there are no items.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the item at `height`, if there is one.
+    pub fn get(&self, height: usize) -> Option<&T> {
+        self.get_shared_ref(height).map(|block| &**block)
+    }
+
+    /// Returns a handle to the item at `height` that can outlive the chain.
+    pub fn get_shared(&self, height: usize) -> Option<Arc<T>> {
+        self.get_shared_ref(height).cloned()
+    }
+
+    /// Returns the first item (the genesis block's).
+    pub fn first(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Returns the last item (the tip's).
+    pub fn last(&self) -> Option<&T> {
+        self.len.checked_sub(1).and_then(|height| self.get(height))
+    }
+
+    /// Iterates over the items, genesis first.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            list: self,
+            front: 0,
+            back: self.len,
+        }
+    }
+
+    /// Copies the items into a vector (e.g. blocks to rebuild a chain with `Blockchain::from_blocks`).
+    pub fn to_vec(&self) -> Vec<T> where T: Clone {
+        self.iter().cloned().collect()
+    }
+
+    /// Appends `item`.
+    pub fn push(&mut self, item: T) {
+        if self.len.is_multiple_of(CHUNK_SIZE) {
+            self.chunks.push(Arc::new(Vec::with_capacity(CHUNK_SIZE)));
+        }
+        // Copies the last chunk's pointers if another list shares it
+        let chunk = self.chunks.last_mut().unwrap();
+        Arc::make_mut(chunk).push(Arc::new(item));
+        self.len += 1;
+    }
+
+    /// Removes the items above `len`, keeping the first `len` ones.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        self.chunks.truncate(len.div_ceil(CHUNK_SIZE));
+        if !len.is_multiple_of(CHUNK_SIZE) {
+            let chunk = self.chunks.last_mut().unwrap();
+            Arc::make_mut(chunk).truncate(len % CHUNK_SIZE);
+        }
+        self.len = len;
+    }
+
+    /// Returns the item at `height` for changing it in place.
+    ///
+    /// The item (and the pointers of its chunk) is copied first if another
+    /// list shares it, so other copies of the chain are left untouched.
+    pub fn get_mut(&mut self, height: usize) -> Option<&mut T> where T: Clone {
+        if height >= self.len {
+            return None;
+        }
+        let chunk = Arc::make_mut(&mut self.chunks[height / CHUNK_SIZE]);
+        Some(Arc::make_mut(&mut chunk[height % CHUNK_SIZE]))
+    }
+
+    fn get_shared_ref(&self, height: usize) -> Option<&Arc<T>> {
+        if height >= self.len {
+            return None;
+        }
+        Some(&self.chunks[height / CHUNK_SIZE][height % CHUNK_SIZE])
+    }
+}
+
+impl<T> Index<usize> for SharedVec<T> {
+    type Output = T;
+
+    fn index(&self, height: usize) -> &T {
+        self.get(height).unwrap_or_else(|| panic!("Height {} is out of range ({} items)", height, self.len))
+    }
+}
+
+impl<T: PartialEq> PartialEq for SharedVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SharedVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> From<Vec<T>> for SharedVec<T> {
+    fn from(items: Vec<T>) -> Self {
+        items.into_iter().collect()
+    }
+}
+
+impl<T> FromIterator<T> for SharedVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(items: I) -> Self {
+        let mut list = SharedVec::new();
+        for item in items {
+            list.push(item);
+        }
+        list
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SharedVec<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+/// Iterator over the items of a `SharedVec` (see `SharedVec::iter`).
+pub struct Iter<'a, T> {
+    list: &'a SharedVec<T>,
+    front: usize,
+    back: usize,
+}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Iter {
+            list: self.list,
+            front: self.front,
+            back: self.back,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        self.list.get(self.front - 1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<&'a T> {
+        self.front = self.front.saturating_add(n).min(self.back);
+        self.next()
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        self.list.get(self.back)
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
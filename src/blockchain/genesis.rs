@@ -0,0 +1,223 @@
+use std::collections::HashSet;
+#[cfg(feature = "fs")]
+use std::fs;
+#[cfg(feature = "fs")]
+use std::path::Path;
+use crate::block::block::{Block, HashAlgorithm};
+use crate::block::bloom::FilterParams;
+use crate::blockchain::blockchain::{Blockchain, DEFAULT_BLOCK_REWARD};
+use crate::codec::json::{self, field, JsonValue};
+use crate::state::state::ChainState;
+use crate::transactions::transactions::{Transaction, COINBASE_ADDRESS, MAX_MEMO_BYTES, TransactionExt};
+
+/// Address the genesis message is "paid" to (0 coins), so it is part of the block.
+pub const GENESIS_MESSAGE_ADDRESS: &str = "GENESIS";
+
+/// Hardest difficulty a genesis file may set (a block hash has 64 hex digits).
+pub const MAX_GENESIS_DIFFICULTY: u32 = 64;
+
+/// Coins an address owns from the genesis block.
+///
+/// # Fields
+///
+/// * `address` - Owner of the coins
+/// * `amount` - Coins allocated
+#[derive(Debug, Clone, PartialEq)]
+pub struct Allocation {
+    pub address: String,
+    pub amount: f64,
+}
+
+/// Definition of a network's genesis block.
+///
+/// The same definition always gives the same genesis block (and hash), so
+/// every node started from the file joins the same network. The message and the
+/// allocations become coinbase transactions of the genesis block.
+///
+/// Files are JSON or, with the `config` feature, TOML, with these keys:
+/// `timestamp` (required), `difficulty` (required), `message`, `block_reward`,
+/// `allocations` (a list of `{ address, amount }`), `filter_bits` and
+/// `filter_hashes` (the size of the per-block address filters), and
+/// `hash_algorithm` (`"sha256"`, the default, `"double_sha256"` or `"keccak256"`).
+///
+/// # Fields
+///
+/// * `timestamp` - Unix time of the genesis block
+/// * `message` - Text recorded in the genesis block (at most `MAX_MEMO_BYTES`)
+/// * `difficulty` - Proof-of-work difficulty of the network
+/// * `block_reward` - Coins minted by each block
+/// * `allocations` - Coins owned from the start
+/// * `filter_params` - Size of the address filters kept for each block
+/// * `hash_algorithm` - Hash function of the block headers
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::genesis::GenesisConfig;
+///
+/// let genesis = GenesisConfig::from_json(r#"{
+///     "timestamp": 1700000000,
+///     "message": "testnet 1",
+///     "difficulty": 1,
+///     "allocations": [{ "address": "Alice", "amount": 1000 }]
+/// }"#).unwrap();
+///
+/// let chain = genesis.build().unwrap();
+/// assert_eq!(chain.get_balance("Alice"), 1000.0);
+/// assert_eq!(chain.get_blocks()[0].time_stamp, 1_700_000_000);
+/// assert_eq!(chain.filter_params().bits, 2048);
+///
+/// // Same file, same network
+/// assert_eq!(genesis.block().block_hash, genesis.build().unwrap().get_latest_hash());
+///
+/// assert!(GenesisConfig::from_json(r#"{ "timestamp": 1, "difficulty": 1, "allocations": [{ "address": "A", "amount": -5 }] }"#).is_err());
+/// assert!(GenesisConfig::from_json(r#"{ "timestamp": 1, "difficulty": 1, "hash_algorithm": "md5" }"#).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenesisConfig {
+    pub timestamp: u32,
+    pub message: String,
+    pub difficulty: u32,
+    pub block_reward: f64,
+    pub allocations: Vec<Allocation>,
+    pub filter_params: FilterParams,
+    pub hash_algorithm: HashAlgorithm,
+}
+
+impl GenesisConfig {
+    /// Reads a genesis file: TOML if its extension is `.toml`, JSON otherwise.
+    #[cfg(feature = "fs")]
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|error| format!("Cannot read {}: {}", path.display(), error))?;
+        let genesis = if path.extension().is_some_and(|extension| extension == "toml") {
+            GenesisConfig::from_toml(&text)
+        } else {
+            GenesisConfig::from_json(&text)
+        };
+        genesis.map_err(|error| format!("{}: {}", path.display(), error))
+    }
+
+    /// Parses and validates a JSON definition.
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        GenesisConfig::from_value(&json::parse(text)?)
+    }
+
+    /// Parses and validates a TOML definition.
+    #[cfg(feature = "config")]
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        let table: toml::Table = text.parse().map_err(|error: toml::de::Error| error.message().to_string())?;
+        GenesisConfig::from_value(&toml_to_json(&toml::Value::Table(table))?)
+    }
+
+    /// TOML needs the `config` feature.
+    #[cfg(not(feature = "config"))]
+    pub fn from_toml(_text: &str) -> Result<Self, String> {
+        Err("TOML genesis files need the `config` feature".to_string())
+    }
+
+    fn from_value(value: &JsonValue) -> Result<Self, String> {
+        let members = value.as_object()?;
+        let known = ["timestamp", "message", "difficulty", "block_reward", "allocations", "filter_bits", "filter_hashes", "hash_algorithm"];
+        if let Some((key, _)) = members.iter().find(|(key, _)| !known.contains(&key.as_str())) {
+            return Err(format!("Unknown key \"{}\"", key));
+        }
+        let optional = |name: &str| members.iter().any(|(key, _)| key == name);
+
+        let genesis = GenesisConfig {
+            timestamp: field(members, "timestamp")?,
+            message: if optional("message") { field(members, "message")? } else { String::new() },
+            difficulty: field(members, "difficulty")?,
+            block_reward: if optional("block_reward") { field(members, "block_reward")? } else { DEFAULT_BLOCK_REWARD },
+            allocations: if optional("allocations") { field(members, "allocations")? } else { Vec::new() },
+            filter_params: FilterParams {
+                bits: if optional("filter_bits") { field(members, "filter_bits")? } else { FilterParams::default().bits },
+                hashes: if optional("filter_hashes") { field(members, "filter_hashes")? } else { FilterParams::default().hashes },
+            },
+            hash_algorithm: if optional("hash_algorithm") { HashAlgorithm::parse(&field::<String>(members, "hash_algorithm")?)? } else { HashAlgorithm::default() },
+        };
+        genesis.validate()?;
+        Ok(genesis)
+    }
+
+    /// Checks the definition can make a valid genesis block.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.difficulty > MAX_GENESIS_DIFFICULTY {
+            return Err(format!("difficulty must be at most {}", MAX_GENESIS_DIFFICULTY));
+        }
+        if !self.block_reward.is_finite() || self.block_reward < 0.0 {
+            return Err("block_reward must be a non-negative number".to_string());
+        }
+        self.filter_params.validate()?;
+        if self.message.len() > MAX_MEMO_BYTES {
+            return Err(format!("message is longer than {} bytes", MAX_MEMO_BYTES));
+        }
+        let mut seen = HashSet::new();
+        for allocation in &self.allocations {
+            if allocation.address.is_empty() || allocation.address == COINBASE_ADDRESS || allocation.address == GENESIS_MESSAGE_ADDRESS {
+                return Err(format!("\"{}\" cannot receive an allocation", allocation.address));
+            }
+            if !allocation.amount.is_finite() || allocation.amount <= 0.0 {
+                return Err(format!("Allocation to {} must be a positive number", allocation.address));
+            }
+            if !seen.insert(&allocation.address) {
+                return Err(format!("{} is allocated twice", allocation.address));
+            }
+        }
+        Ok(())
+    }
+
+    /// Builds the genesis block: the message first, then one coinbase per
+    /// allocation, stamped with `timestamp` and committing to the resulting state.
+    pub fn block(&self) -> Block {
+        let mut message = Transaction::coinbase(GENESIS_MESSAGE_ADDRESS.to_string(), 0.0);
+        message.timestamp = self.timestamp;
+        message.memo = (!self.message.is_empty()).then(|| self.message.clone());
+        let mut transactions = vec![message];
+        for (index, allocation) in self.allocations.iter().enumerate() {
+            let mut coinbase = Transaction::coinbase(allocation.address.clone(), allocation.amount);
+            coinbase.timestamp = self.timestamp;
+            coinbase.nonce = index as u64 + 1;
+            transactions.push(coinbase);
+        }
+
+        let mut state = ChainState::new();
+        for transaction in &transactions {
+            // Validated allocations are positive coinbases, which always apply
+            let _ = state.apply_transaction(transaction);
+        }
+        let mut block = Block::new_at(transactions, "0".to_string(), 0.0, state.state_root(), self.timestamp);
+        block.block_hash = block.calculate_hash_with(self.hash_algorithm);
+        block
+    }
+
+    /// Starts a chain from this genesis.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Blockchain)` - A chain holding only the genesis block
+    /// * `Err(String)` - If the definition is invalid
+    pub fn build(&self) -> Result<Blockchain, String> {
+        self.validate()?;
+        let mut chain = Blockchain::from_blocks_with(self.difficulty, self.block_reward, self.hash_algorithm, vec![self.block()])?;
+        chain.set_filter_params(self.filter_params)?;
+        Ok(chain)
+    }
+}
+
+/// Converts a TOML value to the JSON the decoder reads (dates are rejected).
+#[cfg(feature = "config")]
+fn toml_to_json(value: &toml::Value) -> Result<JsonValue, String> {
+    Ok(match value {
+        toml::Value::String(text) => JsonValue::String(text.clone()),
+        toml::Value::Integer(number) => JsonValue::Number(number.to_string()),
+        toml::Value::Float(number) => crate::codec::json::JsonCodec::to_json(number),
+        toml::Value::Boolean(flag) => JsonValue::Bool(*flag),
+        toml::Value::Datetime(_) => return Err("Dates are not supported, use a Unix timestamp".to_string()),
+        toml::Value::Array(items) => JsonValue::Array(items.iter().map(toml_to_json).collect::<Result<_, _>>()?),
+        toml::Value::Table(table) => JsonValue::Object(
+            table.iter()
+                .map(|(key, value)| Ok((key.clone(), toml_to_json(value)?)))
+                .collect::<Result<_, String>>()?,
+        ),
+    })
+}
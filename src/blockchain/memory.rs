@@ -0,0 +1,72 @@
+use std::mem::size_of;
+use crate::block::block::Block;
+use crate::codec::wire;
+use crate::transactions::transactions::Transaction;
+
+/// Approximate bytes `transaction` takes in memory: the struct itself plus its
+/// variable-size fields (addresses, signature, memo, outputs...), measured by
+/// their wire encoding.
+pub fn transaction_bytes(transaction: &Transaction) -> usize {
+    size_of::<Transaction>() + wire::encode(transaction).len()
+}
+
+/// Approximate bytes `block` takes in memory, its transactions included.
+pub fn block_bytes(block: &Block) -> usize {
+    size_of::<Block>() + block.transaction.len() * size_of::<Transaction>() + wire::encode(block).len()
+}
+
+/// Approximate memory used by the parts of a node, in bytes.
+///
+/// # Fields
+///
+/// * `blocks` - Blocks of the chain
+/// * `filters` - Address filters of the blocks
+/// * `state_cache` - Balance and nonce cache with its undo records
+/// * `mempool` - Pending transactions
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::entity::entity::Entity;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// chain.add_block(Vec::new(), "Alice").unwrap();
+/// let before = chain.memory_usage();
+///
+/// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+/// chain.submit_transaction(Transaction::create_payment(&mut alice, "Bob", 1.0, 0.5).unwrap()).unwrap();
+/// let after = chain.memory_usage();
+/// assert!(after.mempool > before.mempool);
+/// assert_eq!(after.total(), after.blocks + after.filters + after.state_cache + after.mempool);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MemoryUsage {
+    pub blocks: usize,
+    pub filters: usize,
+    pub state_cache: usize,
+    pub mempool: usize,
+}
+
+impl MemoryUsage {
+    /// Returns the sum of every part.
+    pub fn total(&self) -> usize {
+        self.blocks + self.filters + self.state_cache + self.mempool
+    }
+}
+
+/// Ceilings on the memory of the parts of a node that can give some back, in
+/// bytes (`None` for no limit).
+///
+/// Blocks and their filters are always kept: the chain needs them.
+///
+/// * `mempool` - Past it, new transactions evict the lowest-fee pending ones (see
+///   `Mempool::set_max_bytes`)
+/// * `state_cache` - Past it, the oldest undo records of the state cache are
+///   dropped (see `StateCache::set_max_bytes`)
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MemoryLimits {
+    pub mempool: Option<usize>,
+    pub state_cache: Option<usize>,
+}
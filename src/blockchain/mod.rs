@@ -1 +1,9 @@
-pub mod blockchain;
\ No newline at end of file
+pub mod blockchain;
+pub mod blocks;
+pub mod genesis;
+pub mod memory;
+pub mod shared;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod verifier;
+#[cfg(feature = "tamper")]
+pub mod tamper;
\ No newline at end of file
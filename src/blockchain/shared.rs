@@ -0,0 +1,154 @@
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use crate::block::block::Block;
+use crate::blockchain::blockchain::Blockchain;
+use crate::mining::template::BlockTemplate;
+use crate::transactions::transactions::Transaction;
+
+/// A `Blockchain` shared between threads (RPC server, miner, network layer, ...).
+///
+/// Clones are handles to the same chain. Any number of readers can look at it
+/// at once; changes take the lock alone, one at a time. Every method takes the
+/// lock for as short as possible: `mine` builds the template under the read
+/// lock, searches the nonce without holding any lock and only takes the write
+/// lock to connect the block.
+///
+/// A thread that panics while changing the chain does not lock the others out:
+/// the chain only commits a block or a transaction once every check passed, so
+/// it is used as is.
+///
+/// # Example
+///
+/// ```
+/// use std::thread;
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::blockchain::shared::SharedBlockchain;
+///
+/// let chain = SharedBlockchain::new(Blockchain::with_settings(1, 50.0));
+///
+/// let miner = {
+///     let chain = chain.clone();
+///     thread::spawn(move || {
+///         for _ in 0..3 {
+///             chain.mine("Miner").unwrap();
+///         }
+///     })
+/// };
+/// let reader = {
+///     let chain = chain.clone();
+///     thread::spawn(move || {
+///         for _ in 0..100 {
+///             assert!(chain.height() <= 3);
+///         }
+///     })
+/// };
+/// miner.join().unwrap();
+/// reader.join().unwrap();
+///
+/// assert_eq!(chain.height(), 3);
+/// assert_eq!(chain.read(|chain| chain.get_balance("Miner")), 135.0);
+/// assert!(chain.read(|chain| chain.is_valid()));
+/// ```
+#[derive(Clone, Default)]
+pub struct SharedBlockchain {
+    chain: Arc<RwLock<Blockchain>>,
+}
+
+impl SharedBlockchain {
+    /// Shares `chain`.
+    pub fn new(chain: Blockchain) -> Self {
+        SharedBlockchain {
+            chain: Arc::new(RwLock::new(chain)),
+        }
+    }
+
+    /// Locks the chain for reading until the guard is dropped.
+    ///
+    /// Prefer `read` for short reads; hold the guard only to read several
+    /// things from the same state.
+    pub fn lock_read(&self) -> RwLockReadGuard<'_, Blockchain> {
+        self.chain.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Locks the chain for writing until the guard is dropped.
+    pub fn lock_write(&self) -> RwLockWriteGuard<'_, Blockchain> {
+        self.chain.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Runs `read` on the chain under the read lock.
+    pub fn read<R>(&self, read: impl FnOnce(&Blockchain) -> R) -> R {
+        read(&self.lock_read())
+    }
+
+    /// Runs `write` on the chain under the write lock.
+    pub fn write<R>(&self, write: impl FnOnce(&mut Blockchain) -> R) -> R {
+        write(&mut self.lock_write())
+    }
+
+    /// Returns a copy of the chain as it is now.
+    pub fn snapshot(&self) -> Blockchain {
+        self.lock_read().clone()
+    }
+
+    /// Height of the tip (the genesis block is 0).
+    pub fn height(&self) -> usize {
+        self.lock_read().get_blocks().len() - 1
+    }
+
+    /// Hash of the tip.
+    pub fn latest_hash(&self) -> String {
+        self.lock_read().get_latest_hash()
+    }
+
+    /// Balance of `address`.
+    pub fn get_balance(&self, address: &str) -> f64 {
+        self.lock_read().get_balance(address)
+    }
+
+    /// Next nonce expected from `address`.
+    pub fn get_nonce(&self, address: &str) -> u64 {
+        self.lock_read().get_nonce(address)
+    }
+
+    /// Returns the block at `height`, if there is one.
+    pub fn get_block(&self, height: usize) -> Option<Block> {
+        self.lock_read().get_blocks().get(height).cloned()
+    }
+
+    /// Adds a transaction to the mempool (see `Blockchain::submit_transaction`).
+    pub fn submit_transaction(&self, transaction: Transaction) -> Result<(), String> {
+        self.lock_write().submit_transaction(transaction)
+    }
+
+    /// Connects a mined block (see `Blockchain::submit_block`).
+    pub fn submit_block(&self, block: Block) -> Result<(), String> {
+        self.lock_write().submit_block(block)
+    }
+
+    /// Builds a block template from the mempool (see `Blockchain::get_block_template`).
+    pub fn get_block_template(&self, miner_address: &str) -> Result<BlockTemplate, String> {
+        self.lock_read().get_block_template(miner_address)
+    }
+
+    /// Mines a block from the mempool for `miner_address` and connects it.
+    ///
+    /// The proof of work runs without holding the lock, so readers and other
+    /// writers are not blocked meanwhile. If another block was connected in
+    /// the meantime, the work is stale and mining starts again from the new tip.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Block)` - The block connected
+    /// * `Err(String)` - If no template can be built or the block is rejected
+    pub fn mine(&self, miner_address: &str) -> Result<Block, String> {
+        loop {
+            let template = self.get_block_template(miner_address)?;
+            let block = template.mine();
+            let mut chain = self.lock_write();
+            if block.previous_block_hash != chain.get_latest_hash() {
+                continue;
+            }
+            chain.submit_block(block.clone())?;
+            return Ok(block);
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use crate::block::block::{Block, BlockExt, HashAlgorithm};
+use crate::blockchain::blocks::BlockList;
+
+/// Sanctioned way to damage a chain, to show how validation catches it.
+///
+/// Only built with the `tamper` feature. Every change is made in place without
+/// fixing anything else, so `Blockchain::validate` reports the block that was
+/// touched; `reseal` then recomputes that block's Merkle root and proof of work
+/// to show the damage just moves to the next block's hash link.
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// for _ in 0..3 {
+///     chain.add_block(Vec::new(), "Miner").unwrap();
+/// }
+///
+/// // Pay the miner of block 1 more: the block's data no longer matches its hash
+/// chain.tamper().transaction_amount(1, 0, 1_000.0).unwrap();
+/// assert_eq!(chain.first_invalid_block(), Some(1));
+/// assert!(chain.validate().unwrap_err().starts_with("Block 1:"));
+///
+/// // Re-mining the forged block moves the damage to the next link
+/// chain.tamper().reseal(1).unwrap();
+/// assert_eq!(chain.first_invalid_block(), Some(2));
+/// assert!(chain.validate().unwrap_err().contains("previous hash"));
+/// ```
+pub struct Tamper<'a> {
+    chain: &'a mut BlockList,
+    difficulty: u32,
+    hash_algorithm: HashAlgorithm,
+}
+
+impl<'a> Tamper<'a> {
+    pub(crate) fn new(chain: &'a mut BlockList, difficulty: u32, hash_algorithm: HashAlgorithm) -> Self {
+        Tamper {
+            chain: chain,
+            difficulty: difficulty,
+            hash_algorithm: hash_algorithm,
+        }
+    }
+
+    /// Changes the amount of transaction `index` in block `height`.
+    pub fn transaction_amount(&mut self, height: usize, index: usize, amount: f64) -> Result<(), String> {
+        let block = self.block(height)?;
+        let transaction = block.transaction.get_mut(index)
+            .ok_or(format!("Block {} has no transaction {}", height, index))?;
+        transaction.amount = amount;
+        Ok(())
+    }
+
+    /// Changes the timestamp of block `height`.
+    pub fn timestamp(&mut self, height: usize, time_stamp: u32) -> Result<(), String> {
+        self.block(height)?.time_stamp = time_stamp;
+        Ok(())
+    }
+
+    /// Points block `height` to another previous block hash (breaks the hash link).
+    pub fn previous_hash(&mut self, height: usize, previous_block_hash: &str) -> Result<(), String> {
+        self.block(height)?.previous_block_hash = previous_block_hash.to_string();
+        Ok(())
+    }
+
+    /// Recomputes the Merkle root of block `height` and mines it again, like a
+    /// forger covering their tracks in that block only.
+    pub fn reseal(&mut self, height: usize) -> Result<(), String> {
+        let (difficulty, hash_algorithm) = (self.difficulty, self.hash_algorithm);
+        let block = self.block(height)?;
+        block.merkle_root = block.calculate_merkle_root();
+        block.nonce = 0;
+        block.mine_with(difficulty, hash_algorithm);
+        Ok(())
+    }
+
+    fn block(&mut self, height: usize) -> Result<&mut Block, String> {
+        self.chain.get_mut(height).ok_or(format!("The chain has no block at height {}", height))
+    }
+}
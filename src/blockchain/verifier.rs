@@ -0,0 +1,138 @@
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread::{self, JoinHandle};
+use crate::block::block::Block;
+use crate::blockchain::shared::SharedBlockchain;
+
+/// Error of a block the verifier stopped before verifying.
+const STOPPED: &str = "The verifier stopped before verifying the block";
+
+/// A block waiting for a `Verifier` and the channel its outcome goes back on.
+type Job = (Block, Sender<Result<(), String>>);
+
+/// Outcome of a block submitted to a `Verifier`, to wait for or poll.
+#[derive(Debug)]
+pub struct VerificationHandle {
+    block_hash: String,
+    receiver: Receiver<Result<(), String>>,
+    result: Option<Result<(), String>>,
+}
+
+impl VerificationHandle {
+    /// Returns the hash of the block being verified.
+    pub fn block_hash(&self) -> &str {
+        &self.block_hash
+    }
+
+    /// Returns the outcome if the block was verified (`None` while it is queued or
+    /// being verified), without blocking.
+    pub fn poll(&mut self) -> Option<&Result<(), String>> {
+        if self.result.is_none() {
+            self.result = match self.receiver.try_recv() {
+                Ok(result) => Some(result),
+                Err(TryRecvError::Empty) => None,
+                Err(TryRecvError::Disconnected) => Some(Err(STOPPED.to_string())),
+            };
+        }
+        self.result.as_ref()
+    }
+
+    /// Blocks until the block is verified.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The block was connected
+    /// * `Err(String)` - Why it was rejected (see `Blockchain::submit_block`)
+    pub fn wait(mut self) -> Result<(), String> {
+        match self.result.take() {
+            Some(result) => result,
+            None => self.receiver.recv().unwrap_or(Err(STOPPED.to_string())),
+        }
+    }
+}
+
+/// Verifies blocks on a background thread and connects the valid ones, so an
+/// RPC server or a dashboard sharing the chain stays responsive while large
+/// blocks are checked.
+///
+/// Blocks are verified one at a time, in the order they were submitted, on a
+/// copy of the chain (`Blockchain::verify_block`); the chain's write lock is
+/// only taken to connect a block that passed. A block whose parent is no longer
+/// the tip by then is rejected like in `Blockchain::submit_block`; one verified
+/// before the operator changed the local rules or the treasury is verified again
+/// under the lock.
+///
+/// Dropping the verifier finishes the blocks already submitted, then stops the
+/// thread.
+///
+/// `wasm32-unknown-unknown` cannot spawn threads: the module is left out of
+/// wasm builds.
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::blockchain::shared::SharedBlockchain;
+/// use blockc::blockchain::verifier::Verifier;
+///
+/// let chain = SharedBlockchain::new(Blockchain::with_settings(1, 50.0));
+/// let verifier = Verifier::spawn(chain.clone());
+///
+/// let block = chain.get_block_template("Miner").unwrap().mine();
+/// let handle = verifier.submit(block.clone());
+/// assert_eq!(handle.block_hash(), block.block_hash);
+///
+/// // The same block again no longer builds on the tip
+/// let again = verifier.submit(block);
+/// assert_eq!(handle.wait(), Ok(()));
+/// assert!(again.wait().is_err());
+/// assert_eq!(chain.height(), 1);
+/// ```
+#[derive(Debug)]
+pub struct Verifier {
+    sender: Option<Sender<Job>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl Verifier {
+    /// Starts the thread verifying blocks for `chain`.
+    pub fn spawn(chain: SharedBlockchain) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let worker = thread::spawn(move || {
+            for (block, reply) in receiver {
+                let result = chain.snapshot().verify_block(block)
+                    .and_then(|verified| chain.write(|chain| chain.connect_block(verified)));
+                // The handle may have been dropped: nobody is waiting then
+                let _ = reply.send(result);
+            }
+        });
+        Verifier {
+            sender: Some(sender),
+            worker: Some(worker),
+        }
+    }
+
+    /// Queues `block` for verification and returns at once.
+    pub fn submit(&self, block: Block) -> VerificationHandle {
+        let (reply, receiver) = mpsc::channel();
+        let block_hash = block.block_hash.clone();
+        // If the worker is gone, the reply sender is dropped and the handle says so
+        if let Some(sender) = &self.sender {
+            let _ = sender.send((block, reply));
+        }
+        VerificationHandle {
+            block_hash: block_hash,
+            receiver: receiver,
+            result: None,
+        }
+    }
+}
+
+impl Drop for Verifier {
+    fn drop(&mut self) {
+        // Closing the channel ends the worker's loop once the queue is empty
+        self.sender.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::BTreeSet;
+use crate::assets::asset::MAX_TICKER_LENGTH;
+use crate::block::block::BlockHeader;
+
+// Proofs are carried by mint and release transactions and live in `blockc_core`.
+pub use blockc_core::payload::BridgeProof;
+
+/// Headers a block needs (itself included) before the bridge accepts proofs from it.
+pub const BRIDGE_CONFIRMATIONS: u64 = 2;
+
+/// Address holding what is locked for (or issued by) the bridge to `remote_chain`.
+pub fn escrow_address(remote_chain: &str) -> String {
+    format!("bridge:{}", remote_chain)
+}
+
+/// Id of the wrapped asset minted for `asset_id` of `remote_chain`.
+pub fn wrapped_asset_id(remote_chain: &str, asset_id: &str) -> String {
+    format!("{}:{}", remote_chain, asset_id)
+}
+
+/// Ticker of the wrapped version of `ticker` ("GOLD" becomes "WGOLD").
+pub fn wrapped_ticker(ticker: &str) -> String {
+    format!("W{}", ticker).chars().take(MAX_TICKER_LENGTH).collect()
+}
+
+/// One side of a lock-and-mint bridge: a light client of the other chain.
+///
+/// Relayers copy the other chain's block headers onto this one; the link checks
+/// that they extend the headers it already has and meet the other chain's
+/// proof of work. A transaction of the other chain is then proven with a Merkle
+/// proof against one of those headers, once it has `BRIDGE_CONFIRMATIONS`.
+///
+/// Moving an asset from chain A to chain B and back:
+/// 1. On A, `BridgeLock` moves the units to the bridge's escrow address
+/// 2. On B, after relaying A's headers, `BridgeMint` proves the lock and mints
+///    the same number of units of a wrapped asset (see `wrapped_asset_id`)
+/// 3. On B, `BridgeBurn` destroys wrapped units
+/// 4. On A, after relaying B's headers, `BridgeRelease` proves the burn and pays
+///    the units out of escrow
+///
+/// Each lock and burn can be claimed once. This is a simulation: the link
+/// follows a single line of headers and does not handle reorganisations.
+///
+/// # Fields
+///
+/// * `local_chain` - Name of this chain (locks and burns must be addressed to it)
+/// * `remote_chain` - Name of the other chain
+/// * `difficulty` - Proof-of-work difficulty of the other chain
+/// * `headers` - Headers of the other chain, genesis first
+/// * `processed` - Ids of the remote transactions already claimed
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::bridge::bridge::{wrapped_asset_id, BridgeProof};
+/// use blockc::entity::entity::Entity;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+///
+/// let mut chain_a = Blockchain::with_settings(1, 50.0);
+/// let mut chain_b = Blockchain::with_settings(1, 50.0);
+/// chain_a.open_bridge("A", "B", chain_b.get_blocks()[0].header(), 1).unwrap();
+/// chain_b.open_bridge("B", "A", chain_a.get_blocks()[0].header(), 1).unwrap();
+/// chain_a.add_block(Vec::new(), "Alice").unwrap();
+/// chain_b.add_block(Vec::new(), "Bob").unwrap();
+/// let (fee_a, fee_b) = (chain_a.get_base_fee() * 2.0, chain_b.get_base_fee() * 2.0);
+/// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+/// let mut bob = Entity::new("Bob".to_string(), 45.0, Vec::new(), "b".to_string(), "b".to_string());
+///
+/// // Alice locks 300 GOLD on A for Bob on B
+/// let issue = Transaction::create_asset(&mut alice, "GOLD", 0, 1_000, 1_000, fee_a).unwrap();
+/// let gold = issue.id();
+/// chain_a.add_block(vec![issue], "Miner").unwrap();
+/// let lock = Transaction::bridge_lock(&mut alice, chain_a.state(), "B", &gold, 300, "Bob", fee_a).unwrap();
+/// let lock_id = lock.id();
+/// chain_a.add_block(vec![lock], "Miner").unwrap();
+/// chain_a.add_block(Vec::new(), "Miner").unwrap(); // one confirmation on top
+///
+/// // Bob relays A's headers to B and mints the wrapped GOLD
+/// let proof = BridgeProof::new(chain_a.get_blocks(), &lock_id).unwrap();
+/// let relay = Transaction::relay_headers(&mut bob, "A", chain_a.get_headers(1), fee_b).unwrap();
+/// let mint = Transaction::bridge_mint(&mut bob, "A", proof.clone(), fee_b).unwrap();
+/// chain_b.add_block(vec![relay, mint], "Miner").unwrap();
+/// let wrapped = wrapped_asset_id("A", &gold);
+/// assert_eq!(chain_b.state().get_asset_balance("Bob", &wrapped), 300);
+/// assert_eq!(chain_b.state().get_asset(&wrapped).unwrap().ticker, "WGOLD");
+///
+/// // The same lock cannot be minted twice
+/// let replay = Transaction::bridge_mint(&mut bob.clone(), "A", proof, fee_b).unwrap();
+/// assert!(chain_b.add_block(vec![replay], "Miner").is_err());
+///
+/// // Bob sends 100 back to Alice: burn on B, release on A
+/// let burn = Transaction::bridge_burn(&mut bob, "A", &gold, 100, "Alice", fee_b).unwrap();
+/// let burn_id = burn.id();
+/// chain_b.add_block(vec![burn], "Miner").unwrap();
+/// chain_b.add_block(Vec::new(), "Miner").unwrap();
+///
+/// let proof = BridgeProof::new(chain_b.get_blocks(), &burn_id).unwrap();
+/// let relay = Transaction::relay_headers(&mut alice, "B", chain_b.get_headers(1), fee_a).unwrap();
+/// let release = Transaction::bridge_release(&mut alice, "B", proof, fee_a).unwrap();
+/// chain_a.add_block(vec![relay, release], "Miner").unwrap();
+/// assert_eq!(chain_a.state().get_asset_balance("Alice", &gold), 800);
+/// assert_eq!(chain_b.state().get_asset(&wrapped).unwrap().supply, 200);
+///
+/// // The bridges are replayed with the blocks that use them
+/// assert!(chain_a.reindex().unwrap().stale.is_empty());
+/// assert!(chain_b.reindex().unwrap().stale.is_empty());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BridgeLink {
+    pub local_chain: String,
+    pub remote_chain: String,
+    pub difficulty: u32,
+    pub headers: Vec<BlockHeader>,
+    pub processed: BTreeSet<String>,
+}
+
+impl BridgeLink {
+    /// Creates a link following `remote_chain` from its (trusted) genesis header.
+    pub fn new(local_chain: &str, remote_chain: &str, remote_genesis: BlockHeader, difficulty: u32) -> Self {
+        BridgeLink {
+            local_chain: local_chain.to_string(),
+            remote_chain: remote_chain.to_string(),
+            difficulty: difficulty,
+            headers: vec![remote_genesis],
+            processed: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the latest header of the remote chain known to the link.
+    pub fn tip(&self) -> &BlockHeader {
+        &self.headers[self.headers.len() - 1]
+    }
+
+    /// Checks that `headers` extend the known headers, in order, each with a
+    /// correct hash that meets the remote difficulty.
+    ///
+    /// Headers the link already has are skipped, so relayers can resend overlapping ranges.
+    pub fn check_headers(&self, headers: &[BlockHeader]) -> Result<(), String> {
+        let mut tip = self.tip().block_hash.clone();
+        for header in self.new_headers(headers) {
+            if header.previous_block_hash != tip {
+                return Err(format!("Header {} does not extend the {} headers", header.block_hash, self.remote_chain));
+            }
+            if !header.is_valid(self.difficulty) {
+                return Err(format!("Header {} has an invalid hash or proof of work", header.block_hash));
+            }
+            tip = header.block_hash.clone();
+        }
+        Ok(())
+    }
+
+    /// Appends the new headers among `headers`.
+    pub fn add_headers(&mut self, headers: &[BlockHeader]) -> Result<(), String> {
+        self.check_headers(headers)?;
+        let new: Vec<BlockHeader> = self.new_headers(headers).cloned().collect();
+        self.headers.extend(new);
+        Ok(())
+    }
+
+    /// Returns how many headers (itself included) the block `block_hash` has, or `None` if unknown.
+    pub fn confirmations(&self, block_hash: &str) -> Option<u64> {
+        let index = self.headers.iter().position(|header| header.block_hash == block_hash)?;
+        Some((self.headers.len() - index) as u64)
+    }
+
+    /// Checks that `proof` proves a confirmed, not yet claimed transaction of the remote chain.
+    pub fn check_proof(&self, proof: &BridgeProof) -> Result<(), String> {
+        let confirmations = self.confirmations(&proof.block_hash)
+            .ok_or(format!("Block {} of {} was not relayed", proof.block_hash, self.remote_chain))?;
+        if confirmations < BRIDGE_CONFIRMATIONS {
+            return Err(format!("Block {} has {} confirmations, needs {}", proof.block_hash, confirmations, BRIDGE_CONFIRMATIONS));
+        }
+        let header = self.headers.iter().find(|header| header.block_hash == proof.block_hash)
+            .ok_or(format!("Block {} of {} was not relayed", proof.block_hash, self.remote_chain))?;
+        let id = proof.transaction.id();
+        if proof.proof.leaf != id || !proof.proof.verify(&header.merkle_root) {
+            return Err(format!("Transaction {} is not proven to be in block {}", id, proof.block_hash));
+        }
+        if self.processed.contains(&id) {
+            return Err(format!("Transaction {} of {} was already claimed", id, self.remote_chain));
+        }
+        Ok(())
+    }
+
+    fn new_headers<'a>(&self, headers: &'a [BlockHeader]) -> impl Iterator<Item = &'a BlockHeader> {
+        let known: BTreeSet<String> = self.headers.iter().map(|header| header.block_hash.clone()).collect();
+        headers.iter().filter(move |header| !known.contains(&header.block_hash))
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::HashSet;
+use ciborium::value::{Integer, Value};
+use crate::codec::json::{JsonCodec, JsonValue, MAX_DEPTH};
+
+/// Largest CBOR document `from_cbor` accepts.
+pub const MAX_CBOR_BYTES: usize = 8 * 1024 * 1024;
+
+/// Encodes any type with a JSON encoding (blocks, transactions, headers,
+/// proofs, ...) as CBOR (RFC 8949), with the same structure: maps with the
+/// field names as text keys, an integer or a float for each number.
+///
+/// # Example
+///
+/// ```
+/// use blockc::codec::cbor::{from_cbor, to_cbor};
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::block::block::{Block, BlockExt};
+/// use blockc::transactions::transactions::Output;
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// chain.add_block(Vec::new(), "Miner").unwrap();
+/// let block = chain.get_latest_block();
+///
+/// let bytes = to_cbor(block);
+/// assert_eq!(&from_cbor::<Block>(&bytes).unwrap(), block);
+/// assert!(bytes.len() < block.to_bytes().len());
+///
+/// // {"address": "Bob", "amount": 1.5} from another CBOR library (1.5 as a half float)
+/// let mut bytes = vec![0xa2, 0x67];
+/// bytes.extend_from_slice(b"address");
+/// bytes.extend_from_slice(&[0x63, b'B', b'o', b'b', 0x66]);
+/// bytes.extend_from_slice(b"amount");
+/// bytes.extend_from_slice(&[0xf9, 0x3e, 0x00]);
+/// let output: Output = from_cbor(&bytes).unwrap();
+/// assert_eq!((output.address.as_str(), output.amount), ("Bob", 1.5));
+/// ```
+pub fn to_cbor<T: JsonCodec>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    // Writing to a vector never fails
+    ciborium::into_writer(&json_to_cbor(&value.to_json()), &mut bytes).expect("CBOR written to memory");
+    bytes
+}
+
+/// Decodes a value encoded by `to_cbor`, or by any CBOR library writing the
+/// same structure.
+///
+/// Malformed input gives an error, never a panic, and the value is only
+/// decoded, not validated.
+///
+/// # Returns
+///
+/// * `Ok(T)` - The decoded value
+/// * `Err(String)` - If the input is over `MAX_CBOR_BYTES`, is not one CBOR
+///   item, uses byte strings, tags, non-text keys or duplicate keys, is nested
+///   deeper than `MAX_DEPTH` or does not describe a `T`
+///
+/// # Example
+///
+/// ```
+/// use blockc::codec::cbor::{from_cbor, to_cbor};
+/// use blockc::entity::entity::Entity;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+///
+/// let mut alice = Entity::new("Alice".to_string(), 100.0, Vec::new(), "pub".to_string(), "priv".to_string());
+/// let tx = Transaction::create_payment_with_memo(&mut alice, "Bob", 10.0, 0.1, Some("Rent".to_string())).unwrap();
+///
+/// let bytes = to_cbor(&tx);
+/// assert_eq!(from_cbor::<Transaction>(&bytes).unwrap(), tx);
+///
+/// assert!(from_cbor::<Transaction>(&bytes[..bytes.len() - 1]).is_err());
+/// assert!(from_cbor::<Transaction>(&[bytes.as_slice(), &[0]].concat()).is_err());
+///
+/// # #[cfg(feature = "testing")] {
+/// use proptest::test_runner::TestRunner;
+/// use blockc::testing::arbitrary::any_kind_transaction;
+///
+/// // Every kind and lock time survives the trip
+/// TestRunner::default().run(&any_kind_transaction(), |tx| {
+///     assert_eq!(from_cbor::<Transaction>(&to_cbor(&tx)).unwrap(), tx);
+///     Ok(())
+/// }).unwrap();
+/// # }
+/// ```
+pub fn from_cbor<T: JsonCodec>(bytes: &[u8]) -> Result<T, String> {
+    if bytes.len() > MAX_CBOR_BYTES {
+        return Err(format!("CBOR document is over {} bytes", MAX_CBOR_BYTES));
+    }
+    let mut reader = bytes;
+    let value: Value = ciborium::de::from_reader_with_recursion_limit(&mut reader, MAX_DEPTH)
+        .map_err(|error| format!("Invalid CBOR: {}", error))?;
+    if !reader.is_empty() {
+        return Err(format!("{} unexpected bytes after the CBOR item", reader.len()));
+    }
+    T::from_json(&cbor_to_json(value)?)
+}
+
+/// Converts a JSON document to the CBOR value with the same structure.
+pub fn json_to_cbor(value: &JsonValue) -> Value {
+    match value {
+        JsonValue::Null => Value::Null,
+        JsonValue::Bool(value) => Value::Bool(*value),
+        JsonValue::Number(text) => number_to_cbor(text),
+        JsonValue::String(text) => Value::Text(text.clone()),
+        JsonValue::Array(items) => Value::Array(items.iter().map(json_to_cbor).collect()),
+        JsonValue::Object(members) => {
+            Value::Map(members.iter().map(|(key, value)| (Value::Text(key.clone()), json_to_cbor(value))).collect())
+        }
+    }
+}
+
+// Integers stay integers (up to CBOR's 64-bit range), everything else is a float
+fn number_to_cbor(text: &str) -> Value {
+    if let Some(integer) = text.parse::<i128>().ok().and_then(|number| Integer::try_from(number).ok()) {
+        return Value::Integer(integer);
+    }
+    Value::Float(text.parse().unwrap_or(f64::NAN))
+}
+
+/// Converts a CBOR value to the JSON document with the same structure.
+///
+/// # Returns
+///
+/// * `Ok(JsonValue)` - The document
+/// * `Err(String)` - If the value has byte strings, tags, non-finite floats,
+///   non-text or duplicate map keys, or nests deeper than `MAX_DEPTH`
+pub fn cbor_to_json(value: Value) -> Result<JsonValue, String> {
+    convert(value, 0)
+}
+
+fn convert(value: Value, depth: usize) -> Result<JsonValue, String> {
+    if depth > MAX_DEPTH {
+        return Err(format!("CBOR is nested deeper than {}", MAX_DEPTH));
+    }
+    match value {
+        Value::Null => Ok(JsonValue::Null),
+        Value::Bool(value) => Ok(JsonValue::Bool(value)),
+        Value::Integer(integer) => Ok(JsonValue::Number(i128::from(integer).to_string())),
+        Value::Float(number) if number.is_finite() => Ok(JsonValue::Number(format!("{:?}", number))),
+        Value::Float(number) => Err(format!("{} is out of range", number)),
+        Value::Text(text) => Ok(JsonValue::String(text)),
+        Value::Array(items) => items.into_iter().map(|item| convert(item, depth + 1)).collect::<Result<_, _>>().map(JsonValue::Array),
+        Value::Map(entries) => {
+            let mut keys = HashSet::new();
+            let mut members = Vec::with_capacity(entries.len());
+            for (key, value) in entries {
+                let Value::Text(key) = key else {
+                    return Err("CBOR map keys must be text".to_string());
+                };
+                if !keys.insert(key.clone()) {
+                    return Err(format!("Duplicate key \"{}\"", key));
+                }
+                members.push((key, convert(value, depth + 1)?));
+            }
+            Ok(JsonValue::Object(members))
+        }
+        Value::Bytes(_) => Err("CBOR byte strings are not used".to_string()),
+        Value::Tag(tag, _) => Err(format!("CBOR tag {} is not used", tag)),
+        _ => Err("Unsupported CBOR value".to_string()),
+    }
+}
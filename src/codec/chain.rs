@@ -0,0 +1,372 @@
+use crate::block::block::{Block, BlockHeader};
+use crate::blockchain::genesis::Allocation;
+use crate::bridge::bridge::BridgeProof;
+use crate::codec::json::{json_enum, json_struct, JsonCodec, JsonValue};
+use crate::codec::wire::{wire_enum, wire_struct, WireCodec, WireReader};
+use crate::contracts::ballot::VoteWeighting;
+use crate::contracts::channel::ChannelState;
+use crate::contracts::escrow::EscrowOutcome;
+use crate::contracts::vesting::VestingSchedule;
+use crate::dex::orderbook::Side;
+use crate::identity::did::Service;
+use crate::merkle::merkle::{MerkleProof, ProofStep};
+use crate::state::rules::{BridgeOpening, LocalRules, Policy};
+use crate::transactions::transactions::{LockTime, Output, Transaction, TransactionKind};
+use crate::treasury::treasury::{Proposal, ProposalStatus, Treasury};
+use crate::utxo::coinjoin::JoinInput;
+use crate::utxo::utxo::OutPoint;
+use crate::vm::instruction::{self, Instruction};
+#[cfg(feature = "privacy")]
+use crate::privacy::confidential::{BalanceProof, BitProof, ConfidentialOutput, RangeProof};
+#[cfg(feature = "privacy")]
+use crate::privacy::keys::{point_from_hex, point_to_hex, scalar_from_hex, scalar_to_hex};
+#[cfg(feature = "privacy")]
+use crate::privacy::ring::RingSignature;
+#[cfg(feature = "privacy")]
+use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar};
+
+json_struct!(Block { block_hash, previous_block_hash, transaction, time_stamp, nonce, base_fee, merkle_root, state_root });
+json_struct!(BlockHeader { block_hash, previous_block_hash, time_stamp, nonce, base_fee, merkle_root, state_root });
+json_struct!(Transaction { sender_address, receiver_address, amount, fee, nonce, timestamp, signature, memo, kind, lock_time, gas_limit, gas_price, script });
+json_struct!(Output { address, amount });
+json_struct!(OutPoint { transaction_id, index });
+json_struct!(JoinInput { outpoint, signature });
+json_struct!(ChannelState { channel_id, sequence, balance_a, balance_b, signature_a, signature_b });
+json_struct!(Service { id, kind, endpoint });
+json_struct!(ProofStep { hash, is_left });
+json_struct!(MerkleProof { leaf, steps });
+json_struct!(BridgeProof { block_hash, transaction, proof });
+json_struct!(Allocation { address, amount });
+json_struct!(Policy { name, script, since });
+json_struct!(BridgeOpening { local_chain, remote_chain, remote_genesis, remote_difficulty });
+json_struct!(LocalRules { policies, bridges, treasury });
+json_struct!(Treasury { address, share_percent, governors, approvals_required, proposals, spend_count });
+json_struct!(Proposal { id, recipient, amount, description, approvals, status });
+
+json_enum!(EscrowOutcome { Release => "release", Refund => "refund" });
+json_enum!(ProposalStatus { Pending => "pending", Approved => "approved", Executed => "executed" });
+json_enum!(VoteWeighting { OnePerAddress => "one_per_address", Balance => "balance" });
+json_enum!(Side { Buy => "buy", Sell => "sell" });
+json_enum!(VestingSchedule {
+    Linear { start_height, end_height } => "linear",
+    Cliffs { unlocks } => "cliffs",
+});
+
+json_enum!(TransactionKind {
+    Transfer => "transfer",
+    Anchor { doc_hash } => "anchor",
+    MultiPay { outputs } => "multipay",
+    HtlcLock { hash_lock, expiry_height } => "htlc",
+    HtlcClaim { htlc_id, preimage } => "htlc_claim",
+    HtlcRefund { htlc_id } => "htlc_refund",
+    ChannelOpen => "channel_open",
+    ChannelClose { state } => "channel_close",
+    ChannelSettle { channel_id } => "channel_settle",
+    EscrowOpen { arbiter } => "escrow",
+    EscrowVote { escrow_id, outcome } => "escrow_vote",
+    VestingGrant { schedule } => "vesting",
+    StreamOpen { rate_per_block } => "stream",
+    StreamWithdraw { stream_id } => "stream_withdraw",
+    StreamCancel { stream_id } => "stream_cancel",
+    Utxo { inputs, outputs } => "utxo",
+    CoinJoin { inputs, outputs } => "coinjoin",
+    Deploy { code } => "deploy",
+    Call { contract, input } => "call",
+    AssetIssue { ticker, decimals, max_supply, initial_supply } => "asset_issue",
+    AssetMint { asset_id, quantity } => "asset_mint",
+    AssetTransfer { asset_id, quantity } => "asset_transfer",
+    NftMint { token_id, metadata_hash } => "nft_mint",
+    NftTransfer { token_id } => "nft_transfer",
+    NftBurn { token_id } => "nft_burn",
+    NameRegister { name, target } => "name_register",
+    NameRenew { name } => "name_renew",
+    DidRegister { public_keys, services } => "did_register",
+    DidUpdate { public_keys, services } => "did_update",
+    BallotCreate { question, options, start_height, end_height, weighting } => "ballot_create",
+    BallotVote { ballot_id, option } => "ballot_vote",
+    AuctionCreate { item, reserve_price, bidding_end, reveal_end } => "auction_create",
+    AuctionBid { auction_id } => "auction_bid",
+    AuctionCommit { auction_id, commitment } => "auction_commit",
+    AuctionReveal { auction_id, bid, salt } => "auction_reveal",
+    AuctionSettle { auction_id } => "auction_settle",
+    PoolCreate { asset_a, asset_b, amount_a, amount_b, fee_bps } => "pool_create",
+    PoolDeposit { pool_id, amount_a, amount_b } => "pool_deposit",
+    PoolWithdraw { pool_id, shares } => "pool_withdraw",
+    PoolSwap { pool_id, asset_in, amount_in, min_out } => "pool_swap",
+    OrderPlace { base, quote, side, price, quantity } => "order_place",
+    OrderCancel { order_id } => "order_cancel",
+    OracleCreate { feed, reporters, quorum, max_age } => "oracle_create",
+    OracleReport { feed, value } => "oracle_report",
+    BridgeLock { chain_id, asset_id, ticker, decimals, quantity, recipient } => "bridge_lock",
+    BridgeRelay { chain_id, headers } => "bridge_relay",
+    BridgeMint { chain_id, proof } => "bridge_mint",
+    BridgeBurn { chain_id, asset_id, quantity, recipient } => "bridge_burn",
+    BridgeRelease { chain_id, proof } => "bridge_release",
+    StealthTransfer { ephemeral_key } => "stealth_transfer",
+    #[cfg(feature = "privacy")]
+    ConfidentialTransfer { inputs, outputs, withdrawal, proof } => "confidential_transfer",
+    #[cfg(feature = "privacy")]
+    RingDeposit { key } => "ring_deposit",
+    #[cfg(feature = "privacy")]
+    RingWithdraw { ring, signature } => "ring_withdraw",
+});
+
+wire_struct!(Block { block_hash, previous_block_hash, transaction, time_stamp, nonce, base_fee, merkle_root, state_root });
+wire_struct!(BlockHeader { block_hash, previous_block_hash, time_stamp, nonce, base_fee, merkle_root, state_root });
+wire_struct!(Transaction { sender_address, receiver_address, amount, fee, nonce, timestamp, signature, memo, kind, lock_time, gas_limit, gas_price, script });
+wire_struct!(Output { address, amount });
+wire_struct!(OutPoint { transaction_id, index });
+wire_struct!(JoinInput { outpoint, signature });
+wire_struct!(ChannelState { channel_id, sequence, balance_a, balance_b, signature_a, signature_b });
+wire_struct!(Service { id, kind, endpoint });
+wire_struct!(ProofStep { hash, is_left });
+wire_struct!(MerkleProof { leaf, steps });
+wire_struct!(BridgeProof { block_hash, transaction, proof });
+wire_struct!(Allocation { address, amount });
+
+wire_enum!(EscrowOutcome { Release => 0, Refund => 1 });
+wire_enum!(VoteWeighting { OnePerAddress => 0, Balance => 1 });
+wire_enum!(Side { Buy => 0, Sell => 1 });
+wire_enum!(VestingSchedule {
+    Linear { start_height, end_height } => 0,
+    Cliffs { unlocks } => 1,
+});
+
+// Wire tags are part of the format: new variants take the next free number
+wire_enum!(TransactionKind {
+    Transfer => 0,
+    Anchor { doc_hash } => 1,
+    MultiPay { outputs } => 2,
+    HtlcLock { hash_lock, expiry_height } => 3,
+    HtlcClaim { htlc_id, preimage } => 4,
+    HtlcRefund { htlc_id } => 5,
+    ChannelOpen => 6,
+    ChannelClose { state } => 7,
+    ChannelSettle { channel_id } => 8,
+    EscrowOpen { arbiter } => 9,
+    EscrowVote { escrow_id, outcome } => 10,
+    VestingGrant { schedule } => 11,
+    StreamOpen { rate_per_block } => 12,
+    StreamWithdraw { stream_id } => 13,
+    StreamCancel { stream_id } => 14,
+    Utxo { inputs, outputs } => 15,
+    CoinJoin { inputs, outputs } => 16,
+    Deploy { code } => 17,
+    Call { contract, input } => 18,
+    AssetIssue { ticker, decimals, max_supply, initial_supply } => 19,
+    AssetMint { asset_id, quantity } => 20,
+    AssetTransfer { asset_id, quantity } => 21,
+    NftMint { token_id, metadata_hash } => 22,
+    NftTransfer { token_id } => 23,
+    NftBurn { token_id } => 24,
+    NameRegister { name, target } => 25,
+    NameRenew { name } => 26,
+    DidRegister { public_keys, services } => 27,
+    DidUpdate { public_keys, services } => 28,
+    BallotCreate { question, options, start_height, end_height, weighting } => 29,
+    BallotVote { ballot_id, option } => 30,
+    AuctionCreate { item, reserve_price, bidding_end, reveal_end } => 31,
+    AuctionBid { auction_id } => 32,
+    AuctionCommit { auction_id, commitment } => 33,
+    AuctionReveal { auction_id, bid, salt } => 34,
+    AuctionSettle { auction_id } => 35,
+    PoolCreate { asset_a, asset_b, amount_a, amount_b, fee_bps } => 36,
+    PoolDeposit { pool_id, amount_a, amount_b } => 37,
+    PoolWithdraw { pool_id, shares } => 38,
+    PoolSwap { pool_id, asset_in, amount_in, min_out } => 39,
+    OrderPlace { base, quote, side, price, quantity } => 40,
+    OrderCancel { order_id } => 41,
+    OracleCreate { feed, reporters, quorum, max_age } => 42,
+    OracleReport { feed, value } => 43,
+    BridgeLock { chain_id, asset_id, ticker, decimals, quantity, recipient } => 44,
+    BridgeRelay { chain_id, headers } => 45,
+    BridgeMint { chain_id, proof } => 46,
+    BridgeBurn { chain_id, asset_id, quantity, recipient } => 47,
+    BridgeRelease { chain_id, proof } => 48,
+    StealthTransfer { ephemeral_key } => 49,
+    #[cfg(feature = "privacy")]
+    ConfidentialTransfer { inputs, outputs, withdrawal, proof } => 50,
+    #[cfg(feature = "privacy")]
+    RingDeposit { key } => 51,
+    #[cfg(feature = "privacy")]
+    RingWithdraw { ring, signature } => 52,
+});
+
+impl JsonCodec for LockTime {
+    fn to_json(&self) -> JsonValue {
+        let (tag, mut members) = match self {
+            LockTime::None => ("none", Vec::new()),
+            LockTime::Height(height) => ("height", vec![("height", height.to_json())]),
+            LockTime::Timestamp(time) => ("timestamp", vec![("time", time.to_json())]),
+            LockTime::RelativeHeight { transaction_id, blocks } => {
+                ("relative_height", vec![("transaction_id", transaction_id.to_json()), ("blocks", blocks.to_json())])
+            }
+            LockTime::RelativeTime { transaction_id, seconds } => {
+                ("relative_time", vec![("transaction_id", transaction_id.to_json()), ("seconds", seconds.to_json())])
+            }
+        };
+        members.insert(0, ("type", JsonValue::String(tag.to_string())));
+        JsonValue::Object(members.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+    }
+
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        use crate::codec::json::field;
+        let members = value.as_object()?;
+        let tag: String = field(members, "type")?;
+        match tag.as_str() {
+            "none" => Ok(LockTime::None),
+            "height" => Ok(LockTime::Height(field(members, "height")?)),
+            "timestamp" => Ok(LockTime::Timestamp(field(members, "time")?)),
+            "relative_height" => Ok(LockTime::RelativeHeight {
+                transaction_id: field(members, "transaction_id")?,
+                blocks: field(members, "blocks")?,
+            }),
+            "relative_time" => Ok(LockTime::RelativeTime {
+                transaction_id: field(members, "transaction_id")?,
+                seconds: field(members, "seconds")?,
+            }),
+            _ => Err(format!("Unknown LockTime \"{}\"", tag)),
+        }
+    }
+}
+
+impl JsonCodec for Instruction {
+    /// Instructions use their text form (e.g. `"PUSH 5"`).
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(self.encode())
+    }
+
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let text = value.as_str()?;
+        let mut code = instruction::parse(text)?;
+        if code.len() != 1 || text.contains(['\n', ';', '#']) {
+            return Err(format!("{:?} is not a single instruction", text));
+        }
+        Ok(code.remove(0))
+    }
+}
+
+#[cfg(feature = "privacy")]
+json_struct!(ConfidentialOutput { owner, commitment, range_proof });
+#[cfg(feature = "privacy")]
+json_struct!(RangeProof { bits });
+#[cfg(feature = "privacy")]
+json_struct!(BitProof { commitment, challenges, responses });
+#[cfg(feature = "privacy")]
+json_struct!(BalanceProof { nonce, response });
+#[cfg(feature = "privacy")]
+json_struct!(RingSignature { key_image, challenge, responses });
+
+/// Points are their compressed form in hex.
+#[cfg(feature = "privacy")]
+impl JsonCodec for RistrettoPoint {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(point_to_hex(self))
+    }
+
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        point_from_hex(value.as_str()?)
+    }
+}
+
+/// Scalars are their canonical bytes in hex.
+#[cfg(feature = "privacy")]
+impl JsonCodec for Scalar {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(scalar_to_hex(self))
+    }
+
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        scalar_from_hex(value.as_str()?)
+    }
+}
+
+
+impl WireCodec for LockTime {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            LockTime::None => out.push(0),
+            LockTime::Height(height) => {
+                out.push(1);
+                height.encode(out);
+            }
+            LockTime::Timestamp(time) => {
+                out.push(2);
+                time.encode(out);
+            }
+            LockTime::RelativeHeight { transaction_id, blocks } => {
+                out.push(3);
+                transaction_id.encode(out);
+                blocks.encode(out);
+            }
+            LockTime::RelativeTime { transaction_id, seconds } => {
+                out.push(4);
+                transaction_id.encode(out);
+                seconds.encode(out);
+            }
+        }
+    }
+
+    fn decode(reader: &mut WireReader) -> Result<Self, String> {
+        match reader.read_byte()? {
+            0 => Ok(LockTime::None),
+            1 => Ok(LockTime::Height(WireCodec::decode(reader)?)),
+            2 => Ok(LockTime::Timestamp(WireCodec::decode(reader)?)),
+            3 => Ok(LockTime::RelativeHeight {
+                transaction_id: WireCodec::decode(reader)?,
+                blocks: WireCodec::decode(reader)?,
+            }),
+            4 => Ok(LockTime::RelativeTime {
+                transaction_id: WireCodec::decode(reader)?,
+                seconds: WireCodec::decode(reader)?,
+            }),
+            tag => Err(format!("Unknown LockTime tag {}", tag)),
+        }
+    }
+}
+
+impl WireCodec for Instruction {
+    /// Instructions use their text form, like in JSON.
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.encode().encode(out);
+    }
+
+    fn decode(reader: &mut WireReader) -> Result<Self, String> {
+        Instruction::from_json(&JsonValue::String(String::decode(reader)?))
+    }
+}
+
+#[cfg(feature = "privacy")]
+wire_struct!(ConfidentialOutput { owner, commitment, range_proof });
+#[cfg(feature = "privacy")]
+wire_struct!(RangeProof { bits });
+#[cfg(feature = "privacy")]
+wire_struct!(BitProof { commitment, challenges, responses });
+#[cfg(feature = "privacy")]
+wire_struct!(BalanceProof { nonce, response });
+#[cfg(feature = "privacy")]
+wire_struct!(RingSignature { key_image, challenge, responses });
+
+/// Points are their compressed form (32 bytes, as a hex hash).
+#[cfg(feature = "privacy")]
+impl WireCodec for RistrettoPoint {
+    fn encode(&self, out: &mut Vec<u8>) {
+        point_to_hex(self).encode(out);
+    }
+
+    fn decode(reader: &mut WireReader) -> Result<Self, String> {
+        point_from_hex(&String::decode(reader)?)
+    }
+}
+
+/// Scalars are their canonical bytes (32 bytes, as a hex hash).
+#[cfg(feature = "privacy")]
+impl WireCodec for Scalar {
+    fn encode(&self, out: &mut Vec<u8>) {
+        scalar_to_hex(self).encode(out);
+    }
+
+    fn decode(reader: &mut WireReader) -> Result<Self, String> {
+        scalar_from_hex(&String::decode(reader)?)
+    }
+}
@@ -0,0 +1,529 @@
+use std::collections::HashSet;
+use std::fmt;
+
+/// Deepest nesting of arrays and objects the parser accepts.
+pub const MAX_DEPTH: usize = 64;
+
+/// A parsed JSON document.
+///
+/// Numbers keep their text so integers above 2^53 are not rounded; they are
+/// converted when a field is read (see `JsonCodec`). Object keys keep their
+/// order and must be unique.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    /// Returns the members of an object.
+    pub fn as_object(&self) -> Result<&[(String, JsonValue)], String> {
+        match self {
+            JsonValue::Object(members) => Ok(members),
+            _ => Err("Expected an object".to_string()),
+        }
+    }
+
+    /// Returns the items of an array.
+    pub fn as_array(&self) -> Result<&[JsonValue], String> {
+        match self {
+            JsonValue::Array(items) => Ok(items),
+            _ => Err("Expected an array".to_string()),
+        }
+    }
+
+    /// Returns the text of a string.
+    pub fn as_str(&self) -> Result<&str, String> {
+        match self {
+            JsonValue::String(text) => Ok(text),
+            _ => Err("Expected a string".to_string()),
+        }
+    }
+
+    /// Returns the text of a number.
+    pub fn as_number(&self) -> Result<&str, String> {
+        match self {
+            JsonValue::Number(text) => Ok(text),
+            _ => Err("Expected a number".to_string()),
+        }
+    }
+}
+
+impl fmt::Display for JsonValue {
+    /// Writes compact JSON (no spaces).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonValue::Null => write!(f, "null"),
+            JsonValue::Bool(value) => write!(f, "{}", value),
+            JsonValue::Number(text) => write!(f, "{}", text),
+            JsonValue::String(text) => write_string(f, text),
+            JsonValue::Array(items) => {
+                write!(f, "[")?;
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            JsonValue::Object(members) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in members.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, text: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in text.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+/// Parses a JSON document.
+///
+/// Any malformed input (bad syntax, trailing data, invalid escapes, duplicate
+/// keys, nesting deeper than `MAX_DEPTH`) is an error, never a panic.
+///
+/// # Example
+///
+/// ```
+/// use blockc::codec::json::{parse, JsonValue};
+///
+/// let value = parse(r#"{"a":[1,"x\n",null]}"#).unwrap();
+/// assert_eq!(value.to_string(), r#"{"a":[1,"x\n",null]}"#);
+///
+/// assert!(parse("[1,]").is_err());
+/// assert!(parse(r#"{"a":1,"a":2}"#).is_err());
+/// assert!(parse(&"[".repeat(100)).is_err());
+/// ```
+pub fn parse(input: &str) -> Result<JsonValue, String> {
+    let mut parser = Parser { bytes: input.as_bytes(), position: 0 };
+    let value = parser.value(0)?;
+    parser.whitespace();
+    if parser.position != parser.bytes.len() {
+        return Err(format!("Unexpected data at byte {}", parser.position));
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Result<u8, String> {
+        let byte = self.peek().ok_or("Unexpected end of input".to_string())?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<(), String> {
+        let byte = self.next()?;
+        if byte != expected {
+            return Err(format!("Expected '{}' at byte {}", expected as char, self.position - 1));
+        }
+        Ok(())
+    }
+
+    fn whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.position += 1;
+        }
+    }
+
+    fn literal(&mut self, text: &str, value: JsonValue) -> Result<JsonValue, String> {
+        if !self.bytes[self.position..].starts_with(text.as_bytes()) {
+            return Err(format!("Invalid literal at byte {}", self.position));
+        }
+        self.position += text.len();
+        Ok(value)
+    }
+
+    fn value(&mut self, depth: usize) -> Result<JsonValue, String> {
+        if depth > MAX_DEPTH {
+            return Err(format!("Nesting deeper than {}", MAX_DEPTH));
+        }
+        self.whitespace();
+        match self.peek().ok_or("Unexpected end of input".to_string())? {
+            b'n' => self.literal("null", JsonValue::Null),
+            b't' => self.literal("true", JsonValue::Bool(true)),
+            b'f' => self.literal("false", JsonValue::Bool(false)),
+            b'"' => Ok(JsonValue::String(self.string()?)),
+            b'[' => self.array(depth),
+            b'{' => self.object(depth),
+            b'-' | b'0'..=b'9' => self.number(),
+            byte => Err(format!("Unexpected '{}' at byte {}", byte as char, self.position)),
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Result<JsonValue, String> {
+        self.expect(b'[')?;
+        let mut items = Vec::new();
+        self.whitespace();
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            return Ok(JsonValue::Array(items));
+        }
+        loop {
+            items.push(self.value(depth + 1)?);
+            self.whitespace();
+            match self.next()? {
+                b',' => continue,
+                b']' => return Ok(JsonValue::Array(items)),
+                _ => return Err(format!("Expected ',' or ']' at byte {}", self.position - 1)),
+            }
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> Result<JsonValue, String> {
+        self.expect(b'{')?;
+        let mut members: Vec<(String, JsonValue)> = Vec::new();
+        let mut keys = HashSet::new();
+        self.whitespace();
+        if self.peek() == Some(b'}') {
+            self.position += 1;
+            return Ok(JsonValue::Object(members));
+        }
+        loop {
+            self.whitespace();
+            let key = self.string()?;
+            if !keys.insert(key.clone()) {
+                return Err(format!("Duplicate key \"{}\"", key));
+            }
+            self.whitespace();
+            self.expect(b':')?;
+            let value = self.value(depth + 1)?;
+            members.push((key, value));
+            self.whitespace();
+            match self.next()? {
+                b',' => continue,
+                b'}' => return Ok(JsonValue::Object(members)),
+                _ => return Err(format!("Expected ',' or '}}' at byte {}", self.position - 1)),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<JsonValue, String> {
+        let start = self.position;
+        if self.peek() == Some(b'-') {
+            self.position += 1;
+        }
+        match self.next()? {
+            b'0' => {}
+            b'1'..=b'9' => self.digits(),
+            _ => return Err(format!("Invalid number at byte {}", start)),
+        }
+        if self.peek() == Some(b'.') {
+            self.position += 1;
+            self.required_digits(start)?;
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.position += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.position += 1;
+            }
+            self.required_digits(start)?;
+        }
+        // Only ASCII was consumed, so the slice is valid UTF-8
+        let text = String::from_utf8_lossy(&self.bytes[start..self.position]).into_owned();
+        Ok(JsonValue::Number(text))
+    }
+
+    fn digits(&mut self) {
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.position += 1;
+        }
+    }
+
+    fn required_digits(&mut self, start: usize) -> Result<(), String> {
+        if !matches!(self.peek(), Some(b'0'..=b'9')) {
+            return Err(format!("Invalid number at byte {}", start));
+        }
+        self.digits();
+        Ok(())
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect(b'"')?;
+        let mut bytes = Vec::new();
+        loop {
+            match self.next()? {
+                b'"' => break,
+                b'\\' => {
+                    let c = match self.next()? {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => self.unicode_escape()?,
+                        byte => return Err(format!("Invalid escape '\\{}'", byte as char)),
+                    };
+                    let mut buffer = [0; 4];
+                    bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+                }
+                byte if byte < 0x20 => return Err(format!("Control character in string at byte {}", self.position - 1)),
+                byte => bytes.push(byte),
+            }
+        }
+        String::from_utf8(bytes).map_err(|_| "String is not valid UTF-8".to_string())
+    }
+
+    fn hex4(&mut self) -> Result<u32, String> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = (self.next()? as char).to_digit(16).ok_or("Invalid \\u escape".to_string())?;
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn unicode_escape(&mut self) -> Result<char, String> {
+        let high = self.hex4()?;
+        let code = if (0xd800..0xdc00).contains(&high) {
+            // A high surrogate must be followed by an escaped low surrogate
+            self.expect(b'\\')?;
+            self.expect(b'u')?;
+            let low = self.hex4()?;
+            if !(0xdc00..0xe000).contains(&low) {
+                return Err("Invalid surrogate pair".to_string());
+            }
+            0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or("Invalid \\u escape".to_string())
+    }
+}
+
+/// Conversion of a value to and from JSON.
+///
+/// Decoding checks types and ranges, so any JSON input gives either a value or
+/// an error.
+pub trait JsonCodec: Sized {
+    /// Encodes the value.
+    fn to_json(&self) -> JsonValue;
+
+    /// Decodes a value.
+    fn from_json(value: &JsonValue) -> Result<Self, String>;
+}
+
+/// Reads the member `name` of an object.
+pub fn field<T: JsonCodec>(members: &[(String, JsonValue)], name: &str) -> Result<T, String> {
+    let (_, value) = members.iter().find(|(key, _)| key == name)
+        .ok_or(format!("Missing field \"{}\"", name))?;
+    T::from_json(value).map_err(|error| format!("Field \"{}\": {}", name, error))
+}
+
+impl JsonCodec for String {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(self.clone())
+    }
+
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        Ok(value.as_str()?.to_string())
+    }
+}
+
+impl JsonCodec for bool {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Bool(*self)
+    }
+
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        match value {
+            JsonValue::Bool(value) => Ok(*value),
+            _ => Err("Expected a boolean".to_string()),
+        }
+    }
+}
+
+macro_rules! json_integer {
+    ($($type:ty),*) => {
+        $(
+            impl JsonCodec for $type {
+                fn to_json(&self) -> JsonValue {
+                    JsonValue::Number(self.to_string())
+                }
+
+                fn from_json(value: &JsonValue) -> Result<Self, String> {
+                    let text = value.as_number()?;
+                    text.parse().map_err(|_| format!("{} is not a valid {}", text, stringify!($type)))
+                }
+            }
+        )*
+    };
+}
+
+json_integer!(u8, u32, u64, usize, i64);
+
+impl JsonCodec for f64 {
+    /// Non-finite numbers can't be written in JSON: they are encoded as `null`,
+    /// which does not decode.
+    fn to_json(&self) -> JsonValue {
+        if self.is_finite() {
+            JsonValue::Number(format!("{:?}", self))
+        } else {
+            JsonValue::Null
+        }
+    }
+
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let text = value.as_number()?;
+        let number: f64 = text.parse().map_err(|_| format!("{} is not a valid number", text))?;
+        if !number.is_finite() {
+            return Err(format!("{} is out of range", text));
+        }
+        Ok(number)
+    }
+}
+
+impl<T: JsonCodec> JsonCodec for Option<T> {
+    fn to_json(&self) -> JsonValue {
+        match self {
+            Some(value) => value.to_json(),
+            None => JsonValue::Null,
+        }
+    }
+
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        match value {
+            JsonValue::Null => Ok(None),
+            value => T::from_json(value).map(Some),
+        }
+    }
+}
+
+impl<T: JsonCodec> JsonCodec for Vec<T> {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Array(self.iter().map(|item| item.to_json()).collect())
+    }
+
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        value.as_array()?.iter().map(T::from_json).collect()
+    }
+}
+
+impl<T: JsonCodec> JsonCodec for Box<T> {
+    fn to_json(&self) -> JsonValue {
+        (**self).to_json()
+    }
+
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        T::from_json(value).map(Box::new)
+    }
+}
+
+impl<A: JsonCodec, B: JsonCodec> JsonCodec for (A, B) {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Array(vec![self.0.to_json(), self.1.to_json()])
+    }
+
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        match value.as_array()? {
+            [a, b] => Ok((A::from_json(a)?, B::from_json(b)?)),
+            _ => Err("Expected a pair".to_string()),
+        }
+    }
+}
+
+impl<T: JsonCodec> JsonCodec for [T; 2] {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Array(self.iter().map(|item| item.to_json()).collect())
+    }
+
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        match value.as_array()? {
+            [a, b] => Ok([T::from_json(a)?, T::from_json(b)?]),
+            _ => Err("Expected two items".to_string()),
+        }
+    }
+}
+
+/// Implements `JsonCodec` for a struct as an object with the listed fields.
+macro_rules! json_struct {
+    ($type:ty { $($field:ident),* $(,)? }) => {
+        impl $crate::codec::json::JsonCodec for $type {
+            fn to_json(&self) -> $crate::codec::json::JsonValue {
+                $crate::codec::json::JsonValue::Object(vec![
+                    $((stringify!($field).to_string(), $crate::codec::json::JsonCodec::to_json(&self.$field)),)*
+                ])
+            }
+
+            fn from_json(value: &$crate::codec::json::JsonValue) -> Result<Self, String> {
+                let members = value.as_object()?;
+                Ok(Self {
+                    $($field: $crate::codec::json::field(members, stringify!($field))?,)*
+                })
+            }
+        }
+    };
+}
+
+/// Implements `JsonCodec` for an enum as an object with a `type` tag and the
+/// fields of the variant.
+macro_rules! json_enum {
+    ($type:ident { $($(#[$meta:meta])* $variant:ident $({ $($field:ident),* $(,)? })? => $tag:literal),* $(,)? }) => {
+        impl $crate::codec::json::JsonCodec for $type {
+            fn to_json(&self) -> $crate::codec::json::JsonValue {
+                match self {
+                    $(
+                        $(#[$meta])*
+                        $type::$variant $({ $($field),* })? => {
+                            #[allow(unused_mut)]
+                            let mut members = vec![("type".to_string(), $crate::codec::json::JsonValue::String($tag.to_string()))];
+                            $($(members.push((stringify!($field).to_string(), $crate::codec::json::JsonCodec::to_json($field)));)*)?
+                            $crate::codec::json::JsonValue::Object(members)
+                        }
+                    )*
+                }
+            }
+
+            fn from_json(value: &$crate::codec::json::JsonValue) -> Result<Self, String> {
+                let members = value.as_object()?;
+                let tag: String = $crate::codec::json::field(members, "type")?;
+                match tag.as_str() {
+                    $(
+                        $(#[$meta])*
+                        $tag => Ok($type::$variant $({ $($field: $crate::codec::json::field(members, stringify!($field))?),* })?),
+                    )*
+                    _ => Err(format!("Unknown {} \"{}\"", stringify!($type), tag)),
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use json_enum;
+pub(crate) use json_struct;
@@ -0,0 +1,222 @@
+use std::fmt;
+use crate::block::block::{Block, MAX_ENCODED_BLOCK_BYTES};
+use crate::codec::json::{self, JsonCodec, JsonValue, MAX_DEPTH};
+use crate::codec::wire::{WireCodec, WireReader};
+use crate::fees::base_fee::MAX_BLOCK_SIZE;
+use crate::transactions::transactions::{Transaction, MAX_ENCODED_TRANSACTION_BYTES};
+
+/// Coinbase transactions a block starts with at most: the miner's reward and the
+/// treasury's share.
+pub const MAX_COINBASE_TRANSACTIONS: usize = 2;
+
+/// Why a message from a peer or an RPC client was refused.
+///
+/// * `TooLarge` - The message is over the size limit; nothing was decoded
+/// * `TooManyTransactions` - A block holds more transactions than a valid one
+///   can; they were not decoded
+/// * `TooDeep` - Values are nested deeper than the limit
+/// * `Malformed` - The message is not a valid encoding of what was expected
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessageError {
+    TooLarge { size: usize, max: usize },
+    TooManyTransactions { count: u64, max: usize },
+    TooDeep { max: usize },
+    Malformed(String),
+}
+
+impl fmt::Display for MessageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MessageError::TooLarge { size, max } => write!(f, "Message of {} bytes is over the {} bytes limit", size, max),
+            MessageError::TooManyTransactions { count, max } => write!(f, "Block has {} transactions, limit is {}", count, max),
+            MessageError::TooDeep { max } => write!(f, "Values are nested deeper than {}", max),
+            MessageError::Malformed(error) => write!(f, "Malformed message: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for MessageError {}
+
+impl From<MessageError> for String {
+    fn from(error: MessageError) -> Self {
+        error.to_string()
+    }
+}
+
+/// Limits checked on untrusted messages (relayed blocks and transactions, RPC
+/// bodies) before they are decoded, so a hostile one is refused without the
+/// memory and time decoding it would take.
+///
+/// # Fields
+///
+/// * `max_block_bytes` - Largest encoded block
+/// * `max_transaction_bytes` - Largest encoded transaction
+/// * `max_block_transactions` - Most transactions in a block (coinbase transactions included)
+/// * `max_depth` - Deepest nesting of values (JSON arrays and objects, proofs
+///   holding transactions); JSON is never nested deeper than `MAX_DEPTH`
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::codec::limits::{MessageError, MessageLimits};
+/// use blockc::entity::entity::Entity;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+/// use blockc::block::block::BlockExt;
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// chain.add_block(Vec::new(), "Alice").unwrap();
+/// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+/// let payments = (0..3).map(|_| Transaction::create_payment(&mut alice, "Bob", 1.0, 0.5).unwrap()).collect();
+/// chain.add_block(payments, "Miner").unwrap();
+/// let block = chain.get_latest_block();
+///
+/// let limits = MessageLimits::default();
+/// assert_eq!(&limits.decode_block_wire(&block.to_wire()).unwrap(), block);
+/// assert_eq!(&limits.decode_block_json(&block.to_bytes()).unwrap(), block);
+///
+/// // A block with 2 coinbase transactions and 3 payments is refused before its transactions are decoded
+/// let strict = MessageLimits { max_block_transactions: 2, ..MessageLimits::default() };
+/// let refused = MessageError::TooManyTransactions { count: 5, max: 2 };
+/// assert_eq!(strict.decode_block_wire(&block.to_wire()), Err(refused.clone()));
+/// assert_eq!(strict.decode_block_json(&block.to_bytes()), Err(refused));
+///
+/// let tiny = MessageLimits { max_block_bytes: 100, ..MessageLimits::default() };
+/// assert!(matches!(tiny.decode_block_wire(&block.to_wire()), Err(MessageError::TooLarge { .. })));
+///
+/// let nested = format!("{}{}", "[".repeat(10), "]".repeat(10));
+/// let shallow = MessageLimits { max_depth: 4, ..MessageLimits::default() };
+/// assert_eq!(shallow.decode_transaction_json(&nested), Err(MessageError::TooDeep { max: 4 }));
+/// assert!(matches!(limits.decode_transaction_json("{}"), Err(MessageError::Malformed(_))));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MessageLimits {
+    pub max_block_bytes: usize,
+    pub max_transaction_bytes: usize,
+    pub max_block_transactions: usize,
+    pub max_depth: usize,
+}
+
+impl Default for MessageLimits {
+    fn default() -> Self {
+        MessageLimits {
+            max_block_bytes: MAX_ENCODED_BLOCK_BYTES,
+            max_transaction_bytes: MAX_ENCODED_TRANSACTION_BYTES,
+            max_block_transactions: MAX_BLOCK_SIZE + MAX_COINBASE_TRANSACTIONS,
+            max_depth: MAX_DEPTH,
+        }
+    }
+}
+
+impl MessageLimits {
+    /// Decodes a transaction in JSON (see `Transaction::from_json`).
+    pub fn decode_transaction_json(&self, text: &str) -> Result<Transaction, MessageError> {
+        check_size(text.len(), self.max_transaction_bytes)?;
+        let value = self.parse_json(text)?;
+        <Transaction as JsonCodec>::from_json(&value).map_err(MessageError::Malformed)
+    }
+
+    /// Decodes a transaction in the wire format (see `Transaction::from_wire`).
+    pub fn decode_transaction_wire(&self, bytes: &[u8]) -> Result<Transaction, MessageError> {
+        check_size(bytes.len(), self.max_transaction_bytes)?;
+        let mut reader = WireReader::with_max_depth(bytes, self.max_depth);
+        finish(&mut reader, self.max_depth, Transaction::decode)
+    }
+
+    /// Decodes a block in JSON (see `Block::from_bytes`).
+    ///
+    /// The number of transactions is checked on the parsed document, before any
+    /// of them is decoded.
+    pub fn decode_block_json(&self, bytes: &[u8]) -> Result<Block, MessageError> {
+        check_size(bytes.len(), self.max_block_bytes)?;
+        let text = std::str::from_utf8(bytes).map_err(|_| MessageError::Malformed("Encoded block is not UTF-8".to_string()))?;
+        let value = self.parse_json(text)?;
+        let transactions = value.as_object().ok()
+            .and_then(|members| members.iter().find(|(key, _)| key == "transaction"))
+            .and_then(|(_, transactions)| transactions.as_array().ok());
+        if let Some(transactions) = transactions {
+            self.check_transaction_count(transactions.len() as u64)?;
+        }
+        <Block as JsonCodec>::from_json(&value).map_err(MessageError::Malformed)
+    }
+
+    /// Decodes a block in the wire format (see `Block::from_wire`).
+    ///
+    /// The number of transactions is read ahead of them, so an oversized block is
+    /// refused before any of them is decoded.
+    pub fn decode_block_wire(&self, bytes: &[u8]) -> Result<Block, MessageError> {
+        check_size(bytes.len(), self.max_block_bytes)?;
+        let mut reader = WireReader::with_max_depth(bytes, self.max_depth);
+        // The transactions follow the block hash and the previous block hash
+        let mut ahead = reader.clone();
+        String::decode(&mut ahead).map_err(MessageError::Malformed)?;
+        String::decode(&mut ahead).map_err(MessageError::Malformed)?;
+        self.check_transaction_count(ahead.read_varint().map_err(MessageError::Malformed)?)?;
+        finish(&mut reader, self.max_depth, Block::decode)
+    }
+
+    fn parse_json(&self, text: &str) -> Result<JsonValue, MessageError> {
+        if json_depth(text) > self.max_depth {
+            return Err(MessageError::TooDeep { max: self.max_depth });
+        }
+        json::parse(text).map_err(MessageError::Malformed)
+    }
+
+    fn check_transaction_count(&self, count: u64) -> Result<(), MessageError> {
+        if count > self.max_block_transactions as u64 {
+            return Err(MessageError::TooManyTransactions { count: count, max: self.max_block_transactions });
+        }
+        Ok(())
+    }
+}
+
+/// Returns how deep the arrays and objects of a JSON document are nested, by
+/// scanning its brackets (the document may be malformed).
+///
+/// # Example
+///
+/// ```
+/// use blockc::codec::limits::json_depth;
+///
+/// assert_eq!(json_depth(r#"{"a":[1,{"b":"]]]"}]}"#), 3);
+/// assert_eq!(json_depth("42"), 0);
+/// ```
+pub fn json_depth(text: &str) -> usize {
+    let (mut depth, mut deepest) = (0usize, 0);
+    let (mut in_string, mut escaped) = (false, false);
+    for byte in text.bytes() {
+        match (in_string, byte) {
+            (true, _) if escaped => escaped = false,
+            (true, b'\\') => escaped = true,
+            (true, b'"') => in_string = false,
+            (true, _) => {}
+            (false, b'"') => in_string = true,
+            (false, b'[' | b'{') => {
+                depth += 1;
+                deepest = deepest.max(depth);
+            }
+            (false, b']' | b'}') => depth = depth.saturating_sub(1),
+            (false, _) => {}
+        }
+    }
+    deepest
+}
+
+fn check_size(size: usize, max: usize) -> Result<(), MessageError> {
+    if size > max {
+        return Err(MessageError::TooLarge { size: size, max: max });
+    }
+    Ok(())
+}
+
+/// Decodes a value that must take the rest of `reader`.
+fn finish<T>(reader: &mut WireReader, max_depth: usize, decode: fn(&mut WireReader) -> Result<T, String>) -> Result<T, MessageError> {
+    let value = decode(reader).map_err(|error| match reader.too_deep() {
+        true => MessageError::TooDeep { max: max_depth },
+        false => MessageError::Malformed(error),
+    })?;
+    if reader.remaining() > 0 {
+        return Err(MessageError::Malformed(format!("{} unexpected bytes after the value", reader.remaining())));
+    }
+    Ok(value)
+}
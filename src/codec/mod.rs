@@ -0,0 +1,6 @@
+pub mod json;
+pub mod chain;
+pub mod wire;
+pub mod limits;
+#[cfg(feature = "cbor")]
+pub mod cbor;
\ No newline at end of file
@@ -0,0 +1,443 @@
+use crate::codec::json::MAX_DEPTH;
+
+/// Marks a string stored as a 32-byte hash instead of its length.
+const HASH_TAG: u64 = 0;
+
+/// Amounts are stored in hundred-millionths when that loses nothing.
+const AMOUNT_SCALE: f64 = 1e8;
+
+/// Largest whole number of hundred-millionths an `f64` holds exactly.
+const MAX_SCALED_AMOUNT: f64 = 9_007_199_254_740_992.0;
+
+/// A type with a compact binary encoding for storage and the network.
+///
+/// Integers and lengths are LEB128 varints, amounts are varints of
+/// hundred-millionths (or the 8 bytes of the float when that would round),
+/// 64-character hex hashes take 32 bytes and enums are one tag byte followed
+/// by the fields of the variant. There are no field names: fields are written
+/// in declaration order, so adding one changes the format.
+///
+/// Every value has exactly one encoding: decoding rejects overlong varints,
+/// hashes written as text and amounts written as floats when they fit the
+/// compact form, so re-encoding a decoded value gives the same bytes.
+///
+/// # Example
+///
+/// ```
+/// use blockc::codec::wire;
+///
+/// let bytes = wire::encode(&(300u64, Some("Alice".to_string())));
+/// assert_eq!(bytes, [0xac, 0x02, 1, 6, b'A', b'l', b'i', b'c', b'e']);
+/// assert_eq!(wire::decode::<(u64, Option<String>)>(&bytes).unwrap(), (300, Some("Alice".to_string())));
+///
+/// assert_eq!(wire::encode(&0.1f64), [0x80, 0xb4, 0x89, 0x13]);
+/// assert_eq!(wire::encode(&"ab".repeat(32)).len(), 33);
+///
+/// // Overlong varints and trailing bytes are rejected
+/// assert!(wire::decode::<u64>(&[0x80, 0x00]).is_err());
+/// assert!(wire::decode::<u64>(&[1, 2]).is_err());
+/// ```
+pub trait WireCodec: Sized {
+    /// Appends the encoding of the value to `out`.
+    fn encode(&self, out: &mut Vec<u8>);
+
+    /// Reads a value from `reader`.
+    fn decode(reader: &mut WireReader) -> Result<Self, String>;
+}
+
+/// Encodes `value`.
+pub fn encode<T: WireCodec>(value: &T) -> Vec<u8> {
+    let mut out = Vec::new();
+    value.encode(&mut out);
+    out
+}
+
+/// Decodes a value that must take all of `bytes`.
+///
+/// # Returns
+///
+/// * `Ok(T)` - The decoded value
+/// * `Err(String)` - If the bytes are truncated, malformed or followed by more
+pub fn decode<T: WireCodec>(bytes: &[u8]) -> Result<T, String> {
+    let mut reader = WireReader::new(bytes);
+    let value = T::decode(&mut reader)?;
+    if reader.remaining() > 0 {
+        return Err(format!("{} unexpected bytes after the value", reader.remaining()));
+    }
+    Ok(value)
+}
+
+/// Reads encoded values from a byte slice.
+#[derive(Debug, Clone)]
+pub struct WireReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    depth: usize,
+    max_depth: usize,
+    too_deep: bool,
+}
+
+impl<'a> WireReader<'a> {
+    /// Starts reading at the beginning of `bytes`.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        WireReader::with_max_depth(bytes, MAX_DEPTH)
+    }
+
+    /// Starts reading at the beginning of `bytes`, allowing values nested at most
+    /// `max_depth` deep (`new` allows `MAX_DEPTH`).
+    pub fn with_max_depth(bytes: &'a [u8], max_depth: usize) -> Self {
+        WireReader {
+            bytes: bytes,
+            position: 0,
+            depth: 0,
+            max_depth: max_depth,
+            too_deep: false,
+        }
+    }
+
+    /// Returns `true` if a value was rejected for being nested too deep.
+    pub fn too_deep(&self) -> bool {
+        self.too_deep
+    }
+
+    /// Number of bytes not read yet.
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() - self.position
+    }
+
+    /// Reads one byte.
+    pub fn read_byte(&mut self) -> Result<u8, String> {
+        let byte = *self.bytes.get(self.position).ok_or(format!("Unexpected end of input at byte {}", self.position))?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    /// Reads the next `len` bytes.
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if len > self.remaining() {
+            return Err(format!("Unexpected end of input: {} bytes needed at byte {}", len, self.position));
+        }
+        let bytes = &self.bytes[self.position..self.position + len];
+        self.position += len;
+        Ok(bytes)
+    }
+
+    /// Reads an unsigned LEB128 varint.
+    pub fn read_varint(&mut self) -> Result<u64, String> {
+        let start = self.position;
+        let mut value = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = self.read_byte()?;
+            if shift == 63 && byte > 1 {
+                return Err(format!("Varint at byte {} is over 64 bits", start));
+            }
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                if byte == 0 && shift > 0 {
+                    return Err(format!("Varint at byte {} is not minimal", start));
+                }
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+
+    /// Reads a varint count of items, each at least one byte long.
+    pub fn read_len(&mut self) -> Result<usize, String> {
+        let len = self.read_varint()?;
+        if len > self.remaining() as u64 {
+            return Err(format!("Length {} is over the {} bytes left", len, self.remaining()));
+        }
+        Ok(len as usize)
+    }
+
+    // Bounds nesting (a bridge proof holds a transaction, which may hold a proof)
+    fn nested<T>(&mut self, decode: impl FnOnce(&mut Self) -> Result<T, String>) -> Result<T, String> {
+        if self.depth >= self.max_depth {
+            self.too_deep = true;
+            return Err(format!("Values are nested deeper than {}", self.max_depth));
+        }
+        self.depth += 1;
+        let value = decode(self);
+        self.depth -= 1;
+        value
+    }
+}
+
+/// Appends `value` as an unsigned LEB128 varint.
+pub fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+// The 32 bytes of a 64-character lowercase hex string
+fn hash_bytes(text: &str) -> Option<[u8; 32]> {
+    if text.len() != 64 || !text.bytes().all(|byte| matches!(byte, b'0'..=b'9' | b'a'..=b'f')) {
+        return None;
+    }
+    let mut bytes = [0; 32];
+    hex::decode_to_slice(text, &mut bytes).ok()?;
+    Some(bytes)
+}
+
+impl WireCodec for u8 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self);
+    }
+
+    fn decode(reader: &mut WireReader) -> Result<Self, String> {
+        reader.read_byte()
+    }
+}
+
+macro_rules! wire_unsigned {
+    ($($type:ty),*) => {
+        $(
+            impl WireCodec for $type {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    write_varint(out, *self as u64);
+                }
+
+                fn decode(reader: &mut WireReader) -> Result<Self, String> {
+                    let value = reader.read_varint()?;
+                    <$type>::try_from(value).map_err(|_| format!("{} is not a valid {}", value, stringify!($type)))
+                }
+            }
+        )*
+    };
+}
+
+wire_unsigned!(u32, u64, usize);
+
+impl WireCodec for i64 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        write_varint(out, zigzag(*self));
+    }
+
+    fn decode(reader: &mut WireReader) -> Result<Self, String> {
+        reader.read_varint().map(unzigzag)
+    }
+}
+
+impl WireCodec for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+
+    fn decode(reader: &mut WireReader) -> Result<Self, String> {
+        match reader.read_byte()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            byte => Err(format!("{} is not a valid bool", byte)),
+        }
+    }
+}
+
+impl WireCodec for f64 {
+    /// A varint whose lowest bit is 0 for a (zigzag) number of
+    /// hundred-millionths, or 1 followed by the 8 bytes of the float.
+    fn encode(&self, out: &mut Vec<u8>) {
+        match compact_amount(*self) {
+            Some(scaled) => write_varint(out, zigzag(scaled) << 1),
+            None => {
+                out.push(1);
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+        }
+    }
+
+    fn decode(reader: &mut WireReader) -> Result<Self, String> {
+        let header = reader.read_varint()?;
+        if header & 1 == 0 {
+            let scaled = unzigzag(header >> 1);
+            let value = scaled as f64 / AMOUNT_SCALE;
+            if compact_amount(value) != Some(scaled) {
+                return Err(format!("{} is not a valid amount", scaled));
+            }
+            return Ok(value);
+        }
+        if header != 1 {
+            return Err(format!("{} is not a valid number header", header));
+        }
+        let value = f64::from_le_bytes(reader.read_bytes(8)?.try_into().unwrap());
+        if !value.is_finite() {
+            return Err(format!("{} is out of range", value));
+        }
+        if compact_amount(value).is_some() {
+            return Err(format!("{} must be written as an amount", value));
+        }
+        Ok(value)
+    }
+}
+
+// The number of hundred-millionths in `value`, if it converts back to exactly `value`
+fn compact_amount(value: f64) -> Option<i64> {
+    let scaled = value * AMOUNT_SCALE;
+    if scaled.fract() != 0.0 || scaled.abs() > MAX_SCALED_AMOUNT {
+        return None;
+    }
+    let scaled = scaled as i64;
+    ((scaled as f64 / AMOUNT_SCALE).to_bits() == value.to_bits()).then_some(scaled)
+}
+
+impl WireCodec for String {
+    /// A varint of 0 followed by 32 bytes for a hex hash, or of the length plus
+    /// one followed by the UTF-8 text.
+    fn encode(&self, out: &mut Vec<u8>) {
+        match hash_bytes(self) {
+            Some(bytes) => {
+                write_varint(out, HASH_TAG);
+                out.extend_from_slice(&bytes);
+            }
+            None => {
+                write_varint(out, self.len() as u64 + 1);
+                out.extend_from_slice(self.as_bytes());
+            }
+        }
+    }
+
+    fn decode(reader: &mut WireReader) -> Result<Self, String> {
+        let header = reader.read_varint()?;
+        if header == HASH_TAG {
+            return Ok(hex::encode(reader.read_bytes(32)?));
+        }
+        let len = usize::try_from(header - 1).map_err(|_| format!("String length {} is too long", header - 1))?;
+        let text = std::str::from_utf8(reader.read_bytes(len)?).map_err(|_| "String is not UTF-8".to_string())?;
+        if hash_bytes(text).is_some() {
+            return Err(format!("Hash {} must be written as bytes", text));
+        }
+        Ok(text.to_string())
+    }
+}
+
+impl<T: WireCodec> WireCodec for Option<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Some(value) => {
+                out.push(1);
+                value.encode(out);
+            }
+            None => out.push(0),
+        }
+    }
+
+    fn decode(reader: &mut WireReader) -> Result<Self, String> {
+        match bool::decode(reader)? {
+            true => T::decode(reader).map(Some),
+            false => Ok(None),
+        }
+    }
+}
+
+impl<T: WireCodec> WireCodec for Vec<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        write_varint(out, self.len() as u64);
+        for item in self {
+            item.encode(out);
+        }
+    }
+
+    fn decode(reader: &mut WireReader) -> Result<Self, String> {
+        // Every value takes at least one byte, which bounds the allocation
+        let len = reader.read_len()?;
+        let mut items = Vec::with_capacity(len);
+        for _ in 0..len {
+            items.push(T::decode(reader)?);
+        }
+        Ok(items)
+    }
+}
+
+impl<T: WireCodec> WireCodec for Box<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (**self).encode(out);
+    }
+
+    fn decode(reader: &mut WireReader) -> Result<Self, String> {
+        reader.nested(T::decode).map(Box::new)
+    }
+}
+
+impl<A: WireCodec, B: WireCodec> WireCodec for (A, B) {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+        self.1.encode(out);
+    }
+
+    fn decode(reader: &mut WireReader) -> Result<Self, String> {
+        Ok((A::decode(reader)?, B::decode(reader)?))
+    }
+}
+
+impl<T: WireCodec> WireCodec for [T; 2] {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self[0].encode(out);
+        self[1].encode(out);
+    }
+
+    fn decode(reader: &mut WireReader) -> Result<Self, String> {
+        Ok([T::decode(reader)?, T::decode(reader)?])
+    }
+}
+
+/// Implements `WireCodec` for a struct as its listed fields in order.
+macro_rules! wire_struct {
+    ($type:ty { $($field:ident),* $(,)? }) => {
+        impl $crate::codec::wire::WireCodec for $type {
+            fn encode(&self, out: &mut Vec<u8>) {
+                $($crate::codec::wire::WireCodec::encode(&self.$field, out);)*
+            }
+
+            fn decode(reader: &mut $crate::codec::wire::WireReader) -> Result<Self, String> {
+                Ok(Self {
+                    $($field: $crate::codec::wire::WireCodec::decode(reader)?,)*
+                })
+            }
+        }
+    };
+}
+
+/// Implements `WireCodec` for an enum as a tag byte followed by the fields of
+/// the variant. Tags are part of the format: never reuse or renumber one.
+macro_rules! wire_enum {
+    ($type:ident { $($(#[$meta:meta])* $variant:ident $({ $($field:ident),* $(,)? })? => $tag:literal),* $(,)? }) => {
+        impl $crate::codec::wire::WireCodec for $type {
+            fn encode(&self, out: &mut Vec<u8>) {
+                match self {
+                    $(
+                        $(#[$meta])*
+                        $type::$variant $({ $($field),* })? => {
+                            out.push($tag);
+                            $($($crate::codec::wire::WireCodec::encode($field, out);)*)?
+                        }
+                    )*
+                }
+            }
+
+            fn decode(reader: &mut $crate::codec::wire::WireReader) -> Result<Self, String> {
+                match reader.read_byte()? {
+                    $(
+                        $(#[$meta])*
+                        $tag => Ok($type::$variant $({ $($field: $crate::codec::wire::WireCodec::decode(reader)?),* })?),
+                    )*
+                    tag => Err(format!("Unknown {} tag {}", stringify!($type), tag)),
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use wire_enum;
+pub(crate) use wire_struct;
@@ -0,0 +1 @@
+pub mod node;
\ No newline at end of file
@@ -0,0 +1,429 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+use crate::block::block::HashAlgorithm;
+use crate::blockchain::blockchain::{Blockchain, DEFAULT_BLOCK_REWARD};
+use crate::blockchain::memory::MemoryLimits;
+use crate::mempool::mempool::{PackageLimits, RbfPolicy};
+use crate::rpc::limit::RateLimit;
+
+/// Prefix of the environment variables overriding the configuration.
+pub const ENV_PREFIX: &str = "BLOCKC_";
+
+/// Hardest difficulty accepted (a block hash has 64 hex digits).
+pub const MAX_DIFFICULTY: u32 = 64;
+
+/// Chain parameters used when a new chain is created.
+///
+/// # Fields
+///
+/// * `difficulty` - Leading zeros required in block hashes
+/// * `block_reward` - Coins minted by each block
+/// * `hash_algorithm` - Hash function of the block headers
+/// * `assume_valid` - Hash of a trusted block: scripts and signatures of the blocks
+///   up to it are not checked again when the chain is loaded
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkConfig {
+    pub difficulty: u32,
+    pub block_reward: f64,
+    pub hash_algorithm: HashAlgorithm,
+    pub assume_valid: Option<String>,
+}
+
+/// Settings of the JSON API server.
+///
+/// # Fields
+///
+/// * `bind` - Address the server listens on (`host:port`)
+/// * `requests_per_second` - Sustained requests allowed to each client IP address
+///   (0 for no limit)
+/// * `burst` - Requests a client may send at once after a quiet period
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcConfig {
+    pub bind: String,
+    pub requests_per_second: f64,
+    pub burst: f64,
+}
+
+impl RpcConfig {
+    /// Returns the rate limit of each client (`None` if `requests_per_second` is 0).
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        (self.requests_per_second != 0.0).then_some(RateLimit {
+            per_second: self.requests_per_second,
+            burst: self.burst,
+        })
+    }
+}
+
+/// Settings of the miner.
+///
+/// # Fields
+///
+/// * `miner` - Address receiving the rewards and fees
+/// * `blocks` - Blocks mined by one `mine` run
+#[derive(Debug, Clone, PartialEq)]
+pub struct MiningConfig {
+    pub miner: String,
+    pub blocks: u32,
+}
+
+/// Limits of the mempool.
+///
+/// # Fields
+///
+/// * `max_transactions` - Most transactions pending at once
+/// * `min_bump_percent` - Fee increase (in percent) a replacement must pay
+/// * `min_bump_absolute` - Fee increase a replacement must pay at least
+/// * `max_ancestors` - Longest chain of pending transactions ending at one
+/// * `max_descendants` - Longest chain of pending transactions starting at one
+#[derive(Debug, Clone, PartialEq)]
+pub struct MempoolConfig {
+    pub max_transactions: usize,
+    pub min_bump_percent: f64,
+    pub min_bump_absolute: f64,
+    pub max_ancestors: usize,
+    pub max_descendants: usize,
+}
+
+/// Memory ceilings, in bytes (see `Blockchain::set_memory_limits`); a missing key
+/// means no limit.
+///
+/// # Fields
+///
+/// * `mempool_bytes` - Most memory the pending transactions may take
+/// * `state_cache_bytes` - Most memory the state cache may take before it drops
+///   its oldest undo records
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MemoryConfig {
+    pub mempool_bytes: Option<usize>,
+    pub state_cache_bytes: Option<usize>,
+}
+
+/// Configuration of a node, shared by the `blockc` binary and library users.
+///
+/// It is read from a TOML file whose tables mirror the fields (every key is
+/// optional and unknown keys are errors), then environment variables override
+/// single values: `BLOCKC_DATA_DIR`, `BLOCKC_DIFFICULTY`, `BLOCKC_BLOCK_REWARD`,
+/// `BLOCKC_HASH_ALGORITHM`, `BLOCKC_ASSUME_VALID`, `BLOCKC_RPC_BIND`, `BLOCKC_RPC_REQUESTS_PER_SECOND`,
+/// `BLOCKC_MINER`, `BLOCKC_MINING_BLOCKS`, `BLOCKC_MEMPOOL_MAX_TRANSACTIONS`.
+///
+/// # Fields
+///
+/// * `data_dir` - Directory holding the chain, mempool and keys
+/// * `network` - Parameters of new chains
+/// * `rpc` - JSON API server
+/// * `mining` - Miner
+/// * `mempool` - Mempool limits
+/// * `memory` - Memory ceilings
+///
+/// # Example
+///
+/// ```
+/// use blockc::config::node::NodeConfig;
+///
+/// let config = NodeConfig::from_toml(r#"
+///     data_dir = "/var/lib/blockc"
+///
+///     [network]
+///     difficulty = 2
+///     hash_algorithm = "keccak256"
+///
+///     [mempool]
+///     max_transactions = 500
+///     max_ancestors = 10
+///
+///     [memory]
+///     mempool_bytes = 1_000_000
+/// "#).unwrap();
+/// assert_eq!(config.network.difficulty, 2);
+/// assert_eq!(config.network.block_reward, 50.0);
+/// assert_eq!(config.rpc.bind, "127.0.0.1:8080");
+/// assert!(config.rpc.rate_limit().is_some());
+///
+/// let chain = config.new_chain();
+/// assert_eq!(chain.get_difficulty(), 2);
+/// assert_eq!(chain.hash_algorithm().name(), "keccak256");
+/// assert_eq!(chain.mempool().max_transactions(), Some(500));
+/// assert_eq!(chain.mempool().package_limits().max_ancestors, 10);
+/// assert_eq!(chain.memory_limits().mempool, Some(1_000_000));
+/// assert_eq!(chain.memory_limits().state_cache, None);
+///
+/// assert!(NodeConfig::from_toml("[network]\ndificulty = 2").is_err());
+/// assert!(NodeConfig::from_toml("[network]\ndifficulty = 99").is_err());
+/// assert!(NodeConfig::from_toml("[network]\nhash_algorithm = \"md5\"").is_err());
+/// assert!(NodeConfig::from_toml("[rpc]\nrequests_per_second = 0.0").unwrap().rpc.rate_limit().is_none());
+/// assert!(NodeConfig::from_toml("[rpc]\nburst = 0.5").is_err());
+/// assert!(NodeConfig::from_toml("[network]\nassume_valid = \"00ff\"").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeConfig {
+    pub data_dir: PathBuf,
+    pub network: NetworkConfig,
+    pub rpc: RpcConfig,
+    pub mining: MiningConfig,
+    pub mempool: MempoolConfig,
+    pub memory: MemoryConfig,
+}
+
+impl Default for NodeConfig {
+    fn default() -> Self {
+        let rbf = RbfPolicy::default();
+        let rate = RateLimit::default();
+        let limits = PackageLimits::default();
+        NodeConfig {
+            data_dir: PathBuf::from(".blockc"),
+            network: NetworkConfig {
+                difficulty: 3,
+                block_reward: DEFAULT_BLOCK_REWARD,
+                hash_algorithm: HashAlgorithm::Sha256,
+                assume_valid: None,
+            },
+            rpc: RpcConfig {
+                bind: "127.0.0.1:8080".to_string(),
+                requests_per_second: rate.per_second,
+                burst: rate.burst,
+            },
+            mining: MiningConfig {
+                miner: "Miner".to_string(),
+                blocks: 1,
+            },
+            mempool: MempoolConfig {
+                max_transactions: 10_000,
+                min_bump_percent: rbf.min_bump_percent,
+                min_bump_absolute: rbf.min_bump_absolute,
+                max_ancestors: limits.max_ancestors,
+                max_descendants: limits.max_descendants,
+            },
+            memory: MemoryConfig::default(),
+        }
+    }
+}
+
+impl NodeConfig {
+    /// Loads the configuration the way the binary does: the file at `path` if
+    /// any (defaults otherwise), then the `BLOCKC_*` environment variables.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(NodeConfig)` - The validated configuration
+    /// * `Err(String)` - If the file can't be read, is malformed, or a value is invalid
+    pub fn load(path: Option<&Path>) -> Result<Self, String> {
+        let mut config = match path {
+            Some(path) => NodeConfig::from_file(path)?,
+            None => NodeConfig::default(),
+        };
+        config.apply_overrides(&|name| std::env::var(name).ok())?;
+        Ok(config)
+    }
+
+    /// Reads and validates a TOML file (no environment overrides).
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|error| format!("Cannot read {}: {}", path.display(), error))?;
+        NodeConfig::from_toml(&text).map_err(|error| format!("{}: {}", path.display(), error))
+    }
+
+    /// Parses and validates a TOML document; missing keys keep their default.
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        let table: Table = text.parse().map_err(|error: toml::de::Error| error.message().to_string())?;
+        let mut config = NodeConfig::default();
+        for (key, value) in &table {
+            match key.as_str() {
+                "data_dir" => config.data_dir = PathBuf::from(string(key, value)?),
+                "network" => {
+                    for (name, value) in section(key, value)? {
+                        match name.as_str() {
+                            "difficulty" => config.network.difficulty = integer(name, value)?,
+                            "block_reward" => config.network.block_reward = float(name, value)?,
+                            "hash_algorithm" => config.network.hash_algorithm = HashAlgorithm::parse(&string(name, value)?)?,
+                            "assume_valid" => config.network.assume_valid = Some(string(name, value)?),
+                            _ => return Err(unknown(key, name)),
+                        }
+                    }
+                }
+                "rpc" => {
+                    for (name, value) in section(key, value)? {
+                        match name.as_str() {
+                            "bind" => config.rpc.bind = string(name, value)?,
+                            "requests_per_second" => config.rpc.requests_per_second = float(name, value)?,
+                            "burst" => config.rpc.burst = float(name, value)?,
+                            _ => return Err(unknown(key, name)),
+                        }
+                    }
+                }
+                "mining" => {
+                    for (name, value) in section(key, value)? {
+                        match name.as_str() {
+                            "miner" => config.mining.miner = string(name, value)?,
+                            "blocks" => config.mining.blocks = integer(name, value)?,
+                            _ => return Err(unknown(key, name)),
+                        }
+                    }
+                }
+                "mempool" => {
+                    for (name, value) in section(key, value)? {
+                        match name.as_str() {
+                            "max_transactions" => config.mempool.max_transactions = integer(name, value)?,
+                            "min_bump_percent" => config.mempool.min_bump_percent = float(name, value)?,
+                            "min_bump_absolute" => config.mempool.min_bump_absolute = float(name, value)?,
+                            "max_ancestors" => config.mempool.max_ancestors = integer(name, value)?,
+                            "max_descendants" => config.mempool.max_descendants = integer(name, value)?,
+                            _ => return Err(unknown(key, name)),
+                        }
+                    }
+                }
+                "memory" => {
+                    for (name, value) in section(key, value)? {
+                        match name.as_str() {
+                            "mempool_bytes" => config.memory.mempool_bytes = Some(integer(name, value)?),
+                            "state_cache_bytes" => config.memory.state_cache_bytes = Some(integer(name, value)?),
+                            _ => return Err(unknown(key, name)),
+                        }
+                    }
+                }
+                _ => return Err(format!("Unknown key \"{}\"", key)),
+            }
+        }
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Overrides values with the `BLOCKC_*` variables `lookup` returns, then validates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::config::node::NodeConfig;
+    ///
+    /// let mut config = NodeConfig::default();
+    /// config.apply_overrides(&|name| (name == "BLOCKC_MINER").then(|| "Alice".to_string())).unwrap();
+    /// assert_eq!(config.mining.miner, "Alice");
+    ///
+    /// let bad = config.apply_overrides(&|name| (name == "BLOCKC_DIFFICULTY").then(|| "hard".to_string()));
+    /// assert!(bad.is_err());
+    /// ```
+    pub fn apply_overrides(&mut self, lookup: &dyn Fn(&str) -> Option<String>) -> Result<(), String> {
+        let get = |name: &str| lookup(&format!("{}{}", ENV_PREFIX, name)).map(|value| (format!("{}{}", ENV_PREFIX, name), value));
+        if let Some((_, value)) = get("DATA_DIR") {
+            self.data_dir = PathBuf::from(value);
+        }
+        if let Some((name, value)) = get("DIFFICULTY") {
+            self.network.difficulty = parse_env(&name, &value)?;
+        }
+        if let Some((name, value)) = get("BLOCK_REWARD") {
+            self.network.block_reward = parse_env(&name, &value)?;
+        }
+        if let Some((name, value)) = get("HASH_ALGORITHM") {
+            self.network.hash_algorithm = HashAlgorithm::parse(&value).map_err(|error| format!("{}: {}", name, error))?;
+        }
+        if let Some((_, value)) = get("ASSUME_VALID") {
+            self.network.assume_valid = Some(value);
+        }
+        if let Some((_, value)) = get("RPC_BIND") {
+            self.rpc.bind = value;
+        }
+        if let Some((name, value)) = get("RPC_REQUESTS_PER_SECOND") {
+            self.rpc.requests_per_second = parse_env(&name, &value)?;
+        }
+        if let Some((_, value)) = get("MINER") {
+            self.mining.miner = value;
+        }
+        if let Some((name, value)) = get("MINING_BLOCKS") {
+            self.mining.blocks = parse_env(&name, &value)?;
+        }
+        if let Some((name, value)) = get("MEMPOOL_MAX_TRANSACTIONS") {
+            self.mempool.max_transactions = parse_env(&name, &value)?;
+        }
+        self.validate()
+    }
+
+    /// Checks every value is usable.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.network.difficulty > MAX_DIFFICULTY {
+            return Err(format!("network.difficulty must be at most {}", MAX_DIFFICULTY));
+        }
+        if !self.network.block_reward.is_finite() || self.network.block_reward < 0.0 {
+            return Err("network.block_reward must be a non-negative number".to_string());
+        }
+        let is_hash = |hash: &str| hash.len() == 64 && hash.bytes().all(|byte| matches!(byte, b'0'..=b'9' | b'a'..=b'f'));
+        if let Some(hash) = &self.network.assume_valid
+            && !is_hash(hash) {
+            return Err(format!("network.assume_valid \"{}\" is not a block hash (64 lowercase hex digits)", hash));
+        }
+        let port = self.rpc.bind.rsplit_once(':').map(|(host, port)| (host, port.parse::<u16>()));
+        if !matches!(port, Some((host, Ok(_))) if !host.is_empty()) {
+            return Err(format!("rpc.bind \"{}\" is not a host:port address", self.rpc.bind));
+        }
+        if let Some(limit) = self.rpc.rate_limit() {
+            limit.validate().map_err(|error| format!("rpc.requests_per_second and rpc.burst: {}", error))?;
+        }
+        if self.mining.miner.is_empty() {
+            return Err("mining.miner cannot be empty".to_string());
+        }
+        if self.mempool.max_transactions == 0 {
+            return Err("mempool.max_transactions must be at least 1".to_string());
+        }
+        let bumps = [self.mempool.min_bump_percent, self.mempool.min_bump_absolute];
+        if bumps.iter().any(|bump| !bump.is_finite() || *bump < 0.0) {
+            return Err("mempool fee bumps must be non-negative numbers".to_string());
+        }
+        if self.mempool.max_ancestors == 0 || self.mempool.max_descendants == 0 {
+            return Err("mempool.max_ancestors and mempool.max_descendants must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// Creates a chain with the network parameters, mempool limits and memory ceilings.
+    pub fn new_chain(&self) -> Blockchain {
+        let mut chain = Blockchain::with_hash_algorithm(self.network.difficulty, self.network.block_reward, self.network.hash_algorithm);
+        self.apply_to(&mut chain);
+        chain
+    }
+
+    /// Applies the mempool limits and memory ceilings to an existing (e.g. loaded) chain.
+    pub fn apply_to(&self, chain: &mut Blockchain) {
+        let mempool = chain.mempool_mut();
+        mempool.set_max_transactions(Some(self.mempool.max_transactions));
+        mempool.set_rbf_policy(RbfPolicy {
+            min_bump_percent: self.mempool.min_bump_percent,
+            min_bump_absolute: self.mempool.min_bump_absolute,
+        });
+        mempool.set_package_limits(PackageLimits {
+            max_ancestors: self.mempool.max_ancestors,
+            max_descendants: self.mempool.max_descendants,
+        });
+        chain.set_memory_limits(MemoryLimits {
+            mempool: self.memory.mempool_bytes,
+            state_cache: self.memory.state_cache_bytes,
+        });
+    }
+}
+
+fn section<'a>(key: &str, value: &'a Value) -> Result<&'a Table, String> {
+    value.as_table().ok_or(format!("\"{}\" must be a table", key))
+}
+
+fn unknown(section: &str, key: &str) -> String {
+    format!("Unknown key \"{}.{}\"", section, key)
+}
+
+fn string(key: &str, value: &Value) -> Result<String, String> {
+    value.as_str().map(|text| text.to_string()).ok_or(format!("\"{}\" must be a string", key))
+}
+
+fn integer<T: TryFrom<i64>>(key: &str, value: &Value) -> Result<T, String> {
+    value.as_integer()
+        .and_then(|number| T::try_from(number).ok())
+        .ok_or(format!("\"{}\" must be a non-negative integer in range", key))
+}
+
+fn float(key: &str, value: &Value) -> Result<f64, String> {
+    match value {
+        Value::Float(number) => Ok(*number),
+        Value::Integer(number) => Ok(*number as f64),
+        _ => Err(format!("\"{}\" must be a number", key)),
+    }
+}
+
+fn parse_env<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("{} has an invalid value \"{}\"", name, value))
+}
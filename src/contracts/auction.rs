@@ -0,0 +1,162 @@
+use crate::helpers::{self};
+
+/// Returns the commitment of a sealed bid: the SHA-256 hash of the bidder, the
+/// bid and a secret salt.
+///
+/// Including the bidder means nobody can copy another bidder's commitment and
+/// reveal it as their own.
+pub fn commit_bid(bidder: &str, bid: f64, salt: &str) -> String {
+    helpers::helper_functions::sha256_hex(&format!("{}:{}:{}", bidder, bid, salt))
+}
+
+/// A bid held by an auction.
+///
+/// # Fields
+///
+/// * `bidder` - Address that placed the bid
+/// * `deposit` - Coins locked with the bid (the bid itself for open auctions)
+/// * `commitment` - Hash of the hidden bid (sealed auctions only)
+/// * `revealed` - The bid amount, once known (always known in open auctions)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bid {
+    pub bidder: String,
+    pub deposit: f64,
+    pub commitment: Option<String>,
+    pub revealed: Option<f64>,
+}
+
+/// An auction held by the chain.
+///
+/// * Open auction (`reveal_end` is `None`): bids are public and each one must beat
+///   the highest bid so far, until `bidding_end`.
+/// * Sealed-bid auction: until `bidding_end`, bidders lock a deposit together with
+///   the hash of their bid (`commit_bid`); then, until `reveal_end`, they reveal the
+///   bid and its salt. A bid above its deposit or never revealed doesn't count.
+///
+/// Once the auction is over, anyone can settle it: the highest bid at or above the
+/// reserve price wins and the seller is paid that bid. Every other coin locked
+/// (losing bids, unrevealed deposits, what the winner deposited above its bid)
+/// goes back to its bidder.
+///
+/// # Fields
+///
+/// * `id` - Id of the transaction that created the auction
+/// * `seller` - Address paid at settlement
+/// * `item` - Description of what is sold
+/// * `reserve_price` - Lowest winning bid
+/// * `bidding_end` - First height at which bids (or commitments) are refused
+/// * `reveal_end` - First height at which reveals are refused (sealed auctions)
+/// * `bids` - Bids placed, in order
+/// * `settled` - `true` once the auction was settled
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::entity::entity::Entity;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+///
+/// let mut chain = Blockchain::new();
+/// chain.add_block(Vec::new(), "Alice").unwrap();
+/// chain.add_block(Vec::new(), "Bob").unwrap();
+/// chain.add_block(Vec::new(), "Gallery").unwrap();
+/// let fee = chain.get_base_fee() * 2.0;
+/// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+/// let mut bob = Entity::new("Bob".to_string(), 45.0, Vec::new(), "b".to_string(), "b".to_string());
+/// let mut gallery = Entity::new("Gallery".to_string(), 45.0, Vec::new(), "g".to_string(), "g".to_string());
+///
+/// // Sealed bids until height 6, reveals until height 8
+/// let create = Transaction::create_auction(&mut gallery, "Painting", 5.0, 6, Some(8), fee).unwrap();
+/// let auction = create.id();
+/// chain.add_block(vec![create], "Miner").unwrap(); // height 4
+///
+/// let commit_a = Transaction::commit_sealed_bid(&mut alice, &auction, 12.0, "a-salt", 15.0, fee).unwrap();
+/// let commit_b = Transaction::commit_sealed_bid(&mut bob, &auction, 10.0, "b-salt", 20.0, fee).unwrap();
+/// chain.add_block(vec![commit_a, commit_b], "Miner").unwrap();
+///
+/// let reveal_a = Transaction::reveal_sealed_bid(&mut alice, &auction, 12.0, "a-salt", fee).unwrap();
+/// let reveal_b = Transaction::reveal_sealed_bid(&mut bob, &auction, 10.0, "b-salt", fee).unwrap();
+/// chain.add_block(vec![reveal_a, reveal_b], "Miner").unwrap();
+///
+/// chain.add_block(Vec::new(), "Miner").unwrap();
+/// let settle = Transaction::settle_auction(&mut gallery, &auction, fee).unwrap();
+/// chain.add_block(vec![settle], "Miner").unwrap(); // height 8
+///
+/// // Alice wins and pays her bid; Bob gets his whole deposit back
+/// let (winner, price) = chain.state().get_auction(&auction).unwrap().winning_bid().unwrap();
+/// assert_eq!((winner.as_str(), price), ("Alice", 12.0));
+/// assert!((chain.get_balance("Gallery") - (45.0 - 2.0 * fee + 12.0)).abs() < 1e-9);
+/// assert!((chain.get_balance("Alice") - (45.0 - 2.0 * fee - 12.0)).abs() < 1e-9);
+/// assert!((chain.get_balance("Bob") - (45.0 - 2.0 * fee)).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Auction {
+    pub id: String,
+    pub seller: String,
+    pub item: String,
+    pub reserve_price: f64,
+    pub bidding_end: u64,
+    pub reveal_end: Option<u64>,
+    pub bids: Vec<Bid>,
+    pub settled: bool,
+}
+
+impl Auction {
+    /// Returns `true` for a sealed-bid (commit-reveal) auction.
+    pub fn is_sealed(&self) -> bool {
+        self.reveal_end.is_some()
+    }
+
+    /// Returns `true` if bids (or commitments) are accepted in a block at `height`.
+    pub fn is_bidding_at(&self, height: u64) -> bool {
+        height < self.bidding_end
+    }
+
+    /// Returns `true` if sealed bids can be revealed in a block at `height`.
+    pub fn is_revealing_at(&self, height: u64) -> bool {
+        self.reveal_end.is_some_and(|end| height >= self.bidding_end && height < end)
+    }
+
+    /// Returns `true` if the auction can be settled in a block at `height`.
+    pub fn is_over_at(&self, height: u64) -> bool {
+        height >= self.reveal_end.unwrap_or(self.bidding_end)
+    }
+
+    /// Highest bid counted so far (revealed, covered by its deposit).
+    pub fn highest_bid(&self) -> Option<&Bid> {
+        self.highest_index().map(|index| &self.bids[index])
+    }
+
+    /// Winner and price: the highest counted bid, if it meets the reserve price.
+    pub fn winning_bid(&self) -> Option<(String, f64)> {
+        self.winning_index().map(|index| (self.bids[index].bidder.clone(), self.bids[index].revealed.unwrap_or(0.0)))
+    }
+
+    /// Coins to give back at settlement, by bidder (everything locked except the winning bid).
+    pub fn refunds(&self) -> Vec<(String, f64)> {
+        let winner = self.winning_index();
+        self.bids.iter().enumerate()
+            .map(|(index, bid)| {
+                let kept = if winner == Some(index) { bid.revealed.unwrap_or(0.0) } else { 0.0 };
+                (bid.bidder.clone(), bid.deposit - kept)
+            })
+            .filter(|(_, amount)| *amount > 0.0)
+            .collect()
+    }
+
+    fn highest_index(&self) -> Option<usize> {
+        let mut best: Option<(usize, f64)> = None;
+        for (index, bid) in self.bids.iter().enumerate() {
+            let Some(amount) = bid.revealed.filter(|amount| *amount <= bid.deposit) else { continue };
+            // Ties go to the earliest bid
+            if best.is_none_or(|(_, highest)| amount > highest) {
+                best = Some((index, amount));
+            }
+        }
+        best.map(|(index, _)| index)
+    }
+
+    fn winning_index(&self) -> Option<usize> {
+        self.highest_index().filter(|&index| self.bids[index].revealed.unwrap_or(0.0) >= self.reserve_price)
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+// The weighting is chosen by the transaction creating the ballot (`blockc_core`).
+pub use blockc_core::payload::VoteWeighting;
+
+/// Maximum number of options on a ballot.
+pub const MAX_BALLOT_OPTIONS: usize = 16;
+
+/// Final count of a closed ballot.
+///
+/// # Fields
+///
+/// * `totals` - Weight of the votes for each option, in option order
+/// * `voters` - Number of addresses that voted
+/// * `winner` - Index of the option with the most weight (`None` on a tie or without votes)
+#[derive(Debug, Clone, PartialEq)]
+pub struct BallotResult {
+    pub totals: Vec<f64>,
+    pub voters: usize,
+    pub winner: Option<usize>,
+}
+
+/// An application-level ballot held by the chain.
+///
+/// Anyone can create a ballot with a list of options and a voting window. Each
+/// address votes once, in blocks from `start_height` up to (not including)
+/// `end_height`. Once the window is over, the result is counted from the votes
+/// recorded on chain, so every node gets the same tally.
+///
+/// This is separate from the treasury's governance: a ballot decides nothing by
+/// itself, applications read its result.
+///
+/// # Fields
+///
+/// * `id` - Id of the transaction that created the ballot
+/// * `creator` - Address that created it
+/// * `question` - What is being decided
+/// * `options` - Possible answers
+/// * `start_height` - First height at which votes are accepted
+/// * `end_height` - First height at which votes are no longer accepted
+/// * `weighting` - How much each vote counts
+/// * `votes` - Votes cast (voter, option index, weight), in order
+/// * `snapshot` - Spendable balances when voting opened, that `Balance` votes
+///   weigh: taken after the block before `start_height`, or when the ballot is
+///   created if it opens in that block (`None` until then, and for
+///   `OnePerAddress` ballots)
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::contracts::ballot::VoteWeighting;
+/// use blockc::entity::entity::Entity;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+///
+/// let mut chain = Blockchain::new();
+/// chain.add_block(Vec::new(), "Alice").unwrap();
+/// chain.add_block(Vec::new(), "Bob").unwrap();
+/// let fee = chain.get_base_fee() * 2.0;
+/// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+/// let mut bob = Entity::new("Bob".to_string(), 45.0, Vec::new(), "b".to_string(), "b".to_string());
+///
+/// // Votes are accepted in blocks 4 and 5
+/// let options = vec!["Tabs".to_string(), "Spaces".to_string()];
+/// let create = Transaction::create_ballot(&mut alice, "Indentation?", options, 4, 6, VoteWeighting::OnePerAddress, fee).unwrap();
+/// let ballot = create.id();
+/// chain.add_block(vec![create], "Miner").unwrap();
+///
+/// let vote_a = Transaction::cast_vote(&mut alice, &ballot, 1, fee).unwrap();
+/// let vote_b = Transaction::cast_vote(&mut bob, &ballot, 1, fee).unwrap();
+/// chain.add_block(vec![vote_a, vote_b], "Miner").unwrap();
+/// assert!(chain.state().tally_ballot(&ballot).is_err()); // still open
+///
+/// chain.add_block(Vec::new(), "Miner").unwrap();
+/// let result = chain.state().tally_ballot(&ballot).unwrap();
+/// assert_eq!(result.totals, vec![0.0, 2.0]);
+/// assert_eq!(result.winner, Some(1));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ballot {
+    pub id: String,
+    pub creator: String,
+    pub question: String,
+    pub options: Vec<String>,
+    pub start_height: u64,
+    pub end_height: u64,
+    pub weighting: VoteWeighting,
+    pub votes: Vec<(String, usize, f64)>,
+    pub snapshot: Option<HashMap<String, f64>>,
+}
+
+impl Ballot {
+    /// Returns `true` if votes are accepted in a block at `height`.
+    pub fn is_open_at(&self, height: u64) -> bool {
+        height >= self.start_height && height < self.end_height
+    }
+
+    /// Returns the weight of a vote cast by `voter` (see `VoteWeighting`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::contracts::ballot::VoteWeighting;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    ///
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// chain.add_block(Vec::new(), "Bob").unwrap();
+    /// let fee = chain.get_base_fee() * 2.0;
+    /// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+    /// let mut bob = Entity::new("Bob".to_string(), 45.0, Vec::new(), "b".to_string(), "b".to_string());
+    ///
+    /// let options = vec!["Yes".to_string(), "No".to_string()];
+    /// let create = Transaction::create_ballot(&mut alice, "Fork?", options, 4, 6, VoteWeighting::Balance, fee).unwrap();
+    /// let ballot = create.id();
+    /// chain.add_block(vec![create], "Miner").unwrap();
+    ///
+    /// // Alice votes, then hands her coins to Bob, who votes with what he had at height 4
+    /// let vote = Transaction::cast_vote(&mut alice, &ballot, 0, fee).unwrap();
+    /// let payment = Transaction::create_payment(&mut alice, "Bob", 40.0, fee).unwrap();
+    /// chain.add_block(vec![vote, payment], "Miner").unwrap();
+    /// let vote = Transaction::cast_vote(&mut bob, &ballot, 1, fee).unwrap();
+    /// chain.add_block(vec![vote], "Miner").unwrap();
+    ///
+    /// let result = chain.state().tally_ballot(&ballot).unwrap();
+    /// assert_eq!(result.totals[1], 45.0);
+    /// assert_eq!(result.winner, Some(1));
+    /// ```
+    pub fn weight_of(&self, voter: &str) -> f64 {
+        match self.weighting {
+            VoteWeighting::OnePerAddress => 1.0,
+            VoteWeighting::Balance => self.snapshot.as_ref()
+                .and_then(|balances| balances.get(voter))
+                .copied()
+                .unwrap_or(0.0),
+        }
+    }
+
+    /// Returns `true` if `voter` already voted.
+    pub fn has_voted(&self, voter: &str) -> bool {
+        self.votes.iter().any(|(address, _, _)| address == voter)
+    }
+
+    /// Counts the votes cast so far.
+    pub fn tally(&self) -> BallotResult {
+        let mut totals = vec![0.0; self.options.len()];
+        for (_, option, weight) in &self.votes {
+            totals[*option] += weight;
+        }
+        let best = totals.iter().cloned().fold(0.0, f64::max);
+        let leaders: Vec<usize> = (0..totals.len()).filter(|&i| totals[i] == best).collect();
+        let winner = if best > 0.0 && leaders.len() == 1 { Some(leaders[0]) } else { None };
+        BallotResult {
+            totals: totals,
+            voters: self.votes.len(),
+            winner: winner,
+        }
+    }
+}
+
+/// Checks the definition of a new ballot created in a block at `height`.
+pub fn check_ballot(options: &[String], start_height: u64, end_height: u64, height: u64) -> Result<(), String> {
+    if options.len() < 2 || options.len() > MAX_BALLOT_OPTIONS {
+        return Err(format!("A ballot needs 2 to {} options", MAX_BALLOT_OPTIONS));
+    }
+    if options.iter().enumerate().any(|(i, option)| options[..i].contains(option)) {
+        return Err("Ballot options must be distinct".to_string());
+    }
+    if start_height < height || end_height <= start_height {
+        return Err(format!("Voting window {}..{} must start at or after height {} and not be empty", start_height, end_height, height));
+    }
+    Ok(())
+}
@@ -0,0 +1,177 @@
+use crate::entity::entity::Entity;
+
+// States are signed into channel close transactions, so they live in `blockc_core`.
+pub use blockc_core::payload::ChannelState;
+
+/// Number of blocks after a close during which a newer state can still be submitted.
+pub const DISPUTE_WINDOW: u64 = 5;
+
+/// Whether a channel is in use or being closed.
+///
+/// * `Open` - Payments can flow off chain
+/// * `Closing` - A state was submitted on chain; a newer one can replace it until `dispute_until`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChannelStatus {
+    Open,
+    Closing { state: ChannelState, dispute_until: u64 },
+}
+
+/// A bidirectional payment channel held by the chain.
+///
+/// The opener locks `capacity` on chain. From then on both parties pay each other
+/// by exchanging signed `ChannelState`s, without touching the chain. Closing takes
+/// two transactions: a close submitting a state, then, after `DISPUTE_WINDOW`
+/// blocks during which the other party can submit a newer state, a settle paying
+/// both balances.
+///
+/// # Fields
+///
+/// * `id` - Id of the funding transaction
+/// * `party_a` - Address of the opener (funds the channel)
+/// * `party_b` - Address of the counterparty
+/// * `capacity` - Amount locked in the channel
+/// * `status` - Open or closing
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::entity::entity::Entity;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+/// use blockc::contracts::channel::DISPUTE_WINDOW;
+///
+/// let mut chain = Blockchain::new();
+/// chain.add_block(Vec::new(), "Alice").unwrap();
+/// chain.add_block(Vec::new(), "Bob").unwrap();
+/// let fee = chain.get_base_fee() * 2.0;
+///
+/// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+/// let mut bob = Entity::new("Bob".to_string(), 45.0, Vec::new(), "b".to_string(), "b".to_string());
+///
+/// let open = Transaction::create_channel_open(&mut alice, "Bob", 20.0, fee).unwrap();
+/// chain.add_block(vec![open.clone()], "Miner").unwrap();
+/// let channel = chain.state().get_channel(&open.id()).unwrap().clone();
+///
+/// // Off chain: Alice pays Bob 5, then Bob pays Alice 1
+/// let mut first = channel.next_state(&channel.initial_state(), "Alice", 5.0).unwrap();
+/// channel.sign(&mut first, &alice).unwrap();
+/// channel.sign(&mut first, &bob).unwrap();
+/// let mut second = channel.next_state(&first, "Bob", 1.0).unwrap();
+/// channel.sign(&mut second, &alice).unwrap();
+/// channel.sign(&mut second, &bob).unwrap();
+///
+/// // Alice tries to close with the older state, Bob answers with the latest one
+/// let close = Transaction::create_channel_close(&mut alice, first, fee).unwrap();
+/// chain.add_block(vec![close], "Miner").unwrap();
+/// let dispute = Transaction::create_channel_close(&mut bob, second, fee).unwrap();
+/// chain.add_block(vec![dispute], "Miner").unwrap();
+///
+/// for _ in 0..DISPUTE_WINDOW {
+///     chain.add_block(Vec::new(), "Miner").unwrap();
+/// }
+/// let channel = chain.state().get_channel(&open.id()).unwrap().clone();
+/// let settle = Transaction::create_channel_settle(&mut bob, &channel, fee).unwrap();
+/// chain.add_block(vec![settle], "Miner").unwrap();
+///
+/// assert!((chain.get_balance("Bob") - (45.0 + 4.0 - 2.0 * fee)).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Channel {
+    pub id: String,
+    pub party_a: String,
+    pub party_b: String,
+    pub capacity: f64,
+    pub status: ChannelStatus,
+}
+
+impl Channel {
+    /// The state right after opening: everything belongs to the opener.
+    pub fn initial_state(&self) -> ChannelState {
+        ChannelState {
+            channel_id: self.id.clone(),
+            sequence: 0,
+            balance_a: self.capacity,
+            balance_b: 0.0,
+            signature_a: String::new(),
+            signature_b: String::new(),
+        }
+    }
+
+    /// Builds the (unsigned) state following `latest` once `payer` pays `amount`
+    /// to the other party.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ChannelState)` - The new state, to be signed by both parties
+    /// * `Err(String)` - If `payer` is not a party or cannot afford `amount`
+    pub fn next_state(&self, latest: &ChannelState, payer: &str, amount: f64) -> Result<ChannelState, String> {
+        if amount <= 0.0 {
+            return Err("Channel payment must be positive".to_string());
+        }
+        let (balance_a, balance_b) = if payer == self.party_a {
+            (latest.balance_a - amount, latest.balance_b + amount)
+        } else if payer == self.party_b {
+            (latest.balance_a + amount, latest.balance_b - amount)
+        } else {
+            return Err(format!("{} is not a party of channel {}", payer, self.id));
+        };
+        if balance_a < 0.0 || balance_b < 0.0 {
+            return Err(format!("{} cannot afford {} in channel {}", payer, amount, self.id));
+        }
+        Ok(ChannelState {
+            channel_id: self.id.clone(),
+            sequence: latest.sequence + 1,
+            balance_a: balance_a,
+            balance_b: balance_b,
+            signature_a: String::new(),
+            signature_b: String::new(),
+        })
+    }
+
+    /// Adds `party`'s signature to `state`.
+    pub fn sign(&self, state: &mut ChannelState, party: &Entity) -> Result<(), String> {
+        let signature = party.sign(&state.signing_data());
+        if party.address == self.party_a {
+            state.signature_a = signature;
+        } else if party.address == self.party_b {
+            state.signature_b = signature;
+        } else {
+            return Err(format!("{} is not a party of channel {}", party.address, self.id));
+        }
+        Ok(())
+    }
+
+    /// Checks that `state` can close this channel: same channel, both signatures,
+    /// balances adding up to the capacity.
+    ///
+    /// Signatures are only checked for presence: with the simplified signing scheme
+    /// of this crate, a signature cannot be verified without the private key.
+    pub fn check_state(&self, state: &ChannelState) -> Result<(), String> {
+        if state.channel_id != self.id {
+            return Err("State belongs to another channel".to_string());
+        }
+        if !state.is_fully_signed() {
+            return Err("Channel state must be signed by both parties".to_string());
+        }
+        if state.balance_a < 0.0 || state.balance_b < 0.0 || state.balance_a + state.balance_b != self.capacity {
+            return Err(format!("Channel balances must add up to {}", self.capacity));
+        }
+        Ok(())
+    }
+
+    /// Returns the amount `address` gets from `state`, or 0 if it is not a party.
+    pub fn balance_of(&self, state: &ChannelState, address: &str) -> f64 {
+        if address == self.party_a {
+            state.balance_a
+        } else if address == self.party_b {
+            state.balance_b
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns `true` if `address` is one of the two parties.
+    pub fn is_party(&self, address: &str) -> bool {
+        address == self.party_a || address == self.party_b
+    }
+}
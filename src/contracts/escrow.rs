@@ -0,0 +1,100 @@
+// The outcome is signed into escrow votes and lives in `blockc_core`.
+pub use blockc_core::payload::EscrowOutcome;
+
+/// Number of votes (out of buyer, seller and arbiter) needed to settle an escrow.
+pub const ESCROW_VOTES_REQUIRED: usize = 2;
+
+/// Where an escrow stands.
+///
+/// * `Funded` - The funds are locked, waiting for votes
+/// * `Released` - Two parties voted `Release`, the seller was paid
+/// * `Refunded` - Two parties voted `Refund`, the buyer got the funds back
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EscrowStatus {
+    Funded,
+    Released,
+    Refunded,
+}
+
+/// Funds held by the chain for a purchase, under a 2-of-3 arrangement.
+///
+/// The buyer locks `amount`. Buyer, seller and arbiter each get one vote; as soon
+/// as two of them vote for the same outcome, the chain pays the seller
+/// (`Release`) or the buyer (`Refund`). Buyer and seller can settle on their own
+/// when they agree; otherwise the arbiter sides with one of them.
+///
+/// # Fields
+///
+/// * `id` - Id of the transaction that funded the escrow
+/// * `buyer` - Address that locked the funds
+/// * `seller` - Address paid on release
+/// * `arbiter` - Third party deciding disputes
+/// * `amount` - Amount locked
+/// * `votes` - Votes cast so far
+/// * `status` - Funded, released or refunded
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::contracts::escrow::{EscrowOutcome, EscrowStatus};
+/// use blockc::entity::entity::Entity;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+///
+/// let mut chain = Blockchain::new();
+/// chain.add_block(Vec::new(), "Buyer").unwrap();
+/// chain.add_block(Vec::new(), "Judge").unwrap();
+/// let fee = chain.get_base_fee() * 2.0;
+///
+/// let mut buyer = Entity::new("Buyer".to_string(), 45.0, Vec::new(), "b".to_string(), "b".to_string());
+/// let mut judge = Entity::new("Judge".to_string(), 45.0, Vec::new(), "j".to_string(), "j".to_string());
+///
+/// let open = Transaction::create_escrow(&mut buyer, "Seller", "Judge", 20.0, fee).unwrap();
+/// chain.add_block(vec![open.clone()], "Miner").unwrap();
+///
+/// // The goods never arrived: buyer and arbiter both vote for a refund
+/// let vote1 = Transaction::create_escrow_vote(&mut buyer, &open.id(), EscrowOutcome::Refund, fee).unwrap();
+/// let vote2 = Transaction::create_escrow_vote(&mut judge, &open.id(), EscrowOutcome::Refund, fee).unwrap();
+/// chain.add_block(vec![vote1], "Miner").unwrap();
+/// assert_eq!(chain.state().get_escrow(&open.id()).unwrap().status, EscrowStatus::Funded);
+/// chain.add_block(vec![vote2], "Miner").unwrap();
+///
+/// assert_eq!(chain.state().get_escrow(&open.id()).unwrap().status, EscrowStatus::Refunded);
+/// assert_eq!(chain.get_balance("Seller"), 0.0);
+/// assert!((chain.get_balance("Buyer") - (45.0 - 2.0 * fee)).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Escrow {
+    pub id: String,
+    pub buyer: String,
+    pub seller: String,
+    pub arbiter: String,
+    pub amount: f64,
+    pub votes: Vec<(String, EscrowOutcome)>,
+    pub status: EscrowStatus,
+}
+
+impl Escrow {
+    /// Returns `true` if `address` is the buyer, the seller or the arbiter.
+    pub fn is_party(&self, address: &str) -> bool {
+        address == self.buyer || address == self.seller || address == self.arbiter
+    }
+
+    /// Returns `true` if `address` already voted.
+    pub fn has_voted(&self, address: &str) -> bool {
+        self.votes.iter().any(|(voter, _)| voter == address)
+    }
+
+    /// Number of votes for `outcome`.
+    pub fn votes_for(&self, outcome: EscrowOutcome) -> usize {
+        self.votes.iter().filter(|(_, vote)| *vote == outcome).count()
+    }
+
+    /// Address paid when the escrow settles with `outcome`.
+    pub fn beneficiary(&self, outcome: EscrowOutcome) -> &str {
+        match outcome {
+            EscrowOutcome::Release => &self.seller,
+            EscrowOutcome::Refund => &self.buyer,
+        }
+    }
+}
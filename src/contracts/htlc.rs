@@ -0,0 +1,52 @@
+use crate::helpers::{self};
+
+/// A hash time-locked contract (HTLC) held by the chain.
+///
+/// The sender locks `amount` for the receiver. The receiver can claim it by
+/// revealing a secret whose SHA-256 hash is `hash_lock`, as long as the claim is
+/// included before `expiry_height`. From `expiry_height` on, only the sender can
+/// take the funds back (refund).
+///
+/// # Fields
+///
+/// * `id` - Id of the transaction that created the contract
+/// * `sender` - Address that locked the funds (refunded after expiry)
+/// * `receiver` - Address that can claim the funds with the secret
+/// * `amount` - Amount locked
+/// * `hash_lock` - SHA-256 hash (hex) of the secret
+/// * `expiry_height` - First block height at which the claim is no longer possible and the refund is
+#[derive(Debug, Clone, PartialEq)]
+pub struct Htlc {
+    pub id: String,
+    pub sender: String,
+    pub receiver: String,
+    pub amount: f64,
+    pub hash_lock: String,
+    pub expiry_height: u64,
+}
+
+impl Htlc {
+    /// Returns `true` if `preimage` is the secret unlocking this contract.
+    pub fn matches_preimage(&self, preimage: &str) -> bool {
+        hash_secret(preimage) == self.hash_lock
+    }
+
+    /// Returns `true` if a block at `height` can no longer include a claim (and can include a refund).
+    pub fn is_expired_at(&self, height: u64) -> bool {
+        height >= self.expiry_height
+    }
+}
+
+/// Hashes a secret into the hash lock of an HTLC.
+///
+/// # Example
+///
+/// ```
+/// use blockc::contracts::htlc::hash_secret;
+///
+/// let hash_lock = hash_secret("my secret");
+/// assert_eq!(hash_lock.len(), 64);
+/// ```
+pub fn hash_secret(secret: &str) -> String {
+    helpers::helper_functions::sha256_hex(secret)
+}
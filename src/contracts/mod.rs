@@ -0,0 +1,7 @@
+pub mod htlc;
+pub mod channel;
+pub mod escrow;
+pub mod vesting;
+pub mod stream;
+pub mod ballot;
+pub mod auction;
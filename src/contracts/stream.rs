@@ -0,0 +1,63 @@
+/// A payment stream held by the chain.
+///
+/// The sender deposits coins that accrue to the receiver at `rate_per_block`,
+/// starting from the block that opened the stream, until the deposit runs out or
+/// the sender cancels. The receiver withdraws what has accrued whenever it wants;
+/// cancelling pays the receiver everything accrued so far and refunds the rest.
+///
+/// # Fields
+///
+/// * `id` - Id of the transaction that opened the stream
+/// * `sender` - Address paying (and refunded on cancel)
+/// * `receiver` - Address the coins accrue to
+/// * `deposit` - Total amount locked
+/// * `rate_per_block` - Amount accruing to the receiver with every block
+/// * `start_height` - Height of the block that opened the stream
+/// * `withdrawn` - Amount already withdrawn by the receiver
+///
+/// # Example
+///
+/// ```
+/// use blockc::contracts::stream::Stream;
+///
+/// let stream = Stream {
+///     id: "salary".to_string(),
+///     sender: "Company".to_string(),
+///     receiver: "Alice".to_string(),
+///     deposit: 10.0,
+///     rate_per_block: 2.0,
+///     start_height: 5,
+///     withdrawn: 3.0,
+/// };
+/// assert_eq!(stream.accrued_at(7), 4.0);
+/// assert_eq!(stream.withdrawable_at(7), 1.0);
+/// assert_eq!(stream.accrued_at(100), 10.0); // capped by the deposit
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stream {
+    pub id: String,
+    pub sender: String,
+    pub receiver: String,
+    pub deposit: f64,
+    pub rate_per_block: f64,
+    pub start_height: u64,
+    pub withdrawn: f64,
+}
+
+impl Stream {
+    /// Total amount accrued to the receiver at block `height`.
+    pub fn accrued_at(&self, height: u64) -> f64 {
+        let blocks = height.saturating_sub(self.start_height);
+        (self.rate_per_block * blocks as f64).min(self.deposit)
+    }
+
+    /// Amount the receiver can withdraw at block `height`.
+    pub fn withdrawable_at(&self, height: u64) -> f64 {
+        self.accrued_at(height) - self.withdrawn
+    }
+
+    /// Amount going back to the sender if the stream is cancelled at block `height`.
+    pub fn refundable_at(&self, height: u64) -> f64 {
+        self.deposit - self.accrued_at(height)
+    }
+}
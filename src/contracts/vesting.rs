@@ -0,0 +1,66 @@
+// The schedule is part of the grant transaction and lives in `blockc_core`.
+pub use blockc_core::payload::VestingSchedule;
+
+/// Coins granted to an address that unlock over time.
+///
+/// The coins are credited to the beneficiary right away, but the locked part
+/// cannot be spent: the chain rejects any transaction that would dip into it.
+///
+/// # Fields
+///
+/// * `id` - Id of the grant transaction
+/// * `grantor` - Address that paid the grant
+/// * `beneficiary` - Address receiving the coins
+/// * `amount` - Total amount granted
+/// * `schedule` - How the amount unlocks
+///
+/// # Example
+///
+/// ```
+/// use blockc::contracts::vesting::{VestingGrant, VestingSchedule};
+///
+/// let grant = VestingGrant {
+///     id: "grant".to_string(),
+///     grantor: "Company".to_string(),
+///     beneficiary: "Alice".to_string(),
+///     amount: 100.0,
+///     schedule: VestingSchedule::Linear { start_height: 10, end_height: 20 },
+/// };
+/// assert_eq!(grant.vested_at(5), 0.0);
+/// assert_eq!(grant.vested_at(15), 50.0);
+/// assert_eq!(grant.locked_at(25), 0.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct VestingGrant {
+    pub id: String,
+    pub grantor: String,
+    pub beneficiary: String,
+    pub amount: f64,
+    pub schedule: VestingSchedule,
+}
+
+impl VestingGrant {
+    /// Amount unlocked at block `height`.
+    pub fn vested_at(&self, height: u64) -> f64 {
+        match &self.schedule {
+            VestingSchedule::Linear { start_height, end_height } => {
+                if height <= *start_height {
+                    0.0
+                } else if height >= *end_height {
+                    self.amount
+                } else {
+                    self.amount * (height - start_height) as f64 / (end_height - start_height) as f64
+                }
+            }
+            VestingSchedule::Cliffs { unlocks } => unlocks.iter()
+                .filter(|(unlock_height, _)| height >= *unlock_height)
+                .map(|(_, unlocked)| unlocked)
+                .sum(),
+        }
+    }
+
+    /// Amount still locked at block `height`.
+    pub fn locked_at(&self, height: u64) -> f64 {
+        self.amount - self.vested_at(height)
+    }
+}
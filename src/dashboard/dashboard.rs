@@ -0,0 +1,285 @@
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Block as Panel, Borders, List, ListItem, ListState, Paragraph};
+use crate::block::block::Block;
+use crate::blockchain::blockchain::Blockchain;
+use crate::export::dot::short_hash;
+use crate::transactions::transactions::Transaction;
+
+/// Blocks the hash rate is estimated over.
+pub const HASH_RATE_WINDOW: usize = 10;
+
+/// Figures shown at the top of the dashboard.
+///
+/// # Fields
+///
+/// * `height` - Height of the tip
+/// * `tip` - Hash of the tip
+/// * `mempool` - Transactions waiting in the mempool
+/// * `difficulty` - Current difficulty
+/// * `base_fee` - Base fee of the next block
+/// * `hash_rate` - Estimated hashes per second (see `estimate_hash_rate`)
+/// * `peers` - Connected peers
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeStats {
+    pub height: usize,
+    pub tip: String,
+    pub mempool: usize,
+    pub difficulty: u32,
+    pub base_fee: f64,
+    pub hash_rate: f64,
+    pub peers: usize,
+}
+
+impl NodeStats {
+    /// Reads the figures of `chain`.
+    pub fn of(chain: &Blockchain, peers: usize) -> Self {
+        NodeStats {
+            height: chain.get_blocks().len() - 1,
+            tip: chain.get_latest_hash(),
+            mempool: chain.mempool().len(),
+            difficulty: chain.get_difficulty(),
+            base_fee: chain.get_base_fee(),
+            hash_rate: estimate_hash_rate(chain, HASH_RATE_WINDOW),
+            peers: peers,
+        }
+    }
+}
+
+/// Estimates the network's hash rate from the last `window` blocks.
+///
+/// A block needs `16^difficulty` hashes on average, so the rate is that work
+/// divided by the mean time between the blocks. Returns 0 with fewer than two
+/// mined blocks or when they share a timestamp.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::dashboard::dashboard::estimate_hash_rate;
+/// use blockc::helpers::clock::ManualClock;
+///
+/// let clock = ManualClock::new(1_000);
+/// let mut chain = Blockchain::with_clock(2, 50.0, Arc::new(clock.clone()));
+/// for _ in 0..3 {
+///     clock.advance(16);
+///     chain.add_block(Vec::new(), "Miner").unwrap();
+/// }
+/// // 256 hashes per block, one block every 16 seconds
+/// assert_eq!(estimate_hash_rate(&chain, 10), 16.0);
+/// ```
+pub fn estimate_hash_rate(chain: &Blockchain, window: usize) -> f64 {
+    let blocks = chain.get_blocks();
+    let mined = blocks.len() - 1;
+    let recent: Vec<&Block> = blocks.iter().skip(1 + mined.saturating_sub(window)).collect();
+    if recent.len() < 2 {
+        return 0.0;
+    }
+    let elapsed = recent[recent.len() - 1].time_stamp.saturating_sub(recent[0].time_stamp);
+    if elapsed == 0 {
+        return 0.0;
+    }
+    let work = 16f64.powi(chain.get_difficulty() as i32);
+    work * (recent.len() - 1) as f64 / elapsed as f64
+}
+
+/// Screen the dashboard shows.
+#[derive(Debug, Clone, PartialEq)]
+pub enum View {
+    /// Node figures and the recent blocks
+    Overview,
+    /// One block and its transactions
+    Block { height: usize },
+    /// One transaction of a block
+    Transaction { height: usize, index: usize },
+}
+
+/// Keys the dashboard reacts to (independent of the terminal library).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Key {
+    Up,
+    Down,
+    Enter,
+    Back,
+    Quit,
+}
+
+/// State of the terminal dashboard of a node.
+///
+/// It holds no chain: the caller reloads the chain as often as it likes and
+/// passes it to `handle_key` and `render`, so the dashboard follows a node
+/// running in another process.
+///
+/// # Fields
+///
+/// * `view` - Current screen
+/// * `selected` - Highlighted line of the current list (0 = first)
+/// * `peers` - Peer count to display
+/// * `quit` - Set when the user asked to leave
+///
+/// # Example
+///
+/// ```
+/// use ratatui::{backend::TestBackend, Terminal};
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::dashboard::dashboard::{Dashboard, Key, View};
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// chain.add_block(Vec::new(), "Miner").unwrap();
+///
+/// let mut dashboard = Dashboard::new(0);
+/// dashboard.handle_key(Key::Enter, &chain);
+/// assert_eq!(dashboard.view, View::Block { height: 1 });
+/// dashboard.handle_key(Key::Enter, &chain);
+/// assert_eq!(dashboard.view, View::Transaction { height: 1, index: 0 });
+///
+/// let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+/// terminal.draw(|frame| dashboard.render(frame, &chain)).unwrap();
+/// let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+/// assert!(screen.contains("To        Miner"));
+///
+/// dashboard.handle_key(Key::Back, &chain);
+/// dashboard.handle_key(Key::Back, &chain);
+/// assert_eq!(dashboard.view, View::Overview);
+/// dashboard.handle_key(Key::Quit, &chain);
+/// assert!(dashboard.quit);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Dashboard {
+    pub view: View,
+    pub selected: usize,
+    pub peers: usize,
+    pub quit: bool,
+}
+
+impl Dashboard {
+    /// Opens on the overview.
+    pub fn new(peers: usize) -> Self {
+        Dashboard {
+            view: View::Overview,
+            selected: 0,
+            peers: peers,
+            quit: false,
+        }
+    }
+
+    /// Moves the selection or the view.
+    ///
+    /// Up/Down move in the current list, Enter opens the selected block or
+    /// transaction, Back returns to the previous screen and Quit leaves.
+    pub fn handle_key(&mut self, key: Key, chain: &Blockchain) {
+        let length = self.list_length(chain);
+        match key {
+            Key::Quit => self.quit = true,
+            Key::Up => self.selected = self.selected.saturating_sub(1),
+            Key::Down => self.selected = (self.selected + 1).min(length.saturating_sub(1)),
+            Key::Enter => match self.view {
+                View::Overview if length > 0 => {
+                    self.view = View::Block { height: chain.get_blocks().len() - 1 - self.selected };
+                    self.selected = 0;
+                }
+                View::Block { height } if length > 0 => {
+                    self.view = View::Transaction { height: height, index: self.selected };
+                }
+                _ => {}
+            },
+            Key::Back => match self.view {
+                View::Transaction { height, index } => {
+                    self.view = View::Block { height: height };
+                    self.selected = index;
+                }
+                View::Block { height } => {
+                    self.view = View::Overview;
+                    self.selected = (chain.get_blocks().len() - 1).saturating_sub(height);
+                }
+                View::Overview => self.quit = true,
+            },
+        }
+    }
+
+    /// Draws the current screen.
+    pub fn render(&self, frame: &mut Frame, chain: &Blockchain) {
+        let [header, body, footer] = Layout::vertical([Constraint::Length(4), Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+
+        let stats = NodeStats::of(chain, self.peers);
+        let summary = format!(
+            "Height {}  Tip {}  Mempool {}  Peers {}\nDifficulty {}  Base fee {:.4}  Hash rate {:.1} H/s",
+            stats.height, short_hash(&stats.tip), stats.mempool, stats.peers, stats.difficulty, stats.base_fee, stats.hash_rate
+        );
+        frame.render_widget(Paragraph::new(summary).block(Panel::default().borders(Borders::ALL).title(" blockc node ")), header);
+
+        match &self.view {
+            View::Overview => {
+                let items = chain.get_blocks().iter().enumerate().rev()
+                    .map(|(height, block)| ListItem::new(block_line(height, block)))
+                    .collect::<Vec<_>>();
+                self.render_list(frame, body, " Recent blocks ", items);
+            }
+            View::Block { height } => match chain.get_blocks().get(*height) {
+                Some(block) => {
+                    let [info, transactions] = Layout::vertical([Constraint::Length(6), Constraint::Min(3)]).areas(body);
+                    let text = format!(
+                        "Hash      {}\nPrevious  {}\nTime {}  Nonce {}  Base fee {}\nMerkle    {}",
+                        block.block_hash, block.previous_block_hash, block.time_stamp, block.nonce, block.base_fee, block.merkle_root
+                    );
+                    frame.render_widget(Paragraph::new(text).block(Panel::default().borders(Borders::ALL).title(format!(" Block {} ", height))), info);
+                    let items = block.transaction.iter().map(|transaction| ListItem::new(transaction_line(transaction))).collect();
+                    self.render_list(frame, transactions, " Transactions ", items);
+                }
+                None => frame.render_widget(Paragraph::new("Block not found (chain reloaded?)"), body),
+            },
+            View::Transaction { height, index } => {
+                let transaction = chain.get_blocks().get(*height).and_then(|block| block.transaction.get(*index));
+                let text = match transaction {
+                    Some(transaction) => transaction_detail(transaction, *height),
+                    None => "Transaction not found (chain reloaded?)".to_string(),
+                };
+                frame.render_widget(Paragraph::new(text).block(Panel::default().borders(Borders::ALL).title(" Transaction ")), body);
+            }
+        }
+
+        frame.render_widget(Paragraph::new("Up/Down select  Enter open  Esc back  q quit"), footer);
+    }
+
+    fn render_list(&self, frame: &mut Frame, area: Rect, title: &str, items: Vec<ListItem>) {
+        let list = List::new(items)
+            .block(Panel::default().borders(Borders::ALL).title(title.to_string()))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("> ");
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
+    fn list_length(&self, chain: &Blockchain) -> usize {
+        match self.view {
+            View::Overview => chain.get_blocks().len(),
+            View::Block { height } => chain.get_blocks().get(height).map_or(0, |block| block.transaction.len()),
+            View::Transaction { .. } => 0,
+        }
+    }
+}
+
+fn block_line(height: usize, block: &Block) -> String {
+    format!("#{:<6} {}  {} tx  t={}", height, short_hash(&block.block_hash), block.transaction.len(), block.time_stamp)
+}
+
+fn transaction_line(transaction: &Transaction) -> String {
+    format!("{}  {} -> {}  {} (fee {})", short_hash(&transaction.id()), transaction.sender_address, transaction.receiver_address, transaction.amount, transaction.fee)
+}
+
+fn transaction_detail(transaction: &Transaction, height: usize) -> String {
+    let mut lines = vec![
+        format!("Id        {}", transaction.id()),
+        format!("Kind      {}", transaction.kind.name()),
+        format!("From      {}", transaction.sender_address),
+        format!("To        {}", transaction.receiver_address),
+        format!("Amount    {}  Fee {}  Nonce {}", transaction.amount, transaction.fee, transaction.nonce),
+        format!("Block     {}  Time {}", height, transaction.timestamp),
+    ];
+    if let Some(memo) = &transaction.memo {
+        lines.push(format!("Memo      {}", memo));
+    }
+    lines.join("\n")
+}
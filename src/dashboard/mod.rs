@@ -0,0 +1 @@
+pub mod dashboard;
\ No newline at end of file
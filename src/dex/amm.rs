@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+
+/// Highest swap fee a pool can charge, in basis points (10%).
+pub const MAX_POOL_FEE_BPS: u32 = 1_000;
+
+/// Basis points in 100%.
+const BPS: u128 = 10_000;
+
+/// Integer square root (largest `r` with `r * r <= n`).
+fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = n;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
+/// A constant-product liquidity pool trading two assets of the `AssetLedger`.
+///
+/// The pool always prices a swap so that `reserve_a * reserve_b` (`k`) never
+/// goes down: the more of one asset is taken out, the more expensive it gets.
+/// The swap fee stays in the reserves, so `k` grows with every trade and the
+/// providers earn it when they withdraw.
+///
+/// Providers receive shares for the liquidity they add. The first provider
+/// gets `sqrt(amount_a * amount_b)` shares; later providers get shares in
+/// proportion to what they add, based on the side they add least of (deposit in
+/// the pool's current ratio: any excess goes to the existing providers).
+///
+/// Quantities are whole units of each asset, and every division rounds in favour
+/// of the pool.
+///
+/// # Fields
+///
+/// * `id` - Id of the transaction that created the pool (also the address holding the reserves)
+/// * `asset_a` - First asset of the pair
+/// * `asset_b` - Second asset of the pair
+/// * `reserve_a` - Units of `asset_a` in the pool
+/// * `reserve_b` - Units of `asset_b` in the pool
+/// * `fee_bps` - Swap fee, in basis points of the amount sold
+/// * `total_shares` - Shares held by all providers
+/// * `shares` - Shares held, by provider address
+///
+/// # Example
+///
+/// ```
+/// use blockc::dex::amm::LiquidityPool;
+///
+/// let mut pool = LiquidityPool::new("pool", "gold", "silver", 30);
+/// assert_eq!(pool.add_liquidity("Alice", 1_000, 4_000).unwrap(), 2_000);
+///
+/// // Selling 100 gold: 0.3% fee, then x * y = k
+/// let out = pool.swap("gold", 100, 0).unwrap();
+/// assert_eq!(out, 362);
+/// assert_eq!((pool.reserve_a, pool.reserve_b), (1_100, 3_638));
+///
+/// // Alice takes everything back, fee included
+/// assert_eq!(pool.remove_liquidity("Alice", 2_000).unwrap(), (1_100, 3_638));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiquidityPool {
+    pub id: String,
+    pub asset_a: String,
+    pub asset_b: String,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub fee_bps: u32,
+    pub total_shares: u64,
+    pub shares: HashMap<String, u64>,
+}
+
+impl LiquidityPool {
+    /// Creates an empty pool for the pair `asset_a`/`asset_b`.
+    pub fn new(id: &str, asset_a: &str, asset_b: &str, fee_bps: u32) -> Self {
+        LiquidityPool {
+            id: id.to_string(),
+            asset_a: asset_a.to_string(),
+            asset_b: asset_b.to_string(),
+            reserve_a: 0,
+            reserve_b: 0,
+            fee_bps: fee_bps,
+            total_shares: 0,
+            shares: HashMap::new(),
+        }
+    }
+
+    /// Returns `true` if the pool trades `asset_id`.
+    pub fn trades(&self, asset_id: &str) -> bool {
+        self.asset_a == asset_id || self.asset_b == asset_id
+    }
+
+    /// Returns the shares `provider` holds.
+    pub fn shares_of(&self, provider: &str) -> u64 {
+        self.shares.get(provider).copied().unwrap_or(0)
+    }
+
+    /// Price of one unit of `asset_a` in units of `asset_b` (before fees and slippage).
+    pub fn price(&self) -> Option<f64> {
+        if self.reserve_a == 0 {
+            return None;
+        }
+        Some(self.reserve_b as f64 / self.reserve_a as f64)
+    }
+
+    /// Returns how many shares adding `amount_a` and `amount_b` would mint.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u64)` - The shares minted
+    /// * `Err(String)` - If an amount is 0 or the deposit is too small to mint a share
+    pub fn quote_add(&self, amount_a: u64, amount_b: u64) -> Result<u64, String> {
+        if amount_a == 0 || amount_b == 0 {
+            return Err("Liquidity must be added on both sides of the pool".to_string());
+        }
+        let minted = if self.total_shares == 0 {
+            isqrt(amount_a as u128 * amount_b as u128)
+        } else {
+            let by_a = amount_a as u128 * self.total_shares as u128 / self.reserve_a as u128;
+            let by_b = amount_b as u128 * self.total_shares as u128 / self.reserve_b as u128;
+            by_a.min(by_b)
+        };
+        if minted == 0 {
+            return Err("Deposit is too small to mint a pool share".to_string());
+        }
+        u64::try_from(minted).map_err(|_| "Deposit is too large".to_string())
+    }
+
+    /// Returns the amounts of `asset_a` and `asset_b` that burning `shares` would pay out.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((u64, u64))` - Units of `asset_a` and `asset_b`
+    /// * `Err(String)` - If `shares` is 0 or more than `provider` holds
+    pub fn quote_remove(&self, provider: &str, shares: u64) -> Result<(u64, u64), String> {
+        let held = self.shares_of(provider);
+        if shares == 0 || shares > held {
+            return Err(format!("{} holds {} shares of pool {}, cannot burn {}", provider, held, self.id, shares));
+        }
+        let amount_a = shares as u128 * self.reserve_a as u128 / self.total_shares as u128;
+        let amount_b = shares as u128 * self.reserve_b as u128 / self.total_shares as u128;
+        Ok((amount_a as u64, amount_b as u64))
+    }
+
+    /// Returns how many units of the other asset selling `amount_in` of `asset_in` buys.
+    ///
+    /// The fee is taken from `amount_in`, then the output keeps `k` constant:
+    /// `out = in_after_fee * reserve_out / (reserve_in + in_after_fee)`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u64)` - Units bought
+    /// * `Err(String)` - If the pool doesn't trade `asset_in`, has no liquidity, the
+    ///   amount is 0, or the output is below `min_out` (slippage protection)
+    pub fn quote_swap(&self, asset_in: &str, amount_in: u64, min_out: u64) -> Result<u64, String> {
+        let (reserve_in, reserve_out) = if asset_in == self.asset_a {
+            (self.reserve_a, self.reserve_b)
+        } else if asset_in == self.asset_b {
+            (self.reserve_b, self.reserve_a)
+        } else {
+            return Err(format!("Pool {} does not trade {}", self.id, asset_in));
+        };
+        if amount_in == 0 {
+            return Err("Swap amount must be positive".to_string());
+        }
+        if reserve_in == 0 || reserve_out == 0 {
+            return Err(format!("Pool {} has no liquidity", self.id));
+        }
+        let in_after_fee = amount_in as u128 * (BPS - self.fee_bps as u128);
+        let out = in_after_fee * reserve_out as u128 / (reserve_in as u128 * BPS + in_after_fee);
+        let out = out as u64;
+        if out == 0 || out < min_out {
+            return Err(format!("Swap would return {} units, below the minimum {}", out, min_out.max(1)));
+        }
+        Ok(out)
+    }
+
+    /// Adds liquidity for `provider` and returns the shares minted.
+    pub fn add_liquidity(&mut self, provider: &str, amount_a: u64, amount_b: u64) -> Result<u64, String> {
+        let minted = self.quote_add(amount_a, amount_b)?;
+        self.reserve_a += amount_a;
+        self.reserve_b += amount_b;
+        self.total_shares += minted;
+        *self.shares.entry(provider.to_string()).or_insert(0) += minted;
+        Ok(minted)
+    }
+
+    /// Burns `shares` of `provider` and returns the units of `asset_a` and `asset_b` paid out.
+    pub fn remove_liquidity(&mut self, provider: &str, shares: u64) -> Result<(u64, u64), String> {
+        let (amount_a, amount_b) = self.quote_remove(provider, shares)?;
+        self.reserve_a -= amount_a;
+        self.reserve_b -= amount_b;
+        self.total_shares -= shares;
+        if let Some(held) = self.shares.get_mut(provider) {
+            *held -= shares;
+            if *held == 0 {
+                self.shares.remove(provider);
+            }
+        }
+        Ok((amount_a, amount_b))
+    }
+
+    /// Sells `amount_in` of `asset_in` to the pool and returns the units of the other asset bought.
+    pub fn swap(&mut self, asset_in: &str, amount_in: u64, min_out: u64) -> Result<u64, String> {
+        let out = self.quote_swap(asset_in, amount_in, min_out)?;
+        if asset_in == self.asset_a {
+            self.reserve_a += amount_in;
+            self.reserve_b -= out;
+        } else {
+            self.reserve_b += amount_in;
+            self.reserve_a -= out;
+        }
+        Ok(out)
+    }
+
+    /// Returns the asset received when selling `asset_in`.
+    pub fn other_asset(&self, asset_in: &str) -> &str {
+        if asset_in == self.asset_a { &self.asset_b } else { &self.asset_a }
+    }
+}
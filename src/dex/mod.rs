@@ -0,0 +1,2 @@
+pub mod amm;
+pub mod orderbook;
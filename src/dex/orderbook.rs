@@ -0,0 +1,242 @@
+// Sides are signed into order transactions and live in `blockc_core`.
+pub use blockc_core::payload::Side;
+
+/// A limit order resting in the book.
+///
+/// # Fields
+///
+/// * `id` - Id of the transaction that placed the order (also the address holding its locked assets)
+/// * `owner` - Address that placed the order
+/// * `base` - Asset bought or sold
+/// * `quote` - Asset the price is expressed in
+/// * `side` - Buy or sell
+/// * `price` - Units of `quote` per unit of `base`
+/// * `quantity` - Units of `base` still to fill
+/// * `sequence` - Arrival order in the book (earlier orders fill first at the same price)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Order {
+    pub id: String,
+    pub owner: String,
+    pub base: String,
+    pub quote: String,
+    pub side: Side,
+    pub price: u64,
+    pub quantity: u64,
+    pub sequence: u64,
+}
+
+impl Order {
+    /// Units locked when the order is placed: `quantity` of `base` for a sell,
+    /// `price * quantity` of `quote` for a buy.
+    ///
+    /// # Returns
+    ///
+    /// * `Some((asset, units))` - The asset and units to lock
+    /// * `None` - If `price * quantity` overflows
+    pub fn locked(&self) -> Option<(&str, u64)> {
+        match self.side {
+            Side::Sell => Some((&self.base, self.quantity)),
+            Side::Buy => self.price.checked_mul(self.quantity).map(|units| (self.quote.as_str(), units)),
+        }
+    }
+
+    fn trades_on(&self, base: &str, quote: &str) -> bool {
+        self.base == base && self.quote == quote
+    }
+}
+
+/// A match between a buy and a sell order.
+///
+/// Trades execute at the price of the order that was already in the book (the
+/// maker); a buyer who offered more gets the difference back.
+///
+/// # Fields
+///
+/// * `buy_order` - Id of the buy order
+/// * `sell_order` - Id of the sell order
+/// * `buyer` - Owner of the buy order
+/// * `seller` - Owner of the sell order
+/// * `base` - Asset traded
+/// * `quote` - Asset paid
+/// * `price` - Units of `quote` paid per unit of `base`
+/// * `quantity` - Units of `base` traded
+/// * `height` - Height of the block in which the trade happened
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trade {
+    pub buy_order: String,
+    pub sell_order: String,
+    pub buyer: String,
+    pub seller: String,
+    pub base: String,
+    pub quote: String,
+    pub price: u64,
+    pub quantity: u64,
+    pub height: u64,
+}
+
+/// Limit order book with a deterministic matching engine.
+///
+/// An incoming order is matched right away against the resting orders of the
+/// other side, best price first and, at the same price, oldest first
+/// (price-time priority). Whatever is left rests in the book until it is filled
+/// or cancelled. Orders only match within the same `base`/`quote` market.
+///
+/// Matching only depends on the order in which orders arrive, so every node
+/// applying the same blocks ends with the same book and the same trades.
+///
+/// # Fields
+///
+/// * `orders` - Resting orders, in arrival order
+/// * `trades` - Every trade executed, oldest first
+/// * `next_sequence` - Sequence number of the next order
+///
+/// # Example
+///
+/// ```
+/// use blockc::dex::orderbook::{Order, OrderBook, Side};
+///
+/// let order = |id: &str, owner: &str, side, price, quantity| Order {
+///     id: id.to_string(), owner: owner.to_string(), base: "gold".to_string(), quote: "usd".to_string(),
+///     side: side, price: price, quantity: quantity, sequence: 0,
+/// };
+///
+/// let mut book = OrderBook::new();
+/// book.place(order("s1", "Alice", Side::Sell, 21, 5), 1);
+/// book.place(order("s2", "Bob", Side::Sell, 20, 5), 1);
+///
+/// // Takes Bob's cheaper order first, then part of Alice's, at their prices
+/// let trades = book.place(order("b1", "Carol", Side::Buy, 22, 7), 2);
+/// assert_eq!(trades.len(), 2);
+/// assert_eq!((trades[0].seller.as_str(), trades[0].price, trades[0].quantity), ("Bob", 20, 5));
+/// assert_eq!((trades[1].seller.as_str(), trades[1].price, trades[1].quantity), ("Alice", 21, 2));
+///
+/// assert_eq!(book.get("s1").unwrap().quantity, 3);
+/// assert!(book.get("b1").is_none()); // fully filled
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct OrderBook {
+    orders: Vec<Order>,
+    trades: Vec<Trade>,
+    next_sequence: u64,
+}
+
+impl OrderBook {
+    /// Creates an empty book.
+    pub fn new() -> Self {
+        OrderBook {
+            orders: Vec::new(),
+            trades: Vec::new(),
+            next_sequence: 0,
+        }
+    }
+
+    /// Returns the resting order with the given id.
+    pub fn get(&self, id: &str) -> Option<&Order> {
+        self.orders.iter().find(|order| order.id == id)
+    }
+
+    /// Returns the resting buy orders of a market, best (highest) price first.
+    pub fn bids(&self, base: &str, quote: &str) -> Vec<&Order> {
+        let mut bids: Vec<&Order> = self.orders.iter()
+            .filter(|order| order.side == Side::Buy && order.trades_on(base, quote))
+            .collect();
+        bids.sort_by(|a, b| b.price.cmp(&a.price).then(a.sequence.cmp(&b.sequence)));
+        bids
+    }
+
+    /// Returns the resting sell orders of a market, best (lowest) price first.
+    pub fn asks(&self, base: &str, quote: &str) -> Vec<&Order> {
+        let mut asks: Vec<&Order> = self.orders.iter()
+            .filter(|order| order.side == Side::Sell && order.trades_on(base, quote))
+            .collect();
+        asks.sort_by(|a, b| a.price.cmp(&b.price).then(a.sequence.cmp(&b.sequence)));
+        asks
+    }
+
+    /// Returns the resting orders placed by `owner`.
+    pub fn orders_of(&self, owner: &str) -> Vec<&Order> {
+        self.orders.iter().filter(|order| order.owner == owner).collect()
+    }
+
+    /// Returns every trade executed, oldest first.
+    pub fn trades(&self) -> &[Trade] {
+        &self.trades
+    }
+
+    /// Matches `order` against the book and rests whatever is left of it.
+    ///
+    /// The order's `sequence` is assigned by the book.
+    ///
+    /// # Arguments
+    ///
+    /// * `order` - The incoming order
+    /// * `height` - Height of the block placing it (recorded in the trades)
+    ///
+    /// # Returns
+    ///
+    /// The trades executed, in execution order (also appended to `trades`).
+    pub fn place(&mut self, mut order: Order, height: u64) -> Vec<Trade> {
+        order.sequence = self.next_sequence;
+        self.next_sequence += 1;
+
+        let mut executed = Vec::new();
+        while order.quantity > 0 {
+            let best = match order.side {
+                Side::Buy => self.asks(&order.base, &order.quote).first().filter(|ask| ask.price <= order.price).map(|ask| ask.id.clone()),
+                Side::Sell => self.bids(&order.base, &order.quote).first().filter(|bid| bid.price >= order.price).map(|bid| bid.id.clone()),
+            };
+            let Some(best) = best else { break };
+            let Some(index) = self.orders.iter().position(|resting| resting.id == best) else { break };
+
+            let maker = &mut self.orders[index];
+            let quantity = order.quantity.min(maker.quantity);
+            maker.quantity -= quantity;
+            order.quantity -= quantity;
+            let (buy, sell) = match order.side {
+                Side::Buy => (&order, &*maker),
+                Side::Sell => (&*maker, &order),
+            };
+            executed.push(Trade {
+                buy_order: buy.id.clone(),
+                sell_order: sell.id.clone(),
+                buyer: buy.owner.clone(),
+                seller: sell.owner.clone(),
+                base: order.base.clone(),
+                quote: order.quote.clone(),
+                price: maker.price,
+                quantity: quantity,
+                height: height,
+            });
+            if maker.quantity == 0 {
+                self.orders.remove(index);
+            }
+        }
+
+        if order.quantity > 0 {
+            self.orders.push(order);
+        }
+        self.trades.extend(executed.iter().cloned());
+        executed
+    }
+
+    /// Removes an order from the book and returns it (with what is left to fill).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Order)` - The cancelled order
+    /// * `Err(String)` - If no such order rests in the book or `owner` didn't place it
+    pub fn cancel(&mut self, id: &str, owner: &str) -> Result<Order, String> {
+        self.check_cancel(id, owner)?;
+        let index = self.orders.iter().position(|order| order.id == id).ok_or(format!("No open order {}", id))?;
+        Ok(self.orders.remove(index))
+    }
+
+    /// Checks that `owner` can cancel the order `id`.
+    pub fn check_cancel(&self, id: &str, owner: &str) -> Result<(), String> {
+        let order = self.get(id).ok_or(format!("No open order {}", id))?;
+        if order.owner != owner {
+            return Err(format!("Only {} can cancel order {}", order.owner, id));
+        }
+        Ok(())
+    }
+}
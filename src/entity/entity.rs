@@ -1,5 +1,6 @@
 use crate::transactions::transactions::Transaction;
-use sha2::{Sha256, Digest};
+use blockc_core::sign;
+use crate::helpers::random::RandomSource;
 
 
 
@@ -15,9 +16,10 @@ pub const MIN_BALANCE:f64 = 0.0 ;
 /// 
 /// * `address` - Unique identifier for this entity
 /// * `balance` - Current balance (in a simplified model, stored directly)
-/// * `history` - Vector of all transactions this entity has participated in
+/// * `history` - Ids of all transactions this entity has participated in
 /// * `public_key` - Public key for transaction verification
 /// * `private_key` - Private key for signing transactions
+/// * `nonce` - Nonce of the next transaction this entity signs
 /// 
 /// # Example
 /// 
@@ -36,9 +38,10 @@ pub const MIN_BALANCE:f64 = 0.0 ;
 pub struct Entity{
     pub address: String,
     pub balance: f64,
-    pub history: Vec<Transaction>,
+    pub history: Vec<String>,
     pub public_key: String,
     pub private_key: String,
+    pub nonce: u64,
 }
 
 
@@ -50,23 +53,70 @@ impl Entity{
     /// 
     /// * `address` - Unique identifier for this entity
     /// * `balance` - Initial balance for this entity
-    /// * `history` - Initial transaction history (usually empty Vec)
+    /// * `history` - Initial transaction ids (usually empty Vec)
     /// * `public_key` - Public key for transaction verification
     /// * `private_key` - Private key for signing transactions
     /// 
     /// # Returns
     /// 
     /// A new `Entity` instance.
-    pub fn new(address: String, balance: f64, history: Vec<Transaction>, public_key: String, private_key: String) -> Self{
+    pub fn new(address: String, balance: f64, history: Vec<String>, public_key: String, private_key: String) -> Self{
         Entity{
             address: address,
             balance: balance,
             history: history,
             public_key: public_key,
             private_key: private_key,
+            nonce: 0,
         }
     }
 
+    /// Creates an Entity with a fresh key pair drawn from `rng`.
+    /// 
+    /// The private key is 32 random bytes (hex) and the public key its SHA-256
+    /// hash. With a seeded `rng` the same keys come out every run, which keeps
+    /// demos and tests reproducible.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::simulation::rng::SimRng;
+    /// 
+    /// let alice = Entity::generate("Alice".to_string(), 10.0, &mut SimRng::new(1));
+    /// let again = Entity::generate("Alice".to_string(), 10.0, &mut SimRng::new(1));
+    /// assert_eq!(alice.private_key, again.private_key);
+    /// assert_eq!(alice.private_key.len(), 64);
+    /// 
+    /// let bob = Entity::generate("Bob".to_string(), 10.0, &mut SimRng::new(2));
+    /// assert_ne!(alice.private_key, bob.private_key);
+    /// ```
+    pub fn generate(address: String, balance: f64, rng: &mut dyn RandomSource) -> Self {
+        let private_key = rng.hex(32);
+        let public_key = sign::public_key(&private_key);
+        Entity::new(address, balance, Vec::new(), public_key, private_key)
+    }
+
+    /// Like `generate`, but the address is derived from the new public key
+    /// (see `sign::address`) instead of chosen.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::simulation::rng::SimRng;
+    /// use blockc_core::sign;
+    /// 
+    /// let entity = Entity::generate_derived(0.0, &mut SimRng::new(1));
+    /// assert_eq!(entity.address, sign::address(&entity.public_key));
+    /// assert!(entity.address.starts_with(sign::ADDRESS_PREFIX));
+    /// ```
+    pub fn generate_derived(balance: f64, rng: &mut dyn RandomSource) -> Self {
+        let private_key = rng.hex(32);
+        let public_key = sign::public_key(&private_key);
+        Entity::new(sign::address(&public_key), balance, Vec::new(), public_key, private_key)
+    }
+
 
     /// Returns the current balance of this entity.
     /// 
@@ -135,11 +185,28 @@ impl Entity{
 
     /// Adds a transaction to the entity's transaction history.
     /// 
+    /// Only the transaction's id is kept: the transaction itself stays with the
+    /// caller (and later in a block, see `Blockchain::get_history`), so recording
+    /// it copies nothing and cloning an entity only copies the ids.
+    /// 
     /// # Arguments
     /// 
     /// * `transaction` - The transaction to add to history
-    pub fn add_to_history(&mut self, transaction: Transaction) {
-        self.history.push(transaction);
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// 
+    /// let mut alice = Entity::new("Alice".to_string(), 100.0, Vec::new(), "pub".to_string(), "priv".to_string());
+    /// let mut bob = Entity::new("Bob".to_string(), 0.0, Vec::new(), "pub2".to_string(), "priv2".to_string());
+    /// let tx = Transaction::create_and_execute(&mut alice, &mut bob, 25.0).unwrap();
+    /// assert_eq!(alice.history, vec![tx.id()]);
+    /// assert_eq!(bob.history, vec![tx.id()]);
+    /// ```
+    pub fn add_to_history(&mut self, transaction: &Transaction) {
+        self.history.push(transaction.id());
     }
 
     /// Signs transaction data using the entity's private key.
@@ -160,17 +227,7 @@ impl Entity{
     /// This is a simplified signing mechanism for learning purposes.
     /// Real blockchains use more sophisticated cryptographic signatures (ECDSA, RSA, etc.).
     pub fn sign(&self, transaction_data: &str) -> String{
-        let data_to_sign = format!("{}{}", transaction_data, self.private_key);
-        
-        // Hash it
-        let mut hasher = Sha256::new();
-        hasher.update(data_to_sign.as_bytes());
-        let result = hasher.finalize();
-        
-        // Convert to hex string (signature)
-        result.iter()
-            .map(|byte| format!("{:02x}", byte))
-            .collect::<String>()
+        sign::sign(transaction_data, &self.private_key)
     }
     
 }
\ No newline at end of file
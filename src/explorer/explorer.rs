@@ -0,0 +1,264 @@
+use crate::blockchain::blockchain::Blockchain;
+use crate::export::dot::short_hash;
+use crate::transactions::transactions::{Transaction, COINBASE_ADDRESS};
+
+/// Path under which the explorer's pages are served.
+pub const EXPLORER_ROOT: &str = "/explorer";
+
+/// Blocks listed on the home page.
+pub const RECENT_BLOCKS: usize = 20;
+
+/// An HTML page of the explorer.
+///
+/// # Fields
+///
+/// * `status` - HTTP status code
+/// * `body` - The HTML document
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExplorerPage {
+    pub status: u16,
+    pub body: String,
+}
+
+/// Renders the explorer page at `path` (with its query string).
+///
+/// Pages:
+///
+/// * `/explorer` - Chain figures, recent blocks and the search box
+/// * `/explorer/block/{height or hash}` - A block and its transactions
+/// * `/explorer/tx/{id}` - A confirmed or pending transaction
+/// * `/explorer/address/{address}` - Balance, nonce and history of an address
+/// * `/explorer/search?q=...` - A height, block hash, transaction id or address
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::explorer::explorer::page;
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// chain.add_block(Vec::new(), "Alice").unwrap();
+///
+/// let home = page(&chain, "/explorer");
+/// assert_eq!(home.status, 200);
+/// assert!(home.body.contains("href=\"/explorer/block/1\""));
+///
+/// let found = page(&chain, "/explorer/search?q=Alice");
+/// assert!(found.body.contains("Balance</th><td>45</td>"));
+/// assert!(page(&chain, "/explorer/search?q=1").body.contains("<h1>Block 1</h1>"));
+///
+/// // User data is escaped
+/// assert!(page(&chain, "/explorer/address/%3Cscript%3E").body.contains("&lt;script&gt;"));
+/// assert_eq!(page(&chain, "/explorer/block/9").status, 404);
+/// ```
+pub fn page(chain: &Blockchain, path: &str) -> ExplorerPage {
+    let (path, query) = path.split_once('?').unwrap_or((path, ""));
+    let rest = path.strip_prefix(EXPLORER_ROOT).unwrap_or(path).trim_matches('/');
+    let segments: Vec<String> = rest.split('/').filter(|segment| !segment.is_empty()).map(percent_decode).collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    match segments.as_slice() {
+        [] => ok("blockc explorer", home(chain)),
+        ["block", key] => block_page(chain, key),
+        ["tx", id] => transaction_page(chain, id),
+        ["address", address] => ok(&format!("Address {}", address), address_page(chain, address)),
+        ["search"] => {
+            let term = query.split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .find(|(key, _)| *key == "q")
+                .map(|(_, value)| percent_decode(value))
+                .unwrap_or_default();
+            search(chain, term.trim())
+        }
+        _ => not_found("No such page"),
+    }
+}
+
+fn search(chain: &Blockchain, term: &str) -> ExplorerPage {
+    if term.is_empty() {
+        return ok("blockc explorer", home(chain));
+    }
+    if term.parse::<usize>().is_ok() || chain.get_blocks().iter().any(|block| block.block_hash == term) {
+        return block_page(chain, term);
+    }
+    if find_transaction(chain, term).is_some() {
+        return transaction_page(chain, term);
+    }
+    ok(&format!("Address {}", term), address_page(chain, term))
+}
+
+fn home(chain: &Blockchain) -> String {
+    let blocks = chain.get_blocks();
+    let rows: String = blocks.iter().enumerate().rev().take(RECENT_BLOCKS)
+        .map(|(height, block)| format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            block_link(height, &height.to_string()), hash_cell(&block.block_hash), block.transaction.len(), block.time_stamp
+        ))
+        .collect();
+    format!(
+        "<h1>blockc explorer</h1>\
+         <table><tr><th>Height</th><td>{}</td></tr><tr><th>Difficulty</th><td>{}</td></tr>\
+         <tr><th>Base fee</th><td>{}</td></tr><tr><th>Pending</th><td>{}</td></tr></table>\
+         <h2>Recent blocks</h2><table><tr><th>Height</th><th>Hash</th><th>Transactions</th><th>Time</th></tr>{}</table>",
+        blocks.len() - 1, chain.get_difficulty(), chain.get_base_fee(), chain.mempool().len(), rows
+    )
+}
+
+fn block_page(chain: &Blockchain, key: &str) -> ExplorerPage {
+    let blocks = chain.get_blocks();
+    let found = match key.parse::<usize>() {
+        Ok(height) => blocks.get(height).map(|block| (height, block)),
+        Err(_) => blocks.iter().enumerate().find(|(_, block)| block.block_hash == key),
+    };
+    let Some((height, block)) = found else {
+        return not_found(&format!("No block {}", key));
+    };
+    let previous = if height == 0 { "none".to_string() } else { block_link(height - 1, &block.previous_block_hash) };
+    let next = blocks.get(height + 1).map_or("none".to_string(), |next| block_link(height + 1, &next.block_hash));
+    let body = format!(
+        "<h1>Block {}</h1><table>\
+         <tr><th>Hash</th><td>{}</td></tr><tr><th>Previous</th><td>{}</td></tr><tr><th>Next</th><td>{}</td></tr>\
+         <tr><th>Time</th><td>{}</td></tr><tr><th>Nonce</th><td>{}</td></tr><tr><th>Base fee</th><td>{}</td></tr>\
+         <tr><th>Merkle root</th><td>{}</td></tr><tr><th>State root</th><td>{}</td></tr></table>\
+         <h2>Transactions</h2>{}",
+        height, escape(&block.block_hash), previous, next, block.time_stamp, block.nonce, block.base_fee,
+        escape(&block.merkle_root), escape(&block.state_root), transaction_table(block.transaction.iter().map(|transaction| (Some(height as u64), transaction)))
+    );
+    ok(&format!("Block {}", height), body)
+}
+
+fn transaction_page(chain: &Blockchain, id: &str) -> ExplorerPage {
+    let Some((height, transaction)) = find_transaction(chain, id) else {
+        return not_found(&format!("No transaction {}", id));
+    };
+    let status = match height {
+        Some(height) => format!("Confirmed in {}", block_link(height as usize, &format!("block {}", height))),
+        None => "Pending".to_string(),
+    };
+    let memo = transaction.memo.as_deref().map(escape).unwrap_or_default();
+    let body = format!(
+        "<h1>Transaction</h1><table>\
+         <tr><th>Id</th><td>{}</td></tr><tr><th>Status</th><td>{}</td></tr><tr><th>Kind</th><td>{}</td></tr>\
+         <tr><th>From</th><td>{}</td></tr><tr><th>To</th><td>{}</td></tr><tr><th>Amount</th><td>{}</td></tr>\
+         <tr><th>Fee</th><td>{}</td></tr><tr><th>Nonce</th><td>{}</td></tr><tr><th>Time</th><td>{}</td></tr>\
+         <tr><th>Memo</th><td>{}</td></tr></table>",
+        escape(&transaction.id()), status, transaction.kind.name(), address_link(&transaction.sender_address),
+        address_link(&transaction.receiver_address), transaction.amount, transaction.fee, transaction.nonce, transaction.timestamp, memo
+    );
+    ok("Transaction", body)
+}
+
+fn address_page(chain: &Blockchain, address: &str) -> String {
+    let history = chain.get_history(address);
+    let pending = chain.mempool().transactions().iter()
+        .filter(|transaction| transaction.sender_address == address || transaction.pays(address))
+        .map(|transaction| (None, transaction));
+    format!(
+        "<h1>Address {}</h1><table><tr><th>Balance</th><td>{}</td></tr><tr><th>Nonce</th><td>{}</td></tr></table>\
+         <h2>Transactions</h2>{}",
+        escape(address), chain.get_balance(address), chain.get_nonce(address),
+        transaction_table(history.into_iter().rev().map(|(height, transaction)| (Some(height), transaction)).chain(pending))
+    )
+}
+
+/// Finds a confirmed (with its height) or pending transaction.
+fn find_transaction<'a>(chain: &'a Blockchain, id: &str) -> Option<(Option<u64>, &'a Transaction)> {
+    if let Some((height, transaction)) = chain.find_transaction(id) {
+        return Some((Some(height), transaction));
+    }
+    chain.mempool().transactions().iter().find(|transaction| transaction.id() == id).map(|transaction| (None, transaction))
+}
+
+fn transaction_table<'a>(transactions: impl Iterator<Item = (Option<u64>, &'a Transaction)>) -> String {
+    let rows: String = transactions
+        .map(|(height, transaction)| format!(
+            "<tr><td>{}</td><td><a href=\"{}/tx/{}\">{}</a></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            height.map_or("pending".to_string(), |height| block_link(height as usize, &height.to_string())),
+            EXPLORER_ROOT, transaction.id(), short_hash(&transaction.id()),
+            address_link(&transaction.sender_address), address_link(&transaction.receiver_address), transaction.amount, transaction.fee
+        ))
+        .collect();
+    format!("<table><tr><th>Block</th><th>Id</th><th>From</th><th>To</th><th>Amount</th><th>Fee</th></tr>{}</table>", rows)
+}
+
+fn block_link(height: usize, text: &str) -> String {
+    format!("<a href=\"{}/block/{}\">{}</a>", EXPLORER_ROOT, height, escape(text))
+}
+
+fn address_link(address: &str) -> String {
+    if address == COINBASE_ADDRESS {
+        return address.to_string();
+    }
+    format!("<a href=\"{}/address/{}\">{}</a>", EXPLORER_ROOT, percent_encode(address), escape(address))
+}
+
+fn hash_cell(hash: &str) -> String {
+    format!("<code title=\"{}\">{}</code>", escape(hash), escape(short_hash(hash)))
+}
+
+fn ok(title: &str, body: String) -> ExplorerPage {
+    ExplorerPage {
+        status: 200,
+        body: layout(title, &body),
+    }
+}
+
+fn not_found(message: &str) -> ExplorerPage {
+    ExplorerPage {
+        status: 404,
+        body: layout("Not found", &format!("<h1>Not found</h1><p>{}</p>", escape(message))),
+    }
+}
+
+fn layout(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{}</title>\
+         <style>body{{font-family:sans-serif;margin:2em}}table{{border-collapse:collapse;margin-bottom:1em}}\
+         td,th{{border:1px solid #ccc;padding:4px 8px;text-align:left}}code{{font-size:90%}}</style></head>\
+         <body><form action=\"{root}/search\"><a href=\"{root}\">Home</a> \
+         <input name=\"q\" size=\"70\" placeholder=\"Height, block hash, transaction id or address\"> \
+         <button>Search</button></form>{}</body></html>",
+        escape(title), body, root = EXPLORER_ROOT
+    )
+}
+
+/// Escapes text for HTML content and attributes.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;").replace('\'', "&#39;")
+}
+
+/// Decodes `%XX` escapes and `+` (space) of a URL component; invalid escapes stay as they are.
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let hex = bytes.get(index + 1..index + 3)
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u8::from_str_radix(digits, 16).ok());
+        match (bytes[index], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            (b'+', _) => {
+                decoded.push(b' ');
+                index += 1;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Encodes a URL path segment.
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
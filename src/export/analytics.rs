@@ -0,0 +1,175 @@
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray, UInt32Array, UInt64Array};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use parquet::arrow::ArrowWriter;
+use crate::block::block::Block;
+use crate::blockchain::blockchain::Blockchain;
+
+/// Blocks converted into each record batch written to Parquet.
+pub const BLOCKS_PER_BATCH: usize = 1024;
+
+/// File the blocks are written to by `write_parquet`.
+pub const BLOCKS_PARQUET: &str = "blocks.parquet";
+
+/// File the transactions are written to by `write_parquet`.
+pub const TRANSACTIONS_PARQUET: &str = "transactions.parquet";
+
+/// Columns of the block table (one row per block).
+pub fn blocks_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("height", DataType::UInt64, false),
+        Field::new("hash", DataType::Utf8, false),
+        Field::new("previous_hash", DataType::Utf8, false),
+        Field::new("timestamp", DataType::UInt32, false),
+        Field::new("nonce", DataType::UInt32, false),
+        Field::new("base_fee", DataType::Float64, false),
+        Field::new("merkle_root", DataType::Utf8, false),
+        Field::new("state_root", DataType::Utf8, false),
+        Field::new("transaction_count", DataType::UInt64, false),
+    ]))
+}
+
+/// Columns of the transaction table (one row per confirmed transaction).
+pub fn transactions_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("height", DataType::UInt64, false),
+        Field::new("block_timestamp", DataType::UInt32, false),
+        Field::new("transaction_id", DataType::Utf8, false),
+        Field::new("kind", DataType::Utf8, false),
+        Field::new("sender", DataType::Utf8, false),
+        Field::new("receiver", DataType::Utf8, false),
+        Field::new("amount", DataType::Float64, false),
+        Field::new("fee", DataType::Float64, false),
+        Field::new("nonce", DataType::UInt64, false),
+        Field::new("timestamp", DataType::UInt32, false),
+        Field::new("gas_limit", DataType::UInt64, false),
+        Field::new("gas_price", DataType::Float64, false),
+        Field::new("memo", DataType::Utf8, true),
+    ]))
+}
+
+/// Converts blocks into an Arrow record batch with the `blocks_schema` columns.
+///
+/// # Arguments
+///
+/// * `first_height` - Height of `blocks[0]`
+/// * `blocks` - Consecutive blocks
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::export::analytics::{blocks_batch, transactions_batch};
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// chain.add_block(Vec::new(), "Miner").unwrap();
+///
+/// let blocks = blocks_batch(0, &chain.get_blocks().to_vec()).unwrap();
+/// assert_eq!(blocks.num_rows(), 2);
+///
+/// // The reward and the treasury share
+/// let transactions = transactions_batch(0, &chain.get_blocks().to_vec()).unwrap();
+/// assert_eq!(transactions.num_rows(), 2);
+/// assert_eq!(transactions.schema().field(5).name(), "receiver");
+/// ```
+pub fn blocks_batch(first_height: u64, blocks: &[Block]) -> Result<RecordBatch, String> {
+    let strings = |value: fn(&Block) -> &str| Arc::new(StringArray::from_iter_values(blocks.iter().map(value))) as ArrayRef;
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(UInt64Array::from_iter_values((0..blocks.len() as u64).map(|offset| first_height + offset))),
+        strings(|block| &block.block_hash),
+        strings(|block| &block.previous_block_hash),
+        Arc::new(UInt32Array::from_iter_values(blocks.iter().map(|block| block.time_stamp))),
+        Arc::new(UInt32Array::from_iter_values(blocks.iter().map(|block| block.nonce))),
+        Arc::new(Float64Array::from_iter_values(blocks.iter().map(|block| block.base_fee))),
+        strings(|block| &block.merkle_root),
+        strings(|block| &block.state_root),
+        Arc::new(UInt64Array::from_iter_values(blocks.iter().map(|block| block.transaction.len() as u64))),
+    ];
+    RecordBatch::try_new(blocks_schema(), columns).map_err(|error| error.to_string())
+}
+
+/// Converts the transactions of blocks into an Arrow record batch with the
+/// `transactions_schema` columns.
+///
+/// # Arguments
+///
+/// * `first_height` - Height of `blocks[0]`
+/// * `blocks` - Consecutive blocks
+pub fn transactions_batch(first_height: u64, blocks: &[Block]) -> Result<RecordBatch, String> {
+    let rows: Vec<_> = blocks.iter().enumerate()
+        .flat_map(|(offset, block)| block.transaction.iter().map(move |transaction| (first_height + offset as u64, block, transaction)))
+        .collect();
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(UInt64Array::from_iter_values(rows.iter().map(|(height, _, _)| *height))),
+        Arc::new(UInt32Array::from_iter_values(rows.iter().map(|(_, block, _)| block.time_stamp))),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|(_, _, transaction)| transaction.id()))),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|(_, _, transaction)| transaction.kind.name()))),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|(_, _, transaction)| &transaction.sender_address))),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|(_, _, transaction)| &transaction.receiver_address))),
+        Arc::new(Float64Array::from_iter_values(rows.iter().map(|(_, _, transaction)| transaction.amount))),
+        Arc::new(Float64Array::from_iter_values(rows.iter().map(|(_, _, transaction)| transaction.fee))),
+        Arc::new(UInt64Array::from_iter_values(rows.iter().map(|(_, _, transaction)| transaction.nonce))),
+        Arc::new(UInt32Array::from_iter_values(rows.iter().map(|(_, _, transaction)| transaction.timestamp))),
+        Arc::new(UInt64Array::from_iter_values(rows.iter().map(|(_, _, transaction)| transaction.gas_limit))),
+        Arc::new(Float64Array::from_iter_values(rows.iter().map(|(_, _, transaction)| transaction.gas_price))),
+        Arc::new(StringArray::from_iter(rows.iter().map(|(_, _, transaction)| transaction.memo.as_deref()))),
+    ];
+    RecordBatch::try_new(transactions_schema(), columns).map_err(|error| error.to_string())
+}
+
+/// Writes the chain as two Parquet files in `directory`: `BLOCKS_PARQUET` and
+/// `TRANSACTIONS_PARQUET`, built `BLOCKS_PER_BATCH` blocks at a time.
+///
+/// The files load directly with `pandas.read_parquet`, `polars.read_parquet`
+/// or DuckDB's `read_parquet`.
+///
+/// # Returns
+///
+/// * `Ok((usize, usize))` - Number of blocks and transactions written
+/// * `Err(String)` - If a file can't be written
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::export::analytics::write_parquet;
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// chain.add_block(Vec::new(), "Miner").unwrap();
+///
+/// let dir = std::env::temp_dir().join(format!("blockc-parquet-doc-{}", std::process::id()));
+/// std::fs::create_dir_all(&dir).unwrap();
+/// assert_eq!(write_parquet(&chain, &dir).unwrap(), (2, 2));
+///
+/// // Read it back
+/// use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+/// let file = std::fs::File::open(dir.join("transactions.parquet")).unwrap();
+/// let reader = ParquetRecordBatchReaderBuilder::try_new(file).unwrap().build().unwrap();
+/// assert_eq!(reader.map(|batch| batch.unwrap().num_rows()).sum::<usize>(), 2);
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub fn write_parquet(chain: &Blockchain, directory: &Path) -> Result<(usize, usize), String> {
+    let blocks = chain.get_blocks();
+    let mut block_writer = writer(&directory.join(BLOCKS_PARQUET), blocks_schema())?;
+    let mut transaction_writer = writer(&directory.join(TRANSACTIONS_PARQUET), transactions_schema())?;
+    let mut transactions = 0;
+    for start in (0..blocks.len()).step_by(BLOCKS_PER_BATCH) {
+        // Only one batch of blocks is copied at a time
+        let chunk: Vec<Block> = blocks.iter().skip(start).take(BLOCKS_PER_BATCH).cloned().collect();
+        let first_height = start as u64;
+        block_writer.write(&blocks_batch(first_height, &chunk)?).map_err(|error| error.to_string())?;
+        let batch = transactions_batch(first_height, &chunk)?;
+        transactions += batch.num_rows();
+        transaction_writer.write(&batch).map_err(|error| error.to_string())?;
+    }
+    block_writer.close().map_err(|error| error.to_string())?;
+    transaction_writer.close().map_err(|error| error.to_string())?;
+    Ok((blocks.len(), transactions))
+}
+
+fn writer(path: &Path, schema: SchemaRef) -> Result<ArrowWriter<File>, String> {
+    let file = File::create(path).map_err(|error| format!("Cannot create {}: {}", path.display(), error))?;
+    ArrowWriter::try_new(file, schema, None).map_err(|error| error.to_string())
+}
@@ -0,0 +1,101 @@
+#[cfg(feature = "fs")]
+use std::fs::File;
+#[cfg(feature = "fs")]
+use std::io::BufWriter;
+use std::io::Write;
+#[cfg(feature = "fs")]
+use std::path::Path;
+use crate::blockchain::blockchain::Blockchain;
+
+/// Header row of the ledger CSV.
+pub const CSV_HEADER: &str = "height,transaction_id,timestamp,sender,receiver,amount,fee";
+
+/// Which confirmed transactions a ledger export keeps.
+///
+/// Times are compared with the timestamp of the block that confirmed the
+/// transaction (the `timestamp` column). Unset fields keep everything.
+///
+/// # Fields
+///
+/// * `address` - Only transactions sent by or paying this address
+/// * `from_time` - Only blocks at or after this Unix time
+/// * `to_time` - Only blocks at or before this Unix time
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LedgerFilter {
+    pub address: Option<String>,
+    pub from_time: Option<u32>,
+    pub to_time: Option<u32>,
+}
+
+/// Writes the confirmed transactions kept by `filter` as CSV rows of
+/// (height, transaction id, block timestamp, sender, receiver, amount, fee),
+/// oldest first, after a `CSV_HEADER` row.
+///
+/// Text cells are quoted when needed, and cells starting with `=`, `+`, `-` or
+/// `@` get a leading `'` so spreadsheets don't run them as formulas.
+///
+/// # Returns
+///
+/// * `Ok(usize)` - Number of transaction rows written
+/// * `Err(String)` - If writing fails
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::export::csv::{write_csv, LedgerFilter};
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// chain.add_block(Vec::new(), "Alice").unwrap();
+/// chain.add_block(Vec::new(), "Bob").unwrap();
+///
+/// let mut output = Vec::new();
+/// let filter = LedgerFilter { address: Some("Bob".to_string()), ..LedgerFilter::default() };
+/// assert_eq!(write_csv(&chain, &mut output, &filter).unwrap(), 1);
+///
+/// let text = String::from_utf8(output).unwrap();
+/// let row = text.lines().nth(1).unwrap();
+/// assert!(row.starts_with("2,"));
+/// assert!(row.ends_with(",COINBASE,Bob,45,0"));
+/// ```
+pub fn write_csv(chain: &Blockchain, output: &mut dyn Write, filter: &LedgerFilter) -> Result<usize, String> {
+    let error = |error: std::io::Error| format!("Cannot write the CSV: {}", error);
+    writeln!(output, "{}", CSV_HEADER).map_err(error)?;
+    let mut rows = 0;
+    for (height, block) in chain.get_blocks().iter().enumerate() {
+        if filter.from_time.is_some_and(|from| block.time_stamp < from) || filter.to_time.is_some_and(|to| block.time_stamp > to) {
+            continue;
+        }
+        for transaction in &block.transaction {
+            if let Some(address) = &filter.address
+                && transaction.sender_address != *address && !transaction.pays(address) {
+                continue;
+            }
+            writeln!(
+                output, "{},{},{},{},{},{},{}",
+                height, transaction.id(), block.time_stamp, cell(&transaction.sender_address),
+                cell(&transaction.receiver_address), transaction.amount, transaction.fee
+            ).map_err(error)?;
+            rows += 1;
+        }
+    }
+    output.flush().map_err(error)?;
+    Ok(rows)
+}
+
+/// Writes the ledger CSV (see `write_csv`) to a file, replacing it.
+#[cfg(feature = "fs")]
+pub fn export_csv(chain: &Blockchain, path: &Path, filter: &LedgerFilter) -> Result<usize, String> {
+    let file = File::create(path).map_err(|error| format!("Cannot create {}: {}", path.display(), error))?;
+    write_csv(chain, &mut BufWriter::new(file), filter)
+}
+
+/// Quotes a text cell if needed and defuses spreadsheet formulas.
+fn cell(text: &str) -> String {
+    let text = if text.starts_with(['=', '+', '-', '@']) { format!("'{}", text) } else { text.to_string() };
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
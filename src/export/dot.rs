@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use crate::block::block::Block;
+
+/// Hex digits of a hash shown in labels.
+pub const SHORT_HASH_LENGTH: usize = 8;
+
+/// Block tree built from the previous-hash links of a set of blocks.
+///
+/// Blocks may come from several branches (e.g. the chains of different nodes
+/// of a `NetworkSimulator`): each block is linked to its parent when the parent
+/// is in the set. The main branch ends at the highest block (the first one
+/// listed wins a tie).
+pub(crate) struct BlockTree<'a> {
+    /// Blocks without duplicates, in the order given
+    pub blocks: Vec<&'a Block>,
+    /// Height of each block by hash (`None` if an ancestor is missing)
+    pub heights: HashMap<&'a str, Option<u64>>,
+    /// Hashes of the blocks on the main branch
+    pub main_branch: Vec<&'a str>,
+}
+
+impl<'a> BlockTree<'a> {
+    pub fn new(blocks: impl IntoIterator<Item = &'a Block>) -> Self {
+        let mut by_hash: HashMap<&str, &Block> = HashMap::new();
+        let mut unique = Vec::new();
+        for block in blocks {
+            if by_hash.insert(&block.block_hash, block).is_none() {
+                unique.push(block);
+            }
+        }
+
+        let mut heights: HashMap<&str, Option<u64>> = HashMap::new();
+        for block in &unique {
+            // Walk up to a block of known height (or the genesis), then back down
+            let mut path = Vec::new();
+            let mut current = *block;
+            let mut base = loop {
+                if let Some(height) = heights.get(current.block_hash.as_str()) {
+                    break *height;
+                }
+                path.push(current);
+                if current.previous_block_hash == "0" {
+                    break None;
+                }
+                match by_hash.get(current.previous_block_hash.as_str()) {
+                    Some(parent) => current = parent,
+                    None => break None,
+                }
+            };
+            for (index, block) in path.iter().rev().enumerate() {
+                let height = if index == 0 && block.previous_block_hash == "0" {
+                    Some(0)
+                } else {
+                    base.map(|height| height + 1)
+                };
+                heights.insert(&block.block_hash, height);
+                base = height;
+            }
+        }
+
+        let mut main_branch = Vec::new();
+        let tip = unique.iter()
+            .filter_map(|block| heights[block.block_hash.as_str()].map(|height| (height, *block)))
+            .fold(None, |best: Option<(u64, &Block)>, (height, block)| match best {
+                Some((best_height, _)) if best_height >= height => best,
+                _ => Some((height, block)),
+            });
+        let mut current = tip.map(|(_, block)| block);
+        while let Some(block) = current {
+            main_branch.push(block.block_hash.as_str());
+            current = by_hash.get(block.previous_block_hash.as_str()).copied();
+        }
+        main_branch.reverse();
+
+        BlockTree {
+            blocks: unique,
+            heights: heights,
+            main_branch: main_branch,
+        }
+    }
+
+    /// Returns `true` if the block's parent is in the tree.
+    pub fn has_parent(&self, block: &Block) -> bool {
+        self.heights.contains_key(block.previous_block_hash.as_str())
+    }
+
+    /// Returns the label of a block's height (`?` if an ancestor is missing).
+    pub fn height_label(&self, block: &Block) -> String {
+        match self.heights[block.block_hash.as_str()] {
+            Some(height) => height.to_string(),
+            None => "?".to_string(),
+        }
+    }
+}
+
+/// Returns the first `SHORT_HASH_LENGTH` digits of a hash.
+pub fn short_hash(hash: &str) -> &str {
+    &hash[..hash.len().min(SHORT_HASH_LENGTH)]
+}
+
+/// Renders blocks as a Graphviz DOT graph, each block pointing to its parent.
+///
+/// Nodes are labeled with the height, short hash and transaction count. Blocks
+/// off the main branch (forks) are drawn dashed, so the structure of a reorg is
+/// visible. Render with `dot -Tsvg chain.dot -o chain.svg`.
+///
+/// # Arguments
+///
+/// * `blocks` - Blocks of one or several branches (duplicates are ignored)
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::export::dot::blocks_to_dot;
+///
+/// let mut main = Blockchain::with_settings(1, 50.0);
+/// main.add_block(Vec::new(), "Alice").unwrap();
+/// let mut fork = main.clone();
+/// main.add_block(Vec::new(), "Alice").unwrap();
+/// main.add_block(Vec::new(), "Alice").unwrap();
+/// fork.add_block(Vec::new(), "Bob").unwrap();
+///
+/// let blocks: Vec<_> = main.get_blocks().iter().chain(fork.get_blocks()).cloned().collect();
+/// let dot = blocks_to_dot(&blocks);
+/// assert!(dot.starts_with("digraph chain {"));
+/// assert_eq!(dot.matches(" -> ").count(), 4);
+/// assert_eq!(dot.matches("style=dashed").count(), 1);
+/// ```
+pub fn blocks_to_dot<'a>(blocks: impl IntoIterator<Item = &'a Block>) -> String {
+    let tree = BlockTree::new(blocks);
+    let mut lines = vec![
+        "digraph chain {".to_string(),
+        "    rankdir=RL;".to_string(),
+        "    node [shape=box, fontname=\"monospace\"];".to_string(),
+    ];
+    for block in &tree.blocks {
+        let style = if tree.main_branch.contains(&block.block_hash.as_str()) { "" } else { ", style=dashed, color=gray40" };
+        lines.push(format!(
+            "    \"{}\" [label=\"#{}\\n{}\\n{} tx\"{}];",
+            block.block_hash, tree.height_label(block), short_hash(&block.block_hash), block.transaction.len(), style
+        ));
+    }
+    for block in &tree.blocks {
+        if tree.has_parent(block) {
+            lines.push(format!("    \"{}\" -> \"{}\";", block.block_hash, block.previous_block_hash));
+        }
+    }
+    lines.push("}".to_string());
+    lines.join("\n") + "\n"
+}
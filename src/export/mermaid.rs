@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use crate::block::block::Block;
+use crate::export::dot::{short_hash, BlockTree};
+
+/// Renders blocks as a Mermaid flowchart, each block pointing to its parent.
+///
+/// Like `dot::blocks_to_dot`, blocks may come from several branches; blocks
+/// off the main branch get the `fork` class (dashed). Paste the result in a
+/// ```` ```mermaid ```` block of a markdown document.
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::export::mermaid::chain_to_mermaid;
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// chain.add_block(Vec::new(), "Miner").unwrap();
+///
+/// let diagram = chain_to_mermaid(chain.get_blocks());
+/// assert!(diagram.starts_with("flowchart RL\n"));
+/// assert!(diagram.contains("[\"#1<br/>"));
+/// assert_eq!(diagram.matches(" --> ").count(), 1);
+/// ```
+pub fn chain_to_mermaid<'a>(blocks: impl IntoIterator<Item = &'a Block>) -> String {
+    let tree = BlockTree::new(blocks);
+    let mut lines = vec!["flowchart RL".to_string()];
+    for block in &tree.blocks {
+        let class = if tree.main_branch.contains(&block.block_hash.as_str()) { "" } else { ":::fork" };
+        lines.push(format!("    {}{}{}", node_id(&block.block_hash), block_label(&tree.height_label(block), block), class));
+    }
+    for block in &tree.blocks {
+        if tree.has_parent(block) {
+            lines.push(format!("    {} --> {}", node_id(&block.block_hash), node_id(&block.previous_block_hash)));
+        }
+    }
+    lines.push("    classDef fork stroke-dasharray: 5 5, color:#666".to_string());
+    lines.join("\n") + "\n"
+}
+
+/// Renders the payments of a block as a Mermaid flowchart: one node per
+/// address, one arrow per transaction labeled with its amount (and fee).
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::export::mermaid::block_flow_to_mermaid;
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// chain.add_block(Vec::new(), "Miner").unwrap();
+///
+/// let diagram = block_flow_to_mermaid(chain.get_latest_block());
+/// assert!(diagram.contains("a0[\"COINBASE\"]"));
+/// assert!(diagram.contains("a0 -->|45| a1"));
+/// ```
+pub fn block_flow_to_mermaid(block: &Block) -> String {
+    let mut lines = vec![
+        "flowchart LR".to_string(),
+        format!("    %% Block {}", block.block_hash),
+    ];
+    let mut ids: HashMap<String, usize> = HashMap::new();
+    let mut id = |address: &str, lines: &mut Vec<String>| {
+        let next = ids.len();
+        let index = *ids.entry(address.to_string()).or_insert(next);
+        if index == next {
+            lines.push(format!("    a{}[\"{}\"]", index, escape(address)));
+        }
+        format!("a{}", index)
+    };
+    for transaction in &block.transaction {
+        let sender = id(&transaction.sender_address, &mut lines);
+        let receiver = id(&transaction.receiver_address, &mut lines);
+        let label = if transaction.fee > 0.0 {
+            format!("{} + fee {}", transaction.amount, transaction.fee)
+        } else {
+            transaction.amount.to_string()
+        };
+        lines.push(format!("    {} -->|{}| {}", sender, escape(&label), receiver));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Renders a reorg as a Mermaid flowchart: the common ancestor, the blocks the
+/// old branch loses (class `removed`) and the blocks the new branch connects
+/// (class `added`).
+///
+/// # Arguments
+///
+/// * `old_branch` - Blocks of the chain before the reorg, genesis first
+/// * `new_branch` - Blocks of the chain after the reorg, genesis first
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::export::mermaid::reorg_to_mermaid;
+///
+/// let mut old = Blockchain::with_settings(1, 50.0);
+/// old.add_block(Vec::new(), "Alice").unwrap();
+/// let mut new = old.clone();
+/// old.add_block(Vec::new(), "Alice").unwrap();
+/// new.add_block(Vec::new(), "Bob").unwrap();
+/// new.add_block(Vec::new(), "Bob").unwrap();
+///
+/// let diagram = reorg_to_mermaid(&old.get_blocks().to_vec(), &new.get_blocks().to_vec());
+/// assert_eq!(diagram.matches(":::removed").count(), 1);
+/// assert_eq!(diagram.matches(":::added").count(), 2);
+/// ```
+pub fn reorg_to_mermaid(old_branch: &[Block], new_branch: &[Block]) -> String {
+    let common = old_branch.iter().zip(new_branch)
+        .take_while(|(old, new)| old.block_hash == new.block_hash)
+        .count();
+    let mut lines = vec!["flowchart RL".to_string()];
+    if common > 0 {
+        let ancestor = &new_branch[common - 1];
+        lines.push(format!("    {}{}", node_id(&ancestor.block_hash), block_label(&(common - 1).to_string(), ancestor)));
+    }
+    for (class, branch) in [("removed", old_branch), ("added", new_branch)] {
+        for (offset, block) in branch[common..].iter().enumerate() {
+            let height = (common + offset).to_string();
+            lines.push(format!("    {}{}:::{}", node_id(&block.block_hash), block_label(&height, block), class));
+            if common + offset > 0 {
+                lines.push(format!("    {} --> {}", node_id(&block.block_hash), node_id(&block.previous_block_hash)));
+            }
+        }
+    }
+    lines.push("    classDef removed stroke:#c00, stroke-dasharray: 5 5".to_string());
+    lines.push("    classDef added stroke:#080, stroke-width:2px".to_string());
+    lines.join("\n") + "\n"
+}
+
+fn node_id(hash: &str) -> String {
+    format!("b{}", hash)
+}
+
+fn block_label(height: &str, block: &Block) -> String {
+    format!("[\"#{}<br/>{}<br/>{} tx\"]", height, short_hash(&block.block_hash), block.transaction.len())
+}
+
+/// Escapes the characters Mermaid labels can't hold.
+fn escape(text: &str) -> String {
+    text.replace('"', "#quot;").replace('|', "#124;")
+}
@@ -0,0 +1,5 @@
+pub mod dot;
+pub mod mermaid;
+pub mod csv;
+#[cfg(feature = "analytics")]
+pub mod analytics;
\ No newline at end of file
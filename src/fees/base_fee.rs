@@ -0,0 +1,49 @@
+/// Base fee of the first block after genesis.
+pub const INITIAL_BASE_FEE: f64 = 0.1;
+
+/// The base fee never drops below this value.
+pub const MIN_BASE_FEE: f64 = 0.01;
+
+/// Number of transactions per block the base fee tries to reach.
+pub const TARGET_BLOCK_SIZE: usize = 10;
+
+/// Hard limit on transactions per block (twice the target, like EIP-1559).
+pub const MAX_BLOCK_SIZE: usize = TARGET_BLOCK_SIZE * 2;
+
+/// Limits how fast the base fee moves: at most 1/8 (12.5%) per block.
+pub const BASE_FEE_CHANGE_DENOMINATOR: f64 = 8.0;
+
+/// Calculates the base fee of the next block from its parent (EIP-1559 style).
+/// 
+/// - Parent fuller than the target: the base fee goes up
+/// - Parent emptier than the target: the base fee goes down
+/// - Parent exactly at the target: the base fee stays the same
+/// 
+/// # Arguments
+/// 
+/// * `parent_base_fee` - Base fee of the parent block
+/// * `parent_size` - Number of (non-coinbase) transactions in the parent block
+/// * `target_size` - Target number of transactions per block
+/// 
+/// # Returns
+/// 
+/// The base fee every transaction in the next block must pay.
+/// 
+/// # Example
+/// 
+/// ```
+/// use blockc::fees::base_fee::{next_base_fee, TARGET_BLOCK_SIZE, MAX_BLOCK_SIZE};
+/// 
+/// // A full block raises the base fee by 12.5%
+/// assert_eq!(next_base_fee(1.0, MAX_BLOCK_SIZE, TARGET_BLOCK_SIZE), 1.125);
+/// // A block at the target keeps it unchanged
+/// assert_eq!(next_base_fee(1.0, TARGET_BLOCK_SIZE, TARGET_BLOCK_SIZE), 1.0);
+/// ```
+pub fn next_base_fee(parent_base_fee: f64, parent_size: usize, target_size: usize) -> f64 {
+    if target_size == 0 {
+        return parent_base_fee.max(MIN_BASE_FEE);
+    }
+    let delta = (parent_size as f64 - target_size as f64) / target_size as f64;
+    let next = parent_base_fee * (1.0 + delta / BASE_FEE_CHANGE_DENOMINATOR);
+    next.max(MIN_BASE_FEE)
+}
@@ -0,0 +1,98 @@
+use crate::blockchain::blockchain::Blockchain;
+use crate::fees::base_fee::{self, MAX_BLOCK_SIZE, TARGET_BLOCK_SIZE};
+
+/// Number of recent blocks looked at when measuring the tips miners accepted.
+pub const RECENT_BLOCKS: usize = 10;
+
+/// Extra amount added on top of the fee needed to outbid the mempool backlog.
+pub const FEE_STEP: f64 = 0.01;
+
+/// Recommends fees from recent blocks and the current mempool.
+///
+/// The estimate is the sum of:
+/// - The base fee projected `target_blocks` blocks ahead (the backlog pushes it up)
+/// - The tip that recently confirmed transactions paid (median)
+///
+/// If the mempool holds more transactions than fit in `target_blocks` blocks, the
+/// estimate is raised just above the fee of the last transaction that would fit.
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::fees::fee_estimator::FeeEstimator;
+///
+/// let chain = Blockchain::new();
+/// let estimator = FeeEstimator::new(&chain);
+///
+/// // Empty chain and mempool: paying the base fee is enough
+/// assert_eq!(estimator.estimate(1), chain.get_base_fee());
+/// ```
+pub struct FeeEstimator<'a> {
+    chain: &'a Blockchain,
+}
+
+impl<'a> FeeEstimator<'a> {
+    /// Creates an estimator reading from `chain` (blocks and mempool).
+    pub fn new(chain: &'a Blockchain) -> Self {
+        FeeEstimator {
+            chain: chain,
+        }
+    }
+
+    /// Recommends a fee for a transaction that should confirm within `target_blocks` blocks.
+    ///
+    /// # Arguments
+    ///
+    /// * `target_blocks` - How many blocks the caller is willing to wait (0 is treated as 1)
+    ///
+    /// # Returns
+    ///
+    /// The recommended total fee (base fee + tip).
+    pub fn estimate(&self, target_blocks: usize) -> f64 {
+        let target_blocks = target_blocks.max(1);
+
+        // Fees currently waiting, highest first
+        let mut backlog: Vec<f64> = self.chain.mempool().transactions().iter()
+            .map(|t| t.fee)
+            .collect();
+        backlog.sort_by(|a, b| b.total_cmp(a));
+
+        // Project the base fee: each block is filled with as much backlog as fits
+        let mut projected_base_fee = self.chain.get_base_fee();
+        let mut remaining = backlog.len();
+        for _ in 1..target_blocks {
+            let size = remaining.min(MAX_BLOCK_SIZE);
+            remaining -= size;
+            projected_base_fee = base_fee::next_base_fee(projected_base_fee, size, TARGET_BLOCK_SIZE);
+        }
+
+        let mut estimate = projected_base_fee + self.recent_median_tip();
+
+        // Outbid the backlog that would otherwise fill the next blocks
+        let capacity = target_blocks * MAX_BLOCK_SIZE;
+        if backlog.len() >= capacity {
+            estimate = estimate.max(backlog[capacity - 1] + FEE_STEP);
+        }
+        estimate
+    }
+
+    /// Median tip (fee above the block's base fee) paid in the last `RECENT_BLOCKS` blocks.
+    ///
+    /// Returns 0 when no transactions were confirmed recently.
+    pub fn recent_median_tip(&self) -> f64 {
+        let blocks = self.chain.get_blocks();
+        let start = blocks.len().saturating_sub(RECENT_BLOCKS);
+
+        let mut tips: Vec<f64> = blocks.iter().skip(start)
+            .flat_map(|block| block.transaction.iter()
+                .filter(|t| !t.is_coinbase() && t.sender_address != self.chain.treasury().address)
+                .map(move |t| (t.fee - block.base_fee).max(0.0)))
+            .collect();
+        if tips.is_empty() {
+            return 0.0;
+        }
+        tips.sort_by(|a, b| a.total_cmp(b));
+        tips[tips.len() / 2]
+    }
+}
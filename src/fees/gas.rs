@@ -0,0 +1,6 @@
+// Intrinsic gas is part of what a transaction costs, so it lives with the
+// transaction types in `blockc_core`.
+pub use blockc_core::gas::{intrinsic_gas, CODE_INSTRUCTION_GAS, INPUT_GAS, OUTPUT_GAS, TRANSACTION_GAS};
+
+/// Maximum total gas (sum of the transactions' gas limits) a block can contain.
+pub const BLOCK_GAS_LIMIT: u64 = 2_000_000;
@@ -0,0 +1,3 @@
+pub mod base_fee;
+pub mod fee_estimator;
+pub mod gas;
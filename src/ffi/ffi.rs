@@ -0,0 +1,327 @@
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use crate::blockchain::blockchain::Blockchain;
+use crate::entity::entity::Entity;
+use crate::helpers::random::entropy;
+use crate::simulation::rng::SimRng;
+use crate::transactions::transactions::{Transaction, TransactionExt};
+use crate::wallet::wallet::Wallet;
+
+/// Result of every `blockc_*` function that can fail.
+///
+/// When it is not `Ok`, `blockc_last_error` describes what went wrong.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockcStatus {
+    /// Success
+    Ok = 0,
+    /// A pointer argument was null
+    NullArgument = 1,
+    /// A string argument was not UTF-8
+    InvalidUtf8 = 2,
+    /// The chain or the wallet refused the operation (see `blockc_last_error`)
+    Rejected = 3,
+    /// The library panicked; the handles involved should not be used again
+    Panic = 4,
+}
+
+/// Opaque handle of a chain, created by `blockc_chain_new` and freed by `blockc_chain_free`.
+pub struct BlockcChain {
+    chain: Blockchain,
+}
+
+/// Opaque handle of a wallet, created by `blockc_wallet_new` and freed by `blockc_wallet_free`.
+pub struct BlockcWallet {
+    wallet: Wallet,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Runs `body`, turning its error and any panic into a status (and the last error).
+fn guard(body: impl FnOnce() -> Result<(), (BlockcStatus, String)>) -> BlockcStatus {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+    match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(())) => BlockcStatus::Ok,
+        Ok(Err((status, message))) => {
+            set_last_error(message);
+            status
+        }
+        Err(_) => {
+            set_last_error("blockc panicked".to_string());
+            BlockcStatus::Panic
+        }
+    }
+}
+
+fn rejected(error: String) -> (BlockcStatus, String) {
+    (BlockcStatus::Rejected, error)
+}
+
+/// Reads a C string argument.
+///
+/// # Safety
+///
+/// `text` must be null or point to a NUL-terminated string.
+unsafe fn read_str<'a>(text: *const c_char, name: &str) -> Result<&'a str, (BlockcStatus, String)> {
+    if text.is_null() {
+        return Err((BlockcStatus::NullArgument, format!("{} is null", name)));
+    }
+    unsafe { CStr::from_ptr(text) }.to_str().map_err(|_| (BlockcStatus::InvalidUtf8, format!("{} is not UTF-8", name)))
+}
+
+/// Borrows the object behind a handle.
+///
+/// # Safety
+///
+/// `handle` must be null or a live handle of the right type.
+unsafe fn borrow<'a, T>(handle: *const T, name: &str) -> Result<&'a T, (BlockcStatus, String)> {
+    unsafe { handle.as_ref() }.ok_or((BlockcStatus::NullArgument, format!("{} is null", name)))
+}
+
+/// Mutably borrows the object behind a handle.
+///
+/// # Safety
+///
+/// `handle` must be null or a live handle of the right type, used by no one else meanwhile.
+unsafe fn borrow_mut<'a, T>(handle: *mut T, name: &str) -> Result<&'a mut T, (BlockcStatus, String)> {
+    unsafe { handle.as_mut() }.ok_or((BlockcStatus::NullArgument, format!("{} is null", name)))
+}
+
+/// Stores `text` in `*out` as a string the caller frees with `blockc_string_free`.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes.
+unsafe fn write_string(out: *mut *mut c_char, text: String) -> Result<(), (BlockcStatus, String)> {
+    if out.is_null() {
+        return Err((BlockcStatus::NullArgument, "out is null".to_string()));
+    }
+    let text = CString::new(text).map_err(|_| rejected("String contains a NUL byte".to_string()))?;
+    unsafe { *out = text.into_raw() };
+    Ok(())
+}
+
+/// Returns the message of the last failed call on this thread, or null.
+///
+/// The string belongs to the library and stays valid until the next
+/// `blockc_*` call on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn blockc_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
+
+/// Frees a string returned by the library.
+///
+/// # Safety
+///
+/// `text` must be null or a string returned through an `out` argument, not freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn blockc_string_free(text: *mut c_char) {
+    if !text.is_null() {
+        drop(unsafe { CString::from_raw(text) });
+    }
+}
+
+/// Creates a chain with its genesis block. Free it with `blockc_chain_free`.
+#[unsafe(no_mangle)]
+pub extern "C" fn blockc_chain_new(difficulty: u32, block_reward: f64) -> *mut BlockcChain {
+    Box::into_raw(Box::new(BlockcChain {
+        chain: Blockchain::with_settings(difficulty, block_reward),
+    }))
+}
+
+/// Frees a chain.
+///
+/// # Safety
+///
+/// `chain` must be null or a handle from `blockc_chain_new`, not freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn blockc_chain_free(chain: *mut BlockcChain) {
+    if !chain.is_null() {
+        drop(unsafe { Box::from_raw(chain) });
+    }
+}
+
+/// Returns the height of the tip (the genesis block is 0), or 0 if `chain` is null.
+///
+/// # Safety
+///
+/// `chain` must be null or a live chain handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn blockc_chain_height(chain: *const BlockcChain) -> u64 {
+    unsafe { chain.as_ref() }.map_or(0, |handle| handle.chain.get_blocks().len() as u64 - 1)
+}
+
+/// Writes the balance of `address` to `*balance`.
+///
+/// # Safety
+///
+/// `chain` must be a live chain handle, `address` a NUL-terminated string and
+/// `balance` valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn blockc_chain_balance(chain: *const BlockcChain, address: *const c_char, balance: *mut f64) -> BlockcStatus {
+    guard(|| {
+        let handle = unsafe { borrow(chain, "chain") }?;
+        let address = unsafe { read_str(address, "address") }?;
+        let balance = unsafe { borrow_mut(balance, "balance") }?;
+        *balance = handle.chain.get_balance(address);
+        Ok(())
+    })
+}
+
+/// Writes the hash of the tip to `*hash`; free it with `blockc_string_free`.
+///
+/// # Safety
+///
+/// `chain` must be a live chain handle and `hash` valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn blockc_chain_latest_hash(chain: *const BlockcChain, hash: *mut *mut c_char) -> BlockcStatus {
+    guard(|| {
+        let handle = unsafe { borrow(chain, "chain") }?;
+        unsafe { write_string(hash, handle.chain.get_latest_hash()) }
+    })
+}
+
+/// Submits a signed transaction, given as JSON, to the mempool.
+///
+/// # Safety
+///
+/// `chain` must be a live chain handle and `transaction_json` a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn blockc_chain_submit(chain: *mut BlockcChain, transaction_json: *const c_char) -> BlockcStatus {
+    guard(|| {
+        let handle = unsafe { borrow_mut(chain, "chain") }?;
+        let json = unsafe { read_str(transaction_json, "transaction_json") }?;
+        let transaction = Transaction::from_json(json).map_err(rejected)?;
+        handle.chain.submit_transaction(transaction).map_err(rejected)
+    })
+}
+
+/// Mines a block from the mempool for `miner`.
+///
+/// # Safety
+///
+/// `chain` must be a live chain handle and `miner` a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn blockc_chain_mine(chain: *mut BlockcChain, miner: *const c_char) -> BlockcStatus {
+    guard(|| {
+        let handle = unsafe { borrow_mut(chain, "chain") }?;
+        let miner = unsafe { read_str(miner, "miner") }?;
+        handle.chain.add_block_from_mempool(miner).map(|_| ()).map_err(rejected)
+    })
+}
+
+/// Checks the whole chain; on failure `blockc_last_error` names the first problem.
+///
+/// # Safety
+///
+/// `chain` must be a live chain handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn blockc_chain_validate(chain: *const BlockcChain) -> BlockcStatus {
+    guard(|| {
+        let handle = unsafe { borrow(chain, "chain") }?;
+        handle.chain.validate().map_err(rejected)
+    })
+}
+
+/// Creates a wallet for `address`; the same non-zero `seed` always gives the
+/// same keys, 0 picks a fresh seed. Free it with `blockc_wallet_free`.
+///
+/// Returns null if `address` is null or not UTF-8.
+///
+/// # Safety
+///
+/// `address` must be null or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn blockc_wallet_new(address: *const c_char, seed: u64) -> *mut BlockcWallet {
+    let mut wallet = None;
+    guard(|| {
+        let address = unsafe { read_str(address, "address") }?;
+        let seed = if seed == 0 { entropy() } else { seed };
+        let entity = Entity::generate(address.to_string(), 0.0, &mut SimRng::new(seed));
+        wallet = Some(Box::new(BlockcWallet { wallet: Wallet::new(entity) }));
+        Ok(())
+    });
+    wallet.map_or(ptr::null_mut(), Box::into_raw)
+}
+
+/// Frees a wallet.
+///
+/// # Safety
+///
+/// `wallet` must be null or a handle from `blockc_wallet_new`, not freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn blockc_wallet_free(wallet: *mut BlockcWallet) {
+    if !wallet.is_null() {
+        drop(unsafe { Box::from_raw(wallet) });
+    }
+}
+
+/// Signs a payment of `amount` to a name or an address and writes it to
+/// `*transaction_json`, ready for `blockc_chain_submit`; free it with
+/// `blockc_string_free`.
+///
+/// The wallet syncs with `chain` first. A negative `fee` means twice the base fee.
+///
+/// # Safety
+///
+/// `wallet` and `chain` must be live handles, `recipient` a NUL-terminated
+/// string and `transaction_json` valid for writes.
+///
+/// # Example
+///
+/// ```
+/// use std::ffi::{CStr, CString};
+/// use std::ptr;
+/// use blockc::ffi::ffi::*;
+///
+/// unsafe {
+///     let chain = blockc_chain_new(1, 50.0);
+///     let alice = CString::new("Alice").unwrap();
+///     assert_eq!(blockc_chain_mine(chain, alice.as_ptr()), BlockcStatus::Ok);
+///
+///     let wallet = blockc_wallet_new(alice.as_ptr(), 7);
+///     let bob = CString::new("Bob").unwrap();
+///     let mut json = ptr::null_mut();
+///     assert_eq!(blockc_wallet_pay(wallet, chain, bob.as_ptr(), 10.0, -1.0, &mut json), BlockcStatus::Ok);
+///     assert_eq!(blockc_chain_submit(chain, json), BlockcStatus::Ok);
+///
+///     // The same transaction again is a replay
+///     assert_eq!(blockc_chain_submit(chain, json), BlockcStatus::Rejected);
+///     assert!(!CStr::from_ptr(blockc_last_error()).to_str().unwrap().is_empty());
+///     blockc_string_free(json);
+///
+///     assert_eq!(blockc_chain_mine(chain, alice.as_ptr()), BlockcStatus::Ok);
+///     let mut balance = 0.0;
+///     assert_eq!(blockc_chain_balance(chain, bob.as_ptr(), &mut balance), BlockcStatus::Ok);
+///     assert_eq!(balance, 10.0);
+///     assert_eq!(blockc_chain_height(chain), 2);
+///     assert_eq!(blockc_chain_validate(chain), BlockcStatus::Ok);
+///     assert_eq!(blockc_chain_validate(ptr::null()), BlockcStatus::NullArgument);
+///
+///     blockc_wallet_free(wallet);
+///     blockc_chain_free(chain);
+/// }
+/// ```
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn blockc_wallet_pay(wallet: *mut BlockcWallet, chain: *const BlockcChain, recipient: *const c_char, amount: f64, fee: f64, transaction_json: *mut *mut c_char) -> BlockcStatus {
+    guard(|| {
+        let handle = unsafe { borrow_mut(wallet, "wallet") }?;
+        let chain = &unsafe { borrow(chain, "chain") }?.chain;
+        let recipient = unsafe { read_str(recipient, "recipient") }?;
+        handle.wallet.sync_pending(chain);
+        let receiver = handle.wallet.resolve(chain, recipient).map_err(rejected)?;
+        let fee = if fee < 0.0 { chain.get_base_fee() * 2.0 } else { fee };
+        let transaction = Transaction::create_payment(&mut handle.wallet.entity, &receiver, amount, fee).map_err(rejected)?;
+        unsafe { write_string(transaction_json, transaction.to_json()) }
+    })
+}
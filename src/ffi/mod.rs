@@ -0,0 +1 @@
+pub mod ffi;
\ No newline at end of file
@@ -0,0 +1,135 @@
+use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm")))]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Source of the current Unix time, in seconds.
+///
+/// Everything that stamps a time (blocks, transactions, treasury spends) reads
+/// it from a `Clock`, so tests and simulations can control time instead of
+/// depending on the system clock.
+pub trait Clock: Send + Sync {
+    /// Returns the current Unix timestamp.
+    fn now(&self) -> u32;
+}
+
+/// The real clock (`SystemTime::now()`).
+///
+/// `wasm32-unknown-unknown` has no system time, so with the `wasm` feature it
+/// reads the browser's `Date.now()` instead.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    /// # Panics
+    ///
+    /// If the system time is before the Unix epoch or after 2106 (it must fit in a `u32`).
+    #[cfg(not(all(target_arch = "wasm32", feature = "wasm")))]
+    fn now(&self) -> u32 {
+        let a = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        a.try_into().unwrap()
+    }
+
+    #[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+    fn now(&self) -> u32 {
+        (js_sys::Date::now() / 1000.0) as u32
+    }
+}
+
+/// A clock that only moves when told to.
+///
+/// Clones share the same time, so a test can keep one copy and hand another to
+/// a `Blockchain` (or install it with `use_clock`), then move time forward.
+///
+/// # Example
+///
+/// ```
+/// use blockc::helpers::clock::{Clock, ManualClock};
+///
+/// let clock = ManualClock::new(1_000);
+/// let shared = clock.clone();
+/// clock.advance(60);
+/// assert_eq!(shared.now(), 1_060);
+///
+/// clock.set(5);
+/// assert_eq!(shared.now(), 5);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    time: Arc<AtomicU32>,
+}
+
+impl ManualClock {
+    /// Creates a clock stopped at `time`.
+    pub fn new(time: u32) -> Self {
+        ManualClock {
+            time: Arc::new(AtomicU32::new(time)),
+        }
+    }
+
+    /// Sets the time (it may go backwards).
+    pub fn set(&self, time: u32) {
+        self.time.store(time, Ordering::SeqCst);
+    }
+
+    /// Moves the time forward by `seconds`.
+    pub fn advance(&self, seconds: u32) {
+        self.time.fetch_add(seconds, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> u32 {
+        self.time.load(Ordering::SeqCst)
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Arc<dyn Clock>> = RefCell::new(Arc::new(SystemClock));
+}
+
+/// Restores the previous clock of the thread when dropped (see `use_clock`).
+pub struct ClockGuard {
+    previous: Option<Arc<dyn Clock>>,
+}
+
+impl Drop for ClockGuard {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            CURRENT.with(|current| *current.borrow_mut() = previous);
+        }
+    }
+}
+
+/// Makes `clock` the current thread's clock until the returned guard is dropped.
+///
+/// Code that creates blocks or transactions without a `Blockchain` at hand
+/// (`Transaction::create_and_sign`, `Block::new`, ...) reads the time from the
+/// thread's clock, which is the `SystemClock` by default.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use blockc::helpers::clock::{use_clock, ManualClock};
+/// use blockc::helpers::helper_functions::get_time;
+///
+/// {
+///     let _guard = use_clock(Arc::new(ManualClock::new(42)));
+///     assert_eq!(get_time(), 42);
+/// }
+/// // Back to the system clock
+/// assert!(get_time() > 42);
+/// ```
+pub fn use_clock(clock: Arc<dyn Clock>) -> ClockGuard {
+    let previous = CURRENT.with(|current| std::mem::replace(&mut *current.borrow_mut(), clock));
+    ClockGuard {
+        previous: Some(previous),
+    }
+}
+
+/// Returns the time of the current thread's clock.
+pub fn now() -> u32 {
+    CURRENT.with(|current| current.borrow().now())
+}
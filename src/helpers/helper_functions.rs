@@ -1,20 +1,23 @@
-use std::time::{SystemTime, UNIX_EPOCH}; // get current time
+use crate::helpers::clock;
 
 /// Gets the current Unix timestamp as a `u32`.
 /// 
-/// This function retrieves the current time since the Unix epoch (January 1, 1970)
-/// and converts it to a `u32` value. This is sufficient for timestamps until year 2106.
+/// The time comes from the current thread's clock (see `clock::use_clock`),
+/// which is the system clock unless a test or simulation installed another one.
+/// A `u32` is sufficient for timestamps until year 2106.
 /// 
 /// # Returns
 /// 
 /// Current Unix timestamp as `u32`.
+pub fn get_time() -> u32{
+    clock::now()
+}
+
+/// Hashes `data` with SHA-256 (see `blockc_core::hash::sha256_hex`).
 /// 
-/// # Panics
+/// # Returns
 /// 
-/// This function will panic if:
-/// - The system time is before the Unix epoch
-/// - The timestamp cannot be converted to `u32` (unlikely before 2106)
-pub fn get_time() -> u32{
-    let a = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-    a.try_into().unwrap()
-}
\ No newline at end of file
+/// The hash as a hexadecimal string.
+pub fn sha256_hex(data: &str) -> String {
+    blockc_core::hash::sha256_hex(data)
+}
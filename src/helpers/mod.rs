@@ -1 +1,3 @@
-pub mod helper_functions;
\ No newline at end of file
+pub mod helper_functions;
+pub mod clock;
+pub mod random;
\ No newline at end of file
@@ -0,0 +1,77 @@
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm")))]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Source of random numbers.
+///
+/// Code that needs randomness (demo keys, simulator scheduling, block finders)
+/// takes a `RandomSource` instead of reaching for the operating system, so a run
+/// seeded with `SimRng` can be reproduced exactly. Only `next_u64` has to be
+/// implemented.
+///
+/// # Example
+///
+/// ```
+/// use blockc::helpers::random::RandomSource;
+/// use blockc::simulation::rng::SimRng;
+///
+/// let mut rng = SimRng::new(7);
+/// let x = rng.next_f64();
+/// assert!((0.0..1.0).contains(&x));
+/// assert!(rng.below(6) < 6);
+/// assert_eq!(rng.hex(16).len(), 32);
+/// ```
+pub trait RandomSource: Send {
+    /// Returns the next 64 random bits.
+    fn next_u64(&mut self) -> u64;
+
+    /// Returns a number in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns `true` with probability `p`.
+    fn chance(&mut self, p: f64) -> bool {
+        self.next_f64() < p
+    }
+
+    /// Returns a number in `[0, n)` (0 if `n` is 0).
+    fn below(&mut self, n: u64) -> u64 {
+        if n == 0 { 0 } else { self.next_u64() % n }
+    }
+
+    /// Returns `bytes` random bytes as a hexadecimal string.
+    fn hex(&mut self, bytes: usize) -> String {
+        (0..bytes)
+            .map(|_| format!("{:02x}", self.next_u64() as u8))
+            .collect::<String>()
+    }
+}
+
+/// Returns a seed that changes from one call to the next, for a `SimRng` that
+/// should not be reproducible (e.g. keys of a new wallet).
+///
+/// Native builds mix the system time; with the `wasm` feature on
+/// `wasm32-unknown-unknown` it comes from the browser's `Math.random()`.
+///
+/// # Example
+///
+/// ```
+/// use blockc::helpers::random::entropy;
+///
+/// assert_ne!(entropy(), entropy());
+/// ```
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm")))]
+pub fn entropy() -> u64 {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static CALLS: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_nanos() as u64).unwrap_or(0);
+    nanos ^ CALLS.fetch_add(1, Ordering::Relaxed).wrapping_mul(0x9e37_79b9_7f4a_7c15)
+}
+
+/// Returns a seed that changes from one call to the next (see the native version).
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+pub fn entropy() -> u64 {
+    let high = (js_sys::Math::random() * 4_294_967_296.0) as u64;
+    let low = (js_sys::Math::random() * 4_294_967_296.0) as u64;
+    (high << 32) | low
+}
@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+
+// Services are signed into DID transactions and live in `blockc_core`.
+pub use blockc_core::payload::Service;
+
+/// Prefix of every DID on this chain (`did:blockc:<address>`).
+pub const DID_PREFIX: &str = "did:blockc:";
+
+/// Maximum number of public keys in a document.
+pub const MAX_KEYS: usize = 8;
+
+/// Maximum number of service endpoints in a document.
+pub const MAX_SERVICES: usize = 8;
+
+/// Returns the DID of `address`.
+///
+/// # Example
+///
+/// ```
+/// use blockc::identity::did::{did_for, address_of};
+///
+/// let did = did_for("Alice");
+/// assert_eq!(did, "did:blockc:Alice");
+/// assert_eq!(address_of(&did), Some("Alice"));
+/// ```
+pub fn did_for(address: &str) -> String {
+    format!("{}{}", DID_PREFIX, address)
+}
+
+/// Returns the address a DID is derived from (`None` if it is not a DID of this chain).
+pub fn address_of(did: &str) -> Option<&str> {
+    did.strip_prefix(DID_PREFIX).filter(|address| !address.is_empty())
+}
+
+/// Checks the content of a document: 1 to `MAX_KEYS` distinct, non-empty keys
+/// and at most `MAX_SERVICES` services with distinct ids.
+pub fn check_document(public_keys: &[String], services: &[Service]) -> Result<(), String> {
+    if public_keys.is_empty() || public_keys.len() > MAX_KEYS {
+        return Err(format!("A DID document needs 1 to {} public keys", MAX_KEYS));
+    }
+    if public_keys.iter().any(|key| key.is_empty()) || public_keys.iter().enumerate().any(|(i, key)| public_keys[..i].contains(key)) {
+        return Err("Public keys must be non-empty and distinct".to_string());
+    }
+    if services.len() > MAX_SERVICES {
+        return Err(format!("A DID document has at most {} services", MAX_SERVICES));
+    }
+    if services.iter().enumerate().any(|(i, service)| services[..i].iter().any(|other| other.id == service.id)) {
+        return Err("Service ids must be distinct".to_string());
+    }
+    Ok(())
+}
+
+/// Current identity document of a DID.
+///
+/// # Fields
+///
+/// * `did` - The DID (`did:blockc:<controller>`)
+/// * `controller` - Address allowed to update the document
+/// * `public_keys` - Keys currently used to verify the subject's signatures and credentials
+/// * `services` - Service endpoints
+/// * `version` - 1 when registered, +1 on every update
+/// * `updated_at` - Height of the block with the last change
+#[derive(Debug, Clone, PartialEq)]
+pub struct DidDocument {
+    pub did: String,
+    pub controller: String,
+    pub public_keys: Vec<String>,
+    pub services: Vec<Service>,
+    pub version: u64,
+    pub updated_at: u64,
+}
+
+/// A change of a DID's keys.
+///
+/// # Fields
+///
+/// * `height` - Height of the block that made the change
+/// * `version` - Document version from then on
+/// * `public_keys` - Keys valid from `height` on
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyRotation {
+    pub height: u64,
+    pub version: u64,
+    pub public_keys: Vec<String>,
+}
+
+/// Registry of DID documents, with the history of their keys.
+///
+/// Keeping old key sets lets a verifier check a credential against the keys that
+/// were valid when it was issued.
+///
+/// # Fields
+///
+/// * `documents` - Current documents, by DID
+/// * `history` - Key sets of each DID, oldest first
+///
+/// # Example
+///
+/// ```
+/// use blockc::identity::did::{DidRegistry, did_for};
+///
+/// let mut registry = DidRegistry::new();
+/// registry.register("Alice", vec!["key-1".to_string()], Vec::new(), 3).unwrap();
+/// registry.update("Alice", vec!["key-2".to_string()], Vec::new(), 8).unwrap();
+///
+/// let did = did_for("Alice");
+/// assert_eq!(registry.resolve(&did).unwrap().public_keys, vec!["key-2".to_string()]);
+/// assert_eq!(registry.keys_at(&did, 5).unwrap(), &["key-1".to_string()]);
+/// assert_eq!(registry.history(&did).len(), 2);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DidRegistry {
+    documents: HashMap<String, DidDocument>,
+    history: HashMap<String, Vec<KeyRotation>>,
+}
+
+impl DidRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        DidRegistry {
+            documents: HashMap::new(),
+            history: HashMap::new(),
+        }
+    }
+
+    /// Returns the current document of `did`.
+    pub fn resolve(&self, did: &str) -> Option<&DidDocument> {
+        self.documents.get(did)
+    }
+
+    /// Returns every key set `did` has had, oldest first.
+    pub fn history(&self, did: &str) -> &[KeyRotation] {
+        self.history.get(did).map(|rotations| rotations.as_slice()).unwrap_or(&[])
+    }
+
+    /// Returns the keys that were valid for `did` at block `height`.
+    pub fn keys_at(&self, did: &str, height: u64) -> Option<&[String]> {
+        self.history(did).iter().rev()
+            .find(|rotation| rotation.height <= height)
+            .map(|rotation| rotation.public_keys.as_slice())
+    }
+
+    /// Checks that `controller` can register its DID.
+    pub fn check_register(&self, controller: &str, public_keys: &[String], services: &[Service]) -> Result<(), String> {
+        check_document(public_keys, services)?;
+        let did = did_for(controller);
+        if self.documents.contains_key(&did) {
+            return Err(format!("{} is already registered", did));
+        }
+        Ok(())
+    }
+
+    /// Checks that `controller` can update its DID.
+    pub fn check_update(&self, controller: &str, public_keys: &[String], services: &[Service]) -> Result<(), String> {
+        check_document(public_keys, services)?;
+        let did = did_for(controller);
+        if !self.documents.contains_key(&did) {
+            return Err(format!("{} is not registered", did));
+        }
+        Ok(())
+    }
+
+    /// Registers the DID of `controller` at block `height`.
+    pub fn register(&mut self, controller: &str, public_keys: Vec<String>, services: Vec<Service>, height: u64) -> Result<(), String> {
+        self.check_register(controller, &public_keys, &services)?;
+        let did = did_for(controller);
+        self.history.entry(did.clone()).or_default().push(KeyRotation {
+            height: height,
+            version: 1,
+            public_keys: public_keys.clone(),
+        });
+        self.documents.insert(did.clone(), DidDocument {
+            did: did,
+            controller: controller.to_string(),
+            public_keys: public_keys,
+            services: services,
+            version: 1,
+            updated_at: height,
+        });
+        Ok(())
+    }
+
+    /// Replaces the keys and services of `controller`'s DID at block `height`.
+    ///
+    /// A new entry is added to the key history only if the keys changed.
+    pub fn update(&mut self, controller: &str, public_keys: Vec<String>, services: Vec<Service>, height: u64) -> Result<(), String> {
+        self.check_update(controller, &public_keys, &services)?;
+        let did = did_for(controller);
+        let Some(document) = self.documents.get_mut(&did) else { return Ok(()) };
+        document.version += 1;
+        document.updated_at = height;
+        document.services = services;
+        if document.public_keys != public_keys {
+            document.public_keys = public_keys.clone();
+            self.history.entry(did).or_default().push(KeyRotation {
+                height: height,
+                version: document.version,
+                public_keys: public_keys,
+            });
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+// Module layout mirrors the folders (`block/block.rs`, `entity/entity.rs`, ...)
+#![allow(clippy::module_inception, clippy::redundant_field_names)]
+
+pub mod block;
+pub mod transactions;
+pub mod helpers;
+pub mod entity;
+pub mod blockchain;
+pub mod state;
+pub mod treasury;
+pub mod fees;
+pub mod mempool;
+pub mod mining;
+pub mod merkle;
+pub mod wallet;
+pub mod contracts;
+pub mod swap;
+pub mod utxo;
+pub mod vm;
+pub mod assets;
+pub mod names;
+pub mod identity;
+pub mod dex;
+pub mod oracle;
+pub mod bridge;
+pub mod audit;
+pub mod simulation;
+pub mod codec;
+#[cfg(feature = "fs")]
+pub mod shell;
+pub mod export;
+#[cfg(feature = "fs")]
+pub mod storage;
+#[cfg(feature = "net")]
+pub mod rpc;
+#[cfg(feature = "config")]
+pub mod config;
+#[cfg(feature = "tui")]
+pub mod dashboard;
+#[cfg(feature = "explorer")]
+pub mod explorer;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "scripting")]
+pub mod scripting;
+#[cfg(feature = "privacy")]
+pub mod privacy;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "proto")]
+pub mod proto;
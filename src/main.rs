@@ -1,8 +1,726 @@
-mod block;
-mod transactions;
-mod helpers;
-mod entity;
-mod blockchain;
-fn main() {
-    println!("Hello, world!");
+use std::cell::RefCell;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use clap::{Parser, Subcommand};
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use blockc::blockchain::blockchain::Blockchain;
+use blockc::blockchain::genesis::GenesisConfig;
+use blockc::blockchain::shared::SharedBlockchain;
+use blockc::config::node::NodeConfig;
+use blockc::export::csv::LedgerFilter;
+use blockc::export::mermaid;
+use blockc::rpc::notify::{Notifier, NotifyTarget, WEBHOOKS_FILE};
+use blockc::rpc::limit::RateLimiter;
+use blockc::rpc::server;
+use blockc::shell::shell::{self, Session, ShellAction};
+use blockc::simulation::rng::SimRng;
+use blockc::storage::keystore::{load_watch_only, save_watch_only, KeyStore, KEYS_FILE, WATCH_FILE};
+use blockc::storage::store::{ChainStore, BLOCKS_FILE, DAMAGED_BLOCKS_FILE};
+use blockc::transactions::transactions::{Transaction, TransactionExt};
+use blockc::wallet::contacts::{AddressBook, CONTACTS_FILE};
+use blockc::wallet::invoice::{InvoiceBook, InvoiceEvent, InvoiceStatus, INVOICES_FILE};
+use blockc::wallet::offline::{OfflineSignature, SigningRequest};
+use blockc::wallet::request::PaymentRequest;
+use blockc::wallet::vanity::{VanityGenerator, VanityPattern};
+use blockc::wallet::wallet::Wallet;
+
+/// A small proof-of-work blockchain node and wallet.
+#[derive(Parser)]
+#[command(name = "blockc", version)]
+struct Cli {
+    /// TOML configuration file (see `NodeConfig`); `BLOCKC_*` variables override it
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Directory holding the chain, the mempool and the keys [default: .blockc]
+    #[arg(long, global = true)]
+    data_dir: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Creates a new chain (genesis block only)
+    Init {
+        /// Leading zeros required in block hashes [default: 3]
+        #[arg(long)]
+        difficulty: Option<u32>,
+        /// Coins minted by each block [default: 50]
+        #[arg(long)]
+        reward: Option<f64>,
+        /// Genesis file (JSON or TOML) defining the network
+        #[arg(long, conflicts_with_all = ["difficulty", "reward"])]
+        genesis: Option<PathBuf>,
+    },
+    /// Mines blocks from the mempool
+    Mine {
+        /// Address receiving the rewards and fees [default: Miner]
+        #[arg(long)]
+        miner: Option<String>,
+        /// Number of blocks to mine [default: 1]
+        #[arg(long)]
+        blocks: Option<u32>,
+    },
+    /// Signs a payment and adds it to the mempool (keys are created on first use)
+    Send {
+        #[arg(long)]
+        from: String,
+        /// Address, registered name or contact label
+        #[arg(long)]
+        to: String,
+        #[arg(long)]
+        amount: f64,
+        /// Fee (defaults to twice the base fee)
+        #[arg(long)]
+        fee: Option<f64>,
+        #[arg(long)]
+        memo: Option<String>,
+    },
+    /// Prints a payment request URI (`blockc:<address>?amount=..&memo=..`) to share or show as a QR code
+    Request {
+        /// Address to be paid
+        address: String,
+        #[arg(long)]
+        amount: Option<f64>,
+        #[arg(long)]
+        memo: Option<String>,
+    },
+    /// Pays a payment request URI made by `blockc request` (keys are created on first use)
+    PayRequest {
+        uri: String,
+        #[arg(long)]
+        from: String,
+        /// Amount to pay, when the request doesn't give one
+        #[arg(long)]
+        amount: Option<f64>,
+        /// Fee (defaults to twice the base fee)
+        #[arg(long)]
+        fee: Option<f64>,
+    },
+    /// Creates an invoice and prints its payment request URI (kept in invoices.json)
+    Invoice {
+        /// Address to be paid
+        address: String,
+        #[arg(long)]
+        amount: f64,
+        /// Blocks the invoice stays payable for
+        #[arg(long, default_value_t = 100)]
+        expires_in: u64,
+        /// Memo the payment must carry [default: "invoice <id>"]
+        #[arg(long)]
+        memo: Option<String>,
+    },
+    /// Checks the chain for invoice payments and lists the invoices
+    Invoices,
+    /// Grinds keys until the address derived from one fits a pattern, and saves them
+    Vanity {
+        /// Hexadecimal characters the address starts with (`cafe`), ends with (`*cafe`) or contains (`*cafe*`)
+        pattern: String,
+        /// Threads grinding keys [default: one per core]
+        #[arg(long)]
+        threads: Option<usize>,
+        /// Give up after this many seconds
+        #[arg(long)]
+        timeout: Option<u64>,
+    },
+    /// Follows an address (e.g. cold storage) without its private key
+    Watch {
+        /// Address to watch
+        #[arg(required_unless_present = "public_key", conflicts_with = "public_key")]
+        address: Option<String>,
+        /// Watch the address derived from this public key instead
+        #[arg(long)]
+        public_key: Option<String>,
+    },
+    /// Stops following a watch-only address
+    Unwatch { address: String },
+    /// Prints the balances of the watch-only addresses
+    Watched,
+    /// Prints an unsigned payment from a watch-only address, as JSON, for its key holder to sign
+    BuildUnsigned {
+        #[arg(long)]
+        from: String,
+        /// Address, registered name or contact label
+        #[arg(long)]
+        to: String,
+        #[arg(long)]
+        amount: f64,
+        /// Fee (defaults to twice the base fee)
+        #[arg(long)]
+        fee: Option<f64>,
+        /// Write a signing request for `sign-offline` (the payment and its context) to this file instead
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Signs a request written by `build-unsigned --output` with a saved key (needs no chain, for an air-gapped machine)
+    SignOffline {
+        /// Signing request to sign
+        request: PathBuf,
+        /// File to write the signature to
+        #[arg(long)]
+        output: PathBuf,
+    },
+    /// Checks a signature made by `sign-offline` against its request and adds the payment to the mempool
+    ImportSigned {
+        /// Signing request the signature was made for
+        #[arg(long)]
+        request: PathBuf,
+        /// Signature written by `sign-offline`
+        #[arg(long)]
+        signature: PathBuf,
+    },
+    /// Prints the balance and nonce of an address (or contact label)
+    Balance { address: String },
+    /// Prints the confirmed transactions sent or received by an address (or contact label)
+    History { address: String },
+    /// Lists the contact labels and their addresses
+    Contacts,
+    /// Labels an address, so commands accept the label instead
+    AddContact { label: String, address: String },
+    /// Removes a contact label
+    RemoveContact { label: String },
+    /// Has `blockc serve` POST the confirmed transactions touching an address to a URL (kept in webhooks.json)
+    Webhook {
+        address: String,
+        /// http:// URL receiving the notifications as JSON
+        url: String,
+        /// Confirmations a transaction needs before it is notified
+        #[arg(long, default_value_t = 1)]
+        confirmations: u64,
+    },
+    /// Lists the webhooks
+    Webhooks,
+    /// Removes a webhook
+    RemoveWebhook { id: u64 },
+    /// Checks every block of the chain
+    Validate,
+    /// Replays every block to rebuild the transaction index, the address filters and the state cache
+    Reindex,
+    /// Truncates a damaged chain to its last valid block (the damaged blocks file is kept aside)
+    Repair,
+    /// Prints the chain as a Graphviz DOT graph (`blockc dot | dot -Tsvg > chain.svg`)
+    Dot,
+    /// Prints the chain, or the payments of one block, as a Mermaid diagram
+    Mermaid {
+        /// Height of the block whose payments to draw
+        #[arg(long)]
+        block: Option<usize>,
+    },
+    /// Shows a live terminal dashboard of the chain (reloaded from the data directory)
+    #[cfg(feature = "tui")]
+    Dashboard {
+        /// Milliseconds between reloads
+        #[arg(long, default_value_t = 1000)]
+        refresh_ms: u64,
+    },
+    /// Writes the confirmed transactions to a CSV file
+    ExportCsv {
+        /// File to write
+        #[arg(long)]
+        output: PathBuf,
+        /// Only transactions sent by or paying this address
+        #[arg(long)]
+        address: Option<String>,
+        /// Only blocks at or after this Unix time
+        #[arg(long)]
+        from: Option<u32>,
+        /// Only blocks at or before this Unix time
+        #[arg(long)]
+        to: Option<u32>,
+    },
+    /// Writes blocks.parquet and transactions.parquet for analytics tools
+    #[cfg(feature = "analytics")]
+    ExportParquet {
+        /// Directory to write the files in
+        #[arg(long)]
+        output: PathBuf,
+    },
+    /// Opens an interactive prompt (`help` lists its commands)
+    Shell,
+    /// Serves the JSON API over HTTP
+    Serve {
+        /// Address to listen on [default: 127.0.0.1:8080]
+        #[arg(long)]
+        address: Option<String>,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let config = NodeConfig::load(cli.config.as_deref()).map(|mut config| {
+        if let Some(data_dir) = cli.data_dir {
+            config.data_dir = data_dir;
+        }
+        config
+    });
+    match config.and_then(|config| run(&config, cli.command)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(config: &NodeConfig, command: Command) -> Result<(), String> {
+    let data_dir = config.data_dir.as_path();
+    let store = ChainStore::open(data_dir).with_assume_valid(config.network.assume_valid.clone());
+    if let Command::Init { difficulty, reward, genesis } = command {
+        let chain = match genesis {
+            Some(path) => {
+                let mut chain = GenesisConfig::from_file(&path)?.build()?;
+                config.apply_to(&mut chain);
+                chain
+            }
+            None => {
+                let mut config = config.clone();
+                config.network.difficulty = difficulty.unwrap_or(config.network.difficulty);
+                config.network.block_reward = reward.unwrap_or(config.network.block_reward);
+                config.validate()?;
+                config.new_chain()
+            }
+        };
+        store.init(&chain)?;
+        println!("Created a chain in {} (genesis {})", data_dir.display(), chain.get_latest_hash());
+        return Ok(());
+    }
+    if let Command::SignOffline { request, output } = command {
+        let request = SigningRequest::from_json(&read_file(&request)?)?;
+        let keys = KeyStore::load(&data_dir.join(KEYS_FILE))?;
+        let sender = &request.transaction.sender_address;
+        let mut key = keys.entity(sender).ok_or(format!("No key for {} in {}", sender, KEYS_FILE))?;
+        eprintln!("{}", request.summary());
+        let signature = request.sign(&mut key)?;
+        std::fs::write(&output, signature.to_json()).map_err(|error| format!("Cannot write {}: {}", output.display(), error))?;
+        println!("Signed {} (signature in {})", signature.transaction_id, output.display());
+        return Ok(());
+    }
+    if !store.exists() {
+        return Err(format!("No chain in {} (run `blockc init` first)", data_dir.display()));
+    }
+    if let Command::Repair = command {
+        let report = store.repair()?;
+        let Some(damage) = report.damage else {
+            println!("Chain is valid ({} blocks), nothing to repair", report.kept);
+            return Ok(());
+        };
+        println!("{} line {}: {}", BLOCKS_FILE, damage.height + 1, damage);
+        println!("Kept {} blocks, removed {} (the old blocks are in {})", report.kept, report.removed, DAMAGED_BLOCKS_FILE);
+        println!("{} transactions back in the mempool", report.mempool.restored);
+        for (transaction, reason) in &report.mempool.dropped {
+            eprintln!("Dropped pending transaction {}: {}", transaction, reason);
+        }
+        return Ok(());
+    }
+    let (mut chain, restore) = store.load_reporting().map_err(|error| match error.starts_with(BLOCKS_FILE) {
+        true => format!("{} (`blockc repair` truncates the chain to its last valid block)", error),
+        false => error,
+    })?;
+    config.apply_to(&mut chain);
+    if !restore.dropped.is_empty() {
+        for (transaction, reason) in &restore.dropped {
+            eprintln!("Dropped pending transaction {}: {}", transaction, reason);
+        }
+        store.save_mempool(&chain)?;
+    }
+
+    match command {
+        Command::Init { .. } => unreachable!(),
+        Command::Mine { miner, blocks } => {
+            let miner = miner.unwrap_or(config.mining.miner.clone());
+            for _ in 0..blocks.unwrap_or(config.mining.blocks) {
+                let count = chain.add_block_from_mempool(&miner)?;
+                store.save(&chain)?;
+                println!("Block {} {} ({} transactions)", chain.get_blocks().len() - 1, chain.get_latest_hash(), count);
+            }
+        }
+        Command::Send { from, to, amount, fee, memo } => {
+            let mut keys = KeyStore::load(&data_dir.join(KEYS_FILE))?;
+            let mut wallet = Wallet::new(keys.get_or_generate(&from, &mut SimRng::new(entropy())));
+            keys.save()?;
+            wallet.sync_pending(&chain);
+            let contacts = AddressBook::load(&data_dir.join(CONTACTS_FILE))?;
+            let receiver = wallet.resolve_contact(&chain, &contacts, &to)?;
+            let fee = fee.unwrap_or(chain.get_base_fee() * 2.0);
+            let transaction = Transaction::create_payment_with_memo(&mut wallet.entity, &receiver, amount, fee, memo)?;
+            let id = transaction.id();
+            chain.submit_transaction(transaction)?;
+            store.save(&chain)?;
+            println!("Submitted {}", id);
+        }
+        Command::Request { address, amount, memo } => {
+            let payload = PaymentRequest::new(&address, amount, memo)?.qr_payload()?;
+            println!("{}", payload.data);
+            eprintln!("Fits a version {} QR code", payload.version);
+        }
+        Command::PayRequest { uri, from, amount, fee } => {
+            let request = PaymentRequest::parse(&uri)?;
+            let mut keys = KeyStore::load(&data_dir.join(KEYS_FILE))?;
+            let mut wallet = Wallet::new(keys.get_or_generate(&from, &mut SimRng::new(entropy())));
+            keys.save()?;
+            wallet.sync_pending(&chain);
+            let fee = fee.unwrap_or(chain.get_base_fee() * 2.0);
+            let transaction = wallet.pay_request(&chain, &request, amount, fee)?;
+            let id = transaction.id();
+            chain.submit_transaction(transaction)?;
+            store.save(&chain)?;
+            println!("Submitted {}", id);
+        }
+        Command::Invoice { address, amount, expires_in, memo } => {
+            let path = data_dir.join(INVOICES_FILE);
+            let mut invoices = InvoiceBook::load(&path)?;
+            let invoice = invoices.create(&chain, &address, amount, expires_in, memo)?.clone();
+            invoices.save(&path)?;
+            println!("{}", invoice.payment_request());
+            eprintln!("Invoice {} (payable until height {})", invoice.id, invoice.expiry_height);
+        }
+        Command::Invoices => {
+            let path = data_dir.join(INVOICES_FILE);
+            let mut invoices = InvoiceBook::load(&path)?;
+            for event in invoices.update(&chain) {
+                match event {
+                    InvoiceEvent::Paid { invoice_id, transaction_id, height, amount } => {
+                        println!("Invoice {} paid {} by {} at height {}", invoice_id, amount, transaction_id, height);
+                    }
+                    InvoiceEvent::Expired { invoice_id } => println!("Invoice {} expired", invoice_id),
+                }
+            }
+            invoices.save(&path)?;
+            for invoice in invoices.invoices() {
+                let status = match &invoice.status {
+                    InvoiceStatus::Pending => format!("pending until height {}", invoice.expiry_height),
+                    InvoiceStatus::Paid { transaction_id, .. } => format!("paid by {}", transaction_id),
+                    InvoiceStatus::Expired => "expired".to_string(),
+                };
+                println!("{} {} to {} \"{}\": {}", invoice.id, invoice.amount, invoice.address, invoice.memo, status);
+            }
+        }
+        Command::Vanity { pattern, threads, timeout } => {
+            let mut generator = VanityGenerator::new(VanityPattern::parse(&pattern)?);
+            generator.threads = threads.unwrap_or(generator.threads);
+            eprintln!("Searching with {} threads, about {:.0} keys to try", generator.threads, generator.pattern.expected_attempts());
+            let cancel = AtomicBool::new(false);
+            let found = thread::scope(|scope| {
+                if let Some(timeout) = timeout {
+                    let cancel = &cancel;
+                    // Sleeps in short steps so the program exits soon after a match
+                    scope.spawn(move || {
+                        let deadline = Instant::now() + Duration::from_secs(timeout);
+                        while Instant::now() < deadline && !cancel.load(Ordering::Relaxed) {
+                            thread::sleep(Duration::from_millis(50));
+                        }
+                        cancel.store(true, Ordering::Relaxed);
+                    });
+                }
+                let found = generator.search(entropy(), &cancel, &|progress| {
+                    eprint!("\r{} keys tried ({:.0}/s)", progress.attempts, progress.rate());
+                });
+                cancel.store(true, Ordering::Relaxed);
+                found
+            });
+            eprintln!();
+            let key = found.ok_or(format!("No address matching {} found within the timeout", pattern))?;
+            let mut keys = KeyStore::load(&data_dir.join(KEYS_FILE))?;
+            keys.insert(&key.entity);
+            keys.save()?;
+            println!("{} ({} keys tried, keys saved in {})", key.entity.address, key.attempts, KEYS_FILE);
+        }
+        Command::Watch { address, public_key } => {
+            let path = data_dir.join(WATCH_FILE);
+            let mut wallet = Wallet::watching(load_watch_only(&path)?);
+            let address = match (address, public_key) {
+                (_, Some(public_key)) => wallet.watch_public_key(&public_key)?,
+                (Some(address), None) => {
+                    wallet.watch_address(&address)?;
+                    address
+                }
+                (None, None) => unreachable!(),
+            };
+            save_watch_only(&path, &wallet.watch_only)?;
+            println!("Watching {}", address);
+        }
+        Command::Unwatch { address } => {
+            let path = data_dir.join(WATCH_FILE);
+            let mut wallet = Wallet::watching(load_watch_only(&path)?);
+            wallet.unwatch(&address)?;
+            save_watch_only(&path, &wallet.watch_only)?;
+            println!("Stopped watching {}", address);
+        }
+        Command::Watched => {
+            let wallet = Wallet::watching(load_watch_only(&data_dir.join(WATCH_FILE))?);
+            let contacts = AddressBook::load(&data_dir.join(CONTACTS_FILE))?;
+            for watched in wallet.watched_balances(&chain) {
+                println!(
+                    "{}: {} (spendable {}, nonce {}, {} pending)",
+                    contacts.display(&watched.address), watched.balance, watched.spendable, watched.nonce, watched.pending
+                );
+            }
+        }
+        Command::BuildUnsigned { from, to, amount, fee, output } => {
+            let wallet = Wallet::watching(load_watch_only(&data_dir.join(WATCH_FILE))?);
+            let contacts = AddressBook::load(&data_dir.join(CONTACTS_FILE))?;
+            let fee = fee.unwrap_or(chain.get_base_fee() * 2.0);
+            let to = wallet.resolve_contact(&chain, &contacts, &to)?;
+            match output {
+                Some(output) => {
+                    let request = wallet.build_signing_request(&chain, contacts.resolve(&from), &to, amount, fee)?;
+                    std::fs::write(&output, request.to_json()).map_err(|error| format!("Cannot write {}: {}", output.display(), error))?;
+                    println!("Wrote the signing request to {} (payload {})", output.display(), request.payload_hash());
+                }
+                None => {
+                    let transaction = wallet.build_unsigned_payment(&chain, contacts.resolve(&from), &to, amount, fee)?;
+                    println!("{}", transaction.to_json());
+                }
+            }
+        }
+        Command::SignOffline { .. } | Command::Repair => unreachable!(),
+        Command::ImportSigned { request, signature } => {
+            let request = SigningRequest::from_json(&read_file(&request)?)?;
+            let signature = OfflineSignature::from_json(&read_file(&signature)?)?;
+            let transaction = request.import(&chain, &signature)?;
+            let id = transaction.id();
+            chain.submit_transaction(transaction)?;
+            store.save(&chain)?;
+            println!("Submitted {}", id);
+        }
+        Command::Balance { address } => {
+            let contacts = AddressBook::load(&data_dir.join(CONTACTS_FILE))?;
+            let address = contacts.resolve(&address);
+            println!("{}: {} (nonce {})", contacts.display(address), chain.get_balance(address), chain.get_nonce(address));
+        }
+        Command::History { address } => {
+            let contacts = AddressBook::load(&data_dir.join(CONTACTS_FILE))?;
+            for (height, transaction) in chain.get_history(contacts.resolve(&address)) {
+                println!(
+                    "#{} {} -> {} {} (fee {})",
+                    height, contacts.display(&transaction.sender_address), contacts.display(&transaction.receiver_address), transaction.amount, transaction.fee
+                );
+            }
+        }
+        Command::Contacts => {
+            for (label, address) in AddressBook::load(&data_dir.join(CONTACTS_FILE))?.contacts() {
+                println!("{} {}", label, address);
+            }
+        }
+        Command::AddContact { label, address } => {
+            let mut contacts = AddressBook::load(&data_dir.join(CONTACTS_FILE))?;
+            match contacts.add(&label, &address)? {
+                Some(previous) => println!("{} is now {} (was {})", label, address, previous),
+                None => println!("Added {} ({})", label, address),
+            }
+            contacts.save(&data_dir.join(CONTACTS_FILE))?;
+        }
+        Command::RemoveContact { label } => {
+            let mut contacts = AddressBook::load(&data_dir.join(CONTACTS_FILE))?;
+            let address = contacts.remove(&label)?;
+            contacts.save(&data_dir.join(CONTACTS_FILE))?;
+            println!("Removed {} ({})", label, address);
+        }
+        Command::Webhook { address, url, confirmations } => {
+            let mut notifier = Notifier::load(&data_dir.join(WEBHOOKS_FILE))?;
+            let address = AddressBook::load(&data_dir.join(CONTACTS_FILE))?.resolve(&address).to_string();
+            let id = notifier.add_webhook(&chain, &address, &url, confirmations)?;
+            notifier.save()?;
+            println!("Webhook {}: {} -> {}", id, address, url);
+        }
+        Command::Webhooks => {
+            for subscription in Notifier::load(&data_dir.join(WEBHOOKS_FILE))?.subscriptions() {
+                if let NotifyTarget::Webhook(url) = &subscription.target {
+                    println!("{} {} -> {} ({} confirmations, next block {})", subscription.id, subscription.address, url, subscription.confirmations, subscription.next_height);
+                }
+            }
+        }
+        Command::RemoveWebhook { id } => {
+            let mut notifier = Notifier::load(&data_dir.join(WEBHOOKS_FILE))?;
+            notifier.unsubscribe(id)?;
+            notifier.save()?;
+            println!("Removed webhook {}", id);
+        }
+        Command::Validate => {
+            chain.validate()?;
+            println!("Chain is valid ({} blocks)", chain.get_blocks().len());
+        }
+        Command::Reindex => {
+            let report = chain.reindex()?;
+            println!("Replayed {} blocks: {} transactions indexed, {} accounts cached", report.blocks, report.transactions, report.accounts);
+            match report.stale.is_empty() {
+                true => println!("Every index was up to date"),
+                false => println!("Rebuilt stale indexes: {}", report.stale.join(", ")),
+            }
+        }
+        Command::Dot => print!("{}", chain.to_dot()),
+        Command::Mermaid { block: None } => print!("{}", chain.to_mermaid()),
+        Command::Mermaid { block: Some(height) } => {
+            let block = chain.get_blocks().get(height).ok_or(format!("No block at height {}", height))?;
+            print!("{}", mermaid::block_flow_to_mermaid(block));
+        }
+        Command::ExportCsv { output, address, from, to } => {
+            let filter = LedgerFilter {
+                address,
+                from_time: from,
+                to_time: to,
+            };
+            let rows = chain.export_csv(&output, &filter)?;
+            println!("Wrote {} transactions to {}", rows, output.display());
+        }
+        #[cfg(feature = "analytics")]
+        Command::ExportParquet { output } => {
+            std::fs::create_dir_all(&output).map_err(|error| format!("Cannot create {}: {}", output.display(), error))?;
+            let (blocks, transactions) = blockc::export::analytics::write_parquet(&chain, &output)?;
+            println!("Wrote {} blocks and {} transactions to {}", blocks, transactions, output.display());
+        }
+        Command::Shell => run_shell(&store, Session::open(data_dir)?, chain)?,
+        #[cfg(feature = "tui")]
+        Command::Dashboard { refresh_ms } => run_dashboard(&store, config, chain, refresh_ms)?,
+        Command::Serve { address } => {
+            let address = address.unwrap_or(config.rpc.bind.clone());
+            let listener = TcpListener::bind(&address).map_err(|error| format!("Cannot listen on {}: {}", address, error))?;
+            println!("Serving {} on http://{}", data_dir.display(), address);
+            let mut notifier = Notifier::load(&data_dir.join(WEBHOOKS_FILE))?;
+            // Blocks mined while the node was not serving are notified first
+            notify(&mut notifier, &chain);
+            let mut limiter = RateLimiter::new(config.rpc.rate_limit());
+            server::serve(&listener, &SharedBlockchain::new(chain), &mut limiter, &mut |chain| {
+                store.save(chain)?;
+                notify(&mut notifier, chain);
+                Ok(())
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Completes shell commands against the chain being explored.
+struct ShellHelper {
+    chain: Rc<RefCell<Blockchain>>,
+    session: Rc<RefCell<Session>>,
+}
+
+impl Completer for ShellHelper {
+    type Candidate = String;
+
+    fn complete(&self, line: &str, pos: usize, _: &Context<'_>) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(shell::complete(&self.chain.borrow(), &self.session.borrow().contacts, &line[..pos]))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+fn run_shell(store: &ChainStore, session: Session, chain: Blockchain) -> Result<(), String> {
+    let chain = Rc::new(RefCell::new(chain));
+    let session = Rc::new(RefCell::new(session));
+    let mut editor: Editor<ShellHelper, DefaultHistory> = Editor::new().map_err(|error| error.to_string())?;
+    editor.set_helper(Some(ShellHelper { chain: chain.clone(), session: session.clone() }));
+    loop {
+        let line = match editor.readline("blockc> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => return Ok(()),
+            Err(error) => return Err(error.to_string()),
+        };
+        let _ = editor.add_history_entry(line.as_str());
+        let result = shell::execute(&mut chain.borrow_mut(), &mut session.borrow_mut(), &line);
+        match result {
+            Ok(ShellAction::Print(output)) if output.is_empty() => {}
+            Ok(ShellAction::Print(output)) => println!("{}", output),
+            Ok(ShellAction::Changed(output)) => {
+                println!("{}", output);
+                store.save(&chain.borrow())?;
+                session.borrow().save()?;
+            }
+            Ok(ShellAction::Exit) => return Ok(()),
+            Err(error) => eprintln!("error: {}", error),
+        }
+    }
+}
+
+#[cfg(feature = "tui")]
+fn run_dashboard(store: &ChainStore, config: &NodeConfig, mut chain: Blockchain, refresh_ms: u64) -> Result<(), String> {
+    use blockc::dashboard::dashboard::{Dashboard, Key};
+    use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+
+    let refresh = Duration::from_millis(refresh_ms.max(50));
+    let mut dashboard = Dashboard::new(0);
+    let mut terminal = ratatui::init();
+    let mut reloaded = Instant::now();
+    let result = loop {
+        if let Err(error) = terminal.draw(|frame| dashboard.render(frame, &chain)) {
+            break Err(error.to_string());
+        }
+        let timeout = refresh.saturating_sub(reloaded.elapsed());
+        match event::poll(timeout) {
+            Ok(true) => {
+                if let Ok(Event::Key(key)) = event::read()
+                    && key.kind == KeyEventKind::Press {
+                    let key = match key.code {
+                        KeyCode::Up | KeyCode::Char('k') => Some(Key::Up),
+                        KeyCode::Down | KeyCode::Char('j') => Some(Key::Down),
+                        KeyCode::Enter => Some(Key::Enter),
+                        KeyCode::Esc | KeyCode::Backspace => Some(Key::Back),
+                        KeyCode::Char('q') => Some(Key::Quit),
+                        _ => None,
+                    };
+                    if let Some(key) = key {
+                        dashboard.handle_key(key, &chain);
+                    }
+                }
+            }
+            Ok(false) => {}
+            Err(error) => break Err(error.to_string()),
+        }
+        if dashboard.quit {
+            break Ok(());
+        }
+        if reloaded.elapsed() >= refresh {
+            // A writer may be halfway through saving: keep the last good copy
+            if let Ok(mut fresh) = store.load() {
+                config.apply_to(&mut fresh);
+                chain = fresh;
+            }
+            reloaded = Instant::now();
+        }
+    };
+    ratatui::restore();
+    result
+}
+
+/// Delivers the webhook notifications due on `chain` and saves how far each got.
+fn notify(notifier: &mut Notifier, chain: &Blockchain) {
+    let report = notifier.check(chain);
+    for error in &report.errors {
+        eprintln!("{}", error);
+    }
+    if let Err(error) = notifier.save() {
+        eprintln!("{}", error);
+    }
+}
+
+fn read_file(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|error| format!("Cannot read {}: {}", path.display(), error))
+}
+
+/// Seed for new keys. `SimRng` is not a cryptographic generator: keys made
+/// here are only fit for a learning chain.
+fn entropy() -> u64 {
+    blockc::helpers::random::entropy() ^ ((std::process::id() as u64) << 32)
 }
@@ -0,0 +1,711 @@
+use crate::block::ordering;
+use crate::blockchain::memory;
+use crate::transactions::transactions::{Transaction, TransactionKind};
+use crate::utxo::utxo::OutPoint;
+use crate::state::state::ChainState;
+use crate::fees::gas::BLOCK_GAS_LIMIT;
+
+/// Rules a replacement transaction must follow to replace a pending one.
+///
+/// # Fields
+///
+/// * `min_bump_percent` - The new fee must be at least this many percent higher
+/// * `min_bump_absolute` - The new fee must also be at least this much higher
+#[derive(Debug, Clone)]
+pub struct RbfPolicy {
+    pub min_bump_percent: f64,
+    pub min_bump_absolute: f64,
+}
+
+impl Default for RbfPolicy {
+    fn default() -> Self {
+        RbfPolicy {
+            min_bump_percent: 10.0,
+            min_bump_absolute: 0.01,
+        }
+    }
+}
+
+impl RbfPolicy {
+    /// Returns the minimum fee a replacement of a transaction paying `old_fee` must pay.
+    pub fn min_replacement_fee(&self, old_fee: f64) -> f64 {
+        (old_fee * (1.0 + self.min_bump_percent / 100.0)).max(old_fee + self.min_bump_absolute)
+    }
+}
+
+/// Limits on chains of dependent pending transactions.
+///
+/// Counts include the transaction itself, so the defaults allow a chain of 25
+/// pending transactions.
+///
+/// # Fields
+///
+/// * `max_ancestors` - Most pending ancestors a transaction can have, plus itself
+/// * `max_descendants` - Most pending descendants a transaction can have, plus itself
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PackageLimits {
+    pub max_ancestors: usize,
+    pub max_descendants: usize,
+}
+
+impl Default for PackageLimits {
+    fn default() -> Self {
+        PackageLimits {
+            max_ancestors: 25,
+            max_descendants: 25,
+        }
+    }
+}
+
+/// A pending transaction together with its pending ancestors.
+///
+/// The transactions are in an order that can be applied (parents first, nonces in
+/// sequence), so block assembly includes or skips them together.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Package {
+    pub transactions: Vec<Transaction>,
+}
+
+impl Package {
+    /// Returns the sum of the fees of the package.
+    pub fn fees(&self) -> f64 {
+        self.transactions.iter().map(|t| t.fee).sum()
+    }
+
+    /// Returns the sum of the gas limits of the package (saturating at `u64::MAX`).
+    pub fn gas(&self) -> u64 {
+        self.transactions.iter().fold(0, |gas, t| gas.saturating_add(t.gas_limit))
+    }
+
+    /// Returns the average fee per transaction, the score block assembly sorts by.
+    pub fn fee_rate(&self) -> f64 {
+        if self.transactions.is_empty() {
+            return 0.0;
+        }
+        self.fees() / self.transactions.len() as f64
+    }
+}
+
+/// Something that happened in the mempool that wallets may want to know about.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MempoolEvent {
+    /// A new transaction is pending
+    Added { id: String },
+    /// A pending transaction was superseded by a higher-fee version
+    Replaced { old_id: String, new_id: String, sender: String, nonce: u64 },
+    /// A pending transaction was dropped to make room in a full pool
+    Evicted { id: String },
+}
+
+/// Pool of signed transactions waiting to be included in a block.
+///
+/// The mempool only checks the transaction itself; balances and the base fee are
+/// checked by the chain when the transaction is submitted and again when it is
+/// included in a block.
+///
+/// A sender can replace one of its pending transactions by submitting another one
+/// with the same nonce and a fee high enough for the `RbfPolicy` (replace-by-fee).
+///
+/// Pending transactions can depend on each other: a transaction depends on the
+/// pending transactions from the same sender with a lower nonce, on those spending
+/// to its sender (they may fund it) and on those whose outputs it spends. The
+/// `PackageLimits` cap how long these chains get.
+///
+/// # Fields
+///
+/// * `pending` - Transactions waiting for a block, in arrival order
+/// * `rbf_policy` - Minimum fee bump for replacements
+/// * `package_limits` - Longest chains of dependent transactions
+/// * `max_transactions` - Most transactions pending at once (`None` for no limit)
+/// * `bytes` - Approximate memory of the pending transactions
+/// * `max_bytes` - Most memory the pending transactions may take (`None` for no limit)
+/// * `events` - Events not yet collected with `take_events`
+#[derive(Debug, Clone, Default)]
+pub struct Mempool {
+    pending: Vec<Transaction>,
+    rbf_policy: RbfPolicy,
+    package_limits: PackageLimits,
+    max_transactions: Option<usize>,
+    bytes: usize,
+    max_bytes: Option<usize>,
+    events: Vec<MempoolEvent>,
+}
+
+impl Mempool {
+    /// Creates an empty mempool with the default replace-by-fee policy.
+    pub fn new() -> Self {
+        Mempool {
+            pending: Vec::new(),
+            rbf_policy: RbfPolicy::default(),
+            package_limits: PackageLimits::default(),
+            max_transactions: None,
+            bytes: 0,
+            max_bytes: None,
+            events: Vec::new(),
+        }
+    }
+
+    /// Returns the replace-by-fee policy.
+    pub fn rbf_policy(&self) -> &RbfPolicy {
+        &self.rbf_policy
+    }
+
+    /// Changes the replace-by-fee policy.
+    pub fn set_rbf_policy(&mut self, policy: RbfPolicy) {
+        self.rbf_policy = policy;
+    }
+
+    /// Returns the limits on chains of dependent transactions.
+    pub fn package_limits(&self) -> PackageLimits {
+        self.package_limits
+    }
+
+    /// Changes the limits on chains of dependent transactions.
+    ///
+    /// Transactions already pending are kept; the limits apply to new ones.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::mempool::mempool::{Mempool, PackageLimits};
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// use blockc::entity::entity::Entity;
+    ///
+    /// let mut alice = Entity::new("Alice".to_string(), 100.0, Vec::new(), "pub".to_string(), "priv".to_string());
+    /// let mut mempool = Mempool::new();
+    /// mempool.set_package_limits(PackageLimits { max_ancestors: 2, max_descendants: 25 });
+    ///
+    /// // Nonces 0 and 1 fit, nonce 2 would have two pending ancestors
+    /// mempool.add(Transaction::create_payment(&mut alice, "Bob", 1.0, 0.1).unwrap()).unwrap();
+    /// mempool.add(Transaction::create_payment(&mut alice, "Bob", 1.0, 0.1).unwrap()).unwrap();
+    /// assert!(mempool.add(Transaction::create_payment(&mut alice, "Bob", 1.0, 0.1).unwrap()).is_err());
+    /// ```
+    pub fn set_package_limits(&mut self, limits: PackageLimits) {
+        self.package_limits = limits;
+    }
+
+    /// Returns the approximate memory taken by the pending transactions, in bytes
+    /// (see `memory::transaction_bytes`).
+    pub fn memory_bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// Returns the most memory the pending transactions may take (`None` for no limit).
+    pub fn max_bytes(&self) -> Option<usize> {
+        self.max_bytes
+    }
+
+    /// Limits the memory the pending transactions may take, in bytes.
+    ///
+    /// A new transaction that would go over the limit evicts pending ones the same
+    /// way as when the pool is full (see `set_max_transactions`), as many as needed
+    /// to fit; if it doesn't pay enough to evict them, it is rejected and nothing is
+    /// evicted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::memory::transaction_bytes;
+    /// use blockc::mempool::mempool::Mempool;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// use blockc::entity::entity::Entity;
+    ///
+    /// let mut alice = Entity::new("Alice".to_string(), 100.0, Vec::new(), "pub".to_string(), "priv".to_string());
+    /// let mut bob = Entity::new("Bob".to_string(), 100.0, Vec::new(), "pub2".to_string(), "priv2".to_string());
+    /// let cheap = Transaction::create_payment(&mut alice, "Carol", 1.0, 0.1).unwrap();
+    /// let better = Transaction::create_payment(&mut bob, "Carol", 1.0, 0.2).unwrap();
+    ///
+    /// let mut mempool = Mempool::new();
+    /// mempool.set_max_bytes(Some(transaction_bytes(&cheap) + 10));
+    /// mempool.add(cheap.clone()).unwrap();
+    /// assert_eq!(mempool.memory_bytes(), transaction_bytes(&cheap));
+    ///
+    /// mempool.add(better.clone()).unwrap();
+    /// assert!(!mempool.contains(&cheap.id()));
+    /// assert_eq!(mempool.memory_bytes(), transaction_bytes(&better));
+    /// ```
+    pub fn set_max_bytes(&mut self, max_bytes: Option<usize>) {
+        self.max_bytes = max_bytes;
+    }
+
+    /// Returns the most transactions the pool holds (`None` for no limit).
+    pub fn max_transactions(&self) -> Option<usize> {
+        self.max_transactions
+    }
+
+    /// Limits how many transactions can be pending.
+    ///
+    /// When the pool is full, a new transaction evicts the pending transaction whose
+    /// descendant package (itself and everything depending on it) has the lowest
+    /// average fee, together with those descendants, if the new transaction pays a
+    /// higher fee; otherwise it is rejected. A transaction never evicts its own
+    /// ancestors, and replacements are always accepted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::mempool::mempool::{Mempool, MempoolEvent};
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// use blockc::entity::entity::Entity;
+    ///
+    /// let mut alice = Entity::new("Alice".to_string(), 100.0, Vec::new(), "pub".to_string(), "priv".to_string());
+    /// let mut bob = Entity::new("Bob".to_string(), 100.0, Vec::new(), "pub2".to_string(), "priv2".to_string());
+    /// let mut mempool = Mempool::new();
+    /// mempool.set_max_transactions(Some(1));
+    ///
+    /// let cheap = Transaction::create_payment(&mut alice, "Carol", 1.0, 0.1).unwrap();
+    /// mempool.add(cheap.clone()).unwrap();
+    ///
+    /// // Alice's next payment depends on the pending one and can't evict it
+    /// assert!(mempool.add(Transaction::create_payment(&mut alice, "Carol", 1.0, 0.5).unwrap()).is_err());
+    /// // Bob's pays as little and is rejected
+    /// assert!(mempool.add(Transaction::create_payment(&mut bob, "Carol", 1.0, 0.1).unwrap()).is_err());
+    ///
+    /// // A better-paying one takes its place
+    /// mempool.add(Transaction::create_payment(&mut bob, "Carol", 1.0, 0.2).unwrap()).unwrap();
+    /// assert!(!mempool.contains(&cheap.id()));
+    /// assert!(mempool.take_events().contains(&MempoolEvent::Evicted { id: cheap.id() }));
+    /// ```
+    pub fn set_max_transactions(&mut self, max_transactions: Option<usize>) {
+        self.max_transactions = max_transactions;
+    }
+
+    /// Adds a transaction to the pool.
+    ///
+    /// If the sender already has a pending transaction with the same nonce, the new
+    /// one replaces it when its fee is high enough, and a `MempoolEvent::Replaced`
+    /// is recorded. A UTXO transaction spending an output that another pending
+    /// transaction already spends is rejected as a double spend.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the transaction was added
+    /// * `Err(String)` - If it is a coinbase transaction, has a negative amount/fee,
+    ///   a memo over the size limit, a malformed contract payload (see
+    ///   `Transaction::check_payload`), is already pending, is a double spend, is a
+    ///   replacement that doesn't pay enough or would break the `PackageLimits`, or if
+    ///   the pool is full and it can't evict anything (see `set_max_transactions`)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::mempool::mempool::{Mempool, MempoolEvent};
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// use blockc::entity::entity::Entity;
+    ///
+    /// let mut alice = Entity::new("Alice".to_string(), 100.0, Vec::new(), "pub".to_string(), "priv".to_string());
+    /// let mut bob = Entity::new("Bob".to_string(), 0.0, Vec::new(), "pub2".to_string(), "priv2".to_string());
+    ///
+    /// let mut mempool = Mempool::new();
+    /// let original = Transaction::create_and_execute_with_fee(&mut alice, &mut bob, 10.0, 0.1).unwrap();
+    /// mempool.add(original.clone()).unwrap();
+    ///
+    /// let replacement = Transaction::create_replacement(&mut alice, &original, 0.5).unwrap();
+    /// mempool.add(replacement.clone()).unwrap();
+    ///
+    /// assert_eq!(mempool.len(), 1);
+    /// assert!(mempool.take_events().contains(&MempoolEvent::Replaced {
+    ///     old_id: original.id(),
+    ///     new_id: replacement.id(),
+    ///     sender: "Alice".to_string(),
+    ///     nonce: 0,
+    /// }));
+    /// ```
+    pub fn add(&mut self, transaction: Transaction) -> Result<(), String> {
+        if transaction.is_coinbase() {
+            return Err("Coinbase transactions cannot be submitted".to_string());
+        }
+        if transaction.amount < 0.0 || transaction.fee < 0.0 {
+            return Err("Transaction amount and fee cannot be negative".to_string());
+        }
+        if transaction.gas_limit > BLOCK_GAS_LIMIT {
+            return Err(format!("Gas limit {} is over the block gas limit {}", transaction.gas_limit, BLOCK_GAS_LIMIT));
+        }
+        transaction.check_memo()?;
+        transaction.check_payload()?;
+        if self.contains(&transaction.id()) {
+            return Err("Transaction is already pending".to_string());
+        }
+
+        // Same sender and nonce: this is a replacement
+        let existing = self.pending.iter().position(|t| {
+            t.sender_address == transaction.sender_address && t.nonce == transaction.nonce
+        });
+
+        // Spending an output a pending transaction already spends is a double spend
+        let spent = Mempool::spent_outpoints(&transaction);
+        let conflict = self.pending.iter().any(|t| {
+            !(t.sender_address == transaction.sender_address && t.nonce == transaction.nonce)
+                && Mempool::spent_outpoints(t).iter().any(|outpoint| spent.contains(outpoint))
+        });
+        if conflict {
+            return Err("Transaction spends an output already spent by a pending transaction (double spend)".to_string());
+        }
+
+        self.check_package_limits(&transaction, existing)?;
+        let size = memory::transaction_bytes(&transaction);
+
+        if let Some(index) = existing {
+            let min_fee = self.rbf_policy.min_replacement_fee(self.pending[index].fee);
+            if transaction.fee < min_fee {
+                return Err(format!("Replacement fee {} is too low, need at least {}", transaction.fee, min_fee));
+            }
+            let old = std::mem::replace(&mut self.pending[index], transaction);
+            self.bytes = self.bytes - memory::transaction_bytes(&old) + size;
+            let new = &self.pending[index];
+            self.events.push(MempoolEvent::Replaced {
+                old_id: old.id(),
+                new_id: new.id(),
+                sender: new.sender_address.clone(),
+                nonce: new.nonce,
+            });
+            return Ok(());
+        }
+
+        let slots = self.max_transactions.map_or(0, |max| (self.pending.len() + 1).saturating_sub(max));
+        let bytes = self.max_bytes.map_or(0, |max| (self.bytes + size).saturating_sub(max));
+        if slots > 0 || bytes > 0 {
+            self.evict_for(&transaction, slots, bytes)?;
+        }
+        self.events.push(MempoolEvent::Added { id: transaction.id() });
+        self.bytes += size;
+        self.pending.push(transaction);
+        Ok(())
+    }
+
+    /// Checks that adding `transaction` (in place of the pending transaction at
+    /// `replaced`, if any) keeps its ancestors and their descendants within the
+    /// `PackageLimits`.
+    fn check_package_limits(&self, transaction: &Transaction, replaced: Option<usize>) -> Result<(), String> {
+        let mut all: Vec<&Transaction> = self.pending.iter().enumerate()
+            .filter(|(i, _)| Some(*i) != replaced)
+            .map(|(_, t)| t)
+            .collect();
+        all.push(transaction);
+        let newcomer = all.len() - 1;
+
+        let ancestors = Mempool::package_indices(&all, newcomer);
+        if ancestors.len() > self.package_limits.max_ancestors {
+            return Err(format!(
+                "Transaction has too many pending ancestors (chain of {}, limit {})",
+                ancestors.len(), self.package_limits.max_ancestors,
+            ));
+        }
+        for &ancestor in &ancestors {
+            let descendants = Mempool::descendant_indices(&all, ancestor);
+            if descendants.len() > self.package_limits.max_descendants {
+                return Err(format!(
+                    "Pending transaction {} would have too many descendants (chain of {}, limit {})",
+                    all[ancestor].id(), descendants.len(), self.package_limits.max_descendants,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Makes room for `transaction` by evicting at least `slots` transactions and
+    /// `bytes` bytes (see `set_max_transactions` and `set_max_bytes`). Nothing is
+    /// evicted if that isn't possible.
+    fn evict_for(&mut self, transaction: &Transaction, slots: usize, bytes: usize) -> Result<(), String> {
+        let mut all: Vec<&Transaction> = self.pending.iter().collect();
+        all.push(transaction);
+        let newcomer = all.len() - 1;
+        let ancestors = Mempool::package_indices(&all, newcomer);
+
+        let mut evicted: Vec<usize> = Vec::new();
+        let (mut freed_slots, mut freed_bytes) = (0, 0);
+        while freed_slots < slots || freed_bytes < bytes {
+            // Lowest average fee over a transaction and its descendants
+            let mut lowest: Option<(f64, Vec<usize>)> = None;
+            for index in (0..newcomer).filter(|i| !ancestors.contains(i) && !evicted.contains(i)) {
+                let descendants: Vec<usize> = Mempool::descendant_indices(&all, index).into_iter()
+                    .filter(|i| !evicted.contains(i))
+                    .collect();
+                let fee_rate = descendants.iter().map(|&i| all[i].fee).sum::<f64>() / descendants.len() as f64;
+                if lowest.as_ref().is_none_or(|(rate, _)| fee_rate < *rate) {
+                    lowest = Some((fee_rate, descendants));
+                }
+            }
+            let Some((_, descendants)) = lowest.filter(|(fee_rate, _)| transaction.fee > *fee_rate) else {
+                return Err(format!(
+                    "Mempool is full ({} transactions, {} bytes) and the fee is too low to evict enough",
+                    self.pending.len(), self.bytes,
+                ));
+            };
+            freed_slots += descendants.len();
+            freed_bytes += descendants.iter().map(|&i| memory::transaction_bytes(all[i])).sum::<usize>();
+            evicted.extend(descendants);
+        }
+
+        evicted.sort_unstable_by(|a, b| b.cmp(a));
+        for i in evicted {
+            let removed = self.pending.remove(i);
+            self.bytes -= memory::transaction_bytes(&removed);
+            self.events.push(MempoolEvent::Evicted { id: removed.id() });
+        }
+        Ok(())
+    }
+
+    /// Returns and clears the events recorded since the last call.
+    pub fn take_events(&mut self) -> Vec<MempoolEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Returns `true` if a transaction with the given id is pending.
+    pub fn contains(&self, id: &str) -> bool {
+        self.pending.iter().any(|t| t.id() == id)
+    }
+
+    /// Returns all pending transactions, in arrival order.
+    pub fn transactions(&self) -> &[Transaction] {
+        &self.pending
+    }
+
+    /// Returns the number of pending transactions.
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Returns `true` if nothing is pending.
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Picks up to `max` transactions paying at least `min_fee` for the next block.
+    ///
+    /// Transactions are picked by package: a transaction together with all of its
+    /// pending ancestors (see `ancestors`). The package with the highest average fee
+    /// goes first, so a high-fee child pulls in its low-fee parent (child pays for
+    /// parent). Each package is checked against a copy of `state` and added in an
+    /// order that can be applied (parents first, nonces in sequence); packages that
+    /// would fail are skipped, and so are packages whose gas limits would push the
+    /// block over `BLOCK_GAS_LIMIT`.
+    ///
+    /// The selected transactions stay in the pool until they are confirmed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// use blockc::entity::entity::Entity;
+    ///
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Alice").unwrap(); // Alice has 45.0 on chain
+    /// let base_fee = chain.get_base_fee();
+    ///
+    /// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+    /// let mut bob = Entity::new("Bob".to_string(), 0.0, Vec::new(), "b".to_string(), "b".to_string());
+    /// let mut carol = Entity::new("Carol".to_string(), 0.0, Vec::new(), "c".to_string(), "c".to_string());
+    ///
+    /// // Low-fee parent funds Bob, whose child transaction pays a big fee
+    /// let parent = Transaction::create_and_execute_with_fee(&mut alice, &mut bob, 20.0, base_fee).unwrap();
+    /// let child = Transaction::create_and_execute_with_fee(&mut bob, &mut carol, 5.0, 3.0).unwrap();
+    /// chain.submit_transaction(parent.clone()).unwrap();
+    /// chain.mempool_mut().add(child.clone()).unwrap();
+    ///
+    /// let selected = chain.mempool().select(2, base_fee, chain.state());
+    /// assert_eq!(selected[0].id(), parent.id());
+    /// assert_eq!(selected[1].id(), child.id());
+    /// ```
+    pub fn select(&self, max: usize, min_fee: f64, state: &ChainState) -> Vec<Transaction> {
+        let mut remaining: Vec<&Transaction> = self.pending.iter()
+            .filter(|t| t.fee >= min_fee)
+            .collect();
+        let mut simulated = state.clone();
+        let mut selected: Vec<Transaction> = Vec::new();
+        let mut block_gas: u64 = 0;
+
+        while selected.len() < max && !remaining.is_empty() {
+            // Best package = highest average fee among packages that still fit
+            let mut best: Option<(f64, Vec<usize>)> = None;
+            for index in 0..remaining.len() {
+                let package = Mempool::package_indices(&remaining, index);
+                let package_gas = package.iter().fold(0u64, |gas, &i| gas.saturating_add(remaining[i].gas_limit));
+                if selected.len() + package.len() > max || block_gas.saturating_add(package_gas) > BLOCK_GAS_LIMIT {
+                    continue;
+                }
+                let fees: f64 = package.iter().map(|&i| remaining[i].fee).sum();
+                let fee_rate = fees / package.len() as f64;
+                if best.as_ref().is_none_or(|(rate, _)| fee_rate > *rate) {
+                    best = Some((fee_rate, package));
+                }
+            }
+            let Some((_, package)) = best else { break };
+
+            // Apply the package on the simulated state, parents first
+            let ordered = Mempool::order_package(&remaining, &package);
+            let mut attempt = simulated.clone();
+            let applies = ordered.iter().all(|&i| attempt.apply_transaction(remaining[i]).is_ok());
+            if applies {
+                block_gas = ordered.iter().fold(block_gas, |gas, &i| gas.saturating_add(remaining[i].gas_limit));
+                simulated = attempt;
+                selected.extend(ordered.iter().map(|&i| remaining[i].clone()));
+            }
+
+            // Either way these transactions are done for this block
+            let mut done = package;
+            done.sort_unstable_by(|a, b| b.cmp(a));
+            for i in done {
+                remaining.remove(i);
+            }
+        }
+        selected
+    }
+
+    /// Returns the pending ancestors of the transaction with the given id.
+    ///
+    /// A transaction depends on:
+    /// - Pending transactions from the same sender with a lower nonce
+    /// - Pending transactions paying its sender (they may fund it)
+    /// - Pending UTXO transactions whose outputs it spends
+    ///
+    /// Dependencies are followed transitively. The transaction itself is not included.
+    pub fn ancestors(&self, id: &str) -> Vec<Transaction> {
+        let all: Vec<&Transaction> = self.pending.iter().collect();
+        let Some(index) = all.iter().position(|t| t.id() == id) else {
+            return Vec::new();
+        };
+        let package = Mempool::package_indices(&all, index);
+        Mempool::order_package(&all, &package).into_iter()
+            .filter(|&i| i != index)
+            .map(|i| all[i].clone())
+            .collect()
+    }
+
+    /// Returns the pending transactions depending on the transaction with the given
+    /// id, directly or transitively, in arrival order. The transaction itself is not
+    /// included.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::mempool::mempool::Mempool;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// use blockc::entity::entity::Entity;
+    ///
+    /// let mut alice = Entity::new("Alice".to_string(), 100.0, Vec::new(), "a".to_string(), "a".to_string());
+    /// let mut bob = Entity::new("Bob".to_string(), 0.0, Vec::new(), "b".to_string(), "b".to_string());
+    /// let mut carol = Entity::new("Carol".to_string(), 0.0, Vec::new(), "c".to_string(), "c".to_string());
+    ///
+    /// let parent = Transaction::create_and_execute_with_fee(&mut alice, &mut bob, 20.0, 0.1).unwrap();
+    /// let child = Transaction::create_and_execute_with_fee(&mut bob, &mut carol, 5.0, 3.0).unwrap();
+    /// let mut mempool = Mempool::new();
+    /// mempool.add(parent.clone()).unwrap();
+    /// mempool.add(child.clone()).unwrap();
+    ///
+    /// assert_eq!(mempool.descendants(&parent.id()), vec![child.clone()]);
+    /// assert!(mempool.descendants(&child.id()).is_empty());
+    ///
+    /// // The child's package brings its parent along
+    /// let package = mempool.package(&child.id()).unwrap();
+    /// assert_eq!(package.transactions, vec![parent, child]);
+    /// assert_eq!(package.fee_rate(), 1.55);
+    /// ```
+    pub fn descendants(&self, id: &str) -> Vec<Transaction> {
+        let all: Vec<&Transaction> = self.pending.iter().collect();
+        let Some(index) = all.iter().position(|t| t.id() == id) else {
+            return Vec::new();
+        };
+        let mut descendants = Mempool::descendant_indices(&all, index);
+        descendants.sort_unstable();
+        descendants.into_iter()
+            .filter(|&i| i != index)
+            .map(|i| all[i].clone())
+            .collect()
+    }
+
+    /// Returns the package of the transaction with the given id: the transaction and
+    /// its pending ancestors, ordered so they can be applied.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(Package)` - If the transaction is pending
+    /// * `None` - Otherwise
+    pub fn package(&self, id: &str) -> Option<Package> {
+        let all: Vec<&Transaction> = self.pending.iter().collect();
+        let index = all.iter().position(|t| t.id() == id)?;
+        let package = Mempool::package_indices(&all, index);
+        Some(Package {
+            transactions: Mempool::order_package(&all, &package).into_iter().map(|i| all[i].clone()).collect(),
+        })
+    }
+
+    /// Outputs spent by a UTXO transaction (empty for other kinds).
+    fn spent_outpoints(transaction: &Transaction) -> &[OutPoint] {
+        match &transaction.kind {
+            TransactionKind::Utxo { inputs, .. } => inputs,
+            _ => &[],
+        }
+    }
+
+    /// Indices (into `transactions`) of the transaction at `index` and all its ancestors.
+    fn package_indices(transactions: &[&Transaction], index: usize) -> Vec<usize> {
+        Mempool::closure(transactions, index, ordering::depends_on)
+    }
+
+    /// Indices (into `transactions`) of the transaction at `index` and all its descendants.
+    fn descendant_indices(transactions: &[&Transaction], index: usize) -> Vec<usize> {
+        Mempool::closure(transactions, index, |current, candidate| ordering::depends_on(candidate, current))
+    }
+
+    /// Indices reachable from `index` by following `linked(current, candidate)`.
+    fn closure(transactions: &[&Transaction], index: usize, linked: impl Fn(&Transaction, &Transaction) -> bool) -> Vec<usize> {
+        let mut found = vec![index];
+        let mut cursor = 0;
+        while cursor < found.len() {
+            let current = transactions[found[cursor]];
+            for (i, candidate) in transactions.iter().enumerate() {
+                if i != found[cursor] && !found.contains(&i) && linked(current, candidate) {
+                    found.push(i);
+                }
+            }
+            cursor += 1;
+        }
+        found
+    }
+
+    /// Orders a package so parents come before children.
+    ///
+    /// Nonce order within a sender and spent outputs are mandatory; funding parents
+    /// are placed first when possible (funding cycles fall back to the mandatory order).
+    fn order_package(transactions: &[&Transaction], package: &[usize]) -> Vec<usize> {
+        let mut left: Vec<usize> = package.to_vec();
+        let mut ordered: Vec<usize> = Vec::new();
+
+        while !left.is_empty() {
+            let ready = |i: usize, left: &[usize]| !left.iter().any(|&j| {
+                j != i && ordering::must_follow(transactions[i], transactions[j])
+            });
+            let funded = |i: usize, left: &[usize]| !left.iter().any(|&j| {
+                j != i && ordering::funds(transactions[j], transactions[i])
+            });
+
+            let position = left.iter().position(|&i| ready(i, &left) && funded(i, &left))
+                .or_else(|| left.iter().position(|&i| ready(i, &left)))
+                .unwrap_or(0);
+            ordered.push(left.remove(position));
+        }
+        ordered
+    }
+
+    /// Drops every pending transaction (the limits and policies are kept).
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.bytes = 0;
+    }
+
+    /// Removes the transactions that were included in a block.
+    ///
+    /// Pending transactions that reuse a confirmed sender/nonce pair can never be
+    /// included anymore, so they are dropped as well.
+    pub fn remove_confirmed(&mut self, confirmed: &[Transaction]) {
+        let ids: Vec<String> = confirmed.iter().map(|t| t.id()).collect();
+        self.pending.retain(|t| {
+            !ids.contains(&t.id()) && !confirmed.iter().any(|c| {
+                c.sender_address == t.sender_address && c.nonce == t.nonce
+            })
+        });
+        self.bytes = self.pending.iter().map(memory::transaction_bytes).sum();
+    }
+}
@@ -0,0 +1,41 @@
+use crate::transactions::transactions::{Transaction, TransactionKind};
+
+// Merkle trees and their proofs live in `blockc_core` so light clients can use
+// them without `std`.
+pub use blockc_core::hash::hash_pair;
+pub use blockc_core::merkle::{build_proof, merkle_root, MerkleProof, ProofStep};
+
+/// Proof that a document hash was anchored in a given block.
+///
+/// # Fields
+///
+/// * `doc_hash` - The anchored document hash
+/// * `height` - Height of the block containing the anchor transaction
+/// * `timestamp` - Timestamp of that block (the document existed at that time)
+/// * `block_hash` - Hash of that block
+/// * `merkle_root` - Merkle root from that block's header
+/// * `transaction` - The anchor transaction
+/// * `proof` - Merkle proof linking the transaction id to `merkle_root`
+#[derive(Debug, Clone)]
+pub struct ExistenceProof {
+    pub doc_hash: String,
+    pub height: u64,
+    pub timestamp: u32,
+    pub block_hash: String,
+    pub merkle_root: String,
+    pub transaction: Transaction,
+    pub proof: MerkleProof,
+}
+
+impl ExistenceProof {
+    /// Checks the proof on its own:
+    /// 1. The transaction anchors `doc_hash`
+    /// 2. The Merkle proof is for this transaction
+    /// 3. The Merkle proof leads to `merkle_root`
+    ///
+    /// Compare `block_hash` with the chain to make sure the block is really part of it.
+    pub fn verify(&self) -> bool {
+        let anchors_doc = matches!(&self.transaction.kind, TransactionKind::Anchor { doc_hash } if *doc_hash == self.doc_hash);
+        anchors_doc && self.proof.leaf == self.transaction.id() && self.proof.verify(&self.merkle_root)
+    }
+}
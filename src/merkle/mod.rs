@@ -0,0 +1,3 @@
+pub mod merkle;
+pub mod patricia;
+pub mod summation;
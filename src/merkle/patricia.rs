@@ -0,0 +1,279 @@
+use std::collections::BTreeMap;
+use crate::helpers::{self};
+
+/// A node of the trie. Paths are lists of nibbles (half bytes, 0 to 15).
+#[derive(Debug, Clone)]
+enum Node {
+    /// End of a key: the rest of its path and its value
+    Leaf { path: Vec<u8>, value: String },
+    /// Path shared by every key below
+    Extension { path: Vec<u8>, child: Box<Node> },
+    /// One child per next nibble, plus the value of a key ending here
+    Branch { children: Vec<Option<Node>>, value: Option<String> },
+}
+
+/// A node as it appears in a proof: children are replaced by their hashes.
+///
+/// * `Leaf` - Rest of the key's path and its value
+/// * `Extension` - Shared path and hash of the only child
+/// * `Branch` - Hashes of the 16 children (empty when there is none) and the value ending here
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProofNode {
+    Leaf { path: Vec<u8>, value: String },
+    Extension { path: Vec<u8>, child_hash: String },
+    Branch { children: Vec<String>, value: Option<String> },
+}
+
+impl ProofNode {
+    /// Hash of the node (the same hash its parent refers to).
+    pub fn hash(&self) -> String {
+        let data = match self {
+            ProofNode::Leaf { path, value } => format!("leaf:{}:{}", nibbles_to_hex(path), value),
+            ProofNode::Extension { path, child_hash } => format!("ext:{}:{}", nibbles_to_hex(path), child_hash),
+            ProofNode::Branch { children, value } => format!("branch:{}:{}", children.join(","), value.as_deref().unwrap_or("")),
+        };
+        helpers::helper_functions::sha256_hex(&data)
+    }
+}
+
+/// Proof that a key has a given value in a trie (or is absent from it).
+///
+/// # Fields
+///
+/// * `key` - The key proven
+/// * `value` - Its value, or `None` to prove it is absent
+/// * `nodes` - Nodes on the path from the root to the key
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrieProof {
+    pub key: String,
+    pub value: Option<String>,
+    pub nodes: Vec<ProofNode>,
+}
+
+/// A Merkle-Patricia trie: a radix tree over the hex digits of the keys, where
+/// every node is hashed together with the hashes of its children.
+///
+/// The root hash commits to every key and value, and it does not depend on the
+/// order in which keys were inserted. A proof for one key only needs the nodes on
+/// its path, so it stays small even for a big trie.
+///
+/// # Fields
+///
+/// * `entries` - Keys and values stored in the trie
+///
+/// # Example
+///
+/// ```
+/// use blockc::merkle::patricia::{verify_proof, PatriciaTrie};
+///
+/// let mut trie = PatriciaTrie::new();
+/// trie.insert("Alice".to_string(), "10".to_string());
+/// trie.insert("Bob".to_string(), "5".to_string());
+/// let root = trie.root_hash();
+///
+/// let proof = trie.prove("Bob");
+/// assert_eq!(proof.value.as_deref(), Some("5"));
+/// assert!(verify_proof(&root, &proof));
+///
+/// // A forged value does not verify
+/// let mut forged = proof.clone();
+/// forged.value = Some("500".to_string());
+/// assert!(!verify_proof(&root, &forged));
+///
+/// // Absence can be proven too
+/// assert!(verify_proof(&root, &trie.prove("Carol")));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PatriciaTrie {
+    entries: BTreeMap<String, String>,
+}
+
+impl PatriciaTrie {
+    /// Creates an empty trie.
+    pub fn new() -> Self {
+        PatriciaTrie {
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the value of `key`.
+    pub fn insert(&mut self, key: String, value: String) {
+        self.entries.insert(key, value);
+    }
+
+    /// Returns the value of `key`.
+    pub fn get(&self, key: &str) -> Option<&String> {
+        self.entries.get(key)
+    }
+
+    /// Returns the root hash (the hash of an empty string for an empty trie).
+    pub fn root_hash(&self) -> String {
+        match self.build() {
+            Some(root) => node_hash(&root),
+            None => helpers::helper_functions::sha256_hex(""),
+        }
+    }
+
+    /// Builds the proof for `key`: its value (or absence) and the nodes leading to it.
+    pub fn prove(&self, key: &str) -> TrieProof {
+        let mut nodes = Vec::new();
+        let mut value = None;
+        let mut path = key_to_nibbles(key);
+        let mut current = self.build();
+
+        while let Some(node) = current {
+            nodes.push(to_proof_node(&node));
+            current = match node {
+                Node::Leaf { path: leaf_path, value: leaf_value } => {
+                    if leaf_path == path {
+                        value = Some(leaf_value);
+                    }
+                    None
+                }
+                Node::Extension { path: shared, child } => {
+                    if path.starts_with(&shared) {
+                        path.drain(..shared.len());
+                        Some(*child)
+                    } else {
+                        None
+                    }
+                }
+                Node::Branch { mut children, value: branch_value } => {
+                    if path.is_empty() {
+                        value = branch_value;
+                        None
+                    } else {
+                        let nibble = path.remove(0) as usize;
+                        children[nibble].take()
+                    }
+                }
+            };
+        }
+
+        TrieProof {
+            key: key.to_string(),
+            value: value,
+            nodes: nodes,
+        }
+    }
+
+    fn build(&self) -> Option<Node> {
+        let entries: Vec<(Vec<u8>, String)> = self.entries.iter()
+            .map(|(key, value)| (key_to_nibbles(key), value.clone()))
+            .collect();
+        build_node(entries)
+    }
+}
+
+/// Checks `proof` against a trie root hash.
+///
+/// # Returns
+///
+/// `true` if the trie with this root maps `proof.key` to `proof.value` (or does
+/// not contain the key when `proof.value` is `None`).
+pub fn verify_proof(root: &str, proof: &TrieProof) -> bool {
+    if proof.nodes.is_empty() {
+        // Only an empty trie has no nodes
+        return proof.value.is_none() && root == helpers::helper_functions::sha256_hex("");
+    }
+
+    let mut expected = root.to_string();
+    let mut path = key_to_nibbles(&proof.key);
+    for (i, node) in proof.nodes.iter().enumerate() {
+        if node.hash() != expected {
+            return false;
+        }
+        let is_last = i == proof.nodes.len() - 1;
+        let found = match node {
+            ProofNode::Leaf { path: leaf_path, value } => {
+                Some(if *leaf_path == path { Some(value.clone()) } else { None })
+            }
+            ProofNode::Extension { path: shared, child_hash } => {
+                if path.starts_with(shared) {
+                    path.drain(..shared.len());
+                    expected = child_hash.clone();
+                    None
+                } else {
+                    Some(None)
+                }
+            }
+            ProofNode::Branch { children, value } => {
+                if path.is_empty() {
+                    Some(value.clone())
+                } else {
+                    let nibble = path.remove(0) as usize;
+                    match children.get(nibble) {
+                        Some(child) if !child.is_empty() => {
+                            expected = child.clone();
+                            None
+                        }
+                        _ => Some(None),
+                    }
+                }
+            }
+        };
+        if let Some(value) = found {
+            return is_last && value == proof.value;
+        }
+    }
+    // The path continues past the last node given
+    false
+}
+
+fn build_node(mut entries: Vec<(Vec<u8>, String)>) -> Option<Node> {
+    match entries.len() {
+        0 => None,
+        1 => {
+            let (path, value) = entries.remove(0);
+            Some(Node::Leaf { path: path, value: value })
+        }
+        _ => {
+            let shared = common_prefix(&entries);
+            if shared > 0 {
+                let path = entries[0].0[..shared].to_vec();
+                let rest = entries.into_iter().map(|(key, value)| (key[shared..].to_vec(), value)).collect();
+                return Some(Node::Extension { path: path, child: Box::new(build_node(rest)?) });
+            }
+
+            let mut groups: Vec<Vec<(Vec<u8>, String)>> = vec![Vec::new(); 16];
+            let mut value = None;
+            for (key, entry_value) in entries {
+                match key.split_first() {
+                    Some((nibble, rest)) => groups[*nibble as usize].push((rest.to_vec(), entry_value)),
+                    None => value = Some(entry_value),
+                }
+            }
+            Some(Node::Branch { children: groups.into_iter().map(build_node).collect(), value: value })
+        }
+    }
+}
+
+fn common_prefix(entries: &[(Vec<u8>, String)]) -> usize {
+    let first = &entries[0].0;
+    (0..first.len())
+        .take_while(|&i| entries.iter().all(|(key, _)| key.get(i) == Some(&first[i])))
+        .count()
+}
+
+fn to_proof_node(node: &Node) -> ProofNode {
+    match node {
+        Node::Leaf { path, value } => ProofNode::Leaf { path: path.clone(), value: value.clone() },
+        Node::Extension { path, child } => ProofNode::Extension { path: path.clone(), child_hash: node_hash(child) },
+        Node::Branch { children, value } => ProofNode::Branch {
+            children: children.iter().map(|child| child.as_ref().map(node_hash).unwrap_or_default()).collect(),
+            value: value.clone(),
+        },
+    }
+}
+
+fn node_hash(node: &Node) -> String {
+    to_proof_node(node).hash()
+}
+
+fn key_to_nibbles(key: &str) -> Vec<u8> {
+    key.bytes().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect()
+}
+
+fn nibbles_to_hex(nibbles: &[u8]) -> String {
+    nibbles.iter().map(|nibble| format!("{:x}", nibble)).collect()
+}
@@ -0,0 +1,162 @@
+use crate::helpers::{self};
+
+/// A node of a Merkle summation tree: a hash and the sum of the leaves below it.
+///
+/// # Fields
+///
+/// * `hash` - Hash committing to the children's hashes and sums
+/// * `sum` - Sum of the amounts of the leaves below this node
+#[derive(Debug, Clone, PartialEq)]
+pub struct SumNode {
+    pub hash: String,
+    pub sum: f64,
+}
+
+impl SumNode {
+    /// Creates the leaf for `amount` held by `address`.
+    pub fn leaf(address: &str, amount: f64) -> Self {
+        SumNode {
+            hash: helpers::helper_functions::sha256_hex(&format!("sum_leaf:{}:{}", address, amount)),
+            sum: amount,
+        }
+    }
+
+    /// Node standing in for the missing right child of an odd level (sum 0).
+    pub fn empty() -> Self {
+        SumNode {
+            hash: helpers::helper_functions::sha256_hex(""),
+            sum: 0.0,
+        }
+    }
+}
+
+/// Combines two nodes: the parent commits to both hashes and both sums.
+///
+/// Committing to the children's sums (not only their total) means a prover
+/// can't move amounts from one side of the tree to the other.
+pub fn combine(left: &SumNode, right: &SumNode) -> SumNode {
+    SumNode {
+        hash: helpers::helper_functions::sha256_hex(&format!("{}:{}:{}:{}", left.hash, left.sum, right.hash, right.sum)),
+        sum: left.sum + right.sum,
+    }
+}
+
+/// One step of a summation proof: the sibling node and which side it is on.
+///
+/// # Fields
+///
+/// * `node` - The sibling
+/// * `is_left` - `true` if the sibling is the left node of the pair
+#[derive(Debug, Clone, PartialEq)]
+pub struct SumProofStep {
+    pub node: SumNode,
+    pub is_left: bool,
+}
+
+/// Proof that a leaf, with its amount, is counted in a summation tree's root.
+///
+/// # Fields
+///
+/// * `leaf` - The leaf being proven
+/// * `steps` - Siblings from the bottom of the tree to the top
+#[derive(Debug, Clone, PartialEq)]
+pub struct SumProof {
+    pub leaf: SumNode,
+    pub steps: Vec<SumProofStep>,
+}
+
+impl SumProof {
+    /// Returns `true` if this proof links `leaf` to `root`.
+    ///
+    /// Every sibling must have a non-negative sum, so the leaf's amount can't be
+    /// cancelled out by a negative amount elsewhere in the tree.
+    pub fn verify(&self, root: &SumNode) -> bool {
+        let mut current = self.leaf.clone();
+        for step in &self.steps {
+            if step.node.sum < 0.0 {
+                return false;
+            }
+            current = if step.is_left {
+                combine(&step.node, &current)
+            } else {
+                combine(&current, &step.node)
+            };
+        }
+        current == *root
+    }
+}
+
+/// Calculates the root of a Merkle summation tree.
+///
+/// Like `merkle_root`, but every node also carries the sum of its leaves, so the
+/// root commits to the total. When a level has an odd number of nodes, the last
+/// one is paired with `SumNode::empty()` (pairing it with itself would count it twice).
+///
+/// # Returns
+///
+/// The root, or `SumNode::empty()` if there are no leaves.
+///
+/// # Example
+///
+/// ```
+/// use blockc::merkle::summation::{build_sum_proof, sum_root, SumNode};
+///
+/// let leaves = vec![SumNode::leaf("Alice", 10.0), SumNode::leaf("Bob", 5.0), SumNode::leaf("Carol", 1.0)];
+/// let root = sum_root(&leaves);
+/// assert_eq!(root.sum, 16.0);
+///
+/// let proof = build_sum_proof(&leaves, 1).unwrap();
+/// assert!(proof.verify(&root));
+///
+/// // Claiming a different amount breaks the proof
+/// let mut inflated = proof.clone();
+/// inflated.leaf = SumNode::leaf("Bob", 50.0);
+/// assert!(!inflated.verify(&root));
+/// ```
+pub fn sum_root(leaves: &[SumNode]) -> SumNode {
+    if leaves.is_empty() {
+        return SumNode::empty();
+    }
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level.remove(0)
+}
+
+/// Builds the proof that `leaves[index]` is counted in the tree.
+///
+/// # Returns
+///
+/// * `Some(SumProof)` - The proof
+/// * `None` - If `index` is out of range
+pub fn build_sum_proof(leaves: &[SumNode], index: usize) -> Option<SumProof> {
+    if index >= leaves.len() {
+        return None;
+    }
+    let mut steps = Vec::new();
+    let mut level = leaves.to_vec();
+    let mut position = index;
+
+    while level.len() > 1 {
+        let sibling = if position.is_multiple_of(2) {
+            SumProofStep { node: level.get(position + 1).cloned().unwrap_or_else(SumNode::empty), is_left: false }
+        } else {
+            SumProofStep { node: level[position - 1].clone(), is_left: true }
+        };
+        steps.push(sibling);
+        level = next_level(&level);
+        position /= 2;
+    }
+
+    Some(SumProof {
+        leaf: leaves[index].clone(),
+        steps: steps,
+    })
+}
+
+fn next_level(level: &[SumNode]) -> Vec<SumNode> {
+    level.chunks(2)
+        .map(|pair| combine(&pair[0], pair.get(1).unwrap_or(&SumNode::empty())))
+        .collect()
+}
@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+use blockc_core::header;
+use crate::block::block::Block;
+use crate::blockchain::blockchain::Blockchain;
+use crate::entity::entity::Entity;
+use crate::mining::template::BlockTemplate;
+use crate::transactions::transactions::{Transaction, TransactionExt};
+
+/// Result of a share submitted to the pool.
+#[derive(Debug, Clone)]
+pub enum ShareResult {
+    /// The share met the pool's share difficulty and was counted
+    Accepted,
+    /// The share also met the chain's difficulty: here is the mined block
+    BlockFound(Block),
+}
+
+/// A miner working for a pool.
+///
+/// Each worker scans its own slice of the nonce space (`start_nonce`,
+/// `start_nonce + step`, ...) so workers never duplicate each other's work.
+///
+/// # Fields
+///
+/// * `address` - Worker address, used to credit shares and pay rewards
+/// * `start_nonce` - First nonce this worker tries
+/// * `step` - Distance between two nonces tried by this worker (usually the number of workers)
+/// * `tried` - Number of nonces already tried on the current job
+#[derive(Debug, Clone)]
+pub struct Worker {
+    pub address: String,
+    pub start_nonce: u32,
+    pub step: u32,
+    pub tried: u32,
+}
+
+impl Worker {
+    /// Creates a worker scanning nonces `start_nonce`, `start_nonce + step`, ...
+    pub fn new(address: String, start_nonce: u32, step: u32) -> Self {
+        Worker {
+            address: address,
+            start_nonce: start_nonce,
+            step: step.max(1),
+            tried: 0,
+        }
+    }
+
+    /// Starts over from `start_nonce` (call it when the pool hands out a new job).
+    pub fn reset(&mut self) {
+        self.tried = 0;
+    }
+
+    /// Tries the next `attempts` nonces on the template and returns those whose hash
+    /// meets `share_difficulty` (these are the shares to submit to the pool).
+    pub fn find_shares(&mut self, template: &BlockTemplate, share_difficulty: u32, attempts: u32) -> Vec<u32> {
+        let hasher = template.block.header_hasher_with(template.hash_algorithm);
+        let mut shares = Vec::new();
+        for _ in 0..attempts {
+            let nonce = self.start_nonce.wrapping_add(self.tried.wrapping_mul(self.step));
+            self.tried = self.tried.wrapping_add(1);
+            if header::digest_meets_difficulty(&hasher.hash(nonce), share_difficulty) {
+                shares.push(nonce);
+            }
+        }
+        shares
+    }
+}
+
+/// A mining pool: workers mine on a template paying the pool and submit shares.
+///
+/// A share is a nonce whose block hash meets the (lower) share difficulty. Shares
+/// prove how much work each worker did. When a share also meets the chain's
+/// difficulty, the block is found; once it is connected, the pool splits the block
+/// reward between workers proportionally to their shares and starts a new round.
+///
+/// # Fields
+///
+/// * `operator` - The pool's own entity (address and keys used to pay workers)
+/// * `share_difficulty` - Leading zeros a share needs (lower than the chain's difficulty)
+/// * `template` - Current job handed to workers
+/// * `shares` - Shares per worker address in the current round
+/// * `seen_nonces` - Nonces already submitted for the current job
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::mining::pool::{MiningPool, ShareResult, Worker};
+///
+/// let mut chain = Blockchain::new();
+/// let mut pool = MiningPool::new("Pool".to_string(), 1);
+/// let mut workers = vec![
+///     Worker::new("W1".to_string(), 0, 2),
+///     Worker::new("W2".to_string(), 1, 2),
+/// ];
+///
+/// let template = pool.get_work(&chain).unwrap();
+/// let mut found = None;
+/// 'mining: loop {
+///     for worker in workers.iter_mut() {
+///         for nonce in worker.find_shares(&template, 1, 500) {
+///             if let Ok(ShareResult::BlockFound(block)) = pool.submit_share(&worker.address, nonce) {
+///                 found = Some(block);
+///                 break 'mining;
+///             }
+///         }
+///     }
+/// }
+///
+/// chain.submit_block(found.unwrap()).unwrap();
+/// let workers_with_shares = pool.shares().len();
+/// let payouts = pool.distribute_rewards(&chain, 45.0).unwrap();
+/// assert_eq!(payouts.len(), workers_with_shares);
+/// ```
+pub struct MiningPool {
+    operator: Entity,
+    share_difficulty: u32,
+    template: Option<BlockTemplate>,
+    shares: HashMap<String, u64>,
+    seen_nonces: Vec<u32>,
+}
+
+impl MiningPool {
+    /// Creates a pool mining to `address` that accepts shares with `share_difficulty` zeros.
+    pub fn new(address: String, share_difficulty: u32) -> Self {
+        MiningPool {
+            operator: Entity::new(address.clone(), 0.0, Vec::new(), format!("{}_public", address), format!("{}_private", address)),
+            share_difficulty: share_difficulty,
+            template: None,
+            shares: HashMap::new(),
+            seen_nonces: Vec::new(),
+        }
+    }
+
+    /// Returns the pool's address (the coinbase of its templates pays it).
+    pub fn address(&self) -> &str {
+        &self.operator.address
+    }
+
+    /// Returns the number of shares each worker submitted in the current round.
+    pub fn shares(&self) -> &HashMap<String, u64> {
+        &self.shares
+    }
+
+    /// Fetches a fresh template from the chain and makes it the current job.
+    ///
+    /// Shares already submitted in this round are kept; only the job changes.
+    pub fn get_work(&mut self, chain: &Blockchain) -> Result<BlockTemplate, String> {
+        let template = chain.get_block_template(&self.operator.address)?;
+        self.template = Some(template.clone());
+        self.seen_nonces.clear();
+        Ok(template)
+    }
+
+    /// Submits a share for the current job.
+    ///
+    /// # Arguments
+    ///
+    /// * `worker` - Address of the worker that found the nonce
+    /// * `nonce` - The nonce found
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ShareResult::Accepted)` - The share was counted
+    /// * `Ok(ShareResult::BlockFound(block))` - The share was counted and solves the block
+    /// * `Err(String)` - No job, duplicate nonce, or hash too weak
+    pub fn submit_share(&mut self, worker: &str, nonce: u32) -> Result<ShareResult, String> {
+        let template = self.template.as_ref().ok_or("The pool has no job yet".to_string())?;
+        if self.seen_nonces.contains(&nonce) {
+            return Err(format!("Nonce {} was already submitted", nonce));
+        }
+
+        let mut block = template.block.clone();
+        block.nonce = nonce;
+        block.block_hash = block.calculate_hash_with(template.hash_algorithm);
+        if !block.meets_difficulty(self.share_difficulty) {
+            return Err(format!("Share does not meet difficulty {}", self.share_difficulty));
+        }
+
+        self.seen_nonces.push(nonce);
+        *self.shares.entry(worker.to_string()).or_insert(0) += 1;
+
+        if block.meets_difficulty(template.difficulty) {
+            return Ok(ShareResult::BlockFound(block));
+        }
+        Ok(ShareResult::Accepted)
+    }
+
+    /// Pays `reward` to the workers proportionally to their shares and starts a new round.
+    ///
+    /// Call it once the found block is connected and the pool holds the reward on
+    /// chain. Each payout pays the chain's base fee, taken out of the reward first.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<Transaction>)` - One signed payout per worker, to submit to the chain
+    /// * `Err(String)` - If there are no shares, the pool doesn't hold `reward` on chain,
+    ///   or the reward doesn't cover the fees
+    pub fn distribute_rewards(&mut self, chain: &Blockchain, reward: f64) -> Result<Vec<Transaction>, String> {
+        let total_shares: u64 = self.shares.values().sum();
+        if total_shares == 0 {
+            return Err("No shares in this round".to_string());
+        }
+        let fee = chain.get_base_fee();
+        let distributable = reward - fee * self.shares.len() as f64;
+        if distributable <= 0.0 {
+            return Err("Reward does not cover the payout fees".to_string());
+        }
+
+        // Sync the operator with the chain before signing
+        self.operator.balance = chain.get_balance(&self.operator.address);
+        self.operator.nonce = chain.get_nonce(&self.operator.address);
+        if self.operator.balance < reward {
+            return Err(format!("Pool holds {} on chain, cannot distribute {}", self.operator.balance, reward));
+        }
+
+        let mut workers: Vec<(&String, &u64)> = self.shares.iter().collect();
+        workers.sort();
+        let last = workers.len() - 1;
+        let mut paid = 0.0;
+        let mut payouts = Vec::new();
+        for (i, (worker, count)) in workers.into_iter().enumerate() {
+            // The last worker gets what is left, so rounding never overspends
+            let amount = if i == last {
+                (distributable - paid).min(self.operator.balance - fee)
+            } else {
+                distributable * *count as f64 / total_shares as f64
+            };
+            paid += amount;
+            payouts.push(Transaction::create_payment(&mut self.operator, worker, amount, fee)?);
+        }
+
+        self.shares.clear();
+        Ok(payouts)
+    }
+}
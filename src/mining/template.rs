@@ -0,0 +1,36 @@
+use crate::block::block::{Block, BlockExt, HashAlgorithm};
+
+/// A candidate block ready to be mined.
+///
+/// The template already contains everything the chain expects: the coinbase
+/// transactions, the selected mempool transactions, the previous block hash and
+/// the base fee. A miner only has to find a nonce for which the block hash meets
+/// the target, then hand the block back with `Blockchain::submit_block`.
+///
+/// # Fields
+///
+/// * `block` - The unmined block (nonce 0)
+/// * `height` - Height the block will have once connected (genesis is 0)
+/// * `difficulty` - Number of leading zeros the block hash needs
+/// * `hash_algorithm` - Hash function of the chain's block headers
+#[derive(Debug, Clone)]
+pub struct BlockTemplate {
+    pub block: Block,
+    pub height: u64,
+    pub difficulty: u32,
+    pub hash_algorithm: HashAlgorithm,
+}
+
+impl BlockTemplate {
+    /// Returns the hash prefix a valid block must have (e.g. "000").
+    pub fn target(&self) -> String {
+        "0".repeat(self.difficulty as usize)
+    }
+
+    /// Mines the template's block and returns it, ready to be submitted.
+    pub fn mine(&self) -> Block {
+        let mut block = self.block.clone();
+        block.mine_with(self.difficulty, self.hash_algorithm);
+        block
+    }
+}
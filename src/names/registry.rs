@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+
+/// Every registered name ends with this suffix, so names can't be mistaken for addresses.
+pub const NAME_SUFFIX: &str = ".bc";
+
+/// Number of blocks a registration (or a renewal) lasts.
+pub const REGISTRATION_PERIOD: u64 = 100;
+
+/// Maximum length of a name, suffix included.
+pub const MAX_NAME_LENGTH: usize = 32;
+
+/// Returns `true` if `value` looks like a name (ends with `NAME_SUFFIX`) rather than an address.
+pub fn is_name(value: &str) -> bool {
+    value.ends_with(NAME_SUFFIX)
+}
+
+/// Checks that `name` is a valid name: lowercase letters, digits and `-`,
+/// followed by `NAME_SUFFIX`, at most `MAX_NAME_LENGTH` bytes.
+///
+/// # Example
+///
+/// ```
+/// use blockc::names::registry::check_name;
+///
+/// assert!(check_name("alice.bc").is_ok());
+/// assert!(check_name("Alice.bc").is_err());
+/// assert!(check_name("alice").is_err());
+/// ```
+pub fn check_name(name: &str) -> Result<(), String> {
+    let Some(label) = name.strip_suffix(NAME_SUFFIX) else {
+        return Err(format!("Name {} must end with {}", name, NAME_SUFFIX));
+    };
+    if label.is_empty() || name.len() > MAX_NAME_LENGTH {
+        return Err(format!("Name {} must be 1 to {} bytes", name, MAX_NAME_LENGTH));
+    }
+    if !label.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+        return Err(format!("Name {} can only use lowercase letters, digits and '-'", name));
+    }
+    Ok(())
+}
+
+/// A registered name.
+///
+/// # Fields
+///
+/// * `name` - The name (e.g. "alice.bc")
+/// * `owner` - Address that registered it (only it can renew or update it)
+/// * `target` - Address the name resolves to
+/// * `expires_at` - First height at which the name no longer resolves
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameRecord {
+    pub name: String,
+    pub owner: String,
+    pub target: String,
+    pub expires_at: u64,
+}
+
+impl NameRecord {
+    /// Returns `true` if the name still resolves at block `height`.
+    pub fn is_active_at(&self, height: u64) -> bool {
+        height < self.expires_at
+    }
+}
+
+/// Registry of names, kept by the chain (ENS/Namecoin style).
+///
+/// A name is registered for `REGISTRATION_PERIOD` blocks. Its owner can renew it
+/// or point it to another address; once it expires, anyone can register it.
+///
+/// # Fields
+///
+/// * `records` - Registered names (active or expired), by name
+///
+/// # Example
+///
+/// ```
+/// use blockc::names::registry::{NameRegistry, REGISTRATION_PERIOD};
+///
+/// let mut registry = NameRegistry::new();
+/// registry.register("alice.bc", "Alice", "Alice", 1).unwrap();
+/// assert_eq!(registry.resolve("alice.bc", 50), Some("Alice"));
+///
+/// // Taken while active, free again once expired
+/// assert!(registry.register("alice.bc", "Mallory", "Mallory", 50).is_err());
+/// let expiry = 1 + REGISTRATION_PERIOD;
+/// assert_eq!(registry.resolve("alice.bc", expiry), None);
+/// registry.register("alice.bc", "Mallory", "Mallory", expiry).unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct NameRegistry {
+    records: HashMap<String, NameRecord>,
+}
+
+impl NameRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        NameRegistry {
+            records: HashMap::new(),
+        }
+    }
+
+    /// Returns the record of `name`, even if it expired.
+    pub fn get(&self, name: &str) -> Option<&NameRecord> {
+        self.records.get(name)
+    }
+
+    /// Returns the address `name` points to at block `height` (`None` if it is not
+    /// registered or expired).
+    pub fn resolve(&self, name: &str, height: u64) -> Option<&str> {
+        self.records.get(name)
+            .filter(|record| record.is_active_at(height))
+            .map(|record| record.target.as_str())
+    }
+
+    /// Returns the names owned by `owner` that are active at `height`, sorted.
+    pub fn names_of(&self, owner: &str, height: u64) -> Vec<&NameRecord> {
+        let mut names: Vec<&NameRecord> = self.records.values()
+            .filter(|record| record.owner == owner && record.is_active_at(height))
+            .collect();
+        names.sort_by(|a, b| a.name.cmp(&b.name));
+        names
+    }
+
+    /// Checks that `owner` can register `name` at `height`: the name is valid and
+    /// free, expired, or already owned by `owner` (which updates its target).
+    pub fn check_register(&self, name: &str, owner: &str, height: u64) -> Result<(), String> {
+        check_name(name)?;
+        match self.records.get(name) {
+            Some(record) if record.is_active_at(height) && record.owner != owner => {
+                Err(format!("Name {} is registered until height {}", name, record.expires_at))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that `owner` can renew `name` (it owns it and nobody registered it since).
+    pub fn check_renew(&self, name: &str, owner: &str) -> Result<(), String> {
+        match self.records.get(name) {
+            Some(record) if record.owner == owner => Ok(()),
+            Some(record) => Err(format!("Name {} is owned by {}", name, record.owner)),
+            None => Err(format!("Name {} is not registered", name)),
+        }
+    }
+
+    /// Registers `name` for `owner`, pointing to `target`, from block `height`.
+    ///
+    /// Registering a name the owner already holds keeps its expiry and only
+    /// changes the target.
+    pub fn register(&mut self, name: &str, owner: &str, target: &str, height: u64) -> Result<(), String> {
+        self.check_register(name, owner, height)?;
+        let expires_at = match self.records.get(name) {
+            Some(record) if record.is_active_at(height) => record.expires_at,
+            _ => height + REGISTRATION_PERIOD,
+        };
+        self.records.insert(name.to_string(), NameRecord {
+            name: name.to_string(),
+            owner: owner.to_string(),
+            target: target.to_string(),
+            expires_at: expires_at,
+        });
+        Ok(())
+    }
+
+    /// Extends `name` by `REGISTRATION_PERIOD` blocks, from its expiry or from
+    /// `height` if it already expired.
+    pub fn renew(&mut self, name: &str, owner: &str, height: u64) -> Result<(), String> {
+        self.check_renew(name, owner)?;
+        if let Some(record) = self.records.get_mut(name) {
+            record.expires_at = record.expires_at.max(height) + REGISTRATION_PERIOD;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::{BTreeMap, HashMap};
+
+/// Maximum number of reporters on a feed.
+pub const MAX_REPORTERS: usize = 32;
+
+/// Maximum length of a feed name.
+pub const MAX_FEED_NAME_LENGTH: usize = 32;
+
+/// Returns the median of `values` (the mean of the two middle values for an even count).
+///
+/// # Returns
+///
+/// `None` if `values` is empty.
+///
+/// # Example
+///
+/// ```
+/// use blockc::oracle::feed::median;
+///
+/// assert_eq!(median(&[3.0, 1.0, 2.0]), Some(2.0));
+/// assert_eq!(median(&[4.0, 1.0, 3.0, 2.0]), Some(2.5));
+/// assert_eq!(median(&[]), None);
+/// ```
+pub fn median(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let middle = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        Some((sorted[middle - 1] + sorted[middle]) / 2.0)
+    } else {
+        Some(sorted[middle])
+    }
+}
+
+/// Checks the definition of a new feed.
+///
+/// A feed name is 1 to `MAX_FEED_NAME_LENGTH` ASCII letters, digits, `/`, `-` or
+/// `_` (e.g. "BTC/USD"). A feed needs 1 to `MAX_REPORTERS` distinct reporters,
+/// a quorum between 1 and the number of reporters, and a maximum age of at least
+/// one block.
+pub fn check_feed(name: &str, reporters: &[String], quorum: usize, max_age: u64) -> Result<(), String> {
+    if name.is_empty() || name.len() > MAX_FEED_NAME_LENGTH
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '/' || c == '-' || c == '_') {
+        return Err(format!("Feed name {:?} must be 1 to {} letters, digits, '/', '-' or '_'", name, MAX_FEED_NAME_LENGTH));
+    }
+    if reporters.is_empty() || reporters.len() > MAX_REPORTERS {
+        return Err(format!("A feed needs 1 to {} reporters", MAX_REPORTERS));
+    }
+    if reporters.iter().enumerate().any(|(i, reporter)| reporters[..i].contains(reporter)) {
+        return Err("Feed reporters must be distinct".to_string());
+    }
+    if quorum == 0 || quorum > reporters.len() {
+        return Err(format!("Quorum must be between 1 and {}", reporters.len()));
+    }
+    if max_age == 0 {
+        return Err("Reports must stay fresh for at least one block".to_string());
+    }
+    Ok(())
+}
+
+/// A value published by a reporter.
+///
+/// # Fields
+///
+/// * `value` - The value reported (e.g. a price)
+/// * `height` - Height of the block that included the report
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DataPoint {
+    pub value: f64,
+    pub height: u64,
+}
+
+/// Aggregated value of a feed.
+///
+/// # Fields
+///
+/// * `value` - Median of the fresh reports
+/// * `reports` - Number of fresh reports used
+/// * `oldest` - Height of the oldest report used
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeedValue {
+    pub value: f64,
+    pub reports: usize,
+    pub oldest: u64,
+}
+
+/// A data feed published by designated reporters.
+///
+/// Only the reporters listed on the feed can publish to it, with transactions
+/// they sign; the latest report of each reporter is kept. A report goes stale
+/// `max_age` blocks after the block that included it. The feed's value is the
+/// median of the fresh reports, and only exists while at least `quorum` reports
+/// are fresh: the median ignores a minority of wrong or manipulated reports, and
+/// the staleness rule keeps consumers from acting on outdated data.
+///
+/// # Fields
+///
+/// * `name` - Unique name of the feed (e.g. "BTC/USD")
+/// * `owner` - Address that created the feed
+/// * `reporters` - Addresses allowed to report
+/// * `quorum` - Fresh reports needed for the feed to have a value
+/// * `max_age` - Blocks a report stays fresh
+/// * `reports` - Latest report of each reporter
+///
+/// # Example
+///
+/// ```
+/// use blockc::oracle::feed::OracleFeed;
+///
+/// let reporters = vec!["R1".to_string(), "R2".to_string(), "R3".to_string()];
+/// let mut feed = OracleFeed::new("BTC/USD", "Owner", reporters, 2, 10);
+/// feed.report("R1", 100.0, 5).unwrap();
+/// assert!(feed.value_at(6).is_err()); // below the quorum
+///
+/// feed.report("R2", 104.0, 6).unwrap();
+/// feed.report("R3", 900.0, 6).unwrap(); // an outlier doesn't move the median
+/// assert_eq!(feed.value_at(7).unwrap().value, 104.0);
+///
+/// // R1's report goes stale at height 15, R2 and R3 are still enough
+/// assert_eq!(feed.value_at(15).unwrap().value, 502.0);
+/// assert!(feed.value_at(16).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct OracleFeed {
+    pub name: String,
+    pub owner: String,
+    pub reporters: Vec<String>,
+    pub quorum: usize,
+    pub max_age: u64,
+    pub reports: BTreeMap<String, DataPoint>,
+}
+
+impl OracleFeed {
+    /// Creates a feed without reports.
+    pub fn new(name: &str, owner: &str, reporters: Vec<String>, quorum: usize, max_age: u64) -> Self {
+        OracleFeed {
+            name: name.to_string(),
+            owner: owner.to_string(),
+            reporters: reporters,
+            quorum: quorum,
+            max_age: max_age,
+            reports: BTreeMap::new(),
+        }
+    }
+
+    /// Checks that `reporter` can publish `value` to this feed.
+    pub fn check_report(&self, reporter: &str, value: f64) -> Result<(), String> {
+        if !self.reporters.iter().any(|r| r == reporter) {
+            return Err(format!("{} is not a reporter of feed {}", reporter, self.name));
+        }
+        if !value.is_finite() {
+            return Err("Reported value must be a finite number".to_string());
+        }
+        Ok(())
+    }
+
+    /// Records `value` as the latest report of `reporter`, included at block `height`.
+    pub fn report(&mut self, reporter: &str, value: f64, height: u64) -> Result<(), String> {
+        self.check_report(reporter, value)?;
+        self.reports.insert(reporter.to_string(), DataPoint { value: value, height: height });
+        Ok(())
+    }
+
+    /// Returns `true` if a report included at `reported_at` is still fresh at `height`.
+    pub fn is_fresh(&self, reported_at: u64, height: u64) -> bool {
+        height < reported_at + self.max_age
+    }
+
+    /// Returns the feed's value at block `height`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(FeedValue)` - The median of the fresh reports
+    /// * `Err(String)` - If fewer than `quorum` reports are fresh
+    pub fn value_at(&self, height: u64) -> Result<FeedValue, String> {
+        let fresh: Vec<&DataPoint> = self.reports.values()
+            .filter(|point| self.is_fresh(point.height, height))
+            .collect();
+        if fresh.len() < self.quorum {
+            return Err(format!("Feed {} has {} fresh reports, needs {}", self.name, fresh.len(), self.quorum));
+        }
+        let values: Vec<f64> = fresh.iter().map(|point| point.value).collect();
+        Ok(FeedValue {
+            value: median(&values).unwrap_or(0.0),
+            reports: fresh.len(),
+            oldest: fresh.iter().map(|point| point.height).min().unwrap_or(height),
+        })
+    }
+}
+
+/// Registry of oracle feeds, by name.
+///
+/// # Fields
+///
+/// * `feeds` - Feeds, by name
+#[derive(Debug, Clone, Default)]
+pub struct OracleRegistry {
+    feeds: HashMap<String, OracleFeed>,
+}
+
+impl OracleRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        OracleRegistry {
+            feeds: HashMap::new(),
+        }
+    }
+
+    /// Returns the feed with the given name.
+    pub fn get(&self, name: &str) -> Option<&OracleFeed> {
+        self.feeds.get(name)
+    }
+
+    /// Returns the names of every feed, sorted.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.feeds.keys().map(|name| name.as_str()).collect();
+        names.sort();
+        names
+    }
+
+    /// Checks that a feed can be created (valid definition, unused name).
+    pub fn check_create(&self, name: &str, reporters: &[String], quorum: usize, max_age: u64) -> Result<(), String> {
+        check_feed(name, reporters, quorum, max_age)?;
+        if self.feeds.contains_key(name) {
+            return Err(format!("Feed {} already exists", name));
+        }
+        Ok(())
+    }
+
+    /// Creates a feed owned by `owner`.
+    pub fn create(&mut self, name: &str, owner: &str, reporters: Vec<String>, quorum: usize, max_age: u64) -> Result<(), String> {
+        self.check_create(name, &reporters, quorum, max_age)?;
+        self.feeds.insert(name.to_string(), OracleFeed::new(name, owner, reporters, quorum, max_age));
+        Ok(())
+    }
+
+    /// Checks that `reporter` can publish `value` to the feed `name`.
+    pub fn check_report(&self, name: &str, reporter: &str, value: f64) -> Result<(), String> {
+        self.feed(name)?.check_report(reporter, value)
+    }
+
+    /// Records a report on the feed `name`.
+    pub fn report(&mut self, name: &str, reporter: &str, value: f64, height: u64) -> Result<(), String> {
+        self.feeds.get_mut(name).ok_or(format!("No feed {}", name))?.report(reporter, value, height)
+    }
+
+    /// Returns the value of the feed `name` at block `height` (see `OracleFeed::value_at`).
+    pub fn value_at(&self, name: &str, height: u64) -> Result<FeedValue, String> {
+        self.feed(name)?.value_at(height)
+    }
+
+    fn feed(&self, name: &str) -> Result<&OracleFeed, String> {
+        self.feeds.get(name).ok_or(format!("No feed {}", name))
+    }
+}
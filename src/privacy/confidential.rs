@@ -0,0 +1,271 @@
+use std::collections::{HashMap, HashSet};
+use curve25519_dalek::ristretto::RistrettoPoint;
+use curve25519_dalek::scalar::Scalar;
+use crate::transactions::transactions::{Transaction, TransactionKind};
+use crate::utxo::utxo::OutPoint;
+
+// Commitments and proofs are part of the transactions a device signs, so they
+// live in `blockc_core`.
+pub use blockc_core::privacy::{balance_message, commit, excess, value_generator, BalanceProof, BitProof, ConfidentialOutput, RangeProof, RANGE_BITS};
+
+/// Confidential amounts are whole units; one coin is this many units.
+pub const UNITS_PER_COIN: u64 = 1_000_000;
+
+/// Maximum number of outputs of a confidential transfer.
+pub const MAX_CONFIDENTIAL_OUTPUTS: usize = 16;
+
+/// Converts coins to confidential units.
+///
+/// # Returns
+///
+/// * `Ok(u64)` - The number of units
+/// * `Err(String)` - If `coins` is negative, finer than one unit, or too large for a range proof
+///
+/// # Example
+///
+/// ```
+/// use blockc::privacy::confidential::{to_coins, to_units};
+///
+/// assert_eq!(to_units(1.5).unwrap(), 1_500_000);
+/// assert_eq!(to_coins(1_500_000), 1.5);
+/// assert!(to_units(0.0000001).is_err());
+/// ```
+pub fn to_units(coins: f64) -> Result<u64, String> {
+    let units = coins * UNITS_PER_COIN as f64;
+    if !units.is_finite() || units < 0.0 || units >= (1u64 << RANGE_BITS) as f64 {
+        return Err(format!("{} coins cannot be a confidential amount", coins));
+    }
+    if (units - units.round()).abs() > 1e-6 {
+        return Err(format!("{} coins is finer than a confidential unit", coins));
+    }
+    Ok(units.round() as u64)
+}
+
+/// Converts confidential units to coins.
+pub fn to_coins(units: u64) -> f64 {
+    units as f64 / UNITS_PER_COIN as f64
+}
+
+/// What the owner of a confidential output needs to spend it.
+///
+/// Only the commitment is on chain: the sender of a transfer gets the openings
+/// of its outputs from `Transaction::confidential_transfer` and must hand them
+/// to their owners.
+///
+/// # Fields
+///
+/// * `outpoint` - Output this opens
+/// * `owner` - Owner of the output
+/// * `value` - Hidden amount, in units
+/// * `blinding` - Blinding factor of the commitment
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoteOpening {
+    pub outpoint: OutPoint,
+    pub owner: String,
+    pub value: u64,
+    pub blinding: Scalar,
+}
+
+impl NoteOpening {
+    /// Returns the commitment this opens.
+    pub fn commitment(&self) -> RistrettoPoint {
+        commit(self.value, &self.blinding)
+    }
+
+    /// Returns the hidden amount, in coins.
+    pub fn coins(&self) -> f64 {
+        to_coins(self.value)
+    }
+}
+
+/// A confidential output recorded by the chain.
+///
+/// # Fields
+///
+/// * `owner` - Address that can spend it
+/// * `commitment` - Commitment to its amount
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfidentialNote {
+    pub owner: String,
+    pub commitment: RistrettoPoint,
+}
+
+/// What an explorer can tell about a confidential transfer.
+///
+/// Amounts moved between hidden outputs are never shown: only how many outputs
+/// were spent and created, the public deposit and withdrawal, and whether the
+/// commitments balance.
+///
+/// # Fields
+///
+/// * `inputs` - Hidden outputs spent
+/// * `outputs` - Hidden outputs created
+/// * `deposit` - Public coins moved into hidden outputs
+/// * `withdrawal` - Public coins taken out of hidden outputs
+/// * `balanced` - Whether inputs minus outputs commit to zero
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransferSummary {
+    pub inputs: usize,
+    pub outputs: usize,
+    pub deposit: f64,
+    pub withdrawal: f64,
+    pub balanced: bool,
+}
+
+/// Confidential outputs kept by the chain (like `UtxoSet`, but with hidden amounts).
+///
+/// Spent notes are kept, so a transfer can still be checked after its inputs are spent.
+///
+/// # Fields
+///
+/// * `notes` - Every confidential output created, by outpoint
+/// * `spent` - Outpoints already consumed
+/// * `shielded` - Units currently hidden (deposits minus withdrawals)
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::entity::entity::Entity;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+///
+/// let mut chain = Blockchain::new();
+/// chain.add_block(Vec::new(), "Alice").unwrap();
+/// let fee = chain.get_base_fee() * 2.0;
+/// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+/// let mut bob = Entity::new("Bob".to_string(), 0.0, Vec::new(), "b".to_string(), "b".to_string());
+///
+/// // Alice hides 20 coins, then sends 7 of them to Bob
+/// let (deposit, notes) = Transaction::confidential_transfer(&mut alice, &[], &[("Alice", 20.0)], 20.0, 0.0, fee).unwrap();
+/// chain.add_block(vec![deposit], "Miner").unwrap();
+/// let (payment, notes) = Transaction::confidential_transfer(&mut alice, &notes, &[("Bob", 7.0)], 0.0, 0.0, fee).unwrap();
+/// let summary = chain.state().confidential().summarize(&payment).unwrap();
+/// assert!(summary.balanced && summary.inputs == 1 && summary.outputs == 2); // 7 to Bob, 13 of change
+/// chain.add_block(vec![payment], "Miner").unwrap();
+/// assert_eq!((notes[0].coins(), notes[1].coins()), (7.0, 13.0));
+///
+/// // Bob takes his note back to his public balance, paying the fee out of it
+/// let (withdrawal, _) = Transaction::confidential_transfer(&mut bob, &notes[..1], &[], 0.0, 7.0, fee).unwrap();
+/// chain.add_block(vec![withdrawal], "Miner").unwrap();
+/// assert_eq!(chain.get_balance("Bob"), bob.get_balance());
+/// assert_eq!(chain.state().confidential().shielded(), 13.0);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ConfidentialPool {
+    notes: HashMap<OutPoint, ConfidentialNote>,
+    spent: HashSet<OutPoint>,
+    shielded: u64,
+}
+
+impl ConfidentialPool {
+    /// Creates an empty pool.
+    pub fn new() -> Self {
+        ConfidentialPool {
+            notes: HashMap::new(),
+            spent: HashSet::new(),
+            shielded: 0,
+        }
+    }
+
+    /// Returns the note created at `outpoint` (spent or not).
+    pub fn get(&self, outpoint: &OutPoint) -> Option<&ConfidentialNote> {
+        self.notes.get(outpoint)
+    }
+
+    /// Returns `true` if `outpoint` was already spent.
+    pub fn is_spent(&self, outpoint: &OutPoint) -> bool {
+        self.spent.contains(outpoint)
+    }
+
+    /// Returns the unspent notes owned by `address`, sorted by outpoint.
+    pub fn notes_of(&self, address: &str) -> Vec<(OutPoint, ConfidentialNote)> {
+        let mut owned: Vec<(OutPoint, ConfidentialNote)> = self.notes.iter()
+            .filter(|(outpoint, note)| note.owner == address && !self.is_spent(outpoint))
+            .map(|(outpoint, note)| (outpoint.clone(), note.clone()))
+            .collect();
+        owned.sort_by(|a, b| a.0.cmp(&b.0));
+        owned
+    }
+
+    /// Coins currently hidden in confidential outputs (the only total that is public).
+    pub fn shielded(&self) -> f64 {
+        to_coins(self.shielded)
+    }
+
+    /// Checks a confidential transfer of `sender` against the pool.
+    ///
+    /// Every input must be an unspent note of `sender`, spent once; every output
+    /// needs a valid range proof; the balance proof must show that inputs plus
+    /// the deposit commit to the same amount as outputs plus the withdrawal.
+    pub fn check_transfer(&self, sender: &str, inputs: &[OutPoint], outputs: &[ConfidentialOutput], deposit: u64, withdrawal: u64, proof: &BalanceProof) -> Result<(), String> {
+        if outputs.len() > MAX_CONFIDENTIAL_OUTPUTS {
+            return Err(format!("A confidential transfer has at most {} outputs", MAX_CONFIDENTIAL_OUTPUTS));
+        }
+        if withdrawal > self.shielded {
+            return Err(format!("Cannot withdraw {} coins, only {} are shielded", to_coins(withdrawal), self.shielded()));
+        }
+        let mut input_commitments = Vec::with_capacity(inputs.len());
+        for (i, input) in inputs.iter().enumerate() {
+            if inputs[..i].contains(input) || self.is_spent(input) {
+                return Err(format!("Confidential output {}:{} is spent twice (double spend)", input.transaction_id, input.index));
+            }
+            let note = self.get(input).ok_or(format!("Unknown confidential output {}:{}", input.transaction_id, input.index))?;
+            if note.owner != sender {
+                return Err(format!("Confidential output {}:{} is not owned by {}", input.transaction_id, input.index, sender));
+            }
+            input_commitments.push(note.commitment);
+        }
+        for output in outputs {
+            output.range_proof.verify(&output.commitment)?;
+        }
+        let output_commitments: Vec<RistrettoPoint> = outputs.iter().map(|output| output.commitment).collect();
+        let excess = excess(&input_commitments, &output_commitments, deposit, withdrawal);
+        proof.verify(&excess, &balance_message(sender, inputs, outputs, deposit, withdrawal))
+    }
+
+    /// Records a checked transfer: spends its inputs and adds its outputs as
+    /// outputs of `transaction_id`.
+    pub fn apply_transfer(&mut self, transaction_id: &str, inputs: &[OutPoint], outputs: &[ConfidentialOutput], deposit: u64, withdrawal: u64) {
+        self.spent.extend(inputs.iter().cloned());
+        for (index, output) in outputs.iter().enumerate() {
+            self.notes.insert(OutPoint::new(transaction_id.to_string(), index), ConfidentialNote {
+                owner: output.owner.clone(),
+                commitment: output.commitment,
+            });
+        }
+        self.shielded = self.shielded + deposit - withdrawal;
+    }
+
+    /// Summarizes a confidential transfer for an explorer.
+    ///
+    /// The balance is checked against the notes the transfer spends, whether or
+    /// not they have been spent since.
+    ///
+    /// # Returns
+    ///
+    /// `None` if `transaction` is not a confidential transfer.
+    pub fn summarize(&self, transaction: &Transaction) -> Option<TransferSummary> {
+        let TransactionKind::ConfidentialTransfer { inputs, outputs, withdrawal, proof } = &transaction.kind else {
+            return None;
+        };
+        let balanced = match (to_units(transaction.amount), to_units(*withdrawal)) {
+            (Ok(deposit), Ok(withdrawal)) => inputs.iter()
+                .map(|input| self.get(input).map(|note| note.commitment))
+                .collect::<Option<Vec<RistrettoPoint>>>()
+                .is_some_and(|commitments| {
+                    let output_commitments: Vec<RistrettoPoint> = outputs.iter().map(|output| output.commitment).collect();
+                    let excess = excess(&commitments, &output_commitments, deposit, withdrawal);
+                    let message = balance_message(&transaction.sender_address, inputs, outputs, deposit, withdrawal);
+                    proof.verify(&excess, &message).is_ok()
+                }),
+            _ => false,
+        };
+        Some(TransferSummary {
+            inputs: inputs.len(),
+            outputs: outputs.len(),
+            deposit: transaction.amount,
+            withdrawal: *withdrawal,
+            balanced: balanced,
+        })
+    }
+}
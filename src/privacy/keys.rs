@@ -0,0 +1,5 @@
+// Keys are derived and encoded the same way by every device that signs private
+// transactions, so they live in `blockc_core`.
+pub use blockc_core::privacy::{
+    from_hex, hash_to_scalar, point_from_hex, point_to_hex, public_key, scalar_from_hex, scalar_to_hex, secret_key, to_hex,
+};
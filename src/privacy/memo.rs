@@ -0,0 +1,125 @@
+use curve25519_dalek::scalar::Scalar;
+use sha2::{Digest, Sha256, Sha512};
+use crate::entity::entity::Entity;
+use crate::privacy::keys::{from_hex, hash_to_scalar, point_from_hex, point_to_hex, public_key, secret_key, to_hex};
+use crate::transactions::transactions::{ENCRYPTED_MEMO_PREFIX, MAX_MEMO_BYTES};
+
+/// Bytes of the authentication tag closing every encrypted memo.
+pub const MEMO_TAG_BYTES: usize = 16;
+
+/// Derives an entity's memo secret from its private key.
+pub fn memo_secret(private_key: &str) -> Scalar {
+    secret_key(private_key, "blockc/memo")
+}
+
+/// Returns the memo key (hex) to publish so others can encrypt memos to `private_key`'s owner.
+pub fn memo_key(private_key: &str) -> String {
+    point_to_hex(&public_key(&memo_secret(private_key)))
+}
+
+/// Ephemeral secret a sender uses to encrypt `plaintext` to `memo_key`.
+///
+/// Derived from the sender's private key, nonce and the memo itself, so every
+/// memo gets a fresh key without needing a random number generator.
+pub fn ephemeral_secret(sender: &Entity, memo_key: &str, plaintext: &str) -> Scalar {
+    hash_to_scalar("blockc/memo/ephemeral", &[
+        sender.private_key.as_bytes(),
+        memo_key.as_bytes(),
+        plaintext.as_bytes(),
+        &sender.nonce.to_le_bytes(),
+    ])
+}
+
+/// Encryption and MAC keys both sides derive from the shared point (`r * P` = `p * R`).
+fn memo_keys(shared_point: &[u8], ephemeral_key: &[u8]) -> ([u8; 32], [u8; 32]) {
+    let mut hasher = Sha512::new();
+    hasher.update(b"blockc/memo/keys");
+    hasher.update(shared_point);
+    hasher.update(ephemeral_key);
+    let keys = hasher.finalize();
+    let mut encryption_key = [0u8; 32];
+    let mut mac_key = [0u8; 32];
+    encryption_key.copy_from_slice(&keys[..32]);
+    mac_key.copy_from_slice(&keys[32..]);
+    (encryption_key, mac_key)
+}
+
+/// XORs `data` with a SHA-256 keystream (counter mode); encrypts and decrypts alike.
+fn apply_keystream(key: &[u8; 32], data: &[u8]) -> Vec<u8> {
+    data.chunks(32).enumerate().flat_map(|(counter, chunk)| {
+        let block = Sha256::new().chain_update(key).chain_update((counter as u64).to_le_bytes()).finalize();
+        chunk.iter().zip(block).map(|(byte, key_byte)| byte ^ key_byte).collect::<Vec<u8>>()
+    }).collect()
+}
+
+/// HMAC-SHA256 of the ephemeral key and ciphertext, truncated to `MEMO_TAG_BYTES`.
+fn tag(mac_key: &[u8; 32], ephemeral_key: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+    let mut inner_pad = [0x36u8; 64];
+    let mut outer_pad = [0x5cu8; 64];
+    for (i, byte) in mac_key.iter().enumerate() {
+        inner_pad[i] ^= byte;
+        outer_pad[i] ^= byte;
+    }
+    let inner = Sha256::new().chain_update(inner_pad).chain_update(ephemeral_key).chain_update(ciphertext).finalize();
+    let outer = Sha256::new().chain_update(outer_pad).chain_update(inner).finalize();
+    outer[..MEMO_TAG_BYTES].to_vec()
+}
+
+/// Encrypts `plaintext` to the owner of `memo_key` (ECIES).
+///
+/// The result is `enc:<ephemeral key R><ciphertext><tag>` (hex), where the
+/// keys come from the shared point `r * P` and the tag authenticates `R` and
+/// the ciphertext. Only the holder of `p` (`P = p * G`) can read it.
+///
+/// # Returns
+///
+/// * `Ok(String)` - The memo to put on chain
+/// * `Err(String)` - If the memo key is malformed or the memo is over `MAX_MEMO_BYTES`
+///
+/// # Example
+///
+/// ```
+/// use blockc::privacy::keys::secret_key;
+/// use blockc::privacy::memo::{decrypt_memo, encrypt_memo, memo_key, memo_secret};
+///
+/// let key = memo_key("bob-secret");
+/// let memo = encrypt_memo(&secret_key("alice", "ephemeral"), &key, "Invoice #42").unwrap();
+/// assert!(memo.starts_with("enc:"));
+/// assert_eq!(decrypt_memo(&memo_secret("bob-secret"), &memo).unwrap(), "Invoice #42");
+/// assert!(decrypt_memo(&memo_secret("carol-secret"), &memo).is_err());
+/// ```
+pub fn encrypt_memo(ephemeral_secret: &Scalar, memo_key: &str, plaintext: &str) -> Result<String, String> {
+    if plaintext.len() > MAX_MEMO_BYTES {
+        return Err(format!("Memo is {} bytes, limit is {}", plaintext.len(), MAX_MEMO_BYTES));
+    }
+    let receiver = point_from_hex(memo_key)?;
+    let ephemeral_key = public_key(ephemeral_secret).compress().to_bytes();
+    let shared = (ephemeral_secret * receiver).compress().to_bytes();
+    let (encryption_key, mac_key) = memo_keys(&shared, &ephemeral_key);
+    let ciphertext = apply_keystream(&encryption_key, plaintext.as_bytes());
+    let tag = tag(&mac_key, &ephemeral_key, &ciphertext);
+    Ok(format!("{}{}{}{}", ENCRYPTED_MEMO_PREFIX, to_hex(&ephemeral_key), to_hex(&ciphertext), to_hex(&tag)))
+}
+
+/// Decrypts a memo encrypted with `encrypt_memo` using the receiver's memo secret.
+///
+/// # Returns
+///
+/// * `Ok(String)` - The plaintext memo
+/// * `Err(String)` - If the memo is not encrypted, is malformed, or was
+///   encrypted to someone else (its tag doesn't match)
+pub fn decrypt_memo(memo_secret: &Scalar, memo: &str) -> Result<String, String> {
+    let bytes = from_hex(memo.strip_prefix(ENCRYPTED_MEMO_PREFIX).ok_or("Memo is not encrypted".to_string())?)?;
+    if bytes.len() < 32 + MEMO_TAG_BYTES {
+        return Err("Encrypted memo is too short".to_string());
+    }
+    let (ephemeral_key, rest) = bytes.split_at(32);
+    let (ciphertext, memo_tag) = rest.split_at(rest.len() - MEMO_TAG_BYTES);
+    let ephemeral = point_from_hex(&to_hex(ephemeral_key))?;
+    let shared = (memo_secret * ephemeral).compress().to_bytes();
+    let (encryption_key, mac_key) = memo_keys(&shared, ephemeral_key);
+    if tag(&mac_key, ephemeral_key, ciphertext) != memo_tag {
+        return Err("Memo is not encrypted to this key".to_string());
+    }
+    String::from_utf8(apply_keystream(&encryption_key, ciphertext)).map_err(|_| "Decrypted memo is not UTF-8".to_string())
+}
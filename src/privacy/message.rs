@@ -0,0 +1,172 @@
+use curve25519_dalek::ristretto::RistrettoPoint;
+use curve25519_dalek::scalar::Scalar;
+use crate::blockchain::blockchain::Blockchain;
+use crate::identity::did::did_for;
+use crate::privacy::keys::{hash_to_scalar, point_from_hex, point_to_hex, public_key, scalar_from_hex, scalar_to_hex, secret_key};
+
+/// Purpose of the key message signatures are made with.
+const KEY_PURPOSE: &str = "blockc/message/key";
+
+/// Largest message `sign_message` and `verify_message` accept.
+pub const MAX_MESSAGE_BYTES: usize = 64 * 1024;
+
+/// A Schnorr signature of an off-chain message by an address.
+///
+/// It proves the holder of a key vouches for `message` as `address`, without
+/// making a transaction (login flows, airdrop claims, ...). Both the address
+/// and the message are under the signature, and its challenge is tagged as a
+/// message signature, so it can't be replayed as another address's, as a
+/// transaction or as any other proof of the chain.
+///
+/// The key is derived from the entity's private key (see `message_public_key`);
+/// publish it in the address's DID document so anyone can check signatures
+/// with `verify_address_message`.
+///
+/// # Fields
+///
+/// * `nonce` - Schnorr nonce commitment (`k * G`)
+/// * `response` - Schnorr response (`k + e * x`)
+///
+/// # Example
+///
+/// ```
+/// use blockc::privacy::message::{message_public_key, sign_message, verify_message};
+///
+/// let key = message_public_key("alice-private-key");
+/// let signature = sign_message("alice-private-key", "Alice", "Log in to example.com at 1700000000");
+///
+/// assert!(verify_message(&key, "Alice", "Log in to example.com at 1700000000", &signature).is_ok());
+/// assert!(verify_message(&key, "Alice", "Log in to evil.com at 1700000000", &signature).is_err());
+/// assert!(verify_message(&key, "Bob", "Log in to example.com at 1700000000", &signature).is_err());
+/// assert!(verify_message(&message_public_key("other"), "Alice", "Log in to example.com at 1700000000", &signature).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MessageSignature {
+    pub nonce: RistrettoPoint,
+    pub response: Scalar,
+}
+
+impl MessageSignature {
+    /// Signs `message` as `address` with the message key of `private_key`.
+    ///
+    /// The nonce is derived from the key, address and message, so signing the
+    /// same message twice gives the same signature.
+    pub fn sign(private_key: &str, address: &str, message: &str) -> Self {
+        let secret = secret_key(private_key, KEY_PURPOSE);
+        let nonce = hash_to_scalar("blockc/message/nonce", &[secret.as_bytes(), address.as_bytes(), message.as_bytes()]);
+        let nonce_point = public_key(&nonce);
+        let challenge = challenge(&public_key(&secret), &nonce_point, address, message);
+        MessageSignature {
+            nonce: nonce_point,
+            response: nonce + challenge * secret,
+        }
+    }
+
+    /// Checks the signature was made by `key` for `message` as `address`.
+    pub fn verify(&self, key: &RistrettoPoint, address: &str, message: &str) -> Result<(), String> {
+        let challenge = challenge(key, &self.nonce, address, message);
+        if public_key(&self.response) != self.nonce + challenge * key {
+            return Err(format!("Signature is not valid for {} and this message", address));
+        }
+        Ok(())
+    }
+
+    /// Encodes the signature as 128 hex characters (nonce, then response).
+    pub fn encode(&self) -> String {
+        format!("{}{}", point_to_hex(&self.nonce), scalar_to_hex(&self.response))
+    }
+
+    /// Decodes a signature encoded with `encode`.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        if value.len() != 128 || !value.is_ascii() {
+            return Err("A message signature is 128 hex characters".to_string());
+        }
+        Ok(MessageSignature {
+            nonce: point_from_hex(&value[..64])?,
+            response: scalar_from_hex(&value[64..])?,
+        })
+    }
+}
+
+fn challenge(key: &RistrettoPoint, nonce: &RistrettoPoint, address: &str, message: &str) -> Scalar {
+    hash_to_scalar("blockc/message", &[
+        key.compress().as_bytes(),
+        nonce.compress().as_bytes(),
+        address.as_bytes(),
+        message.as_bytes(),
+    ])
+}
+
+/// Returns the public key message signatures of `private_key` verify against
+/// (64 hex characters), to publish e.g. in a DID document.
+pub fn message_public_key(private_key: &str) -> String {
+    point_to_hex(&public_key(&secret_key(private_key, KEY_PURPOSE)))
+}
+
+/// Signs `message` as `address` and returns the encoded signature (see
+/// `MessageSignature`).
+pub fn sign_message(private_key: &str, address: &str, message: &str) -> String {
+    MessageSignature::sign(private_key, address, message).encode()
+}
+
+/// Checks an encoded signature of `message` by `address` against a known
+/// public key (see `message_public_key`).
+///
+/// # Returns
+///
+/// * `Ok(())` - The key's holder signed `message` as `address`
+/// * `Err(String)` - If the key or signature is malformed, the message is over
+///   `MAX_MESSAGE_BYTES` or the signature does not match
+pub fn verify_message(public_key: &str, address: &str, message: &str, signature: &str) -> Result<(), String> {
+    if message.len() > MAX_MESSAGE_BYTES {
+        return Err(format!("Message is over {} bytes", MAX_MESSAGE_BYTES));
+    }
+    let key = point_from_hex(public_key)?;
+    MessageSignature::parse(signature)?.verify(&key, address, message)
+}
+
+/// Checks an encoded signature of `message` by `address` against the keys of
+/// the address's DID document on `chain`.
+///
+/// # Returns
+///
+/// * `Ok(String)` - The document key that made the signature
+/// * `Err(String)` - If the address has no DID document, or no key of it made
+///   the signature
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::entity::entity::Entity;
+/// use blockc::privacy::message::verify_address_message;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+/// use blockc::wallet::wallet::Wallet;
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// chain.add_block(Vec::new(), "Alice").unwrap();
+/// let fee = chain.get_base_fee() * 2.0;
+/// let mut alice = Wallet::new(Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "alice-key".to_string()));
+///
+/// let claim = "Airdrop claim: pay Alice";
+/// let signature = alice.sign_message("Alice", claim).unwrap();
+/// assert!(verify_address_message(&chain, "Alice", claim, &signature).is_err());
+///
+/// // Publish the message key in Alice's DID document
+/// let key = alice.message_public_key();
+/// let register = Transaction::register_did(&mut alice.entity, vec![key.clone()], Vec::new(), fee).unwrap();
+/// chain.add_block(vec![register], "Miner").unwrap();
+/// assert_eq!(verify_address_message(&chain, "Alice", claim, &signature).unwrap(), key);
+///
+/// // Bob listing Alice's key does not make her signature his
+/// assert!(verify_address_message(&chain, "Bob", claim, &signature).is_err());
+/// assert!(alice.sign_message("Bob", claim).is_err());
+/// ```
+pub fn verify_address_message(chain: &Blockchain, address: &str, message: &str, signature: &str) -> Result<String, String> {
+    let did = did_for(address);
+    let document = chain.state().resolve_did(&did).ok_or(format!("{} has no DID document", address))?;
+    document.public_keys.iter()
+        .find(|key| verify_message(key, address, message, signature).is_ok())
+        .cloned()
+        .ok_or(format!("No key of {} made this signature", did))
+}
@@ -0,0 +1,6 @@
+pub mod confidential;
+pub mod keys;
+pub mod memo;
+pub mod message;
+pub mod ring;
+pub mod stealth;
@@ -0,0 +1,166 @@
+use std::collections::{BTreeMap, HashSet};
+use curve25519_dalek::ristretto::RistrettoPoint;
+use crate::privacy::keys::{point_from_hex, point_to_hex};
+
+// Ring signatures are verified by light clients too: they live in `blockc_core`.
+pub use blockc_core::privacy::{hash_to_point, key_image, RingSignature};
+
+/// Smallest ring a withdrawal can be signed with.
+pub const MIN_RING_SIZE: usize = 2;
+
+/// Largest ring a withdrawal can be signed with.
+pub const MAX_RING_SIZE: usize = 16;
+
+/// Message the ring signature of a withdrawal signs.
+///
+/// It binds the withdrawal to the address receiving it and to its nonce and fee,
+/// so nobody can take a signature from the mempool and redirect the coins.
+pub fn withdrawal_message(sender: &str, nonce: u64, fee: f64) -> String {
+    format!("ring_withdraw:{}:{}:{}", sender, nonce, fee)
+}
+
+/// Deposits that can be withdrawn anonymously with ring signatures.
+///
+/// Each deposit registers a one-time public key with an amount. A withdrawal
+/// is signed by a ring of deposits of the same amount: the chain knows one of
+/// them is being withdrawn, not which, so the withdrawal cannot be linked to
+/// the depositor. Deposits are never removed; the key images of withdrawals
+/// are recorded instead, so each deposit can only be withdrawn once.
+///
+/// # Fields
+///
+/// * `deposits` - Amount deposited with each one-time key (hex)
+/// * `key_images` - Key images of the withdrawals (hex)
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::entity::entity::Entity;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+///
+/// let mut chain = Blockchain::new();
+/// chain.add_block(Vec::new(), "Alice").unwrap();
+/// chain.add_block(Vec::new(), "Bob").unwrap();
+/// let fee = chain.get_base_fee() * 2.0;
+/// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+/// let mut bob = Entity::new("Bob".to_string(), 45.0, Vec::new(), "b".to_string(), "b".to_string());
+///
+/// // Alice and Bob both deposit 10 coins
+/// let (deposit, secret) = Transaction::ring_deposit(&mut alice, 10.0, fee).unwrap();
+/// let (other, _) = Transaction::ring_deposit(&mut bob, 10.0, fee).unwrap();
+/// chain.add_block(vec![deposit, other], "Miner").unwrap();
+///
+/// // A fresh address withdraws Alice's deposit: the chain only sees "Alice or Bob"
+/// let mut fresh = Entity::new("Fresh".to_string(), 0.0, Vec::new(), "f".to_string(), "f".to_string());
+/// let withdrawal = Transaction::ring_withdraw(&mut fresh, chain.state(), &secret, 4, fee).unwrap();
+/// chain.add_block(vec![withdrawal], "Miner").unwrap();
+/// assert_eq!(chain.get_balance("Fresh"), fresh.get_balance());
+///
+/// // The same deposit cannot be withdrawn twice
+/// let again = Transaction::ring_withdraw(&mut fresh, chain.state(), &secret, 4, fee).unwrap();
+/// assert!(chain.add_block(vec![again], "Miner").unwrap_err().contains("key image"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RingPool {
+    deposits: BTreeMap<String, f64>,
+    key_images: HashSet<String>,
+}
+
+impl RingPool {
+    /// Creates an empty pool.
+    pub fn new() -> Self {
+        RingPool {
+            deposits: BTreeMap::new(),
+            key_images: HashSet::new(),
+        }
+    }
+
+    /// Returns the amount deposited with `key`.
+    pub fn get(&self, key: &str) -> Option<f64> {
+        self.deposits.get(key).copied()
+    }
+
+    /// Returns `true` if a withdrawal with this key image was already made.
+    pub fn is_spent(&self, key_image: &RistrettoPoint) -> bool {
+        self.key_images.contains(&point_to_hex(key_image))
+    }
+
+    /// Picks a ring of up to `size` deposits with the same amount as `key`'s, `key` included.
+    ///
+    /// The ring is sorted, so the position of `key` reveals nothing.
+    pub fn ring_for(&self, key: &str, size: usize) -> Result<Vec<String>, String> {
+        let amount = self.get(key).ok_or(format!("No ring deposit with key {}", key))?;
+        let mut ring: Vec<String> = self.deposits.iter()
+            .filter(|(other, other_amount)| *other != key && **other_amount == amount)
+            .map(|(other, _)| other.clone())
+            .take(size.clamp(MIN_RING_SIZE, MAX_RING_SIZE) - 1)
+            .collect();
+        ring.push(key.to_string());
+        ring.sort();
+        Ok(ring)
+    }
+
+    /// Checks that `key` can be deposited (a valid point not used before).
+    pub fn check_deposit(&self, key: &str, amount: f64) -> Result<(), String> {
+        if amount <= 0.0 {
+            return Err("Ring deposit amount must be positive".to_string());
+        }
+        point_from_hex(key)?;
+        if self.deposits.contains_key(key) {
+            return Err(format!("Ring key {} was already deposited", key));
+        }
+        Ok(())
+    }
+
+    /// Records a deposit.
+    pub fn deposit(&mut self, key: &str, amount: f64) {
+        self.deposits.insert(key.to_string(), amount);
+    }
+
+    /// Checks a withdrawal signed by a member of `ring`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(f64)` - The amount withdrawn (every ring member deposited it)
+    /// * `Err(String)` - If the ring is invalid, mixes amounts, the signature is
+    ///   invalid or its key image was already used
+    pub fn check_withdraw(&self, ring: &[String], signature: &RingSignature, message: &str) -> Result<f64, String> {
+        let (amount, keys) = self.ring_keys(ring, signature)?;
+        signature.verify(message, &keys)?;
+        Ok(amount)
+    }
+
+    /// Checks a withdrawal like `check_withdraw`, except the signature itself
+    /// (for blocks whose signatures are trusted).
+    pub fn check_ring(&self, ring: &[String], signature: &RingSignature) -> Result<f64, String> {
+        self.ring_keys(ring, signature).map(|(amount, _)| amount)
+    }
+
+    // The amount every member of `ring` deposited, and their keys
+    fn ring_keys(&self, ring: &[String], signature: &RingSignature) -> Result<(f64, Vec<RistrettoPoint>), String> {
+        if ring.len() < MIN_RING_SIZE || ring.len() > MAX_RING_SIZE {
+            return Err(format!("A ring has {} to {} members", MIN_RING_SIZE, MAX_RING_SIZE));
+        }
+        if ring.iter().enumerate().any(|(i, key)| ring[..i].contains(key)) {
+            return Err("Ring members must be distinct".to_string());
+        }
+        let amount = self.get(&ring[0]).ok_or(format!("No ring deposit with key {}", ring[0]))?;
+        let mut keys = Vec::with_capacity(ring.len());
+        for key in ring {
+            if self.get(key).ok_or(format!("No ring deposit with key {}", key))? != amount {
+                return Err("Ring members must have deposited the same amount".to_string());
+            }
+            keys.push(point_from_hex(key)?);
+        }
+        if self.is_spent(&signature.key_image) {
+            return Err(format!("Ring key image {} was already used (double spend)", point_to_hex(&signature.key_image)));
+        }
+        Ok((amount, keys))
+    }
+
+    /// Records the key image of a withdrawal.
+    pub fn withdraw(&mut self, signature: &RingSignature) {
+        self.key_images.insert(point_to_hex(&signature.key_image));
+    }
+}
@@ -0,0 +1,204 @@
+use curve25519_dalek::ristretto::RistrettoPoint;
+use curve25519_dalek::scalar::Scalar;
+use crate::block::block::Block;
+use crate::entity::entity::Entity;
+use crate::privacy::keys::{hash_to_scalar, point_from_hex, point_to_hex, public_key, scalar_to_hex, secret_key};
+use crate::transactions::transactions::{Transaction, TransactionKind};
+
+/// Every one-time stealth address starts with this prefix.
+pub const STEALTH_PREFIX: &str = "sx";
+
+/// Every published stealth meta-address starts with this prefix.
+pub const META_ADDRESS_PREFIX: &str = "st:";
+
+/// Returns `true` if `address` is a one-time stealth address.
+pub fn is_stealth_address(address: &str) -> bool {
+    address.starts_with(STEALTH_PREFIX)
+}
+
+/// The shared secret sender and receiver both compute (`r * A` = `a * R`).
+fn shared_secret(shared_point: &RistrettoPoint) -> Scalar {
+    hash_to_scalar("blockc/stealth/shared", &[shared_point.compress().as_bytes()])
+}
+
+/// What a receiver publishes so others can pay it privately.
+///
+/// # Fields
+///
+/// * `scan_key` - Public key used to find incoming payments (`A = a * G`)
+/// * `spend_key` - Public key the one-time addresses are built on (`B = b * G`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StealthMetaAddress {
+    pub scan_key: RistrettoPoint,
+    pub spend_key: RistrettoPoint,
+}
+
+impl StealthMetaAddress {
+    /// Encodes the meta-address as `st:<scan key><spend key>` (hex).
+    pub fn encode(&self) -> String {
+        format!("{}{}{}", META_ADDRESS_PREFIX, point_to_hex(&self.scan_key), point_to_hex(&self.spend_key))
+    }
+
+    /// Decodes a meta-address encoded with `encode`.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let keys = value.strip_prefix(META_ADDRESS_PREFIX)
+            .filter(|keys| keys.len() == 128)
+            .ok_or(format!("{:?} is not a stealth meta-address", value))?;
+        Ok(StealthMetaAddress {
+            scan_key: point_from_hex(&keys[..64])?,
+            spend_key: point_from_hex(&keys[64..])?,
+        })
+    }
+
+    /// Derives a one-time address from an ephemeral secret `r`.
+    ///
+    /// The address is `P = H(r * A) * G + B`; the ephemeral key `R = r * G` goes on
+    /// chain with the payment so the receiver can find it.
+    ///
+    /// # Returns
+    ///
+    /// The one-time address and the ephemeral key (hex).
+    pub fn derive(&self, ephemeral_secret: &Scalar) -> (String, String) {
+        let shared = shared_secret(&(ephemeral_secret * self.scan_key));
+        let one_time = public_key(&shared) + self.spend_key;
+        (format!("{}{}", STEALTH_PREFIX, point_to_hex(&one_time)), point_to_hex(&public_key(ephemeral_secret)))
+    }
+}
+
+/// Ephemeral secret a sender uses to pay `meta_address`.
+///
+/// Derived from the sender's private key and nonce, so every payment gets a new
+/// one-time address without needing a random number generator.
+pub fn ephemeral_secret(sender: &Entity, meta_address: &StealthMetaAddress) -> Scalar {
+    hash_to_scalar("blockc/stealth/ephemeral", &[
+        sender.private_key.as_bytes(),
+        meta_address.encode().as_bytes(),
+        &sender.nonce.to_le_bytes(),
+    ])
+}
+
+/// An incoming stealth payment found by scanning the chain.
+///
+/// # Fields
+///
+/// * `address` - The one-time address paid
+/// * `amount` - Amount received
+/// * `transaction_id` - Id of the payment
+/// * `secret_key` - One-time private key that spends from `address` (hex)
+#[derive(Debug, Clone, PartialEq)]
+pub struct StealthPayment {
+    pub address: String,
+    pub amount: f64,
+    pub transaction_id: String,
+    pub secret_key: String,
+}
+
+impl StealthPayment {
+    /// Returns an entity able to spend the payment (sync its balance and nonce with the chain).
+    pub fn entity(&self) -> Entity {
+        let public_key = self.address.trim_start_matches(STEALTH_PREFIX).to_string();
+        Entity::new(self.address.clone(), self.amount, Vec::new(), public_key, self.secret_key.clone())
+    }
+}
+
+/// The private side of a stealth meta-address.
+///
+/// The scan secret `a` is enough to detect payments (it can be given to a
+/// watch-only service); the spend secret `b` is also needed to spend them.
+/// Both are derived from the entity's private key.
+///
+/// # Fields
+///
+/// * `scan_secret` - `a`
+/// * `spend_secret` - `b`
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::entity::entity::Entity;
+/// use blockc::privacy::stealth::StealthKeys;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+/// use blockc::wallet::wallet::Wallet;
+///
+/// let mut chain = Blockchain::new();
+/// chain.add_block(Vec::new(), "Alice").unwrap();
+/// let fee = chain.get_base_fee() * 2.0;
+/// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+/// let bob = Wallet::new(Entity::new("Bob".to_string(), 0.0, Vec::new(), "b".to_string(), "bob-secret".to_string()));
+///
+/// // Bob publishes his meta-address once; Alice pays it twice
+/// let meta = StealthKeys::from_private_key("bob-secret").meta_address().encode();
+/// let first = Transaction::create_stealth_payment(&mut alice, &meta, 10.0, fee).unwrap();
+/// let second = Transaction::create_stealth_payment(&mut alice, &meta, 5.0, fee).unwrap();
+/// assert_ne!(first.receiver_address, second.receiver_address);
+/// chain.add_block(vec![first, second], "Miner").unwrap();
+/// assert_eq!(chain.get_balance("Bob"), 0.0); // Bob's address never appears
+///
+/// // Bob scans the chain, finds both payments and spends one
+/// let found = bob.scan_stealth(&chain);
+/// assert_eq!(found.len(), 2);
+/// let mut one_time = found[0].entity();
+/// let spend = Transaction::create_payment(&mut one_time, "Carol", 9.0, fee).unwrap();
+/// chain.add_block(vec![spend], "Miner").unwrap();
+/// assert_eq!(chain.get_balance("Carol"), 9.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StealthKeys {
+    pub scan_secret: Scalar,
+    pub spend_secret: Scalar,
+}
+
+impl StealthKeys {
+    /// Derives the stealth keys of an entity from its private key.
+    pub fn from_private_key(private_key: &str) -> Self {
+        StealthKeys {
+            scan_secret: secret_key(private_key, "blockc/stealth/scan"),
+            spend_secret: secret_key(private_key, "blockc/stealth/spend"),
+        }
+    }
+
+    /// Returns the meta-address to publish.
+    pub fn meta_address(&self) -> StealthMetaAddress {
+        StealthMetaAddress {
+            scan_key: public_key(&self.scan_secret),
+            spend_key: public_key(&self.spend_secret),
+        }
+    }
+
+    /// Checks whether a payment to `address` with `ephemeral_key` is for these keys.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(Scalar)` - The one-time private key `H(a * R) + b` spending from `address`
+    /// * `None` - If the payment is for someone else (or the key is malformed)
+    pub fn detect(&self, address: &str, ephemeral_key: &str) -> Option<Scalar> {
+        let ephemeral = point_from_hex(ephemeral_key).ok()?;
+        let shared = shared_secret(&(self.scan_secret * ephemeral));
+        let expected = public_key(&shared) + public_key(&self.spend_secret);
+        if address != format!("{}{}", STEALTH_PREFIX, point_to_hex(&expected)) {
+            return None;
+        }
+        Some(shared + self.spend_secret)
+    }
+
+    /// Returns the stealth payments to these keys in `block`.
+    pub fn scan_block(&self, block: &Block) -> Vec<StealthPayment> {
+        block.transaction.iter().filter_map(|transaction| self.scan_transaction(transaction)).collect()
+    }
+
+    /// Returns the payment carried by `transaction` if it is a stealth payment to these keys.
+    pub fn scan_transaction(&self, transaction: &Transaction) -> Option<StealthPayment> {
+        let TransactionKind::StealthTransfer { ephemeral_key } = &transaction.kind else {
+            return None;
+        };
+        let secret = self.detect(&transaction.receiver_address, ephemeral_key)?;
+        Some(StealthPayment {
+            address: transaction.receiver_address.clone(),
+            amount: transaction.amount,
+            transaction_id: transaction.id(),
+            secret_key: scalar_to_hex(&secret),
+        })
+    }
+}
+
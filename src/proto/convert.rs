@@ -0,0 +1,1057 @@
+use prost::Message as _;
+use crate::block::block::{Block, BlockHeader, MAX_ENCODED_BLOCK_BYTES};
+use crate::bridge::bridge::BridgeProof;
+use crate::codec::json::{JsonCodec, JsonValue};
+use crate::contracts::ballot::VoteWeighting;
+use crate::contracts::channel::ChannelState;
+use crate::contracts::escrow::EscrowOutcome;
+use crate::contracts::vesting::VestingSchedule;
+use crate::dex::orderbook::Side;
+use crate::identity::did::Service;
+use crate::merkle::merkle::{MerkleProof, ProofStep};
+use crate::proto::types::{self as pb, lock_time, message::Payload, transaction_kind::{self as tk, Kind}, vesting_schedule};
+use crate::simulation::network::Message;
+use crate::transactions::transactions::{LockTime, Output, Transaction, TransactionKind, MAX_ENCODED_TRANSACTION_BYTES};
+use crate::utxo::coinjoin::JoinInput;
+use crate::utxo::utxo::OutPoint;
+use crate::vm::instruction::Instruction;
+#[cfg(feature = "privacy")]
+use crate::privacy::confidential::{BalanceProof, BitProof, ConfidentialOutput, RangeProof};
+#[cfg(feature = "privacy")]
+use crate::privacy::keys::{point_from_hex, scalar_from_hex};
+#[cfg(feature = "privacy")]
+use crate::privacy::ring::RingSignature;
+#[cfg(feature = "privacy")]
+use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar};
+
+/// Encodes a block as a protobuf `blockc.v1.Block`.
+pub fn encode_block(block: &Block) -> Vec<u8> {
+    pb::Block::from(block).encode_to_vec()
+}
+
+/// Decodes a protobuf `blockc.v1.Block` written by any implementation of
+/// `proto/blockc.proto`.
+///
+/// The block is only decoded, not validated (see `Blockchain::submit_block`).
+///
+/// # Returns
+///
+/// * `Ok(Block)` - The decoded block
+/// * `Err(String)` - If the input is over `MAX_ENCODED_BLOCK_BYTES`, is not a
+///   protobuf message, misses a required field or has out-of-range values
+///
+/// # Example
+///
+/// ```
+/// use prost::Message;
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::proto::convert::{decode_block, encode_block};
+/// use blockc::proto::types;
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// chain.add_block(Vec::new(), "Miner").unwrap();
+/// let block = chain.get_latest_block();
+///
+/// let bytes = encode_block(block);
+/// assert_eq!(&decode_block(&bytes).unwrap(), block);
+///
+/// // Other tools see the generated message
+/// let message = types::Block::decode(bytes.as_slice()).unwrap();
+/// assert_eq!(message.block_hash, block.block_hash);
+/// assert_eq!(message.transactions[0].receiver_address, "Miner");
+///
+/// assert!(decode_block(&bytes[..bytes.len() - 1]).is_err());
+/// ```
+pub fn decode_block(bytes: &[u8]) -> Result<Block, String> {
+    if bytes.len() > MAX_ENCODED_BLOCK_BYTES {
+        return Err(format!("Encoded block is over {} bytes", MAX_ENCODED_BLOCK_BYTES));
+    }
+    pb::Block::decode(bytes).map_err(|error| error.to_string())?.try_into()
+}
+
+/// Encodes a transaction as a protobuf `blockc.v1.Transaction`.
+pub fn encode_transaction(transaction: &Transaction) -> Vec<u8> {
+    pb::Transaction::from(transaction).encode_to_vec()
+}
+
+/// Decodes a protobuf `blockc.v1.Transaction`, without validating it.
+///
+/// # Returns
+///
+/// * `Ok(Transaction)` - The decoded transaction
+/// * `Err(String)` - If the input is over `MAX_ENCODED_TRANSACTION_BYTES`, is
+///   not a protobuf message, misses a required field or has out-of-range values
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "testing")] {
+/// use proptest::test_runner::TestRunner;
+/// use blockc::proto::convert::{decode_transaction, encode_transaction};
+/// use blockc::testing::arbitrary::any_kind_transaction;
+///
+/// // Every kind and lock time survives the trip
+/// TestRunner::default().run(&any_kind_transaction(), |tx| {
+///     assert_eq!(decode_transaction(&encode_transaction(&tx)).unwrap(), tx);
+///     Ok(())
+/// }).unwrap();
+/// # }
+/// ```
+pub fn decode_transaction(bytes: &[u8]) -> Result<Transaction, String> {
+    if bytes.len() > MAX_ENCODED_TRANSACTION_BYTES {
+        return Err(format!("Encoded transaction is over {} bytes", MAX_ENCODED_TRANSACTION_BYTES));
+    }
+    pb::Transaction::decode(bytes).map_err(|error| error.to_string())?.try_into()
+}
+
+/// Encodes a gossip message as a protobuf `blockc.v1.Message`.
+pub fn encode_message(message: &Message) -> Vec<u8> {
+    pb::Message::from(message).encode_to_vec()
+}
+
+/// Decodes a protobuf `blockc.v1.Message`.
+///
+/// # Returns
+///
+/// * `Ok(Message)` - The decoded message
+/// * `Err(String)` - If the input is over `MAX_ENCODED_BLOCK_BYTES` or does
+///   not hold a valid block or transaction
+///
+/// # Example
+///
+/// ```
+/// use blockc::entity::entity::Entity;
+/// use blockc::proto::convert::{decode_message, encode_message};
+/// use blockc::simulation::network::Message;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+///
+/// let mut alice = Entity::new("Alice".to_string(), 100.0, Vec::new(), "pub".to_string(), "priv".to_string());
+/// let tx = Transaction::create_payment(&mut alice, "Bob", 10.0, 0.1).unwrap();
+///
+/// let bytes = encode_message(&Message::Transaction(Box::new(tx.clone())));
+/// match decode_message(&bytes).unwrap() {
+///     Message::Transaction(decoded) => assert_eq!(*decoded, tx),
+///     Message::Block(_) => unreachable!(),
+/// }
+///
+/// // An empty message holds neither
+/// assert!(decode_message(&[]).is_err());
+/// ```
+pub fn decode_message(bytes: &[u8]) -> Result<Message, String> {
+    if bytes.len() > MAX_ENCODED_BLOCK_BYTES {
+        return Err(format!("Encoded message is over {} bytes", MAX_ENCODED_BLOCK_BYTES));
+    }
+    pb::Message::decode(bytes).map_err(|error| error.to_string())?.try_into()
+}
+
+fn required<T>(value: Option<T>, field: &str) -> Result<T, String> {
+    value.ok_or(format!("Missing {}", field))
+}
+
+fn convert_all<T, U: TryFrom<T, Error = String>>(items: Vec<T>) -> Result<Vec<U>, String> {
+    items.into_iter().map(U::try_from).collect()
+}
+
+fn to_usize(value: u64, field: &str) -> Result<usize, String> {
+    usize::try_from(value).map_err(|_| format!("{} {} is out of range", field, value))
+}
+
+fn to_u8(value: u32, field: &str) -> Result<u8, String> {
+    u8::try_from(value).map_err(|_| format!("{} {} is out of range", field, value))
+}
+
+impl From<&Message> for pb::Message {
+    fn from(message: &Message) -> Self {
+        let payload = match message {
+            Message::Block(block) => Payload::Block(block.into()),
+            Message::Transaction(transaction) => Payload::Transaction(transaction.as_ref().into()),
+        };
+        pb::Message {
+            payload: Some(payload),
+        }
+    }
+}
+
+impl TryFrom<pb::Message> for Message {
+    type Error = String;
+
+    fn try_from(message: pb::Message) -> Result<Self, String> {
+        match required(message.payload, "message payload")? {
+            Payload::Block(block) => Ok(Message::Block(block.try_into()?)),
+            Payload::Transaction(transaction) => Ok(Message::Transaction(Box::new(transaction.try_into()?))),
+        }
+    }
+}
+
+impl From<&Block> for pb::Block {
+    fn from(block: &Block) -> Self {
+        pb::Block {
+            block_hash: block.block_hash.clone(),
+            previous_block_hash: block.previous_block_hash.clone(),
+            transactions: block.transaction.iter().map(pb::Transaction::from).collect(),
+            time_stamp: block.time_stamp,
+            nonce: block.nonce,
+            base_fee: block.base_fee,
+            merkle_root: block.merkle_root.clone(),
+            state_root: block.state_root.clone(),
+        }
+    }
+}
+
+impl TryFrom<pb::Block> for Block {
+    type Error = String;
+
+    fn try_from(block: pb::Block) -> Result<Self, String> {
+        Ok(Block {
+            block_hash: block.block_hash,
+            previous_block_hash: block.previous_block_hash,
+            transaction: convert_all(block.transactions)?,
+            time_stamp: block.time_stamp,
+            nonce: block.nonce,
+            base_fee: block.base_fee,
+            merkle_root: block.merkle_root,
+            state_root: block.state_root,
+        })
+    }
+}
+
+impl From<&BlockHeader> for pb::BlockHeader {
+    fn from(header: &BlockHeader) -> Self {
+        pb::BlockHeader {
+            block_hash: header.block_hash.clone(),
+            previous_block_hash: header.previous_block_hash.clone(),
+            time_stamp: header.time_stamp,
+            nonce: header.nonce,
+            base_fee: header.base_fee,
+            merkle_root: header.merkle_root.clone(),
+            state_root: header.state_root.clone(),
+        }
+    }
+}
+
+impl From<pb::BlockHeader> for BlockHeader {
+    fn from(header: pb::BlockHeader) -> Self {
+        BlockHeader {
+            block_hash: header.block_hash,
+            previous_block_hash: header.previous_block_hash,
+            time_stamp: header.time_stamp,
+            nonce: header.nonce,
+            base_fee: header.base_fee,
+            merkle_root: header.merkle_root,
+            state_root: header.state_root,
+        }
+    }
+}
+
+impl From<&Transaction> for pb::Transaction {
+    fn from(transaction: &Transaction) -> Self {
+        pb::Transaction {
+            sender_address: transaction.sender_address.clone(),
+            receiver_address: transaction.receiver_address.clone(),
+            amount: transaction.amount,
+            fee: transaction.fee,
+            nonce: transaction.nonce,
+            timestamp: transaction.timestamp,
+            signature: transaction.signature.clone(),
+            memo: transaction.memo.clone(),
+            kind: Some((&transaction.kind).into()),
+            lock_time: lock_time_to_proto(&transaction.lock_time),
+            gas_limit: transaction.gas_limit,
+            gas_price: transaction.gas_price,
+            script: transaction.script.clone(),
+        }
+    }
+}
+
+impl TryFrom<pb::Transaction> for Transaction {
+    type Error = String;
+
+    fn try_from(transaction: pb::Transaction) -> Result<Self, String> {
+        Ok(Transaction {
+            sender_address: transaction.sender_address,
+            receiver_address: transaction.receiver_address,
+            amount: transaction.amount,
+            fee: transaction.fee,
+            nonce: transaction.nonce,
+            timestamp: transaction.timestamp,
+            signature: transaction.signature,
+            memo: transaction.memo,
+            kind: required(transaction.kind, "transaction kind")?.try_into()?,
+            lock_time: lock_time_from_proto(transaction.lock_time)?,
+            gas_limit: transaction.gas_limit,
+            gas_price: transaction.gas_price,
+            script: transaction.script,
+        })
+    }
+}
+
+fn lock_time_to_proto(lock: &LockTime) -> Option<pb::LockTime> {
+    let lock = match lock {
+        LockTime::None => return None,
+        LockTime::Height(height) => lock_time::Lock::Height(*height),
+        LockTime::Timestamp(time) => lock_time::Lock::Timestamp(*time),
+        LockTime::RelativeHeight { transaction_id, blocks } => lock_time::Lock::RelativeHeight(lock_time::RelativeHeight {
+            transaction_id: transaction_id.clone(),
+            blocks: *blocks,
+        }),
+        LockTime::RelativeTime { transaction_id, seconds } => lock_time::Lock::RelativeTime(lock_time::RelativeTime {
+            transaction_id: transaction_id.clone(),
+            seconds: *seconds,
+        }),
+    };
+    Some(pb::LockTime {
+        lock: Some(lock),
+    })
+}
+
+fn lock_time_from_proto(lock: Option<pb::LockTime>) -> Result<LockTime, String> {
+    let Some(lock) = lock else {
+        return Ok(LockTime::None);
+    };
+    Ok(match required(lock.lock, "lock time")? {
+        lock_time::Lock::Height(height) => LockTime::Height(height),
+        lock_time::Lock::Timestamp(time) => LockTime::Timestamp(time),
+        lock_time::Lock::RelativeHeight(lock) => LockTime::RelativeHeight {
+            transaction_id: lock.transaction_id,
+            blocks: lock.blocks,
+        },
+        lock_time::Lock::RelativeTime(lock) => LockTime::RelativeTime {
+            transaction_id: lock.transaction_id,
+            seconds: lock.seconds,
+        },
+    })
+}
+
+impl From<&Output> for pb::Output {
+    fn from(output: &Output) -> Self {
+        pb::Output {
+            address: output.address.clone(),
+            amount: output.amount,
+        }
+    }
+}
+
+impl TryFrom<pb::Output> for Output {
+    type Error = String;
+
+    fn try_from(output: pb::Output) -> Result<Self, String> {
+        Ok(Output {
+            address: output.address,
+            amount: output.amount,
+        })
+    }
+}
+
+impl From<&OutPoint> for pb::OutPoint {
+    fn from(outpoint: &OutPoint) -> Self {
+        pb::OutPoint {
+            transaction_id: outpoint.transaction_id.clone(),
+            index: outpoint.index as u64,
+        }
+    }
+}
+
+impl TryFrom<pb::OutPoint> for OutPoint {
+    type Error = String;
+
+    fn try_from(outpoint: pb::OutPoint) -> Result<Self, String> {
+        Ok(OutPoint {
+            transaction_id: outpoint.transaction_id,
+            index: to_usize(outpoint.index, "Output index")?,
+        })
+    }
+}
+
+impl From<&JoinInput> for pb::JoinInput {
+    fn from(input: &JoinInput) -> Self {
+        pb::JoinInput {
+            outpoint: Some((&input.outpoint).into()),
+            signature: input.signature.clone(),
+        }
+    }
+}
+
+impl TryFrom<pb::JoinInput> for JoinInput {
+    type Error = String;
+
+    fn try_from(input: pb::JoinInput) -> Result<Self, String> {
+        Ok(JoinInput {
+            outpoint: required(input.outpoint, "join input outpoint")?.try_into()?,
+            signature: input.signature,
+        })
+    }
+}
+
+impl From<&ChannelState> for pb::ChannelState {
+    fn from(state: &ChannelState) -> Self {
+        pb::ChannelState {
+            channel_id: state.channel_id.clone(),
+            sequence: state.sequence,
+            balance_a: state.balance_a,
+            balance_b: state.balance_b,
+            signature_a: state.signature_a.clone(),
+            signature_b: state.signature_b.clone(),
+        }
+    }
+}
+
+impl From<pb::ChannelState> for ChannelState {
+    fn from(state: pb::ChannelState) -> Self {
+        ChannelState {
+            channel_id: state.channel_id,
+            sequence: state.sequence,
+            balance_a: state.balance_a,
+            balance_b: state.balance_b,
+            signature_a: state.signature_a,
+            signature_b: state.signature_b,
+        }
+    }
+}
+
+impl From<&Service> for pb::Service {
+    fn from(service: &Service) -> Self {
+        pb::Service {
+            id: service.id.clone(),
+            kind: service.kind.clone(),
+            endpoint: service.endpoint.clone(),
+        }
+    }
+}
+
+impl TryFrom<pb::Service> for Service {
+    type Error = String;
+
+    fn try_from(service: pb::Service) -> Result<Self, String> {
+        Ok(Service {
+            id: service.id,
+            kind: service.kind,
+            endpoint: service.endpoint,
+        })
+    }
+}
+
+impl From<&BridgeProof> for pb::BridgeProof {
+    fn from(proof: &BridgeProof) -> Self {
+        pb::BridgeProof {
+            block_hash: proof.block_hash.clone(),
+            transaction: Some((&proof.transaction).into()),
+            proof: Some(pb::MerkleProof {
+                leaf: proof.proof.leaf.clone(),
+                steps: proof.proof.steps.iter()
+                    .map(|step| pb::ProofStep {
+                        hash: step.hash.clone(),
+                        is_left: step.is_left,
+                    })
+                    .collect(),
+            }),
+        }
+    }
+}
+
+impl TryFrom<pb::BridgeProof> for BridgeProof {
+    type Error = String;
+
+    fn try_from(proof: pb::BridgeProof) -> Result<Self, String> {
+        let merkle = required(proof.proof, "bridge Merkle proof")?;
+        Ok(BridgeProof {
+            block_hash: proof.block_hash,
+            transaction: required(proof.transaction, "bridged transaction")?.try_into()?,
+            proof: MerkleProof {
+                leaf: merkle.leaf,
+                steps: merkle.steps.into_iter()
+                    .map(|step| ProofStep {
+                        hash: step.hash,
+                        is_left: step.is_left,
+                    })
+                    .collect(),
+            },
+        })
+    }
+}
+
+fn bridge_proof_from_proto(proof: Option<Box<pb::BridgeProof>>) -> Result<Box<BridgeProof>, String> {
+    Ok(Box::new((*required(proof, "bridge proof")?).try_into()?))
+}
+
+impl From<&VestingSchedule> for pb::VestingSchedule {
+    fn from(schedule: &VestingSchedule) -> Self {
+        let schedule = match schedule {
+            VestingSchedule::Linear { start_height, end_height } => vesting_schedule::Schedule::Linear(vesting_schedule::Linear {
+                start_height: *start_height,
+                end_height: *end_height,
+            }),
+            VestingSchedule::Cliffs { unlocks } => vesting_schedule::Schedule::Cliffs(vesting_schedule::Cliffs {
+                unlocks: unlocks.iter()
+                    .map(|(height, amount)| vesting_schedule::Unlock {
+                        height: *height,
+                        amount: *amount,
+                    })
+                    .collect(),
+            }),
+        };
+        pb::VestingSchedule {
+            schedule: Some(schedule),
+        }
+    }
+}
+
+impl TryFrom<pb::VestingSchedule> for VestingSchedule {
+    type Error = String;
+
+    fn try_from(schedule: pb::VestingSchedule) -> Result<Self, String> {
+        Ok(match required(schedule.schedule, "vesting schedule")? {
+            vesting_schedule::Schedule::Linear(linear) => VestingSchedule::Linear {
+                start_height: linear.start_height,
+                end_height: linear.end_height,
+            },
+            vesting_schedule::Schedule::Cliffs(cliffs) => VestingSchedule::Cliffs {
+                unlocks: cliffs.unlocks.into_iter().map(|unlock| (unlock.height, unlock.amount)).collect(),
+            },
+        })
+    }
+}
+
+fn escrow_outcome_to_proto(outcome: &EscrowOutcome) -> i32 {
+    match outcome {
+        EscrowOutcome::Release => pb::EscrowOutcome::Release as i32,
+        EscrowOutcome::Refund => pb::EscrowOutcome::Refund as i32,
+    }
+}
+
+fn escrow_outcome_from_proto(outcome: i32) -> Result<EscrowOutcome, String> {
+    match pb::EscrowOutcome::try_from(outcome) {
+        Ok(pb::EscrowOutcome::Release) => Ok(EscrowOutcome::Release),
+        Ok(pb::EscrowOutcome::Refund) => Ok(EscrowOutcome::Refund),
+        _ => Err(format!("{} is not a valid escrow outcome", outcome)),
+    }
+}
+
+fn weighting_to_proto(weighting: &VoteWeighting) -> i32 {
+    match weighting {
+        VoteWeighting::OnePerAddress => pb::VoteWeighting::OnePerAddress as i32,
+        VoteWeighting::Balance => pb::VoteWeighting::Balance as i32,
+    }
+}
+
+fn weighting_from_proto(weighting: i32) -> Result<VoteWeighting, String> {
+    match pb::VoteWeighting::try_from(weighting) {
+        Ok(pb::VoteWeighting::OnePerAddress) => Ok(VoteWeighting::OnePerAddress),
+        Ok(pb::VoteWeighting::Balance) => Ok(VoteWeighting::Balance),
+        _ => Err(format!("{} is not a valid vote weighting", weighting)),
+    }
+}
+
+fn side_to_proto(side: &Side) -> i32 {
+    match side {
+        Side::Buy => pb::Side::Buy as i32,
+        Side::Sell => pb::Side::Sell as i32,
+    }
+}
+
+fn side_from_proto(side: i32) -> Result<Side, String> {
+    match pb::Side::try_from(side) {
+        Ok(pb::Side::Buy) => Ok(Side::Buy),
+        Ok(pb::Side::Sell) => Ok(Side::Sell),
+        _ => Err(format!("{} is not a valid order side", side)),
+    }
+}
+
+// Instructions travel in their text form, like in JSON
+fn instruction_from_text(text: String) -> Result<Instruction, String> {
+    Instruction::from_json(&JsonValue::String(text))
+}
+
+impl From<&TransactionKind> for pb::TransactionKind {
+    fn from(kind: &TransactionKind) -> Self {
+        let outputs = |outputs: &[Output]| outputs.iter().map(pb::Output::from).collect();
+        let services = |services: &[Service]| services.iter().map(pb::Service::from).collect();
+        let kind = match kind {
+            TransactionKind::Transfer => Kind::Transfer(tk::Transfer {}),
+            TransactionKind::Anchor { doc_hash } => Kind::Anchor(tk::Anchor { doc_hash: doc_hash.clone() }),
+            TransactionKind::MultiPay { outputs: paid } => Kind::MultiPay(tk::MultiPay { outputs: outputs(paid) }),
+            TransactionKind::HtlcLock { hash_lock, expiry_height } => Kind::HtlcLock(tk::HtlcLock {
+                hash_lock: hash_lock.clone(),
+                expiry_height: *expiry_height,
+            }),
+            TransactionKind::HtlcClaim { htlc_id, preimage } => Kind::HtlcClaim(tk::HtlcClaim {
+                htlc_id: htlc_id.clone(),
+                preimage: preimage.clone(),
+            }),
+            TransactionKind::HtlcRefund { htlc_id } => Kind::HtlcRefund(tk::HtlcRefund { htlc_id: htlc_id.clone() }),
+            TransactionKind::ChannelOpen => Kind::ChannelOpen(tk::ChannelOpen {}),
+            TransactionKind::ChannelClose { state } => Kind::ChannelClose(tk::ChannelClose { state: Some(state.into()) }),
+            TransactionKind::ChannelSettle { channel_id } => Kind::ChannelSettle(tk::ChannelSettle { channel_id: channel_id.clone() }),
+            TransactionKind::EscrowOpen { arbiter } => Kind::EscrowOpen(tk::EscrowOpen { arbiter: arbiter.clone() }),
+            TransactionKind::EscrowVote { escrow_id, outcome } => Kind::EscrowVote(tk::EscrowVote {
+                escrow_id: escrow_id.clone(),
+                outcome: escrow_outcome_to_proto(outcome),
+            }),
+            TransactionKind::VestingGrant { schedule } => Kind::VestingGrant(tk::VestingGrant { schedule: Some(schedule.into()) }),
+            TransactionKind::StreamOpen { rate_per_block } => Kind::StreamOpen(tk::StreamOpen { rate_per_block: *rate_per_block }),
+            TransactionKind::StreamWithdraw { stream_id } => Kind::StreamWithdraw(tk::StreamWithdraw { stream_id: stream_id.clone() }),
+            TransactionKind::StreamCancel { stream_id } => Kind::StreamCancel(tk::StreamCancel { stream_id: stream_id.clone() }),
+            TransactionKind::Utxo { inputs, outputs: paid } => Kind::Utxo(tk::Utxo {
+                inputs: inputs.iter().map(pb::OutPoint::from).collect(),
+                outputs: outputs(paid),
+            }),
+            TransactionKind::CoinJoin { inputs, outputs: paid } => Kind::CoinJoin(tk::CoinJoin {
+                inputs: inputs.iter().map(pb::JoinInput::from).collect(),
+                outputs: outputs(paid),
+            }),
+            TransactionKind::Deploy { code } => Kind::Deploy(tk::Deploy { code: code.iter().map(Instruction::encode).collect() }),
+            TransactionKind::Call { contract, input } => Kind::Call(tk::Call {
+                contract: contract.clone(),
+                input: input.clone(),
+            }),
+            TransactionKind::AssetIssue { ticker, decimals, max_supply, initial_supply } => Kind::AssetIssue(tk::AssetIssue {
+                ticker: ticker.clone(),
+                decimals: u32::from(*decimals),
+                max_supply: *max_supply,
+                initial_supply: *initial_supply,
+            }),
+            TransactionKind::AssetMint { asset_id, quantity } => Kind::AssetMint(tk::AssetMint {
+                asset_id: asset_id.clone(),
+                quantity: *quantity,
+            }),
+            TransactionKind::AssetTransfer { asset_id, quantity } => Kind::AssetTransfer(tk::AssetTransfer {
+                asset_id: asset_id.clone(),
+                quantity: *quantity,
+            }),
+            TransactionKind::NftMint { token_id, metadata_hash } => Kind::NftMint(tk::NftMint {
+                token_id: token_id.clone(),
+                metadata_hash: metadata_hash.clone(),
+            }),
+            TransactionKind::NftTransfer { token_id } => Kind::NftTransfer(tk::NftTransfer { token_id: token_id.clone() }),
+            TransactionKind::NftBurn { token_id } => Kind::NftBurn(tk::NftBurn { token_id: token_id.clone() }),
+            TransactionKind::NameRegister { name, target } => Kind::NameRegister(tk::NameRegister {
+                name: name.clone(),
+                target: target.clone(),
+            }),
+            TransactionKind::NameRenew { name } => Kind::NameRenew(tk::NameRenew { name: name.clone() }),
+            TransactionKind::DidRegister { public_keys, services: listed } => Kind::DidRegister(tk::DidRegister {
+                public_keys: public_keys.clone(),
+                services: services(listed),
+            }),
+            TransactionKind::DidUpdate { public_keys, services: listed } => Kind::DidUpdate(tk::DidUpdate {
+                public_keys: public_keys.clone(),
+                services: services(listed),
+            }),
+            TransactionKind::BallotCreate { question, options, start_height, end_height, weighting } => Kind::BallotCreate(tk::BallotCreate {
+                question: question.clone(),
+                options: options.clone(),
+                start_height: *start_height,
+                end_height: *end_height,
+                weighting: weighting_to_proto(weighting),
+            }),
+            TransactionKind::BallotVote { ballot_id, option } => Kind::BallotVote(tk::BallotVote {
+                ballot_id: ballot_id.clone(),
+                option: *option as u64,
+            }),
+            TransactionKind::AuctionCreate { item, reserve_price, bidding_end, reveal_end } => Kind::AuctionCreate(tk::AuctionCreate {
+                item: item.clone(),
+                reserve_price: *reserve_price,
+                bidding_end: *bidding_end,
+                reveal_end: *reveal_end,
+            }),
+            TransactionKind::AuctionBid { auction_id } => Kind::AuctionBid(tk::AuctionBid { auction_id: auction_id.clone() }),
+            TransactionKind::AuctionCommit { auction_id, commitment } => Kind::AuctionCommit(tk::AuctionCommit {
+                auction_id: auction_id.clone(),
+                commitment: commitment.clone(),
+            }),
+            TransactionKind::AuctionReveal { auction_id, bid, salt } => Kind::AuctionReveal(tk::AuctionReveal {
+                auction_id: auction_id.clone(),
+                bid: *bid,
+                salt: salt.clone(),
+            }),
+            TransactionKind::AuctionSettle { auction_id } => Kind::AuctionSettle(tk::AuctionSettle { auction_id: auction_id.clone() }),
+            TransactionKind::PoolCreate { asset_a, asset_b, amount_a, amount_b, fee_bps } => Kind::PoolCreate(tk::PoolCreate {
+                asset_a: asset_a.clone(),
+                asset_b: asset_b.clone(),
+                amount_a: *amount_a,
+                amount_b: *amount_b,
+                fee_bps: *fee_bps,
+            }),
+            TransactionKind::PoolDeposit { pool_id, amount_a, amount_b } => Kind::PoolDeposit(tk::PoolDeposit {
+                pool_id: pool_id.clone(),
+                amount_a: *amount_a,
+                amount_b: *amount_b,
+            }),
+            TransactionKind::PoolWithdraw { pool_id, shares } => Kind::PoolWithdraw(tk::PoolWithdraw {
+                pool_id: pool_id.clone(),
+                shares: *shares,
+            }),
+            TransactionKind::PoolSwap { pool_id, asset_in, amount_in, min_out } => Kind::PoolSwap(tk::PoolSwap {
+                pool_id: pool_id.clone(),
+                asset_in: asset_in.clone(),
+                amount_in: *amount_in,
+                min_out: *min_out,
+            }),
+            TransactionKind::OrderPlace { base, quote, side, price, quantity } => Kind::OrderPlace(tk::OrderPlace {
+                base: base.clone(),
+                quote: quote.clone(),
+                side: side_to_proto(side),
+                price: *price,
+                quantity: *quantity,
+            }),
+            TransactionKind::OrderCancel { order_id } => Kind::OrderCancel(tk::OrderCancel { order_id: order_id.clone() }),
+            TransactionKind::OracleCreate { feed, reporters, quorum, max_age } => Kind::OracleCreate(tk::OracleCreate {
+                feed: feed.clone(),
+                reporters: reporters.clone(),
+                quorum: *quorum as u64,
+                max_age: *max_age,
+            }),
+            TransactionKind::OracleReport { feed, value } => Kind::OracleReport(tk::OracleReport {
+                feed: feed.clone(),
+                value: *value,
+            }),
+            TransactionKind::BridgeLock { chain_id, asset_id, ticker, decimals, quantity, recipient } => Kind::BridgeLock(tk::BridgeLock {
+                chain_id: chain_id.clone(),
+                asset_id: asset_id.clone(),
+                ticker: ticker.clone(),
+                decimals: u32::from(*decimals),
+                quantity: *quantity,
+                recipient: recipient.clone(),
+            }),
+            TransactionKind::BridgeRelay { chain_id, headers } => Kind::BridgeRelay(tk::BridgeRelay {
+                chain_id: chain_id.clone(),
+                headers: headers.iter().map(pb::BlockHeader::from).collect(),
+            }),
+            TransactionKind::BridgeMint { chain_id, proof } => Kind::BridgeMint(tk::BridgeMint {
+                chain_id: chain_id.clone(),
+                proof: Some(Box::new(proof.as_ref().into())),
+            }),
+            TransactionKind::BridgeBurn { chain_id, asset_id, quantity, recipient } => Kind::BridgeBurn(tk::BridgeBurn {
+                chain_id: chain_id.clone(),
+                asset_id: asset_id.clone(),
+                quantity: *quantity,
+                recipient: recipient.clone(),
+            }),
+            TransactionKind::BridgeRelease { chain_id, proof } => Kind::BridgeRelease(tk::BridgeRelease {
+                chain_id: chain_id.clone(),
+                proof: Some(Box::new(proof.as_ref().into())),
+            }),
+            TransactionKind::StealthTransfer { ephemeral_key } => Kind::StealthTransfer(tk::StealthTransfer { ephemeral_key: ephemeral_key.clone() }),
+            #[cfg(feature = "privacy")]
+            TransactionKind::ConfidentialTransfer { inputs, outputs, withdrawal, proof } => Kind::ConfidentialTransfer(tk::ConfidentialTransfer {
+                inputs: inputs.iter().map(pb::OutPoint::from).collect(),
+                outputs: outputs.iter().map(pb::ConfidentialOutput::from).collect(),
+                withdrawal: *withdrawal,
+                proof: Some(proof.into()),
+            }),
+            #[cfg(feature = "privacy")]
+            TransactionKind::RingDeposit { key } => Kind::RingDeposit(tk::RingDeposit { key: key.clone() }),
+            #[cfg(feature = "privacy")]
+            TransactionKind::RingWithdraw { ring, signature } => Kind::RingWithdraw(tk::RingWithdraw {
+                ring: ring.clone(),
+                signature: Some(signature.into()),
+            }),
+        };
+        pb::TransactionKind {
+            kind: Some(kind),
+        }
+    }
+}
+
+impl TryFrom<pb::TransactionKind> for TransactionKind {
+    type Error = String;
+
+    fn try_from(kind: pb::TransactionKind) -> Result<Self, String> {
+        Ok(match required(kind.kind, "transaction kind")? {
+            Kind::Transfer(_) => TransactionKind::Transfer,
+            Kind::Anchor(kind) => TransactionKind::Anchor { doc_hash: kind.doc_hash },
+            Kind::MultiPay(kind) => TransactionKind::MultiPay { outputs: convert_all(kind.outputs)? },
+            Kind::HtlcLock(kind) => TransactionKind::HtlcLock {
+                hash_lock: kind.hash_lock,
+                expiry_height: kind.expiry_height,
+            },
+            Kind::HtlcClaim(kind) => TransactionKind::HtlcClaim {
+                htlc_id: kind.htlc_id,
+                preimage: kind.preimage,
+            },
+            Kind::HtlcRefund(kind) => TransactionKind::HtlcRefund { htlc_id: kind.htlc_id },
+            Kind::ChannelOpen(_) => TransactionKind::ChannelOpen,
+            Kind::ChannelClose(kind) => TransactionKind::ChannelClose { state: required(kind.state, "channel state")?.into() },
+            Kind::ChannelSettle(kind) => TransactionKind::ChannelSettle { channel_id: kind.channel_id },
+            Kind::EscrowOpen(kind) => TransactionKind::EscrowOpen { arbiter: kind.arbiter },
+            Kind::EscrowVote(kind) => TransactionKind::EscrowVote {
+                escrow_id: kind.escrow_id,
+                outcome: escrow_outcome_from_proto(kind.outcome)?,
+            },
+            Kind::VestingGrant(kind) => TransactionKind::VestingGrant { schedule: required(kind.schedule, "vesting schedule")?.try_into()? },
+            Kind::StreamOpen(kind) => TransactionKind::StreamOpen { rate_per_block: kind.rate_per_block },
+            Kind::StreamWithdraw(kind) => TransactionKind::StreamWithdraw { stream_id: kind.stream_id },
+            Kind::StreamCancel(kind) => TransactionKind::StreamCancel { stream_id: kind.stream_id },
+            Kind::Utxo(kind) => TransactionKind::Utxo {
+                inputs: convert_all(kind.inputs)?,
+                outputs: convert_all(kind.outputs)?,
+            },
+            Kind::CoinJoin(kind) => TransactionKind::CoinJoin {
+                inputs: convert_all(kind.inputs)?,
+                outputs: convert_all(kind.outputs)?,
+            },
+            Kind::Deploy(kind) => TransactionKind::Deploy {
+                code: kind.code.into_iter().map(instruction_from_text).collect::<Result<_, _>>()?,
+            },
+            Kind::Call(kind) => TransactionKind::Call {
+                contract: kind.contract,
+                input: kind.input,
+            },
+            Kind::AssetIssue(kind) => TransactionKind::AssetIssue {
+                ticker: kind.ticker,
+                decimals: to_u8(kind.decimals, "Decimals")?,
+                max_supply: kind.max_supply,
+                initial_supply: kind.initial_supply,
+            },
+            Kind::AssetMint(kind) => TransactionKind::AssetMint {
+                asset_id: kind.asset_id,
+                quantity: kind.quantity,
+            },
+            Kind::AssetTransfer(kind) => TransactionKind::AssetTransfer {
+                asset_id: kind.asset_id,
+                quantity: kind.quantity,
+            },
+            Kind::NftMint(kind) => TransactionKind::NftMint {
+                token_id: kind.token_id,
+                metadata_hash: kind.metadata_hash,
+            },
+            Kind::NftTransfer(kind) => TransactionKind::NftTransfer { token_id: kind.token_id },
+            Kind::NftBurn(kind) => TransactionKind::NftBurn { token_id: kind.token_id },
+            Kind::NameRegister(kind) => TransactionKind::NameRegister {
+                name: kind.name,
+                target: kind.target,
+            },
+            Kind::NameRenew(kind) => TransactionKind::NameRenew { name: kind.name },
+            Kind::DidRegister(kind) => TransactionKind::DidRegister {
+                public_keys: kind.public_keys,
+                services: convert_all(kind.services)?,
+            },
+            Kind::DidUpdate(kind) => TransactionKind::DidUpdate {
+                public_keys: kind.public_keys,
+                services: convert_all(kind.services)?,
+            },
+            Kind::BallotCreate(kind) => TransactionKind::BallotCreate {
+                question: kind.question,
+                options: kind.options,
+                start_height: kind.start_height,
+                end_height: kind.end_height,
+                weighting: weighting_from_proto(kind.weighting)?,
+            },
+            Kind::BallotVote(kind) => TransactionKind::BallotVote {
+                ballot_id: kind.ballot_id,
+                option: to_usize(kind.option, "Ballot option")?,
+            },
+            Kind::AuctionCreate(kind) => TransactionKind::AuctionCreate {
+                item: kind.item,
+                reserve_price: kind.reserve_price,
+                bidding_end: kind.bidding_end,
+                reveal_end: kind.reveal_end,
+            },
+            Kind::AuctionBid(kind) => TransactionKind::AuctionBid { auction_id: kind.auction_id },
+            Kind::AuctionCommit(kind) => TransactionKind::AuctionCommit {
+                auction_id: kind.auction_id,
+                commitment: kind.commitment,
+            },
+            Kind::AuctionReveal(kind) => TransactionKind::AuctionReveal {
+                auction_id: kind.auction_id,
+                bid: kind.bid,
+                salt: kind.salt,
+            },
+            Kind::AuctionSettle(kind) => TransactionKind::AuctionSettle { auction_id: kind.auction_id },
+            Kind::PoolCreate(kind) => TransactionKind::PoolCreate {
+                asset_a: kind.asset_a,
+                asset_b: kind.asset_b,
+                amount_a: kind.amount_a,
+                amount_b: kind.amount_b,
+                fee_bps: kind.fee_bps,
+            },
+            Kind::PoolDeposit(kind) => TransactionKind::PoolDeposit {
+                pool_id: kind.pool_id,
+                amount_a: kind.amount_a,
+                amount_b: kind.amount_b,
+            },
+            Kind::PoolWithdraw(kind) => TransactionKind::PoolWithdraw {
+                pool_id: kind.pool_id,
+                shares: kind.shares,
+            },
+            Kind::PoolSwap(kind) => TransactionKind::PoolSwap {
+                pool_id: kind.pool_id,
+                asset_in: kind.asset_in,
+                amount_in: kind.amount_in,
+                min_out: kind.min_out,
+            },
+            Kind::OrderPlace(kind) => TransactionKind::OrderPlace {
+                base: kind.base,
+                quote: kind.quote,
+                side: side_from_proto(kind.side)?,
+                price: kind.price,
+                quantity: kind.quantity,
+            },
+            Kind::OrderCancel(kind) => TransactionKind::OrderCancel { order_id: kind.order_id },
+            Kind::OracleCreate(kind) => TransactionKind::OracleCreate {
+                feed: kind.feed,
+                reporters: kind.reporters,
+                quorum: to_usize(kind.quorum, "Oracle quorum")?,
+                max_age: kind.max_age,
+            },
+            Kind::OracleReport(kind) => TransactionKind::OracleReport {
+                feed: kind.feed,
+                value: kind.value,
+            },
+            Kind::BridgeLock(kind) => TransactionKind::BridgeLock {
+                chain_id: kind.chain_id,
+                asset_id: kind.asset_id,
+                ticker: kind.ticker,
+                decimals: to_u8(kind.decimals, "Decimals")?,
+                quantity: kind.quantity,
+                recipient: kind.recipient,
+            },
+            Kind::BridgeRelay(kind) => TransactionKind::BridgeRelay {
+                chain_id: kind.chain_id,
+                headers: kind.headers.into_iter().map(BlockHeader::from).collect(),
+            },
+            Kind::BridgeMint(kind) => TransactionKind::BridgeMint {
+                chain_id: kind.chain_id,
+                proof: bridge_proof_from_proto(kind.proof)?,
+            },
+            Kind::BridgeBurn(kind) => TransactionKind::BridgeBurn {
+                chain_id: kind.chain_id,
+                asset_id: kind.asset_id,
+                quantity: kind.quantity,
+                recipient: kind.recipient,
+            },
+            Kind::BridgeRelease(kind) => TransactionKind::BridgeRelease {
+                chain_id: kind.chain_id,
+                proof: bridge_proof_from_proto(kind.proof)?,
+            },
+            Kind::StealthTransfer(kind) => TransactionKind::StealthTransfer { ephemeral_key: kind.ephemeral_key },
+            #[cfg(feature = "privacy")]
+            Kind::ConfidentialTransfer(kind) => TransactionKind::ConfidentialTransfer {
+                inputs: convert_all(kind.inputs)?,
+                outputs: convert_all(kind.outputs)?,
+                withdrawal: kind.withdrawal,
+                proof: required(kind.proof, "balance proof")?.try_into()?,
+            },
+            #[cfg(feature = "privacy")]
+            Kind::RingDeposit(kind) => TransactionKind::RingDeposit { key: kind.key },
+            #[cfg(feature = "privacy")]
+            Kind::RingWithdraw(kind) => TransactionKind::RingWithdraw {
+                ring: kind.ring,
+                signature: required(kind.signature, "ring signature")?.try_into()?,
+            },
+            #[cfg(not(feature = "privacy"))]
+            Kind::ConfidentialTransfer(_) | Kind::RingDeposit(_) | Kind::RingWithdraw(_) => {
+                return Err("Privacy transactions need the privacy feature".to_string());
+            }
+        })
+    }
+}
+
+#[cfg(feature = "privacy")]
+fn point_to_bytes(point: &RistrettoPoint) -> Vec<u8> {
+    point.compress().to_bytes().to_vec()
+}
+
+#[cfg(feature = "privacy")]
+fn point_from_bytes(bytes: &[u8]) -> Result<RistrettoPoint, String> {
+    point_from_hex(&hex::encode(bytes))
+}
+
+#[cfg(feature = "privacy")]
+fn scalar_from_bytes(bytes: &[u8]) -> Result<Scalar, String> {
+    scalar_from_hex(&hex::encode(bytes))
+}
+
+#[cfg(feature = "privacy")]
+fn scalar_pair(scalars: &[Vec<u8>], field: &str) -> Result<[Scalar; 2], String> {
+    match scalars {
+        [a, b] => Ok([scalar_from_bytes(a)?, scalar_from_bytes(b)?]),
+        _ => Err(format!("Expected two {}", field)),
+    }
+}
+
+#[cfg(feature = "privacy")]
+impl From<&ConfidentialOutput> for pb::ConfidentialOutput {
+    fn from(output: &ConfidentialOutput) -> Self {
+        pb::ConfidentialOutput {
+            owner: output.owner.clone(),
+            commitment: point_to_bytes(&output.commitment),
+            range_proof: Some(pb::RangeProof {
+                bits: output.range_proof.bits.iter()
+                    .map(|bit| pb::BitProof {
+                        commitment: point_to_bytes(&bit.commitment),
+                        challenges: bit.challenges.iter().map(|scalar| scalar.to_bytes().to_vec()).collect(),
+                        responses: bit.responses.iter().map(|scalar| scalar.to_bytes().to_vec()).collect(),
+                    })
+                    .collect(),
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "privacy")]
+impl TryFrom<pb::ConfidentialOutput> for ConfidentialOutput {
+    type Error = String;
+
+    fn try_from(output: pb::ConfidentialOutput) -> Result<Self, String> {
+        let bits = required(output.range_proof, "range proof")?.bits.into_iter()
+            .map(|bit| {
+                Ok(BitProof {
+                    commitment: point_from_bytes(&bit.commitment)?,
+                    challenges: scalar_pair(&bit.challenges, "challenges")?,
+                    responses: scalar_pair(&bit.responses, "responses")?,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(ConfidentialOutput {
+            owner: output.owner,
+            commitment: point_from_bytes(&output.commitment)?,
+            range_proof: RangeProof { bits: bits },
+        })
+    }
+}
+
+#[cfg(feature = "privacy")]
+impl From<&BalanceProof> for pb::BalanceProof {
+    fn from(proof: &BalanceProof) -> Self {
+        pb::BalanceProof {
+            nonce: point_to_bytes(&proof.nonce),
+            response: proof.response.to_bytes().to_vec(),
+        }
+    }
+}
+
+#[cfg(feature = "privacy")]
+impl TryFrom<pb::BalanceProof> for BalanceProof {
+    type Error = String;
+
+    fn try_from(proof: pb::BalanceProof) -> Result<Self, String> {
+        Ok(BalanceProof {
+            nonce: point_from_bytes(&proof.nonce)?,
+            response: scalar_from_bytes(&proof.response)?,
+        })
+    }
+}
+
+#[cfg(feature = "privacy")]
+impl From<&RingSignature> for pb::RingSignature {
+    fn from(signature: &RingSignature) -> Self {
+        pb::RingSignature {
+            key_image: point_to_bytes(&signature.key_image),
+            challenge: signature.challenge.to_bytes().to_vec(),
+            responses: signature.responses.iter().map(|scalar| scalar.to_bytes().to_vec()).collect(),
+        }
+    }
+}
+
+#[cfg(feature = "privacy")]
+impl TryFrom<pb::RingSignature> for RingSignature {
+    type Error = String;
+
+    fn try_from(signature: pb::RingSignature) -> Result<Self, String> {
+        Ok(RingSignature {
+            key_image: point_from_bytes(&signature.key_image)?,
+            challenge: scalar_from_bytes(&signature.challenge)?,
+            responses: signature.responses.iter().map(|bytes| scalar_from_bytes(bytes)).collect::<Result<_, _>>()?,
+        })
+    }
+}
@@ -0,0 +1,253 @@
+/// Scalar types of protobuf, written the same way in a `.proto` file and in
+/// prost attributes.
+const SCALARS: [&str; 15] = [
+    "double", "float", "int32", "int64", "uint32", "uint64", "sint32", "sint64",
+    "fixed32", "fixed64", "sfixed32", "sfixed64", "bool", "string", "bytes",
+];
+
+/// A field of a message (or a value of an enum) as declared by a schema.
+///
+/// # Fields
+///
+/// * `message` - Name of the message or enum, without its package or parents
+/// * `name` - Field name in snake case (enum values without their prefix)
+/// * `kind` - Type, prefixed with `repeated` or `optional` (scalars only);
+///   messages and enums by name, `value` for enum values
+/// * `number` - Field number (or enum value)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SchemaField {
+    pub message: String,
+    pub number: u32,
+    pub name: String,
+    pub kind: String,
+}
+
+/// Scope a declaration is read in.
+enum Scope {
+    Message(String),
+    Oneof,
+    Enum(String),
+    Other,
+}
+
+/// Returns the fields declared by a `.proto` file (proto3), sorted by message
+/// and number. Fields of a `oneof` belong to the message around it.
+///
+/// # Example
+///
+/// ```
+/// use blockc::proto::schema::proto_fields;
+///
+/// let fields = proto_fields("message Pay { string to = 1; oneof memo { bytes raw = 2; } }").unwrap();
+/// assert_eq!(fields.len(), 2);
+/// assert_eq!((fields[1].message.as_str(), fields[1].name.as_str(), fields[1].kind.as_str()), ("Pay", "raw", "bytes"));
+/// ```
+pub fn proto_fields(source: &str) -> Result<Vec<SchemaField>, String> {
+    let source: String = source.lines()
+        .map(|line| line.split("//").next().unwrap_or(""))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut fields = Vec::new();
+    let mut scopes: Vec<Scope> = Vec::new();
+    let mut statement = String::new();
+    for c in source.chars() {
+        match c {
+            '{' => {
+                let words: Vec<&str> = statement.split_whitespace().collect();
+                scopes.push(match words.as_slice() {
+                    ["message", name] => Scope::Message(name.to_string()),
+                    ["oneof", _] => Scope::Oneof,
+                    ["enum", name] => Scope::Enum(name.to_string()),
+                    _ => Scope::Other,
+                });
+                statement.clear();
+            }
+            '}' => {
+                scopes.pop().ok_or("Unbalanced \"}\"".to_string())?;
+                statement.clear();
+            }
+            ';' => {
+                if let Some(field) = proto_field(&scopes, &statement)? {
+                    fields.push(field);
+                }
+                statement.clear();
+            }
+            c => statement.push(c),
+        }
+    }
+    fields.sort();
+    Ok(fields)
+}
+
+/// Reads one `;`-terminated statement of a `.proto` file.
+fn proto_field(scopes: &[Scope], statement: &str) -> Result<Option<SchemaField>, String> {
+    let Some((declaration, number)) = statement.split_once('=') else {
+        return Ok(None);
+    };
+    let words: Vec<&str> = declaration.split_whitespace().collect();
+    if matches!(words.first(), Some(&"syntax") | Some(&"option") | Some(&"package") | Some(&"import") | Some(&"reserved")) {
+        return Ok(None);
+    }
+    let number = number.split('[').next().unwrap_or("").trim();
+    let number: u32 = number.parse().map_err(|_| format!("Invalid field number in \"{}\"", statement.trim()))?;
+
+    if let Some(Scope::Enum(name)) = scopes.last() {
+        let prefix = format!("{}_", screaming_snake_case(name));
+        let value = words.first().ok_or(format!("Missing enum value in \"{}\"", statement.trim()))?;
+        return Ok(Some(SchemaField {
+            message: name.clone(),
+            number: number,
+            name: value.strip_prefix(&prefix).unwrap_or(value).to_lowercase(),
+            kind: "value".to_string(),
+        }));
+    }
+    let message = scopes.iter().rev()
+        .find_map(|scope| match scope {
+            Scope::Message(name) => Some(name.clone()),
+            _ => None,
+        })
+        .ok_or(format!("Field outside a message: \"{}\"", statement.trim()))?;
+    let (label, kind, name) = match words.as_slice() {
+        [label @ ("repeated" | "optional"), kind, name] => (*label, *kind, *name),
+        [kind, name] => ("", *kind, *name),
+        _ => return Err(format!("Cannot read field \"{}\"", statement.trim())),
+    };
+    Ok(Some(SchemaField {
+        message: message,
+        number: number,
+        name: name.to_string(),
+        kind: field_kind(label, kind.rsplit('.').next().unwrap_or(kind)),
+    }))
+}
+
+/// Returns the fields declared by prost types (Rust source in the form
+/// prost-build generates), sorted by message and number, in the form of
+/// `proto_fields`.
+///
+/// `proto::types` is written by hand: comparing both keeps it in sync with
+/// `proto/blockc.proto`.
+///
+/// # Example
+///
+/// ```
+/// use blockc::proto::schema::{prost_fields, proto_fields};
+///
+/// let proto = proto_fields(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/proto/blockc.proto"))).unwrap();
+/// let types = prost_fields(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/proto/types.rs"))).unwrap();
+/// assert!(proto.iter().any(|field| field.message == "Block" && field.name == "transactions" && field.kind == "repeated Transaction"));
+///
+/// let missing: Vec<_> = proto.iter().filter(|field| !types.contains(field)).collect();
+/// let extra: Vec<_> = types.iter().filter(|field| !proto.contains(field)).collect();
+/// assert!(missing.is_empty() && extra.is_empty(), "missing from types.rs: {:?}\nnot in blockc.proto: {:?}", missing, extra);
+/// ```
+pub fn prost_fields(source: &str) -> Result<Vec<SchemaField>, String> {
+    let mut fields = Vec::new();
+    let mut scopes: Vec<Scope> = Vec::new();
+    let mut derive = String::new();
+    let mut attribute: Option<String> = None;
+    for line in source.lines().map(str::trim) {
+        if line.starts_with("#[derive(") {
+            derive = line.to_string();
+        } else if let Some(prost) = line.strip_prefix("#[prost(").and_then(|rest| rest.strip_suffix(")]")) {
+            attribute = Some(prost.to_string());
+        } else if line.starts_with('}') {
+            scopes.pop().ok_or("Unbalanced \"}\"".to_string())?;
+        } else if line.ends_with('{') {
+            let words: Vec<&str> = line.trim_end_matches('{').split_whitespace().collect();
+            scopes.push(match words.as_slice() {
+                ["pub", "mod", name] => Scope::Message(upper_camel_case(name)),
+                ["pub", "struct", name] => Scope::Message(name.to_string()),
+                ["pub", "enum", name] if derive.contains("::prost::Enumeration") => Scope::Enum(name.to_string()),
+                ["pub", "enum", _] => Scope::Oneof,
+                _ => Scope::Other,
+            });
+        } else if let Some(prost) = attribute.take() {
+            fields.extend(prost_field(&scopes, &prost, line)?);
+        } else if let (Some(Scope::Enum(name)), Some((value, number))) = (scopes.last(), line.trim_end_matches(',').split_once(" = ")) {
+            fields.push(SchemaField {
+                message: name.clone(),
+                number: number.parse().map_err(|_| format!("Invalid enum value in \"{}\"", line))?,
+                name: snake_case(value),
+                kind: "value".to_string(),
+            });
+        }
+    }
+    fields.sort();
+    Ok(fields)
+}
+
+/// Reads a field (or `oneof` variant) declared by `line` with the `prost`
+/// attribute `prost`.
+fn prost_field(scopes: &[Scope], prost: &str, line: &str) -> Result<Option<SchemaField>, String> {
+    let items: Vec<&str> = prost.split(", ").collect();
+    let kind = items.first().copied().unwrap_or("");
+    if kind.starts_with("oneof") {
+        return Ok(None);
+    }
+    let number = items.iter()
+        .find_map(|item| item.strip_prefix("tag = \"").and_then(|tag| tag.strip_suffix('"')))
+        .ok_or(format!("Missing tag in #[prost({})]", prost))?;
+    let label = if items.contains(&"repeated") { "repeated" } else if items.contains(&"optional") { "optional" } else { "" };
+
+    let (name, rust_type) = match (scopes.last(), line.split_once(':'), line.split_once('(')) {
+        (Some(Scope::Oneof), _, Some((variant, rust_type))) => (snake_case(variant.trim()), rust_type),
+        (_, Some((field, rust_type)), _) => (field.trim_start_matches("pub ").trim().to_string(), rust_type),
+        _ => return Err(format!("Cannot read field \"{}\"", line)),
+    };
+    let type_name = match kind.split_once(" = ") {
+        Some(("enumeration", name)) => name.trim_matches('"').rsplit("::").next().unwrap_or(name).to_string(),
+        Some((scalar, _)) => scalar.to_string(),
+        None if kind == "message" => rust_type.trim_end_matches([',', ')', '>', ' '])
+            .rsplit(['<', ':'])
+            .next()
+            .unwrap_or("")
+            .to_string(),
+        None => kind.to_string(),
+    };
+    let message = scopes.iter().rev()
+        .find_map(|scope| match scope {
+            Scope::Message(name) => Some(name.clone()),
+            _ => None,
+        })
+        .ok_or(format!("Field outside a message: \"{}\"", line))?;
+    Ok(Some(SchemaField {
+        message: message,
+        number: number.parse().map_err(|_| format!("Invalid tag in #[prost({})]", prost))?,
+        name: name,
+        kind: field_kind(label, &type_name),
+    }))
+}
+
+/// Writes a field type with its label; only scalars keep `optional` (message
+/// fields always are).
+fn field_kind(label: &str, type_name: &str) -> String {
+    match label {
+        "repeated" => format!("repeated {}", type_name),
+        "optional" if SCALARS.contains(&type_name) => format!("optional {}", type_name),
+        _ => type_name.to_string(),
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (index, c) in name.chars().enumerate() {
+        if c.is_uppercase() && index > 0 {
+            snake.push('_');
+        }
+        snake.push(c.to_ascii_lowercase());
+    }
+    snake
+}
+
+fn screaming_snake_case(name: &str) -> String {
+    snake_case(name).to_uppercase()
+}
+
+fn upper_camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map(|first| first.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+        })
+        .collect()
+}
@@ -0,0 +1,862 @@
+// Rust types for `proto/blockc.proto` (package `blockc.v1`), in the form
+// prost-build generates them. Keep both files in sync: the example of
+// `proto::schema::prost_fields` fails if a field number or type differs.
+// `proto::convert` turns them into the native structs and back.
+
+/// What nodes gossip to each other.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Message {
+    #[prost(oneof = "message::Payload", tags = "1, 2")]
+    pub payload: ::core::option::Option<message::Payload>,
+}
+/// Nested message and enum types in `Message`.
+pub mod message {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Payload {
+        #[prost(message, tag = "1")]
+        Block(super::Block),
+        #[prost(message, tag = "2")]
+        Transaction(super::Transaction),
+    }
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Block {
+    #[prost(string, tag = "1")]
+    pub block_hash: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub previous_block_hash: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "3")]
+    pub transactions: ::prost::alloc::vec::Vec<Transaction>,
+    #[prost(uint32, tag = "4")]
+    pub time_stamp: u32,
+    #[prost(uint32, tag = "5")]
+    pub nonce: u32,
+    #[prost(double, tag = "6")]
+    pub base_fee: f64,
+    #[prost(string, tag = "7")]
+    pub merkle_root: ::prost::alloc::string::String,
+    #[prost(string, tag = "8")]
+    pub state_root: ::prost::alloc::string::String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BlockHeader {
+    #[prost(string, tag = "1")]
+    pub block_hash: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub previous_block_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "3")]
+    pub time_stamp: u32,
+    #[prost(uint32, tag = "4")]
+    pub nonce: u32,
+    #[prost(double, tag = "5")]
+    pub base_fee: f64,
+    #[prost(string, tag = "6")]
+    pub merkle_root: ::prost::alloc::string::String,
+    #[prost(string, tag = "7")]
+    pub state_root: ::prost::alloc::string::String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Transaction {
+    #[prost(string, tag = "1")]
+    pub sender_address: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub receiver_address: ::prost::alloc::string::String,
+    #[prost(double, tag = "3")]
+    pub amount: f64,
+    #[prost(double, tag = "4")]
+    pub fee: f64,
+    #[prost(uint64, tag = "5")]
+    pub nonce: u64,
+    #[prost(uint32, tag = "6")]
+    pub timestamp: u32,
+    #[prost(string, tag = "7")]
+    pub signature: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "8")]
+    pub memo: ::core::option::Option<::prost::alloc::string::String>,
+    /// Required
+    #[prost(message, optional, tag = "9")]
+    pub kind: ::core::option::Option<TransactionKind>,
+    /// Absent when the transaction has no lock time
+    #[prost(message, optional, tag = "10")]
+    pub lock_time: ::core::option::Option<LockTime>,
+    #[prost(uint64, tag = "11")]
+    pub gas_limit: u64,
+    #[prost(double, tag = "12")]
+    pub gas_price: f64,
+    #[prost(string, optional, tag = "13")]
+    pub script: ::core::option::Option<::prost::alloc::string::String>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LockTime {
+    #[prost(oneof = "lock_time::Lock", tags = "1, 2, 3, 4")]
+    pub lock: ::core::option::Option<lock_time::Lock>,
+}
+/// Nested message and enum types in `LockTime`.
+pub mod lock_time {
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct RelativeHeight {
+        #[prost(string, tag = "1")]
+        pub transaction_id: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "2")]
+        pub blocks: u64,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct RelativeTime {
+        #[prost(string, tag = "1")]
+        pub transaction_id: ::prost::alloc::string::String,
+        #[prost(uint32, tag = "2")]
+        pub seconds: u32,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Lock {
+        #[prost(uint64, tag = "1")]
+        Height(u64),
+        #[prost(uint32, tag = "2")]
+        Timestamp(u32),
+        #[prost(message, tag = "3")]
+        RelativeHeight(RelativeHeight),
+        #[prost(message, tag = "4")]
+        RelativeTime(RelativeTime),
+    }
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Output {
+    #[prost(string, tag = "1")]
+    pub address: ::prost::alloc::string::String,
+    #[prost(double, tag = "2")]
+    pub amount: f64,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OutPoint {
+    #[prost(string, tag = "1")]
+    pub transaction_id: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub index: u64,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct JoinInput {
+    #[prost(message, optional, tag = "1")]
+    pub outpoint: ::core::option::Option<OutPoint>,
+    #[prost(string, tag = "2")]
+    pub signature: ::prost::alloc::string::String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChannelState {
+    #[prost(string, tag = "1")]
+    pub channel_id: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub sequence: u64,
+    #[prost(double, tag = "3")]
+    pub balance_a: f64,
+    #[prost(double, tag = "4")]
+    pub balance_b: f64,
+    #[prost(string, tag = "5")]
+    pub signature_a: ::prost::alloc::string::String,
+    #[prost(string, tag = "6")]
+    pub signature_b: ::prost::alloc::string::String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Service {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub kind: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub endpoint: ::prost::alloc::string::String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProofStep {
+    #[prost(string, tag = "1")]
+    pub hash: ::prost::alloc::string::String,
+    #[prost(bool, tag = "2")]
+    pub is_left: bool,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MerkleProof {
+    #[prost(string, tag = "1")]
+    pub leaf: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "2")]
+    pub steps: ::prost::alloc::vec::Vec<ProofStep>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BridgeProof {
+    #[prost(string, tag = "1")]
+    pub block_hash: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "2")]
+    pub transaction: ::core::option::Option<Transaction>,
+    #[prost(message, optional, tag = "3")]
+    pub proof: ::core::option::Option<MerkleProof>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum EscrowOutcome {
+    Unspecified = 0,
+    Release = 1,
+    Refund = 2,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum VoteWeighting {
+    Unspecified = 0,
+    OnePerAddress = 1,
+    Balance = 2,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum Side {
+    Unspecified = 0,
+    Buy = 1,
+    Sell = 2,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VestingSchedule {
+    #[prost(oneof = "vesting_schedule::Schedule", tags = "1, 2")]
+    pub schedule: ::core::option::Option<vesting_schedule::Schedule>,
+}
+/// Nested message and enum types in `VestingSchedule`.
+pub mod vesting_schedule {
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Linear {
+        #[prost(uint64, tag = "1")]
+        pub start_height: u64,
+        #[prost(uint64, tag = "2")]
+        pub end_height: u64,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Unlock {
+        #[prost(uint64, tag = "1")]
+        pub height: u64,
+        #[prost(double, tag = "2")]
+        pub amount: f64,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Cliffs {
+        #[prost(message, repeated, tag = "1")]
+        pub unlocks: ::prost::alloc::vec::Vec<Unlock>,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Schedule {
+        #[prost(message, tag = "1")]
+        Linear(Linear),
+        #[prost(message, tag = "2")]
+        Cliffs(Cliffs),
+    }
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConfidentialOutput {
+    #[prost(string, tag = "1")]
+    pub owner: ::prost::alloc::string::String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub commitment: ::prost::alloc::vec::Vec<u8>,
+    #[prost(message, optional, tag = "3")]
+    pub range_proof: ::core::option::Option<RangeProof>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RangeProof {
+    #[prost(message, repeated, tag = "1")]
+    pub bits: ::prost::alloc::vec::Vec<BitProof>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BitProof {
+    #[prost(bytes = "vec", tag = "1")]
+    pub commitment: ::prost::alloc::vec::Vec<u8>,
+    /// Two each
+    #[prost(bytes = "vec", repeated, tag = "2")]
+    pub challenges: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    #[prost(bytes = "vec", repeated, tag = "3")]
+    pub responses: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BalanceProof {
+    #[prost(bytes = "vec", tag = "1")]
+    pub nonce: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub response: ::prost::alloc::vec::Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RingSignature {
+    #[prost(bytes = "vec", tag = "1")]
+    pub key_image: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub challenge: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", repeated, tag = "3")]
+    pub responses: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
+
+/// What a transaction does. Tags are stable: new kinds take the next free one.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TransactionKind {
+    #[prost(oneof = "transaction_kind::Kind", tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50, 51, 52, 53")]
+    pub kind: ::core::option::Option<transaction_kind::Kind>,
+}
+/// Nested message and enum types in `TransactionKind`.
+pub mod transaction_kind {
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Transfer {
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Anchor {
+        #[prost(string, tag = "1")]
+        pub doc_hash: ::prost::alloc::string::String,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct MultiPay {
+        #[prost(message, repeated, tag = "1")]
+        pub outputs: ::prost::alloc::vec::Vec<super::Output>,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct HtlcLock {
+        #[prost(string, tag = "1")]
+        pub hash_lock: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "2")]
+        pub expiry_height: u64,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct HtlcClaim {
+        #[prost(string, tag = "1")]
+        pub htlc_id: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub preimage: ::prost::alloc::string::String,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct HtlcRefund {
+        #[prost(string, tag = "1")]
+        pub htlc_id: ::prost::alloc::string::String,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ChannelOpen {
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ChannelClose {
+        #[prost(message, optional, tag = "1")]
+        pub state: ::core::option::Option<super::ChannelState>,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ChannelSettle {
+        #[prost(string, tag = "1")]
+        pub channel_id: ::prost::alloc::string::String,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct EscrowOpen {
+        #[prost(string, tag = "1")]
+        pub arbiter: ::prost::alloc::string::String,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct EscrowVote {
+        #[prost(string, tag = "1")]
+        pub escrow_id: ::prost::alloc::string::String,
+        #[prost(enumeration = "super::EscrowOutcome", tag = "2")]
+        pub outcome: i32,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct VestingGrant {
+        #[prost(message, optional, tag = "1")]
+        pub schedule: ::core::option::Option<super::VestingSchedule>,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct StreamOpen {
+        #[prost(double, tag = "1")]
+        pub rate_per_block: f64,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct StreamWithdraw {
+        #[prost(string, tag = "1")]
+        pub stream_id: ::prost::alloc::string::String,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct StreamCancel {
+        #[prost(string, tag = "1")]
+        pub stream_id: ::prost::alloc::string::String,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Utxo {
+        #[prost(message, repeated, tag = "1")]
+        pub inputs: ::prost::alloc::vec::Vec<super::OutPoint>,
+        #[prost(message, repeated, tag = "2")]
+        pub outputs: ::prost::alloc::vec::Vec<super::Output>,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct CoinJoin {
+        #[prost(message, repeated, tag = "1")]
+        pub inputs: ::prost::alloc::vec::Vec<super::JoinInput>,
+        #[prost(message, repeated, tag = "2")]
+        pub outputs: ::prost::alloc::vec::Vec<super::Output>,
+    }
+
+    /// Instructions in their text form (e.g. "PUSH 5")
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Deploy {
+        #[prost(string, repeated, tag = "1")]
+        pub code: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Call {
+        #[prost(string, tag = "1")]
+        pub contract: ::prost::alloc::string::String,
+        #[prost(sint64, repeated, tag = "2")]
+        pub input: ::prost::alloc::vec::Vec<i64>,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AssetIssue {
+        #[prost(string, tag = "1")]
+        pub ticker: ::prost::alloc::string::String,
+        #[prost(uint32, tag = "2")]
+        pub decimals: u32,
+        #[prost(uint64, tag = "3")]
+        pub max_supply: u64,
+        #[prost(uint64, tag = "4")]
+        pub initial_supply: u64,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AssetMint {
+        #[prost(string, tag = "1")]
+        pub asset_id: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "2")]
+        pub quantity: u64,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AssetTransfer {
+        #[prost(string, tag = "1")]
+        pub asset_id: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "2")]
+        pub quantity: u64,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct NftMint {
+        #[prost(string, tag = "1")]
+        pub token_id: ::prost::alloc::string::String,
+        #[prost(string, optional, tag = "2")]
+        pub metadata_hash: ::core::option::Option<::prost::alloc::string::String>,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct NftTransfer {
+        #[prost(string, tag = "1")]
+        pub token_id: ::prost::alloc::string::String,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct NftBurn {
+        #[prost(string, tag = "1")]
+        pub token_id: ::prost::alloc::string::String,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct NameRegister {
+        #[prost(string, tag = "1")]
+        pub name: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub target: ::prost::alloc::string::String,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct NameRenew {
+        #[prost(string, tag = "1")]
+        pub name: ::prost::alloc::string::String,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct DidRegister {
+        #[prost(string, repeated, tag = "1")]
+        pub public_keys: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+        #[prost(message, repeated, tag = "2")]
+        pub services: ::prost::alloc::vec::Vec<super::Service>,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct DidUpdate {
+        #[prost(string, repeated, tag = "1")]
+        pub public_keys: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+        #[prost(message, repeated, tag = "2")]
+        pub services: ::prost::alloc::vec::Vec<super::Service>,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct BallotCreate {
+        #[prost(string, tag = "1")]
+        pub question: ::prost::alloc::string::String,
+        #[prost(string, repeated, tag = "2")]
+        pub options: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+        #[prost(uint64, tag = "3")]
+        pub start_height: u64,
+        #[prost(uint64, tag = "4")]
+        pub end_height: u64,
+        #[prost(enumeration = "super::VoteWeighting", tag = "5")]
+        pub weighting: i32,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct BallotVote {
+        #[prost(string, tag = "1")]
+        pub ballot_id: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "2")]
+        pub option: u64,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AuctionCreate {
+        #[prost(string, tag = "1")]
+        pub item: ::prost::alloc::string::String,
+        #[prost(double, tag = "2")]
+        pub reserve_price: f64,
+        #[prost(uint64, tag = "3")]
+        pub bidding_end: u64,
+        #[prost(uint64, optional, tag = "4")]
+        pub reveal_end: ::core::option::Option<u64>,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AuctionBid {
+        #[prost(string, tag = "1")]
+        pub auction_id: ::prost::alloc::string::String,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AuctionCommit {
+        #[prost(string, tag = "1")]
+        pub auction_id: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub commitment: ::prost::alloc::string::String,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AuctionReveal {
+        #[prost(string, tag = "1")]
+        pub auction_id: ::prost::alloc::string::String,
+        #[prost(double, tag = "2")]
+        pub bid: f64,
+        #[prost(string, tag = "3")]
+        pub salt: ::prost::alloc::string::String,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AuctionSettle {
+        #[prost(string, tag = "1")]
+        pub auction_id: ::prost::alloc::string::String,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct PoolCreate {
+        #[prost(string, tag = "1")]
+        pub asset_a: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub asset_b: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "3")]
+        pub amount_a: u64,
+        #[prost(uint64, tag = "4")]
+        pub amount_b: u64,
+        #[prost(uint32, tag = "5")]
+        pub fee_bps: u32,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct PoolDeposit {
+        #[prost(string, tag = "1")]
+        pub pool_id: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "2")]
+        pub amount_a: u64,
+        #[prost(uint64, tag = "3")]
+        pub amount_b: u64,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct PoolWithdraw {
+        #[prost(string, tag = "1")]
+        pub pool_id: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "2")]
+        pub shares: u64,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct PoolSwap {
+        #[prost(string, tag = "1")]
+        pub pool_id: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub asset_in: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "3")]
+        pub amount_in: u64,
+        #[prost(uint64, tag = "4")]
+        pub min_out: u64,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct OrderPlace {
+        #[prost(string, tag = "1")]
+        pub base: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub quote: ::prost::alloc::string::String,
+        #[prost(enumeration = "super::Side", tag = "3")]
+        pub side: i32,
+        #[prost(uint64, tag = "4")]
+        pub price: u64,
+        #[prost(uint64, tag = "5")]
+        pub quantity: u64,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct OrderCancel {
+        #[prost(string, tag = "1")]
+        pub order_id: ::prost::alloc::string::String,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct OracleCreate {
+        #[prost(string, tag = "1")]
+        pub feed: ::prost::alloc::string::String,
+        #[prost(string, repeated, tag = "2")]
+        pub reporters: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+        #[prost(uint64, tag = "3")]
+        pub quorum: u64,
+        #[prost(uint64, tag = "4")]
+        pub max_age: u64,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct OracleReport {
+        #[prost(string, tag = "1")]
+        pub feed: ::prost::alloc::string::String,
+        #[prost(double, tag = "2")]
+        pub value: f64,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct BridgeLock {
+        #[prost(string, tag = "1")]
+        pub chain_id: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub asset_id: ::prost::alloc::string::String,
+        #[prost(string, tag = "3")]
+        pub ticker: ::prost::alloc::string::String,
+        #[prost(uint32, tag = "4")]
+        pub decimals: u32,
+        #[prost(uint64, tag = "5")]
+        pub quantity: u64,
+        #[prost(string, tag = "6")]
+        pub recipient: ::prost::alloc::string::String,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct BridgeRelay {
+        #[prost(string, tag = "1")]
+        pub chain_id: ::prost::alloc::string::String,
+        #[prost(message, repeated, tag = "2")]
+        pub headers: ::prost::alloc::vec::Vec<super::BlockHeader>,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct BridgeMint {
+        #[prost(string, tag = "1")]
+        pub chain_id: ::prost::alloc::string::String,
+        #[prost(message, optional, boxed, tag = "2")]
+        pub proof: ::core::option::Option<::prost::alloc::boxed::Box<super::BridgeProof>>,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct BridgeBurn {
+        #[prost(string, tag = "1")]
+        pub chain_id: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub asset_id: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "3")]
+        pub quantity: u64,
+        #[prost(string, tag = "4")]
+        pub recipient: ::prost::alloc::string::String,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct BridgeRelease {
+        #[prost(string, tag = "1")]
+        pub chain_id: ::prost::alloc::string::String,
+        #[prost(message, optional, boxed, tag = "2")]
+        pub proof: ::core::option::Option<::prost::alloc::boxed::Box<super::BridgeProof>>,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct StealthTransfer {
+        #[prost(string, tag = "1")]
+        pub ephemeral_key: ::prost::alloc::string::String,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ConfidentialTransfer {
+        #[prost(message, repeated, tag = "1")]
+        pub inputs: ::prost::alloc::vec::Vec<super::OutPoint>,
+        #[prost(message, repeated, tag = "2")]
+        pub outputs: ::prost::alloc::vec::Vec<super::ConfidentialOutput>,
+        #[prost(double, tag = "3")]
+        pub withdrawal: f64,
+        #[prost(message, optional, tag = "4")]
+        pub proof: ::core::option::Option<super::BalanceProof>,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct RingDeposit {
+        #[prost(string, tag = "1")]
+        pub key: ::prost::alloc::string::String,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct RingWithdraw {
+        #[prost(string, repeated, tag = "1")]
+        pub ring: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+        #[prost(message, optional, tag = "2")]
+        pub signature: ::core::option::Option<super::RingSignature>,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Kind {
+        #[prost(message, tag = "1")]
+        Transfer(Transfer),
+        #[prost(message, tag = "2")]
+        Anchor(Anchor),
+        #[prost(message, tag = "3")]
+        MultiPay(MultiPay),
+        #[prost(message, tag = "4")]
+        HtlcLock(HtlcLock),
+        #[prost(message, tag = "5")]
+        HtlcClaim(HtlcClaim),
+        #[prost(message, tag = "6")]
+        HtlcRefund(HtlcRefund),
+        #[prost(message, tag = "7")]
+        ChannelOpen(ChannelOpen),
+        #[prost(message, tag = "8")]
+        ChannelClose(ChannelClose),
+        #[prost(message, tag = "9")]
+        ChannelSettle(ChannelSettle),
+        #[prost(message, tag = "10")]
+        EscrowOpen(EscrowOpen),
+        #[prost(message, tag = "11")]
+        EscrowVote(EscrowVote),
+        #[prost(message, tag = "12")]
+        VestingGrant(VestingGrant),
+        #[prost(message, tag = "13")]
+        StreamOpen(StreamOpen),
+        #[prost(message, tag = "14")]
+        StreamWithdraw(StreamWithdraw),
+        #[prost(message, tag = "15")]
+        StreamCancel(StreamCancel),
+        #[prost(message, tag = "16")]
+        Utxo(Utxo),
+        #[prost(message, tag = "17")]
+        CoinJoin(CoinJoin),
+        #[prost(message, tag = "18")]
+        Deploy(Deploy),
+        #[prost(message, tag = "19")]
+        Call(Call),
+        #[prost(message, tag = "20")]
+        AssetIssue(AssetIssue),
+        #[prost(message, tag = "21")]
+        AssetMint(AssetMint),
+        #[prost(message, tag = "22")]
+        AssetTransfer(AssetTransfer),
+        #[prost(message, tag = "23")]
+        NftMint(NftMint),
+        #[prost(message, tag = "24")]
+        NftTransfer(NftTransfer),
+        #[prost(message, tag = "25")]
+        NftBurn(NftBurn),
+        #[prost(message, tag = "26")]
+        NameRegister(NameRegister),
+        #[prost(message, tag = "27")]
+        NameRenew(NameRenew),
+        #[prost(message, tag = "28")]
+        DidRegister(DidRegister),
+        #[prost(message, tag = "29")]
+        DidUpdate(DidUpdate),
+        #[prost(message, tag = "30")]
+        BallotCreate(BallotCreate),
+        #[prost(message, tag = "31")]
+        BallotVote(BallotVote),
+        #[prost(message, tag = "32")]
+        AuctionCreate(AuctionCreate),
+        #[prost(message, tag = "33")]
+        AuctionBid(AuctionBid),
+        #[prost(message, tag = "34")]
+        AuctionCommit(AuctionCommit),
+        #[prost(message, tag = "35")]
+        AuctionReveal(AuctionReveal),
+        #[prost(message, tag = "36")]
+        AuctionSettle(AuctionSettle),
+        #[prost(message, tag = "37")]
+        PoolCreate(PoolCreate),
+        #[prost(message, tag = "38")]
+        PoolDeposit(PoolDeposit),
+        #[prost(message, tag = "39")]
+        PoolWithdraw(PoolWithdraw),
+        #[prost(message, tag = "40")]
+        PoolSwap(PoolSwap),
+        #[prost(message, tag = "41")]
+        OrderPlace(OrderPlace),
+        #[prost(message, tag = "42")]
+        OrderCancel(OrderCancel),
+        #[prost(message, tag = "43")]
+        OracleCreate(OracleCreate),
+        #[prost(message, tag = "44")]
+        OracleReport(OracleReport),
+        #[prost(message, tag = "45")]
+        BridgeLock(BridgeLock),
+        #[prost(message, tag = "46")]
+        BridgeRelay(BridgeRelay),
+        #[prost(message, tag = "47")]
+        BridgeMint(BridgeMint),
+        #[prost(message, tag = "48")]
+        BridgeBurn(BridgeBurn),
+        #[prost(message, tag = "49")]
+        BridgeRelease(BridgeRelease),
+        #[prost(message, tag = "50")]
+        StealthTransfer(StealthTransfer),
+        #[prost(message, tag = "51")]
+        ConfidentialTransfer(ConfidentialTransfer),
+        #[prost(message, tag = "52")]
+        RingDeposit(RingDeposit),
+        #[prost(message, tag = "53")]
+        RingWithdraw(RingWithdraw),
+    }
+}
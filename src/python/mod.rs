@@ -0,0 +1 @@
+pub mod python;
\ No newline at end of file
@@ -0,0 +1,426 @@
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyIndexError};
+use pyo3::prelude::*;
+use crate::block::block::Block;
+use crate::blockchain::blockchain::{Blockchain, DEFAULT_BLOCK_REWARD};
+use crate::entity::entity::Entity;
+use crate::helpers::random::entropy;
+use crate::simulation::rng::SimRng;
+use crate::transactions::transactions::{Transaction, TransactionExt};
+use crate::wallet::wallet::Wallet;
+
+create_exception!(blockc, BlockcError, PyException, "Raised when the chain, a wallet or the miner rejects an operation.");
+
+fn to_py_err(error: String) -> PyErr {
+    BlockcError::new_err(error)
+}
+
+/// A signed transaction, as seen from Python.
+#[pyclass(name = "Transaction", module = "blockc", frozen)]
+pub struct PyTransaction {
+    pub inner: Transaction,
+}
+
+#[pymethods]
+impl PyTransaction {
+    /// Parses a transaction from its JSON form.
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        Transaction::from_json(json).map(|inner| PyTransaction { inner: inner }).map_err(to_py_err)
+    }
+
+    fn to_json(&self) -> String {
+        self.inner.to_json()
+    }
+
+    #[getter]
+    fn id(&self) -> String {
+        self.inner.id()
+    }
+
+    #[getter]
+    fn sender(&self) -> String {
+        self.inner.sender_address.clone()
+    }
+
+    #[getter]
+    fn receiver(&self) -> String {
+        self.inner.receiver_address.clone()
+    }
+
+    #[getter]
+    fn amount(&self) -> f64 {
+        self.inner.amount
+    }
+
+    #[getter]
+    fn fee(&self) -> f64 {
+        self.inner.fee
+    }
+
+    #[getter]
+    fn nonce(&self) -> u64 {
+        self.inner.nonce
+    }
+
+    #[getter]
+    fn timestamp(&self) -> u32 {
+        self.inner.timestamp
+    }
+
+    #[getter]
+    fn memo(&self) -> Option<String> {
+        self.inner.memo.clone()
+    }
+
+    #[getter]
+    fn kind(&self) -> &'static str {
+        self.inner.kind.name()
+    }
+
+    #[getter]
+    fn is_coinbase(&self) -> bool {
+        self.inner.is_coinbase()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Transaction({} -> {}, amount={}, fee={})", self.inner.sender_address, self.inner.receiver_address, self.inner.amount, self.inner.fee)
+    }
+}
+
+/// A block and its height, as seen from Python.
+#[pyclass(name = "Block", module = "blockc", frozen)]
+pub struct PyBlock {
+    pub height: usize,
+    pub inner: Block,
+}
+
+#[pymethods]
+impl PyBlock {
+    #[getter]
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    #[getter]
+    fn hash(&self) -> String {
+        self.inner.block_hash.clone()
+    }
+
+    #[getter]
+    fn previous_hash(&self) -> String {
+        self.inner.previous_block_hash.clone()
+    }
+
+    #[getter]
+    fn timestamp(&self) -> u32 {
+        self.inner.time_stamp
+    }
+
+    #[getter]
+    fn nonce(&self) -> u32 {
+        self.inner.nonce
+    }
+
+    #[getter]
+    fn base_fee(&self) -> f64 {
+        self.inner.base_fee
+    }
+
+    #[getter]
+    fn transactions(&self) -> Vec<PyTransaction> {
+        self.inner.transaction.iter().map(|transaction| PyTransaction { inner: transaction.clone() }).collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.transaction.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Block(height={}, hash={}, transactions={})", self.height, self.inner.block_hash, self.inner.transaction.len())
+    }
+}
+
+/// A blockchain, as seen from Python.
+///
+/// Supports `len(chain)`, `chain[height]` (negative heights count from the
+/// tip) and `for block in chain`.
+#[pyclass(name = "Blockchain", module = "blockc")]
+pub struct PyBlockchain {
+    pub inner: Blockchain,
+}
+
+impl PyBlockchain {
+    fn block(&self, height: usize) -> Option<PyBlock> {
+        self.inner.get_blocks().get(height).map(|block| PyBlock { height: height, inner: block.clone() })
+    }
+}
+
+#[pymethods]
+impl PyBlockchain {
+    #[new]
+    #[pyo3(signature = (difficulty = 3, block_reward = DEFAULT_BLOCK_REWARD))]
+    fn new(difficulty: u32, block_reward: f64) -> Self {
+        PyBlockchain { inner: Blockchain::with_settings(difficulty, block_reward) }
+    }
+
+    /// Height of the tip (the genesis block is 0).
+    #[getter]
+    fn height(&self) -> usize {
+        self.inner.get_blocks().len() - 1
+    }
+
+    #[getter]
+    fn difficulty(&self) -> u32 {
+        self.inner.get_difficulty()
+    }
+
+    #[getter]
+    fn block_reward(&self) -> f64 {
+        self.inner.get_block_reward()
+    }
+
+    #[getter]
+    fn base_fee(&self) -> f64 {
+        self.inner.get_base_fee()
+    }
+
+    #[getter]
+    fn latest_hash(&self) -> String {
+        self.inner.get_latest_hash()
+    }
+
+    /// Transactions waiting in the mempool.
+    #[getter]
+    fn pending(&self) -> Vec<PyTransaction> {
+        self.inner.mempool().transactions().iter().map(|transaction| PyTransaction { inner: transaction.clone() }).collect()
+    }
+
+    fn balance(&self, address: &str) -> f64 {
+        self.inner.get_balance(address)
+    }
+
+    fn nonce(&self, address: &str) -> u64 {
+        self.inner.get_nonce(address)
+    }
+
+    /// Confirmed transactions sent or received by `address`, as `(height, transaction)` pairs.
+    fn history(&self, address: &str) -> Vec<(u64, PyTransaction)> {
+        self.inner.get_history(address).into_iter()
+            .map(|(height, transaction)| (height, PyTransaction { inner: transaction.clone() }))
+            .collect()
+    }
+
+    /// Adds a signed transaction to the mempool; raises `BlockcError` if it is rejected.
+    fn submit(&mut self, transaction: PyRef<'_, PyTransaction>) -> PyResult<()> {
+        self.inner.submit_transaction(transaction.inner.clone()).map_err(to_py_err)
+    }
+
+    /// Raises `BlockcError` naming the first problem if the chain is invalid.
+    fn validate(&self) -> PyResult<()> {
+        self.inner.validate().map_err(to_py_err)
+    }
+
+    fn is_valid(&self) -> bool {
+        self.inner.is_valid()
+    }
+
+    fn to_dot(&self) -> String {
+        self.inner.to_dot()
+    }
+
+    fn to_mermaid(&self) -> String {
+        self.inner.to_mermaid()
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.get_blocks().len()
+    }
+
+    fn __getitem__(&self, height: isize) -> PyResult<PyBlock> {
+        let length = self.inner.get_blocks().len() as isize;
+        let index = if height < 0 { height + length } else { height };
+        if index < 0 {
+            return Err(PyIndexError::new_err(format!("No block at height {}", height)));
+        }
+        self.block(index as usize).ok_or_else(|| PyIndexError::new_err(format!("No block at height {}", height)))
+    }
+
+    fn __iter__(slf: Bound<'_, Self>) -> BlockIterator {
+        BlockIterator {
+            chain: slf.unbind(),
+            next: 0,
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Blockchain(height={}, difficulty={})", self.inner.get_blocks().len() - 1, self.inner.get_difficulty())
+    }
+}
+
+/// Iterator over the blocks of a `Blockchain`, from the genesis block to the tip.
+///
+/// Blocks mined while iterating are included.
+#[pyclass(module = "blockc")]
+pub struct BlockIterator {
+    chain: Py<PyBlockchain>,
+    next: usize,
+}
+
+#[pymethods]
+impl BlockIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> Option<PyBlock> {
+        let block = self.chain.borrow(py).block(self.next)?;
+        self.next += 1;
+        Some(block)
+    }
+}
+
+/// A wallet with freshly generated keys, as seen from Python.
+#[pyclass(name = "Wallet", module = "blockc")]
+pub struct PyWallet {
+    pub inner: Wallet,
+}
+
+#[pymethods]
+impl PyWallet {
+    /// Generates the keys of `address`; the same `seed` always gives the same keys.
+    #[new]
+    #[pyo3(signature = (address, seed = None))]
+    fn new(address: String, seed: Option<u64>) -> Self {
+        let seed = seed.unwrap_or_else(entropy);
+        PyWallet { inner: Wallet::new(Entity::generate(address, 0.0, &mut SimRng::new(seed))) }
+    }
+
+    #[getter]
+    fn address(&self) -> String {
+        self.inner.address().to_string()
+    }
+
+    /// Balance as of the last `sync`.
+    #[getter]
+    fn balance(&self) -> f64 {
+        self.inner.entity.balance
+    }
+
+    /// Nonce the next transaction will use.
+    #[getter]
+    fn nonce(&self) -> u64 {
+        self.inner.entity.nonce
+    }
+
+    /// Refreshes the balance and nonce from `chain`, counting the wallet's pending transactions.
+    fn sync(&mut self, chain: PyRef<'_, PyBlockchain>) {
+        self.inner.sync_pending(&chain.inner);
+    }
+
+    /// Signs a payment of `amount` to a name or an address.
+    ///
+    /// The wallet syncs with `chain` first; `fee` defaults to twice the base fee.
+    /// The transaction still has to be given to `Blockchain.submit`.
+    #[pyo3(signature = (chain, recipient, amount, fee = None, memo = None))]
+    fn pay(&mut self, chain: PyRef<'_, PyBlockchain>, recipient: &str, amount: f64, fee: Option<f64>, memo: Option<String>) -> PyResult<PyTransaction> {
+        self.inner.sync_pending(&chain.inner);
+        let receiver = self.inner.resolve(&chain.inner, recipient).map_err(to_py_err)?;
+        let fee = fee.unwrap_or(chain.inner.get_base_fee() * 2.0);
+        Transaction::create_payment_with_memo(&mut self.inner.entity, &receiver, amount, fee, memo)
+            .map(|inner| PyTransaction { inner: inner })
+            .map_err(to_py_err)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Wallet({})", self.inner.address())
+    }
+}
+
+/// Mines blocks paying their rewards to one address.
+#[pyclass(name = "Miner", module = "blockc")]
+pub struct PyMiner {
+    address: String,
+}
+
+#[pymethods]
+impl PyMiner {
+    #[new]
+    fn new(address: String) -> Self {
+        PyMiner { address: address }
+    }
+
+    #[getter]
+    fn address(&self) -> String {
+        self.address.clone()
+    }
+
+    /// Mines one block from the mempool of `chain` and returns it.
+    ///
+    /// Builds a template, searches a nonce meeting the difficulty and submits
+    /// the block, like an external miner would.
+    fn mine(&self, mut chain: PyRefMut<'_, PyBlockchain>) -> PyResult<PyBlock> {
+        let template = chain.inner.get_block_template(&self.address).map_err(to_py_err)?;
+        let block = template.mine();
+        chain.inner.submit_block(block.clone()).map_err(to_py_err)?;
+        Ok(PyBlock {
+            height: template.height as usize,
+            inner: block,
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Miner({})", self.address)
+    }
+}
+
+/// The `blockc` Python module.
+///
+/// Build it with `maturin develop --features python` (see `pyproject.toml`),
+/// then `import blockc` from Python or a Jupyter notebook. Errors of the chain
+/// are raised as `blockc.BlockcError`.
+///
+/// # Example
+///
+/// ```
+/// use pyo3::prelude::*;
+/// use blockc::python::python::blockc as module;
+///
+/// pyo3::append_to_inittab!(module);
+/// Python::initialize();
+/// Python::attach(|py| {
+///     py.run(c"
+/// import blockc
+///
+/// chain = blockc.Blockchain(difficulty=1)
+/// miner = blockc.Miner('Alice')
+/// block = miner.mine(chain)
+/// assert block.height == 1 and len(chain) == 2
+///
+/// alice = blockc.Wallet('Alice', seed=7)
+/// payment = alice.pay(chain, 'Bob', 10.0)
+/// chain.submit(payment)
+/// miner.mine(chain)
+/// assert chain.balance('Bob') == 10.0
+/// assert [b.height for b in chain] == [0, 1, 2]
+/// assert chain[-1].transactions[-1].receiver == 'Bob'
+///
+/// try:
+///     chain.submit(payment)
+///     raise AssertionError('replayed payment accepted')
+/// except blockc.BlockcError:
+///     pass
+/// ", None, None)
+/// }).unwrap();
+/// ```
+#[pymodule]
+pub fn blockc(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add("BlockcError", module.py().get_type::<BlockcError>())?;
+    module.add_class::<PyBlockchain>()?;
+    module.add_class::<PyBlock>()?;
+    module.add_class::<BlockIterator>()?;
+    module.add_class::<PyTransaction>()?;
+    module.add_class::<PyWallet>()?;
+    module.add_class::<PyMiner>()?;
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Clients a `RateLimiter` tracks before it forgets those whose bucket refilled.
+pub const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// How fast a client may send requests.
+///
+/// A client starts with `burst` tokens and gets `per_second` more every second,
+/// up to `burst`; every request spends one.
+///
+/// # Fields
+///
+/// * `per_second` - Sustained requests per second
+/// * `burst` - Requests allowed at once after a quiet period
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub per_second: f64,
+    pub burst: f64,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        RateLimit {
+            per_second: 20.0,
+            burst: 40.0,
+        }
+    }
+}
+
+impl RateLimit {
+    /// Checks the limit lets requests through at all.
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.per_second.is_finite() || self.per_second <= 0.0 {
+            return Err("The request rate must be a positive number".to_string());
+        }
+        if !self.burst.is_finite() || self.burst < 1.0 {
+            return Err("The burst must allow at least one request".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Tokens left to a client, as of `updated`.
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Token buckets of every client (an IP address, a peer id...), so one client
+/// sending too many requests is throttled without slowing the others.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use blockc::rpc::limit::{RateLimit, RateLimiter};
+///
+/// let mut limiter = RateLimiter::new(Some(RateLimit { per_second: 2.0, burst: 3.0 }));
+/// let start = Instant::now();
+/// for _ in 0..3 {
+///     assert!(limiter.check_at("10.0.0.1", start).is_ok());
+/// }
+///
+/// // The fourth request has to wait half a second for a token
+/// assert_eq!(limiter.check_at("10.0.0.1", start), Err(Duration::from_millis(500)));
+/// assert!(limiter.check_at("10.0.0.2", start).is_ok());
+/// assert!(limiter.check_at("10.0.0.1", start + Duration::from_millis(500)).is_ok());
+///
+/// // Without a limit every request goes through
+/// let mut unlimited = RateLimiter::new(None);
+/// assert!((0..1_000).all(|_| unlimited.check_at("10.0.0.1", start).is_ok()));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RateLimiter {
+    limit: Option<RateLimit>,
+    buckets: HashMap<String, Bucket>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter applying `limit` to each client (`None` lets everything through).
+    pub fn new(limit: Option<RateLimit>) -> Self {
+        RateLimiter {
+            limit: limit,
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Returns the limit applied to each client.
+    pub fn limit(&self) -> Option<RateLimit> {
+        self.limit
+    }
+
+    /// Spends a token of `client` now (see `check_at`).
+    pub fn check(&mut self, client: &str) -> Result<(), Duration> {
+        self.check_at(client, Instant::now())
+    }
+
+    /// Spends a token of `client` at `now`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The request may go through
+    /// * `Err(Duration)` - The client is throttled: how long until it has a token again
+    pub fn check_at(&mut self, client: &str, now: Instant) -> Result<(), Duration> {
+        let Some(limit) = self.limit else {
+            return Ok(());
+        };
+        if self.buckets.len() >= MAX_TRACKED_CLIENTS && !self.buckets.contains_key(client) {
+            self.forget_idle(limit, now);
+        }
+        let bucket = self.buckets.entry(client.to_string()).or_insert(Bucket {
+            tokens: limit.burst,
+            updated: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * limit.per_second).min(limit.burst);
+        bucket.updated = bucket.updated.max(now);
+        if bucket.tokens < 1.0 {
+            return Err(Duration::from_secs_f64((1.0 - bucket.tokens) / limit.per_second));
+        }
+        bucket.tokens -= 1.0;
+        Ok(())
+    }
+
+    /// Returns the number of clients tracked.
+    pub fn clients(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// Drops the buckets that refilled (their clients start over with a full one).
+    fn forget_idle(&mut self, limit: RateLimit, now: Instant) {
+        self.buckets.retain(|_, bucket| {
+            let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+            bucket.tokens + elapsed * limit.per_second < limit.burst
+        });
+    }
+}
@@ -0,0 +1,3 @@
+pub mod server;
+pub mod notify;
+pub mod limit;
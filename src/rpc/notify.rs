@@ -0,0 +1,378 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+#[cfg(feature = "fs")]
+use std::fs;
+#[cfg(feature = "fs")]
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use crate::blockchain::blockchain::Blockchain;
+#[cfg(feature = "fs")]
+use crate::codec::json;
+use crate::codec::json::{JsonCodec, JsonValue};
+use crate::transactions::transactions::Transaction;
+
+/// File holding the webhooks, in a store's directory.
+pub const WEBHOOKS_FILE: &str = "webhooks.json";
+
+/// How long a webhook may take to connect, read the notification or answer.
+pub const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Function called with each notification of a subscription.
+pub type NotifyCallback = Box<dyn FnMut(&Notification) -> Result<(), String> + Send>;
+
+/// Where the notifications of a subscription go.
+pub enum NotifyTarget {
+    /// A function of the process
+    Callback(NotifyCallback),
+    /// An `http://` URL, POSTed each notification as JSON
+    Webhook(String),
+}
+
+/// A request to be told about the confirmed transactions touching `address`
+/// (sent by it or paying it).
+///
+/// # Fields
+///
+/// * `id` - Identifier of the subscription
+/// * `address` - Address watched
+/// * `confirmations` - Confirmations a transaction needs before it is notified (1 = in a block)
+/// * `target` - Callback or webhook URL
+/// * `next_height` - Height of the next block to notify
+pub struct Subscription {
+    pub id: u64,
+    pub address: String,
+    pub confirmations: u64,
+    pub target: NotifyTarget,
+    pub next_height: u64,
+}
+
+/// A transaction touching a watched address that reached the confirmations
+/// its subscription asked for.
+///
+/// # Fields
+///
+/// * `subscription_id` - Subscription notified
+/// * `address` - Address watched
+/// * `height` - Height of the block holding the transaction
+/// * `confirmations` - Confirmations when notified (blocks from `height` to the tip)
+/// * `transaction` - The transaction
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub subscription_id: u64,
+    pub address: String,
+    pub height: u64,
+    pub confirmations: u64,
+    pub transaction: Transaction,
+}
+
+impl Notification {
+    /// Encodes the notification as a webhook receives it: `{"subscription": n,
+    /// "address": ..., "transaction_id": ..., "height": n, "confirmations": n,
+    /// "transaction": {...}}`.
+    pub fn to_json(&self) -> JsonValue {
+        JsonValue::Object(vec![
+            ("subscription".to_string(), self.subscription_id.to_json()),
+            ("address".to_string(), self.address.to_json()),
+            ("transaction_id".to_string(), self.transaction.id().to_json()),
+            ("height".to_string(), self.height.to_json()),
+            ("confirmations".to_string(), self.confirmations.to_json()),
+            ("transaction".to_string(), JsonCodec::to_json(&self.transaction)),
+        ])
+    }
+}
+
+/// Result of `Notifier::check`.
+///
+/// # Fields
+///
+/// * `sent` - Notifications delivered
+/// * `errors` - Failed deliveries (retried by the next check)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NotifyReport {
+    pub sent: usize,
+    pub errors: Vec<String>,
+}
+
+/// Tells callbacks and webhooks about the activity of addresses.
+///
+/// Subscriptions only see blocks added after they were made. Call `check`
+/// whenever the chain changes (`blockc serve` does after each change): every
+/// transaction touching a subscribed address is notified once its block has
+/// the confirmations asked for. A failed delivery stops the subscription at
+/// that block until a later `check` delivers it, so a notification may come
+/// twice but is never skipped. Blocks replaced by a reorganization are not
+/// notified again.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::entity::entity::Entity;
+/// use blockc::rpc::notify::Notifier;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// chain.add_block(Vec::new(), "Alice").unwrap();
+/// let fee = chain.get_base_fee() * 2.0;
+///
+/// let mut notifier = Notifier::new();
+/// let seen = Arc::new(Mutex::new(Vec::new()));
+/// let log = seen.clone();
+/// notifier.subscribe(&chain, "Shop", 2, Box::new(move |notification| {
+///     log.lock().unwrap().push((notification.transaction.amount, notification.confirmations));
+///     Ok(())
+/// })).unwrap();
+///
+/// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+/// let payment = Transaction::create_payment(&mut alice, "Shop", 5.0, fee).unwrap();
+/// chain.add_block(vec![payment], "Miner").unwrap();
+/// assert_eq!(notifier.check(&chain).sent, 0);
+///
+/// // Notified once, on the second confirmation
+/// chain.add_block(Vec::new(), "Miner").unwrap();
+/// assert_eq!(notifier.check(&chain).sent, 1);
+/// assert_eq!(notifier.check(&chain).sent, 0);
+/// assert_eq!(*seen.lock().unwrap(), vec![(5.0, 2)]);
+/// ```
+///
+/// A webhook receives the notification as an HTTP POST:
+///
+/// ```
+/// use std::io::{BufRead, BufReader, Read, Write};
+/// use std::net::TcpListener;
+/// use std::thread;
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::rpc::notify::Notifier;
+///
+/// let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+/// let url = format!("http://{}/hooks/payments", listener.local_addr().unwrap());
+/// let receiver = thread::spawn(move || {
+///     let (mut stream, _) = listener.accept().unwrap();
+///     let mut reader = BufReader::new(stream.try_clone().unwrap());
+///     let mut request = String::new();
+///     let mut length = 0;
+///     loop {
+///         let mut line = String::new();
+///         reader.read_line(&mut line).unwrap();
+///         if let Some(value) = line.strip_prefix("Content-Length: ") {
+///             length = value.trim().parse().unwrap();
+///         }
+///         request.push_str(&line);
+///         if line == "\r\n" {
+///             break;
+///         }
+///     }
+///     let mut body = vec![0; length];
+///     reader.read_exact(&mut body).unwrap();
+///     stream.write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n").unwrap();
+///     (request, String::from_utf8(body).unwrap())
+/// });
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// let mut notifier = Notifier::new();
+/// notifier.add_webhook(&chain, "Miner", &url, 1).unwrap();
+/// chain.add_block(Vec::new(), "Miner").unwrap();
+/// assert_eq!(notifier.check(&chain).sent, 1);
+///
+/// let (request, body) = receiver.join().unwrap();
+/// assert!(request.starts_with("POST /hooks/payments HTTP/1.1"));
+/// assert!(body.contains("\"address\":\"Miner\"") && body.contains("\"height\":1"));
+///
+/// assert!(notifier.add_webhook(&chain, "Miner", "https://example.com", 1).is_err());
+/// ```
+#[derive(Default)]
+pub struct Notifier {
+    subscriptions: Vec<Subscription>,
+    next_id: u64,
+    #[cfg(feature = "fs")]
+    path: Option<PathBuf>,
+}
+
+impl Notifier {
+    /// Creates a notifier without subscriptions.
+    pub fn new() -> Self {
+        Notifier::default()
+    }
+
+    /// Reads the webhooks saved at `path` (none if the file doesn't exist
+    /// yet); `save` writes them back there.
+    #[cfg(feature = "fs")]
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let mut notifier = Notifier::new();
+        notifier.path = Some(path.to_path_buf());
+        if !path.exists() {
+            return Ok(notifier);
+        }
+        let text = fs::read_to_string(path).map_err(|error| format!("Cannot read {}: {}", path.display(), error))?;
+        let value = json::parse(&text).map_err(|error| format!("{}: {}", path.display(), error))?;
+        for webhook in value.as_array()? {
+            let members = webhook.as_object()?;
+            let url: String = json::field(members, "url")?;
+            check_url(&url)?;
+            let subscription = Subscription {
+                id: json::field(members, "id")?,
+                address: json::field(members, "address")?,
+                confirmations: json::field(members, "confirmations")?,
+                target: NotifyTarget::Webhook(url),
+                next_height: json::field(members, "next_height")?,
+            };
+            notifier.next_id = notifier.next_id.max(subscription.id + 1);
+            notifier.subscriptions.push(subscription);
+        }
+        Ok(notifier)
+    }
+
+    /// Writes the webhooks (not the callbacks) to the file they were loaded from.
+    #[cfg(feature = "fs")]
+    pub fn save(&self) -> Result<(), String> {
+        let path = self.path.as_ref().ok_or("The notifier was not loaded from a file")?;
+        let webhooks = self.subscriptions.iter()
+            .filter_map(|subscription| match &subscription.target {
+                NotifyTarget::Webhook(url) => Some(JsonValue::Object(vec![
+                    ("id".to_string(), subscription.id.to_json()),
+                    ("address".to_string(), subscription.address.to_json()),
+                    ("url".to_string(), url.to_json()),
+                    ("confirmations".to_string(), subscription.confirmations.to_json()),
+                    ("next_height".to_string(), subscription.next_height.to_json()),
+                ])),
+                NotifyTarget::Callback(_) => None,
+            })
+            .collect();
+        fs::write(path, JsonValue::Array(webhooks).to_string()).map_err(|error| format!("Cannot write {}: {}", path.display(), error))
+    }
+
+    /// Calls `callback` for the transactions touching `address` in the blocks
+    /// added to `chain` from now on, once they have `confirmations`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u64)` - Id of the subscription
+    /// * `Err(String)` - If the address is empty or `confirmations` is 0
+    pub fn subscribe(&mut self, chain: &Blockchain, address: &str, confirmations: u64, callback: NotifyCallback) -> Result<u64, String> {
+        self.add(chain, address, confirmations, NotifyTarget::Callback(callback))
+    }
+
+    /// Like `subscribe`, but POSTs the notifications to an `http://` URL.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u64)` - Id of the subscription
+    /// * `Err(String)` - If the address is empty, `confirmations` is 0 or the
+    ///   URL is not `http://host[:port][/path]`
+    pub fn add_webhook(&mut self, chain: &Blockchain, address: &str, url: &str, confirmations: u64) -> Result<u64, String> {
+        check_url(url)?;
+        self.add(chain, address, confirmations, NotifyTarget::Webhook(url.to_string()))
+    }
+
+    fn add(&mut self, chain: &Blockchain, address: &str, confirmations: u64, target: NotifyTarget) -> Result<u64, String> {
+        if address.is_empty() {
+            return Err("A subscription needs an address".to_string());
+        }
+        if confirmations == 0 {
+            return Err("Notifications need at least one confirmation".to_string());
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        self.subscriptions.push(Subscription {
+            id: id,
+            address: address.to_string(),
+            confirmations: confirmations,
+            target: target,
+            next_height: chain.get_blocks().len() as u64,
+        });
+        Ok(id)
+    }
+
+    /// Removes a subscription.
+    pub fn unsubscribe(&mut self, id: u64) -> Result<(), String> {
+        let count = self.subscriptions.len();
+        self.subscriptions.retain(|subscription| subscription.id != id);
+        if self.subscriptions.len() == count {
+            return Err(format!("No subscription {}", id));
+        }
+        Ok(())
+    }
+
+    /// Returns the subscriptions, oldest first.
+    pub fn subscriptions(&self) -> &[Subscription] {
+        &self.subscriptions
+    }
+
+    /// Delivers the notifications of the blocks that reached the confirmations
+    /// of each subscription since the last check.
+    pub fn check(&mut self, chain: &Blockchain) -> NotifyReport {
+        let mut report = NotifyReport::default();
+        let tip = chain.get_blocks().len() as u64 - 1;
+        for subscription in &mut self.subscriptions {
+            'blocks: while subscription.next_height + subscription.confirmations - 1 <= tip {
+                let height = subscription.next_height;
+                let Some(block) = chain.get_blocks().get(height as usize) else {
+                    break;
+                };
+                for transaction in &block.transaction {
+                    if transaction.sender_address != subscription.address && !transaction.pays(&subscription.address) {
+                        continue;
+                    }
+                    let notification = Notification {
+                        subscription_id: subscription.id,
+                        address: subscription.address.clone(),
+                        height: height,
+                        confirmations: tip - height + 1,
+                        transaction: transaction.clone(),
+                    };
+                    let delivered = match &mut subscription.target {
+                        NotifyTarget::Callback(callback) => callback(&notification),
+                        NotifyTarget::Webhook(url) => post_json(url, &notification.to_json().to_string()),
+                    };
+                    match delivered {
+                        Ok(()) => report.sent += 1,
+                        Err(error) => {
+                            report.errors.push(format!("Subscription {}: {}", subscription.id, error));
+                            break 'blocks;
+                        }
+                    }
+                }
+                subscription.next_height += 1;
+            }
+        }
+        report
+    }
+}
+
+/// Splits an `http://host[:port][/path]` URL into its address and path.
+fn check_url(url: &str) -> Result<(String, String), String> {
+    let rest = url.strip_prefix("http://").ok_or(format!("Webhook {} is not an http:// URL", url))?;
+    let (host, path) = match rest.find('/') {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, "/"),
+    };
+    if host.is_empty() || path.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(format!("Invalid webhook URL {}", url));
+    }
+    let address = if host.contains(':') { host.to_string() } else { format!("{}:80", host) };
+    Ok((address, path.to_string()))
+}
+
+/// POSTs `body` to `url` and checks the answer is a 2xx status.
+fn post_json(url: &str, body: &str) -> Result<(), String> {
+    let (address, path) = check_url(url)?;
+    let socket = address.to_socket_addrs().map_err(|error| format!("Cannot resolve {}: {}", address, error))?
+        .next()
+        .ok_or(format!("Cannot resolve {}", address))?;
+    let mut stream = TcpStream::connect_timeout(&socket, WEBHOOK_TIMEOUT).map_err(|error| format!("Cannot connect to {}: {}", address, error))?;
+    stream.set_read_timeout(Some(WEBHOOK_TIMEOUT)).map_err(|error| error.to_string())?;
+    stream.set_write_timeout(Some(WEBHOOK_TIMEOUT)).map_err(|error| error.to_string())?;
+    let request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        path, address, body.len(), body
+    );
+    stream.write_all(request.as_bytes()).map_err(|error| format!("Cannot send to {}: {}", url, error))?;
+    let mut status_line = String::new();
+    BufReader::new(stream).read_line(&mut status_line).map_err(|error| format!("No answer from {}: {}", url, error))?;
+    match status_line.split_whitespace().nth(1) {
+        Some(status) if status.starts_with('2') && status.len() == 3 => Ok(()),
+        Some(status) => Err(format!("{} answered {}", url, status)),
+        None => Err(format!("Malformed answer from {}", url)),
+    }
+}
@@ -0,0 +1,274 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use crate::blockchain::blockchain::Blockchain;
+use crate::blockchain::memory::MemoryUsage;
+use crate::blockchain::shared::SharedBlockchain;
+use crate::codec::json::{JsonCodec, JsonValue};
+use crate::codec::limits::{MessageError, MessageLimits};
+use crate::rpc::limit::RateLimiter;
+#[cfg(feature = "explorer")]
+use crate::explorer::explorer::{self, EXPLORER_ROOT};
+
+/// Largest request body the server reads (1 MiB).
+pub const MAX_REQUEST_BODY: usize = 1024 * 1024;
+
+/// Largest request line or header the server reads.
+pub const MAX_HEADER_LINE: usize = 8 * 1024;
+
+/// Answer of the RPC API.
+///
+/// # Fields
+///
+/// * `status` - HTTP status code
+/// * `body` - JSON body (`{"error": ...}` when the request failed)
+/// * `changed` - Whether the request changed the chain (so it should be saved)
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcResponse {
+    pub status: u16,
+    pub body: JsonValue,
+    pub changed: bool,
+}
+
+impl RpcResponse {
+    fn ok(body: JsonValue, changed: bool) -> Self {
+        RpcResponse {
+            status: 200,
+            body: body,
+            changed: changed,
+        }
+    }
+
+    fn error(status: u16, message: String) -> Self {
+        RpcResponse {
+            status: status,
+            body: object(vec![("error", message.to_json())]),
+            changed: false,
+        }
+    }
+}
+
+fn object(members: Vec<(&str, JsonValue)>) -> JsonValue {
+    JsonValue::Object(members.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+}
+
+fn memory_json(usage: MemoryUsage) -> JsonValue {
+    object(vec![
+        ("blocks", (usage.blocks as u64).to_json()),
+        ("filters", (usage.filters as u64).to_json()),
+        ("state_cache", (usage.state_cache as u64).to_json()),
+        ("mempool", (usage.mempool as u64).to_json()),
+        ("total", (usage.total() as u64).to_json()),
+    ])
+}
+
+/// Answers one request of the JSON API.
+///
+/// Endpoints:
+///
+/// * `GET /status` - Height, latest hash, difficulty, base fee, mempool size and
+///   approximate memory use (see `Blockchain::memory_usage`)
+/// * `GET /balance/{address}` - Balance and nonce of an address
+/// * `GET /blocks/{height}` - A block
+/// * `GET /history/{address}` - Confirmed transactions sent or received by an address
+/// * `POST /transactions` - Submits a signed transaction (JSON) to the mempool;
+///   bodies over the `MessageLimits` are refused before they are decoded
+/// * `POST /mine/{address}` - Mines a block from the mempool for `address`
+///
+/// # Arguments
+///
+/// * `chain` - The chain to query or update
+/// * `method` - HTTP method
+/// * `path` - Request path
+/// * `body` - Request body
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::rpc::server::handle;
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// let mined = handle(&mut chain, "POST", "/mine/Miner", "");
+/// assert_eq!(mined.status, 200);
+/// assert!(mined.changed);
+///
+/// let balance = handle(&mut chain, "GET", "/balance/Miner", "");
+/// assert_eq!(balance.body.to_string(), r#"{"address":"Miner","balance":45.0,"nonce":0}"#);
+///
+/// assert_eq!(handle(&mut chain, "GET", "/blocks/7", "").status, 404);
+/// assert_eq!(handle(&mut chain, "POST", "/transactions", "not json").status, 400);
+/// ```
+pub fn handle(chain: &mut Blockchain, method: &str, path: &str, body: &str) -> RpcResponse {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match (method, segments.as_slice()) {
+        ("GET", ["status"]) => RpcResponse::ok(object(vec![
+            ("height", (chain.get_blocks().len() as u64 - 1).to_json()),
+            ("latest_hash", chain.get_latest_hash().to_json()),
+            ("difficulty", chain.get_difficulty().to_json()),
+            ("base_fee", chain.get_base_fee().to_json()),
+            ("mempool", (chain.mempool().len() as u64).to_json()),
+            ("memory", memory_json(chain.memory_usage())),
+        ]), false),
+        ("GET", ["balance", address]) => RpcResponse::ok(object(vec![
+            ("address", address.to_string().to_json()),
+            ("balance", chain.get_balance(address).to_json()),
+            ("nonce", chain.get_nonce(address).to_json()),
+        ]), false),
+        ("GET", ["blocks", height]) => {
+            match height.parse::<usize>().ok().and_then(|height| chain.get_blocks().get(height)) {
+                Some(block) => RpcResponse::ok(block.to_json(), false),
+                None => RpcResponse::error(404, format!("No block at height {}", height)),
+            }
+        }
+        ("GET", ["history", address]) => {
+            let entries = chain.get_history(address).into_iter()
+                .map(|(height, transaction)| object(vec![("height", height.to_json()), ("transaction", JsonCodec::to_json(transaction))]))
+                .collect();
+            RpcResponse::ok(JsonValue::Array(entries), false)
+        }
+        ("POST", ["transactions"]) => {
+            let transaction = match MessageLimits::default().decode_transaction_json(body) {
+                Ok(transaction) => transaction,
+                Err(error @ MessageError::TooLarge { .. }) => return RpcResponse::error(413, error.to_string()),
+                Err(error) => return RpcResponse::error(400, error.to_string()),
+            };
+            let id = transaction.id();
+            match chain.submit_transaction(transaction) {
+                Ok(()) => RpcResponse::ok(object(vec![("transaction_id", id.to_json())]), true),
+                Err(error) => RpcResponse::error(422, error),
+            }
+        }
+        ("POST", ["mine", address]) => match chain.add_block_from_mempool(address) {
+            Ok(count) => RpcResponse::ok(object(vec![
+                ("height", (chain.get_blocks().len() as u64 - 1).to_json()),
+                ("hash", chain.get_latest_hash().to_json()),
+                ("transactions", (count as u64).to_json()),
+            ]), true),
+            Err(error) => RpcResponse::error(422, error),
+        },
+        (_, ["status"] | ["balance", _] | ["blocks", _] | ["history", _] | ["transactions"] | ["mine", _]) => {
+            RpcResponse::error(405, format!("Method {} not allowed on {}", method, path))
+        }
+        _ => RpcResponse::error(404, format!("Unknown endpoint {}", path)),
+    }
+}
+
+/// Serves the JSON API of `handle` over HTTP/1.1, one connection at a time.
+///
+/// With the `explorer` feature, `GET /explorer...` requests are answered with
+/// the HTML pages of `explorer::explorer::page`.
+///
+/// Each connection carries one request. Requests only hold the chain's lock
+/// while they are answered, so other threads (e.g. a miner) can share `chain`.
+/// After a request changes the chain, `on_change` is called under the read
+/// lock (e.g. to save it); its errors are logged to stderr. Only fails if the
+/// listener itself fails.
+///
+/// Requests are rate limited per client IP address: a client out of tokens gets
+/// `429 Too Many Requests` with a `Retry-After` header, without touching the chain.
+///
+/// # Arguments
+///
+/// * `listener` - Socket accepting the connections
+/// * `chain` - The chain served
+/// * `limiter` - Request rate allowed to each client
+/// * `on_change` - Called after every change
+pub fn serve(listener: &TcpListener, chain: &SharedBlockchain, limiter: &mut RateLimiter, on_change: &mut dyn FnMut(&Blockchain) -> Result<(), String>) -> Result<(), String> {
+    for stream in listener.incoming() {
+        let stream = stream.map_err(|error| format!("Cannot accept a connection: {}", error))?;
+        match handle_connection(stream, chain, limiter) {
+            Ok(true) => {
+                if let Err(error) = chain.read(|chain| on_change(chain)) {
+                    eprintln!("{}", error);
+                }
+            }
+            Ok(false) => {}
+            Err(error) => eprintln!("{}", error),
+        }
+    }
+    Ok(())
+}
+
+/// Reads one request from `stream`, answers it and returns whether the chain changed.
+fn handle_connection(stream: TcpStream, chain: &SharedBlockchain, limiter: &mut RateLimiter) -> Result<bool, String> {
+    let client = stream.peer_addr().map(|address| address.ip().to_string()).map_err(|error| error.to_string())?;
+    let mut reader = BufReader::new(stream.try_clone().map_err(|error| error.to_string())?);
+    let request_line = read_line(&mut reader)?;
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = match (parts.next(), parts.next()) {
+        (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+        _ => return respond(stream, &RpcResponse::error(400, "Malformed request line".to_string())).map(|_| false),
+    };
+
+    let mut content_length = 0;
+    loop {
+        let line = read_line(&mut reader)?;
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length") {
+            content_length = value.trim().parse().map_err(|_| "Invalid Content-Length".to_string())?;
+        }
+    }
+    if content_length > MAX_REQUEST_BODY {
+        let response = RpcResponse::error(413, format!("Body larger than {} bytes", MAX_REQUEST_BODY));
+        return respond(stream, &response).map(|_| false);
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).map_err(|error| error.to_string())?;
+
+    if let Err(wait) = limiter.check(&client) {
+        let retry_after = wait.as_secs_f64().ceil().max(1.0) as u64;
+        let response = RpcResponse::error(429, format!("Too many requests, retry in {} s", retry_after));
+        let headers = format!("Retry-After: {}\r\n", retry_after);
+        write_response(stream, response.status, "application/json", &headers, &response.body.to_string())?;
+        return Ok(false);
+    }
+
+    #[cfg(feature = "explorer")]
+    if method == "GET" && (path == EXPLORER_ROOT || path.starts_with(&format!("{}/", EXPLORER_ROOT))) {
+        let page = chain.read(|chain| explorer::page(chain, &path));
+        write_response(stream, page.status, "text/html; charset=utf-8", "", &page.body)?;
+        return Ok(false);
+    }
+
+    let response = match String::from_utf8(body) {
+        Ok(body) => chain.write(|chain| handle(chain, &method, &path, &body)),
+        Err(_) => RpcResponse::error(400, "Body is not UTF-8".to_string()),
+    };
+    respond(stream, &response)?;
+    Ok(response.changed)
+}
+
+fn read_line(reader: &mut BufReader<TcpStream>) -> Result<String, String> {
+    let mut line = Vec::new();
+    reader.by_ref().take(MAX_HEADER_LINE as u64).read_until(b'\n', &mut line).map_err(|error| error.to_string())?;
+    if !line.ends_with(b"\n") {
+        return Err("Request line or header too long or truncated".to_string());
+    }
+    String::from_utf8(line).map(|line| line.trim_end().to_string()).map_err(|_| "Header is not UTF-8".to_string())
+}
+
+fn respond(stream: TcpStream, response: &RpcResponse) -> Result<(), String> {
+    write_response(stream, response.status, "application/json", "", &response.body.to_string())
+}
+
+/// Writes a response; `headers` are extra header lines, each ending with CRLF.
+fn write_response(mut stream: TcpStream, status: u16, content_type: &str, headers: &str, body: &str) -> Result<(), String> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        429 => "Too Many Requests",
+        _ => "Unprocessable Entity",
+    };
+    let message = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+        status, reason, content_type, body.len(), headers, body
+    );
+    stream.write_all(message.as_bytes()).map_err(|error| error.to_string())
+}
@@ -0,0 +1,150 @@
+use rhai::packages::{BasicStringPackage, CorePackage, LogicPackage, Package};
+use rhai::{Dynamic, Engine, Scope};
+use crate::state::state::ChainState;
+use crate::transactions::transactions::Transaction;
+
+/// Maximum size of a script, in bytes.
+pub const MAX_SCRIPT_BYTES: usize = 1_024;
+
+/// Maximum number of operations a script can run before it is stopped.
+pub const MAX_OPERATIONS: u64 = 10_000;
+
+/// Maximum length of a string built by a script.
+pub const MAX_STRING_SIZE: usize = 256;
+
+/// What a script can see about the transaction being validated.
+///
+/// Scripts read these values as variables (e.g. `amount <= 10.0 && height > 5`).
+///
+/// # Fields
+///
+/// * `sender` - Sender address
+/// * `receiver` - Receiver address
+/// * `amount` - Amount sent
+/// * `fee` - Fee paid
+/// * `nonce` - Sender nonce
+/// * `memo` - Memo (empty if there is none)
+/// * `kind` - Name of the transaction kind (e.g. `"transfer"`)
+/// * `sender_balance` - Sender's spendable balance before the transaction
+/// * `height` - Height of the block the transaction would be included in
+/// * `time` - Timestamp of the latest block
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptContext {
+    pub sender: String,
+    pub receiver: String,
+    pub amount: f64,
+    pub fee: f64,
+    pub nonce: u64,
+    pub memo: String,
+    pub kind: String,
+    pub sender_balance: f64,
+    pub height: u64,
+    pub time: u32,
+}
+
+impl ScriptContext {
+    /// Builds the context of `transaction` against the current chain state.
+    pub fn new(transaction: &Transaction, state: &ChainState) -> Self {
+        ScriptContext {
+            sender: transaction.sender_address.clone(),
+            receiver: transaction.receiver_address.clone(),
+            amount: transaction.amount,
+            fee: transaction.fee,
+            nonce: transaction.nonce,
+            memo: transaction.memo.clone().unwrap_or_default(),
+            kind: transaction.kind.name().to_string(),
+            sender_balance: state.get_spendable_balance(&transaction.sender_address),
+            height: state.height() + 1,
+            time: state.time(),
+        }
+    }
+
+    fn scope(&self) -> Scope<'static> {
+        let mut scope = Scope::new();
+        scope.push_constant("sender", self.sender.clone());
+        scope.push_constant("receiver", self.receiver.clone());
+        scope.push_constant("amount", self.amount);
+        scope.push_constant("fee", self.fee);
+        scope.push_constant("nonce", self.nonce as i64);
+        scope.push_constant("memo", self.memo.clone());
+        scope.push_constant("kind", self.kind.clone());
+        scope.push_constant("sender_balance", self.sender_balance);
+        scope.push_constant("height", self.height as i64);
+        scope.push_constant("time", self.time as i64);
+        scope
+    }
+}
+
+/// Builds the sandboxed engine scripts run in.
+///
+/// Only the core, logic and string packages are loaded: there is no access to the
+/// clock, random numbers or I/O, so every node gets the same result. `print` and
+/// `debug` do nothing, and the operation count, call depth and string sizes are
+/// limited.
+pub fn engine() -> Engine {
+    let mut engine = Engine::new_raw();
+    engine.register_global_module(CorePackage::new().as_shared_module());
+    engine.register_global_module(LogicPackage::new().as_shared_module());
+    engine.register_global_module(BasicStringPackage::new().as_shared_module());
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_call_levels(16);
+    engine.set_max_expr_depths(32, 32);
+    engine.set_max_string_size(MAX_STRING_SIZE);
+    engine.on_print(|_| {});
+    engine.on_debug(|_, _, _| {});
+    engine
+}
+
+/// Checks that a script is small enough and compiles.
+///
+/// # Returns
+///
+/// * `Ok(())` - The script can be attached or registered
+/// * `Err(String)` - It is too large or has a syntax error
+pub fn check_script(source: &str) -> Result<(), String> {
+    if source.len() > MAX_SCRIPT_BYTES {
+        return Err(format!("Script is {} bytes, limit is {}", source.len(), MAX_SCRIPT_BYTES));
+    }
+    engine().compile(source).map(|_| ()).map_err(|error| format!("Script does not compile: {}", error))
+}
+
+/// Runs a validation script against a transaction.
+///
+/// The script must evaluate to a boolean: `true` accepts the transaction.
+///
+/// # Arguments
+///
+/// * `source` - The script
+/// * `context` - Values the script can read
+///
+/// # Returns
+///
+/// * `Ok(bool)` - What the script returned
+/// * `Err(String)` - If it does not compile, fails, runs out of operations or
+///   does not return a boolean
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::entity::entity::Entity;
+/// use blockc::scripting::script::{evaluate, ScriptContext};
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+///
+/// let chain = Blockchain::new();
+/// let mut alice = Entity::new("Alice".to_string(), 50.0, Vec::new(), "a".to_string(), "a".to_string());
+/// let tx = Transaction::create_payment(&mut alice, "Bob", 20.0, 0.1).unwrap();
+/// let context = ScriptContext::new(&tx, chain.state());
+///
+/// assert_eq!(evaluate("amount <= 25.0 && receiver == \"Bob\"", &context), Ok(true));
+/// assert_eq!(evaluate("kind != \"transfer\"", &context), Ok(false));
+/// assert!(evaluate("loop {}", &context).is_err());
+/// ```
+pub fn evaluate(source: &str, context: &ScriptContext) -> Result<bool, String> {
+    if source.len() > MAX_SCRIPT_BYTES {
+        return Err(format!("Script is {} bytes, limit is {}", source.len(), MAX_SCRIPT_BYTES));
+    }
+    let mut scope = context.scope();
+    let result: Dynamic = engine().eval_with_scope(&mut scope, source).map_err(|error| format!("Script failed: {}", error))?;
+    result.as_bool().map_err(|_| format!("Script returned a {}, not a boolean", result.type_name()))
+}
@@ -0,0 +1 @@
+pub mod shell;
\ No newline at end of file
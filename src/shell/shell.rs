@@ -0,0 +1,344 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use crate::block::block::Block;
+use crate::blockchain::blockchain::Blockchain;
+use crate::helpers::random::entropy;
+use crate::simulation::rng::SimRng;
+use crate::storage::keystore::{KeyStore, KEYS_FILE};
+use crate::transactions::transactions::{Transaction, COINBASE_ADDRESS, TransactionExt};
+use crate::wallet::contacts::{AddressBook, CONTACTS_FILE};
+use crate::wallet::wallet::{Address, Wallet};
+
+/// Address mining the shell's blocks when `mine` is given none.
+pub const DEFAULT_MINER: &str = "Miner";
+
+/// Commands of the shell, with their arguments.
+pub const COMMANDS: [(&str, &str); 14] = [
+    ("help", ""),
+    ("status", ""),
+    ("block", "<height|hash>"),
+    ("tx", "<id>"),
+    ("balance", "<address>"),
+    ("history", "<address>"),
+    ("mempool", ""),
+    ("mine", "[count] [miner]"),
+    ("addresses", ""),
+    ("contacts", "[add <label> <address> | remove <label>]"),
+    ("wallet", "[address]"),
+    ("send", "<to> <amount> [fee]"),
+    ("validate", ""),
+    ("exit", ""),
+];
+
+/// What the shell should do after a command.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShellAction {
+    /// Print the text
+    Print(String),
+    /// Print the text; the command changed the chain or the session (so they
+    /// should be saved)
+    Changed(String),
+    /// Leave the shell
+    Exit,
+}
+
+/// Wallet side of a shell: the contacts addresses can be written as, the keys
+/// `send` signs with and the address it sends from.
+///
+/// # Fields
+///
+/// * `data_dir` - Directory the contacts are saved in
+/// * `contacts` - Labels accepted wherever an address is, and shown next to addresses
+/// * `keys` - Keys of the addresses the user controls (created on first `send`)
+/// * `sender` - Address `send` pays from, chosen with `wallet`
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub data_dir: PathBuf,
+    pub contacts: AddressBook,
+    pub keys: KeyStore,
+    pub sender: Option<Address>,
+}
+
+impl Session {
+    /// Loads the contacts and keys saved in `data_dir` (none if there are no files yet).
+    pub fn open(data_dir: &Path) -> Result<Self, String> {
+        Ok(Session {
+            data_dir: data_dir.to_path_buf(),
+            contacts: AddressBook::load(&data_dir.join(CONTACTS_FILE))?,
+            keys: KeyStore::load(&data_dir.join(KEYS_FILE))?,
+            sender: None,
+        })
+    }
+
+    /// Writes the contacts and keys back to their files.
+    pub fn save(&self) -> Result<(), String> {
+        self.contacts.save(&self.data_dir.join(CONTACTS_FILE))?;
+        self.keys.save()
+    }
+}
+
+/// Runs one line of the interactive shell against `chain`.
+///
+/// Wherever a command takes an address, a contact label of `session` works too.
+///
+/// # Returns
+///
+/// * `Ok(ShellAction)` - The output, or `Exit`
+/// * `Err(String)` - If the command is unknown, malformed or fails
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::shell::shell::{execute, Session, ShellAction};
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// let mut session = Session::open(&std::env::temp_dir().join("blockc-shell-doc-unsaved")).unwrap();
+/// assert!(matches!(execute(&mut chain, &mut session, "mine 2 alice"), Ok(ShellAction::Changed(_))));
+/// assert_eq!(execute(&mut chain, &mut session, "balance alice"), Ok(ShellAction::Print("alice: 90 (nonce 0)".to_string())));
+///
+/// let ShellAction::Print(block) = execute(&mut chain, &mut session, "block 1").unwrap() else { panic!() };
+/// assert!(block.starts_with("Block 1 "));
+///
+/// assert!(execute(&mut chain, &mut session, "block 123456789").is_err());
+/// assert!(execute(&mut chain, &mut session, "fly").is_err());
+/// assert_eq!(execute(&mut chain, &mut session, "exit"), Ok(ShellAction::Exit));
+/// ```
+///
+/// Paying a contact:
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::shell::shell::{execute, Session, ShellAction};
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// let mut session = Session::open(&std::env::temp_dir().join("blockc-shell-doc-unsaved")).unwrap();
+/// execute(&mut chain, &mut session, "contacts add bob 7d0e3b95c1").unwrap();
+/// assert!(execute(&mut chain, &mut session, "send bob 10").is_err()); // no wallet chosen
+///
+/// execute(&mut chain, &mut session, "wallet alice").unwrap();
+/// execute(&mut chain, &mut session, "mine 1 alice").unwrap();
+/// assert!(matches!(execute(&mut chain, &mut session, "send bob 10"), Ok(ShellAction::Changed(_))));
+/// execute(&mut chain, &mut session, "mine").unwrap();
+///
+/// assert_eq!(chain.get_balance("7d0e3b95c1"), 10.0);
+/// let ShellAction::Print(history) = execute(&mut chain, &mut session, "history bob").unwrap() else { panic!() };
+/// assert!(history.contains("alice -> bob (7d0e3b95c1) 10"));
+/// ```
+pub fn execute(chain: &mut Blockchain, session: &mut Session, line: &str) -> Result<ShellAction, String> {
+    let contacts = &session.contacts;
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let output = match words.as_slice() {
+        [] => String::new(),
+        ["help"] => COMMANDS.iter()
+            .map(|(name, arguments)| format!("{} {}", name, arguments).trim_end().to_string())
+            .collect::<Vec<String>>()
+            .join("\n"),
+        ["exit"] | ["quit"] => return Ok(ShellAction::Exit),
+        ["status"] => format!(
+            "Height {}, latest {}\nDifficulty {}, base fee {}, {} pending",
+            chain.get_blocks().len() - 1, chain.get_latest_hash(), chain.get_difficulty(), chain.get_base_fee(), chain.mempool().len()
+        ),
+        ["block", key] => {
+            let (height, block) = find_block(chain, key)?;
+            describe_block(contacts, height, block)
+        }
+        ["tx", id] => {
+            if let Some((height, transaction)) = chain.find_transaction(id) {
+                format!("{}\nConfirmed in block {}", describe_transaction(contacts, transaction), height)
+            } else if let Some(transaction) = chain.mempool().transactions().iter().find(|transaction| transaction.id() == *id) {
+                format!("{}\nPending", describe_transaction(contacts, transaction))
+            } else {
+                return Err(format!("No transaction {}", id));
+            }
+        }
+        ["balance", address] => {
+            let address = contacts.resolve(address);
+            format!("{}: {} (nonce {})", contacts.display(address), chain.get_balance(address), chain.get_nonce(address))
+        }
+        ["history", address] => chain.get_history(contacts.resolve(address)).iter()
+            .map(|(height, transaction)| format!("#{} {}", height, summary(contacts, transaction)))
+            .collect::<Vec<String>>()
+            .join("\n"),
+        ["mempool"] => chain.mempool().transactions().iter()
+            .map(|transaction| summary(contacts, transaction))
+            .collect::<Vec<String>>()
+            .join("\n"),
+        ["addresses"] => addresses(chain).iter().map(|address| contacts.display(address)).collect::<Vec<String>>().join("\n"),
+        ["contacts"] => contacts.contacts()
+            .map(|(label, address)| format!("{} {}", label, address))
+            .collect::<Vec<String>>()
+            .join("\n"),
+        ["contacts", "add", label, address] => {
+            let output = match session.contacts.add(label, address)? {
+                Some(previous) => format!("{} is now {} (was {})", label, address, previous),
+                None => format!("Added {} ({})", label, address),
+            };
+            return Ok(ShellAction::Changed(output));
+        }
+        ["contacts", "remove", label] => {
+            let address = session.contacts.remove(label)?;
+            return Ok(ShellAction::Changed(format!("Removed {} ({})", label, address)));
+        }
+        ["wallet"] => match &session.sender {
+            Some(sender) => format!("Sending from {}", contacts.display(sender)),
+            None => "No wallet chosen (wallet <address>)".to_string(),
+        },
+        ["wallet", address] => {
+            let address = contacts.resolve(address).to_string();
+            let output = format!("Sending from {}", contacts.display(&address));
+            session.sender = Some(address);
+            output
+        }
+        ["send", to, amount, rest @ ..] if rest.len() <= 1 => {
+            let sender = session.sender.clone().ok_or("Choose the wallet to send from first (wallet <address>)")?;
+            let amount: f64 = amount.parse().map_err(|_| format!("Invalid amount {}", amount))?;
+            let fee = match rest.first() {
+                Some(fee) => fee.parse().map_err(|_| format!("Invalid fee {}", fee))?,
+                None => chain.get_base_fee() * 2.0,
+            };
+            let mut wallet = Wallet::new(session.keys.get_or_generate(&sender, &mut SimRng::new(entropy())));
+            wallet.sync_pending(chain);
+            let receiver = wallet.resolve_contact(chain, &session.contacts, to)?;
+            let transaction = Transaction::create_payment(&mut wallet.entity, &receiver, amount, fee)?;
+            let output = format!("Submitted {}", summary(&session.contacts, &transaction));
+            chain.submit_transaction(transaction)?;
+            return Ok(ShellAction::Changed(output));
+        }
+        ["validate"] => {
+            chain.validate()?;
+            format!("Chain is valid ({} blocks)", chain.get_blocks().len())
+        }
+        ["mine", rest @ ..] if rest.len() <= 2 => {
+            let count: u32 = match rest.first() {
+                Some(count) => count.parse().map_err(|_| format!("Invalid block count {}", count))?,
+                None => 1,
+            };
+            let miner = rest.get(1).copied().unwrap_or(DEFAULT_MINER);
+            let mut lines = Vec::new();
+            for _ in 0..count {
+                let included = chain.add_block_from_mempool(miner)?;
+                lines.push(format!("Block {} {} ({} transactions)", chain.get_blocks().len() - 1, chain.get_latest_hash(), included));
+            }
+            return Ok(ShellAction::Changed(lines.join("\n")));
+        }
+        [command, ..] => {
+            return match COMMANDS.iter().find(|(name, _)| name == command) {
+                Some((name, arguments)) => Err(format!("Usage: {} {}", name, arguments)),
+                None => Err(format!("Unknown command {} (try help)", command)),
+            };
+        }
+    };
+    Ok(ShellAction::Print(output))
+}
+
+/// Completes the word under the cursor: command names first, then addresses
+/// and contact labels (`balance`, `history`, `mine`, `wallet`, `send`), block
+/// hashes (`block`) or transaction ids (`tx`).
+///
+/// # Arguments
+///
+/// * `chain` - The chain the candidates come from
+/// * `contacts` - The labels to complete along with addresses
+/// * `line` - The line up to the cursor
+///
+/// # Returns
+///
+/// The byte offset where the completed word starts, and the candidates.
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::shell::shell::complete;
+/// use blockc::wallet::contacts::AddressBook;
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// chain.add_block(Vec::new(), "alice").unwrap();
+/// let mut contacts = AddressBook::new();
+/// contacts.add("albert", "5be07c19d2").unwrap();
+///
+/// assert_eq!(complete(&chain, &contacts, "ba"), (0, vec!["balance".to_string()]));
+/// assert_eq!(complete(&chain, &contacts, "balance al"), (8, vec!["albert".to_string(), "alice".to_string()]));
+/// let hash = chain.get_latest_hash();
+/// assert_eq!(complete(&chain, &contacts, &format!("block {}", &hash[..6])).1, vec![hash]);
+/// ```
+pub fn complete(chain: &Blockchain, contacts: &AddressBook, line: &str) -> (usize, Vec<String>) {
+    let start = line.rfind(char::is_whitespace).map(|index| index + 1).unwrap_or(0);
+    let prefix = &line[start..];
+    let previous: Vec<&str> = line[..start].split_whitespace().collect();
+    let candidates: Vec<String> = match previous.as_slice() {
+        [] => COMMANDS.iter().map(|(name, _)| name.to_string()).collect(),
+        ["balance"] | ["history"] | ["mine", _] | ["wallet"] | ["send"] => {
+            let mut candidates = addresses(chain);
+            candidates.extend(contacts.contacts().map(|(label, _)| label.to_string()));
+            candidates.into_iter().collect()
+        }
+        ["block"] => chain.get_blocks().iter().map(|block| block.block_hash.clone()).collect(),
+        ["tx"] => chain.get_blocks().iter()
+            .flat_map(|block| block.transaction.iter())
+            .chain(chain.mempool().transactions())
+            .map(|transaction| transaction.id())
+            .collect(),
+        _ => Vec::new(),
+    };
+    let matches = candidates.into_iter().filter(|candidate| candidate.starts_with(prefix)).collect();
+    (start, matches)
+}
+
+/// Returns every address that sent or received a confirmed transaction.
+fn addresses(chain: &Blockchain) -> BTreeSet<String> {
+    let mut addresses = BTreeSet::new();
+    for transaction in chain.get_blocks().iter().flat_map(|block| block.transaction.iter()) {
+        addresses.insert(transaction.sender_address.clone());
+        addresses.insert(transaction.receiver_address.clone());
+    }
+    addresses.remove(COINBASE_ADDRESS);
+    addresses
+}
+
+/// Finds a block by height or by a unique prefix of its hash.
+fn find_block<'a>(chain: &'a Blockchain, key: &str) -> Result<(usize, &'a Block), String> {
+    if let Ok(height) = key.parse::<usize>()
+        && let Some(block) = chain.get_blocks().get(height) {
+        return Ok((height, block));
+    }
+    let mut found = chain.get_blocks().iter().enumerate().filter(|(_, block)| block.block_hash.starts_with(key));
+    match (found.next(), found.next()) {
+        (Some(block), None) => Ok(block),
+        (Some(_), Some(_)) => Err(format!("Several blocks start with {}", key)),
+        _ => Err(format!("No block {}", key)),
+    }
+}
+
+fn describe_block(contacts: &AddressBook, height: usize, block: &Block) -> String {
+    let mut lines = vec![
+        format!("Block {} {}", height, block.block_hash),
+        format!("Previous {}", block.previous_block_hash),
+        format!("Time {}, nonce {}, base fee {}", block.time_stamp, block.nonce, block.base_fee),
+        format!("Merkle root {}", block.merkle_root),
+    ];
+    lines.extend(block.transaction.iter().map(|transaction| format!("  {}", summary(contacts, transaction))));
+    lines.join("\n")
+}
+
+fn describe_transaction(contacts: &AddressBook, transaction: &Transaction) -> String {
+    let mut lines = vec![
+        format!("Transaction {} ({})", transaction.id(), transaction.kind.name()),
+        format!(
+            "{} -> {} {} (fee {}, nonce {})",
+            contacts.display(&transaction.sender_address), contacts.display(&transaction.receiver_address), transaction.amount, transaction.fee, transaction.nonce
+        ),
+    ];
+    if let Some(memo) = &transaction.memo {
+        lines.push(format!("Memo {}", memo));
+    }
+    lines.join("\n")
+}
+
+fn summary(contacts: &AddressBook, transaction: &Transaction) -> String {
+    format!(
+        "{} {} -> {} {} (fee {})",
+        &transaction.id()[..12], contacts.display(&transaction.sender_address), contacts.display(&transaction.receiver_address), transaction.amount, transaction.fee
+    )
+}
@@ -0,0 +1,204 @@
+use crate::blockchain::blockchain::{Blockchain, DEFAULT_BLOCK_REWARD};
+use crate::entity::entity::Entity;
+use crate::helpers::random::RandomSource;
+use crate::simulation::rng::SimRng;
+use crate::transactions::transactions::{Transaction, TransactionExt};
+
+/// Address mining the honest chain.
+pub const HONEST_MINER: &str = "HonestMiner";
+
+/// Address of the attacker (it mines the secret fork and pays the merchant).
+pub const ATTACKER: &str = "Attacker";
+
+/// Address the attacker sells to.
+pub const MERCHANT: &str = "Merchant";
+
+/// Address receiving the conflicting payment on the secret fork.
+pub const ATTACKER_VAULT: &str = "AttackerVault";
+
+/// Settings of a double-spend attack.
+///
+/// # Fields
+///
+/// * `attacker_hash_share` - Share of the total hash power the attacker controls (`q`, 0 to 1)
+/// * `confirmations` - Blocks the merchant waits for (the payment's block included)
+///   before handing over the goods
+/// * `amount` - Amount paid to the merchant (and double spent)
+/// * `give_up_behind` - The attacker gives up when the fork falls this many blocks behind
+/// * `max_blocks` - The race stops (attack failed) after this many blocks in total
+/// * `difficulty` - Proof-of-work difficulty of both chains (keep it low)
+/// * `seed` - Seed deciding who finds each block
+#[derive(Debug, Clone)]
+pub struct DoubleSpendConfig {
+    pub attacker_hash_share: f64,
+    pub confirmations: u64,
+    pub amount: f64,
+    pub give_up_behind: u64,
+    pub max_blocks: u64,
+    pub difficulty: u32,
+    pub seed: u64,
+}
+
+impl DoubleSpendConfig {
+    /// Attack by `attacker_hash_share` of the hash power against a merchant waiting
+    /// for `confirmations` blocks, with default limits.
+    pub fn new(attacker_hash_share: f64, confirmations: u64, seed: u64) -> Self {
+        DoubleSpendConfig {
+            attacker_hash_share: attacker_hash_share,
+            confirmations: confirmations,
+            amount: 10.0,
+            give_up_behind: 10,
+            max_blocks: 200,
+            difficulty: 1,
+            seed: seed,
+        }
+    }
+}
+
+/// How a double-spend attack ended.
+///
+/// # Fields
+///
+/// * `succeeded` - `true` if the secret fork overtook the honest chain after the
+///   merchant accepted the payment, so honest nodes switch to it and the payment vanishes
+/// * `honest_blocks` - Blocks mined on the honest chain during the race
+/// * `attacker_blocks` - Blocks mined on the secret fork during the race
+/// * `merchant_balance` - What the merchant holds on the winning chain
+#[derive(Debug, Clone, PartialEq)]
+pub struct DoubleSpendOutcome {
+    pub succeeded: bool,
+    pub honest_blocks: u64,
+    pub attacker_blocks: u64,
+    pub merchant_balance: f64,
+}
+
+/// Runs one double-spend attack on real chains.
+///
+/// 1. The attacker mines a block to get coins, then the chain is copied: the
+///    copy is its secret fork
+/// 2. On the honest chain it pays the merchant; on the fork it pays the same
+///    coins (same nonce) to its own vault
+/// 3. Each new block is found by the attacker with probability
+///    `attacker_hash_share`, otherwise by the honest miners
+/// 4. Once the payment has `confirmations`, the merchant delivers; the attack
+///    succeeds if the fork then becomes longer than the honest chain (longest
+///    chain rule) before falling `give_up_behind` blocks behind
+///
+/// # Returns
+///
+/// * `Ok(DoubleSpendOutcome)` - How the race went
+/// * `Err(String)` - If the hash share is not between 0 and 1 or a block is rejected
+///
+/// # Example
+///
+/// ```
+/// use blockc::simulation::double_spend::{simulate, DoubleSpendConfig};
+///
+/// // An attacker with most of the hash power always wins eventually
+/// let strong = simulate(&DoubleSpendConfig::new(0.9, 2, 1)).unwrap();
+/// assert!(strong.succeeded);
+/// assert_eq!(strong.merchant_balance, 0.0);
+///
+/// // One with none never does
+/// let weak = simulate(&DoubleSpendConfig::new(0.0, 2, 1)).unwrap();
+/// assert!(!weak.succeeded);
+/// assert_eq!(weak.merchant_balance, 10.0);
+/// ```
+pub fn simulate(config: &DoubleSpendConfig) -> Result<DoubleSpendOutcome, String> {
+    simulate_with_rng(config, &mut SimRng::new(config.seed))
+}
+
+/// Runs one double-spend attack like `simulate`, drawing the block finders from
+/// `rng` instead of `config.seed`.
+pub fn simulate_with_rng(config: &DoubleSpendConfig, rng: &mut dyn RandomSource) -> Result<DoubleSpendOutcome, String> {
+    if !(0.0..=1.0).contains(&config.attacker_hash_share) {
+        return Err("Attacker hash share must be between 0 and 1".to_string());
+    }
+    let mut honest = Blockchain::with_settings(config.difficulty, DEFAULT_BLOCK_REWARD);
+    honest.add_block(Vec::new(), ATTACKER)?;
+    let mut secret = honest.clone();
+
+    let fee = honest.get_base_fee() * 2.0;
+    let mut attacker = Entity::new(ATTACKER.to_string(), honest.get_balance(ATTACKER), Vec::new(), "attacker".to_string(), "attacker".to_string());
+    let mut attacker_copy = attacker.clone();
+    let mut payment = Some(Transaction::create_payment(&mut attacker, MERCHANT, config.amount, fee)?);
+    let mut double_spend = Some(Transaction::create_payment(&mut attacker_copy, ATTACKER_VAULT, config.amount, fee)?);
+
+    let mut honest_blocks = 0;
+    let mut attacker_blocks = 0;
+    let mut succeeded = false;
+    while honest_blocks + attacker_blocks < config.max_blocks {
+        if rng.chance(config.attacker_hash_share) {
+            secret.add_block(double_spend.take().into_iter().collect(), ATTACKER)?;
+            attacker_blocks += 1;
+        } else {
+            honest.add_block(payment.take().into_iter().collect(), HONEST_MINER)?;
+            honest_blocks += 1;
+        }
+
+        let delivered = honest_blocks >= config.confirmations;
+        if delivered && attacker_blocks > honest_blocks {
+            succeeded = true;
+            break;
+        }
+        if honest_blocks >= attacker_blocks + config.give_up_behind {
+            break;
+        }
+    }
+
+    let winner = if succeeded { &secret } else { &honest };
+    Ok(DoubleSpendOutcome {
+        succeeded: succeeded,
+        honest_blocks: honest_blocks,
+        attacker_blocks: attacker_blocks,
+        merchant_balance: winner.get_balance(MERCHANT),
+    })
+}
+
+/// Runs `trials` attacks (seeds `seed`, `seed + 1`, ...) and returns the share that succeeded.
+pub fn success_rate(config: &DoubleSpendConfig, trials: u64) -> Result<f64, String> {
+    let mut successes = 0;
+    for trial in 0..trials {
+        let mut run = config.clone();
+        run.seed = config.seed.wrapping_add(trial);
+        if simulate(&run)?.succeeded {
+            successes += 1;
+        }
+    }
+    Ok(successes as f64 / trials.max(1) as f64)
+}
+
+/// Probability that an attacker with `attacker_hash_share` of the hash power ever
+/// catches up once the payment has `confirmations` blocks (section 11 of the
+/// Bitcoin paper).
+///
+/// Useful to compare with `success_rate`: the simulation lets the attacker give
+/// up, so it succeeds a bit less often than this.
+///
+/// # Example
+///
+/// ```
+/// use blockc::simulation::double_spend::attack_success_probability;
+///
+/// // With 10% of the hash power, 6 confirmations leave a 0.02% chance
+/// assert!((attack_success_probability(0.1, 6) - 0.0002428).abs() < 1e-6);
+/// assert_eq!(attack_success_probability(0.5, 6), 1.0);
+/// ```
+pub fn attack_success_probability(attacker_hash_share: f64, confirmations: u64) -> f64 {
+    let q = attacker_hash_share;
+    let p = 1.0 - q;
+    if q >= p {
+        return 1.0;
+    }
+    let z = confirmations as f64;
+    let lambda = z * q / p;
+    let mut poisson = (-lambda).exp();
+    let mut sum = 1.0;
+    for k in 0..=confirmations {
+        if k > 0 {
+            poisson *= lambda / k as f64;
+        }
+        sum -= poisson * (1.0 - (q / p).powf(z - k as f64));
+    }
+    sum
+}
@@ -0,0 +1,5 @@
+pub mod double_spend;
+pub mod network;
+pub mod rng;
+pub mod strategies;
+pub mod workload;
@@ -0,0 +1,459 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+use crate::block::block::Block;
+use crate::blockchain::blockchain::Blockchain;
+use crate::helpers::clock::ManualClock;
+use crate::helpers::random::RandomSource;
+use crate::simulation::rng::SimRng;
+use crate::transactions::transactions::Transaction;
+
+/// Approximate size of a block header on the wire, in bytes.
+pub const HEADER_BYTES: u64 = 80;
+
+/// Approximate size of a transaction on the wire, in bytes.
+pub const TRANSACTION_BYTES: u64 = 250;
+
+/// A virtual link between two nodes.
+///
+/// A message sent at time `t` arrives at `t + latency + jitter + size / bandwidth`,
+/// where the jitter is drawn between 0 and `jitter`. Times are in milliseconds.
+///
+/// # Fields
+///
+/// * `latency` - Fixed delay of every message
+/// * `jitter` - Most random delay added on top of the latency
+/// * `bandwidth` - Bytes the link carries per millisecond (0 for unlimited)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinkConfig {
+    pub latency: u64,
+    pub jitter: u64,
+    pub bandwidth: u64,
+}
+
+impl LinkConfig {
+    /// A link with `latency` ms of delay, no jitter and unlimited bandwidth.
+    pub fn new(latency: u64) -> Self {
+        LinkConfig {
+            latency: latency,
+            jitter: 0,
+            bandwidth: 0,
+        }
+    }
+
+    /// Time a message of `size` bytes takes on this link, with `jitter` already drawn.
+    fn delay(&self, size: u64, jitter: u64) -> u64 {
+        let transfer = if self.bandwidth == 0 { 0 } else { size.div_ceil(self.bandwidth) };
+        self.latency + jitter + transfer
+    }
+}
+
+/// What nodes gossip to each other.
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// A new block
+    Block(Block),
+    /// A new pending transaction
+    Transaction(Box<Transaction>),
+}
+
+impl Message {
+    /// Approximate size of the message on the wire, in bytes.
+    pub fn size(&self) -> u64 {
+        match self {
+            Message::Block(block) => HEADER_BYTES + block.transaction.len() as u64 * TRANSACTION_BYTES,
+            Message::Transaction(_) => TRANSACTION_BYTES,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Event {
+    Mine,
+    Deliver { from: usize, to: usize, message: Box<Message> },
+}
+
+/// One full node of the simulated network.
+///
+/// # Fields
+///
+/// * `address` - Address the node mines to
+/// * `hash_power` - Share of the mining done by this node (normalised over all nodes)
+/// * `chain` - The node's best chain
+/// * `known` - Every valid or not yet connected block the node has seen, by hash
+/// * `invalid` - Hashes of blocks that failed validation
+/// * `reorgs` - Times the node switched to another branch
+pub struct SimNode {
+    pub address: String,
+    pub hash_power: f64,
+    chain: Blockchain,
+    known: HashMap<String, Block>,
+    invalid: BTreeSet<String>,
+    reorgs: u64,
+}
+
+impl SimNode {
+    /// Returns the node's best chain.
+    pub fn chain(&self) -> &Blockchain {
+        &self.chain
+    }
+
+    /// Height of the node's best chain.
+    pub fn height(&self) -> u64 {
+        self.chain.get_blocks().len() as u64 - 1
+    }
+
+    /// Hash of the tip of the node's best chain.
+    pub fn tip(&self) -> String {
+        self.chain.get_latest_hash()
+    }
+
+    /// Times the node switched to another branch.
+    pub fn reorgs(&self) -> u64 {
+        self.reorgs
+    }
+}
+
+/// N full nodes in one process, connected by virtual links, on a simulated clock.
+///
+/// Every node starts from the same genesis chain. Blocks are found at random
+/// times (exponentially distributed around `block_interval`) by a node picked in
+/// proportion to its hash power; blocks and transactions are gossiped over the
+/// links, and each node follows the longest branch it has fully received (the
+/// first one seen wins ties). Nodes in different partitions can't reach each
+/// other: messages between them are dropped, even those already in flight.
+///
+/// Everything random comes from the seed, so a run can be replayed exactly.
+/// Every node's chain reads the simulated time from a shared `ManualClock`, so
+/// blocks are stamped with the time they were found in the simulation.
+///
+/// # Fields
+///
+/// * `block_interval` - Mean time between two blocks, in milliseconds
+/// * `genesis` - Chain every node started from (used to rebuild after a reorg)
+/// * `nodes` - The nodes, by index
+/// * `links` - Link between two nodes, keyed by (lower index, higher index)
+/// * `partitions` - Partition of every node (all in partition 0 when the network is whole)
+/// * `now` - Simulated time, in milliseconds
+/// * `clock` - Clock of every node's chain: the genesis time plus `now`, in seconds
+/// * `events` - Pending events, by (time, sequence number)
+/// * `next_event` - Sequence number of the next event (keeps same-time events in order)
+/// * `rng` - Decides block times, finders and jitter
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::simulation::network::{LinkConfig, NetworkSimulator};
+///
+/// let mut network = NetworkSimulator::new(Blockchain::with_settings(1, 50.0), 4, 1_000, 7);
+/// network.connect_all(LinkConfig::new(50));
+///
+/// // Split the network in two: each side keeps mining its own branch
+/// network.partition(&[vec![0, 1], vec![2, 3]]);
+/// network.run_until(20_000);
+/// assert_ne!(network.node(0).tip(), network.node(2).tip());
+///
+/// // Heal it: the shorter branch is abandoned and every node converges
+/// network.heal();
+/// network.run_until(20_500);
+/// assert!(network.converged());
+/// ```
+pub struct NetworkSimulator {
+    pub block_interval: u64,
+    genesis: Blockchain,
+    nodes: Vec<SimNode>,
+    links: BTreeMap<(usize, usize), LinkConfig>,
+    partitions: Vec<usize>,
+    now: u64,
+    clock: ManualClock,
+    events: BTreeMap<(u64, u64), Event>,
+    next_event: u64,
+    rng: Box<dyn RandomSource>,
+}
+
+impl NetworkSimulator {
+    /// Creates `nodes` unconnected nodes with equal hash power, all starting from `genesis`.
+    ///
+    /// Give `genesis` a low difficulty: every simulated block is really mined.
+    pub fn new(genesis: Blockchain, nodes: usize, block_interval: u64, seed: u64) -> Self {
+        NetworkSimulator::with_rng(genesis, nodes, block_interval, Box::new(SimRng::new(seed)))
+    }
+
+    /// Creates the nodes like `new`, drawing every random choice from `rng`.
+    pub fn with_rng(mut genesis: Blockchain, nodes: usize, block_interval: u64, rng: Box<dyn RandomSource>) -> Self {
+        let clock = ManualClock::new(genesis.get_latest_block().time_stamp);
+        genesis.set_clock(Arc::new(clock.clone()));
+        let mut simulator = NetworkSimulator {
+            block_interval: block_interval.max(1),
+            nodes: (0..nodes).map(|index| SimNode {
+                address: format!("Node{}", index),
+                hash_power: 1.0,
+                chain: genesis.clone(),
+                known: HashMap::new(),
+                invalid: BTreeSet::new(),
+                reorgs: 0,
+            }).collect(),
+            genesis: genesis,
+            links: BTreeMap::new(),
+            partitions: vec![0; nodes],
+            now: 0,
+            clock: clock,
+            events: BTreeMap::new(),
+            next_event: 0,
+            rng: rng,
+        };
+        simulator.schedule_mining();
+        simulator
+    }
+
+    /// Returns node `index`.
+    pub fn node(&self, index: usize) -> &SimNode {
+        &self.nodes[index]
+    }
+
+    /// Returns every node.
+    pub fn nodes(&self) -> &[SimNode] {
+        &self.nodes
+    }
+
+    /// Sets the hash power of node `index` (0 to stop it mining).
+    pub fn set_hash_power(&mut self, index: usize, hash_power: f64) {
+        self.nodes[index].hash_power = hash_power.max(0.0);
+    }
+
+    /// Current simulated time, in milliseconds.
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    /// Connects nodes `a` and `b` (replacing any previous link between them).
+    pub fn connect(&mut self, a: usize, b: usize, link: LinkConfig) {
+        if a != b {
+            self.links.insert((a.min(b), a.max(b)), link);
+        }
+    }
+
+    /// Connects every pair of nodes with the same kind of link.
+    pub fn connect_all(&mut self, link: LinkConfig) {
+        for a in 0..self.nodes.len() {
+            for b in a + 1..self.nodes.len() {
+                self.connect(a, b, link);
+            }
+        }
+    }
+
+    /// Removes the link between nodes `a` and `b`.
+    pub fn disconnect(&mut self, a: usize, b: usize) {
+        self.links.remove(&(a.min(b), a.max(b)));
+    }
+
+    /// Splits the network: nodes of different groups can't reach each other.
+    ///
+    /// Nodes left out of every group form one more group.
+    pub fn partition(&mut self, groups: &[Vec<usize>]) {
+        self.partitions = vec![groups.len(); self.nodes.len()];
+        for (group, members) in groups.iter().enumerate() {
+            for &node in members {
+                if let Some(partition) = self.partitions.get_mut(node) {
+                    *partition = group;
+                }
+            }
+        }
+    }
+
+    /// Reconnects every partition; each node announces its whole branch to its
+    /// neighbours so the others can catch up.
+    pub fn heal(&mut self) {
+        self.partitions = vec![0; self.nodes.len()];
+        for node in 0..self.nodes.len() {
+            let blocks: Vec<Block> = self.nodes[node].chain.get_blocks().iter().skip(1).cloned().collect();
+            for block in blocks {
+                self.broadcast(node, None, Message::Block(block));
+            }
+        }
+    }
+
+    /// Hands `transaction` to node `index`, which gossips it if it accepts it.
+    pub fn submit_transaction(&mut self, index: usize, transaction: Transaction) -> Result<(), String> {
+        self.nodes[index].chain.submit_transaction(transaction.clone())?;
+        self.broadcast(index, None, Message::Transaction(Box::new(transaction)));
+        Ok(())
+    }
+
+    /// Processes every event up to `time` (mining, message deliveries), then
+    /// moves the clock to `time`.
+    pub fn run_until(&mut self, time: u64) {
+        while let Some(entry) = self.events.first_entry() {
+            if entry.key().0 > time {
+                break;
+            }
+            let ((at, _), event) = entry.remove_entry();
+            self.advance_to(at);
+            match event {
+                Event::Mine => {
+                    self.mine();
+                    self.schedule_mining();
+                }
+                Event::Deliver { from, to, message } => {
+                    if self.partitions[from] == self.partitions[to] {
+                        self.receive(to, from, *message);
+                    }
+                }
+            }
+        }
+        self.advance_to(self.now.max(time));
+    }
+
+    /// Returns `true` if every node has the same tip.
+    pub fn converged(&self) -> bool {
+        self.nodes.windows(2).all(|pair| pair[0].tip() == pair[1].tip())
+    }
+
+    fn advance_to(&mut self, time: u64) {
+        let seconds = (time / 1_000 - self.now / 1_000) as u32;
+        self.clock.advance(seconds);
+        self.now = time;
+    }
+
+    fn schedule(&mut self, at: u64, event: Event) {
+        self.events.insert((at, self.next_event), event);
+        self.next_event += 1;
+    }
+
+    fn schedule_mining(&mut self) {
+        // Exponential wait: blocks are found at a constant rate, independently
+        let wait = -(1.0 - self.rng.next_f64()).ln() * self.block_interval as f64;
+        let at = self.now + (wait.round() as u64).max(1);
+        self.schedule(at, Event::Mine);
+    }
+
+    fn mine(&mut self) {
+        let total: f64 = self.nodes.iter().map(|node| node.hash_power).sum();
+        if total <= 0.0 {
+            return;
+        }
+        let mut target = self.rng.next_f64() * total;
+        let mut finder = self.nodes.len() - 1;
+        for (index, node) in self.nodes.iter().enumerate() {
+            if target < node.hash_power {
+                finder = index;
+                break;
+            }
+            target -= node.hash_power;
+        }
+
+        let node = &mut self.nodes[finder];
+        let address = node.address.clone();
+        if node.chain.add_block_from_mempool(&address).is_err() {
+            return;
+        }
+        let block = node.chain.get_latest_block().clone();
+        node.known.insert(block.block_hash.clone(), block.clone());
+        self.broadcast(finder, None, Message::Block(block));
+    }
+
+    fn broadcast(&mut self, from: usize, except: Option<usize>, message: Message) {
+        let neighbours: Vec<(usize, LinkConfig)> = self.links.iter()
+            .filter_map(|(&(a, b), link)| match (a == from, b == from) {
+                (true, _) => Some((b, *link)),
+                (_, true) => Some((a, *link)),
+                _ => None,
+            })
+            .filter(|(to, _)| Some(*to) != except && self.partitions[*to] == self.partitions[from])
+            .collect();
+        for (to, link) in neighbours {
+            let jitter = if link.jitter == 0 { 0 } else { self.rng.below(link.jitter + 1) };
+            let at = self.now + link.delay(message.size(), jitter);
+            self.schedule(at, Event::Deliver { from: from, to: to, message: Box::new(message.clone()) });
+        }
+    }
+
+    fn receive(&mut self, to: usize, from: usize, message: Message) {
+        match message {
+            Message::Transaction(transaction) => {
+                if self.nodes[to].chain.submit_transaction((*transaction).clone()).is_ok() {
+                    self.broadcast(to, Some(from), Message::Transaction(transaction));
+                }
+            }
+            Message::Block(block) => {
+                let node = &mut self.nodes[to];
+                if node.known.contains_key(&block.block_hash) || node.invalid.contains(&block.block_hash) {
+                    return;
+                }
+                node.known.insert(block.block_hash.clone(), block.clone());
+                self.broadcast(to, Some(from), Message::Block(block));
+                self.choose_best_branch(to);
+            }
+        }
+    }
+
+    /// Returns the blocks from the genesis (excluded) to `tip`, or `None` if one
+    /// of them hasn't been received yet or is invalid.
+    fn branch(&self, node: &SimNode, tip: &str) -> Option<Vec<Block>> {
+        let genesis_hash = &self.genesis.get_blocks()[0].block_hash;
+        let mut branch = Vec::new();
+        let mut current = tip.to_string();
+        while current != *genesis_hash {
+            if node.invalid.contains(&current) {
+                return None;
+            }
+            let block = node.known.get(&current)?;
+            current = block.previous_block_hash.clone();
+            branch.push(block.clone());
+        }
+        branch.reverse();
+        Some(branch)
+    }
+
+    fn choose_best_branch(&mut self, index: usize) {
+        loop {
+            let node = &self.nodes[index];
+            let best = node.known.keys()
+                .filter_map(|hash| self.branch(node, hash))
+                .filter(|branch| branch.len() as u64 > node.height())
+                .max_by(|a, b| a.len().cmp(&b.len()).then_with(|| b.last().map(|block| &block.block_hash).cmp(&a.last().map(|block| &block.block_hash))));
+            let Some(branch) = best else {
+                return;
+            };
+            if self.switch_to(index, branch) {
+                return;
+            }
+        }
+    }
+
+    /// Switches node `index` to `branch`, extending its chain if the branch builds
+    /// on its tip and rebuilding it from the genesis otherwise.
+    ///
+    /// Returns `false` (and marks the offending block invalid) if a block is rejected.
+    fn switch_to(&mut self, index: usize, branch: Vec<Block>) -> bool {
+        let node = &mut self.nodes[index];
+        let current: Vec<String> = node.chain.get_blocks().iter().map(|block| block.block_hash.clone()).collect();
+        let extends = branch.len() >= current.len() - 1
+            && branch.iter().zip(current.iter().skip(1)).all(|(block, hash)| block.block_hash == *hash);
+
+        let mut chain = if extends { node.chain.clone() } else { self.genesis.clone() };
+        let skip = if extends { current.len() - 1 } else { 0 };
+        for block in branch.into_iter().skip(skip) {
+            let hash = block.block_hash.clone();
+            if chain.submit_block(block).is_err() {
+                node.invalid.insert(hash);
+                return false;
+            }
+        }
+        if !extends {
+            // Transactions of the abandoned blocks go back to the mempool if still valid
+            let pending: Vec<Transaction> = node.chain.get_blocks().iter()
+                .flat_map(|block| block.transaction.iter())
+                .filter(|transaction| !transaction.is_coinbase())
+                .chain(node.chain.mempool().transactions())
+                .cloned()
+                .collect();
+            for transaction in pending {
+                let _ = chain.submit_transaction(transaction);
+            }
+            node.reorgs += 1;
+        }
+        node.chain = chain;
+        true
+    }
+}
@@ -0,0 +1,44 @@
+use crate::helpers::random::RandomSource;
+
+/// Small deterministic random number generator (SplitMix64) for simulations.
+///
+/// The same seed always gives the same sequence, so a simulation can be replayed
+/// exactly; it is not suitable for anything cryptographic. Its methods come
+/// from `RandomSource`.
+///
+/// # Example
+///
+/// ```
+/// use blockc::helpers::random::RandomSource;
+/// use blockc::simulation::rng::SimRng;
+///
+/// let mut a = SimRng::new(7);
+/// let mut b = SimRng::new(7);
+/// assert_eq!(a.next_u64(), b.next_u64());
+///
+/// let x = a.next_f64();
+/// assert!((0.0..1.0).contains(&x));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SimRng {
+    state: u64,
+}
+
+impl SimRng {
+    /// Creates a generator from `seed`.
+    pub fn new(seed: u64) -> Self {
+        SimRng {
+            state: seed,
+        }
+    }
+}
+
+impl RandomSource for SimRng {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
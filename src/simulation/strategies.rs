@@ -0,0 +1,397 @@
+use std::collections::BTreeMap;
+use crate::helpers::random::RandomSource;
+use crate::simulation::rng::SimRng;
+
+/// What a miner sees when it decides what to publish.
+///
+/// # Fields
+///
+/// * `lead` - Height of the miner's branch minus the height of the public chain
+/// * `withheld` - Blocks the miner found but hasn't published yet
+/// * `mined` - `true` if the miner just found a block, `false` if the public chain changed
+/// * `racing` - `true` if the miner just found a block on one of two tied public tips
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StrategyView {
+    pub lead: i64,
+    pub withheld: usize,
+    pub mined: bool,
+    pub racing: bool,
+}
+
+/// What a miner does after an event.
+///
+/// * `Wait` - Keep withheld blocks private
+/// * `Publish` - Publish this many withheld blocks (oldest first)
+/// * `PublishAll` - Publish every withheld block
+/// * `Adopt` - Drop the private branch and mine on the public chain
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Wait,
+    Publish(usize),
+    PublishAll,
+    Adopt,
+}
+
+/// How a miner decides which blocks to publish.
+///
+/// The simulation asks after every block the miner finds and every change of
+/// the public chain. A miner mines on its own latest block while it has one at
+/// least as high as the public chain, and on the public chain otherwise.
+pub trait MinerStrategy {
+    /// Name the strategy is reported under.
+    fn name(&self) -> &str;
+
+    /// Decides what to publish after an event.
+    fn decide(&mut self, view: &StrategyView) -> Action;
+
+    /// `true` if the miner always mines on the public chain (honest miners do):
+    /// when two tips are tied, it picks one with the simulation's tie rule.
+    fn follows_public_chain(&self) -> bool {
+        false
+    }
+}
+
+/// Publishes every block as soon as it is found and mines on the public chain.
+#[derive(Debug, Clone, Default)]
+pub struct Honest;
+
+impl MinerStrategy for Honest {
+    fn name(&self) -> &str {
+        "honest"
+    }
+
+    fn decide(&mut self, _view: &StrategyView) -> Action {
+        Action::PublishAll
+    }
+
+    fn follows_public_chain(&self) -> bool {
+        true
+    }
+}
+
+/// Selfish mining (Eyal and Sirer, 2013).
+///
+/// Keeps found blocks private and publishes just enough of them to orphan the
+/// honest miners' work:
+/// * Behind the public chain - give up and adopt it
+/// * Level with it after the others caught up - publish everything and race
+/// * One block ahead after the others caught up - publish everything and win
+/// * Further ahead - publish up to the public height, keep the rest
+/// * Winning a race with a new block - publish it
+#[derive(Debug, Clone, Default)]
+pub struct Selfish;
+
+impl MinerStrategy for Selfish {
+    fn name(&self) -> &str {
+        "selfish"
+    }
+
+    fn decide(&mut self, view: &StrategyView) -> Action {
+        if view.mined {
+            return if view.racing { Action::PublishAll } else { Action::Wait };
+        }
+        match view.lead {
+            lead if lead < 0 => Action::Adopt,
+            0 | 1 => Action::PublishAll,
+            lead => Action::Publish(view.withheld.saturating_sub(lead as usize)),
+        }
+    }
+}
+
+/// Majority (51%) attacker: mines a private branch and publishes it once it is
+/// `release_lead` blocks ahead, orphaning that many honest blocks at once.
+///
+/// # Fields
+///
+/// * `release_lead` - Lead at which the private branch is published
+/// * `give_up_behind` - The branch is dropped when it falls this many blocks behind
+#[derive(Debug, Clone)]
+pub struct MajorityAttacker {
+    pub release_lead: i64,
+    pub give_up_behind: i64,
+}
+
+impl MajorityAttacker {
+    /// Attacker publishing once `release_lead` blocks ahead, giving up 10 blocks behind.
+    pub fn new(release_lead: i64) -> Self {
+        MajorityAttacker {
+            release_lead: release_lead.max(1),
+            give_up_behind: 10,
+        }
+    }
+}
+
+impl MinerStrategy for MajorityAttacker {
+    fn name(&self) -> &str {
+        "majority"
+    }
+
+    fn decide(&mut self, view: &StrategyView) -> Action {
+        if view.lead >= self.release_lead {
+            Action::PublishAll
+        } else if view.lead <= -self.give_up_behind {
+            Action::Adopt
+        } else {
+            Action::Wait
+        }
+    }
+}
+
+/// Result of one strategy over a simulation (several miners can share a strategy).
+///
+/// # Fields
+///
+/// * `hash_share` - Share of the hash power of the miners using it
+/// * `blocks_mined` - Blocks they found
+/// * `blocks_in_chain` - Of those, blocks in the final chain
+/// * `revenue_share` - Share of the final chain's blocks (and rewards) they mined
+/// * `orphan_rate` - Share of their blocks left out of the final chain
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategyReport {
+    pub hash_share: f64,
+    pub blocks_mined: u64,
+    pub blocks_in_chain: u64,
+    pub revenue_share: f64,
+    pub orphan_rate: f64,
+}
+
+#[derive(Debug, Clone)]
+struct SimBlock {
+    parent: Option<usize>,
+    height: u64,
+    miner: usize,
+}
+
+struct SimMiner {
+    hash_share: f64,
+    strategy: Box<dyn MinerStrategy>,
+    head: usize,
+    withheld: Vec<usize>,
+}
+
+/// Many rounds of mining between miners with different strategies.
+///
+/// Blocks are abstract (no transactions or proof of work): each round one miner,
+/// picked in proportion to its hash power, finds a block. The public chain is
+/// the longest published branch, the first one seen winning ties; while two tips
+/// are tied, a `tie_share` of honest blocks go to the later one (the "gamma" of
+/// the selfish mining paper).
+///
+/// # Fields
+///
+/// * `tie_share` - Chance an honest miner picks the later of two tied tips
+/// * `blocks` - Every block found (0 is the genesis)
+/// * `miners` - The miners, in the order they were added
+/// * `best` - Tip of the public chain
+/// * `tied` - A later published tip as high as `best`, if any
+/// * `rng` - Decides who finds each block and how ties go
+///
+/// # Example
+///
+/// ```
+/// use blockc::simulation::strategies::{Honest, MiningSimulation, Selfish};
+///
+/// // A selfish miner with a third of the hash power and every tie
+/// let mut simulation = MiningSimulation::new(1.0, 42);
+/// simulation.add_miner(1.0 / 3.0, Box::new(Selfish));
+/// simulation.add_miner(2.0 / 3.0, Box::new(Honest));
+/// let report = simulation.run(20_000);
+///
+/// // It earns more than its fair share, and honest miners lose blocks to it
+/// assert!(report["selfish"].revenue_share > 0.4);
+/// assert!(report["honest"].orphan_rate > 0.1);
+/// ```
+pub struct MiningSimulation {
+    pub tie_share: f64,
+    blocks: Vec<SimBlock>,
+    miners: Vec<SimMiner>,
+    best: usize,
+    tied: Option<usize>,
+    rng: Box<dyn RandomSource>,
+}
+
+impl MiningSimulation {
+    /// Creates a simulation with only the genesis block.
+    pub fn new(tie_share: f64, seed: u64) -> Self {
+        MiningSimulation::with_rng(tie_share, Box::new(SimRng::new(seed)))
+    }
+
+    /// Creates a simulation drawing its randomness from `rng` instead of a seed.
+    pub fn with_rng(tie_share: f64, rng: Box<dyn RandomSource>) -> Self {
+        MiningSimulation {
+            tie_share: tie_share,
+            blocks: vec![SimBlock { parent: None, height: 0, miner: usize::MAX }],
+            miners: Vec::new(),
+            best: 0,
+            tied: None,
+            rng: rng,
+        }
+    }
+
+    /// Adds a miner with `hash_share` of the hash power (shares are normalised
+    /// over all miners).
+    pub fn add_miner(&mut self, hash_share: f64, strategy: Box<dyn MinerStrategy>) {
+        self.miners.push(SimMiner {
+            hash_share: hash_share.max(0.0),
+            strategy: strategy,
+            head: self.best,
+            withheld: Vec::new(),
+        });
+    }
+
+    /// Height of the public chain.
+    pub fn public_height(&self) -> u64 {
+        self.blocks[self.best].height
+    }
+
+    /// Runs `rounds` blocks, then reports on every strategy.
+    ///
+    /// Blocks still withheld at the end are counted as orphaned.
+    pub fn run(&mut self, rounds: u64) -> BTreeMap<String, StrategyReport> {
+        for _ in 0..rounds {
+            self.round();
+        }
+        self.report()
+    }
+
+    fn round(&mut self) {
+        let Some(index) = self.pick_miner() else {
+            return;
+        };
+        let parent = self.mining_parent(index);
+        let racing = self.tied.is_some() && (parent == self.best || Some(parent) == self.tied);
+        let block = self.blocks.len();
+        self.blocks.push(SimBlock { parent: Some(parent), height: self.blocks[parent].height + 1, miner: index });
+        self.miners[index].head = block;
+        self.miners[index].withheld.push(block);
+
+        let public_before = (self.best, self.tied);
+        let view = self.view(index, true, racing);
+        let action = self.miners[index].strategy.decide(&view);
+        self.act(index, action);
+
+        // Let the other miners react until nobody changes the public chain
+        let mut public = public_before;
+        while (self.best, self.tied) != public {
+            public = (self.best, self.tied);
+            for other in 0..self.miners.len() {
+                if self.miners[other].withheld.is_empty() {
+                    self.follow_public(other);
+                    continue;
+                }
+                let view = self.view(other, false, false);
+                let action = self.miners[other].strategy.decide(&view);
+                self.act(other, action);
+            }
+        }
+    }
+
+    fn pick_miner(&mut self) -> Option<usize> {
+        let total: f64 = self.miners.iter().map(|miner| miner.hash_share).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut target = self.rng.next_f64() * total;
+        for (index, miner) in self.miners.iter().enumerate() {
+            if target < miner.hash_share {
+                return Some(index);
+            }
+            target -= miner.hash_share;
+        }
+        Some(self.miners.len() - 1)
+    }
+
+    fn mining_parent(&mut self, index: usize) -> usize {
+        let miner = &self.miners[index];
+        if !miner.strategy.follows_public_chain() {
+            return miner.head;
+        }
+        match self.tied {
+            Some(tied) if self.rng.chance(self.tie_share) => tied,
+            _ => self.best,
+        }
+    }
+
+    fn view(&self, index: usize, mined: bool, racing: bool) -> StrategyView {
+        let miner = &self.miners[index];
+        StrategyView {
+            lead: self.blocks[miner.head].height as i64 - self.public_height() as i64,
+            withheld: miner.withheld.len(),
+            mined: mined,
+            racing: racing,
+        }
+    }
+
+    fn act(&mut self, index: usize, action: Action) {
+        let count = match action {
+            Action::Wait => 0,
+            Action::Publish(count) => count,
+            Action::PublishAll => self.miners[index].withheld.len(),
+            Action::Adopt => {
+                self.miners[index].withheld.clear();
+                self.miners[index].head = self.best;
+                0
+            }
+        };
+        let count = count.min(self.miners[index].withheld.len());
+        let published: Vec<usize> = self.miners[index].withheld.drain(..count).collect();
+        for block in published {
+            self.publish(block);
+        }
+    }
+
+    fn publish(&mut self, block: usize) {
+        let height = self.blocks[block].height;
+        if height > self.public_height() {
+            self.best = block;
+            self.tied = None;
+        } else if height == self.public_height() && block != self.best && self.tied.is_none() {
+            self.tied = Some(block);
+        }
+    }
+
+    fn follow_public(&mut self, index: usize) {
+        let miner = &mut self.miners[index];
+        if self.blocks[miner.head].height < self.blocks[self.best].height {
+            miner.head = self.best;
+        }
+    }
+
+    fn report(&self) -> BTreeMap<String, StrategyReport> {
+        let mut in_chain = vec![false; self.blocks.len()];
+        let mut current = Some(self.best);
+        while let Some(block) = current {
+            in_chain[block] = true;
+            current = self.blocks[block].parent;
+        }
+        let chain_length = self.public_height().max(1) as f64;
+        let total_hash: f64 = self.miners.iter().map(|miner| miner.hash_share).sum::<f64>().max(f64::MIN_POSITIVE);
+
+        let mut reports: BTreeMap<String, StrategyReport> = BTreeMap::new();
+        for (index, miner) in self.miners.iter().enumerate() {
+            let report = reports.entry(miner.strategy.name().to_string()).or_insert(StrategyReport {
+                hash_share: 0.0,
+                blocks_mined: 0,
+                blocks_in_chain: 0,
+                revenue_share: 0.0,
+                orphan_rate: 0.0,
+            });
+            report.hash_share += miner.hash_share / total_hash;
+            for (block, data) in self.blocks.iter().enumerate() {
+                if data.miner == index {
+                    report.blocks_mined += 1;
+                    if in_chain[block] {
+                        report.blocks_in_chain += 1;
+                    }
+                }
+            }
+        }
+        for report in reports.values_mut() {
+            report.revenue_share = report.blocks_in_chain as f64 / chain_length;
+            if report.blocks_mined > 0 {
+                report.orphan_rate = 1.0 - report.blocks_in_chain as f64 / report.blocks_mined as f64;
+            }
+        }
+        reports
+    }
+}
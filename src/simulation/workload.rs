@@ -0,0 +1,311 @@
+use crate::blockchain::blockchain::Blockchain;
+use crate::entity::entity::Entity;
+use crate::helpers::random::RandomSource;
+use crate::simulation::rng::SimRng;
+use crate::transactions::transactions::{Transaction, TransactionExt};
+use crate::wallet::wallet::Wallet;
+
+/// Address mining the coins that fund the wallets.
+pub const FAUCET: &str = "Faucet";
+
+/// Settings of a synthetic workload.
+///
+/// # Fields
+///
+/// * `wallets` - Number of wallets sending and receiving payments
+/// * `zipf_exponent` - Skew of the senders: wallet `i` sends with weight
+///   `1 / (i + 1)^zipf_exponent` (0 is uniform, 1 is a classic Zipf law)
+/// * `transactions_per_tick` - Transactions generated by a normal tick
+/// * `burst_chance` - Chance a tick is a burst
+/// * `burst_factor` - A burst generates this many times more transactions
+/// * `invalid_ratio` - Share of transactions deliberately made invalid
+/// * `min_amount` - Smallest payment
+/// * `max_amount` - Largest payment
+/// * `funding` - Coins every wallet receives from the faucet
+/// * `seed` - Seed of the keys and of every random choice
+#[derive(Debug, Clone)]
+pub struct WorkloadConfig {
+    pub wallets: usize,
+    pub zipf_exponent: f64,
+    pub transactions_per_tick: usize,
+    pub burst_chance: f64,
+    pub burst_factor: usize,
+    pub invalid_ratio: f64,
+    pub min_amount: f64,
+    pub max_amount: f64,
+    pub funding: f64,
+    pub seed: u64,
+}
+
+impl WorkloadConfig {
+    /// `wallets` wallets generating `transactions_per_tick` transactions, with
+    /// Zipf-distributed senders, occasional bursts and 5% invalid transactions.
+    pub fn new(wallets: usize, transactions_per_tick: usize, seed: u64) -> Self {
+        WorkloadConfig {
+            wallets: wallets,
+            zipf_exponent: 1.0,
+            transactions_per_tick: transactions_per_tick,
+            burst_chance: 0.1,
+            burst_factor: 5,
+            invalid_ratio: 0.05,
+            min_amount: 0.01,
+            max_amount: 1.0,
+            funding: 20.0,
+            seed: seed,
+        }
+    }
+}
+
+/// Counters of what a workload generated and how the chain took it.
+///
+/// # Fields
+///
+/// * `ticks` - Ticks generated
+/// * `bursts` - Ticks that were bursts
+/// * `valid` - Valid transactions generated
+/// * `invalid` - Invalid transactions generated
+/// * `accepted` - Transactions the mempool accepted
+/// * `rejected` - Transactions the mempool rejected
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorkloadStats {
+    pub ticks: u64,
+    pub bursts: u64,
+    pub valid: u64,
+    pub invalid: u64,
+    pub accepted: u64,
+    pub rejected: u64,
+}
+
+/// Ways a generated transaction is made invalid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Invalid {
+    Overspend,
+    LowFee,
+    StaleNonce,
+}
+
+/// Generator of realistic transaction streams for benchmarks and stress tests.
+///
+/// A population of wallets (keys drawn from the seed) is funded by the faucet,
+/// then every tick produces a batch of payments between them: senders follow a
+/// Zipf distribution (a few wallets send most transactions), some ticks are
+/// bursts, and a share of the transactions are invalid (overspends, fees below
+/// the base fee, reused nonces) so the rejection paths are exercised too.
+///
+/// # Fields
+///
+/// * `config` - The workload's settings
+/// * `wallets` - The wallets, most active first
+/// * `sender_weights` - Cumulative Zipf weights of the senders
+/// * `stats` - Counters since the workload was created
+/// * `rng` - Decides every choice
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::simulation::workload::{Workload, WorkloadConfig};
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// let mut workload = Workload::new(WorkloadConfig::new(20, 10, 3));
+/// workload.fund(&mut chain).unwrap();
+/// assert_eq!(chain.get_balance(&workload.wallets()[0].address), 20.0);
+///
+/// for _ in 0..5 {
+///     workload.feed(&mut chain);
+///     chain.add_block_from_mempool("Miner").unwrap();
+///     workload.sync(&chain);
+/// }
+///
+/// // Only the deliberately invalid transactions were rejected
+/// let stats = workload.stats();
+/// assert!(stats.valid > stats.invalid);
+/// assert_eq!(stats.rejected, stats.invalid);
+/// ```
+pub struct Workload {
+    pub config: WorkloadConfig,
+    wallets: Vec<Entity>,
+    sender_weights: Vec<f64>,
+    stats: WorkloadStats,
+    rng: Box<dyn RandomSource>,
+}
+
+impl Workload {
+    /// Creates the wallets of `config` (addresses `Wallet0`, `Wallet1`, ...).
+    pub fn new(config: WorkloadConfig) -> Self {
+        let rng = Box::new(SimRng::new(config.seed));
+        Workload::with_rng(config, rng)
+    }
+
+    /// Creates the wallets of `config`, drawing keys and choices from `rng`.
+    pub fn with_rng(config: WorkloadConfig, mut rng: Box<dyn RandomSource>) -> Self {
+        let wallets: Vec<Entity> = (0..config.wallets)
+            .map(|index| Entity::generate(format!("Wallet{}", index), 0.0, rng.as_mut()))
+            .collect();
+        let mut total = 0.0;
+        let sender_weights = (0..config.wallets)
+            .map(|index| {
+                total += 1.0 / ((index + 1) as f64).powf(config.zipf_exponent);
+                total
+            })
+            .collect();
+        Workload {
+            config: config,
+            wallets: wallets,
+            sender_weights: sender_weights,
+            stats: WorkloadStats::default(),
+            rng: rng,
+        }
+    }
+
+    /// Returns the wallets, most active first.
+    pub fn wallets(&self) -> &[Entity] {
+        &self.wallets
+    }
+
+    /// Returns the counters since the workload was created.
+    pub fn stats(&self) -> &WorkloadStats {
+        &self.stats
+    }
+
+    /// Gives every wallet `funding` coins: the faucet mines enough blocks, pays
+    /// the wallets with batched transactions and mines them.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Every wallet is funded (and synced)
+    /// * `Err(String)` - If a block or a funding transaction is rejected
+    pub fn fund(&mut self, chain: &mut Blockchain) -> Result<(), String> {
+        let payments: Vec<(String, f64)> = self.wallets.iter()
+            .map(|wallet| (wallet.address.clone(), self.config.funding))
+            .collect();
+        let mut faucet = Wallet::new(Entity::new(FAUCET.to_string(), 0.0, Vec::new(), FAUCET.to_string(), FAUCET.to_string()));
+        let fee = chain.get_base_fee() * 2.0;
+        let groups = payments.len().div_ceil(faucet.max_outputs_per_transaction.max(1));
+        let needed = self.config.funding * payments.len() as f64 + fee * groups as f64;
+        while chain.get_balance(FAUCET) < needed {
+            chain.add_block(Vec::new(), FAUCET)?;
+        }
+
+        faucet.sync(chain);
+        for transaction in faucet.batch_send(payments, fee)?.transactions {
+            chain.submit_transaction(transaction)?;
+        }
+        while !chain.mempool().is_empty() {
+            chain.add_block_from_mempool(FAUCET)?;
+        }
+        self.sync(chain);
+        Ok(())
+    }
+
+    /// Refreshes every wallet's balance and nonce from `chain`, counting the
+    /// transactions it still has in the mempool.
+    pub fn sync(&mut self, chain: &Blockchain) {
+        for wallet in &mut self.wallets {
+            let pending: Vec<&Transaction> = chain.mempool().transactions().iter()
+                .filter(|transaction| transaction.sender_address == wallet.address)
+                .collect();
+            let spent: f64 = pending.iter().map(|transaction| transaction.total_cost()).sum();
+            wallet.balance = chain.state().get_spendable_balance(&wallet.address) - spent;
+            wallet.nonce = chain.get_nonce(&wallet.address) + pending.len() as u64;
+        }
+    }
+
+    /// Generates the transactions of one tick against the current state of `chain`.
+    ///
+    /// Valid transactions update their sender's local balance and nonce; invalid
+    /// ones are signed by a copy so the wallet's next transaction stays valid. A
+    /// sender that can't afford a valid payment skips its turn.
+    pub fn next_tick(&mut self, chain: &Blockchain) -> Vec<Transaction> {
+        let mut count = self.config.transactions_per_tick;
+        self.stats.ticks += 1;
+        if self.rng.chance(self.config.burst_chance) {
+            count *= self.config.burst_factor.max(1);
+            self.stats.bursts += 1;
+        }
+        if self.wallets.len() < 2 {
+            return Vec::new();
+        }
+
+        let fee = chain.get_base_fee() * 2.0;
+        let mut transactions = Vec::new();
+        for _ in 0..count {
+            let sender = self.pick_sender();
+            let mut receiver = self.rng.below(self.wallets.len() as u64 - 1) as usize;
+            if receiver >= sender {
+                receiver += 1;
+            }
+            let receiver = self.wallets[receiver].address.clone();
+            let spread = self.config.max_amount - self.config.min_amount;
+            let amount = self.config.min_amount + self.rng.next_f64() * spread.max(0.0);
+
+            if self.rng.chance(self.config.invalid_ratio) {
+                let invalid = match self.rng.below(3) {
+                    0 => Invalid::Overspend,
+                    1 => Invalid::LowFee,
+                    _ => Invalid::StaleNonce,
+                };
+                if let Some(transaction) = self.invalid_payment(chain, sender, &receiver, amount, fee, invalid) {
+                    self.stats.invalid += 1;
+                    transactions.push(transaction);
+                }
+            } else if let Ok(transaction) = Transaction::create_payment(&mut self.wallets[sender], &receiver, amount, fee) {
+                self.stats.valid += 1;
+                transactions.push(transaction);
+            }
+        }
+        transactions
+    }
+
+    /// Generates one tick and submits it to the mempool of `chain`.
+    ///
+    /// # Returns
+    ///
+    /// The counters of this tick only.
+    pub fn feed(&mut self, chain: &mut Blockchain) -> WorkloadStats {
+        let before = self.stats.clone();
+        for transaction in self.next_tick(chain) {
+            if chain.submit_transaction(transaction).is_ok() {
+                self.stats.accepted += 1;
+            } else {
+                self.stats.rejected += 1;
+            }
+        }
+        WorkloadStats {
+            ticks: self.stats.ticks - before.ticks,
+            bursts: self.stats.bursts - before.bursts,
+            valid: self.stats.valid - before.valid,
+            invalid: self.stats.invalid - before.invalid,
+            accepted: self.stats.accepted - before.accepted,
+            rejected: self.stats.rejected - before.rejected,
+        }
+    }
+
+    fn pick_sender(&mut self) -> usize {
+        let total = self.sender_weights.last().copied().unwrap_or(0.0);
+        let target = self.rng.next_f64() * total;
+        self.sender_weights.partition_point(|weight| *weight <= target).min(self.wallets.len() - 1)
+    }
+
+    fn invalid_payment(&self, chain: &Blockchain, sender: usize, receiver: &str, amount: f64, fee: f64, invalid: Invalid) -> Option<Transaction> {
+        let mut copy = self.wallets[sender].clone();
+        let used_nonce = chain.get_nonce(&copy.address);
+        match invalid {
+            Invalid::Overspend => {
+                let amount = chain.get_balance(&copy.address) + amount + 1.0;
+                copy.balance = amount + fee;
+                Transaction::create_payment(&mut copy, receiver, amount, fee).ok()
+            }
+            Invalid::StaleNonce if used_nonce > 0 => {
+                copy.nonce = used_nonce - 1;
+                copy.balance = amount + fee;
+                Transaction::create_payment(&mut copy, receiver, amount, fee).ok()
+            }
+            // Nothing to reuse yet: fall back to a fee below the base fee
+            Invalid::LowFee | Invalid::StaleNonce => {
+                copy.balance = amount + fee;
+                Transaction::create_payment(&mut copy, receiver, amount, chain.get_base_fee() / 2.0).ok()
+            }
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::mem::size_of;
+use crate::state::state::ChainState;
+
+/// Blocks a `StateCache` can disconnect at most (older undo records are dropped).
+pub const UNDO_DEPTH: usize = 100;
+
+/// Balance and nonce of an address.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Account {
+    pub balance: f64,
+    pub nonce: u64,
+}
+
+/// Balances and nonces of every address, kept up to date block by block.
+///
+/// `ChainState` holds everything blocks change and is what validation applies
+/// transactions on; the cache only holds the accounts, and records what each
+/// connected block changed so the block can be disconnected again without
+/// replaying the chain. `Blockchain` answers balance and nonce lookups from it.
+///
+/// # Fields
+///
+/// * `accounts` - Balance and nonce by address
+/// * `undo` - For each connected block (oldest first), the accounts it changed as
+///   they were before it (`None` if the address had no account)
+/// * `bytes` - Approximate memory of the accounts and undo records
+/// * `max_bytes` - Most memory before the oldest undo records are dropped (`None`
+///   for no limit)
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// chain.add_block(Vec::new(), "Alice").unwrap();
+/// let mut cache = chain.state_cache().clone();
+/// assert_eq!(cache.get_balance("Alice"), 45.0);
+///
+/// // Disconnecting the block brings back the accounts of the genesis
+/// cache.disconnect().unwrap();
+/// assert_eq!(cache.get_balance("Alice"), 0.0);
+/// assert!(cache.verify(Blockchain::with_settings(1, 50.0).state()).is_ok());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StateCache {
+    accounts: HashMap<String, Account>,
+    undo: VecDeque<Vec<(String, Option<Account>)>>,
+    bytes: usize,
+    max_bytes: Option<usize>,
+}
+
+impl StateCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        StateCache {
+            accounts: HashMap::new(),
+            undo: VecDeque::new(),
+            bytes: 0,
+            max_bytes: None,
+        }
+    }
+
+    /// Creates a cache holding every account of `state`, with nothing to disconnect.
+    pub fn from_state(state: &ChainState) -> Self {
+        let mut cache = StateCache::new();
+        for address in state.accounts() {
+            let account = StateCache::account_in(state, &address);
+            cache.bytes += StateCache::account_bytes(&address);
+            cache.accounts.insert(address, account);
+        }
+        cache
+    }
+
+    /// Records a connected block: the accounts of `touched` (see
+    /// `ChainState::take_touched`) are copied from `state`, the state after the block.
+    pub fn connect(&mut self, state: &ChainState, touched: &BTreeSet<String>) {
+        let mut undo = Vec::with_capacity(touched.len());
+        for address in touched {
+            let account = StateCache::account_in(state, address);
+            let previous = self.accounts.insert(address.clone(), account);
+            if previous.is_none() {
+                self.bytes += StateCache::account_bytes(address);
+            }
+            self.bytes += StateCache::undo_bytes(address);
+            undo.push((address.clone(), previous));
+        }
+        self.undo.push_back(undo);
+        if self.undo.len() > UNDO_DEPTH {
+            self.drop_oldest_undo();
+        }
+        self.enforce_max_bytes();
+    }
+
+    /// Undoes the last connected block.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The accounts are back to what they were before the block
+    /// * `Err(String)` - If no block is left to disconnect (none connected, or more
+    ///   than `UNDO_DEPTH` disconnected)
+    pub fn disconnect(&mut self) -> Result<(), String> {
+        let undo = self.undo.pop_back().ok_or("No connected block to disconnect".to_string())?;
+        for (address, previous) in undo {
+            self.bytes -= StateCache::undo_bytes(&address);
+            match previous {
+                Some(account) => {
+                    self.accounts.insert(address, account);
+                }
+                None => {
+                    self.bytes -= StateCache::account_bytes(&address);
+                    self.accounts.remove(&address);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns how many blocks can be disconnected.
+    pub fn depth(&self) -> usize {
+        self.undo.len()
+    }
+
+    /// Returns the approximate memory taken by the accounts and undo records, in bytes.
+    pub fn memory_bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// Returns the most memory the cache may take (`None` for no limit).
+    pub fn max_bytes(&self) -> Option<usize> {
+        self.max_bytes
+    }
+
+    /// Limits the memory the cache may take, in bytes.
+    ///
+    /// Accounts are always kept, since lookups need them; past the limit the oldest
+    /// undo records are dropped, so fewer blocks can be disconnected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    ///
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// chain.add_block(Vec::new(), "Bob").unwrap();
+    /// let mut cache = chain.state_cache().clone();
+    /// assert_eq!(cache.depth(), 2);
+    ///
+    /// cache.set_max_bytes(Some(0));
+    /// assert_eq!(cache.depth(), 0);
+    /// assert_eq!(cache.get_balance("Bob"), 45.0);
+    /// ```
+    pub fn set_max_bytes(&mut self, max_bytes: Option<usize>) {
+        self.max_bytes = max_bytes;
+        self.enforce_max_bytes();
+    }
+
+    /// Returns the account of `address`, if it has one.
+    pub fn get(&self, address: &str) -> Option<Account> {
+        self.accounts.get(address).copied()
+    }
+
+    /// Returns the balance of `address` (0 if it has no account).
+    pub fn get_balance(&self, address: &str) -> f64 {
+        self.get(address).map(|account| account.balance).unwrap_or(0.0)
+    }
+
+    /// Returns the nonce the next transaction from `address` must use.
+    pub fn get_nonce(&self, address: &str) -> u64 {
+        self.get(address).map(|account| account.nonce).unwrap_or(0)
+    }
+
+    /// Returns the number of accounts.
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    /// Returns `true` if no address has an account.
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// Checks the cache holds exactly the accounts of `state` (e.g. a state rebuilt
+    /// by replaying the chain).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If every account matches
+    /// * `Err(String)` - The first address (in order) that differs
+    pub fn verify(&self, state: &ChainState) -> Result<(), String> {
+        let in_state: BTreeSet<String> = state.accounts().into_iter().collect();
+        let addresses: BTreeSet<&String> = in_state.iter().chain(self.accounts.keys()).collect();
+        for address in addresses {
+            let expected = in_state.contains(address).then(|| StateCache::account_in(state, address));
+            let cached = self.get(address);
+            if cached != expected {
+                return Err(format!("Account {} is {:?} in the cache, {:?} in the state", address, cached, expected));
+            }
+        }
+        Ok(())
+    }
+
+    fn enforce_max_bytes(&mut self) {
+        while self.max_bytes.is_some_and(|max| self.bytes > max) && !self.undo.is_empty() {
+            self.drop_oldest_undo();
+        }
+    }
+
+    fn drop_oldest_undo(&mut self) {
+        if let Some(undo) = self.undo.pop_front() {
+            self.bytes -= undo.iter().map(|(address, _)| StateCache::undo_bytes(address)).sum::<usize>();
+        }
+    }
+
+    fn account_bytes(address: &str) -> usize {
+        size_of::<(String, Account)>() + address.len()
+    }
+
+    fn undo_bytes(address: &str) -> usize {
+        size_of::<(String, Option<Account>)>() + address.len()
+    }
+
+    fn account_in(state: &ChainState, address: &str) -> Account {
+        Account {
+            balance: state.get_balance(address),
+            nonce: state.get_nonce(address),
+        }
+    }
+}
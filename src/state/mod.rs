@@ -0,0 +1,4 @@
+pub mod state;
+pub mod parallel;
+pub mod cache;
+pub mod rules;
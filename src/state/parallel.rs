@@ -0,0 +1,255 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use crate::fees::base_fee::TARGET_BLOCK_SIZE;
+use crate::state::state::ChainState;
+use crate::transactions::transactions::{Transaction, TransactionKind};
+
+/// Fewest transactions `apply_parallel` spreads over threads; shorter lists are
+/// applied one after the other, which is faster than starting threads. Blocks at
+/// the base fee's target size or fuller (up to `MAX_BLOCK_SIZE`) reach it.
+pub const PARALLEL_THRESHOLD: usize = TARGET_BLOCK_SIZE;
+
+/// Stages spread over threads since the process started.
+static PARALLEL_STAGES: AtomicU64 = AtomicU64::new(0);
+
+/// Returns how many stages `apply_parallel` has spread over threads in this
+/// process (see `Blockchain::set_execution_threads`).
+pub fn parallel_stages() -> u64 {
+    PARALLEL_STAGES.load(Ordering::Relaxed)
+}
+
+/// A step of the plan `apply_parallel` follows.
+///
+/// * `Parallel` - Groups of account-local transactions (indices, in block order);
+///   no two groups touch the same account, so they can be applied at the same time
+/// * `Sequential` - A transaction that may touch any state, applied on its own
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stage {
+    Parallel(Vec<Vec<usize>>),
+    Sequential(usize),
+}
+
+/// Returns the accounts an account-local transaction reads or writes: its sender
+/// and whoever it pays.
+pub fn touched_accounts(transaction: &Transaction) -> Vec<&str> {
+    let mut accounts = vec![transaction.sender_address.as_str()];
+    match &transaction.kind {
+        TransactionKind::MultiPay { outputs } => accounts.extend(outputs.iter().map(|output| output.address.as_str())),
+        TransactionKind::Anchor { .. } => {}
+        _ => accounts.push(transaction.receiver_address.as_str()),
+    }
+    accounts
+}
+
+/// Splits `transactions` into stages that can be applied on `state`.
+///
+/// Runs of account-local transactions (see `ChainState::is_account_local`) are
+/// grouped by the accounts they touch: two transactions sharing an account, directly
+/// or through others, end up in the same group. Any other transaction ends the run
+/// and becomes a sequential stage. Groups are ordered by their first transaction.
+///
+/// # Example
+///
+/// ```
+/// use blockc::state::parallel::{self, Stage};
+/// use blockc::state::state::ChainState;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+/// use blockc::entity::entity::Entity;
+///
+/// let mut alice = Entity::new("Alice".to_string(), 100.0, Vec::new(), "a".to_string(), "a".to_string());
+/// let mut carol = Entity::new("Carol".to_string(), 100.0, Vec::new(), "c".to_string(), "c".to_string());
+/// let transactions = vec![
+///     Transaction::create_payment(&mut alice, "Bob", 1.0, 0.1).unwrap(),
+///     Transaction::create_payment(&mut carol, "Dave", 1.0, 0.1).unwrap(),
+///     Transaction::create_payment(&mut carol, "Bob", 1.0, 0.1).unwrap(),
+///     Transaction::create_payment(&mut alice, "Erin", 1.0, 0.1).unwrap(),
+/// ];
+///
+/// // The last two link Alice's and Carol's payments through Bob
+/// assert_eq!(parallel::plan(&ChainState::new(), &transactions), vec![Stage::Parallel(vec![vec![0, 1, 2, 3]])]);
+/// assert_eq!(parallel::plan(&ChainState::new(), &transactions[..2]), vec![Stage::Parallel(vec![vec![0], vec![1]])]);
+/// ```
+pub fn plan(state: &ChainState, transactions: &[Transaction]) -> Vec<Stage> {
+    let mut stages = Vec::new();
+    let mut run: Vec<usize> = Vec::new();
+    for (index, transaction) in transactions.iter().enumerate() {
+        if state.is_account_local(transaction) {
+            run.push(index);
+            continue;
+        }
+        if !run.is_empty() {
+            stages.push(Stage::Parallel(group(transactions, &std::mem::take(&mut run))));
+        }
+        stages.push(Stage::Sequential(index));
+    }
+    if !run.is_empty() {
+        stages.push(Stage::Parallel(group(transactions, &run)));
+    }
+    stages
+}
+
+/// Groups the transactions at `run` by shared accounts (union-find over the run).
+fn group(transactions: &[Transaction], run: &[usize]) -> Vec<Vec<usize>> {
+    fn root(parents: &mut [usize], mut i: usize) -> usize {
+        while parents[i] != i {
+            parents[i] = parents[parents[i]];
+            i = parents[i];
+        }
+        i
+    }
+
+    let mut parents: Vec<usize> = (0..run.len()).collect();
+    let mut owner: HashMap<&str, usize> = HashMap::new();
+    for (position, &index) in run.iter().enumerate() {
+        for account in touched_accounts(&transactions[index]) {
+            match owner.get(account) {
+                Some(&other) => {
+                    let (a, b) = (root(&mut parents, position), root(&mut parents, other));
+                    // The earlier transaction stays the root, so groups keep their order
+                    parents[a.max(b)] = a.min(b);
+                }
+                None => {
+                    owner.insert(account, position);
+                }
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut group_of: HashMap<usize, usize> = HashMap::new();
+    for (position, &index) in run.iter().enumerate() {
+        let root = root(&mut parents, position);
+        let slot = *group_of.entry(root).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[slot].push(index);
+    }
+    groups
+}
+
+/// Applies `transactions` on `state` like `ChainState::apply_transaction` in a loop,
+/// spreading independent groups over up to `threads` threads (see `plan`).
+///
+/// Every account is only touched by one thread, and within a group transactions
+/// are applied in block order, so the result is the same as applying them one
+/// after the other. Lists shorter than `PARALLEL_THRESHOLD`, or a single thread,
+/// are applied in order, as is everything on wasm32 (no threads there).
+///
+/// # Returns
+///
+/// * `Ok(Vec<u64>)` - Gas used by each transaction
+/// * `Err(String)` - The error of the first transaction (in block order) that can't
+///   be applied; `state` may then hold some of the later transactions and should
+///   be discarded
+///
+/// # Example
+///
+/// ```
+/// use blockc::state::parallel;
+/// use blockc::state::state::ChainState;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+/// use blockc::entity::entity::Entity;
+///
+/// let mut state = ChainState::new();
+/// let mut transactions = Vec::new();
+/// for i in 0..200 {
+///     let name = format!("sender{}", i);
+///     state.credit(&name, 10.0);
+///     let mut sender = Entity::new(name, 10.0, Vec::new(), "pub".to_string(), "priv".to_string());
+///     transactions.push(Transaction::create_payment(&mut sender, &format!("receiver{}", i % 50), 1.0, 0.1).unwrap());
+/// }
+///
+/// let mut sequential = state.clone();
+/// for transaction in &transactions {
+///     sequential.apply_transaction(transaction).unwrap();
+/// }
+/// parallel::apply_parallel(&mut state, &transactions, 4).unwrap();
+/// assert_eq!(state.state_root(), sequential.state_root());
+/// ```
+pub fn apply_parallel(state: &mut ChainState, transactions: &[Transaction], threads: usize) -> Result<Vec<u64>, String> {
+    let mut gas_used = vec![0; transactions.len()];
+    if threads <= 1 || transactions.len() < PARALLEL_THRESHOLD || cfg!(target_arch = "wasm32") {
+        for (index, transaction) in transactions.iter().enumerate() {
+            gas_used[index] = state.apply_transaction(transaction)?;
+        }
+        return Ok(gas_used);
+    }
+
+    for stage in plan(state, transactions) {
+        match stage {
+            Stage::Sequential(index) => gas_used[index] = state.apply_transaction(&transactions[index])?,
+            Stage::Parallel(groups) if groups.len() == 1 => {
+                for index in &groups[0] {
+                    gas_used[*index] = state.apply_transaction(&transactions[*index])?;
+                }
+            }
+            Stage::Parallel(groups) => apply_groups(state, transactions, &groups, threads, &mut gas_used)?,
+        }
+    }
+    Ok(gas_used)
+}
+
+/// Index of a transaction and what applying it returned.
+type Outcome = (usize, Result<u64, String>);
+
+/// Applies independent groups on up to `threads` threads, then merges their accounts back.
+fn apply_groups(state: &mut ChainState, transactions: &[Transaction], groups: &[Vec<usize>], threads: usize, gas_used: &mut [u64]) -> Result<(), String> {
+    PARALLEL_STAGES.fetch_add(1, Ordering::Relaxed);
+    // Deal the groups to the least loaded worker, biggest first
+    let workers = threads.min(groups.len());
+    let mut by_size: Vec<&Vec<usize>> = groups.iter().collect();
+    by_size.sort_by_key(|group| std::cmp::Reverse(group.len()));
+    let mut assigned: Vec<Vec<&Vec<usize>>> = vec![Vec::new(); workers];
+    let mut load = vec![0; workers];
+    for group in by_size {
+        let worker = (0..workers).min_by_key(|&w| load[w]).unwrap_or(0);
+        load[worker] += group.len();
+        assigned[worker].push(group);
+    }
+
+    let parts: Vec<ChainState> = assigned.iter().map(|groups| {
+        let accounts: BTreeSet<&str> = groups.iter()
+            .flat_map(|group| group.iter().flat_map(|&i| touched_accounts(&transactions[i])))
+            .collect();
+        state.split_accounts(&accounts)
+    }).collect();
+
+    let results: Vec<(ChainState, Vec<Outcome>)> = thread::scope(|scope| {
+        let handles: Vec<_> = parts.into_iter().zip(&assigned).map(|(mut part, groups)| {
+            scope.spawn(move || {
+                let mut outcomes = Vec::new();
+                for group in groups {
+                    // A failed transaction stops its group, not the others
+                    for &index in group.iter() {
+                        let outcome = part.apply_transaction(&transactions[index]);
+                        let failed = outcome.is_err();
+                        outcomes.push((index, outcome));
+                        if failed {
+                            break;
+                        }
+                    }
+                }
+                (part, outcomes)
+            })
+        }).collect();
+        handles.into_iter().map(|handle| handle.join().expect("execution thread panicked")).collect()
+    });
+
+    let mut first_error: Option<(usize, String)> = None;
+    for (part, outcomes) in results {
+        state.merge_accounts(part);
+        for (index, outcome) in outcomes {
+            match outcome {
+                Ok(gas) => gas_used[index] = gas,
+                Err(error) if first_error.as_ref().is_none_or(|(first, _)| index < *first) => first_error = Some((index, error)),
+                Err(_) => {}
+            }
+        }
+    }
+    match first_error {
+        Some((_, error)) => Err(error),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,120 @@
+use crate::block::block::BlockHeader;
+use crate::treasury::treasury::Treasury;
+
+/// A chain-level policy script every user transaction must pass (see
+/// `Blockchain::register_policy`).
+///
+/// # Fields
+///
+/// * `name` - Name the policy is registered under
+/// * `script` - Rhai script that must return `true`
+/// * `since` - First height whose blocks it applies to; blocks accepted before it
+///   was registered are replayed without it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Policy {
+    pub name: String,
+    pub script: String,
+    pub since: u64,
+}
+
+/// A bridge opened by the node operator (see `Blockchain::open_bridge`).
+///
+/// # Fields
+///
+/// * `local_chain` - Name of this chain, as the remote chain knows it
+/// * `remote_chain` - Name of the other chain
+/// * `remote_genesis` - Genesis header of the other chain (trusted)
+/// * `remote_difficulty` - Proof-of-work difficulty of the other chain
+#[derive(Debug, Clone, PartialEq)]
+pub struct BridgeOpening {
+    pub local_chain: String,
+    pub remote_chain: String,
+    pub remote_genesis: BlockHeader,
+    pub remote_difficulty: u32,
+}
+
+/// Rules a node operator adds to the chain's own, rather than transactions.
+///
+/// They change which transactions are valid, so they are saved with the chain
+/// (see `storage::store::RULES_FILE`) and installed on the state before its blocks
+/// are replayed: reloading, reindexing or repairing a chain accepts the same
+/// blocks as the node that built it. Peers must be configured with the same rules
+/// to follow the chain.
+///
+/// The treasury's governance (governors, proposals and approvals, see
+/// `Blockchain::treasury_mut`) is kept the same way, rewound to before its
+/// spends (see `Treasury::rewound`): its share applies to every block replayed.
+///
+/// # Fields
+///
+/// * `policies` - Policy scripts, sorted by name
+/// * `bridges` - Bridges to other chains, in the order they were opened
+/// * `treasury` - Treasury to replay the blocks with (`None` for the default one)
+///
+/// # Example
+///
+/// ```
+/// use blockc::block::block::HashAlgorithm;
+/// use blockc::blockchain::blockchain::Blockchain;
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// chain.add_block(Vec::new(), "Alice").unwrap();
+/// assert!(chain.local_rules().is_empty());
+///
+/// // Bridges are rules too: blocks relaying the other chain replay with them
+/// let remote = Blockchain::with_settings(1, 50.0);
+/// chain.open_bridge("A", "B", remote.get_blocks()[0].header(), 1).unwrap();
+/// assert_eq!(chain.local_rules().bridges[0].remote_chain, "B");
+///
+/// // So is the treasury: its spends replay against the proposals they executed
+/// let treasury = chain.treasury_mut();
+/// treasury.add_governor("Carol".to_string());
+/// let id = treasury.propose("Dave".to_string(), 3.0, "Grant".to_string()).unwrap();
+/// treasury.approve(id, "Carol").unwrap();
+/// let spend = chain.treasury().create_spend(id).unwrap();
+/// chain.add_block(vec![spend], "Miner").unwrap();
+/// let blocks = chain.get_blocks().to_vec();
+/// assert!(Blockchain::from_blocks(1, 50.0, blocks.clone()).is_err());
+/// let (replayed, damage) = Blockchain::from_blocks_until_invalid(1, 50.0, HashAlgorithm::Sha256, blocks, None, &chain.local_rules()).unwrap();
+/// assert_eq!(damage, None);
+/// assert_eq!(replayed.treasury(), chain.treasury());
+///
+/// # #[cfg(feature = "scripting")] {
+/// use blockc::entity::entity::Entity;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+///
+/// // Alice pays 20 before the policy is registered
+/// let fee = chain.get_base_fee() * 2.0;
+/// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+/// chain.add_block(vec![Transaction::create_payment(&mut alice, "Bob", 20.0, fee).unwrap()], "Miner").unwrap();
+/// chain.register_policy("max_amount", "amount <= 10.0").unwrap();
+/// let rules = chain.local_rules();
+/// assert_eq!(rules.get_policy("max_amount").unwrap().since, 4);
+///
+/// // Replaying the blocks with the rules gives back the same chain
+/// let blocks = chain.get_blocks().to_vec();
+/// let (replayed, damage) = Blockchain::from_blocks_until_invalid(1, 50.0, HashAlgorithm::Sha256, blocks, None, &rules).unwrap();
+/// assert_eq!(damage, None);
+/// assert_eq!(replayed.local_rules(), rules);
+/// let big = Transaction::create_payment(&mut alice, "Bob", 20.0, fee).unwrap();
+/// assert!(replayed.clone().submit_transaction(big).unwrap_err().contains("max_amount"));
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LocalRules {
+    pub policies: Vec<Policy>,
+    pub bridges: Vec<BridgeOpening>,
+    pub treasury: Option<Treasury>,
+}
+
+impl LocalRules {
+    /// Returns `true` if no rule was added.
+    pub fn is_empty(&self) -> bool {
+        self.policies.is_empty() && self.bridges.is_empty() && self.treasury.is_none()
+    }
+
+    /// Returns the policy registered under `name`.
+    pub fn get_policy(&self, name: &str) -> Option<&Policy> {
+        self.policies.iter().find(|policy| policy.name == name)
+    }
+}
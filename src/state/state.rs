@@ -0,0 +1,1698 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use crate::merkle::patricia::{PatriciaTrie, TrieProof};
+use crate::contracts::htlc::Htlc;
+use crate::contracts::channel::{Channel, ChannelStatus, DISPUTE_WINDOW};
+use crate::contracts::escrow::{Escrow, EscrowOutcome, EscrowStatus, ESCROW_VOTES_REQUIRED};
+use crate::contracts::vesting::VestingGrant;
+use crate::contracts::stream::Stream;
+use crate::contracts::ballot::{check_ballot, Ballot, BallotResult, VoteWeighting};
+use crate::contracts::auction::{commit_bid, Auction, Bid};
+use crate::transactions::transactions::Output;
+use crate::utxo::coinjoin::MIN_COINJOIN_PARTICIPANTS;
+use crate::utxo::utxo::{OutPoint, UtxoSet};
+use crate::vm::contract::{CallReceipt, Contract};
+use crate::assets::asset::{Asset, AssetLedger};
+use crate::assets::nft::{Nft, NftRegistry};
+use crate::names::registry::{NameRecord, NameRegistry};
+use crate::identity::did::{DidDocument, DidRegistry, KeyRotation};
+use crate::dex::amm::{LiquidityPool, MAX_POOL_FEE_BPS};
+use crate::dex::orderbook::{Order, OrderBook, Side};
+use crate::oracle::feed::{FeedValue, OracleFeed, OracleRegistry};
+use crate::block::block::BlockHeader;
+use crate::state::rules::{BridgeOpening, LocalRules};
+use crate::fees::gas::BLOCK_GAS_LIMIT;
+use crate::bridge::bridge::{escrow_address, wrapped_asset_id, wrapped_ticker, BridgeLink, BridgeProof};
+#[cfg(feature = "privacy")]
+use crate::privacy::confidential::{to_units, ConfidentialPool};
+#[cfg(feature = "privacy")]
+use crate::privacy::ring::{withdrawal_message, RingPool};
+use crate::vm::vm::{self, CallContext};
+use crate::transactions::transactions::{LockTime, Transaction, TransactionKind};
+
+/// Balances as recorded by the chain itself.
+///
+/// `Entity` keeps its own balance for the simple wallet flow, but the chain needs
+/// its own view to pay block rewards and to check that senders can afford the
+/// transactions included in a block. The state is only changed by applying
+/// transactions, block after block.
+///
+/// # Fields
+///
+/// * `balances` - Map of address to current on-chain balance
+/// * `nonces` - Map of address to the nonce its next transaction must use
+/// * `height` - Height of the latest block applied (genesis is 0)
+/// * `time` - Timestamp of the latest block applied
+/// * `confirmations` - Map of transaction id to the height and time of the block that confirmed it
+/// * `htlcs` - Open hash time-locked contracts, by id
+/// * `channels` - Open or closing payment channels, by id
+/// * `escrows` - Escrows (funded or settled), by id
+/// * `vesting` - Vesting grants, by beneficiary
+/// * `streams` - Active payment streams, by id
+/// * `utxos` - Unspent outputs of UTXO transactions (and the outpoints already spent)
+/// * `contracts` - Contract accounts (code and storage), by address
+/// * `call_receipts` - Outcome of each contract call, by transaction id
+/// * `assets` - Issued assets and per-asset balances
+/// * `nfts` - Non-fungible tokens and their owners
+/// * `names` - Registered names and the addresses they point to
+/// * `identities` - DID documents and their key history
+/// * `ballots` - Application ballots and their votes, by id
+/// * `auctions` - Auctions and the bids they hold, by id
+/// * `pools` - Liquidity pools of the AMM, by id
+/// * `orders` - Limit order book and the trades it executed
+/// * `oracles` - Oracle data feeds and their latest reports
+/// * `bridges` - Bridges to other chains, by remote chain name
+/// * `confidential` - Confidential outputs with hidden amounts (`privacy` feature)
+/// * `ring_pool` - Deposits withdrawable with ring signatures (`privacy` feature)
+/// * `rules` - Rules added by the node operator: policy scripts every transaction
+///   must pass (`scripting` feature) and the bridges opened
+/// * `touched` - Addresses whose balance or nonce changed since `take_touched`
+/// * `assume_valid` - Scripts and ring signatures are trusted instead of checked
+///   (see `Blockchain::from_blocks_assume_valid`)
+#[derive(Debug, Clone, Default)]
+pub struct ChainState {
+    balances: HashMap<String, f64>,
+    nonces: HashMap<String, u64>,
+    height: u64,
+    time: u32,
+    confirmations: HashMap<String, (u64, u32)>,
+    htlcs: HashMap<String, Htlc>,
+    channels: HashMap<String, Channel>,
+    escrows: HashMap<String, Escrow>,
+    vesting: HashMap<String, Vec<VestingGrant>>,
+    streams: HashMap<String, Stream>,
+    utxos: UtxoSet,
+    contracts: HashMap<String, Contract>,
+    call_receipts: HashMap<String, CallReceipt>,
+    assets: AssetLedger,
+    nfts: NftRegistry,
+    names: NameRegistry,
+    identities: DidRegistry,
+    ballots: HashMap<String, Ballot>,
+    auctions: HashMap<String, Auction>,
+    pools: HashMap<String, LiquidityPool>,
+    orders: OrderBook,
+    oracles: OracleRegistry,
+    bridges: HashMap<String, BridgeLink>,
+    #[cfg(feature = "privacy")]
+    confidential: ConfidentialPool,
+    #[cfg(feature = "privacy")]
+    ring_pool: RingPool,
+    rules: LocalRules,
+    touched: BTreeSet<String>,
+    assume_valid: bool,
+}
+
+impl ChainState {
+    /// Creates an empty state (every address has a balance of 0).
+    pub fn new() -> Self {
+        ChainState {
+            balances: HashMap::new(),
+            nonces: HashMap::new(),
+            height: 0,
+            time: 0,
+            confirmations: HashMap::new(),
+            htlcs: HashMap::new(),
+            channels: HashMap::new(),
+            escrows: HashMap::new(),
+            vesting: HashMap::new(),
+            streams: HashMap::new(),
+            utxos: UtxoSet::new(),
+            contracts: HashMap::new(),
+            call_receipts: HashMap::new(),
+            assets: AssetLedger::new(),
+            nfts: NftRegistry::new(),
+            names: NameRegistry::new(),
+            identities: DidRegistry::new(),
+            ballots: HashMap::new(),
+            auctions: HashMap::new(),
+            pools: HashMap::new(),
+            orders: OrderBook::new(),
+            oracles: OracleRegistry::new(),
+            bridges: HashMap::new(),
+            #[cfg(feature = "privacy")]
+            confidential: ConfidentialPool::new(),
+            #[cfg(feature = "privacy")]
+            ring_pool: RingPool::new(),
+            rules: LocalRules::default(),
+            touched: BTreeSet::new(),
+            assume_valid: false,
+        }
+    }
+
+    /// Returns the addresses with an account entry (balance or nonce), sorted.
+    pub fn accounts(&self) -> Vec<String> {
+        let addresses: BTreeSet<&String> = self.balances.keys().chain(self.nonces.keys()).collect();
+        addresses.into_iter().cloned().collect()
+    }
+
+    /// Canonical encoding of the account of `address` (`balance:nonce`).
+    pub fn account_entry(&self, address: &str) -> String {
+        format!("{}:{}", self.get_balance(address), self.get_nonce(address))
+    }
+
+    /// Builds the Merkle-Patricia trie of the accounts (address to `account_entry`).
+    pub fn account_trie(&self) -> PatriciaTrie {
+        let mut trie = PatriciaTrie::new();
+        for address in self.accounts() {
+            let entry = self.account_entry(&address);
+            trie.insert(address, entry);
+        }
+        trie
+    }
+
+    /// Commitment to the account state: the root hash of the account trie.
+    ///
+    /// Two states with the same balances and nonces always have the same root, so
+    /// nodes can compare their state by comparing roots. Blocks store the root of
+    /// the state after them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::state::state::ChainState;
+    ///
+    /// let mut a = ChainState::new();
+    /// let mut b = ChainState::new();
+    /// a.credit("Alice", 1.0);
+    /// a.credit("Bob", 2.0);
+    /// b.credit("Bob", 2.0);
+    /// b.credit("Alice", 1.0);
+    /// assert_eq!(a.state_root(), b.state_root());
+    ///
+    /// b.credit("Bob", 0.5);
+    /// assert_ne!(a.state_root(), b.state_root());
+    /// ```
+    pub fn state_root(&self) -> String {
+        self.account_trie().root_hash()
+    }
+
+    /// Proves the account of `address` (its `balance:nonce` entry, or its absence)
+    /// against `state_root`.
+    ///
+    /// A light client holding only block headers can check the proof with
+    /// `merkle::patricia::verify_proof` and the header's state root.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::merkle::patricia::verify_proof;
+    ///
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    ///
+    /// let proof = chain.state().prove("Alice");
+    /// let header_root = &chain.get_latest_block().state_root;
+    /// assert!(verify_proof(header_root, &proof));
+    /// assert_eq!(proof.value.as_deref(), Some("45:0"));
+    /// ```
+    pub fn prove(&self, address: &str) -> TrieProof {
+        self.account_trie().prove(address)
+    }
+
+    /// Returns the UTXO set.
+    pub fn utxos(&self) -> &UtxoSet {
+        &self.utxos
+    }
+
+    /// Returns the contract deployed at `address` (the id of its deploy transaction).
+    pub fn get_contract(&self, address: &str) -> Option<&Contract> {
+        self.contracts.get(address)
+    }
+
+    /// Returns the outcome of the contract call with the given transaction id.
+    pub fn get_call_receipt(&self, transaction_id: &str) -> Option<&CallReceipt> {
+        self.call_receipts.get(transaction_id)
+    }
+
+    /// Returns the registry of issued assets and their balances.
+    pub fn assets(&self) -> &AssetLedger {
+        &self.assets
+    }
+
+    /// Returns the asset with the given id (the id of the transaction that issued it).
+    pub fn get_asset(&self, asset_id: &str) -> Option<&Asset> {
+        self.assets.get(asset_id)
+    }
+
+    /// Returns how many units of `asset_id` `address` holds.
+    pub fn get_asset_balance(&self, address: &str, asset_id: &str) -> u64 {
+        self.assets.balance_of(address, asset_id)
+    }
+
+    /// Returns the registry of non-fungible tokens.
+    pub fn nfts(&self) -> &NftRegistry {
+        &self.nfts
+    }
+
+    /// Returns the owner of a non-fungible token (`None` if it doesn't exist or was burned).
+    pub fn owner_of(&self, token_id: &str) -> Option<&str> {
+        self.nfts.owner_of(token_id)
+    }
+
+    /// Returns the non-fungible tokens owned by `address`, sorted by id.
+    pub fn tokens_of(&self, address: &str) -> Vec<&Nft> {
+        self.nfts.tokens_of(address)
+    }
+
+    /// Returns the address a name points to in the next block (`None` if it is not
+    /// registered or expired).
+    pub fn resolve_name(&self, name: &str) -> Option<&str> {
+        self.names.resolve(name, self.height + 1)
+    }
+
+    /// Returns the record of a name, even if it expired.
+    pub fn get_name(&self, name: &str) -> Option<&NameRecord> {
+        self.names.get(name)
+    }
+
+    /// Returns the active names owned by `address`, sorted.
+    pub fn names_of(&self, address: &str) -> Vec<&NameRecord> {
+        self.names.names_of(address, self.height + 1)
+    }
+
+    /// Returns the current document of a DID (`did:blockc:<address>`).
+    pub fn resolve_did(&self, did: &str) -> Option<&DidDocument> {
+        self.identities.resolve(did)
+    }
+
+    /// Returns every key set a DID has had, oldest first.
+    pub fn get_did_history(&self, did: &str) -> &[KeyRotation] {
+        self.identities.history(did)
+    }
+
+    /// Returns the registry of DID documents.
+    pub fn identities(&self) -> &DidRegistry {
+        &self.identities
+    }
+
+    /// Returns the ballot with the given id (the id of the transaction that created it).
+    pub fn get_ballot(&self, id: &str) -> Option<&Ballot> {
+        self.ballots.get(id)
+    }
+
+    /// Counts the votes of a ballot whose voting window is over.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(BallotResult)` - The final result
+    /// * `Err(String)` - If there is no such ballot or votes can still be cast
+    pub fn tally_ballot(&self, id: &str) -> Result<BallotResult, String> {
+        let ballot = self.ballots.get(id).ok_or(format!("No ballot {}", id))?;
+        if self.height + 1 < ballot.end_height {
+            return Err(format!("Ballot {} is open until height {}", id, ballot.end_height));
+        }
+        Ok(ballot.tally())
+    }
+
+    /// Returns the auction with the given id (the id of the transaction that created it).
+    pub fn get_auction(&self, id: &str) -> Option<&Auction> {
+        self.auctions.get(id)
+    }
+
+    /// Returns the liquidity pool with the given id (the id of the transaction that created it).
+    pub fn get_pool(&self, id: &str) -> Option<&LiquidityPool> {
+        self.pools.get(id)
+    }
+
+    /// Returns the liquidity pool trading `asset_a` against `asset_b`, in either order.
+    pub fn find_pool(&self, asset_a: &str, asset_b: &str) -> Option<&LiquidityPool> {
+        self.pools.values().find(|pool| pool.trades(asset_a) && pool.trades(asset_b))
+    }
+
+    /// Returns the limit order book (resting orders and executed trades).
+    pub fn order_book(&self) -> &OrderBook {
+        &self.orders
+    }
+
+    /// Returns the oracle feeds.
+    pub fn oracles(&self) -> &OracleRegistry {
+        &self.oracles
+    }
+
+    /// Returns the oracle feed with the given name.
+    pub fn get_feed(&self, name: &str) -> Option<&OracleFeed> {
+        self.oracles.get(name)
+    }
+
+    /// Returns the value of an oracle feed for the next block: the median of its
+    /// fresh reports, or an error when fewer than its quorum are fresh.
+    pub fn feed_value(&self, name: &str) -> Result<FeedValue, String> {
+        self.oracles.value_at(name, self.height + 1)
+    }
+
+    /// Returns the bridge to `remote_chain`.
+    pub fn get_bridge(&self, remote_chain: &str) -> Option<&BridgeLink> {
+        self.bridges.get(remote_chain)
+    }
+
+    /// Opens a bridge to `remote_chain` (see `Blockchain::open_bridge`).
+    pub fn open_bridge(&mut self, local_chain: &str, remote_chain: &str, remote_genesis: BlockHeader, remote_difficulty: u32) -> Result<(), String> {
+        let opening = BridgeOpening {
+            local_chain: local_chain.to_string(),
+            remote_chain: remote_chain.to_string(),
+            remote_genesis: remote_genesis,
+            remote_difficulty: remote_difficulty,
+        };
+        self.link_bridge(&opening)?;
+        self.rules.bridges.push(opening);
+        Ok(())
+    }
+
+    fn link_bridge(&mut self, opening: &BridgeOpening) -> Result<(), String> {
+        if self.bridges.contains_key(&opening.remote_chain) {
+            return Err(format!("A bridge to {} is already open", opening.remote_chain));
+        }
+        let link = BridgeLink::new(&opening.local_chain, &opening.remote_chain, opening.remote_genesis.clone(), opening.remote_difficulty);
+        self.bridges.insert(opening.remote_chain.clone(), link);
+        Ok(())
+    }
+
+    /// Returns the confidential outputs (`privacy` feature).
+    #[cfg(feature = "privacy")]
+    pub fn confidential(&self) -> &ConfidentialPool {
+        &self.confidential
+    }
+
+    /// Returns the deposits withdrawable with ring signatures (`privacy` feature).
+    #[cfg(feature = "privacy")]
+    pub fn ring_pool(&self) -> &RingPool {
+        &self.ring_pool
+    }
+
+    /// Returns the rules added by the node operator (policy scripts, bridges; the
+    /// treasury is kept by the chain, see `Blockchain::local_rules`).
+    pub fn rules(&self) -> &LocalRules {
+        &self.rules
+    }
+
+    /// Installs `rules` on a state rebuilt from the genesis, before its blocks are
+    /// replayed (see `Blockchain::from_blocks_until_invalid`).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The rules are in place
+    /// * `Err(String)` - If a policy script does not compile, policies are set
+    ///   without the `scripting` feature to run them, or a bridge is opened twice
+    pub fn install_rules(&mut self, rules: &LocalRules) -> Result<(), String> {
+        #[cfg(feature = "scripting")]
+        for policy in &rules.policies {
+            crate::scripting::script::check_script(&policy.script)
+                .map_err(|error| format!("Policy {}: {}", policy.name, error))?;
+        }
+        #[cfg(not(feature = "scripting"))]
+        if let Some(policy) = rules.policies.first() {
+            return Err(format!("Policy {} needs the scripting feature", policy.name));
+        }
+        for opening in &rules.bridges {
+            self.link_bridge(opening)?;
+        }
+        self.rules = LocalRules {
+            treasury: None,
+            ..rules.clone()
+        };
+        Ok(())
+    }
+
+    /// Registers (or replaces) a chain-level policy script.
+    ///
+    /// From the next block on, every user transaction must make the script return
+    /// `true` (see `scripting::script::evaluate`), on top of the usual rules.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The policy is active
+    /// * `Err(String)` - The script is too large or does not compile
+    #[cfg(feature = "scripting")]
+    pub fn register_policy(&mut self, name: &str, script: &str) -> Result<(), String> {
+        crate::scripting::script::check_script(script)?;
+        let policy = crate::state::rules::Policy {
+            name: name.to_string(),
+            script: script.to_string(),
+            since: self.height + 1,
+        };
+        match self.rules.policies.binary_search_by(|existing| existing.name.as_str().cmp(name)) {
+            Ok(index) => self.rules.policies[index] = policy,
+            Err(index) => self.rules.policies.insert(index, policy),
+        }
+        Ok(())
+    }
+
+    /// Trusts (`true`) or checks (`false`) the scripts and ring signatures of the
+    /// transactions applied from now on; everything else is still checked.
+    pub(crate) fn set_assume_valid(&mut self, assume_valid: bool) {
+        self.assume_valid = assume_valid;
+    }
+
+    /// Removes a policy script; returns `true` if it was registered.
+    #[cfg(feature = "scripting")]
+    pub fn remove_policy(&mut self, name: &str) -> bool {
+        let before = self.rules.policies.len();
+        self.rules.policies.retain(|policy| policy.name != name);
+        self.rules.policies.len() < before
+    }
+
+    /// Runs the transaction's own script and every policy script.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Every script returned `true`
+    /// * `Err(String)` - The first script that rejected the transaction or failed
+    #[cfg(feature = "scripting")]
+    pub fn check_scripts(&self, transaction: &Transaction) -> Result<(), String> {
+        use crate::scripting::script::{evaluate, ScriptContext};
+        let context = ScriptContext::new(transaction, self);
+        if let Some(script) = &transaction.script
+            && !evaluate(script, &context)? {
+            return Err("Transaction script rejected the transaction".to_string());
+        }
+        for policy in self.rules.policies.iter().filter(|policy| policy.since <= self.height + 1) {
+            if !evaluate(&policy.script, &context)? {
+                return Err(format!("Policy script {} rejected the transaction", policy.name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Without the `scripting` feature, scripts cannot be run: a transaction
+    /// carrying one is rejected rather than accepted unchecked.
+    #[cfg(not(feature = "scripting"))]
+    pub fn check_scripts(&self, transaction: &Transaction) -> Result<(), String> {
+        if transaction.script.is_some() {
+            return Err("Transaction scripts need the scripting feature".to_string());
+        }
+        Ok(())
+    }
+
+    /// Returns the unspent outputs owned by `address`.
+    pub fn get_utxos(&self, address: &str) -> Vec<(OutPoint, Output)> {
+        self.utxos.owned_by(address)
+    }
+
+    /// Returns the sum of the unspent outputs owned by `address` (its UTXO balance,
+    /// separate from its account balance).
+    pub fn get_utxo_balance(&self, address: &str) -> f64 {
+        self.utxos.balance_of(address)
+    }
+
+    /// Returns the active payment stream with the given id (the id of the transaction that opened it).
+    pub fn get_stream(&self, id: &str) -> Option<&Stream> {
+        self.streams.get(id)
+    }
+
+    /// Returns the vesting grants received by `address`.
+    pub fn get_vesting_grants(&self, address: &str) -> &[VestingGrant] {
+        self.vesting.get(address).map(|grants| grants.as_slice()).unwrap_or(&[])
+    }
+
+    /// Amount of `address`'s vesting grants still locked in the next block.
+    pub fn get_locked_balance(&self, address: &str) -> f64 {
+        let next_height = self.height + 1;
+        self.get_vesting_grants(address).iter().map(|grant| grant.locked_at(next_height)).sum()
+    }
+
+    /// Amount of `address`'s vesting grants unlocked in the next block.
+    pub fn get_vested_balance(&self, address: &str) -> f64 {
+        let next_height = self.height + 1;
+        self.get_vesting_grants(address).iter().map(|grant| grant.vested_at(next_height)).sum()
+    }
+
+    /// Balance `address` can spend in the next block (balance minus locked vesting).
+    pub fn get_spendable_balance(&self, address: &str) -> f64 {
+        self.get_balance(address) - self.get_locked_balance(address)
+    }
+
+    /// Returns the escrow with the given id (the id of its funding transaction).
+    pub fn get_escrow(&self, id: &str) -> Option<&Escrow> {
+        self.escrows.get(id)
+    }
+
+    /// Returns the payment channel with the given id (the id of its funding transaction).
+    pub fn get_channel(&self, id: &str) -> Option<&Channel> {
+        self.channels.get(id)
+    }
+
+    /// Returns the open HTLC with the given id (the id of its funding transaction).
+    pub fn get_htlc(&self, id: &str) -> Option<&Htlc> {
+        self.htlcs.get(id)
+    }
+
+    /// Returns the height of the latest block applied to this state.
+    pub fn height(&self) -> u64 {
+        self.height
+    }
+
+    /// Returns the timestamp of the latest block applied to this state.
+    pub fn time(&self) -> u32 {
+        self.time
+    }
+
+    /// Returns the height and time of the block that confirmed `transaction_id`.
+    pub fn get_confirmation(&self, transaction_id: &str) -> Option<(u64, u32)> {
+        self.confirmations.get(transaction_id).copied()
+    }
+
+    /// Returns the number of transactions confirmed so far.
+    pub fn transactions_confirmed(&self) -> usize {
+        self.confirmations.len()
+    }
+
+    /// Takes the confirmations of `rebuilt` (a state rebuilt by replaying the same
+    /// blocks) in place of these, and returns whether they differed.
+    pub(crate) fn replace_confirmations(&mut self, rebuilt: &mut ChainState) -> bool {
+        let stale = self.confirmations != rebuilt.confirmations;
+        self.confirmations = std::mem::take(&mut rebuilt.confirmations);
+        stale
+    }
+
+    /// Records a connected block: it becomes the latest block, its transactions
+    /// are confirmed (used by relative lock times) and the `Balance` ballots
+    /// opening at the next height take their balance snapshot.
+    ///
+    /// # Arguments
+    ///
+    /// * `height` - Height of the block
+    /// * `time` - Timestamp of the block
+    /// * `transactions` - Transactions of the block, already applied
+    pub fn record_block(&mut self, height: u64, time: u32, transactions: &[Transaction]) {
+        self.height = height;
+        self.time = time;
+        for transaction in transactions {
+            self.confirmations.insert(transaction.id(), (height, time));
+        }
+        self.snapshot_ballots(height + 1);
+    }
+
+    /// Takes the balance snapshot of the `Balance` ballots opening at or before
+    /// `height` that don't have one yet (see `Ballot::snapshot`).
+    fn snapshot_ballots(&mut self, height: u64) {
+        let opening: Vec<String> = self.ballots.values()
+            .filter(|ballot| ballot.weighting == VoteWeighting::Balance && ballot.snapshot.is_none() && ballot.start_height <= height)
+            .map(|ballot| ballot.id.clone())
+            .collect();
+        if opening.is_empty() {
+            return;
+        }
+        let balances: HashMap<String, f64> = self.balances.keys()
+            .map(|address| (address.clone(), self.get_spendable_balance(address)))
+            .collect();
+        for id in opening {
+            if let Some(ballot) = self.ballots.get_mut(&id) {
+                ballot.snapshot = Some(balances.clone());
+            }
+        }
+    }
+
+    /// Checks that the lock time of `transaction` has expired, so it can go in the
+    /// next block (at `height() + 1`).
+    ///
+    /// Time locks are compared with the latest block's timestamp.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the transaction can be included in the next block
+    /// * `Err(String)` - If it is still locked (or its relative lock points to an unconfirmed transaction)
+    pub fn check_lock_time(&self, transaction: &Transaction) -> Result<(), String> {
+        let next_height = self.height + 1;
+        let unlocked = match &transaction.lock_time {
+            LockTime::None => true,
+            LockTime::Height(height) => next_height >= *height,
+            LockTime::Timestamp(time) => self.time >= *time,
+            LockTime::RelativeHeight { transaction_id, blocks } => {
+                let (confirmed_at, _) = self.confirmation_of(transaction_id)?;
+                next_height >= confirmed_at + blocks
+            }
+            LockTime::RelativeTime { transaction_id, seconds } => {
+                let (_, confirmed_time) = self.confirmation_of(transaction_id)?;
+                self.time as u64 >= confirmed_time as u64 + *seconds as u64
+            }
+        };
+        if !unlocked {
+            return Err(format!("Transaction is locked until {:?}", transaction.lock_time));
+        }
+        Ok(())
+    }
+
+    fn confirmation_of(&self, transaction_id: &str) -> Result<(u64, u32), String> {
+        self.get_confirmation(transaction_id)
+            .ok_or(format!("Transaction {} is not confirmed yet", transaction_id))
+    }
+
+    /// Returns the nonce the next transaction from `address` must use.
+    pub fn get_nonce(&self, address: &str) -> u64 {
+        *self.nonces.get(address).unwrap_or(&0)
+    }
+
+    /// Returns the on-chain balance of `address` (0 if it never received anything).
+    pub fn get_balance(&self, address: &str) -> f64 {
+        *self.balances.get(address).unwrap_or(&0.0)
+    }
+
+    /// Adds `amount` to the balance of `address`.
+    pub fn credit(&mut self, address: &str, amount: f64) {
+        *self.balances.entry(address.to_string()).or_insert(0.0) += amount;
+        self.touch(address);
+    }
+
+    /// Removes `amount` from the balance of `address`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the address had enough funds
+    /// * `Err(String)` - If the balance is too low, the state is left untouched
+    pub fn debit(&mut self, address: &str, amount: f64) -> Result<(), String> {
+        let balance = self.get_balance(address);
+        if balance < amount {
+            return Err(format!("Insufficient on-chain balance for {}. Have: {}, Need: {}", address, balance, amount));
+        }
+        self.balances.insert(address.to_string(), balance - amount);
+        self.touch(address);
+        Ok(())
+    }
+
+    /// Returns the addresses whose balance or nonce changed since the last call, and
+    /// forgets them (`StateCache::connect` uses them after each block).
+    pub fn take_touched(&mut self) -> BTreeSet<String> {
+        std::mem::take(&mut self.touched)
+    }
+
+    fn touch(&mut self, address: &str) {
+        if !self.touched.contains(address) {
+            self.touched.insert(address.to_string());
+        }
+    }
+
+    /// Applies a single transaction to the state.
+    ///
+    /// The transaction's lock time must have expired (see `check_lock_time`).
+    /// The sender pays `amount + fee` (coinbase transactions have no sender to debit)
+    /// and the receiver gets `amount` (anchors transfer nothing, multi-recipient
+    /// payments credit each output, HTLCs, channels, escrows and streams hold it
+    /// until they are closed, UTXO transactions turn it into new outputs, deploys
+    /// fund the contract, calls send it to the contract). Funds
+    /// released to the sender (HTLC claim or refund, channel settle, stream
+    /// withdrawal or cancellation, spent UTXOs) can pay for the transaction. The
+    /// transaction must use the sender's next nonce, so two transactions with the
+    /// same nonce can never both be applied.
+    ///
+    /// The sender also sets aside `gas_limit * gas_price` and gets back the price of
+    /// the gas it did not use. Every transaction uses its intrinsic gas; a contract
+    /// call also uses the gas of the instructions it runs.
+    ///
+    /// Fees are not credited here: the block producer collects them (and the gas
+    /// paid) through the block's coinbase transactions.
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction` - The transaction to apply
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u64)` - The gas used (0 for coinbase transactions)
+    /// * `Err(String)` - If the sender cannot afford it (locked vesting excluded), the
+    ///   nonce is wrong, the gas limit is below the intrinsic gas or over the block
+    ///   gas limit, it is still locked, its script or a policy script rejects it, or
+    ///   a kind-specific rule is broken
+    pub fn apply_transaction(&mut self, transaction: &Transaction) -> Result<u64, String> {
+        if transaction.amount < 0.0 || transaction.fee < 0.0 || transaction.gas_price < 0.0 {
+            return Err("Transaction amount, fee and gas price cannot be negative".to_string());
+        }
+        if !transaction.is_coinbase() && transaction.gas_limit < transaction.intrinsic_gas() {
+            return Err(format!("Gas limit {} is below the intrinsic gas {}", transaction.gas_limit, transaction.intrinsic_gas()));
+        }
+        if transaction.gas_limit > BLOCK_GAS_LIMIT {
+            return Err(format!("Gas limit {} is over the block gas limit {}", transaction.gas_limit, BLOCK_GAS_LIMIT));
+        }
+        self.check_lock_time(transaction)?;
+        if !transaction.is_coinbase() && !self.assume_valid {
+            self.check_scripts(transaction)?;
+        }
+        let released = self.check_kind(transaction)?;
+
+        if !transaction.is_coinbase() {
+            let expected = self.get_nonce(&transaction.sender_address);
+            if transaction.nonce != expected {
+                return Err(format!("Invalid nonce for {}. Expected: {}, Got: {}", transaction.sender_address, expected, transaction.nonce));
+            }
+            let available = self.get_spendable_balance(&transaction.sender_address) + released;
+            if available < transaction.total_cost() {
+                return Err(format!("Insufficient on-chain balance for {}. Have: {}, Need: {}", transaction.sender_address, available, transaction.total_cost()));
+            }
+            self.credit(&transaction.sender_address, released);
+            self.debit(&transaction.sender_address, transaction.total_cost())?;
+            self.nonces.insert(transaction.sender_address.clone(), expected + 1);
+            self.touch(&transaction.sender_address);
+        }
+        let gas_used = self.apply_kind(transaction);
+        if transaction.is_coinbase() {
+            return Ok(0);
+        }
+        let unused = transaction.gas_limit.saturating_sub(gas_used);
+        self.credit(&transaction.sender_address, unused as f64 * transaction.gas_price);
+        Ok(gas_used)
+    }
+
+    /// Returns `true` if applying `transaction` only reads and writes the accounts it
+    /// names (see `parallel::touched_accounts`), so it can be applied on a state
+    /// holding just those accounts.
+    ///
+    /// That is the case for plain payments, multi-recipient payments, stealth payments
+    /// and anchors without a lock time or script, while no policy script is registered.
+    pub fn is_account_local(&self, transaction: &Transaction) -> bool {
+        let local_kind = matches!(
+            transaction.kind,
+            TransactionKind::Transfer | TransactionKind::StealthTransfer { .. } | TransactionKind::MultiPay { .. } | TransactionKind::Anchor { .. }
+        );
+        local_kind
+            && !transaction.is_coinbase()
+            && transaction.lock_time == LockTime::None
+            && transaction.script.is_none()
+            && self.rules.policies.is_empty()
+    }
+
+    /// Moves the balances and nonces of `addresses` into a new state at the same
+    /// height and time (their vesting grants are copied), to apply account-local
+    /// transactions on it; `merge_accounts` moves them back.
+    pub(crate) fn split_accounts(&mut self, addresses: &BTreeSet<&str>) -> ChainState {
+        let mut part = ChainState::new();
+        part.height = self.height;
+        part.time = self.time;
+        for &address in addresses {
+            if let Some(balance) = self.balances.remove(address) {
+                part.balances.insert(address.to_string(), balance);
+            }
+            if let Some(nonce) = self.nonces.remove(address) {
+                part.nonces.insert(address.to_string(), nonce);
+            }
+            if let Some(grants) = self.vesting.get(address) {
+                part.vesting.insert(address.to_string(), grants.clone());
+            }
+        }
+        part
+    }
+
+    /// Moves the balances and nonces of a state made by `split_accounts` back.
+    pub(crate) fn merge_accounts(&mut self, part: ChainState) {
+        self.balances.extend(part.balances);
+        self.nonces.extend(part.nonces);
+        self.touched.extend(part.touched);
+    }
+
+    /// Checks the rules specific to the transaction's kind, without changing anything.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(f64)` - Funds the transaction releases to its sender (HTLC claims and refunds)
+    /// * `Err(String)` - Why the transaction is invalid
+    fn check_kind(&self, transaction: &Transaction) -> Result<f64, String> {
+        let next_height = self.height + 1;
+        match &transaction.kind {
+            TransactionKind::Transfer => Ok(0.0),
+            // The ephemeral key is only meaningful to the receiver
+            TransactionKind::StealthTransfer { ephemeral_key } if ephemeral_key.is_empty() => {
+                Err("Stealth payments need an ephemeral key".to_string())
+            }
+            TransactionKind::StealthTransfer { .. } => Ok(0.0),
+            TransactionKind::Anchor { .. } => {
+                if transaction.amount != 0.0 {
+                    return Err("Anchor transactions cannot transfer funds".to_string());
+                }
+                Ok(0.0)
+            }
+            TransactionKind::MultiPay { outputs } => {
+                if outputs.is_empty() || outputs.iter().any(|output| output.amount < 0.0) {
+                    return Err("Multi-recipient payment has no outputs or a negative output".to_string());
+                }
+                let total: f64 = outputs.iter().map(|output| output.amount).sum();
+                if total != transaction.amount {
+                    return Err(format!("Outputs sum to {}, transaction amount is {}", total, transaction.amount));
+                }
+                Ok(0.0)
+            }
+            TransactionKind::HtlcLock { expiry_height, .. } => {
+                if transaction.amount <= 0.0 {
+                    return Err("HTLC amount must be positive".to_string());
+                }
+                if *expiry_height <= next_height {
+                    return Err(format!("HTLC expiry {} is not in the future", expiry_height));
+                }
+                Ok(0.0)
+            }
+            TransactionKind::HtlcClaim { htlc_id, preimage } => {
+                let htlc = self.htlc(htlc_id)?;
+                if transaction.sender_address != htlc.receiver {
+                    return Err("Only the HTLC receiver can claim it".to_string());
+                }
+                if htlc.is_expired_at(next_height) {
+                    return Err(format!("HTLC {} has expired", htlc_id));
+                }
+                if !htlc.matches_preimage(preimage) {
+                    return Err("Preimage does not match the hash lock".to_string());
+                }
+                Ok(htlc.amount)
+            }
+            TransactionKind::HtlcRefund { htlc_id } => {
+                let htlc = self.htlc(htlc_id)?;
+                if transaction.sender_address != htlc.sender {
+                    return Err("Only the HTLC sender can get a refund".to_string());
+                }
+                if !htlc.is_expired_at(next_height) {
+                    return Err(format!("HTLC {} cannot be refunded before height {}", htlc_id, htlc.expiry_height));
+                }
+                Ok(htlc.amount)
+            }
+            TransactionKind::ChannelOpen => {
+                if transaction.amount <= 0.0 {
+                    return Err("Channel capacity must be positive".to_string());
+                }
+                if transaction.receiver_address == transaction.sender_address {
+                    return Err("Cannot open a channel with yourself".to_string());
+                }
+                Ok(0.0)
+            }
+            TransactionKind::ChannelClose { state } => {
+                let channel = self.channel(&state.channel_id)?;
+                if !channel.is_party(&transaction.sender_address) {
+                    return Err(format!("{} is not a party of channel {}", transaction.sender_address, channel.id));
+                }
+                channel.check_state(state)?;
+                if let ChannelStatus::Closing { state: current, dispute_until } = &channel.status {
+                    if next_height >= *dispute_until {
+                        return Err(format!("Dispute window of channel {} is over", channel.id));
+                    }
+                    if state.sequence <= current.sequence {
+                        return Err(format!("Channel state {} is not newer than {}", state.sequence, current.sequence));
+                    }
+                }
+                Ok(0.0)
+            }
+            TransactionKind::ChannelSettle { channel_id } => {
+                let channel = self.channel(channel_id)?;
+                if !channel.is_party(&transaction.sender_address) {
+                    return Err(format!("{} is not a party of channel {}", transaction.sender_address, channel.id));
+                }
+                match &channel.status {
+                    ChannelStatus::Closing { state, dispute_until } if next_height >= *dispute_until => {
+                        Ok(channel.balance_of(state, &transaction.sender_address))
+                    }
+                    _ => Err(format!("Channel {} cannot be settled yet", channel_id)),
+                }
+            }
+            TransactionKind::EscrowOpen { arbiter } => {
+                if transaction.amount <= 0.0 {
+                    return Err("Escrow amount must be positive".to_string());
+                }
+                let (buyer, seller) = (&transaction.sender_address, &transaction.receiver_address);
+                if buyer == seller || buyer == arbiter || seller == arbiter {
+                    return Err("Buyer, seller and arbiter must be different".to_string());
+                }
+                Ok(0.0)
+            }
+            TransactionKind::EscrowVote { escrow_id, .. } => {
+                let escrow = self.escrows.get(escrow_id).ok_or(format!("No escrow {}", escrow_id))?;
+                if escrow.status != EscrowStatus::Funded {
+                    return Err(format!("Escrow {} is already settled", escrow_id));
+                }
+                if !escrow.is_party(&transaction.sender_address) {
+                    return Err(format!("{} is not a party of escrow {}", transaction.sender_address, escrow_id));
+                }
+                if escrow.has_voted(&transaction.sender_address) {
+                    return Err(format!("{} already voted on escrow {}", transaction.sender_address, escrow_id));
+                }
+                Ok(0.0)
+            }
+            TransactionKind::VestingGrant { schedule } => {
+                if transaction.amount <= 0.0 {
+                    return Err("Vesting amount must be positive".to_string());
+                }
+                schedule.check(transaction.amount)?;
+                Ok(0.0)
+            }
+            TransactionKind::StreamOpen { rate_per_block } => {
+                if transaction.amount <= 0.0 || *rate_per_block <= 0.0 {
+                    return Err("Stream deposit and rate must be positive".to_string());
+                }
+                Ok(0.0)
+            }
+            TransactionKind::StreamWithdraw { stream_id } => {
+                let stream = self.stream(stream_id)?;
+                if transaction.sender_address != stream.receiver {
+                    return Err("Only the stream receiver can withdraw".to_string());
+                }
+                Ok(stream.withdrawable_at(next_height))
+            }
+            TransactionKind::StreamCancel { stream_id } => {
+                let stream = self.stream(stream_id)?;
+                if transaction.sender_address != stream.sender {
+                    return Err("Only the stream sender can cancel it".to_string());
+                }
+                Ok(stream.refundable_at(next_height))
+            }
+            TransactionKind::Utxo { inputs, outputs } => {
+                if inputs.is_empty() && outputs.is_empty() {
+                    return Err("A UTXO transaction needs inputs or outputs".to_string());
+                }
+                if outputs.iter().any(|output| output.amount < 0.0) {
+                    return Err("UTXO outputs cannot be negative".to_string());
+                }
+                let output_value: f64 = outputs.iter().map(|output| output.amount).sum();
+                if output_value != transaction.amount {
+                    return Err(format!("Outputs sum to {}, transaction amount is {}", output_value, transaction.amount));
+                }
+                let mut input_value = 0.0;
+                for (i, input) in inputs.iter().enumerate() {
+                    if inputs[..i].contains(input) {
+                        return Err(format!("Output {}:{} is spent twice (double spend)", input.transaction_id, input.index));
+                    }
+                    let output = self.utxos.check_unspent(input)?;
+                    if output.address != transaction.sender_address {
+                        return Err(format!("Output {}:{} is not owned by {}", input.transaction_id, input.index, transaction.sender_address));
+                    }
+                    input_value += output.amount;
+                }
+                Ok(input_value)
+            }
+            TransactionKind::CoinJoin { inputs, outputs } => {
+                if outputs.iter().any(|output| output.amount < 0.0) {
+                    return Err("CoinJoin outputs cannot be negative".to_string());
+                }
+                let output_value: f64 = outputs.iter().map(|output| output.amount).sum();
+                if output_value != transaction.amount {
+                    return Err(format!("Outputs sum to {}, transaction amount is {}", output_value, transaction.amount));
+                }
+                let mut owners = BTreeSet::new();
+                let mut input_value = 0.0;
+                for (i, input) in inputs.iter().enumerate() {
+                    let outpoint = &input.outpoint;
+                    if inputs[..i].iter().any(|other| other.outpoint == *outpoint) {
+                        return Err(format!("Output {}:{} is spent twice (double spend)", outpoint.transaction_id, outpoint.index));
+                    }
+                    let output = self.utxos.check_unspent(outpoint)?;
+                    if input.signature.is_empty() {
+                        return Err(format!("Output {}:{} is not signed by {}", outpoint.transaction_id, outpoint.index, output.address));
+                    }
+                    owners.insert(output.address.clone());
+                    input_value += output.amount;
+                }
+                if owners.len() < MIN_COINJOIN_PARTICIPANTS {
+                    return Err(format!("A CoinJoin needs inputs of at least {} owners", MIN_COINJOIN_PARTICIPANTS));
+                }
+                Ok(input_value)
+            }
+            TransactionKind::Deploy { .. } => {
+                transaction.check_payload()?;
+                Ok(0.0)
+            }
+            TransactionKind::Call { contract, .. } => {
+                transaction.check_payload()?;
+                if !self.contracts.contains_key(contract) {
+                    return Err(format!("No contract at {}", contract));
+                }
+                Ok(0.0)
+            }
+            TransactionKind::AssetIssue { .. } | TransactionKind::AssetMint { .. } | TransactionKind::AssetTransfer { .. }
+            | TransactionKind::NftMint { .. } | TransactionKind::NftTransfer { .. } | TransactionKind::NftBurn { .. }
+            | TransactionKind::NameRegister { .. } | TransactionKind::NameRenew { .. }
+            | TransactionKind::DidRegister { .. } | TransactionKind::DidUpdate { .. }
+            | TransactionKind::BallotCreate { .. } | TransactionKind::BallotVote { .. }
+            | TransactionKind::AuctionCreate { .. } | TransactionKind::AuctionReveal { .. }
+            | TransactionKind::AuctionSettle { .. }
+            | TransactionKind::PoolCreate { .. } | TransactionKind::PoolDeposit { .. }
+            | TransactionKind::PoolWithdraw { .. } | TransactionKind::PoolSwap { .. }
+            | TransactionKind::OrderPlace { .. } | TransactionKind::OrderCancel { .. }
+            | TransactionKind::OracleCreate { .. } | TransactionKind::OracleReport { .. }
+            | TransactionKind::BridgeLock { .. } | TransactionKind::BridgeRelay { .. } | TransactionKind::BridgeMint { .. }
+            | TransactionKind::BridgeBurn { .. } | TransactionKind::BridgeRelease { .. } if transaction.amount != 0.0 => {
+                Err(format!("{} transactions cannot transfer native coins", transaction.kind.name()))
+            }
+            TransactionKind::AssetIssue { ticker, decimals, max_supply, initial_supply } => {
+                self.assets.check_issue(&transaction.id(), ticker, *decimals, *max_supply, *initial_supply)?;
+                Ok(0.0)
+            }
+            TransactionKind::AssetMint { asset_id, quantity } => {
+                self.assets.check_mint(asset_id, &transaction.sender_address, *quantity)?;
+                Ok(0.0)
+            }
+            TransactionKind::AssetTransfer { asset_id, quantity } => {
+                self.assets.check_transfer(asset_id, &transaction.sender_address, *quantity)?;
+                Ok(0.0)
+            }
+            TransactionKind::NftMint { token_id, .. } => {
+                self.nfts.check_mint(token_id)?;
+                Ok(0.0)
+            }
+            TransactionKind::NftTransfer { token_id } | TransactionKind::NftBurn { token_id } => {
+                self.nfts.check_owner(token_id, &transaction.sender_address)?;
+                Ok(0.0)
+            }
+            TransactionKind::NameRegister { name, .. } => {
+                self.names.check_register(name, &transaction.sender_address, next_height)?;
+                Ok(0.0)
+            }
+            TransactionKind::NameRenew { name } => {
+                self.names.check_renew(name, &transaction.sender_address)?;
+                Ok(0.0)
+            }
+            TransactionKind::DidRegister { public_keys, services } => {
+                self.identities.check_register(&transaction.sender_address, public_keys, services)?;
+                Ok(0.0)
+            }
+            TransactionKind::DidUpdate { public_keys, services } => {
+                self.identities.check_update(&transaction.sender_address, public_keys, services)?;
+                Ok(0.0)
+            }
+            TransactionKind::BallotCreate { options, start_height, end_height, .. } => {
+                check_ballot(options, *start_height, *end_height, next_height)?;
+                Ok(0.0)
+            }
+            TransactionKind::BallotVote { ballot_id, option } => {
+                let ballot = self.ballots.get(ballot_id).ok_or(format!("No ballot {}", ballot_id))?;
+                if !ballot.is_open_at(next_height) {
+                    return Err(format!("Ballot {} accepts votes from height {} to {}", ballot_id, ballot.start_height, ballot.end_height - 1));
+                }
+                if *option >= ballot.options.len() {
+                    return Err(format!("Ballot {} has no option {}", ballot_id, option));
+                }
+                if ballot.has_voted(&transaction.sender_address) {
+                    return Err(format!("{} already voted on ballot {}", transaction.sender_address, ballot_id));
+                }
+                Ok(0.0)
+            }
+            TransactionKind::AuctionCreate { reserve_price, bidding_end, reveal_end, .. } => {
+                if *reserve_price < 0.0 || *bidding_end <= next_height || reveal_end.is_some_and(|end| end <= *bidding_end) {
+                    return Err("Auction needs a non-negative reserve price and bidding (then reveal) phases ending in the future".to_string());
+                }
+                Ok(0.0)
+            }
+            TransactionKind::AuctionBid { auction_id } => {
+                let auction = self.auction(auction_id)?;
+                if auction.is_sealed() || !auction.is_bidding_at(next_height) {
+                    return Err(format!("Auction {} does not accept open bids now", auction_id));
+                }
+                let highest = auction.highest_bid().and_then(|bid| bid.revealed).unwrap_or(0.0);
+                if transaction.amount <= highest || transaction.amount < auction.reserve_price {
+                    return Err(format!("Bid {} must beat {} and meet the reserve price {}", transaction.amount, highest, auction.reserve_price));
+                }
+                Ok(0.0)
+            }
+            TransactionKind::AuctionCommit { auction_id, .. } => {
+                let auction = self.auction(auction_id)?;
+                if !auction.is_sealed() || !auction.is_bidding_at(next_height) {
+                    return Err(format!("Auction {} does not accept sealed bids now", auction_id));
+                }
+                if transaction.amount <= 0.0 {
+                    return Err("A sealed bid needs a deposit".to_string());
+                }
+                if auction.bids.iter().any(|bid| bid.bidder == transaction.sender_address) {
+                    return Err(format!("{} already committed a bid", transaction.sender_address));
+                }
+                Ok(0.0)
+            }
+            TransactionKind::AuctionReveal { auction_id, bid, salt } => {
+                let auction = self.auction(auction_id)?;
+                if !auction.is_revealing_at(next_height) {
+                    return Err(format!("Auction {} does not accept reveals now", auction_id));
+                }
+                let committed = auction.bids.iter()
+                    .find(|b| b.bidder == transaction.sender_address && b.revealed.is_none())
+                    .ok_or(format!("{} has no bid to reveal", transaction.sender_address))?;
+                if committed.commitment.as_deref() != Some(commit_bid(&transaction.sender_address, *bid, salt).as_str()) {
+                    return Err("Bid and salt do not match the commitment".to_string());
+                }
+                Ok(0.0)
+            }
+            TransactionKind::AuctionSettle { auction_id } => {
+                let auction = self.auction(auction_id)?;
+                if auction.settled || !auction.is_over_at(next_height) {
+                    return Err(format!("Auction {} is not over or already settled", auction_id));
+                }
+                Ok(0.0)
+            }
+            TransactionKind::PoolCreate { asset_a, asset_b, amount_a, amount_b, fee_bps } => {
+                if asset_a == asset_b || *fee_bps > MAX_POOL_FEE_BPS {
+                    return Err(format!("A pool needs two different assets and a fee of at most {} basis points", MAX_POOL_FEE_BPS));
+                }
+                if self.find_pool(asset_a, asset_b).is_some() {
+                    return Err(format!("A pool already trades {} against {}", asset_a, asset_b));
+                }
+                self.assets.check_transfer(asset_a, &transaction.sender_address, *amount_a)?;
+                self.assets.check_transfer(asset_b, &transaction.sender_address, *amount_b)?;
+                LiquidityPool::new(&transaction.id(), asset_a, asset_b, *fee_bps).quote_add(*amount_a, *amount_b)?;
+                Ok(0.0)
+            }
+            TransactionKind::PoolDeposit { pool_id, amount_a, amount_b } => {
+                let pool = self.pool(pool_id)?;
+                pool.quote_add(*amount_a, *amount_b)?;
+                self.assets.check_transfer(&pool.asset_a, &transaction.sender_address, *amount_a)?;
+                self.assets.check_transfer(&pool.asset_b, &transaction.sender_address, *amount_b)?;
+                Ok(0.0)
+            }
+            TransactionKind::PoolWithdraw { pool_id, shares } => {
+                self.pool(pool_id)?.quote_remove(&transaction.sender_address, *shares)?;
+                Ok(0.0)
+            }
+            TransactionKind::PoolSwap { pool_id, asset_in, amount_in, min_out } => {
+                self.pool(pool_id)?.quote_swap(asset_in, *amount_in, *min_out)?;
+                self.assets.check_transfer(asset_in, &transaction.sender_address, *amount_in)?;
+                Ok(0.0)
+            }
+            TransactionKind::OrderPlace { base, quote, side, price, quantity } => {
+                if base == quote || *price == 0 || *quantity == 0 {
+                    return Err("An order needs two different assets, a price and a quantity".to_string());
+                }
+                if let Some(missing) = [base, quote].into_iter().find(|asset| self.assets.get(asset).is_none()) {
+                    return Err(format!("No asset {}", missing));
+                }
+                let order = ChainState::new_order(transaction, base, quote, *side, *price, *quantity);
+                let (asset, units) = order.locked().ok_or("Order value overflows".to_string())?;
+                self.assets.check_transfer(asset, &transaction.sender_address, units)?;
+                Ok(0.0)
+            }
+            TransactionKind::OrderCancel { order_id } => {
+                self.orders.check_cancel(order_id, &transaction.sender_address)?;
+                Ok(0.0)
+            }
+            TransactionKind::OracleCreate { feed, reporters, quorum, max_age } => {
+                self.oracles.check_create(feed, reporters, *quorum, *max_age)?;
+                Ok(0.0)
+            }
+            TransactionKind::OracleReport { feed, value } => {
+                self.oracles.check_report(feed, &transaction.sender_address, *value)?;
+                Ok(0.0)
+            }
+            TransactionKind::BridgeLock { chain_id, asset_id, ticker, decimals, quantity, .. } => {
+                self.bridge(chain_id)?;
+                let asset = self.assets.get(asset_id).ok_or(format!("No asset {}", asset_id))?;
+                if asset.ticker != *ticker || asset.decimals != *decimals || *quantity == 0 {
+                    return Err(format!("Lock of {} does not match the asset or moves nothing", asset_id));
+                }
+                self.assets.check_transfer(asset_id, &transaction.sender_address, *quantity)?;
+                Ok(0.0)
+            }
+            TransactionKind::BridgeRelay { chain_id, headers } => {
+                self.bridge(chain_id)?.check_headers(headers)?;
+                Ok(0.0)
+            }
+            TransactionKind::BridgeMint { chain_id, proof } => {
+                let bridge = self.bridge(chain_id)?;
+                bridge.check_proof(proof)?;
+                let TransactionKind::BridgeLock { chain_id: destination, asset_id, ticker, decimals, .. } = &proof.transaction.kind else {
+                    return Err("Proven transaction is not a bridge lock".to_string());
+                };
+                if *destination != bridge.local_chain {
+                    return Err(format!("Lock is for {}, not {}", destination, bridge.local_chain));
+                }
+                let wrapped = wrapped_asset_id(chain_id, asset_id);
+                if self.assets.get(&wrapped).is_none() {
+                    self.assets.check_issue(&wrapped, &wrapped_ticker(ticker), *decimals, u64::MAX, 0)?;
+                }
+                Ok(0.0)
+            }
+            TransactionKind::BridgeBurn { chain_id, asset_id, quantity, .. } => {
+                self.bridge(chain_id)?;
+                if *quantity == 0 {
+                    return Err("Bridged quantity must be positive".to_string());
+                }
+                self.assets.check_transfer(&wrapped_asset_id(chain_id, asset_id), &transaction.sender_address, *quantity)?;
+                Ok(0.0)
+            }
+            TransactionKind::BridgeRelease { chain_id, proof } => {
+                let bridge = self.bridge(chain_id)?;
+                bridge.check_proof(proof)?;
+                let TransactionKind::BridgeBurn { chain_id: destination, asset_id, quantity, .. } = &proof.transaction.kind else {
+                    return Err("Proven transaction is not a bridge burn".to_string());
+                };
+                if *destination != bridge.local_chain {
+                    return Err(format!("Burn is for {}, not {}", destination, bridge.local_chain));
+                }
+                self.assets.check_transfer(asset_id, &escrow_address(chain_id), *quantity)?;
+                Ok(0.0)
+            }
+            // The withdrawal is released to the sender, who can pay the fee out of it
+            #[cfg(feature = "privacy")]
+            TransactionKind::ConfidentialTransfer { inputs, outputs, withdrawal, proof } => {
+                if !transaction.receiver_address.is_empty() {
+                    return Err("A confidential transfer has no public receiver".to_string());
+                }
+                let (deposit, withdrawn) = (to_units(transaction.amount)?, to_units(*withdrawal)?);
+                if inputs.is_empty() && deposit == 0 {
+                    return Err("A confidential transfer needs inputs or a deposit".to_string());
+                }
+                self.confidential.check_transfer(&transaction.sender_address, inputs, outputs, deposit, withdrawn, proof)?;
+                Ok(*withdrawal)
+            }
+            #[cfg(feature = "privacy")]
+            TransactionKind::RingDeposit { key } => {
+                self.ring_pool.check_deposit(key, transaction.amount)?;
+                Ok(0.0)
+            }
+            // The withdrawn deposit is released to the sender, who can pay the fee out of it
+            #[cfg(feature = "privacy")]
+            TransactionKind::RingWithdraw { ring, signature } => {
+                if transaction.amount != 0.0 || !transaction.receiver_address.is_empty() {
+                    return Err("A ring withdrawal pays its sender and transfers no coins".to_string());
+                }
+                let message = withdrawal_message(&transaction.sender_address, transaction.nonce, transaction.fee);
+                match self.assume_valid {
+                    true => self.ring_pool.check_ring(ring, signature),
+                    false => self.ring_pool.check_withdraw(ring, signature, &message),
+                }
+            }
+        }
+    }
+
+    /// Applies the effects specific to the transaction's kind (credits, contracts)
+    /// and returns the gas used.
+    fn apply_kind(&mut self, transaction: &Transaction) -> u64 {
+        match &transaction.kind {
+            TransactionKind::Transfer | TransactionKind::StealthTransfer { .. } => {
+                self.credit(&transaction.receiver_address, transaction.amount)
+            }
+            TransactionKind::Anchor { .. } => {}
+            TransactionKind::MultiPay { outputs } => {
+                for output in outputs {
+                    self.credit(&output.address, output.amount);
+                }
+            }
+            TransactionKind::HtlcLock { hash_lock, expiry_height } => {
+                let id = transaction.id();
+                self.htlcs.insert(id.clone(), Htlc {
+                    id: id,
+                    sender: transaction.sender_address.clone(),
+                    receiver: transaction.receiver_address.clone(),
+                    amount: transaction.amount,
+                    hash_lock: hash_lock.clone(),
+                    expiry_height: *expiry_height,
+                });
+            }
+            TransactionKind::HtlcClaim { htlc_id, .. } | TransactionKind::HtlcRefund { htlc_id } => {
+                self.htlcs.remove(htlc_id);
+            }
+            TransactionKind::ChannelOpen => {
+                let id = transaction.id();
+                self.channels.insert(id.clone(), Channel {
+                    id: id,
+                    party_a: transaction.sender_address.clone(),
+                    party_b: transaction.receiver_address.clone(),
+                    capacity: transaction.amount,
+                    status: ChannelStatus::Open,
+                });
+            }
+            TransactionKind::ChannelClose { state } => {
+                let next_height = self.height + 1;
+                if let Some(channel) = self.channels.get_mut(&state.channel_id) {
+                    // A dispute keeps the original deadline
+                    let dispute_until = match &channel.status {
+                        ChannelStatus::Closing { dispute_until, .. } => *dispute_until,
+                        ChannelStatus::Open => next_height + DISPUTE_WINDOW,
+                    };
+                    channel.status = ChannelStatus::Closing { state: state.clone(), dispute_until: dispute_until };
+                }
+            }
+            TransactionKind::ChannelSettle { channel_id } => {
+                // The sender's share was released to it, pay the other party
+                if let Some(channel) = self.channels.remove(channel_id)
+                    && let ChannelStatus::Closing { state, .. } = &channel.status {
+                    let other = if transaction.sender_address == channel.party_a { &channel.party_b } else { &channel.party_a };
+                    self.credit(other, channel.balance_of(state, other));
+                }
+            }
+            TransactionKind::EscrowOpen { arbiter } => {
+                let id = transaction.id();
+                self.escrows.insert(id.clone(), Escrow {
+                    id: id,
+                    buyer: transaction.sender_address.clone(),
+                    seller: transaction.receiver_address.clone(),
+                    arbiter: arbiter.clone(),
+                    amount: transaction.amount,
+                    votes: Vec::new(),
+                    status: EscrowStatus::Funded,
+                });
+            }
+            TransactionKind::EscrowVote { escrow_id, outcome } => {
+                let Some(escrow) = self.escrows.get_mut(escrow_id) else { return transaction.intrinsic_gas() };
+                escrow.votes.push((transaction.sender_address.clone(), *outcome));
+                if escrow.votes_for(*outcome) >= ESCROW_VOTES_REQUIRED {
+                    escrow.status = match outcome {
+                        EscrowOutcome::Release => EscrowStatus::Released,
+                        EscrowOutcome::Refund => EscrowStatus::Refunded,
+                    };
+                    let (beneficiary, amount) = (escrow.beneficiary(*outcome).to_string(), escrow.amount);
+                    self.credit(&beneficiary, amount);
+                }
+            }
+            TransactionKind::VestingGrant { schedule } => {
+                self.credit(&transaction.receiver_address, transaction.amount);
+                self.vesting.entry(transaction.receiver_address.clone()).or_default().push(VestingGrant {
+                    id: transaction.id(),
+                    grantor: transaction.sender_address.clone(),
+                    beneficiary: transaction.receiver_address.clone(),
+                    amount: transaction.amount,
+                    schedule: schedule.clone(),
+                });
+            }
+            TransactionKind::StreamOpen { rate_per_block } => {
+                let id = transaction.id();
+                self.streams.insert(id.clone(), Stream {
+                    id: id,
+                    sender: transaction.sender_address.clone(),
+                    receiver: transaction.receiver_address.clone(),
+                    deposit: transaction.amount,
+                    rate_per_block: *rate_per_block,
+                    start_height: self.height + 1,
+                    withdrawn: 0.0,
+                });
+            }
+            TransactionKind::StreamWithdraw { stream_id } => {
+                // The withdrawn coins were released to the receiver
+                let next_height = self.height + 1;
+                if let Some(stream) = self.streams.get_mut(stream_id) {
+                    stream.withdrawn = stream.accrued_at(next_height);
+                    if stream.withdrawn >= stream.deposit {
+                        self.streams.remove(stream_id);
+                    }
+                }
+            }
+            TransactionKind::StreamCancel { stream_id } => {
+                // The refund was released to the sender, settle the receiver's share
+                let next_height = self.height + 1;
+                if let Some(stream) = self.streams.remove(stream_id) {
+                    self.credit(&stream.receiver, stream.withdrawable_at(next_height));
+                }
+            }
+            TransactionKind::Utxo { inputs, outputs } => {
+                // The inputs' value was released to the sender, which paid for the outputs
+                for input in inputs {
+                    let _ = self.utxos.spend(input);
+                }
+                let id = transaction.id();
+                for (index, output) in outputs.iter().enumerate() {
+                    self.utxos.add(OutPoint::new(id.clone(), index), output.clone());
+                }
+            }
+            TransactionKind::CoinJoin { inputs, outputs } => {
+                // Same as a UTXO transaction, with inputs of several owners
+                for input in inputs {
+                    let _ = self.utxos.spend(&input.outpoint);
+                }
+                let id = transaction.id();
+                for (index, output) in outputs.iter().enumerate() {
+                    self.utxos.add(OutPoint::new(id.clone(), index), output.clone());
+                }
+            }
+            TransactionKind::Deploy { code } => {
+                let address = transaction.id();
+                self.credit(&address, transaction.amount);
+                self.contracts.insert(address.clone(), Contract {
+                    address: address,
+                    creator: transaction.sender_address.clone(),
+                    code: code.clone(),
+                    storage: BTreeMap::new(),
+                });
+            }
+            TransactionKind::Call { contract, input } => {
+                let receipt = self.execute_call(transaction, contract, input);
+                let gas_used = receipt.gas_used;
+                self.call_receipts.insert(transaction.id(), receipt);
+                return gas_used;
+            }
+            // Checked by check_kind, these cannot fail
+            TransactionKind::AssetIssue { ticker, decimals, max_supply, initial_supply } => {
+                let _ = self.assets.issue(&transaction.id(), ticker, *decimals, *max_supply, &transaction.sender_address, *initial_supply);
+            }
+            TransactionKind::AssetMint { asset_id, quantity } => {
+                let _ = self.assets.mint(asset_id, &transaction.sender_address, *quantity);
+            }
+            TransactionKind::AssetTransfer { asset_id, quantity } => {
+                let _ = self.assets.transfer(asset_id, &transaction.sender_address, &transaction.receiver_address, *quantity);
+            }
+            TransactionKind::NftMint { token_id, metadata_hash } => {
+                let _ = self.nfts.mint(token_id, &transaction.sender_address, metadata_hash.clone());
+            }
+            TransactionKind::NftTransfer { token_id } => {
+                let _ = self.nfts.transfer(token_id, &transaction.sender_address, &transaction.receiver_address);
+            }
+            TransactionKind::NftBurn { token_id } => {
+                let _ = self.nfts.burn(token_id, &transaction.sender_address);
+            }
+            TransactionKind::NameRegister { name, target } => {
+                let _ = self.names.register(name, &transaction.sender_address, target, self.height + 1);
+            }
+            TransactionKind::NameRenew { name } => {
+                let _ = self.names.renew(name, &transaction.sender_address, self.height + 1);
+            }
+            TransactionKind::DidRegister { public_keys, services } => {
+                let _ = self.identities.register(&transaction.sender_address, public_keys.clone(), services.clone(), self.height + 1);
+            }
+            TransactionKind::DidUpdate { public_keys, services } => {
+                let _ = self.identities.update(&transaction.sender_address, public_keys.clone(), services.clone(), self.height + 1);
+            }
+            TransactionKind::BallotCreate { question, options, start_height, end_height, weighting } => {
+                let id = transaction.id();
+                self.ballots.insert(id.clone(), Ballot {
+                    id: id,
+                    creator: transaction.sender_address.clone(),
+                    question: question.clone(),
+                    options: options.clone(),
+                    start_height: *start_height,
+                    end_height: *end_height,
+                    weighting: *weighting,
+                    votes: Vec::new(),
+                    snapshot: None,
+                });
+                // A ballot opening in this very block weighs the balances as of now
+                self.snapshot_ballots(self.height + 1);
+            }
+            TransactionKind::BallotVote { ballot_id, option } => {
+                if let Some(ballot) = self.ballots.get_mut(ballot_id) {
+                    let weight = ballot.weight_of(&transaction.sender_address);
+                    ballot.votes.push((transaction.sender_address.clone(), *option, weight));
+                }
+            }
+            TransactionKind::AuctionCreate { item, reserve_price, bidding_end, reveal_end } => {
+                let id = transaction.id();
+                self.auctions.insert(id.clone(), Auction {
+                    id: id,
+                    seller: transaction.sender_address.clone(),
+                    item: item.clone(),
+                    reserve_price: *reserve_price,
+                    bidding_end: *bidding_end,
+                    reveal_end: *reveal_end,
+                    bids: Vec::new(),
+                    settled: false,
+                });
+            }
+            TransactionKind::AuctionBid { auction_id } | TransactionKind::AuctionCommit { auction_id, .. } => {
+                let commitment = match &transaction.kind {
+                    TransactionKind::AuctionCommit { commitment, .. } => Some(commitment.clone()),
+                    _ => None,
+                };
+                if let Some(auction) = self.auctions.get_mut(auction_id) {
+                    auction.bids.push(Bid {
+                        bidder: transaction.sender_address.clone(),
+                        deposit: transaction.amount,
+                        revealed: if commitment.is_none() { Some(transaction.amount) } else { None },
+                        commitment: commitment,
+                    });
+                }
+            }
+            TransactionKind::AuctionReveal { auction_id, bid, .. } => {
+                if let Some(auction) = self.auctions.get_mut(auction_id)
+                    && let Some(committed) = auction.bids.iter_mut().find(|b| b.bidder == transaction.sender_address && b.revealed.is_none()) {
+                    committed.revealed = Some(*bid);
+                }
+            }
+            TransactionKind::AuctionSettle { auction_id } => {
+                let Some(auction) = self.auctions.get_mut(auction_id) else { return transaction.intrinsic_gas() };
+                auction.settled = true;
+                let (seller, winner, refunds) = (auction.seller.clone(), auction.winning_bid(), auction.refunds());
+                if let Some((_, price)) = winner {
+                    self.credit(&seller, price);
+                }
+                for (bidder, amount) in refunds {
+                    self.credit(&bidder, amount);
+                }
+            }
+            // The reserves are held in the ledger by the pool's id
+            TransactionKind::PoolCreate { asset_a, asset_b, amount_a, amount_b, fee_bps } => {
+                let mut pool = LiquidityPool::new(&transaction.id(), asset_a, asset_b, *fee_bps);
+                let _ = pool.add_liquidity(&transaction.sender_address, *amount_a, *amount_b);
+                let _ = self.assets.transfer(asset_a, &transaction.sender_address, &pool.id, *amount_a);
+                let _ = self.assets.transfer(asset_b, &transaction.sender_address, &pool.id, *amount_b);
+                self.pools.insert(pool.id.clone(), pool);
+            }
+            TransactionKind::PoolDeposit { pool_id, amount_a, amount_b } => {
+                if let Some(pool) = self.pools.get_mut(pool_id) {
+                    let _ = pool.add_liquidity(&transaction.sender_address, *amount_a, *amount_b);
+                    let _ = self.assets.transfer(&pool.asset_a, &transaction.sender_address, pool_id, *amount_a);
+                    let _ = self.assets.transfer(&pool.asset_b, &transaction.sender_address, pool_id, *amount_b);
+                }
+            }
+            TransactionKind::PoolWithdraw { pool_id, shares } => {
+                if let Some(pool) = self.pools.get_mut(pool_id)
+                    && let Ok((amount_a, amount_b)) = pool.remove_liquidity(&transaction.sender_address, *shares) {
+                    let _ = self.assets.transfer(&pool.asset_a, pool_id, &transaction.sender_address, amount_a);
+                    let _ = self.assets.transfer(&pool.asset_b, pool_id, &transaction.sender_address, amount_b);
+                }
+            }
+            TransactionKind::PoolSwap { pool_id, asset_in, amount_in, min_out } => {
+                if let Some(pool) = self.pools.get_mut(pool_id)
+                    && let Ok(out) = pool.swap(asset_in, *amount_in, *min_out) {
+                    let _ = self.assets.transfer(asset_in, &transaction.sender_address, pool_id, *amount_in);
+                    let _ = self.assets.transfer(pool.other_asset(asset_in), pool_id, &transaction.sender_address, out);
+                }
+            }
+            // Locked assets are held in the ledger by the order's id
+            TransactionKind::OrderPlace { base, quote, side, price, quantity } => {
+                let order = ChainState::new_order(transaction, base, quote, *side, *price, *quantity);
+                if let Some((asset, units)) = order.locked() {
+                    let _ = self.assets.transfer(asset, &transaction.sender_address, &order.id, units);
+                }
+                let trades = self.orders.place(order, self.height + 1);
+                for trade in trades {
+                    let paid = trade.price * trade.quantity;
+                    let _ = self.assets.transfer(base, &trade.sell_order, &trade.buyer, trade.quantity);
+                    let _ = self.assets.transfer(quote, &trade.buy_order, &trade.seller, paid);
+                    // A buyer taking a cheaper ask gets back what it locked above the trade price
+                    if *side == Side::Buy && *price > trade.price {
+                        let _ = self.assets.transfer(quote, &trade.buy_order, &trade.buyer, (*price - trade.price) * trade.quantity);
+                    }
+                }
+            }
+            TransactionKind::OrderCancel { order_id } => {
+                if let Ok(order) = self.orders.cancel(order_id, &transaction.sender_address)
+                    && let Some((asset, units)) = order.locked() {
+                    let _ = self.assets.transfer(asset, order_id, &transaction.sender_address, units);
+                }
+            }
+            TransactionKind::OracleCreate { feed, reporters, quorum, max_age } => {
+                let _ = self.oracles.create(feed, &transaction.sender_address, reporters.clone(), *quorum, *max_age);
+            }
+            TransactionKind::OracleReport { feed, value } => {
+                let _ = self.oracles.report(feed, &transaction.sender_address, *value, self.height + 1);
+            }
+            TransactionKind::BridgeLock { chain_id, asset_id, quantity, .. } => {
+                let _ = self.assets.transfer(asset_id, &transaction.sender_address, &escrow_address(chain_id), *quantity);
+            }
+            TransactionKind::BridgeRelay { chain_id, headers } => {
+                if let Some(bridge) = self.bridges.get_mut(chain_id) {
+                    let _ = bridge.add_headers(headers);
+                }
+            }
+            // Wrapped units are issued by the bridge's escrow address, then paid out
+            TransactionKind::BridgeMint { chain_id, proof } => {
+                if let TransactionKind::BridgeLock { asset_id, ticker, decimals, quantity, recipient, .. } = &proof.transaction.kind {
+                    let (wrapped, issuer) = (wrapped_asset_id(chain_id, asset_id), escrow_address(chain_id));
+                    if self.assets.get(&wrapped).is_none() {
+                        let _ = self.assets.issue(&wrapped, &wrapped_ticker(ticker), *decimals, u64::MAX, &issuer, 0);
+                    }
+                    let _ = self.assets.mint(&wrapped, &issuer, *quantity);
+                    let _ = self.assets.transfer(&wrapped, &issuer, recipient, *quantity);
+                }
+                self.claim(chain_id, proof);
+            }
+            TransactionKind::BridgeBurn { chain_id, asset_id, quantity, .. } => {
+                let _ = self.assets.burn(&wrapped_asset_id(chain_id, asset_id), &transaction.sender_address, *quantity);
+            }
+            TransactionKind::BridgeRelease { chain_id, proof } => {
+                if let TransactionKind::BridgeBurn { asset_id, quantity, recipient, .. } = &proof.transaction.kind {
+                    let _ = self.assets.transfer(asset_id, &escrow_address(chain_id), recipient, *quantity);
+                }
+                self.claim(chain_id, proof);
+            }
+            #[cfg(feature = "privacy")]
+            TransactionKind::ConfidentialTransfer { inputs, outputs, withdrawal, .. } => {
+                if let (Ok(deposit), Ok(withdrawn)) = (to_units(transaction.amount), to_units(*withdrawal)) {
+                    self.confidential.apply_transfer(&transaction.id(), inputs, outputs, deposit, withdrawn);
+                }
+            }
+            #[cfg(feature = "privacy")]
+            TransactionKind::RingDeposit { key } => self.ring_pool.deposit(key, transaction.amount),
+            #[cfg(feature = "privacy")]
+            TransactionKind::RingWithdraw { signature, .. } => self.ring_pool.withdraw(signature),
+        }
+        transaction.intrinsic_gas()
+    }
+
+    /// Runs a contract call and applies its effects if it succeeds.
+    ///
+    /// The code gets the gas limit minus the intrinsic gas. A failed call leaves
+    /// the contract unchanged, returns the coins sent to the caller and uses the
+    /// whole gas limit (the fee is still paid).
+    fn execute_call(&mut self, transaction: &Transaction, contract: &str, input: &[i64]) -> CallReceipt {
+        let intrinsic = transaction.intrinsic_gas();
+        let failed = |error: String| CallReceipt { success: false, return_value: None, error: Some(error), gas_used: transaction.gas_limit };
+        let Some(account) = self.contracts.get(contract) else {
+            self.credit(&transaction.sender_address, transaction.amount);
+            return failed(format!("No contract at {}", contract));
+        };
+        let context = CallContext {
+            caller: transaction.sender_address.clone(),
+            value: transaction.amount,
+            balance: self.get_balance(contract) + transaction.amount,
+            input: input.to_vec(),
+        };
+        match vm::execute(&account.code, &context, &account.storage, transaction.gas_limit - intrinsic) {
+            Ok(result) => {
+                self.credit(contract, transaction.amount);
+                for (recipient, amount) in &result.transfers {
+                    // The VM checked every transfer against the contract's balance
+                    let _ = self.debit(contract, *amount);
+                    self.credit(recipient, *amount);
+                }
+                if let Some(account) = self.contracts.get_mut(contract) {
+                    account.storage = result.storage;
+                }
+                CallReceipt { success: true, return_value: result.return_value, error: None, gas_used: intrinsic + result.gas_used }
+            }
+            Err(error) => {
+                self.credit(&transaction.sender_address, transaction.amount);
+                failed(error)
+            }
+        }
+    }
+
+    fn htlc(&self, id: &str) -> Result<&Htlc, String> {
+        self.htlcs.get(id).ok_or(format!("No open HTLC {}", id))
+    }
+
+    fn stream(&self, id: &str) -> Result<&Stream, String> {
+        self.streams.get(id).ok_or(format!("No active stream {}", id))
+    }
+
+    fn new_order(transaction: &Transaction, base: &str, quote: &str, side: Side, price: u64, quantity: u64) -> Order {
+        Order {
+            id: transaction.id(),
+            owner: transaction.sender_address.clone(),
+            base: base.to_string(),
+            quote: quote.to_string(),
+            side: side,
+            price: price,
+            quantity: quantity,
+            sequence: 0,
+        }
+    }
+
+    fn bridge(&self, remote_chain: &str) -> Result<&BridgeLink, String> {
+        self.bridges.get(remote_chain).ok_or(format!("No bridge to {}", remote_chain))
+    }
+
+    /// Marks the remote transaction proven by `proof` as claimed.
+    fn claim(&mut self, remote_chain: &str, proof: &BridgeProof) {
+        if let Some(bridge) = self.bridges.get_mut(remote_chain) {
+            bridge.processed.insert(proof.transaction.id());
+        }
+    }
+
+    fn pool(&self, id: &str) -> Result<&LiquidityPool, String> {
+        self.pools.get(id).ok_or(format!("No liquidity pool {}", id))
+    }
+
+    fn auction(&self, id: &str) -> Result<&Auction, String> {
+        self.auctions.get(id).ok_or(format!("No auction {}", id))
+    }
+
+    fn channel(&self, id: &str) -> Result<&Channel, String> {
+        self.channels.get(id).ok_or(format!("No open channel {}", id))
+    }
+}
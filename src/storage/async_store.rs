@@ -0,0 +1,221 @@
+use std::future::Future;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::{Mutex, RwLock};
+use crate::block::block::{Block, BlockExt};
+use crate::blockchain::blockchain::Blockchain;
+use crate::storage::store::BLOCKS_FILE;
+
+/// Blocks stored by height, with an async API that never blocks the runtime.
+///
+/// Stores are append-only like a chain: `put_block` only accepts the next
+/// height. Nothing is validated here; `load_chain` replays the blocks through
+/// `Blockchain::from_blocks`.
+pub trait AsyncBlockStore: Send + Sync {
+    /// Stores `block` at `height`, which must be the number of blocks already stored.
+    fn put_block(&self, height: u64, block: &Block) -> impl Future<Output = Result<(), String>> + Send;
+
+    /// Returns the block at `height`, or `None` if there isn't one yet.
+    fn get_block(&self, height: u64) -> impl Future<Output = Result<Option<Block>, String>> + Send;
+
+    /// Returns how many blocks are stored (the genesis block included).
+    fn block_count(&self) -> impl Future<Output = u64> + Send;
+}
+
+/// An `AsyncBlockStore` keeping the blocks in memory (tests, caches).
+#[derive(Debug, Default)]
+pub struct MemoryBlockStore {
+    blocks: RwLock<Vec<Block>>,
+}
+
+impl MemoryBlockStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        MemoryBlockStore::default()
+    }
+}
+
+impl AsyncBlockStore for MemoryBlockStore {
+    async fn put_block(&self, height: u64, block: &Block) -> Result<(), String> {
+        let mut blocks = self.blocks.write().await;
+        check_next(height, blocks.len() as u64)?;
+        blocks.push(block.clone());
+        Ok(())
+    }
+
+    async fn get_block(&self, height: u64) -> Result<Option<Block>, String> {
+        Ok(self.blocks.read().await.get(height as usize).cloned())
+    }
+
+    async fn block_count(&self) -> u64 {
+        self.blocks.read().await.len() as u64
+    }
+}
+
+/// An `AsyncBlockStore` on the blocks file of a `ChainStore` directory, read
+/// and appended with `tokio::fs`.
+///
+/// Opening the store reads the file once to find where each line starts, so
+/// `get_block` reads just one block. Don't let a `ChainStore` rewrite the file
+/// while the store is open: the offsets would no longer match.
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::storage::async_store::{load_chain, save_chain, AsyncBlockStore, FileBlockStore};
+///
+/// let dir = std::env::temp_dir().join(format!("blockc-async-store-doc-{}", std::process::id()));
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// runtime.block_on(async {
+///     let mut chain = Blockchain::with_settings(1, 50.0);
+///     chain.add_block(Vec::new(), "Miner").unwrap();
+///
+///     let store = FileBlockStore::open(&dir).await.unwrap();
+///     assert_eq!(save_chain(&store, &chain).await.unwrap(), 2);
+///     chain.add_block(Vec::new(), "Miner").unwrap();
+///     assert_eq!(save_chain(&store, &chain).await.unwrap(), 1);
+///
+///     // Only the next height can be stored
+///     assert!(store.put_block(7, &chain.get_blocks()[0]).await.is_err());
+///
+///     let reopened = FileBlockStore::open(&dir).await.unwrap();
+///     assert_eq!(reopened.block_count().await, 3);
+///     let block = reopened.get_block(1).await.unwrap().unwrap();
+///     assert_eq!(block, chain.get_blocks()[1]);
+///     assert_eq!(reopened.get_block(3).await.unwrap(), None);
+///
+///     let loaded = load_chain(&reopened, 1, 50.0).await.unwrap();
+///     assert_eq!(loaded.get_balance("Miner"), 90.0);
+/// });
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct FileBlockStore {
+    path: PathBuf,
+    offsets: Mutex<Vec<u64>>,
+}
+
+impl FileBlockStore {
+    /// Opens (or creates) the blocks file of the store directory `dir`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(FileBlockStore)` - The store, with one entry per line of the file
+    /// * `Err(String)` - If the directory can't be created or the file read
+    pub async fn open(dir: &Path) -> Result<Self, String> {
+        fs::create_dir_all(dir).await.map_err(|error| format!("Cannot create {}: {}", dir.display(), error))?;
+        let path = dir.join(BLOCKS_FILE);
+        let contents = match fs::read(&path).await {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(error) => return Err(format!("Cannot read {}: {}", path.display(), error)),
+        };
+        let mut offsets = Vec::new();
+        let mut start = 0;
+        for (index, byte) in contents.iter().enumerate() {
+            if *byte == b'\n' {
+                offsets.push(start as u64);
+                start = index + 1;
+            }
+        }
+        if start != contents.len() {
+            return Err(format!("{} ends with an incomplete line", path.display()));
+        }
+        // One offset past the last line, where the next block goes
+        offsets.push(contents.len() as u64);
+        Ok(FileBlockStore {
+            path: path,
+            offsets: Mutex::new(offsets),
+        })
+    }
+
+    fn error(&self, error: std::io::Error) -> String {
+        format!("Cannot access {}: {}", self.path.display(), error)
+    }
+}
+
+impl AsyncBlockStore for FileBlockStore {
+    async fn put_block(&self, height: u64, block: &Block) -> Result<(), String> {
+        let mut offsets = self.offsets.lock().await;
+        check_next(height, offsets.len() as u64 - 1)?;
+        let mut line = block.to_bytes();
+        line.push(b'\n');
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path).await.map_err(|error| self.error(error))?;
+        file.write_all(&line).await.map_err(|error| self.error(error))?;
+        file.sync_data().await.map_err(|error| self.error(error))?;
+        let end = offsets[offsets.len() - 1] + line.len() as u64;
+        offsets.push(end);
+        Ok(())
+    }
+
+    async fn get_block(&self, height: u64) -> Result<Option<Block>, String> {
+        let (start, end) = {
+            let offsets = self.offsets.lock().await;
+            match (offsets.get(height as usize), offsets.get(height as usize + 1)) {
+                (Some(start), Some(end)) => (*start, *end),
+                _ => return Ok(None),
+            }
+        };
+        let mut file = File::open(&self.path).await.map_err(|error| self.error(error))?;
+        file.seek(SeekFrom::Start(start)).await.map_err(|error| self.error(error))?;
+        let mut line = vec![0; (end - start) as usize];
+        file.read_exact(&mut line).await.map_err(|error| self.error(error))?;
+        Block::from_bytes(line.trim_ascii_end())
+            .map(Some)
+            .map_err(|error| format!("{} line {}: {}", BLOCKS_FILE, height + 1, error))
+    }
+
+    async fn block_count(&self) -> u64 {
+        self.offsets.lock().await.len() as u64 - 1
+    }
+}
+
+fn check_next(height: u64, count: u64) -> Result<(), String> {
+    if height != count {
+        return Err(format!("Block {} is not the next block ({} are stored)", height, count));
+    }
+    Ok(())
+}
+
+/// Stores the blocks of `chain` that `store` doesn't have yet.
+///
+/// # Returns
+///
+/// * `Ok(usize)` - Number of blocks stored
+/// * `Err(String)` - If the store holds a different chain (its last block is
+///   not in `chain`) or a block can't be stored
+pub async fn save_chain(store: &impl AsyncBlockStore, chain: &Blockchain) -> Result<usize, String> {
+    let count = store.block_count().await;
+    let blocks = chain.get_blocks();
+    if count > 0 {
+        let stored = store.get_block(count - 1).await?;
+        let known = blocks.get(count as usize - 1);
+        if stored.as_ref().map(|block| &block.block_hash) != known.map(|block| &block.block_hash) {
+            return Err(format!("The store's block {} is not part of this chain", count - 1));
+        }
+    }
+    for (height, block) in blocks.iter().enumerate().skip(count as usize) {
+        store.put_block(height as u64, block).await?;
+    }
+    Ok(blocks.len() - count as usize)
+}
+
+/// Reads every block of `store` and rebuilds the chain (see `Blockchain::from_blocks`).
+///
+/// # Returns
+///
+/// * `Ok(Blockchain)` - The validated chain (with an empty mempool)
+/// * `Err(String)` - If a block can't be read or the blocks are not a valid chain
+pub async fn load_chain(store: &impl AsyncBlockStore, difficulty: u32, block_reward: f64) -> Result<Blockchain, String> {
+    let count = store.block_count().await;
+    let mut blocks = Vec::with_capacity(count as usize);
+    for height in 0..count {
+        let block = store.get_block(height).await?
+            .ok_or(format!("Block {} is missing", height))?;
+        blocks.push(block);
+    }
+    Blockchain::from_blocks(difficulty, block_reward, blocks)
+}
@@ -0,0 +1,180 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use crate::block::block::Block;
+use crate::storage::async_store::AsyncBlockStore;
+
+/// Blocks a `CachedBlockStore` keeps by default.
+pub const DEFAULT_BLOCK_CACHE_BLOCKS: usize = 256;
+
+/// Counters of a `CachedBlockStore`.
+///
+/// # Fields
+///
+/// * `hits` - `get_block` calls answered from the cache
+/// * `misses` - `get_block` calls that read the underlying store
+/// * `evictions` - Blocks dropped to make room for others
+/// * `cached` - Blocks in the cache now
+/// * `capacity` - Blocks the cache holds at most
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BlockCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub cached: usize,
+    pub capacity: usize,
+}
+
+impl BlockCacheStats {
+    /// Returns the share of lookups answered from the cache (0 before any lookup).
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            return 0.0;
+        }
+        self.hits as f64 / lookups as f64
+    }
+}
+
+/// Least recently used blocks, by height.
+#[derive(Debug, Default)]
+struct Lru {
+    blocks: HashMap<u64, (Block, u64)>, // Block and the tick it was last used at
+    order: BTreeMap<u64, u64>,          // Tick to height, oldest first
+    tick: u64,
+    stats: BlockCacheStats,
+}
+
+impl Lru {
+    fn get(&mut self, height: u64) -> Option<Block> {
+        self.tick += 1;
+        let (block, used) = self.blocks.get_mut(&height)?;
+        self.order.remove(used);
+        *used = self.tick;
+        self.order.insert(self.tick, height);
+        Some(block.clone())
+    }
+
+    fn insert(&mut self, height: u64, block: Block) {
+        if self.stats.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        if let Some((_, used)) = self.blocks.insert(height, (block, self.tick)) {
+            self.order.remove(&used);
+        }
+        self.order.insert(self.tick, height);
+        while self.blocks.len() > self.stats.capacity {
+            let Some((_, oldest)) = self.order.pop_first() else { break };
+            self.blocks.remove(&oldest);
+            self.stats.evictions += 1;
+        }
+        self.stats.cached = self.blocks.len();
+    }
+}
+
+/// An `AsyncBlockStore` keeping the most recently used blocks of another store in
+/// memory, so explorers and RPC handlers asking for the same hot blocks don't
+/// read them from disk every time.
+///
+/// `get_block` answers from the cache when it can and caches what it reads;
+/// `put_block` writes through to the store and caches the new block (the tip is
+/// usually what gets asked for next). When the cache is full the least recently
+/// used block is dropped. Blocks never change once stored, so cached blocks
+/// never go stale.
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::storage::async_store::{save_chain, AsyncBlockStore, MemoryBlockStore};
+/// use blockc::storage::block_cache::CachedBlockStore;
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// runtime.block_on(async {
+///     let mut chain = Blockchain::with_settings(1, 50.0);
+///     chain.add_block(Vec::new(), "Miner").unwrap();
+///     chain.add_block(Vec::new(), "Miner").unwrap();
+///
+///     let store = CachedBlockStore::new(MemoryBlockStore::new(), 2);
+///     save_chain(&store, &chain).await.unwrap();
+///
+///     // Blocks 1 and 2 were cached when stored; block 0 was evicted
+///     assert_eq!(store.get_block(2).await.unwrap().unwrap(), chain.get_blocks()[2]);
+///     assert_eq!(store.get_block(0).await.unwrap().unwrap(), chain.get_blocks()[0]);
+///     store.get_block(0).await.unwrap();
+///
+///     let stats = store.stats();
+///     assert_eq!((stats.hits, stats.misses, stats.cached), (2, 1, 2));
+///     assert_eq!(stats.evictions, 2);
+/// });
+/// ```
+#[derive(Debug)]
+pub struct CachedBlockStore<S> {
+    inner: S,
+    cache: Mutex<Lru>,
+}
+
+impl<S: AsyncBlockStore> CachedBlockStore<S> {
+    /// Caches up to `capacity` blocks of `inner` (0 turns the cache off).
+    pub fn new(inner: S, capacity: usize) -> Self {
+        let cache = Lru {
+            stats: BlockCacheStats { capacity: capacity, ..BlockCacheStats::default() },
+            ..Lru::default()
+        };
+        CachedBlockStore {
+            inner: inner,
+            cache: Mutex::new(cache),
+        }
+    }
+
+    /// Returns the underlying store.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Returns the hit, miss and eviction counters.
+    pub fn stats(&self) -> BlockCacheStats {
+        self.lock().stats
+    }
+
+    /// Drops every cached block (the counters are kept).
+    pub fn clear(&self) {
+        let mut cache = self.lock();
+        cache.blocks.clear();
+        cache.order.clear();
+        cache.stats.cached = 0;
+    }
+
+    // The lock is never held across an await, so a std mutex is enough
+    fn lock(&self) -> std::sync::MutexGuard<'_, Lru> {
+        self.cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<S: AsyncBlockStore> AsyncBlockStore for CachedBlockStore<S> {
+    async fn put_block(&self, height: u64, block: &Block) -> Result<(), String> {
+        self.inner.put_block(height, block).await?;
+        self.lock().insert(height, block.clone());
+        Ok(())
+    }
+
+    async fn get_block(&self, height: u64) -> Result<Option<Block>, String> {
+        {
+            let mut cache = self.lock();
+            if let Some(block) = cache.get(height) {
+                cache.stats.hits += 1;
+                return Ok(Some(block));
+            }
+            cache.stats.misses += 1;
+        }
+        let block = self.inner.get_block(height).await?;
+        if let Some(block) = &block {
+            self.lock().insert(height, block.clone());
+        }
+        Ok(block)
+    }
+
+    async fn block_count(&self) -> u64 {
+        self.inner.block_count().await
+    }
+}
@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::codec::json::{self, JsonCodec, JsonValue};
+use crate::entity::entity::Entity;
+use crate::helpers::random::RandomSource;
+use crate::wallet::wallet::WatchOnly;
+
+/// File holding the keys, in a store's directory.
+pub const KEYS_FILE: &str = "keys.json";
+
+/// File holding the watch-only addresses, in a store's directory.
+pub const WATCH_FILE: &str = "watch.json";
+
+/// Reads the watch-only addresses saved at `path` (none if the file doesn't
+/// exist yet).
+///
+/// # Example
+///
+/// ```
+/// use blockc::storage::keystore::{load_watch_only, save_watch_only};
+/// use blockc::wallet::wallet::Wallet;
+///
+/// let path = std::env::temp_dir().join(format!("blockc-watch-doc-{}.json", std::process::id()));
+/// let mut wallet = Wallet::watching(load_watch_only(&path).unwrap());
+/// wallet.watch_address("ColdVault").unwrap();
+/// save_watch_only(&path, &wallet.watch_only).unwrap();
+///
+/// assert_eq!(load_watch_only(&path).unwrap(), wallet.watch_only);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn load_watch_only(path: &Path) -> Result<Vec<WatchOnly>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let text = fs::read_to_string(path).map_err(|error| format!("Cannot read {}: {}", path.display(), error))?;
+    json::parse(&text).and_then(|value| Vec::from_json(&value)).map_err(|error| format!("{}: {}", path.display(), error))
+}
+
+/// Writes watch-only addresses to `path`.
+pub fn save_watch_only(path: &Path, watch_only: &[WatchOnly]) -> Result<(), String> {
+    fs::write(path, JsonValue::Array(watch_only.iter().map(JsonCodec::to_json).collect()).to_string())
+        .map_err(|error| format!("Cannot write {}: {}", path.display(), error))
+}
+
+/// Key pairs of the addresses a user controls, saved in a JSON file.
+///
+/// Keys are stored in clear: fine for a learning chain, not for real funds.
+///
+/// # Example
+///
+/// ```
+/// use blockc::simulation::rng::SimRng;
+/// use blockc::storage::keystore::KeyStore;
+///
+/// let path = std::env::temp_dir().join(format!("blockc-keys-doc-{}.json", std::process::id()));
+/// let mut keys = KeyStore::load(&path).unwrap();
+/// let alice = keys.get_or_generate("Alice", &mut SimRng::new(1));
+/// keys.save().unwrap();
+///
+/// let reloaded = KeyStore::load(&path).unwrap();
+/// assert_eq!(reloaded.entity("Alice").unwrap().private_key, alice.private_key);
+/// assert!(reloaded.entity("Bob").is_none());
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct KeyStore {
+    path: PathBuf,
+    keys: BTreeMap<String, (String, String)>,
+}
+
+impl KeyStore {
+    /// Reads the keys saved at `path` (none if the file doesn't exist yet).
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let mut keys = BTreeMap::new();
+        if path.exists() {
+            let text = fs::read_to_string(path).map_err(|error| format!("Cannot read {}: {}", path.display(), error))?;
+            let value = json::parse(&text).map_err(|error| format!("{}: {}", path.display(), error))?;
+            for (address, pair) in value.as_object()? {
+                let members = pair.as_object()?;
+                let public_key: String = json::field(members, "public_key")?;
+                let private_key: String = json::field(members, "private_key")?;
+                keys.insert(address.clone(), (public_key, private_key));
+            }
+        }
+        Ok(KeyStore {
+            path: path.to_path_buf(),
+            keys: keys,
+        })
+    }
+
+    /// Returns the addresses with keys.
+    pub fn addresses(&self) -> Vec<String> {
+        self.keys.keys().cloned().collect()
+    }
+
+    /// Returns an entity (balance 0, nonce 0) with the keys of `address`.
+    pub fn entity(&self, address: &str) -> Option<Entity> {
+        let (public_key, private_key) = self.keys.get(address)?;
+        Some(Entity::new(address.to_string(), 0.0, Vec::new(), public_key.clone(), private_key.clone()))
+    }
+
+    /// Returns the entity of `address`, generating its keys from `rng` if it has none.
+    pub fn get_or_generate(&mut self, address: &str, rng: &mut dyn RandomSource) -> Entity {
+        if let Some(entity) = self.entity(address) {
+            return entity;
+        }
+        let entity = Entity::generate(address.to_string(), 0.0, rng);
+        self.keys.insert(address.to_string(), (entity.public_key.clone(), entity.private_key.clone()));
+        entity
+    }
+
+    /// Adds the keys of `entity` under its address, replacing any it had.
+    pub fn insert(&mut self, entity: &Entity) {
+        self.keys.insert(entity.address.clone(), (entity.public_key.clone(), entity.private_key.clone()));
+    }
+
+    /// Writes the keys to the file.
+    pub fn save(&self) -> Result<(), String> {
+        let members = self.keys.iter()
+            .map(|(address, (public_key, private_key))| {
+                (address.clone(), JsonValue::Object(vec![
+                    ("public_key".to_string(), JsonValue::String(public_key.clone())),
+                    ("private_key".to_string(), JsonValue::String(private_key.clone())),
+                ]))
+            })
+            .collect();
+        fs::write(&self.path, JsonValue::Object(members).to_string())
+            .map_err(|error| format!("Cannot write {}: {}", self.path.display(), error))
+    }
+}
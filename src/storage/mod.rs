@@ -0,0 +1,6 @@
+pub mod store;
+pub mod keystore;
+#[cfg(feature = "async")]
+pub mod async_store;
+#[cfg(feature = "async")]
+pub mod block_cache;
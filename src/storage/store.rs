@@ -0,0 +1,493 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::block::block::{Block, BlockExt, HashAlgorithm};
+use crate::block::bloom::FilterParams;
+use crate::blockchain::blockchain::{Blockchain, ChainDamage};
+use crate::codec::json::{self, field, JsonCodec, JsonValue};
+use crate::state::rules::LocalRules;
+use crate::transactions::transactions::{Transaction, TransactionExt};
+
+/// File holding the chain's settings (difficulty, block reward, address filter
+/// size and block hash algorithm).
+pub const SETTINGS_FILE: &str = "settings.json";
+
+/// File holding the blocks, one encoded block per line, genesis first.
+pub const BLOCKS_FILE: &str = "blocks.jsonl";
+
+/// File holding the transactions waiting in the mempool, one per line.
+pub const MEMPOOL_FILE: &str = "mempool.jsonl";
+
+/// File holding the rules added by the node operator (see `LocalRules`).
+pub const RULES_FILE: &str = "rules.json";
+
+/// File `ChainStore::repair` copies the damaged `BLOCKS_FILE` to before
+/// truncating it.
+pub const DAMAGED_BLOCKS_FILE: &str = "blocks.jsonl.damaged";
+
+/// What happened to the saved pending transactions when a chain was loaded.
+///
+/// # Fields
+///
+/// * `restored` - Transactions back in the mempool
+/// * `dropped` - Transactions left out, with why (confirmed since, no longer
+///   valid, or unreadable): a transaction id, or the line of `MEMPOOL_FILE`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MempoolRestore {
+    pub restored: usize,
+    pub dropped: Vec<(String, String)>,
+}
+
+/// What `ChainStore::repair` did.
+///
+/// # Fields
+///
+/// * `damage` - Block the chain was cut at: the first one that couldn't be
+///   decoded, is damaged (see `Blockchain::first_damaged_block`) or was rejected
+///   on replay (`None` if the store was sound; nothing was changed then)
+/// * `kept` - Blocks kept, the genesis included
+/// * `removed` - Blocks removed: the damaged one and every line after it
+/// * `mempool` - Pending transactions restored and dropped, those of the removed
+///   blocks included
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RepairReport {
+    pub damage: Option<ChainDamage>,
+    pub kept: usize,
+    pub removed: usize,
+    pub mempool: MempoolRestore,
+}
+
+/// Resubmits saved pending transactions to `chain`, dropping those it has
+/// confirmed since and those it no longer accepts.
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::entity::entity::Entity;
+/// use blockc::storage::store::restore_mempool;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// chain.add_block(Vec::new(), "Alice").unwrap();
+/// let fee = chain.get_base_fee() * 2.0;
+/// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+/// let confirmed = Transaction::create_payment(&mut alice, "Bob", 5.0, fee).unwrap();
+/// let pending = Transaction::create_payment(&mut alice, "Bob", 5.0, fee).unwrap();
+/// let unsigned = Transaction::create_unsigned_payment("Alice", "Bob", 1.0, fee, 2).unwrap();
+/// chain.add_block(vec![confirmed.clone()], "Miner").unwrap();
+///
+/// let restore = restore_mempool(&mut chain, vec![confirmed.clone(), pending.clone(), unsigned]);
+/// assert_eq!(restore.restored, 1);
+/// assert!(chain.mempool().contains(&pending.id()));
+/// assert_eq!(restore.dropped[0], (confirmed.id(), "Confirmed in block 2".to_string()));
+/// assert!(restore.dropped[1].1.contains("not signed"));
+/// ```
+pub fn restore_mempool(chain: &mut Blockchain, pending: Vec<Transaction>) -> MempoolRestore {
+    let mut restore = MempoolRestore::default();
+    for transaction in pending {
+        let id = transaction.id();
+        if let Some((height, _)) = chain.find_transaction(&id) {
+            restore.dropped.push((id, format!("Confirmed in block {}", height)));
+            continue;
+        }
+        match chain.submit_transaction(transaction) {
+            Ok(()) => restore.restored += 1,
+            Err(error) => restore.dropped.push((id, error)),
+        }
+    }
+    restore
+}
+
+/// A chain saved in a directory.
+///
+/// Blocks and pending transactions are stored with their JSON encoding (see
+/// `Block::to_bytes`), one per line, and the chain's `LocalRules` in
+/// `RULES_FILE`. Loading installs the rules, then replays every block (see
+/// `Blockchain::from_blocks_until_invalid`), so a damaged file is reported
+/// instead of trusted (`repair` truncates it to its last valid block).
+/// Files are replaced atomically (written next to the old one, then renamed).
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::storage::store::ChainStore;
+///
+/// let dir = std::env::temp_dir().join(format!("blockc-store-doc-{}", std::process::id()));
+/// let store = ChainStore::open(&dir);
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// store.init(&chain).unwrap();
+/// chain.add_block(Vec::new(), "Miner").unwrap();
+/// store.save(&chain).unwrap();
+///
+/// let loaded = store.load().unwrap();
+/// assert_eq!(loaded.get_latest_hash(), chain.get_latest_hash());
+/// assert_eq!(loaded.get_balance("Miner"), 45.0);
+///
+/// // Bridges opened by the operator are saved with the chain
+/// let remote = Blockchain::with_settings(1, 50.0);
+/// chain.open_bridge("A", "B", remote.get_blocks()[0].header(), 1).unwrap();
+/// store.save(&chain).unwrap();
+/// assert!(store.load().unwrap().state().get_bridge("B").is_some());
+///
+/// // So is the treasury's governance, so its spends replay
+/// chain.treasury_mut().add_governor("Alice".to_string());
+/// let id = chain.treasury_mut().propose("Bob".to_string(), 1.0, "Grant".to_string()).unwrap();
+/// chain.treasury_mut().approve(id, "Alice").unwrap();
+/// chain.add_block(vec![chain.treasury().create_spend(id).unwrap()], "Miner").unwrap();
+/// store.save(&chain).unwrap();
+/// assert_eq!(store.load().unwrap().treasury(), chain.treasury());
+///
+/// # #[cfg(feature = "scripting")] {
+/// // Policy scripts are saved with the chain and applied again on load
+/// chain.register_policy("max_amount", "amount <= 10.0").unwrap();
+/// store.save(&chain).unwrap();
+/// assert_eq!(store.load().unwrap().local_rules(), chain.local_rules());
+/// # }
+///
+/// // A store can only be created once
+/// assert!(store.init(&chain).is_err());
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChainStore {
+    dir: PathBuf,
+    assume_valid: Option<String>,
+}
+
+impl ChainStore {
+    /// Uses `dir` as the store's directory (nothing is read or written yet).
+    pub fn open(dir: &Path) -> Self {
+        ChainStore {
+            dir: dir.to_path_buf(),
+            assume_valid: None,
+        }
+    }
+
+    /// Trusts the scripts and signatures of the blocks up to the one hashed
+    /// `assume_valid` when loading (see `Blockchain::from_blocks_assume_valid`).
+    pub fn with_assume_valid(mut self, assume_valid: Option<String>) -> Self {
+        self.assume_valid = assume_valid;
+        self
+    }
+
+    /// Returns the store's directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns `true` if a chain was saved in the directory.
+    pub fn exists(&self) -> bool {
+        self.dir.join(SETTINGS_FILE).exists()
+    }
+
+    /// Creates the directory and saves `chain` in it.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The store is ready
+    /// * `Err(String)` - If a chain is already saved there or a file can't be written
+    pub fn init(&self, chain: &Blockchain) -> Result<(), String> {
+        if self.exists() {
+            return Err(format!("A chain already exists in {}", self.dir.display()));
+        }
+        fs::create_dir_all(&self.dir).map_err(|error| format!("Cannot create {}: {}", self.dir.display(), error))?;
+        self.save(chain)
+    }
+
+    /// Saves the settings, blocks, local rules and mempool of `chain`.
+    ///
+    /// Every change of the mempool should be saved (the CLI and `blockc serve`
+    /// do), so pending transactions survive a restart however the node stops.
+    pub fn save(&self, chain: &Blockchain) -> Result<(), String> {
+        let settings = JsonValue::Object(vec![
+            ("difficulty".to_string(), chain.get_difficulty().to_json()),
+            ("block_reward".to_string(), chain.get_block_reward().to_json()),
+            ("filter_bits".to_string(), chain.filter_params().bits.to_json()),
+            ("filter_hashes".to_string(), chain.filter_params().hashes.to_json()),
+            ("hash_algorithm".to_string(), chain.hash_algorithm().name().to_string().to_json()),
+        ]);
+        self.write(SETTINGS_FILE, &settings.to_string())?;
+
+        let blocks: Vec<String> = chain.get_blocks().iter()
+            .map(|block| String::from_utf8_lossy(&block.to_bytes()).into_owned())
+            .collect();
+        self.write(BLOCKS_FILE, &lines(&blocks))?;
+        self.write(RULES_FILE, &chain.local_rules().to_json().to_string())?;
+        self.save_mempool(chain)
+    }
+
+    /// Saves only the mempool of `chain` (its blocks are already saved).
+    pub fn save_mempool(&self, chain: &Blockchain) -> Result<(), String> {
+        let pending: Vec<String> = chain.mempool().transactions().iter()
+            .map(TransactionExt::to_json)
+            .collect();
+        self.write(MEMPOOL_FILE, &lines(&pending))
+    }
+
+    /// Loads the saved chain.
+    ///
+    /// Pending transactions the chain no longer accepts (e.g. confirmed since)
+    /// are dropped (see `load_reporting` to know which).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Blockchain)` - The chain with its mempool
+    /// * `Err(String)` - If a file is missing or malformed, or a block is invalid
+    pub fn load(&self) -> Result<Blockchain, String> {
+        self.load_reporting().map(|(chain, _)| chain)
+    }
+
+    /// Like `load`, but also tells which pending transactions were restored
+    /// and which were dropped (see `restore_mempool`). A damaged line of
+    /// `MEMPOOL_FILE` is dropped too, instead of failing the load.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::storage::store::{ChainStore, MEMPOOL_FILE};
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// use blockc::block::block::BlockExt;
+    ///
+    /// let dir = std::env::temp_dir().join(format!("blockc-mempool-doc-{}", std::process::id()));
+    /// let store = ChainStore::open(&dir);
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// let fee = chain.get_base_fee() * 2.0;
+    /// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+    /// chain.submit_transaction(Transaction::create_payment(&mut alice, "Bob", 5.0, fee).unwrap()).unwrap();
+    /// store.init(&chain).unwrap();
+    ///
+    /// // The node restarts: the payment is still pending
+    /// let (mut restarted, restore) = store.load_reporting().unwrap();
+    /// assert_eq!((restore.restored, restore.dropped.len()), (1, 0));
+    ///
+    /// // Once mined (by a node that didn't save), it is dropped on the next start
+    /// restarted.add_block_from_mempool("Miner").unwrap();
+    /// store.save_mempool(&chain).unwrap();
+    /// std::fs::write(dir.join("blocks.jsonl"), restarted.get_blocks().iter()
+    ///     .map(|block| format!("{}\n", String::from_utf8(block.to_bytes()).unwrap()))
+    ///     .collect::<String>()).unwrap();
+    /// let mempool = std::fs::read_to_string(dir.join(MEMPOOL_FILE)).unwrap();
+    /// std::fs::write(dir.join(MEMPOOL_FILE), format!("{}not json\n", mempool)).unwrap();
+    ///
+    /// let (loaded, restore) = store.load_reporting().unwrap();
+    /// assert_eq!(restore.restored, 0);
+    /// assert!(restore.dropped[0].1.starts_with("Confirmed in block 2"));
+    /// assert_eq!(restore.dropped[1].0, format!("{} line 2", MEMPOOL_FILE));
+    /// assert!(loaded.mempool().is_empty());
+    /// # std::fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    pub fn load_reporting(&self) -> Result<(Blockchain, MempoolRestore), String> {
+        let (blocks, unreadable) = self.read_blocks()?;
+        let (mut chain, invalid) = self.read_chain(blocks)?;
+        if let Some(damage) = invalid.or(unreadable) {
+            return Err(format!("{} line {}: {}; the {} blocks before it are valid", BLOCKS_FILE, damage.height + 1, damage, damage.height));
+        }
+        let restore = self.restore_saved_mempool(&mut chain, Vec::new())?;
+        Ok((chain, restore))
+    }
+
+    /// Truncates the saved chain to its last valid block, so the node can start
+    /// again and mine or sync the rest.
+    ///
+    /// The chain is cut at the first block whose line can't be decoded, that fails
+    /// the checks of `Blockchain::validate` (see `Blockchain::first_damaged_block`)
+    /// or that is rejected when the blocks are replayed with the saved local rules
+    /// (like `load`). If there is one, `BLOCKS_FILE` is copied to
+    /// `DAMAGED_BLOCKS_FILE`, then the blocks before it are saved, with the user
+    /// transactions of the removed blocks resubmitted to the mempool along with
+    /// the saved pending ones.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(RepairReport)` - The block the chain was cut at, with why, and what
+    ///   was kept
+    /// * `Err(String)` - If the settings or the genesis block are damaged, or a
+    ///   file can't be read or written
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::storage::store::{ChainStore, BLOCKS_FILE, DAMAGED_BLOCKS_FILE, RULES_FILE};
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    ///
+    /// let dir = std::env::temp_dir().join(format!("blockc-repair-doc-{}", std::process::id()));
+    /// let store = ChainStore::open(&dir);
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// for _ in 0..4 {
+    ///     chain.add_block(Vec::new(), "Miner").unwrap();
+    /// }
+    /// store.init(&chain).unwrap();
+    /// assert_eq!(store.repair().unwrap().damage, None);
+    ///
+    /// // Block 2 is altered on disk: loading names it and refuses the chain
+    /// let blocks = std::fs::read_to_string(dir.join(BLOCKS_FILE)).unwrap();
+    /// let mut lines: Vec<&str> = blocks.lines().collect();
+    /// let altered = lines[2].replacen("\"amount\":45", "\"amount\":450", 1);
+    /// lines[2] = &altered;
+    /// std::fs::write(dir.join(BLOCKS_FILE), lines.join("\n")).unwrap();
+    /// let error = store.load().unwrap_err();
+    /// assert!(error.starts_with("blocks.jsonl line 3: Block 2:"), "{}", error);
+    ///
+    /// let report = store.repair().unwrap();
+    /// assert_eq!(report.damage.unwrap().height, 2);
+    /// assert_eq!((report.kept, report.removed), (2, 3));
+    /// assert!(dir.join(DAMAGED_BLOCKS_FILE).exists());
+    ///
+    /// let repaired = store.load().unwrap();
+    /// assert_eq!(repaired.get_latest_hash(), chain.get_blocks()[1].block_hash);
+    ///
+    /// // Blocks the saved rules no longer accept are cut too, and the reason kept
+    /// let remote = Blockchain::with_settings(1, 50.0);
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.open_bridge("B", "A", remote.get_blocks()[0].header(), 1).unwrap();
+    /// chain.add_block(Vec::new(), "Bob").unwrap();
+    /// let mut bob = Entity::new("Bob".to_string(), 45.0, Vec::new(), "b".to_string(), "b".to_string());
+    /// let relay = Transaction::relay_headers(&mut bob, "A", Vec::new(), chain.get_base_fee() * 2.0).unwrap();
+    /// chain.add_block(vec![relay], "Miner").unwrap();
+    /// store.save(&chain).unwrap();
+    /// std::fs::remove_file(dir.join(RULES_FILE)).unwrap();
+    /// assert!(store.load().is_err());
+    /// let report = store.repair().unwrap();
+    /// let damage = report.damage.unwrap();
+    /// assert_eq!(damage.height, 2);
+    /// assert_eq!(damage.reason, "No bridge to A");
+    /// assert_eq!((report.kept, report.removed), (2, 1));
+    /// assert_eq!(std::fs::read_to_string(dir.join(DAMAGED_BLOCKS_FILE)).unwrap().lines().count(), 3);
+    /// assert_eq!(store.load().unwrap().get_blocks().len(), 2);
+    /// # std::fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    pub fn repair(&self) -> Result<RepairReport, String> {
+        let (stored, unreadable) = self.read_blocks()?;
+        let (mut chain, rejected) = self.read_chain(stored.clone())?;
+        // The chain is cut at the first block that is damaged or rejected
+        let damaged = Blockchain::first_damaged_block(chain.get_difficulty(), chain.hash_algorithm(), &stored).or(unreadable);
+        let Some(damage) = [rejected, damaged].into_iter().flatten().min_by_key(|damage| damage.height) else {
+            return Ok(RepairReport {
+                damage: None,
+                kept: stored.len(),
+                removed: 0,
+                mempool: MempoolRestore::default(),
+            });
+        };
+        if damage.height == 0 {
+            return Err(format!("{} line 1: the genesis block is damaged ({})", BLOCKS_FILE, damage.reason));
+        }
+        if chain.get_blocks().len() != damage.height {
+            (chain, _) = self.read_chain(stored[..damage.height].to_vec())?;
+        }
+        let path = self.dir.join(BLOCKS_FILE);
+        fs::copy(&path, self.dir.join(DAMAGED_BLOCKS_FILE))
+            .map_err(|error| format!("Cannot back up {}: {}", path.display(), error))?;
+
+        let lines = self.read(BLOCKS_FILE)?.lines().count();
+        let removed = stored.iter().skip(damage.height)
+            .flat_map(|block| block.transaction.iter().filter(|transaction| !transaction.is_coinbase()).cloned())
+            .collect();
+        let mempool = self.restore_saved_mempool(&mut chain, removed)?;
+        self.save(&chain)?;
+        Ok(RepairReport {
+            kept: chain.get_blocks().len(),
+            removed: lines - damage.height,
+            damage: Some(damage),
+            mempool: mempool,
+        })
+    }
+
+    /// Decodes the saved blocks up to the first line that can't be decoded.
+    ///
+    /// Returns the decoded blocks and the undecodable one, if any.
+    fn read_blocks(&self) -> Result<(Vec<Block>, Option<ChainDamage>), String> {
+        let mut blocks = Vec::new();
+        let mut unreadable = None;
+        for (height, text) in self.read(BLOCKS_FILE)?.lines().enumerate() {
+            match Block::from_bytes(text.as_bytes()) {
+                Ok(block) => blocks.push(block),
+                Err(error) => {
+                    unreadable = Some(ChainDamage {
+                        height: height,
+                        reason: format!("cannot be decoded ({})", error),
+                    });
+                    break;
+                }
+            }
+        }
+        if let Some(damage) = unreadable.as_ref().filter(|damage| damage.height == 0) {
+            return Err(format!("{} line 1: the genesis block {}", BLOCKS_FILE, damage.reason));
+        }
+        Ok((blocks, unreadable))
+    }
+
+    /// Reads the settings and the local rules, and replays `blocks` with them up
+    /// to the first one that can't be connected.
+    ///
+    /// Returns the chain of the blocks connected and the first one rejected, if any.
+    fn read_chain(&self, blocks: Vec<Block>) -> Result<(Blockchain, Option<ChainDamage>), String> {
+        let settings = json::parse(&self.read(SETTINGS_FILE)?)
+            .map_err(|error| format!("{}: {}", SETTINGS_FILE, error))?;
+        let members = settings.as_object()?;
+        let difficulty: u32 = field(members, "difficulty")?;
+        let block_reward: f64 = field(members, "block_reward")?;
+        // Stores saved before the hash algorithm was configurable are SHA-256
+        let hash_algorithm = match members.iter().any(|(key, _)| key == "hash_algorithm") {
+            true => HashAlgorithm::parse(&field::<String>(members, "hash_algorithm")?)?,
+            false => HashAlgorithm::Sha256,
+        };
+        // Stores saved before rules were kept have none
+        let rules = match self.dir.join(RULES_FILE).exists() {
+            true => json::parse(&self.read(RULES_FILE)?)
+                .and_then(|value| LocalRules::from_json(&value))
+                .map_err(|error| format!("{}: {}", RULES_FILE, error))?,
+            false => LocalRules::default(),
+        };
+        let (mut chain, invalid) = Blockchain::from_blocks_until_invalid(difficulty, block_reward, hash_algorithm, blocks, self.assume_valid.as_deref(), &rules)
+            .map_err(|error| format!("{} line 1: {}", BLOCKS_FILE, error))?;
+        // Stores saved before address filters were configurable keep the defaults
+        if members.iter().any(|(key, _)| key == "filter_bits") {
+            chain.set_filter_params(FilterParams {
+                bits: field(members, "filter_bits")?,
+                hashes: field(members, "filter_hashes")?,
+            })?;
+        }
+        Ok((chain, invalid))
+    }
+
+    /// Resubmits `pending` then the saved pending transactions to `chain` (see
+    /// `restore_mempool`); lines of `MEMPOOL_FILE` that can't be decoded are dropped.
+    fn restore_saved_mempool(&self, chain: &mut Blockchain, mut pending: Vec<Transaction>) -> Result<MempoolRestore, String> {
+        let saved = if self.dir.join(MEMPOOL_FILE).exists() { self.read(MEMPOOL_FILE)? } else { String::new() };
+        let mut unreadable = Vec::new();
+        for (line, text) in saved.lines().enumerate() {
+            match <Transaction as TransactionExt>::from_json(text) {
+                Ok(transaction) => pending.push(transaction),
+                Err(error) => unreadable.push((format!("{} line {}", MEMPOOL_FILE, line + 1), error)),
+            }
+        }
+        let mut restore = restore_mempool(chain, pending);
+        restore.dropped.extend(unreadable);
+        Ok(restore)
+    }
+
+    fn read(&self, name: &str) -> Result<String, String> {
+        let path = self.dir.join(name);
+        fs::read_to_string(&path).map_err(|error| format!("Cannot read {}: {}", path.display(), error))
+    }
+
+    fn write(&self, name: &str, contents: &str) -> Result<(), String> {
+        let path = self.dir.join(name);
+        let temporary = self.dir.join(format!("{}.tmp", name));
+        fs::write(&temporary, contents)
+            .and_then(|_| fs::rename(&temporary, &path))
+            .map_err(|error| format!("Cannot write {}: {}", path.display(), error))
+    }
+}
+
+fn lines(items: &[String]) -> String {
+    items.iter().map(|item| format!("{}\n", item)).collect()
+}
@@ -0,0 +1,264 @@
+use crate::blockchain::blockchain::Blockchain;
+use crate::contracts::htlc::{hash_secret, Htlc};
+use crate::entity::entity::Entity;
+use crate::transactions::transactions::{Transaction, TransactionKind, TransactionExt};
+
+/// Where an atomic swap stands.
+///
+/// * `Created` - Terms agreed, nothing on chain yet
+/// * `Initiated` - The initiator locked its coins on chain A
+/// * `Participated` - The participant locked its coins on chain B
+/// * `SecretRevealed` - The initiator claimed on chain B, revealing the secret
+/// * `Completed` - The participant claimed on chain A with the revealed secret
+/// * `Refunded` - The swap was abandoned and the locked coins went back
+#[derive(Debug, Clone, PartialEq)]
+pub enum SwapStage {
+    Created,
+    Initiated,
+    Participated,
+    SecretRevealed,
+    Completed,
+    Refunded,
+}
+
+/// An HTLC-based atomic swap between two independent chains.
+///
+/// The initiator trades `amount_a` coins of chain A for `amount_b` coins of chain B:
+/// 1. The initiator picks a secret and locks `amount_a` on chain A, hashed with it
+/// 2. The participant checks that lock and locks `amount_b` on chain B with the same hash
+/// 3. The initiator claims on chain B, which publishes the secret
+/// 4. The participant reads the secret from chain B and claims on chain A
+///
+/// Either both claims can happen or neither does. The lock on chain A expires
+/// later than the one on chain B, so the participant always has time to claim
+/// once the secret is out; if the swap stalls, each side gets a refund after its
+/// lock expires.
+///
+/// Every step includes its transaction in a new block mined by `miner`.
+///
+/// # Fields
+///
+/// * `hash_lock` - Hash of the initiator's secret
+/// * `amount_a` - Coins of chain A paid by the initiator
+/// * `amount_b` - Coins of chain B paid by the participant
+/// * `timeout_a` - Blocks until the lock on chain A expires
+/// * `timeout_b` - Blocks until the lock on chain B expires (shorter than `timeout_a`)
+/// * `miner` - Address mining the blocks of the swap
+/// * `htlc_a` - Id of the lock on chain A, once created
+/// * `htlc_b` - Id of the lock on chain B, once created
+/// * `stage` - Progress of the swap
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::contracts::htlc::hash_secret;
+/// use blockc::entity::entity::Entity;
+/// use blockc::swap::swap::{AtomicSwap, SwapStage};
+///
+/// // Two chains with different parameters: Alice has coins on A, Bob on B
+/// let mut chain_a = Blockchain::new();
+/// let mut chain_b = Blockchain::with_settings(2, 100.0);
+/// chain_a.add_block(Vec::new(), "Alice").unwrap();
+/// chain_b.add_block(Vec::new(), "Bob").unwrap();
+///
+/// let mut alice_a = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+/// let mut alice_b = Entity::new("Alice".to_string(), 0.0, Vec::new(), "a".to_string(), "a".to_string());
+/// let mut bob_a = Entity::new("Bob".to_string(), 0.0, Vec::new(), "b".to_string(), "b".to_string());
+/// let mut bob_b = Entity::new("Bob".to_string(), 90.0, Vec::new(), "b".to_string(), "b".to_string());
+///
+/// let mut swap = AtomicSwap::new(hash_secret("open sesame"), 10.0, 50.0, 6, 3, "Miner").unwrap();
+/// swap.initiate(&mut chain_a, &mut alice_a, "Bob").unwrap();
+/// swap.participate(&chain_a, &mut chain_b, &mut bob_b, "Alice").unwrap();
+/// swap.redeem_b(&mut chain_b, &mut alice_b, "open sesame").unwrap();
+/// swap.redeem_a(&mut chain_a, &chain_b, &mut bob_a).unwrap();
+///
+/// assert_eq!(swap.stage, SwapStage::Completed);
+/// assert!(chain_a.get_balance("Bob") > 9.0);
+/// assert!(chain_b.get_balance("Alice") > 49.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AtomicSwap {
+    pub hash_lock: String,
+    pub amount_a: f64,
+    pub amount_b: f64,
+    pub timeout_a: u64,
+    pub timeout_b: u64,
+    pub miner: String,
+    pub htlc_a: Option<String>,
+    pub htlc_b: Option<String>,
+    pub stage: SwapStage,
+}
+
+impl AtomicSwap {
+    /// Agrees on the terms of a swap.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(AtomicSwap)` - The swap, in the `Created` stage
+    /// * `Err(String)` - If an amount is not positive or `timeout_a` is not longer than `timeout_b`
+    pub fn new(hash_lock: String, amount_a: f64, amount_b: f64, timeout_a: u64, timeout_b: u64, miner: &str) -> Result<Self, String> {
+        if amount_a <= 0.0 || amount_b <= 0.0 {
+            return Err("Swap amounts must be positive".to_string());
+        }
+        if timeout_b == 0 || timeout_a <= timeout_b {
+            return Err("The initiator's lock must expire after the participant's".to_string());
+        }
+        Ok(AtomicSwap {
+            hash_lock: hash_lock,
+            amount_a: amount_a,
+            amount_b: amount_b,
+            timeout_a: timeout_a,
+            timeout_b: timeout_b,
+            miner: miner.to_string(),
+            htlc_a: None,
+            htlc_b: None,
+            stage: SwapStage::Created,
+        })
+    }
+
+    /// Step 1: the initiator locks `amount_a` on chain A for the participant.
+    pub fn initiate(&mut self, chain_a: &mut Blockchain, initiator: &mut Entity, participant_address: &str) -> Result<(), String> {
+        self.expect_stage(SwapStage::Created)?;
+        let expiry = chain_a.get_blocks().len() as u64 + self.timeout_a;
+        let fee = chain_a.get_base_fee();
+        let lock = Transaction::create_htlc(initiator, participant_address, self.amount_a, fee, self.hash_lock.clone(), expiry)?;
+        chain_a.add_block(vec![lock.clone()], &self.miner)?;
+        self.htlc_a = Some(lock.id());
+        self.stage = SwapStage::Initiated;
+        Ok(())
+    }
+
+    /// Step 2: the participant checks the lock on chain A, then locks `amount_b` on
+    /// chain B for the initiator, with the same hash.
+    pub fn participate(&mut self, chain_a: &Blockchain, chain_b: &mut Blockchain, participant: &mut Entity, initiator_address: &str) -> Result<(), String> {
+        self.expect_stage(SwapStage::Initiated)?;
+        let htlc_a = self.lock_on(chain_a, &self.htlc_a)?;
+        if htlc_a.receiver != participant.address || htlc_a.amount != self.amount_a || htlc_a.hash_lock != self.hash_lock {
+            return Err("The lock on chain A does not match the swap terms".to_string());
+        }
+
+        let expiry = chain_b.get_blocks().len() as u64 + self.timeout_b;
+        let fee = chain_b.get_base_fee();
+        let lock = Transaction::create_htlc(participant, initiator_address, self.amount_b, fee, self.hash_lock.clone(), expiry)?;
+        chain_b.add_block(vec![lock.clone()], &self.miner)?;
+        self.htlc_b = Some(lock.id());
+        self.stage = SwapStage::Participated;
+        Ok(())
+    }
+
+    /// Step 3: the initiator claims `amount_b` on chain B, revealing the secret.
+    pub fn redeem_b(&mut self, chain_b: &mut Blockchain, initiator: &mut Entity, secret: &str) -> Result<(), String> {
+        self.expect_stage(SwapStage::Participated)?;
+        if hash_secret(secret) != self.hash_lock {
+            return Err("Secret does not match the swap's hash lock".to_string());
+        }
+        let htlc_b = self.lock_on(chain_b, &self.htlc_b)?;
+        let claim = Transaction::create_htlc_claim(initiator, &htlc_b, secret.to_string(), chain_b.get_base_fee())?;
+        chain_b.add_block(vec![claim], &self.miner)?;
+        self.stage = SwapStage::SecretRevealed;
+        Ok(())
+    }
+
+    /// Step 4: the participant reads the secret from chain B and claims `amount_a` on chain A.
+    pub fn redeem_a(&mut self, chain_a: &mut Blockchain, chain_b: &Blockchain, participant: &mut Entity) -> Result<(), String> {
+        self.expect_stage(SwapStage::SecretRevealed)?;
+        let htlc_b = self.htlc_b.as_deref().unwrap_or_default();
+        let secret = revealed_secret(chain_b, htlc_b).ok_or("The secret is not on chain B".to_string())?;
+        let htlc_a = self.lock_on(chain_a, &self.htlc_a)?;
+        let claim = Transaction::create_htlc_claim(participant, &htlc_a, secret, chain_a.get_base_fee())?;
+        chain_a.add_block(vec![claim], &self.miner)?;
+        self.stage = SwapStage::Completed;
+        Ok(())
+    }
+
+    /// Gives the initiator its coins back on chain A once the lock has expired.
+    ///
+    /// Possible as long as the participant has not claimed them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::contracts::htlc::hash_secret;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::swap::swap::{AtomicSwap, SwapStage};
+    ///
+    /// let mut chain_a = Blockchain::new();
+    /// let mut chain_b = Blockchain::with_settings(2, 100.0);
+    /// chain_a.add_block(Vec::new(), "Alice").unwrap();
+    /// chain_b.add_block(Vec::new(), "Bob").unwrap();
+    ///
+    /// let mut alice_a = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+    /// let mut bob_b = Entity::new("Bob".to_string(), 90.0, Vec::new(), "b".to_string(), "b".to_string());
+    ///
+    /// let mut swap = AtomicSwap::new(hash_secret("open sesame"), 10.0, 50.0, 4, 2, "Miner").unwrap();
+    /// swap.initiate(&mut chain_a, &mut alice_a, "Bob").unwrap();
+    /// swap.participate(&chain_a, &mut chain_b, &mut bob_b, "Alice").unwrap();
+    ///
+    /// // Alice never claims: both locks expire and both sides get their coins back
+    /// assert!(swap.refund_a(&mut chain_a, &mut alice_a).is_err());
+    /// for _ in 0..4 {
+    ///     chain_a.add_block(Vec::new(), "Miner").unwrap();
+    ///     chain_b.add_block(Vec::new(), "Miner").unwrap();
+    /// }
+    /// swap.refund_b(&mut chain_b, &mut bob_b).unwrap();
+    /// swap.refund_a(&mut chain_a, &mut alice_a).unwrap();
+    ///
+    /// assert_eq!(swap.stage, SwapStage::Refunded);
+    /// assert!(chain_a.get_balance("Alice") > 44.0);
+    /// assert_eq!(chain_a.get_balance("Bob"), 0.0);
+    /// ```
+    pub fn refund_a(&mut self, chain_a: &mut Blockchain, initiator: &mut Entity) -> Result<(), String> {
+        if !matches!(self.stage, SwapStage::Initiated | SwapStage::Participated | SwapStage::Refunded) {
+            return Err(format!("Cannot refund the initiator at stage {:?}", self.stage));
+        }
+        AtomicSwap::refund(chain_a, &self.htlc_a, initiator, &self.miner)?;
+        self.stage = SwapStage::Refunded;
+        Ok(())
+    }
+
+    /// Gives the participant its coins back on chain B once the lock has expired.
+    ///
+    /// Possible as long as the initiator has not claimed them.
+    pub fn refund_b(&mut self, chain_b: &mut Blockchain, participant: &mut Entity) -> Result<(), String> {
+        if !matches!(self.stage, SwapStage::Participated | SwapStage::Refunded) {
+            return Err(format!("Cannot refund the participant at stage {:?}", self.stage));
+        }
+        AtomicSwap::refund(chain_b, &self.htlc_b, participant, &self.miner)?;
+        self.stage = SwapStage::Refunded;
+        Ok(())
+    }
+
+    fn refund(chain: &mut Blockchain, htlc_id: &Option<String>, owner: &mut Entity, miner: &str) -> Result<(), String> {
+        let id = htlc_id.as_deref().ok_or("Nothing was locked".to_string())?;
+        let htlc = chain.state().get_htlc(id).ok_or(format!("No open HTLC {}", id))?.clone();
+        if !htlc.is_expired_at(chain.get_blocks().len() as u64) {
+            return Err(format!("HTLC {} cannot be refunded before height {}", id, htlc.expiry_height));
+        }
+        let refund = Transaction::create_htlc_refund(owner, &htlc, chain.get_base_fee())?;
+        chain.add_block(vec![refund], miner)
+    }
+
+    fn lock_on(&self, chain: &Blockchain, htlc_id: &Option<String>) -> Result<Htlc, String> {
+        let id = htlc_id.as_deref().ok_or("Nothing was locked".to_string())?;
+        chain.state().get_htlc(id).cloned().ok_or(format!("No open HTLC {}", id))
+    }
+
+    fn expect_stage(&self, stage: SwapStage) -> Result<(), String> {
+        if self.stage != stage {
+            return Err(format!("Swap is at stage {:?}, expected {:?}", self.stage, stage));
+        }
+        Ok(())
+    }
+}
+
+/// Returns the secret revealed by the claim of HTLC `htlc_id` on `chain`, if any.
+pub fn revealed_secret(chain: &Blockchain, htlc_id: &str) -> Option<String> {
+    chain.get_blocks().iter()
+        .flat_map(|block| block.transaction.iter())
+        .find_map(|transaction| match &transaction.kind {
+            TransactionKind::HtlcClaim { htlc_id: claimed, preimage } if claimed == htlc_id => Some(preimage.clone()),
+            _ => None,
+        })
+}
@@ -0,0 +1,291 @@
+use std::sync::Arc;
+use proptest::prelude::*;
+use crate::block::block::BlockHeader;
+use crate::blockchain::blockchain::{Blockchain, DEFAULT_BLOCK_REWARD};
+use crate::bridge::bridge::BridgeProof;
+use crate::contracts::ballot::VoteWeighting;
+use crate::contracts::channel::ChannelState;
+use crate::contracts::escrow::EscrowOutcome;
+use crate::contracts::vesting::VestingSchedule;
+use crate::dex::orderbook::Side;
+use crate::entity::entity::Entity;
+use crate::helpers::clock::ManualClock;
+use crate::identity::did::Service;
+use crate::merkle::merkle::{MerkleProof, ProofStep};
+use crate::transactions::transactions::{LockTime, Output, Transaction, TransactionKind, TransactionExt};
+use crate::utxo::coinjoin::JoinInput;
+use crate::utxo::utxo::OutPoint;
+use crate::vm::instruction::Instruction;
+
+// Payments and blocks need nothing but `blockc_core`, which implements
+// `Arbitrary` for them.
+pub use blockc_core::testing::{address, amount, block, memo, transaction, CHAIN_TIME};
+
+/// Blocks generated by `any::<Blockchain>()` at most (genesis excluded).
+pub const DEFAULT_MAX_BLOCKS: usize = 8;
+
+/// Addresses mining and paying each other in generated chains.
+pub const CHAIN_ADDRESSES: [&str; 3] = ["Alice", "Bob", "Carol"];
+
+/// Transactions of every kind (privacy ones aside) and lock time, with any
+/// field values: for codecs, not validation, as they are neither signed nor
+/// consistent.
+///
+/// # Example
+///
+/// ```
+/// use proptest::test_runner::TestRunner;
+/// use blockc::codec::wire;
+/// use blockc::testing::arbitrary::any_kind_transaction;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+/// use blockc::block::block::BlockExt;
+///
+/// // Both encodings give back the same transaction, and the wire one is canonical
+/// TestRunner::default().run(&any_kind_transaction(), |tx| {
+///     assert_eq!(Transaction::from_json(&tx.to_json()).unwrap(), tx);
+///     let bytes = tx.to_wire();
+///     let decoded = Transaction::from_wire(&bytes).unwrap();
+///     assert_eq!(decoded, tx);
+///     assert_eq!(wire::encode(&decoded), bytes);
+///     assert!(Transaction::from_wire(&bytes[..bytes.len() - 1]).is_err());
+///     Ok(())
+/// }).unwrap();
+/// ```
+pub fn any_kind_transaction() -> impl Strategy<Value = Transaction> {
+    let amounts = (number(), number(), any::<u64>(), any::<u32>(), any::<u64>(), number());
+    (text(), text(), text(), proptest::option::of(text()), kind(), lock_time(), proptest::option::of(text()), amounts)
+        .prop_map(|(sender_address, receiver_address, signature, memo, kind, lock_time, script, (amount, fee, nonce, timestamp, gas_limit, gas_price))| {
+            Transaction {
+                sender_address: sender_address,
+                receiver_address: receiver_address,
+                amount: amount,
+                fee: fee,
+                nonce: nonce,
+                timestamp: timestamp,
+                signature: signature,
+                memo: memo,
+                kind: kind,
+                lock_time: lock_time,
+                gas_limit: gas_limit,
+                gas_price: gas_price,
+                script: script,
+            }
+        })
+}
+
+/// Every lock time, with any values.
+pub fn lock_time() -> impl Strategy<Value = LockTime> {
+    prop_oneof![
+        Just(LockTime::None),
+        any::<u64>().prop_map(LockTime::Height),
+        any::<u32>().prop_map(LockTime::Timestamp),
+        (text(), any::<u64>()).prop_map(|(transaction_id, blocks)| LockTime::RelativeHeight { transaction_id: transaction_id, blocks: blocks }),
+        (text(), any::<u32>()).prop_map(|(transaction_id, seconds)| LockTime::RelativeTime { transaction_id: transaction_id, seconds: seconds }),
+    ]
+}
+
+/// Every transaction kind but the privacy ones, with any field values.
+pub fn kind() -> impl Strategy<Value = TransactionKind> {
+    let texts = || prop::collection::vec(text(), 0..4);
+    prop_oneof![
+        Just(TransactionKind::Transfer),
+        text().prop_map(|doc_hash| TransactionKind::Anchor { doc_hash: doc_hash }),
+        outputs().prop_map(|outputs| TransactionKind::MultiPay { outputs: outputs }),
+        (text(), any::<u64>()).prop_map(|(hash_lock, expiry_height)| TransactionKind::HtlcLock { hash_lock: hash_lock, expiry_height: expiry_height }),
+        (text(), text()).prop_map(|(htlc_id, preimage)| TransactionKind::HtlcClaim { htlc_id: htlc_id, preimage: preimage }),
+        text().prop_map(|htlc_id| TransactionKind::HtlcRefund { htlc_id: htlc_id }),
+        Just(TransactionKind::ChannelOpen),
+        (text(), any::<u64>(), number(), number(), text(), text()).prop_map(|(channel_id, sequence, balance_a, balance_b, signature_a, signature_b)| {
+            TransactionKind::ChannelClose { state: ChannelState { channel_id: channel_id, sequence: sequence, balance_a: balance_a, balance_b: balance_b, signature_a: signature_a, signature_b: signature_b } }
+        }),
+        text().prop_map(|channel_id| TransactionKind::ChannelSettle { channel_id: channel_id }),
+        text().prop_map(|arbiter| TransactionKind::EscrowOpen { arbiter: arbiter }),
+        (text(), prop_oneof![Just(EscrowOutcome::Release), Just(EscrowOutcome::Refund)]).prop_map(|(escrow_id, outcome)| TransactionKind::EscrowVote { escrow_id: escrow_id, outcome: outcome }),
+        prop_oneof![
+            (any::<u64>(), any::<u64>()).prop_map(|(start_height, end_height)| VestingSchedule::Linear { start_height: start_height, end_height: end_height }),
+            prop::collection::vec((any::<u64>(), number()), 0..4).prop_map(|unlocks| VestingSchedule::Cliffs { unlocks: unlocks }),
+        ].prop_map(|schedule| TransactionKind::VestingGrant { schedule: schedule }),
+        number().prop_map(|rate_per_block| TransactionKind::StreamOpen { rate_per_block: rate_per_block }),
+        text().prop_map(|stream_id| TransactionKind::StreamWithdraw { stream_id: stream_id }),
+        text().prop_map(|stream_id| TransactionKind::StreamCancel { stream_id: stream_id }),
+        (prop::collection::vec(outpoint(), 0..4), outputs()).prop_map(|(inputs, outputs)| TransactionKind::Utxo { inputs: inputs, outputs: outputs }),
+        (prop::collection::vec((outpoint(), text()), 0..4), outputs()).prop_map(|(inputs, outputs)| TransactionKind::CoinJoin {
+            inputs: inputs.into_iter().map(|(outpoint, signature)| JoinInput { outpoint: outpoint, signature: signature }).collect(),
+            outputs: outputs,
+        }),
+        prop::collection::vec(instruction(), 0..8).prop_map(|code| TransactionKind::Deploy { code: code }),
+        (text(), prop::collection::vec(any::<i64>(), 0..4)).prop_map(|(contract, input)| TransactionKind::Call { contract: contract, input: input }),
+        (text(), any::<u8>(), any::<u64>(), any::<u64>()).prop_map(|(ticker, decimals, max_supply, initial_supply)| {
+            TransactionKind::AssetIssue { ticker: ticker, decimals: decimals, max_supply: max_supply, initial_supply: initial_supply }
+        }),
+        (text(), any::<u64>()).prop_map(|(asset_id, quantity)| TransactionKind::AssetMint { asset_id: asset_id, quantity: quantity }),
+        (text(), any::<u64>()).prop_map(|(asset_id, quantity)| TransactionKind::AssetTransfer { asset_id: asset_id, quantity: quantity }),
+        (text(), proptest::option::of(text())).prop_map(|(token_id, metadata_hash)| TransactionKind::NftMint { token_id: token_id, metadata_hash: metadata_hash }),
+        text().prop_map(|token_id| TransactionKind::NftTransfer { token_id: token_id }),
+        text().prop_map(|token_id| TransactionKind::NftBurn { token_id: token_id }),
+        (text(), text()).prop_map(|(name, target)| TransactionKind::NameRegister { name: name, target: target }),
+        text().prop_map(|name| TransactionKind::NameRenew { name: name }),
+        (texts(), services()).prop_map(|(public_keys, services)| TransactionKind::DidRegister { public_keys: public_keys, services: services }),
+        (texts(), services()).prop_map(|(public_keys, services)| TransactionKind::DidUpdate { public_keys: public_keys, services: services }),
+        (text(), texts(), any::<u64>(), any::<u64>(), prop_oneof![Just(VoteWeighting::OnePerAddress), Just(VoteWeighting::Balance)])
+            .prop_map(|(question, options, start_height, end_height, weighting)| {
+                TransactionKind::BallotCreate { question: question, options: options, start_height: start_height, end_height: end_height, weighting: weighting }
+            }),
+        (text(), any::<usize>()).prop_map(|(ballot_id, option)| TransactionKind::BallotVote { ballot_id: ballot_id, option: option }),
+        (text(), number(), any::<u64>(), proptest::option::of(any::<u64>())).prop_map(|(item, reserve_price, bidding_end, reveal_end)| {
+            TransactionKind::AuctionCreate { item: item, reserve_price: reserve_price, bidding_end: bidding_end, reveal_end: reveal_end }
+        }),
+        text().prop_map(|auction_id| TransactionKind::AuctionBid { auction_id: auction_id }),
+        (text(), text()).prop_map(|(auction_id, commitment)| TransactionKind::AuctionCommit { auction_id: auction_id, commitment: commitment }),
+        (text(), number(), text()).prop_map(|(auction_id, bid, salt)| TransactionKind::AuctionReveal { auction_id: auction_id, bid: bid, salt: salt }),
+        text().prop_map(|auction_id| TransactionKind::AuctionSettle { auction_id: auction_id }),
+        (text(), text(), any::<u64>(), any::<u64>(), any::<u32>()).prop_map(|(asset_a, asset_b, amount_a, amount_b, fee_bps)| {
+            TransactionKind::PoolCreate { asset_a: asset_a, asset_b: asset_b, amount_a: amount_a, amount_b: amount_b, fee_bps: fee_bps }
+        }),
+        (text(), any::<u64>(), any::<u64>()).prop_map(|(pool_id, amount_a, amount_b)| TransactionKind::PoolDeposit { pool_id: pool_id, amount_a: amount_a, amount_b: amount_b }),
+        (text(), any::<u64>()).prop_map(|(pool_id, shares)| TransactionKind::PoolWithdraw { pool_id: pool_id, shares: shares }),
+        (text(), text(), any::<u64>(), any::<u64>()).prop_map(|(pool_id, asset_in, amount_in, min_out)| {
+            TransactionKind::PoolSwap { pool_id: pool_id, asset_in: asset_in, amount_in: amount_in, min_out: min_out }
+        }),
+        (text(), text(), prop_oneof![Just(Side::Buy), Just(Side::Sell)], any::<u64>(), any::<u64>()).prop_map(|(base, quote, side, price, quantity)| {
+            TransactionKind::OrderPlace { base: base, quote: quote, side: side, price: price, quantity: quantity }
+        }),
+        text().prop_map(|order_id| TransactionKind::OrderCancel { order_id: order_id }),
+        (text(), texts(), any::<usize>(), any::<u64>()).prop_map(|(feed, reporters, quorum, max_age)| {
+            TransactionKind::OracleCreate { feed: feed, reporters: reporters, quorum: quorum, max_age: max_age }
+        }),
+        (text(), number()).prop_map(|(feed, value)| TransactionKind::OracleReport { feed: feed, value: value }),
+        (text(), text(), text(), any::<u8>(), any::<u64>(), text()).prop_map(|(chain_id, asset_id, ticker, decimals, quantity, recipient)| {
+            TransactionKind::BridgeLock { chain_id: chain_id, asset_id: asset_id, ticker: ticker, decimals: decimals, quantity: quantity, recipient: recipient }
+        }),
+        (text(), prop::collection::vec(header(), 0..3)).prop_map(|(chain_id, headers)| TransactionKind::BridgeRelay { chain_id: chain_id, headers: headers }),
+        (text(), bridge_proof()).prop_map(|(chain_id, proof)| TransactionKind::BridgeMint { chain_id: chain_id, proof: Box::new(proof) }),
+        (text(), text(), any::<u64>(), text()).prop_map(|(chain_id, asset_id, quantity, recipient)| {
+            TransactionKind::BridgeBurn { chain_id: chain_id, asset_id: asset_id, quantity: quantity, recipient: recipient }
+        }),
+        (text(), bridge_proof()).prop_map(|(chain_id, proof)| TransactionKind::BridgeRelease { chain_id: chain_id, proof: Box::new(proof) }),
+        text().prop_map(|ephemeral_key| TransactionKind::StealthTransfer { ephemeral_key: ephemeral_key }),
+    ]
+}
+
+// Any text, or a hex hash (which some encodings store as bytes)
+fn text() -> impl Strategy<Value = String> {
+    prop_oneof!["\\PC{0,12}", "[0-9a-f]{64}"]
+}
+
+// Amounts in cents, or any finite number
+fn number() -> impl Strategy<Value = f64> {
+    prop_oneof![amount(), any::<f64>().prop_filter("finite", |number| number.is_finite())]
+}
+
+fn outputs() -> impl Strategy<Value = Vec<Output>> {
+    prop::collection::vec((text(), number()).prop_map(|(address, amount)| Output { address: address, amount: amount }), 0..4)
+}
+
+fn outpoint() -> impl Strategy<Value = OutPoint> {
+    (text(), any::<usize>()).prop_map(|(transaction_id, index)| OutPoint { transaction_id: transaction_id, index: index })
+}
+
+fn services() -> impl Strategy<Value = Vec<Service>> {
+    prop::collection::vec((text(), text(), text()).prop_map(|(id, kind, endpoint)| Service { id: id, kind: kind, endpoint: endpoint }), 0..3)
+}
+
+fn instruction() -> impl Strategy<Value = Instruction> {
+    prop_oneof![
+        any::<i64>().prop_map(Instruction::Push),
+        any::<usize>().prop_map(Instruction::Jump),
+        any::<usize>().prop_map(Instruction::JumpIf),
+        address().prop_map(Instruction::Transfer),
+        prop::sample::select(vec![
+            Instruction::Pop, Instruction::Dup, Instruction::Swap, Instruction::Add, Instruction::Sub, Instruction::Mul,
+            Instruction::Div, Instruction::Mod, Instruction::Eq, Instruction::Lt, Instruction::Gt, Instruction::Not,
+            Instruction::Load, Instruction::Store, Instruction::CallValue, Instruction::Balance, Instruction::TransferToCaller,
+            Instruction::Stop,
+        ]),
+    ]
+}
+
+fn header() -> impl Strategy<Value = BlockHeader> {
+    (text(), text(), any::<u32>(), any::<u32>(), number(), text(), text())
+        .prop_map(|(block_hash, previous_block_hash, time_stamp, nonce, base_fee, merkle_root, state_root)| BlockHeader {
+            block_hash: block_hash,
+            previous_block_hash: previous_block_hash,
+            time_stamp: time_stamp,
+            nonce: nonce,
+            base_fee: base_fee,
+            merkle_root: merkle_root,
+            state_root: state_root,
+        })
+}
+
+fn bridge_proof() -> impl Strategy<Value = BridgeProof> {
+    let steps = prop::collection::vec((text(), any::<bool>()).prop_map(|(hash, is_left)| ProofStep { hash: hash, is_left: is_left }), 0..4);
+    (text(), transaction(), text(), steps).prop_map(|(block_hash, transaction, leaf, steps)| BridgeProof {
+        block_hash: block_hash,
+        transaction: transaction,
+        proof: MerkleProof { leaf: leaf, steps: steps },
+    })
+}
+
+/// Valid chains of 1 to `max_blocks` blocks (difficulty 1) mined by the
+/// `CHAIN_ADDRESSES`, which pay each other whatever they can afford.
+///
+/// # Example
+///
+/// ```
+/// use proptest::test_runner::{Config, TestRunner};
+/// use blockc::testing::arbitrary::{chain, CHAIN_ADDRESSES};
+///
+/// // Generated chains pass validation, and the coins add up to the rewards
+/// let mut runner = TestRunner::new(Config::with_cases(16));
+/// runner.run(&chain(5), |chain| {
+///     assert!(chain.is_valid());
+///     let blocks = chain.get_blocks().len() as f64 - 1.0;
+///     let held: f64 = CHAIN_ADDRESSES.iter().map(|address| chain.get_balance(address)).sum();
+///     let supply = held + chain.get_balance(&chain.treasury().address) + chain.get_total_burned();
+///     assert!((supply - blocks * chain.get_block_reward()).abs() < 1e-6);
+///     Ok(())
+/// }).unwrap();
+/// ```
+pub fn chain(max_blocks: usize) -> impl Strategy<Value = Blockchain> {
+    let payment = (0..CHAIN_ADDRESSES.len(), 0..CHAIN_ADDRESSES.len(), 1u64..5_000);
+    let step = (0..CHAIN_ADDRESSES.len(), prop::collection::vec(payment, 0..4));
+    prop::collection::vec(step, 1..=max_blocks.max(1)).prop_map(build_chain)
+}
+
+/// A payment in a generated chain: sender and receiver (indexes into
+/// `CHAIN_ADDRESSES`) and the amount in cents.
+type PlannedPayment = (usize, usize, u64);
+
+/// Mines one block per step: the miner (an index into `CHAIN_ADDRESSES`) and
+/// its payments. Payments the sender can't afford (or to itself) are skipped.
+fn build_chain(steps: Vec<(usize, Vec<PlannedPayment>)>) -> Blockchain {
+    let mut chain = Blockchain::with_clock(1, DEFAULT_BLOCK_REWARD, Arc::new(ManualClock::new(CHAIN_TIME)));
+    for (miner, payments) in steps {
+        let fee = chain.get_base_fee() * 2.0;
+        let mut entities: Vec<Entity> = CHAIN_ADDRESSES.iter()
+            .map(|address| {
+                let mut entity = Entity::new(address.to_string(), chain.get_balance(address), Vec::new(), address.to_string(), address.to_string());
+                entity.nonce = chain.get_nonce(address);
+                entity
+            })
+            .collect();
+        let transactions = payments.into_iter()
+            .filter(|(sender, receiver, _)| sender != receiver)
+            .filter_map(|(sender, receiver, cents)| {
+                Transaction::create_payment(&mut entities[sender], CHAIN_ADDRESSES[receiver], cents as f64 / 100.0, fee).ok()
+            })
+            .collect();
+        chain.add_block(transactions, CHAIN_ADDRESSES[miner]).expect("generated blocks are valid");
+    }
+    chain
+}
+
+impl Arbitrary for Blockchain {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        chain(DEFAULT_MAX_BLOCKS).boxed()
+    }
+}
@@ -0,0 +1 @@
+pub mod arbitrary;
\ No newline at end of file
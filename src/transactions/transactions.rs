@@ -1,86 +1,165 @@
 use crate::helpers::{self};
 use crate::entity::entity::Entity;
+use crate::contracts::htlc::Htlc;
+use crate::contracts::channel::{Channel, ChannelState, ChannelStatus};
+use crate::contracts::escrow::EscrowOutcome;
+use crate::contracts::vesting::VestingSchedule;
+use crate::contracts::stream::Stream;
+use crate::contracts::ballot::{check_ballot, VoteWeighting};
+use crate::contracts::auction::commit_bid;
+use crate::utxo::coinjoin::JoinInput;
+use crate::utxo::utxo::OutPoint;
+use crate::assets::asset::check_definition;
+use crate::names::registry::{self, check_name};
+use crate::identity::did::{check_document, Service};
+use crate::dex::amm::MAX_POOL_FEE_BPS;
+use crate::dex::orderbook::Side;
+use crate::oracle::feed::check_feed;
+use crate::block::block::BlockHeader;
+use crate::bridge::bridge::BridgeProof;
+#[cfg(feature = "privacy")]
+use crate::privacy::confidential::{BalanceProof, ConfidentialOutput, NoteOpening};
+#[cfg(feature = "privacy")]
+use crate::privacy::ring::RingSignature;
+use crate::state::state::ChainState;
+use crate::vm::instruction::Instruction;
+use crate::codec::json::{self, JsonCodec};
+use crate::codec::wire;
 
+// The transaction types, their signing data and the checks that need no chain
+// live in `blockc_core`, so a device without `std` can build and sign them.
+pub use blockc_core::transaction::{
+    LockTime, Output, Transaction, TransactionKind, COINBASE_ADDRESS, ENCRYPTED_MEMO_OVERHEAD, ENCRYPTED_MEMO_PREFIX,
+    MAX_ENCRYPTED_MEMO_BYTES, MAX_MEMO_BYTES,
+};
 
-/// Represents a transaction between two entities in the blockchain.
-/// 
-/// Transactions store only addresses (not full Entity objects) to:
-/// - Avoid stale data issues
-/// - Match real blockchain design
-/// - Reduce memory usage
-/// - Improve data consistency
-/// 
-/// # Fields
-/// 
-/// * `sender_address` - Address of the entity sending funds
-/// * `receiver_address` - Address of the entity receiving funds
-/// * `amount` - Amount being transferred
-/// * `timestamp` - Unix timestamp when transaction was created
-/// * `signature` - Digital signature created by the sender
+/// Largest encoded transaction `Transaction::from_json` accepts.
+pub const MAX_ENCODED_TRANSACTION_BYTES: usize = 1024 * 1024;
+
+/// What the node and wallets do with transactions on top of `blockc_core`:
+/// stamping them with the clock, creating and signing them for an `Entity`
+/// (whose balance, nonce and history follow), and encoding them.
 /// 
 /// # Example
 /// 
 /// ```
-/// use blockc::transactions::transactions::Transaction;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
 /// use blockc::entity::entity::Entity;
 /// 
 /// // Create entities
-/// let mut alice = Entity::new(...);
-/// let mut bob = Entity::new(...);
+/// let mut alice = Entity::new("Alice".to_string(), 100.0, Vec::new(), "pub".to_string(), "priv".to_string());
+/// let mut bob = Entity::new("Bob".to_string(), 50.0, Vec::new(), "pub2".to_string(), "priv2".to_string());
 /// 
 /// // Create and execute transaction
-/// let transaction = Transaction::create_and_execute(&mut alice, &mut bob, 50.0)?;
+/// let transaction = Transaction::create_and_execute(&mut alice, &mut bob, 50.0).unwrap();
+/// assert_eq!(transaction.amount, 50.0);
 /// ```
-#[derive(Debug, Clone)]
-pub struct Transaction 
-{
-    pub sender_address: String,
-    pub receiver_address: String,
-    pub amount: f64,
-    pub timestamp: u32,
-    pub signature: String,
+pub trait TransactionExt: Sized {
+    /// Creates a coinbase transaction that pays `amount` of newly created coins.
+    /// 
+    /// Coinbase transactions are built by the chain itself when a block is added
+    /// (block reward, fees, treasury share), so they carry no signature.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `receiver_address` - Address receiving the new coins
+    /// * `amount` - Amount created
+    /// 
+    /// # Returns
+    /// 
+    /// A new coinbase `Transaction`.
+    fn coinbase(receiver_address: String, amount: f64) -> Self;
 
-}
+    /// Signs an unsigned transaction (see `create_unsigned_payment`) with the
+    /// keys of its sender.
+    /// 
+    /// Only the signature changes: the sender's balance and nonce were accounted
+    /// for when the transaction was built.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(())` - The transaction is signed
+    /// * `Err(String)` - If `signer` is not the sender, or the transaction is already signed
+    fn sign(&mut self, signer: &Entity) -> Result<(), String>;
 
-impl Transaction 
-{
-    fn new(sender_address: String, receiver_address: String, amount_tx: f64, signature: String, time_stamp: u32) -> Self
-    {
-        Transaction
-        {
-            sender_address: sender_address,
-            receiver_address: receiver_address,
-            amount: amount_tx,
-            timestamp: time_stamp,
-            signature: signature
-        }
-
-    }
-
-    fn create_and_sign(sender: &Entity, receiver: &Entity, amount_tx: f64) -> Self{
-        // create transaction
-        let time_stamp = helpers::helper_functions::get_time();
-        let transaction_data = format!("{}{}{}{}", 
-            sender.address,      
-            receiver.address,   
-            amount_tx,          
-            time_stamp          
-        );
+    /// Encodes the transaction as JSON (every field, the kind tagged by its name).
+    fn to_json(&self) -> String;
+
+    /// Decodes a transaction received from the network or read from storage.
+    /// 
+    /// Malformed input gives an error, never a panic. The transaction is only
+    /// decoded, not validated: submit it to the chain for that.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Transaction)` - The decoded transaction
+    /// * `Err(String)` - If the input is over `MAX_ENCODED_TRANSACTION_BYTES`, is
+    ///   not JSON or does not describe a transaction
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// 
+    /// let mut alice = Entity::new("Alice".to_string(), 100.0, Vec::new(), "pub".to_string(), "priv".to_string());
+    /// let tx = Transaction::create_payment_with_memo(&mut alice, "Bob", 10.0, 0.1, Some("Rent".to_string())).unwrap();
+    /// 
+    /// let json = tx.to_json();
+    /// assert_eq!(Transaction::from_json(&json).unwrap(), tx);
+    /// 
+    /// assert!(Transaction::from_json("{}").is_err());
+    /// assert!(Transaction::from_json(&json.replace("\"transfer\"", "\"teleport\"")).is_err());
+    /// ```
+    fn from_json(json: &str) -> Result<Self, String>;
 
-        // calling for signature
-        let signature = sender.sign(&transaction_data);
+    /// Encodes the transaction in the compact wire format (see `WireCodec`).
+    fn to_wire(&self) -> Vec<u8>;
 
-        // create new transaction using new (store addresses only)
-        Transaction::new(
-            sender.address.clone(),
-            receiver.address.clone(),
-            amount_tx, 
-            signature, 
-            time_stamp
-        )
-    }
+    /// Decodes a transaction encoded with `to_wire`, without validating it.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Transaction)` - The decoded transaction
+    /// * `Err(String)` - If the input is over `MAX_ENCODED_TRANSACTION_BYTES`,
+    ///   is truncated or malformed, or has bytes after the transaction
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// use blockc::block::block::BlockExt;
+    /// 
+    /// let mut alice = Entity::new("Alice".to_string(), 100.0, Vec::new(), "pub".to_string(), "priv".to_string());
+    /// let tx = Transaction::create_payment_with_memo(&mut alice, "Bob", 10.0, 0.1, Some("Rent".to_string())).unwrap();
+    /// 
+    /// let bytes = tx.to_wire();
+    /// assert_eq!(Transaction::from_wire(&bytes).unwrap(), tx);
+    /// assert!(bytes.len() * 4 < tx.to_json().len());
+    /// assert!(Transaction::from_wire(&bytes[1..]).is_err());
+    /// ```
+    fn from_wire(bytes: &[u8]) -> Result<Self, String>;
+
+    /// Creates a replacement for a pending transaction that pays a higher fee
+    /// (replace-by-fee).
+    /// 
+    /// The replacement keeps everything but the fee and timestamp of `original`
+    /// (same receiver, amount, kind and nonce), so only one of the two can ever be
+    /// included in the chain. The extra fee is deducted from the sender.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sender` - The entity that signed `original`
+    /// * `original` - The pending transaction to replace
+    /// * `new_fee` - The new, higher fee
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Transaction)` - The signed replacement, to be submitted to the mempool
+    /// * `Err(String)` - If `original` is not from `sender`, the fee is not higher, or the balance is too low
+    fn create_replacement(sender: &mut Entity, original: &Transaction, new_fee: f64) -> Result<Self, String>;
 
-    
     /// Creates, signs, validates, and executes a transaction between two entities.
     /// 
     /// This method handles the complete transaction flow:
@@ -104,7 +183,7 @@ impl Transaction
     /// # Example
     /// 
     /// ```
-    /// # use blockc::transactions::transactions::Transaction;
+    /// # use blockc::transactions::transactions::{Transaction, TransactionExt};
     /// # use blockc::entity::entity::Entity;
     /// # let mut alice = Entity::new("Alice".to_string(), 100.0, Vec::new(), "pub".to_string(), "priv".to_string());
     /// # let mut bob = Entity::new("Bob".to_string(), 50.0, Vec::new(), "pub2".to_string(), "priv2".to_string());
@@ -113,28 +192,2141 @@ impl Transaction
     ///     Err(e) => println!("Transaction failed: {}", e),
     /// }
     /// ```
-    pub fn create_and_execute(sender: &mut Entity, receiver: &mut Entity, amount: f64) -> Result<Self, String> {
-        // Validate sender has enough
-        if !sender.can_send(amount) {
-            return Err("Insufficient balance".to_string());
-        }
-        
-        // Create and sign transaction (pass references, not clones)
-        let transaction = Transaction::create_and_sign(
-            sender,    // &Entity reference
-            receiver,  // &Entity reference
-            amount
-        );
-        
-        // Update balances
-        sender.send_amount(amount)?;
-        receiver.receive_amount(amount);
-        
-        // Add to histories
-        sender.add_to_history(transaction.clone());
-        receiver.add_to_history(transaction.clone());
-        
-        Ok(transaction)
+    fn create_and_execute(sender: &mut Entity, receiver: &mut Entity, amount: f64) -> Result<Self, String>;
+
+    /// Same as [`Transaction::create_and_execute`], but the sender also pays `fee`
+    /// to whoever includes the transaction in a block.
+    /// 
+    /// The fee is deducted from the sender together with the amount; the receiver
+    /// only gets `amount`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sender` - Mutable reference to the sending entity
+    /// * `receiver` - Mutable reference to the receiving entity
+    /// * `amount` - Amount to transfer
+    /// * `fee` - Fee offered to the block producer
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Transaction)` - The created and executed transaction
+    /// * `Err(String)` - Error message if validation fails (e.g., insufficient balance)
+    fn create_and_execute_with_fee(sender: &mut Entity, receiver: &mut Entity, amount: f64, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a payment to an address, updating only the sender.
+    /// 
+    /// Used when the receiver is not a local `Entity` (e.g. paying an address taken
+    /// from the chain); the receiver is credited on chain once the transaction is
+    /// included in a block.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sender` - Mutable reference to the sending entity
+    /// * `receiver_address` - Address receiving the funds
+    /// * `amount` - Amount to transfer
+    /// * `fee` - Fee offered to the block producer
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Transaction)` - The signed transaction (sender balance, nonce and history updated)
+    /// * `Err(String)` - Error message if validation fails (e.g., insufficient balance)
+    fn create_payment(sender: &mut Entity, receiver_address: &str, amount: f64, fee: f64) -> Result<Self, String>;
+
+    /// Builds a payment without signing it, for an address whose private key is
+    /// kept elsewhere (see `Wallet::build_unsigned_payment`).
+    /// 
+    /// The key holder signs it with `sign` before it is submitted.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sender_address` - Address paying
+    /// * `receiver_address` - Address receiving the funds
+    /// * `amount` - Amount to transfer
+    /// * `fee` - Fee offered to the block producer
+    /// * `nonce` - Next nonce of the sender
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Transaction)` - The unsigned transaction
+    /// * `Err(String)` - If the amount or fee is negative
+    fn create_unsigned_payment(sender_address: &str, receiver_address: &str, amount: f64, fee: f64, nonce: u64) -> Result<Self, String>;
+
+    /// Same as [`Transaction::create_payment`], with an optional memo.
+    /// 
+    /// The memo is part of the signed data and of the block hash, so it cannot be
+    /// changed once the transaction is signed.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sender` - Mutable reference to the sending entity
+    /// * `receiver_address` - Address receiving the funds
+    /// * `amount` - Amount to transfer
+    /// * `fee` - Fee offered to the block producer
+    /// * `memo` - Optional note, at most `MAX_MEMO_BYTES` bytes
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Transaction)` - The signed transaction (sender balance, nonce and history updated)
+    /// * `Err(String)` - If the memo is too long or the sender cannot afford it
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// use blockc::entity::entity::Entity;
+    /// 
+    /// let mut alice = Entity::new("Alice".to_string(), 100.0, Vec::new(), "pub".to_string(), "priv".to_string());
+    /// let tx = Transaction::create_payment_with_memo(&mut alice, "Bob", 10.0, 0.1, Some("Invoice #42".to_string())).unwrap();
+    /// assert_eq!(tx.memo.as_deref(), Some("Invoice #42"));
+    /// 
+    /// let too_long = "x".repeat(500);
+    /// assert!(Transaction::create_payment_with_memo(&mut alice, "Bob", 1.0, 0.1, Some(too_long)).is_err());
+    /// ```
+    fn create_payment_with_memo(sender: &mut Entity, receiver_address: &str, amount: f64, fee: f64, memo: Option<String>) -> Result<Self, String>;
+
+    /// Creates a transaction anchoring a document hash on chain.
+    /// 
+    /// Nothing is transferred: the sender only pays the fee. Once the transaction
+    /// is in a block, `Blockchain::prove_existence` proves the document existed at
+    /// that block's time.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sender` - Mutable reference to the entity anchoring the document
+    /// * `doc_hash` - Fingerprint of the document (e.g. its SHA-256 hash)
+    /// * `fee` - Fee offered to the block producer
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Transaction)` - The signed anchor transaction
+    /// * `Err(String)` - If the hash is empty or the sender cannot afford the fee
+    fn create_anchor(sender: &mut Entity, doc_hash: String, fee: f64) -> Result<Self, String>;
+
+    /// Creates one signed transaction paying several recipients (e.g. a payroll batch).
+    /// 
+    /// A single signature, nonce and fee cover all the outputs. The transaction's
+    /// `amount` is the sum of the outputs, so the chain checks the sender can afford
+    /// the whole batch before crediting anyone.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sender` - Mutable reference to the sending entity
+    /// * `outputs` - Recipients and their amounts (at least one)
+    /// * `fee` - Fee offered to the block producer
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Transaction)` - The signed transaction (sender balance, nonce and history updated)
+    /// * `Err(String)` - If there are no outputs, an amount is negative, or the sender cannot afford the total
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::transactions::transactions::{Output, Transaction, TransactionExt};
+    /// use blockc::entity::entity::Entity;
+    /// 
+    /// let mut alice = Entity::new("Alice".to_string(), 100.0, Vec::new(), "pub".to_string(), "priv".to_string());
+    /// let outputs = vec![
+    ///     Output::new("Bob".to_string(), 10.0),
+    ///     Output::new("Carol".to_string(), 20.0),
+    /// ];
+    /// let tx = Transaction::create_multi_payment(&mut alice, outputs, 0.1).unwrap();
+    /// assert_eq!(tx.amount, 30.0);
+    /// assert!(tx.pays("Carol"));
+    /// 
+    /// let too_much = vec![Output::new("Bob".to_string(), 500.0)];
+    /// assert!(Transaction::create_multi_payment(&mut alice, too_much, 0.1).is_err());
+    /// ```
+    fn create_multi_payment(sender: &mut Entity, outputs: Vec<Output>, fee: f64) -> Result<Self, String>;
+
+    /// Creates a payment that cannot be included in a block before `lock_time`
+    /// (e.g. a scheduled payment, or an escrow refund after a timeout).
+    /// 
+    /// The sender's local balance and nonce are updated right away; the chain only
+    /// accepts the transaction once the lock has expired.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sender` - Mutable reference to the sending entity
+    /// * `receiver_address` - Address receiving the funds
+    /// * `amount` - Amount to transfer
+    /// * `fee` - Fee offered to the block producer
+    /// * `lock_time` - Earliest block the transaction can be included in
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Transaction)` - The signed transaction
+    /// * `Err(String)` - If an amount is negative or the sender cannot afford it
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{LockTime, Transaction, TransactionExt};
+    /// 
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// 
+    /// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "pub".to_string(), "priv".to_string());
+    /// let fee = chain.get_base_fee() * 2.0;
+    /// let tx = Transaction::create_timelocked_payment(&mut alice, "Bob", 10.0, fee, LockTime::Height(3)).unwrap();
+    /// 
+    /// // Block 2 is too early
+    /// assert!(chain.submit_transaction(tx.clone()).is_err());
+    /// assert!(chain.add_block(vec![tx.clone()], "Miner").is_err());
+    /// 
+    /// chain.add_block(Vec::new(), "Miner").unwrap();
+    /// chain.add_block(vec![tx], "Miner").unwrap();
+    /// assert_eq!(chain.get_balance("Bob"), 10.0);
+    /// ```
+    fn create_timelocked_payment(sender: &mut Entity, receiver_address: &str, amount: f64, fee: f64, lock_time: LockTime) -> Result<Self, String>;
+
+    /// Creates a payment that is only valid while `script` returns `true`.
+    ///
+    /// The script is checked every time the transaction is validated (mempool,
+    /// block assembly, block validation) with the values of `ScriptContext`, so it
+    /// can for example refuse to be included after some height.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed transaction
+    /// * `Err(String)` - If the script is too large or does not compile, an amount is
+    ///   negative, or the sender cannot afford it
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    ///
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// let fee = chain.get_base_fee() * 2.0;
+    /// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+    ///
+    /// // Only valid in the next two blocks
+    /// let tx = Transaction::create_scripted_payment(&mut alice, "Bob", 10.0, fee, "height <= 3".to_string()).unwrap();
+    /// chain.add_block(Vec::new(), "Miner").unwrap();
+    /// chain.add_block(Vec::new(), "Miner").unwrap();
+    /// assert!(chain.add_block(vec![tx], "Miner").unwrap_err().contains("script"));
+    /// ```
+    #[cfg(feature = "scripting")]
+    fn create_scripted_payment(sender: &mut Entity, receiver_address: &str, amount: f64, fee: f64, script: String) -> Result<Self, String>;
+
+    /// Same as [`Transaction::create_payment_with_memo`], with the memo encrypted
+    /// to the receiver's memo key (`privacy` feature).
+    ///
+    /// Only the holder of the memo key can read the memo (see `Wallet::read_memo`);
+    /// everyone else only sees that the payment carries an encrypted memo.
+    ///
+    /// # Arguments
+    ///
+    /// * `sender` - Mutable reference to the sending entity
+    /// * `receiver_address` - Address receiving the funds
+    /// * `amount` - Amount to transfer
+    /// * `fee` - Fee offered to the block producer
+    /// * `memo` - Plaintext memo, at most `MAX_MEMO_BYTES` bytes
+    /// * `memo_key` - Memo key published by the receiver (see `Wallet::memo_key`)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed payment
+    /// * `Err(String)` - If the memo key is malformed, the memo is too long, or the
+    ///   sender cannot afford it
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// use blockc::wallet::wallet::Wallet;
+    ///
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// let fee = chain.get_base_fee() * 2.0;
+    /// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+    /// let bob = Wallet::new(Entity::new("Bob".to_string(), 0.0, Vec::new(), "b".to_string(), "bob-secret".to_string()));
+    /// let carol = Wallet::new(Entity::new("Carol".to_string(), 0.0, Vec::new(), "c".to_string(), "carol-secret".to_string()));
+    ///
+    /// let tx = Transaction::create_payment_with_encrypted_memo(&mut alice, "Bob", 10.0, fee, "Invoice #42", &bob.memo_key()).unwrap();
+    /// assert!(tx.has_encrypted_memo());
+    /// chain.add_block(vec![tx.clone()], "Miner").unwrap();
+    ///
+    /// assert_eq!(bob.read_memo(&tx).as_deref(), Some("Invoice #42"));
+    /// assert_eq!(carol.read_memo(&tx), None);
+    /// ```
+    #[cfg(feature = "privacy")]
+    fn create_payment_with_encrypted_memo(sender: &mut Entity, receiver_address: &str, amount: f64, fee: f64, memo: &str, memo_key: &str) -> Result<Self, String>;
+
+    /// Pays a stealth meta-address (`privacy` feature).
+    ///
+    /// A new one-time address is derived for every payment (see `StealthKeys`), so
+    /// the receiver's address never appears on chain and two payments to the same
+    /// receiver can't be linked.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed payment
+    /// * `Err(String)` - If the meta-address is malformed, the amount is negative, or
+    ///   the sender cannot afford it
+    #[cfg(feature = "privacy")]
+    fn create_stealth_payment(sender: &mut Entity, meta_address: &str, amount: f64, fee: f64) -> Result<Self, String>;
+
+    /// Creates a confidential transfer (`privacy` feature).
+    ///
+    /// Spends hidden outputs of the sender and creates new ones whose amounts are
+    /// hidden behind Pedersen commitments, each with a range proof. Whatever the
+    /// inputs and the deposit hold beyond the payments and the withdrawal comes
+    /// back to the sender as a change output. The chain only sees that the
+    /// commitments balance (see `BalanceProof`); the deposit, withdrawal and fee
+    /// stay public.
+    ///
+    /// # Arguments
+    ///
+    /// * `sender` - Mutable reference to the entity spending
+    /// * `inputs` - Openings of the sender's hidden outputs to spend
+    /// * `payments` - Owner and amount of each new hidden output
+    /// * `deposit` - Coins moved from the sender's public balance into hidden outputs
+    /// * `withdrawal` - Coins taken out of hidden outputs into the sender's public balance
+    ///   (the fee can be paid out of them)
+    /// * `fee` - Fee offered to the block producer
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((Transaction, Vec<NoteOpening>))` - The signed transfer, and the openings
+    ///   of its outputs (payments first, then the change) to hand to their owners
+    /// * `Err(String)` - If an input is not the sender's, an amount is negative or finer
+    ///   than a unit, the outputs exceed the inputs, or the sender cannot afford it
+    #[cfg(feature = "privacy")]
+    fn confidential_transfer(sender: &mut Entity, inputs: &[NoteOpening], payments: &[(&str, f64)], deposit: f64, withdrawal: f64, fee: f64) -> Result<(Self, Vec<NoteOpening>), String>;
+
+    /// Creates a deposit that can later be withdrawn anonymously (`privacy` feature).
+    ///
+    /// The deposit is made under a one-time key derived from the sender's private
+    /// key and nonce. Withdrawals are signed by a ring of deposits of the same
+    /// amount, so depositing a common amount gives a larger crowd to hide in.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((Transaction, Scalar))` - The signed deposit, and the one-time secret
+    ///   key needed to withdraw it (see `ring_withdraw`)
+    /// * `Err(String)` - If the amount is not positive or the sender cannot afford it
+    #[cfg(feature = "privacy")]
+    fn ring_deposit(sender: &mut Entity, amount: f64, fee: f64) -> Result<(Self, curve25519_dalek::scalar::Scalar), String>;
+
+    /// Withdraws a ring deposit to `receiver` (`privacy` feature).
+    ///
+    /// The withdrawal is signed with a ring of up to `ring_size` deposits of the
+    /// same amount, picked from the chain (see `RingPool::ring_for`): it proves
+    /// one of them is withdrawn, not which. Its key image stops the same deposit
+    /// from being withdrawn twice. The fee is paid out of the withdrawn coins, so
+    /// `receiver` can be a fresh address.
+    ///
+    /// # Arguments
+    ///
+    /// * `receiver` - Mutable reference to the entity receiving the coins (the sender)
+    /// * `state` - Chain state to pick the ring from
+    /// * `secret` - One-time secret key returned by `ring_deposit`
+    /// * `ring_size` - Number of ring members wanted (fewer if not enough deposits match)
+    /// * `fee` - Fee offered to the block producer
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed withdrawal
+    /// * `Err(String)` - If the deposit is unknown, has no other deposit of the same
+    ///   amount to hide among, or the fee is too high
+    #[cfg(feature = "privacy")]
+    fn ring_withdraw(receiver: &mut Entity, state: &ChainState, secret: &curve25519_dalek::scalar::Scalar, ring_size: usize, fee: f64) -> Result<Self, String>;
+
+    /// Creates a transaction locking `amount` in a hash time-locked contract.
+    /// 
+    /// `receiver_address` can claim the funds with the secret behind `hash_lock`
+    /// until `expiry_height`; after that the sender can get them back. The contract
+    /// id is the id of the returned transaction.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sender` - Mutable reference to the entity locking the funds
+    /// * `receiver_address` - Address that can claim the funds
+    /// * `amount` - Amount locked
+    /// * `fee` - Fee offered to the block producer
+    /// * `hash_lock` - SHA-256 hash of the secret (see `contracts::htlc::hash_secret`)
+    /// * `expiry_height` - First block height at which the claim is no longer accepted
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Transaction)` - The signed funding transaction
+    /// * `Err(String)` - If the amount is not positive or the sender cannot afford it
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::contracts::htlc::hash_secret;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// 
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// let fee = chain.get_base_fee() * 2.0;
+    /// 
+    /// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+    /// let mut bob = Entity::new("Bob".to_string(), 0.0, Vec::new(), "b".to_string(), "b".to_string());
+    /// 
+    /// let lock = Transaction::create_htlc(&mut alice, "Bob", 10.0, fee, hash_secret("s3cret"), 10).unwrap();
+    /// chain.add_block(vec![lock.clone()], "Miner").unwrap();
+    /// 
+    /// // Bob claims with the secret; the fee comes out of the claimed funds
+    /// let htlc = chain.state().get_htlc(&lock.id()).unwrap().clone();
+    /// assert!(Transaction::create_htlc_claim(&mut bob, &htlc, "wrong".to_string(), fee).is_err());
+    /// let claim = Transaction::create_htlc_claim(&mut bob, &htlc, "s3cret".to_string(), fee).unwrap();
+    /// chain.add_block(vec![claim], "Miner").unwrap();
+    /// assert_eq!(chain.get_balance("Bob"), 10.0 - fee);
+    /// ```
+    fn create_htlc(sender: &mut Entity, receiver_address: &str, amount: f64, fee: f64, hash_lock: String, expiry_height: u64) -> Result<Self, String>;
+
+    /// Creates the transaction claiming an HTLC with its secret.
+    /// 
+    /// Only the contract's receiver can claim, and only before the expiry height.
+    /// Including the claim on chain reveals `preimage`, which is what makes atomic
+    /// swaps work. The fee is paid out of the claimed funds.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `claimer` - Mutable reference to the receiver of the contract
+    /// * `htlc` - The contract (see `ChainState::get_htlc`)
+    /// * `preimage` - The secret
+    /// * `fee` - Fee offered to the block producer
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Transaction)` - The signed claim (the claimer's local balance gets the funds minus the fee)
+    /// * `Err(String)` - If the claimer is not the receiver, the secret is wrong, or the fee is too high
+    fn create_htlc_claim(claimer: &mut Entity, htlc: &Htlc, preimage: String, fee: f64) -> Result<Self, String>;
+
+    /// Creates the transaction refunding an expired HTLC to its sender.
+    /// 
+    /// The refund is locked until the contract's expiry height, so it can be signed
+    /// right away and kept as a safety net. The fee is paid out of the refunded funds.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sender` - Mutable reference to the entity that locked the funds
+    /// * `htlc` - The contract (see `ChainState::get_htlc`)
+    /// * `fee` - Fee offered to the block producer
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Transaction)` - The signed refund (the sender's local balance gets the funds minus the fee)
+    /// * `Err(String)` - If `sender` did not create the contract or the fee is too high
+    fn create_htlc_refund(sender: &mut Entity, htlc: &Htlc, fee: f64) -> Result<Self, String>;
+
+    /// Creates a transaction opening a payment channel with `counterparty`.
+    /// 
+    /// `capacity` is locked on chain and initially belongs to the sender. The
+    /// channel id is the id of the returned transaction (see `contracts::channel::Channel`).
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Transaction)` - The signed funding transaction
+    /// * `Err(String)` - If the capacity is not positive or the sender cannot afford it
+    fn create_channel_open(sender: &mut Entity, counterparty: &str, capacity: f64, fee: f64) -> Result<Self, String>;
+
+    /// Creates a transaction submitting a channel state on chain.
+    /// 
+    /// On an open channel it starts the close and the `DISPUTE_WINDOW`. While the
+    /// channel is closing, either party can submit a state with a higher sequence,
+    /// which replaces the previous one (without extending the window).
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Transaction)` - The signed close transaction
+    /// * `Err(String)` - If the state is not signed by both parties or the fee cannot be paid
+    fn create_channel_close(sender: &mut Entity, state: ChannelState, fee: f64) -> Result<Self, String>;
+
+    /// Creates the transaction paying out a closed channel.
+    /// 
+    /// Both parties are paid their balance from the last submitted state. The
+    /// sender's fee comes out of its own share. The transaction is locked until the
+    /// end of the dispute window.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Transaction)` - The signed settle transaction
+    /// * `Err(String)` - If the channel is not closing or `sender` is not a party
+    fn create_channel_settle(sender: &mut Entity, channel: &Channel, fee: f64) -> Result<Self, String>;
+
+    /// Creates a transaction locking `amount` in escrow for `seller_address`.
+    /// 
+    /// The funds are paid out once two of buyer, seller and arbiter vote for the
+    /// same outcome (see `contracts::escrow::Escrow`). The escrow id is the id of
+    /// the returned transaction.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Transaction)` - The signed funding transaction
+    /// * `Err(String)` - If the amount is not positive, the three parties are not distinct,
+    ///   or the buyer cannot afford it
+    fn create_escrow(buyer: &mut Entity, seller_address: &str, arbiter_address: &str, amount: f64, fee: f64) -> Result<Self, String>;
+
+    /// Creates a vote on an escrow by one of its parties.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Transaction)` - The signed vote
+    /// * `Err(String)` - If the voter cannot afford the fee
+    fn create_escrow_vote(voter: &mut Entity, escrow_id: &str, outcome: EscrowOutcome, fee: f64) -> Result<Self, String>;
+
+    /// Creates a grant paying `amount` to `beneficiary_address` that unlocks
+    /// following `schedule`.
+    /// 
+    /// The beneficiary's balance grows by `amount` once the grant is in a block,
+    /// but the chain rejects spends of the part still locked.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Transaction)` - The signed grant
+    /// * `Err(String)` - If the schedule does not match the amount or the grantor cannot afford it
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::contracts::vesting::VestingSchedule;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// 
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Company").unwrap();
+    /// let fee = chain.get_base_fee() * 2.0;
+    /// 
+    /// let mut company = Entity::new("Company".to_string(), 45.0, Vec::new(), "c".to_string(), "c".to_string());
+    /// let mut alice = Entity::new("Alice".to_string(), 0.0, Vec::new(), "a".to_string(), "a".to_string());
+    /// 
+    /// // 10 unlock at height 4, 20 more at height 6
+    /// let schedule = VestingSchedule::Cliffs { unlocks: vec![(4, 10.0), (6, 20.0)] };
+    /// let grant = Transaction::create_vesting_grant(&mut company, "Alice", 30.0, fee, schedule).unwrap();
+    /// chain.add_block(vec![grant], "Miner").unwrap();
+    /// assert_eq!(chain.state().get_locked_balance("Alice"), 30.0);
+    /// 
+    /// alice.balance = 30.0;
+    /// let early = Transaction::create_payment(&mut alice, "Bob", 5.0, fee).unwrap();
+    /// assert!(chain.submit_transaction(early.clone()).is_err());
+    /// 
+    /// chain.add_block(Vec::new(), "Miner").unwrap();
+    /// assert_eq!(chain.state().get_vested_balance("Alice"), 10.0);
+    /// chain.add_block(vec![early], "Miner").unwrap();
+    /// ```
+    fn create_vesting_grant(grantor: &mut Entity, beneficiary_address: &str, amount: f64, fee: f64, schedule: VestingSchedule) -> Result<Self, String>;
+
+    /// Creates a transaction opening a payment stream to `receiver_address`.
+    /// 
+    /// `deposit` is locked and accrues to the receiver at `rate_per_block` from the
+    /// block including this transaction. The stream id is the id of the returned
+    /// transaction.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Transaction)` - The signed transaction
+    /// * `Err(String)` - If the deposit or rate is not positive or the sender cannot afford it
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// 
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Company").unwrap();
+    /// let fee = chain.get_base_fee() * 2.0;
+    /// 
+    /// let mut company = Entity::new("Company".to_string(), 45.0, Vec::new(), "c".to_string(), "c".to_string());
+    /// let mut alice = Entity::new("Alice".to_string(), 0.0, Vec::new(), "a".to_string(), "a".to_string());
+    /// 
+    /// // 1 coin per block, opened in block 2
+    /// let open = Transaction::create_stream(&mut company, "Alice", 20.0, 1.0, fee).unwrap();
+    /// chain.add_block(vec![open.clone()], "Miner").unwrap();
+    /// chain.add_block(Vec::new(), "Miner").unwrap();
+    /// 
+    /// // Withdrawn in block 4: 2 coins accrued
+    /// let stream = chain.state().get_stream(&open.id()).unwrap().clone();
+    /// let withdraw = Transaction::withdraw_from_stream(&mut alice, &stream, 4, fee).unwrap();
+    /// chain.add_block(vec![withdraw], "Miner").unwrap();
+    /// assert!((chain.get_balance("Alice") - (2.0 - fee)).abs() < 1e-9);
+    /// 
+    /// // Cancelled in block 5: Alice gets 1 more, the company gets 17 back
+    /// let stream = chain.state().get_stream(&open.id()).unwrap().clone();
+    /// let cancel = Transaction::cancel_stream(&mut company, &stream, 5, fee).unwrap();
+    /// chain.add_block(vec![cancel], "Miner").unwrap();
+    /// assert!((chain.get_balance("Alice") - (3.0 - fee)).abs() < 1e-9);
+    /// assert!(chain.state().get_stream(&open.id()).is_none());
+    /// ```
+    fn create_stream(sender: &mut Entity, receiver_address: &str, deposit: f64, rate_per_block: f64, fee: f64) -> Result<Self, String>;
+
+    /// Creates a withdrawal of the coins accrued on a stream.
+    /// 
+    /// The chain pays what has accrued in the block that includes the withdrawal;
+    /// `height` is the height that block is expected to have, used to update the
+    /// receiver's local balance (sync the wallet if it ends up in a later block).
+    /// The fee is paid out of the withdrawn coins.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Transaction)` - The signed withdrawal
+    /// * `Err(String)` - If `receiver` is not the stream's receiver or the fee is too high
+    fn withdraw_from_stream(receiver: &mut Entity, stream: &Stream, height: u64, fee: f64) -> Result<Self, String>;
+
+    /// Creates the cancellation of a stream by its sender.
+    /// 
+    /// The chain settles the stream in the block that includes the cancellation:
+    /// the receiver gets everything accrued and not withdrawn yet, the sender gets
+    /// the rest of the deposit back (the fee is paid out of it). `height` is the
+    /// height that block is expected to have, used for the sender's local balance.
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Transaction)` - The signed cancellation
+    /// * `Err(String)` - If `sender` did not open the stream or cannot pay the fee
+    fn cancel_stream(sender: &mut Entity, stream: &Stream, height: u64, fee: f64) -> Result<Self, String>;
+
+    /// Creates a UTXO transaction spending `inputs` and creating `outputs`.
+    /// 
+    /// Every input must be an unspent output owned by the sender. The value of the
+    /// inputs pays for the outputs and the fee; if it is short, the difference comes
+    /// from the sender's account balance (this is how account coins enter the UTXO
+    /// set), and if it is over, the change goes back to the account. Use a change
+    /// output to keep everything in the UTXO set.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sender` - Mutable reference to the owner of the inputs
+    /// * `inputs` - Outputs to spend, with their value (see `ChainState::get_utxos`)
+    /// * `outputs` - New outputs
+    /// * `fee` - Fee offered to the block producer
+    /// 
+    /// # Returns
+    /// 
+    /// * `Ok(Transaction)` - The signed transaction; the new outputs are numbered in order
+    /// * `Err(String)` - If an input is not the sender's, there is nothing to spend or
+    ///   create, an amount is negative, or the sender cannot afford it
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Output, Transaction, TransactionExt};
+    /// 
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// let fee = chain.get_base_fee() * 2.0;
+    /// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+    /// 
+    /// // Move 10 coins from Alice's account into a UTXO she owns
+    /// let deposit = Transaction::create_utxo_transaction(&mut alice, Vec::new(), vec![Output::new("Alice".to_string(), 10.0)], fee).unwrap();
+    /// chain.add_block(vec![deposit], "Miner").unwrap();
+    /// assert_eq!(chain.state().get_utxo_balance("Alice"), 10.0);
+    /// 
+    /// // Spend it: 6 to Bob, the rest back to Alice as change
+    /// let coins = chain.state().get_utxos("Alice");
+    /// let outputs = vec![Output::new("Bob".to_string(), 6.0), Output::new("Alice".to_string(), 4.0 - fee)];
+    /// let spend = Transaction::create_utxo_transaction(&mut alice, coins.clone(), outputs, fee).unwrap();
+    /// chain.add_block(vec![spend], "Miner").unwrap();
+    /// assert_eq!(chain.state().get_utxo_balance("Bob"), 6.0);
+    /// 
+    /// // The same coin cannot be spent twice
+    /// let again = Transaction::create_utxo_transaction(&mut alice, coins, vec![Output::new("Bob".to_string(), 9.0)], fee).unwrap();
+    /// assert!(chain.add_block(vec![again], "Miner").unwrap_err().contains("double spend"));
+    /// ```
+    fn create_utxo_transaction(sender: &mut Entity, inputs: Vec<(OutPoint, Output)>, outputs: Vec<Output>, fee: f64) -> Result<Self, String>;
+
+    /// Creates the transaction of a CoinJoin, sent by its coordinator.
+    ///
+    /// Use `CoinJoin::finalize`, which collects the inputs and their signatures.
+    /// The fee is whatever the inputs hold beyond the outputs, so the coordinator
+    /// neither gains nor loses coins.
+    ///
+    /// # Arguments
+    ///
+    /// * `coordinator` - Mutable reference to the entity sending the join
+    /// * `inputs` - Outputs spent, each signed by its owner
+    /// * `outputs` - New outputs
+    /// * `input_value` - Sum of the spent outputs
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed transaction; the new outputs are numbered in order
+    /// * `Err(String)` - If an amount is negative or the outputs exceed the inputs
+    fn create_coinjoin(coordinator: &mut Entity, inputs: Vec<JoinInput>, outputs: Vec<Output>, input_value: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction deploying a contract.
+    ///
+    /// The contract's address is the id of this transaction. `amount` becomes the
+    /// contract's starting balance.
+    ///
+    /// # Arguments
+    ///
+    /// * `sender` - The deployer (its nonce and balance are updated)
+    /// * `code` - The contract's program
+    /// * `amount` - Coins given to the contract
+    /// * `fee` - Fee paid to the block producer
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed transaction
+    /// * `Err(String)` - If the code is invalid (see `instruction::validate`), an amount
+    ///   is negative, or the sender cannot afford it
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// use blockc::vm::instruction::parse;
+    ///
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// let fee = chain.get_base_fee() * 2.0;
+    /// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+    ///
+    /// // A faucet: pays the caller the amount it asks for, and counts the calls in slot 0
+    /// let code = parse("TRANSFERCALLER; PUSH 0; PUSH 0; LOAD; PUSH 1; ADD; STORE").unwrap();
+    /// let deploy = Transaction::create_deploy(&mut alice, code, 10.0, fee).unwrap();
+    /// let faucet = deploy.id();
+    /// chain.add_block(vec![deploy], "Miner").unwrap();
+    /// assert_eq!(chain.get_balance(&faucet), 10.0);
+    ///
+    /// let mut bob = Entity::new("Bob".to_string(), 0.0, Vec::new(), "b".to_string(), "b".to_string());
+    /// let call = Transaction::create_call(&mut alice, &faucet, 0.0, vec![3], 50_000, 0.0, fee).unwrap();
+    /// chain.add_block(vec![call], "Miner").unwrap();
+    /// assert_eq!(chain.get_balance(&faucet), 7.0);
+    /// assert_eq!(chain.state().get_contract(&faucet).unwrap().storage.get(&0), Some(&1));
+    ///
+    /// // Asking for too much fails: the call is included but changes nothing
+    /// chain.add_block(Vec::new(), "Bob").unwrap();
+    /// bob.balance = chain.get_balance("Bob");
+    /// let greedy = Transaction::create_call(&mut bob, &faucet, 0.0, vec![100], 50_000, 0.0, fee).unwrap();
+    /// let greedy_id = greedy.id();
+    /// chain.add_block(vec![greedy], "Miner").unwrap();
+    /// assert!(!chain.state().get_call_receipt(&greedy_id).unwrap().success);
+    /// assert_eq!(chain.get_balance(&faucet), 7.0);
+    /// ```
+    fn create_deploy(sender: &mut Entity, code: Vec<Instruction>, amount: f64, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction calling a contract.
+    ///
+    /// # Arguments
+    ///
+    /// * `sender` - The caller (its nonce and balance are updated)
+    /// * `contract` - Address of the contract
+    /// * `amount` - Coins sent to the contract with the call
+    /// * `input` - Arguments pushed on the VM stack before the code runs
+    /// * `gas_limit` - Most gas the call can use (intrinsic gas included)
+    /// * `gas_price` - Coins paid per unit of gas used, on top of `fee`
+    /// * `fee` - Fee paid to the block producer
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed transaction; the sender sets aside `gas_limit * gas_price`
+    /// * `Err(String)` - If an amount is negative, there are more than `MAX_INPUTS`
+    ///   arguments, the gas limit does not cover the intrinsic gas, or the sender
+    ///   cannot afford it
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// use blockc::vm::instruction::parse;
+    ///
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// let fee = chain.get_base_fee();
+    /// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+    ///
+    /// let deploy = Transaction::create_deploy(&mut alice, parse("PUSH 1; PUSH 42; STORE").unwrap(), 0.0, fee).unwrap();
+    /// let contract = deploy.id();
+    /// chain.add_block(vec![deploy], "Miner").unwrap();
+    ///
+    /// // Alice sets aside 50 000 gas at 0.0001 but only pays for the gas used
+    /// let before = chain.get_balance("Alice");
+    /// let call = Transaction::create_call(&mut alice, &contract, 0.0, Vec::new(), 50_000, 0.0001, fee).unwrap();
+    /// let id = call.id();
+    /// chain.add_block(vec![call], "Miner").unwrap();
+    /// let gas_used = chain.state().get_call_receipt(&id).unwrap().gas_used;
+    /// assert!(gas_used < 50_000);
+    /// assert!((before - fee - gas_used as f64 * 0.0001 - chain.get_balance("Alice")).abs() < 1e-9);
+    /// ```
+    fn create_call(sender: &mut Entity, contract: &str, amount: f64, input: Vec<i64>, gas_limit: u64, gas_price: f64, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction issuing a new fungible asset.
+    ///
+    /// The asset's id is the id of this transaction. The sender becomes its issuer
+    /// and receives `initial_supply` units.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - The issuer (its nonce and balance are updated)
+    /// * `ticker` - Unique ticker, 1 to 8 uppercase letters or digits
+    /// * `decimals` - Decimals used to display quantities
+    /// * `max_supply` - Most units that can ever exist
+    /// * `initial_supply` - Units created now
+    /// * `fee` - Fee paid to the block producer
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed transaction
+    /// * `Err(String)` - If the definition is invalid, the initial supply is above the
+    ///   max supply, or the issuer cannot afford the fee
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    ///
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Bank").unwrap();
+    /// let fee = chain.get_base_fee() * 2.0;
+    /// let mut bank = Entity::new("Bank".to_string(), 45.0, Vec::new(), "b".to_string(), "b".to_string());
+    ///
+    /// let issue = Transaction::create_asset(&mut bank, "USD", 2, 1_000_000, 10_000, fee).unwrap();
+    /// let usd = issue.id();
+    /// chain.add_block(vec![issue], "Miner").unwrap();
+    ///
+    /// let pay = Transaction::transfer_asset(&mut bank, "Alice", &usd, 2_550, fee).unwrap();
+    /// chain.add_block(vec![pay], "Miner").unwrap();
+    /// assert_eq!(chain.state().get_asset_balance("Alice", &usd), 2_550);
+    /// assert_eq!(chain.state().get_asset(&usd).unwrap().format_quantity(2_550), "25.50");
+    ///
+    /// // Only the issuer can mint, up to the max supply
+    /// let mint = Transaction::mint_asset(&mut bank, &usd, 5_000, fee).unwrap();
+    /// chain.add_block(vec![mint], "Miner").unwrap();
+    /// assert_eq!(chain.state().get_asset(&usd).unwrap().supply, 15_000);
+    /// ```
+    fn create_asset(issuer: &mut Entity, ticker: &str, decimals: u8, max_supply: u64, initial_supply: u64, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction minting `quantity` more units of an asset.
+    ///
+    /// Only the asset's issuer can mint, and never above the max supply (both are
+    /// checked by the chain).
+    fn mint_asset(issuer: &mut Entity, asset_id: &str, quantity: u64, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction sending `quantity` units of an asset.
+    ///
+    /// The sender pays the fee in native coins; the chain checks it holds the units.
+    fn transfer_asset(sender: &mut Entity, receiver_address: &str, asset_id: &str, quantity: u64, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction minting a non-fungible token.
+    ///
+    /// # Arguments
+    ///
+    /// * `creator` - The creator and first owner (its nonce and balance are updated)
+    /// * `token_id` - Unique id of the token (the chain rejects ids already used)
+    /// * `metadata_hash` - Optional hash of the token's metadata
+    /// * `fee` - Fee paid to the block producer
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed transaction
+    /// * `Err(String)` - If the fee is negative or the creator cannot afford it
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    ///
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Artist").unwrap();
+    /// let fee = chain.get_base_fee() * 2.0;
+    /// let mut artist = Entity::new("Artist".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+    ///
+    /// let mint = Transaction::mint_nft(&mut artist, "sunset-1", Some("9f86d0".to_string()), fee).unwrap();
+    /// chain.add_block(vec![mint], "Miner").unwrap();
+    /// let sale = Transaction::transfer_nft(&mut artist, "Alice", "sunset-1", fee).unwrap();
+    /// chain.add_block(vec![sale], "Miner").unwrap();
+    ///
+    /// assert_eq!(chain.state().owner_of("sunset-1"), Some("Alice"));
+    /// assert_eq!(chain.state().tokens_of("Alice").len(), 1);
+    /// assert!(chain.state().tokens_of("Artist").is_empty());
+    ///
+    /// // The artist no longer owns it
+    /// let again = Transaction::burn_nft(&mut artist, "sunset-1", fee).unwrap();
+    /// assert!(chain.add_block(vec![again], "Miner").is_err());
+    /// ```
+    fn mint_nft(creator: &mut Entity, token_id: &str, metadata_hash: Option<String>, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction giving a token the sender owns to `receiver_address`.
+    fn transfer_nft(owner: &mut Entity, receiver_address: &str, token_id: &str, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction destroying a token the sender owns.
+    fn burn_nft(owner: &mut Entity, token_id: &str, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction registering `name` so it resolves to `target`.
+    ///
+    /// The registration lasts `REGISTRATION_PERIOD` blocks. Registering a name the
+    /// sender already owns changes its target.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The owner of the name (its nonce and balance are updated)
+    /// * `name` - The name, e.g. "alice.bc" (see `registry::check_name`)
+    /// * `target` - Address the name resolves to
+    /// * `fee` - Fee paid to the block producer
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed transaction
+    /// * `Err(String)` - If the name is invalid or the owner cannot afford the fee
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    ///
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// chain.add_block(Vec::new(), "Bob").unwrap();
+    /// let fee = chain.get_base_fee() * 2.0;
+    /// let mut bob = Entity::new("Bob".to_string(), 45.0, Vec::new(), "b".to_string(), "b".to_string());
+    /// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+    ///
+    /// let register = Transaction::register_name(&mut bob, "bob.bc", "Bob", fee).unwrap();
+    /// chain.add_block(vec![register], "Miner").unwrap();
+    /// assert_eq!(chain.state().resolve_name("bob.bc"), Some("Bob"));
+    ///
+    /// let pay = Transaction::create_payment_to_name(&mut alice, chain.state(), "bob.bc", 10.0, fee).unwrap();
+    /// assert_eq!(pay.receiver_address, "Bob");
+    /// ```
+    fn register_name(owner: &mut Entity, name: &str, target: &str, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction extending a name the sender owns by
+    /// `REGISTRATION_PERIOD` blocks.
+    fn renew_name(owner: &mut Entity, name: &str, fee: f64) -> Result<Self, String>;
+
+    /// Creates a payment to a registered name.
+    ///
+    /// The name is resolved against `state` when the transaction is created and the
+    /// resolved address is the receiver, so the payment can't be redirected if the
+    /// name later points elsewhere.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed payment to the name's address
+    /// * `Err(String)` - If the name doesn't resolve or the payment can't be created
+    fn create_payment_to_name(sender: &mut Entity, state: &ChainState, name: &str, amount: f64, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction registering the DID document of the sender.
+    ///
+    /// The DID is derived from the sender's address (`did:blockc:<address>`), so only
+    /// that address can register or update it.
+    ///
+    /// # Arguments
+    ///
+    /// * `controller` - The DID subject (its nonce and balance are updated)
+    /// * `public_keys` - Keys used to verify its signatures and credentials
+    /// * `services` - Service endpoints
+    /// * `fee` - Fee paid to the block producer
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed transaction
+    /// * `Err(String)` - If the document is invalid (see `did::check_document`) or the
+    ///   controller cannot afford the fee
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::identity::did::{did_for, Service};
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    ///
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// let fee = chain.get_base_fee() * 2.0;
+    /// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+    ///
+    /// let inbox = Service::new("inbox", "Inbox", "https://alice.example/inbox");
+    /// let register = Transaction::register_did(&mut alice, vec!["key-1".to_string()], vec![inbox.clone()], fee).unwrap();
+    /// chain.add_block(vec![register], "Miner").unwrap();
+    ///
+    /// // Rotate the key
+    /// let rotate = Transaction::update_did(&mut alice, vec!["key-2".to_string()], vec![inbox], fee).unwrap();
+    /// chain.add_block(vec![rotate], "Miner").unwrap();
+    ///
+    /// let did = did_for("Alice");
+    /// let document = chain.state().resolve_did(&did).unwrap();
+    /// assert_eq!(document.public_keys, vec!["key-2".to_string()]);
+    /// assert_eq!(document.version, 2);
+    /// assert_eq!(chain.state().get_did_history(&did).len(), 2);
+    /// ```
+    fn register_did(controller: &mut Entity, public_keys: Vec<String>, services: Vec<Service>, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction replacing the keys and services of the
+    /// sender's DID document (key rotation).
+    fn update_did(controller: &mut Entity, public_keys: Vec<String>, services: Vec<Service>, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction opening a ballot.
+    ///
+    /// The ballot's id is the id of this transaction (see `Ballot` for an example).
+    ///
+    /// # Arguments
+    ///
+    /// * `creator` - The creator (its nonce and balance are updated)
+    /// * `question` - What is being decided
+    /// * `options` - 2 to `MAX_BALLOT_OPTIONS` distinct answers
+    /// * `start_height` - First height at which votes are accepted (not before the block including this transaction)
+    /// * `end_height` - First height at which votes are no longer accepted
+    /// * `weighting` - One vote per address or votes weighted by balance
+    /// * `fee` - Fee paid to the block producer
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed transaction
+    /// * `Err(String)` - If the options or the window are invalid, or the creator cannot afford the fee
+    fn create_ballot(creator: &mut Entity, question: &str, options: Vec<String>, start_height: u64, end_height: u64, weighting: VoteWeighting, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a vote for option `option` (index in the ballot's options).
+    ///
+    /// The chain accepts one vote per address, only inside the voting window.
+    fn cast_vote(voter: &mut Entity, ballot_id: &str, option: usize, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction opening an auction.
+    ///
+    /// The auction's id is the id of this transaction; see `Auction` for the rules
+    /// and an example.
+    ///
+    /// # Arguments
+    ///
+    /// * `seller` - The seller, paid at settlement (its nonce and balance are updated)
+    /// * `item` - Description of what is sold
+    /// * `reserve_price` - Lowest winning bid
+    /// * `bidding_end` - First height at which bids are refused
+    /// * `reveal_end` - `Some(height)` for a sealed-bid auction: first height at which
+    ///   reveals are refused; `None` for an open auction
+    /// * `fee` - Fee paid to the block producer
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed transaction
+    /// * `Err(String)` - If the reserve price is negative, the reveal phase doesn't
+    ///   end after bidding, or the seller cannot afford the fee
+    fn create_auction(seller: &mut Entity, item: &str, reserve_price: f64, bidding_end: u64, reveal_end: Option<u64>, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a bid of `amount` in an open auction.
+    ///
+    /// The coins stay locked until settlement; the bid must beat the highest bid so far.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    ///
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// chain.add_block(Vec::new(), "Bob").unwrap();
+    /// let fee = chain.get_base_fee() * 2.0;
+    /// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+    /// let mut bob = Entity::new("Bob".to_string(), 45.0, Vec::new(), "b".to_string(), "b".to_string());
+    ///
+    /// let create = Transaction::create_auction(&mut alice, "Bike", 1.0, 5, None, fee).unwrap();
+    /// let auction = create.id();
+    /// chain.add_block(vec![create], "Miner").unwrap();
+    ///
+    /// let low = Transaction::bid_on_auction(&mut bob, &auction, 3.0, fee).unwrap();
+    /// let high = Transaction::bid_on_auction(&mut bob, &auction, 7.0, fee).unwrap();
+    /// chain.add_block(vec![low, high], "Miner").unwrap();
+    ///
+    /// // Too low now
+    /// let late = Transaction::bid_on_auction(&mut bob, &auction, 6.0, fee).unwrap();
+    /// assert!(chain.add_block(vec![late], "Miner").is_err());
+    /// ```
+    fn bid_on_auction(bidder: &mut Entity, auction_id: &str, amount: f64, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs the commitment of a sealed bid.
+    ///
+    /// Only the hash of `bid` and `salt` is published; `deposit` is locked and must
+    /// cover the bid (it hides the bid, so it can be higher). Keep `salt` secret
+    /// until the reveal.
+    fn commit_sealed_bid(bidder: &mut Entity, auction_id: &str, bid: f64, salt: &str, deposit: f64, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs the reveal of a sealed bid committed with `commit_sealed_bid`.
+    fn reveal_sealed_bid(bidder: &mut Entity, auction_id: &str, bid: f64, salt: &str, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction settling an auction that is over (anyone can send it).
+    fn settle_auction(sender: &mut Entity, auction_id: &str, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction opening a liquidity pool for two assets.
+    ///
+    /// The creator deposits the first liquidity, which sets the starting price,
+    /// and receives `sqrt(amount_a * amount_b)` shares. The pool's id is the id of
+    /// this transaction; the chain allows one pool per pair of assets.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The creator and first provider (its nonce and balance are updated)
+    /// * `asset_a` - Id of the first asset
+    /// * `amount_a` - Units of `asset_a` deposited
+    /// * `asset_b` - Id of the second asset
+    /// * `amount_b` - Units of `asset_b` deposited
+    /// * `fee_bps` - Swap fee paid to the providers, in basis points (at most `MAX_POOL_FEE_BPS`)
+    /// * `fee` - Fee paid to the block producer
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed transaction
+    /// * `Err(String)` - If both assets are the same, an amount is 0, the swap fee is
+    ///   too high, or the provider cannot afford the fee
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    ///
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// chain.add_block(Vec::new(), "Bob").unwrap();
+    /// let fee = chain.get_base_fee() * 2.0;
+    /// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+    /// let mut bob = Entity::new("Bob".to_string(), 45.0, Vec::new(), "b".to_string(), "b".to_string());
+    ///
+    /// let gold = Transaction::create_asset(&mut alice, "GOLD", 0, 10_000, 10_000, fee).unwrap();
+    /// let usd = Transaction::create_asset(&mut alice, "USD", 0, 100_000, 100_000, fee).unwrap();
+    /// let (gold, usd, issues) = (gold.id(), usd.id(), vec![gold, usd]);
+    /// let pay = Transaction::transfer_asset(&mut alice, "Bob", &usd, 1_000, fee).unwrap();
+    /// chain.add_block(issues, "Miner").unwrap();
+    /// chain.add_block(vec![pay], "Miner").unwrap();
+    ///
+    /// // 1 GOLD = 20 USD, 0.3% swap fee
+    /// let create = Transaction::create_pool(&mut alice, &gold, 1_000, &usd, 20_000, 30, fee).unwrap();
+    /// let pool = create.id();
+    /// chain.add_block(vec![create], "Miner").unwrap();
+    ///
+    /// // Bob buys gold with 1000 USD, and gets a bit less than 50 GOLD
+    /// let quote = chain.state().get_pool(&pool).unwrap().quote_swap(&usd, 1_000, 0).unwrap();
+    /// assert_eq!(quote, 47);
+    /// let swap = Transaction::swap(&mut bob, &pool, &usd, 1_000, 45, fee).unwrap();
+    /// chain.add_block(vec![swap], "Miner").unwrap();
+    /// assert_eq!(chain.state().get_asset_balance("Bob", &gold), 47);
+    ///
+    /// // Alice withdraws her liquidity, with Bob's USD and the swap fee in it
+    /// let shares = chain.state().get_pool(&pool).unwrap().shares_of("Alice");
+    /// let withdraw = Transaction::remove_liquidity(&mut alice, &pool, shares, fee).unwrap();
+    /// chain.add_block(vec![withdraw], "Miner").unwrap();
+    /// assert_eq!(chain.state().get_asset_balance("Alice", &gold), 10_000 - 47);
+    /// assert_eq!(chain.state().get_asset_balance("Alice", &usd), 100_000);
+    /// ```
+    fn create_pool(provider: &mut Entity, asset_a: &str, amount_a: u64, asset_b: &str, amount_b: u64, fee_bps: u32, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction adding liquidity to a pool.
+    ///
+    /// Deposit in the pool's current ratio: shares are minted for the smaller side
+    /// and any excess goes to the existing providers.
+    fn add_liquidity(provider: &mut Entity, pool_id: &str, amount_a: u64, amount_b: u64, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction burning pool shares for their part of both reserves.
+    fn remove_liquidity(provider: &mut Entity, pool_id: &str, shares: u64, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction selling `amount_in` units of `asset_in` to a pool.
+    ///
+    /// The chain rejects the swap if it would buy less than `min_out` units of the
+    /// other asset (the price may move before the transaction is included).
+    fn swap(trader: &mut Entity, pool_id: &str, asset_in: &str, amount_in: u64, min_out: u64, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a limit order on the `base`/`quote` market.
+    ///
+    /// When the order is applied, the chain locks what it could spend (`quantity`
+    /// of `base` for a sell, `price * quantity` of `quote` for a buy) and matches it
+    /// against the resting orders (see `OrderBook`). Whatever is not filled rests in
+    /// the book until it is filled or cancelled. The order's id is the id of this
+    /// transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `trader` - The trader (its nonce and balance are updated)
+    /// * `base` - Id of the asset bought or sold
+    /// * `quote` - Id of the asset the price is expressed in
+    /// * `side` - `Side::Buy` or `Side::Sell`
+    /// * `price` - Limit price, in units of `quote` per unit of `base`
+    /// * `quantity` - Units of `base` to buy or sell
+    /// * `fee` - Fee paid to the block producer
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed transaction
+    /// * `Err(String)` - If both assets are the same, the price or quantity is 0, or
+    ///   the trader cannot afford the fee
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::dex::orderbook::Side;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    ///
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// chain.add_block(Vec::new(), "Bob").unwrap();
+    /// let fee = chain.get_base_fee() * 2.0;
+    /// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+    /// let mut bob = Entity::new("Bob".to_string(), 45.0, Vec::new(), "b".to_string(), "b".to_string());
+    ///
+    /// let gold = Transaction::create_asset(&mut alice, "GOLD", 0, 100, 100, fee).unwrap();
+    /// let usd = Transaction::create_asset(&mut bob, "USD", 0, 10_000, 10_000, fee).unwrap();
+    /// let (gold, usd, issues) = (gold.id(), usd.id(), vec![gold, usd]);
+    /// chain.add_block(issues, "Miner").unwrap();
+    ///
+    /// // Alice asks 20 USD per GOLD; then Bob bids up to 25 for 15 GOLD
+    /// let ask = Transaction::place_order(&mut alice, &gold, &usd, Side::Sell, 20, 10, fee).unwrap();
+    /// chain.add_block(vec![ask], "Miner").unwrap();
+    /// let bid = Transaction::place_order(&mut bob, &gold, &usd, Side::Buy, 25, 15, fee).unwrap();
+    /// let bid_id = bid.id();
+    /// chain.add_block(vec![bid], "Miner").unwrap();
+    ///
+    /// // 10 GOLD traded at Alice's price; the rest of Bob's bid rests in the book
+    /// let trades = chain.state().order_book().trades();
+    /// assert_eq!((trades[0].price, trades[0].quantity), (20, 10));
+    /// assert_eq!(chain.state().get_asset_balance("Bob", &gold), 10);
+    /// assert_eq!(chain.state().get_asset_balance("Alice", &usd), 200);
+    /// assert_eq!(chain.state().order_book().get(&bid_id).unwrap().quantity, 5);
+    ///
+    /// // Cancelling unlocks the 5 * 25 USD still locked
+    /// let cancel = Transaction::cancel_order(&mut bob, &bid_id, fee).unwrap();
+    /// chain.add_block(vec![cancel], "Miner").unwrap();
+    /// assert_eq!(chain.state().get_asset_balance("Bob", &usd), 10_000 - 200);
+    /// ```
+    fn place_order(trader: &mut Entity, base: &str, quote: &str, side: Side, price: u64, quantity: u64, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction cancelling one of the trader's resting orders.
+    fn cancel_order(trader: &mut Entity, order_id: &str, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction creating an oracle data feed.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The feed's creator (its nonce and balance are updated)
+    /// * `feed` - Unique name of the feed (e.g. "BTC/USD")
+    /// * `reporters` - Addresses allowed to publish to the feed
+    /// * `quorum` - Fresh reports needed for the feed to have a value
+    /// * `max_age` - Number of blocks a report stays fresh
+    /// * `fee` - Fee paid to the block producer
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed transaction
+    /// * `Err(String)` - If the feed definition is invalid (see `check_feed`) or the
+    ///   owner cannot afford the fee
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    ///
+    /// let mut chain = Blockchain::new();
+    /// for address in ["Owner", "R1", "R2", "R3"] {
+    ///     chain.add_block(Vec::new(), address).unwrap();
+    /// }
+    /// let fee = chain.get_base_fee() * 2.0;
+    /// let entity = |name: &str| Entity::new(name.to_string(), 45.0, Vec::new(), name.to_string(), name.to_string());
+    /// let (mut owner, mut r1, mut r2, mut r3) = (entity("Owner"), entity("R1"), entity("R2"), entity("R3"));
+    ///
+    /// let reporters = vec!["R1".to_string(), "R2".to_string(), "R3".to_string()];
+    /// let create = Transaction::create_feed(&mut owner, "BTC/USD", reporters, 2, 5, fee).unwrap();
+    /// chain.add_block(vec![create], "Miner").unwrap();
+    ///
+    /// let reports = vec![
+    ///     Transaction::report_value(&mut r1, "BTC/USD", 60_000.0, fee).unwrap(),
+    ///     Transaction::report_value(&mut r2, "BTC/USD", 60_400.0, fee).unwrap(),
+    ///     Transaction::report_value(&mut r3, "BTC/USD", 61_000.0, fee).unwrap(),
+    /// ];
+    /// chain.add_block(reports, "Miner").unwrap();
+    /// assert_eq!(chain.state().feed_value("BTC/USD").unwrap().value, 60_400.0);
+    ///
+    /// // Only designated reporters can publish
+    /// let forged = Transaction::report_value(&mut owner, "BTC/USD", 1.0, fee).unwrap();
+    /// assert!(chain.add_block(vec![forged], "Miner").is_err());
+    ///
+    /// // Five blocks later, every report is stale
+    /// for _ in 0..5 {
+    ///     chain.add_block(Vec::new(), "Miner").unwrap();
+    /// }
+    /// assert!(chain.state().feed_value("BTC/USD").is_err());
+    /// ```
+    fn create_feed(owner: &mut Entity, feed: &str, reporters: Vec<String>, quorum: usize, max_age: u64, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a report of `value` on an oracle feed.
+    ///
+    /// The transaction's signature authenticates the reporter; the chain checks it
+    /// is one of the feed's reporters.
+    fn report_value(reporter: &mut Entity, feed: &str, value: f64, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction locking asset units for a recipient on another chain.
+    ///
+    /// The units move to the bridge's escrow address; once the lock is confirmed,
+    /// its proof mints wrapped units on the other chain (see `BridgeLink`).
+    ///
+    /// # Arguments
+    ///
+    /// * `sender` - Holder of the units (its nonce and balance are updated)
+    /// * `state` - State of this chain, to look up the asset's ticker and decimals
+    /// * `chain_id` - Name of the destination chain
+    /// * `asset_id` - Asset locked
+    /// * `quantity` - Units locked
+    /// * `recipient` - Address receiving the wrapped units on the destination chain
+    /// * `fee` - Fee paid to the block producer
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed transaction
+    /// * `Err(String)` - If the asset doesn't exist, the quantity is 0, or the sender
+    ///   cannot afford the fee
+    fn bridge_lock(sender: &mut Entity, state: &ChainState, chain_id: &str, asset_id: &str, quantity: u64, recipient: &str, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction relaying block headers of another chain.
+    ///
+    /// Anyone can relay; headers the bridge already has are skipped.
+    fn relay_headers(relayer: &mut Entity, chain_id: &str, headers: Vec<BlockHeader>, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction minting the wrapped units of a lock made on `chain_id`.
+    ///
+    /// Anyone can submit the proof; the units always go to the lock's recipient.
+    fn bridge_mint(sender: &mut Entity, chain_id: &str, proof: BridgeProof, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction burning wrapped units of `asset_id` (an asset of
+    /// `chain_id`), to be released to `recipient` on that chain.
+    fn bridge_burn(sender: &mut Entity, chain_id: &str, asset_id: &str, quantity: u64, recipient: &str, fee: f64) -> Result<Self, String>;
+
+    /// Creates and signs a transaction releasing escrowed units for a burn made on `chain_id`.
+    fn bridge_release(sender: &mut Entity, chain_id: &str, proof: BridgeProof, fee: f64) -> Result<Self, String>;
+}
+
+impl TransactionExt for Transaction {
+    fn coinbase(receiver_address: String, amount: f64) -> Self {
+        Transaction::coinbase_at(receiver_address, amount, helpers::helper_functions::get_time())
+    }
+
+    fn sign(&mut self, signer: &Entity) -> Result<(), String> {
+        if signer.address != self.sender_address {
+            return Err(format!("{} cannot sign a transaction from {}", signer.address, self.sender_address));
+        }
+        self.attach_signature(signer.sign(&self.signing_data()))
+    }
+
+    fn to_json(&self) -> String {
+        JsonCodec::to_json(self).to_string()
+    }
+
+    fn from_json(json: &str) -> Result<Self, String> {
+        if json.len() > MAX_ENCODED_TRANSACTION_BYTES {
+            return Err(format!("Encoded transaction is over {} bytes", MAX_ENCODED_TRANSACTION_BYTES));
+        }
+        <Transaction as JsonCodec>::from_json(&json::parse(json)?)
+    }
+
+    fn to_wire(&self) -> Vec<u8> {
+        wire::encode(self)
+    }
+
+    fn from_wire(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() > MAX_ENCODED_TRANSACTION_BYTES {
+            return Err(format!("Encoded transaction is over {} bytes", MAX_ENCODED_TRANSACTION_BYTES));
+        }
+        wire::decode(bytes)
+    }
+
+    fn create_replacement(sender: &mut Entity, original: &Transaction, new_fee: f64) -> Result<Self, String> {
+        if original.sender_address != sender.address {
+            return Err("Only the sender can replace a transaction".to_string());
+        }
+        if new_fee <= original.fee {
+            return Err(format!("Replacement fee {} must be higher than {}", new_fee, original.fee));
+        }
+        sender.send_amount(new_fee - original.fee)?;
+
+        let mut replacement = original.clone();
+        replacement.fee = new_fee;
+        replacement.timestamp = helpers::helper_functions::get_time();
+        replacement.signature = sender.sign(&replacement.signing_data());
+        sender.add_to_history(&replacement);
+        Ok(replacement)
+    }
+
+    fn create_and_execute(sender: &mut Entity, receiver: &mut Entity, amount: f64) -> Result<Self, String> {
+        Transaction::create_and_execute_with_fee(sender, receiver, amount, 0.0)
+    }
+
+    fn create_and_execute_with_fee(sender: &mut Entity, receiver: &mut Entity, amount: f64, fee: f64) -> Result<Self, String> {
+        let transaction = Transaction::create_payment(sender, &receiver.address, amount, fee)?;
+        
+        // Update receiver
+        receiver.receive_amount(amount);
+        receiver.add_to_history(&transaction);
+        
+        Ok(transaction)
+    }
+
+    fn create_payment(sender: &mut Entity, receiver_address: &str, amount: f64, fee: f64) -> Result<Self, String> {
+        Transaction::create_payment_with_memo(sender, receiver_address, amount, fee, None)
+    }
+
+    fn create_unsigned_payment(sender_address: &str, receiver_address: &str, amount: f64, fee: f64, nonce: u64) -> Result<Self, String> {
+        if amount < 0.0 || fee < 0.0 {
+            return Err("Amount and fee cannot be negative".to_string());
+        }
+        Ok(Transaction::new(
+            sender_address.to_string(),
+            receiver_address.to_string(),
+            amount,
+            fee,
+            nonce,
+            String::new(),
+            helpers::helper_functions::get_time()
+        ))
+    }
+
+    fn create_payment_with_memo(sender: &mut Entity, receiver_address: &str, amount: f64, fee: f64, memo: Option<String>) -> Result<Self, String> {
+        if amount < 0.0 || fee < 0.0 {
+            return Err("Amount and fee cannot be negative".to_string());
+        }
+
+        // Validate sender has enough
+        if !sender.can_send(amount + fee) {
+            return Err("Insufficient balance".to_string());
+        }
+        
+        // Create and sign transaction (pass references, not clones)
+        let transaction = create_and_sign(
+            sender,    // &Entity reference
+            receiver_address,
+            amount,
+            fee,
+            sender.nonce,
+            memo
+        );
+        transaction.check_memo()?;
+        
+        // Update sender
+        sender.send_amount(amount + fee)?;
+        sender.nonce += 1;
+        sender.add_to_history(&transaction);
+        
+        Ok(transaction)
+    }
+
+    fn create_anchor(sender: &mut Entity, doc_hash: String, fee: f64) -> Result<Self, String> {
+        if doc_hash.is_empty() {
+            return Err("Document hash cannot be empty".to_string());
+        }
+        let mut transaction = unsigned(sender, "", 0.0, fee);
+        transaction.kind = TransactionKind::Anchor { doc_hash: doc_hash };
+        sign_and_spend(sender, transaction)
+    }
+
+    fn create_multi_payment(sender: &mut Entity, outputs: Vec<Output>, fee: f64) -> Result<Self, String> {
+        if outputs.is_empty() {
+            return Err("A multi-recipient payment needs at least one output".to_string());
+        }
+        if fee < 0.0 || outputs.iter().any(|output| output.amount < 0.0) {
+            return Err("Amount and fee cannot be negative".to_string());
+        }
+        let total: f64 = outputs.iter().map(|output| output.amount).sum();
+        let mut transaction = unsigned(sender, "", total, fee);
+        transaction.kind = TransactionKind::MultiPay { outputs: outputs };
+        sign_and_spend(sender, transaction)
+    }
+
+    fn create_timelocked_payment(sender: &mut Entity, receiver_address: &str, amount: f64, fee: f64, lock_time: LockTime) -> Result<Self, String> {
+        if amount < 0.0 || fee < 0.0 {
+            return Err("Amount and fee cannot be negative".to_string());
+        }
+        let mut transaction = unsigned(sender, receiver_address, amount, fee);
+        transaction.lock_time = lock_time;
+        sign_and_spend(sender, transaction)
+    }
+
+    #[cfg(feature = "scripting")]
+    fn create_scripted_payment(sender: &mut Entity, receiver_address: &str, amount: f64, fee: f64, script: String) -> Result<Self, String> {
+        if amount < 0.0 {
+            return Err("Amount cannot be negative".to_string());
+        }
+        crate::scripting::script::check_script(&script)?;
+        let mut transaction = unsigned(sender, receiver_address, amount, fee);
+        transaction.script = Some(script);
+        sign_and_spend(sender, transaction)
+    }
+
+    #[cfg(feature = "privacy")]
+    fn create_payment_with_encrypted_memo(sender: &mut Entity, receiver_address: &str, amount: f64, fee: f64, memo: &str, memo_key: &str) -> Result<Self, String> {
+        use crate::privacy::memo::{encrypt_memo, ephemeral_secret};
+
+        let encrypted = encrypt_memo(&ephemeral_secret(sender, memo_key, memo), memo_key, memo)?;
+        Transaction::create_payment_with_memo(sender, receiver_address, amount, fee, Some(encrypted))
+    }
+
+    #[cfg(feature = "privacy")]
+    fn create_stealth_payment(sender: &mut Entity, meta_address: &str, amount: f64, fee: f64) -> Result<Self, String> {
+        use crate::privacy::stealth::{ephemeral_secret, StealthMetaAddress};
+
+        if amount < 0.0 {
+            return Err("Amount cannot be negative".to_string());
+        }
+        let meta_address = StealthMetaAddress::parse(meta_address)?;
+        let (address, ephemeral_key) = meta_address.derive(&ephemeral_secret(sender, &meta_address));
+        let mut transaction = unsigned(sender, &address, amount, fee);
+        transaction.kind = TransactionKind::StealthTransfer { ephemeral_key: ephemeral_key };
+        sign_and_spend(sender, transaction)
+    }
+
+    #[cfg(feature = "privacy")]
+    fn confidential_transfer(sender: &mut Entity, inputs: &[NoteOpening], payments: &[(&str, f64)], deposit: f64, withdrawal: f64, fee: f64) -> Result<(Self, Vec<NoteOpening>), String> {
+        use crate::privacy::confidential::{balance_message, commit, to_units, RangeProof, MAX_CONFIDENTIAL_OUTPUTS};
+        use crate::privacy::keys::hash_to_scalar;
+        use curve25519_dalek::scalar::Scalar;
+
+        if let Some(input) = inputs.iter().find(|input| input.owner != sender.address) {
+            return Err(format!("Confidential output {}:{} is not owned by {}", input.outpoint.transaction_id, input.outpoint.index, sender.address));
+        }
+        let (deposit_units, withdrawal_units) = (to_units(deposit)?, to_units(withdrawal)?);
+        let mut amounts = Vec::with_capacity(payments.len() + 1);
+        for (owner, amount) in payments {
+            amounts.push((owner.to_string(), to_units(*amount)?));
+        }
+        let available = inputs.iter().map(|input| input.value).sum::<u64>() + deposit_units;
+        let spent = amounts.iter().map(|(_, units)| units).sum::<u64>() + withdrawal_units;
+        if spent > available {
+            return Err(format!("Outputs need {} units, inputs and deposit hold {}", spent, available));
+        }
+        if available > spent {
+            amounts.push((sender.address.clone(), available - spent));
+        }
+        if amounts.len() > MAX_CONFIDENTIAL_OUTPUTS {
+            return Err(format!("A confidential transfer has at most {} outputs", MAX_CONFIDENTIAL_OUTPUTS));
+        }
+
+        // Blinding factors are derived from the sender's key and nonce, never reused
+        let mut outputs = Vec::with_capacity(amounts.len());
+        let mut blindings = Vec::with_capacity(amounts.len());
+        for (index, (owner, units)) in amounts.iter().enumerate() {
+            let blinding = hash_to_scalar("blockc/confidential/blinding", &[
+                sender.private_key.as_bytes(),
+                &sender.nonce.to_le_bytes(),
+                &index.to_le_bytes(),
+            ]);
+            outputs.push(ConfidentialOutput {
+                owner: owner.clone(),
+                commitment: commit(*units, &blinding),
+                range_proof: RangeProof::prove(*units, &blinding)?,
+            });
+            blindings.push(blinding);
+        }
+        let outpoints: Vec<OutPoint> = inputs.iter().map(|input| input.outpoint.clone()).collect();
+        let excess = inputs.iter().map(|input| input.blinding).sum::<Scalar>() - blindings.iter().sum::<Scalar>();
+        let message = balance_message(&sender.address, &outpoints, &outputs, deposit_units, withdrawal_units);
+
+        let mut transaction = unsigned(sender, "", deposit, fee);
+        transaction.kind = TransactionKind::ConfidentialTransfer {
+            inputs: outpoints,
+            outputs: outputs,
+            withdrawal: withdrawal,
+            proof: BalanceProof::prove(&excess, &message),
+        };
+        sender.receive_amount(withdrawal);
+        let transaction = sign_and_spend(sender, transaction)?;
+        let id = transaction.id();
+        let openings = amounts.into_iter().zip(blindings).enumerate()
+            .map(|(index, ((owner, units), blinding))| NoteOpening {
+                outpoint: OutPoint::new(id.clone(), index),
+                owner: owner,
+                value: units,
+                blinding: blinding,
+            })
+            .collect();
+        Ok((transaction, openings))
+    }
+
+    #[cfg(feature = "privacy")]
+    fn ring_deposit(sender: &mut Entity, amount: f64, fee: f64) -> Result<(Self, curve25519_dalek::scalar::Scalar), String> {
+        use crate::privacy::keys::{point_to_hex, public_key, secret_key};
+
+        if amount <= 0.0 {
+            return Err("Ring deposit amount must be positive".to_string());
+        }
+        let secret = secret_key(&sender.private_key, &format!("blockc/ring/deposit:{}", sender.nonce));
+        let mut transaction = unsigned(sender, "", amount, fee);
+        transaction.kind = TransactionKind::RingDeposit { key: point_to_hex(&public_key(&secret)) };
+        Ok((sign_and_spend(sender, transaction)?, secret))
+    }
+
+    #[cfg(feature = "privacy")]
+    fn ring_withdraw(receiver: &mut Entity, state: &ChainState, secret: &curve25519_dalek::scalar::Scalar, ring_size: usize, fee: f64) -> Result<Self, String> {
+        use crate::privacy::keys::{point_from_hex, point_to_hex, public_key};
+        use crate::privacy::ring::withdrawal_message;
+
+        let key = point_to_hex(&public_key(secret));
+        let ring = state.ring_pool().ring_for(&key, ring_size)?;
+        let amount = state.ring_pool().get(&key).unwrap_or(0.0);
+        if fee > amount {
+            return Err("Fee is higher than the deposit".to_string());
+        }
+        let keys = ring.iter().map(|key| point_from_hex(key)).collect::<Result<Vec<_>, String>>()?;
+        let signature = RingSignature::sign(&withdrawal_message(&receiver.address, receiver.nonce, fee), &keys, secret)?;
+        let mut transaction = unsigned(receiver, "", 0.0, fee);
+        transaction.kind = TransactionKind::RingWithdraw { ring: ring, signature: signature };
+        receiver.receive_amount(amount);
+        sign_and_spend(receiver, transaction)
+    }
+
+    fn create_htlc(sender: &mut Entity, receiver_address: &str, amount: f64, fee: f64, hash_lock: String, expiry_height: u64) -> Result<Self, String> {
+        if amount <= 0.0 {
+            return Err("HTLC amount must be positive".to_string());
+        }
+        let mut transaction = unsigned(sender, receiver_address, amount, fee);
+        transaction.kind = TransactionKind::HtlcLock { hash_lock: hash_lock, expiry_height: expiry_height };
+        sign_and_spend(sender, transaction)
+    }
+
+    fn create_htlc_claim(claimer: &mut Entity, htlc: &Htlc, preimage: String, fee: f64) -> Result<Self, String> {
+        if htlc.receiver != claimer.address {
+            return Err("Only the HTLC receiver can claim it".to_string());
+        }
+        if !htlc.matches_preimage(&preimage) {
+            return Err("Preimage does not match the hash lock".to_string());
+        }
+        if fee > htlc.amount {
+            return Err("Fee is higher than the HTLC amount".to_string());
+        }
+        let mut transaction = unsigned(claimer, "", 0.0, fee);
+        transaction.kind = TransactionKind::HtlcClaim { htlc_id: htlc.id.clone(), preimage: preimage };
+        claimer.receive_amount(htlc.amount);
+        sign_and_spend(claimer, transaction)
+    }
+
+    fn create_htlc_refund(sender: &mut Entity, htlc: &Htlc, fee: f64) -> Result<Self, String> {
+        if htlc.sender != sender.address {
+            return Err("Only the HTLC sender can get a refund".to_string());
+        }
+        if fee > htlc.amount {
+            return Err("Fee is higher than the HTLC amount".to_string());
+        }
+        let mut transaction = unsigned(sender, "", 0.0, fee);
+        transaction.kind = TransactionKind::HtlcRefund { htlc_id: htlc.id.clone() };
+        transaction.lock_time = LockTime::Height(htlc.expiry_height);
+        sender.receive_amount(htlc.amount);
+        sign_and_spend(sender, transaction)
+    }
+
+    fn create_channel_open(sender: &mut Entity, counterparty: &str, capacity: f64, fee: f64) -> Result<Self, String> {
+        if capacity <= 0.0 {
+            return Err("Channel capacity must be positive".to_string());
+        }
+        let mut transaction = unsigned(sender, counterparty, capacity, fee);
+        transaction.kind = TransactionKind::ChannelOpen;
+        sign_and_spend(sender, transaction)
+    }
+
+    fn create_channel_close(sender: &mut Entity, state: ChannelState, fee: f64) -> Result<Self, String> {
+        if !state.is_fully_signed() {
+            return Err("Channel state must be signed by both parties".to_string());
+        }
+        let mut transaction = unsigned(sender, "", 0.0, fee);
+        transaction.kind = TransactionKind::ChannelClose { state: state };
+        sign_and_spend(sender, transaction)
+    }
+
+    fn create_channel_settle(sender: &mut Entity, channel: &Channel, fee: f64) -> Result<Self, String> {
+        let ChannelStatus::Closing { state, dispute_until } = &channel.status else {
+            return Err(format!("Channel {} is not closing", channel.id));
+        };
+        if !channel.is_party(&sender.address) {
+            return Err(format!("{} is not a party of channel {}", sender.address, channel.id));
+        }
+        let mut transaction = unsigned(sender, "", 0.0, fee);
+        transaction.kind = TransactionKind::ChannelSettle { channel_id: channel.id.clone() };
+        transaction.lock_time = LockTime::Height(*dispute_until);
+        sender.receive_amount(channel.balance_of(state, &sender.address));
+        sign_and_spend(sender, transaction)
+    }
+
+    fn create_escrow(buyer: &mut Entity, seller_address: &str, arbiter_address: &str, amount: f64, fee: f64) -> Result<Self, String> {
+        if amount <= 0.0 {
+            return Err("Escrow amount must be positive".to_string());
+        }
+        if buyer.address == seller_address || buyer.address == arbiter_address || seller_address == arbiter_address {
+            return Err("Buyer, seller and arbiter must be different".to_string());
+        }
+        let mut transaction = unsigned(buyer, seller_address, amount, fee);
+        transaction.kind = TransactionKind::EscrowOpen { arbiter: arbiter_address.to_string() };
+        sign_and_spend(buyer, transaction)
+    }
+
+    fn create_escrow_vote(voter: &mut Entity, escrow_id: &str, outcome: EscrowOutcome, fee: f64) -> Result<Self, String> {
+        let mut transaction = unsigned(voter, "", 0.0, fee);
+        transaction.kind = TransactionKind::EscrowVote { escrow_id: escrow_id.to_string(), outcome: outcome };
+        sign_and_spend(voter, transaction)
+    }
+
+    fn create_vesting_grant(grantor: &mut Entity, beneficiary_address: &str, amount: f64, fee: f64, schedule: VestingSchedule) -> Result<Self, String> {
+        if amount <= 0.0 {
+            return Err("Vesting amount must be positive".to_string());
+        }
+        schedule.check(amount)?;
+        let mut transaction = unsigned(grantor, beneficiary_address, amount, fee);
+        transaction.kind = TransactionKind::VestingGrant { schedule: schedule };
+        sign_and_spend(grantor, transaction)
+    }
+
+    fn create_stream(sender: &mut Entity, receiver_address: &str, deposit: f64, rate_per_block: f64, fee: f64) -> Result<Self, String> {
+        if deposit <= 0.0 || rate_per_block <= 0.0 {
+            return Err("Stream deposit and rate must be positive".to_string());
+        }
+        let mut transaction = unsigned(sender, receiver_address, deposit, fee);
+        transaction.kind = TransactionKind::StreamOpen { rate_per_block: rate_per_block };
+        sign_and_spend(sender, transaction)
+    }
+
+    fn withdraw_from_stream(receiver: &mut Entity, stream: &Stream, height: u64, fee: f64) -> Result<Self, String> {
+        if stream.receiver != receiver.address {
+            return Err("Only the stream receiver can withdraw".to_string());
+        }
+        let mut transaction = unsigned(receiver, "", 0.0, fee);
+        transaction.kind = TransactionKind::StreamWithdraw { stream_id: stream.id.clone() };
+        receiver.receive_amount(stream.withdrawable_at(height));
+        sign_and_spend(receiver, transaction)
+    }
+
+    fn cancel_stream(sender: &mut Entity, stream: &Stream, height: u64, fee: f64) -> Result<Self, String> {
+        if stream.sender != sender.address {
+            return Err("Only the stream sender can cancel it".to_string());
+        }
+        let mut transaction = unsigned(sender, "", 0.0, fee);
+        transaction.kind = TransactionKind::StreamCancel { stream_id: stream.id.clone() };
+        sender.receive_amount(stream.refundable_at(height));
+        sign_and_spend(sender, transaction)
+    }
+
+    fn create_utxo_transaction(sender: &mut Entity, inputs: Vec<(OutPoint, Output)>, outputs: Vec<Output>, fee: f64) -> Result<Self, String> {
+        if inputs.is_empty() && outputs.is_empty() {
+            return Err("A UTXO transaction needs inputs or outputs".to_string());
+        }
+        if inputs.iter().any(|(_, output)| output.address != sender.address) {
+            return Err("Only the owner can spend an output".to_string());
+        }
+        if outputs.iter().any(|output| output.amount < 0.0) {
+            return Err("Amount and fee cannot be negative".to_string());
+        }
+        let input_value: f64 = inputs.iter().map(|(_, output)| output.amount).sum();
+        let output_value: f64 = outputs.iter().map(|output| output.amount).sum();
+
+        let mut transaction = unsigned(sender, "", output_value, fee);
+        transaction.kind = TransactionKind::Utxo {
+            inputs: inputs.into_iter().map(|(outpoint, _)| outpoint).collect(),
+            outputs: outputs,
+        };
+        sender.receive_amount(input_value);
+        let signed = sign_and_spend(sender, transaction);
+        if signed.is_err() {
+            sender.send_amount(input_value)?;
+        }
+        signed
+    }
+
+    fn create_coinjoin(coordinator: &mut Entity, inputs: Vec<JoinInput>, outputs: Vec<Output>, input_value: f64) -> Result<Self, String> {
+        if outputs.iter().any(|output| output.amount < 0.0) {
+            return Err("Amount and fee cannot be negative".to_string());
+        }
+        let output_value: f64 = outputs.iter().map(|output| output.amount).sum();
+        if output_value > input_value {
+            return Err(format!("Outputs sum to {}, inputs only hold {}", output_value, input_value));
+        }
+
+        let mut transaction = unsigned(coordinator, "", output_value, input_value - output_value);
+        transaction.kind = TransactionKind::CoinJoin {
+            inputs: inputs,
+            outputs: outputs,
+        };
+        coordinator.receive_amount(input_value);
+        let signed = sign_and_spend(coordinator, transaction);
+        if signed.is_err() {
+            coordinator.send_amount(input_value)?;
+        }
+        signed
+    }
+
+    fn create_deploy(sender: &mut Entity, code: Vec<Instruction>, amount: f64, fee: f64) -> Result<Self, String> {
+        if amount < 0.0 {
+            return Err("Amount cannot be negative".to_string());
+        }
+        let mut transaction = unsigned(sender, "", amount, fee);
+        transaction.kind = TransactionKind::Deploy { code: code };
+        transaction.check_payload()?;
+        sign_and_spend(sender, transaction)
+    }
+
+    fn create_call(sender: &mut Entity, contract: &str, amount: f64, input: Vec<i64>, gas_limit: u64, gas_price: f64, fee: f64) -> Result<Self, String> {
+        if amount < 0.0 {
+            return Err("Amount cannot be negative".to_string());
+        }
+        let mut transaction = unsigned(sender, contract, amount, fee);
+        transaction.kind = TransactionKind::Call { contract: contract.to_string(), input: input };
+        transaction.check_payload()?;
+        if gas_limit < transaction.intrinsic_gas() {
+            return Err(format!("Gas limit {} is below the intrinsic gas {}", gas_limit, transaction.intrinsic_gas()));
+        }
+        transaction.gas_limit = gas_limit;
+        transaction.gas_price = gas_price;
+        sign_and_spend(sender, transaction)
+    }
+
+    fn create_asset(issuer: &mut Entity, ticker: &str, decimals: u8, max_supply: u64, initial_supply: u64, fee: f64) -> Result<Self, String> {
+        check_definition(ticker, decimals)?;
+        if initial_supply > max_supply {
+            return Err(format!("Initial supply {} is above the max supply {}", initial_supply, max_supply));
+        }
+        let mut transaction = unsigned(issuer, "", 0.0, fee);
+        transaction.kind = TransactionKind::AssetIssue {
+            ticker: ticker.to_string(),
+            decimals: decimals,
+            max_supply: max_supply,
+            initial_supply: initial_supply,
+        };
+        sign_and_spend(issuer, transaction)
+    }
+
+    fn mint_asset(issuer: &mut Entity, asset_id: &str, quantity: u64, fee: f64) -> Result<Self, String> {
+        let mut transaction = unsigned(issuer, "", 0.0, fee);
+        transaction.kind = TransactionKind::AssetMint { asset_id: asset_id.to_string(), quantity: quantity };
+        sign_and_spend(issuer, transaction)
+    }
+
+    fn transfer_asset(sender: &mut Entity, receiver_address: &str, asset_id: &str, quantity: u64, fee: f64) -> Result<Self, String> {
+        let mut transaction = unsigned(sender, receiver_address, 0.0, fee);
+        transaction.kind = TransactionKind::AssetTransfer { asset_id: asset_id.to_string(), quantity: quantity };
+        sign_and_spend(sender, transaction)
+    }
+
+    fn mint_nft(creator: &mut Entity, token_id: &str, metadata_hash: Option<String>, fee: f64) -> Result<Self, String> {
+        let mut transaction = unsigned(creator, "", 0.0, fee);
+        transaction.kind = TransactionKind::NftMint { token_id: token_id.to_string(), metadata_hash: metadata_hash };
+        sign_and_spend(creator, transaction)
+    }
+
+    fn transfer_nft(owner: &mut Entity, receiver_address: &str, token_id: &str, fee: f64) -> Result<Self, String> {
+        let mut transaction = unsigned(owner, receiver_address, 0.0, fee);
+        transaction.kind = TransactionKind::NftTransfer { token_id: token_id.to_string() };
+        sign_and_spend(owner, transaction)
+    }
+
+    fn burn_nft(owner: &mut Entity, token_id: &str, fee: f64) -> Result<Self, String> {
+        let mut transaction = unsigned(owner, "", 0.0, fee);
+        transaction.kind = TransactionKind::NftBurn { token_id: token_id.to_string() };
+        sign_and_spend(owner, transaction)
+    }
+
+    fn register_name(owner: &mut Entity, name: &str, target: &str, fee: f64) -> Result<Self, String> {
+        check_name(name)?;
+        let mut transaction = unsigned(owner, "", 0.0, fee);
+        transaction.kind = TransactionKind::NameRegister { name: name.to_string(), target: target.to_string() };
+        sign_and_spend(owner, transaction)
+    }
+
+    fn renew_name(owner: &mut Entity, name: &str, fee: f64) -> Result<Self, String> {
+        let mut transaction = unsigned(owner, "", 0.0, fee);
+        transaction.kind = TransactionKind::NameRenew { name: name.to_string() };
+        sign_and_spend(owner, transaction)
+    }
+
+    fn create_payment_to_name(sender: &mut Entity, state: &ChainState, name: &str, amount: f64, fee: f64) -> Result<Self, String> {
+        if !registry::is_name(name) {
+            return Err(format!("{} is not a name", name));
+        }
+        let address = state.resolve_name(name).ok_or(format!("Name {} is not registered", name))?.to_string();
+        Transaction::create_payment(sender, &address, amount, fee)
+    }
+
+    fn register_did(controller: &mut Entity, public_keys: Vec<String>, services: Vec<Service>, fee: f64) -> Result<Self, String> {
+        check_document(&public_keys, &services)?;
+        let mut transaction = unsigned(controller, "", 0.0, fee);
+        transaction.kind = TransactionKind::DidRegister { public_keys: public_keys, services: services };
+        sign_and_spend(controller, transaction)
+    }
+
+    fn update_did(controller: &mut Entity, public_keys: Vec<String>, services: Vec<Service>, fee: f64) -> Result<Self, String> {
+        check_document(&public_keys, &services)?;
+        let mut transaction = unsigned(controller, "", 0.0, fee);
+        transaction.kind = TransactionKind::DidUpdate { public_keys: public_keys, services: services };
+        sign_and_spend(controller, transaction)
+    }
+
+    fn create_ballot(creator: &mut Entity, question: &str, options: Vec<String>, start_height: u64, end_height: u64, weighting: VoteWeighting, fee: f64) -> Result<Self, String> {
+        check_ballot(&options, start_height, end_height, 0)?;
+        let mut transaction = unsigned(creator, "", 0.0, fee);
+        transaction.kind = TransactionKind::BallotCreate {
+            question: question.to_string(),
+            options: options,
+            start_height: start_height,
+            end_height: end_height,
+            weighting: weighting,
+        };
+        sign_and_spend(creator, transaction)
+    }
+
+    fn cast_vote(voter: &mut Entity, ballot_id: &str, option: usize, fee: f64) -> Result<Self, String> {
+        let mut transaction = unsigned(voter, "", 0.0, fee);
+        transaction.kind = TransactionKind::BallotVote { ballot_id: ballot_id.to_string(), option: option };
+        sign_and_spend(voter, transaction)
+    }
+
+    fn create_auction(seller: &mut Entity, item: &str, reserve_price: f64, bidding_end: u64, reveal_end: Option<u64>, fee: f64) -> Result<Self, String> {
+        if reserve_price < 0.0 {
+            return Err("Reserve price cannot be negative".to_string());
+        }
+        if reveal_end.is_some_and(|end| end <= bidding_end) {
+            return Err("The reveal phase must end after bidding".to_string());
+        }
+        let mut transaction = unsigned(seller, "", 0.0, fee);
+        transaction.kind = TransactionKind::AuctionCreate {
+            item: item.to_string(),
+            reserve_price: reserve_price,
+            bidding_end: bidding_end,
+            reveal_end: reveal_end,
+        };
+        sign_and_spend(seller, transaction)
+    }
+
+    fn bid_on_auction(bidder: &mut Entity, auction_id: &str, amount: f64, fee: f64) -> Result<Self, String> {
+        if amount <= 0.0 {
+            return Err("A bid must be positive".to_string());
+        }
+        let mut transaction = unsigned(bidder, "", amount, fee);
+        transaction.kind = TransactionKind::AuctionBid { auction_id: auction_id.to_string() };
+        sign_and_spend(bidder, transaction)
+    }
+
+    fn commit_sealed_bid(bidder: &mut Entity, auction_id: &str, bid: f64, salt: &str, deposit: f64, fee: f64) -> Result<Self, String> {
+        if bid <= 0.0 || bid > deposit {
+            return Err("A sealed bid must be positive and covered by the deposit".to_string());
+        }
+        let mut transaction = unsigned(bidder, "", deposit, fee);
+        transaction.kind = TransactionKind::AuctionCommit {
+            auction_id: auction_id.to_string(),
+            commitment: commit_bid(&bidder.address, bid, salt),
+        };
+        sign_and_spend(bidder, transaction)
+    }
+
+    fn reveal_sealed_bid(bidder: &mut Entity, auction_id: &str, bid: f64, salt: &str, fee: f64) -> Result<Self, String> {
+        let mut transaction = unsigned(bidder, "", 0.0, fee);
+        transaction.kind = TransactionKind::AuctionReveal { auction_id: auction_id.to_string(), bid: bid, salt: salt.to_string() };
+        sign_and_spend(bidder, transaction)
+    }
+
+    fn settle_auction(sender: &mut Entity, auction_id: &str, fee: f64) -> Result<Self, String> {
+        let mut transaction = unsigned(sender, "", 0.0, fee);
+        transaction.kind = TransactionKind::AuctionSettle { auction_id: auction_id.to_string() };
+        sign_and_spend(sender, transaction)
+    }
+
+    fn create_pool(provider: &mut Entity, asset_a: &str, amount_a: u64, asset_b: &str, amount_b: u64, fee_bps: u32, fee: f64) -> Result<Self, String> {
+        if asset_a == asset_b {
+            return Err("A pool needs two different assets".to_string());
+        }
+        if amount_a == 0 || amount_b == 0 {
+            return Err("A pool needs liquidity on both sides".to_string());
+        }
+        if fee_bps > MAX_POOL_FEE_BPS {
+            return Err(format!("Pool fee cannot be above {} basis points", MAX_POOL_FEE_BPS));
+        }
+        let mut transaction = unsigned(provider, "", 0.0, fee);
+        transaction.kind = TransactionKind::PoolCreate {
+            asset_a: asset_a.to_string(),
+            asset_b: asset_b.to_string(),
+            amount_a: amount_a,
+            amount_b: amount_b,
+            fee_bps: fee_bps,
+        };
+        sign_and_spend(provider, transaction)
+    }
+
+    fn add_liquidity(provider: &mut Entity, pool_id: &str, amount_a: u64, amount_b: u64, fee: f64) -> Result<Self, String> {
+        let mut transaction = unsigned(provider, "", 0.0, fee);
+        transaction.kind = TransactionKind::PoolDeposit { pool_id: pool_id.to_string(), amount_a: amount_a, amount_b: amount_b };
+        sign_and_spend(provider, transaction)
+    }
+
+    fn remove_liquidity(provider: &mut Entity, pool_id: &str, shares: u64, fee: f64) -> Result<Self, String> {
+        let mut transaction = unsigned(provider, "", 0.0, fee);
+        transaction.kind = TransactionKind::PoolWithdraw { pool_id: pool_id.to_string(), shares: shares };
+        sign_and_spend(provider, transaction)
+    }
+
+    fn swap(trader: &mut Entity, pool_id: &str, asset_in: &str, amount_in: u64, min_out: u64, fee: f64) -> Result<Self, String> {
+        let mut transaction = unsigned(trader, "", 0.0, fee);
+        transaction.kind = TransactionKind::PoolSwap {
+            pool_id: pool_id.to_string(),
+            asset_in: asset_in.to_string(),
+            amount_in: amount_in,
+            min_out: min_out,
+        };
+        sign_and_spend(trader, transaction)
+    }
+
+    fn place_order(trader: &mut Entity, base: &str, quote: &str, side: Side, price: u64, quantity: u64, fee: f64) -> Result<Self, String> {
+        if base == quote {
+            return Err("An order needs two different assets".to_string());
+        }
+        if price == 0 || quantity == 0 {
+            return Err("Order price and quantity must be positive".to_string());
+        }
+        let mut transaction = unsigned(trader, "", 0.0, fee);
+        transaction.kind = TransactionKind::OrderPlace {
+            base: base.to_string(),
+            quote: quote.to_string(),
+            side: side,
+            price: price,
+            quantity: quantity,
+        };
+        sign_and_spend(trader, transaction)
+    }
+
+    fn cancel_order(trader: &mut Entity, order_id: &str, fee: f64) -> Result<Self, String> {
+        let mut transaction = unsigned(trader, "", 0.0, fee);
+        transaction.kind = TransactionKind::OrderCancel { order_id: order_id.to_string() };
+        sign_and_spend(trader, transaction)
+    }
+
+    fn create_feed(owner: &mut Entity, feed: &str, reporters: Vec<String>, quorum: usize, max_age: u64, fee: f64) -> Result<Self, String> {
+        check_feed(feed, &reporters, quorum, max_age)?;
+        let mut transaction = unsigned(owner, "", 0.0, fee);
+        transaction.kind = TransactionKind::OracleCreate { feed: feed.to_string(), reporters: reporters, quorum: quorum, max_age: max_age };
+        sign_and_spend(owner, transaction)
+    }
+
+    fn report_value(reporter: &mut Entity, feed: &str, value: f64, fee: f64) -> Result<Self, String> {
+        if !value.is_finite() {
+            return Err("Reported value must be a finite number".to_string());
+        }
+        let mut transaction = unsigned(reporter, "", 0.0, fee);
+        transaction.kind = TransactionKind::OracleReport { feed: feed.to_string(), value: value };
+        sign_and_spend(reporter, transaction)
+    }
+
+    fn bridge_lock(sender: &mut Entity, state: &ChainState, chain_id: &str, asset_id: &str, quantity: u64, recipient: &str, fee: f64) -> Result<Self, String> {
+        let asset = state.get_asset(asset_id).ok_or(format!("No asset {}", asset_id))?;
+        if quantity == 0 {
+            return Err("Bridged quantity must be positive".to_string());
+        }
+        let mut transaction = unsigned(sender, "", 0.0, fee);
+        transaction.kind = TransactionKind::BridgeLock {
+            chain_id: chain_id.to_string(),
+            asset_id: asset_id.to_string(),
+            ticker: asset.ticker.clone(),
+            decimals: asset.decimals,
+            quantity: quantity,
+            recipient: recipient.to_string(),
+        };
+        sign_and_spend(sender, transaction)
+    }
+
+    fn relay_headers(relayer: &mut Entity, chain_id: &str, headers: Vec<BlockHeader>, fee: f64) -> Result<Self, String> {
+        let mut transaction = unsigned(relayer, "", 0.0, fee);
+        transaction.kind = TransactionKind::BridgeRelay { chain_id: chain_id.to_string(), headers: headers };
+        sign_and_spend(relayer, transaction)
+    }
+
+    fn bridge_mint(sender: &mut Entity, chain_id: &str, proof: BridgeProof, fee: f64) -> Result<Self, String> {
+        let mut transaction = unsigned(sender, "", 0.0, fee);
+        transaction.kind = TransactionKind::BridgeMint { chain_id: chain_id.to_string(), proof: Box::new(proof) };
+        sign_and_spend(sender, transaction)
+    }
+
+    fn bridge_burn(sender: &mut Entity, chain_id: &str, asset_id: &str, quantity: u64, recipient: &str, fee: f64) -> Result<Self, String> {
+        if quantity == 0 {
+            return Err("Bridged quantity must be positive".to_string());
+        }
+        let mut transaction = unsigned(sender, "", 0.0, fee);
+        transaction.kind = TransactionKind::BridgeBurn {
+            chain_id: chain_id.to_string(),
+            asset_id: asset_id.to_string(),
+            quantity: quantity,
+            recipient: recipient.to_string(),
+        };
+        sign_and_spend(sender, transaction)
+    }
+
+    fn bridge_release(sender: &mut Entity, chain_id: &str, proof: BridgeProof, fee: f64) -> Result<Self, String> {
+        let mut transaction = unsigned(sender, "", 0.0, fee);
+        transaction.kind = TransactionKind::BridgeRelease { chain_id: chain_id.to_string(), proof: Box::new(proof) };
+        sign_and_spend(sender, transaction)
+    }
+}
+
+fn create_and_sign(sender: &Entity, receiver_address: &str, amount_tx: f64, fee: f64, nonce: u64, memo: Option<String>) -> Transaction {
+    // create transaction (store addresses only)
+    let time_stamp = helpers::helper_functions::get_time();
+    let mut transaction = Transaction::new(
+        sender.address.clone(),
+        receiver_address.to_string(),
+        amount_tx, 
+        fee,
+        nonce,
+        String::new(), 
+        time_stamp
+    );
+    transaction.memo = memo;
+
+    // calling for signature
+    transaction.signature = sender.sign(&transaction.signing_data());
+    transaction
+}
+
+/// Builds an unsigned transaction from `sender` using its next nonce.
+fn unsigned(sender: &Entity, receiver_address: &str, amount: f64, fee: f64) -> Transaction {
+    Transaction::new(
+        sender.address.clone(),
+        receiver_address.to_string(),
+        amount,
+        fee,
+        sender.nonce,
+        String::new(),
+        helpers::helper_functions::get_time()
+    )
+}
+
+/// Signs `transaction` and updates the sender (balance, nonce and history).
+/// 
+/// The gas limit is raised to the transaction's intrinsic gas if it is lower.
+/// Fails without changing anything if the sender cannot afford `total_cost()`.
+fn sign_and_spend(sender: &mut Entity, mut transaction: Transaction) -> Result<Transaction, String> {
+    if transaction.fee < 0.0 || transaction.gas_price < 0.0 {
+        return Err("Fee and gas price cannot be negative".to_string());
+    }
+    transaction.gas_limit = transaction.gas_limit.max(transaction.intrinsic_gas());
+    if !sender.can_send(transaction.total_cost()) {
+        return Err("Insufficient balance".to_string());
     }
+    transaction.signature = sender.sign(&transaction.signing_data());
 
+    sender.send_amount(transaction.total_cost())?;
+    sender.nonce += 1;
+    sender.add_to_history(&transaction);
+    Ok(transaction)
 }
@@ -0,0 +1,253 @@
+use crate::helpers::{self};
+use crate::transactions::transactions::{Transaction, TransactionKind};
+
+/// Default address of the protocol treasury.
+pub const TREASURY_ADDRESS: &str = "TREASURY";
+
+/// Lifecycle of a treasury spend proposal.
+///
+/// `Pending` -> `Approved` (enough governors signed off) -> `Executed` (the spend
+/// transaction was included in a block).
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProposalStatus {
+    Pending,
+    Approved,
+    Executed,
+}
+
+/// A request to pay `amount` from the treasury to `recipient`.
+///
+/// # Fields
+///
+/// * `id` - Unique proposal id (assigned by the treasury)
+/// * `recipient` - Address that will receive the funds
+/// * `amount` - Amount requested
+/// * `description` - Why the funds are needed
+/// * `approvals` - Governors that approved this proposal
+/// * `status` - Current status of the proposal
+#[derive(Debug, Clone, PartialEq)]
+pub struct Proposal {
+    pub id: u64,
+    pub recipient: String,
+    pub amount: f64,
+    pub description: String,
+    pub approvals: Vec<String>,
+    pub status: ProposalStatus,
+}
+
+/// Protocol treasury funded by a share of every block reward and fee.
+///
+/// The treasury has no private key: it is owned by governance. Funds can only
+/// leave it through a spend transaction that points to an approved proposal.
+///
+/// # Fields
+///
+/// * `address` - On-chain address holding the treasury funds
+/// * `share_percent` - Percentage (0-100) of each block reward and fees sent to the treasury
+/// * `governors` - Addresses allowed to approve proposals
+/// * `approvals_required` - Number of governor approvals needed to approve a proposal
+/// * `proposals` - All proposals ever created
+/// * `spend_count` - Number of executed spends (the treasury's next nonce)
+///
+/// # Example
+///
+/// ```
+/// use blockc::treasury::treasury::{Treasury, ProposalStatus};
+///
+/// let mut treasury = Treasury::new("TREASURY".to_string(), 10.0);
+/// treasury.add_governor("Alice".to_string());
+///
+/// let id = treasury.propose("Bob".to_string(), 5.0, "Docs bounty".to_string()).unwrap();
+/// assert_eq!(treasury.approve(id, "Alice").unwrap(), ProposalStatus::Approved);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Treasury {
+    pub address: String,
+    pub share_percent: f64,
+    pub governors: Vec<String>,
+    pub approvals_required: usize,
+    pub proposals: Vec<Proposal>,
+    pub spend_count: u64,
+}
+
+impl Treasury {
+    /// Creates a treasury with no governors that requires a single approval.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - On-chain address of the treasury
+    /// * `share_percent` - Percentage (0-100) of rewards and fees to collect
+    ///
+    /// # Returns
+    ///
+    /// A new `Treasury` instance.
+    pub fn new(address: String, share_percent: f64) -> Self {
+        Treasury {
+            address: address,
+            share_percent: share_percent.clamp(0.0, 100.0),
+            governors: Vec::new(),
+            approvals_required: 1,
+            proposals: Vec::new(),
+            spend_count: 0,
+        }
+    }
+
+    /// Adds a governor allowed to approve proposals.
+    pub fn add_governor(&mut self, governor: String) {
+        if !self.governors.contains(&governor) {
+            self.governors.push(governor);
+        }
+    }
+
+    /// Splits `amount` into (treasury share, remainder).
+    pub fn split(&self, amount: f64) -> (f64, f64) {
+        let treasury_part = amount * self.share_percent / 100.0;
+        (treasury_part, amount - treasury_part)
+    }
+
+    /// Creates a new spend proposal.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u64)` - Id of the new proposal
+    /// * `Err(String)` - If the amount is not positive
+    pub fn propose(&mut self, recipient: String, amount: f64, description: String) -> Result<u64, String> {
+        if amount <= 0.0 {
+            return Err("Proposal amount must be positive".to_string());
+        }
+        let id = self.proposals.len() as u64;
+        self.proposals.push(Proposal {
+            id: id,
+            recipient: recipient,
+            amount: amount,
+            description: description,
+            approvals: Vec::new(),
+            status: ProposalStatus::Pending,
+        });
+        Ok(id)
+    }
+
+    /// Returns the treasury as it was before its spends were mined: executed
+    /// proposals are approved again and the spend count is back to 0.
+    ///
+    /// Proposals and approvals are not transactions, so this is what a chain's
+    /// blocks are replayed from (see `LocalRules`): replaying the spends executes
+    /// the proposals again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::treasury::treasury::{Treasury, ProposalStatus};
+    ///
+    /// let mut treasury = Treasury::new("TREASURY".to_string(), 10.0);
+    /// treasury.add_governor("Alice".to_string());
+    /// let id = treasury.propose("Bob".to_string(), 5.0, "Docs bounty".to_string()).unwrap();
+    /// treasury.approve(id, "Alice").unwrap();
+    /// let spend = treasury.create_spend(id).unwrap();
+    /// treasury.authorize_spend(&spend).unwrap();
+    ///
+    /// let mut rewound = treasury.rewound();
+    /// assert_eq!(rewound.get_proposal(id).unwrap().status, ProposalStatus::Approved);
+    /// rewound.authorize_spend(&spend).unwrap();
+    /// assert_eq!(rewound, treasury);
+    /// ```
+    pub fn rewound(&self) -> Treasury {
+        let mut rewound = self.clone();
+        for proposal in &mut rewound.proposals {
+            if proposal.status == ProposalStatus::Executed {
+                proposal.status = ProposalStatus::Approved;
+            }
+        }
+        rewound.spend_count = 0;
+        rewound
+    }
+
+    /// Returns the proposal with the given id, if any.
+    pub fn get_proposal(&self, id: u64) -> Option<&Proposal> {
+        self.proposals.iter().find(|p| p.id == id)
+    }
+
+    /// Records a governor's approval of a proposal.
+    ///
+    /// Once `approvals_required` distinct governors approved it, the proposal
+    /// becomes `Approved`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ProposalStatus)` - Status of the proposal after the approval
+    /// * `Err(String)` - If the governor or proposal is unknown, or the proposal is not pending
+    pub fn approve(&mut self, id: u64, governor: &str) -> Result<ProposalStatus, String> {
+        if !self.governors.iter().any(|g| g == governor) {
+            return Err(format!("{} is not a treasury governor", governor));
+        }
+        let required = self.approvals_required;
+        let proposal = self.proposals.iter_mut()
+            .find(|p| p.id == id)
+            .ok_or(format!("Unknown proposal {}", id))?;
+
+        if proposal.status != ProposalStatus::Pending {
+            return Err(format!("Proposal {} is not pending", id));
+        }
+        if !proposal.approvals.iter().any(|g| g == governor) {
+            proposal.approvals.push(governor.to_string());
+        }
+        if proposal.approvals.len() >= required {
+            proposal.status = ProposalStatus::Approved;
+        }
+        Ok(proposal.status.clone())
+    }
+
+    /// Builds the spend transaction for an approved proposal.
+    ///
+    /// The transaction is signed with a reference to the proposal instead of a key;
+    /// the chain checks that reference when the transaction is included in a block.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The spend transaction, ready to be added to a block
+    /// * `Err(String)` - If the proposal is unknown or not approved
+    pub fn create_spend(&self, id: u64) -> Result<Transaction, String> {
+        let proposal = self.get_proposal(id).ok_or(format!("Unknown proposal {}", id))?;
+        if proposal.status != ProposalStatus::Approved {
+            return Err(format!("Proposal {} is not approved", id));
+        }
+        Ok(Transaction::new(
+            self.address.clone(),
+            proposal.recipient.clone(),
+            proposal.amount,
+            0.0,
+            self.spend_count,
+            Treasury::spend_signature(id),
+            helpers::helper_functions::get_time()
+        ))
+    }
+
+    /// Checks that a transaction sent from the treasury matches an approved proposal
+    /// and marks that proposal as executed.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the spend is authorized
+    /// * `Err(String)` - If no approved proposal matches the transaction
+    pub fn authorize_spend(&mut self, transaction: &Transaction) -> Result<(), String> {
+        let proposal = self.proposals.iter_mut()
+            .find(|p| Treasury::spend_signature(p.id) == transaction.signature)
+            .ok_or("Treasury spend does not reference a proposal".to_string())?;
+
+        if proposal.status != ProposalStatus::Approved {
+            return Err(format!("Proposal {} is not approved", proposal.id));
+        }
+        if transaction.kind != TransactionKind::Transfer
+            || proposal.recipient != transaction.receiver_address
+            || proposal.amount != transaction.amount {
+            return Err(format!("Treasury spend does not match proposal {}", proposal.id));
+        }
+        proposal.status = ProposalStatus::Executed;
+        self.spend_count += 1;
+        Ok(())
+    }
+
+    fn spend_signature(id: u64) -> String {
+        format!("proposal:{}", id)
+    }
+}
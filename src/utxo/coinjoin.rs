@@ -0,0 +1,255 @@
+use std::collections::BTreeSet;
+use crate::entity::entity::Entity;
+use crate::transactions::transactions::{Output, Transaction, TransactionExt};
+use crate::utxo::utxo::OutPoint;
+
+// Join inputs are carried by the CoinJoin transaction (`blockc_core`).
+pub use blockc_core::payload::JoinInput;
+
+/// Fewest participants a CoinJoin can have: with one, nothing is mixed.
+pub const MIN_COINJOIN_PARTICIPANTS: usize = 2;
+
+/// Returns the data every participant signs: all inputs and outputs of the join.
+///
+/// Signing it means "spend my inputs only if these exact outputs are created",
+/// so the coordinator can't change the outputs once anyone has signed.
+pub fn join_message(inputs: &[OutPoint], outputs: &[Output]) -> String {
+    let ins: Vec<String> = inputs.iter()
+        .map(|input| format!("{}:{}", input.transaction_id, input.index))
+        .collect();
+    let outs: Vec<String> = outputs.iter()
+        .map(|output| format!("{}:{}", output.address, output.amount))
+        .collect();
+    format!("coinjoin:{}->{}", ins.join(","), outs.join(","))
+}
+
+/// What one participant brings to a CoinJoin.
+///
+/// # Fields
+///
+/// * `owner` - Address owning the inputs
+/// * `inputs` - Outputs to spend, with their value (see `ChainState::get_utxos`)
+/// * `mix_address` - Address receiving the mixed output (ideally a fresh one)
+/// * `change_address` - Address receiving what is left after the denomination and fee
+#[derive(Debug, Clone, PartialEq)]
+pub struct Registration {
+    pub owner: String,
+    pub inputs: Vec<(OutPoint, Output)>,
+    pub mix_address: String,
+    pub change_address: String,
+}
+
+impl Registration {
+    /// Sum of the registered inputs.
+    pub fn input_value(&self) -> f64 {
+        self.inputs.iter().map(|(_, output)| output.amount).sum()
+    }
+}
+
+/// Coordinator merging the UTXOs of several wallets into one jointly-signed
+/// transaction (CoinJoin).
+///
+/// Every participant gets one output of exactly `denomination` coins, plus
+/// change. Outputs are sorted, so their order says nothing about who registered
+/// them: an observer sees several equal outputs and can't tell which input paid
+/// for which. Change outputs can still be linked to their inputs by amount, so
+/// only the mixed outputs are private.
+///
+/// 1. Participants `register` their inputs and addresses
+/// 2. The coordinator builds the join (`inputs` and `outputs`)
+/// 3. Each participant checks its outputs are there and `sign`s its inputs
+/// 4. The coordinator `finalize`s the transaction, once every input is signed
+///
+/// The coordinator sends the transaction: the inputs are released to it and it
+/// pays the outputs and the fee, which the participants share, so it neither
+/// gains nor loses coins.
+///
+/// # Fields
+///
+/// * `denomination` - Amount of every mixed output
+/// * `fee_per_participant` - Share of the transaction fee each participant pays
+/// * `registrations` - Participants, in registration order
+/// * `signatures` - One signature per input of `inputs()` (empty until signed)
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::entity::entity::Entity;
+/// use blockc::transactions::transactions::{Output, Transaction, TransactionExt};
+/// use blockc::utxo::coinjoin::CoinJoin;
+///
+/// let mut chain = Blockchain::new();
+/// chain.add_block(Vec::new(), "Alice").unwrap();
+/// chain.add_block(Vec::new(), "Bob").unwrap();
+/// let fee = chain.get_base_fee() * 2.0;
+/// let mut alice = Entity::new("Alice".to_string(), 45.0, Vec::new(), "a".to_string(), "a".to_string());
+/// let mut bob = Entity::new("Bob".to_string(), 45.0, Vec::new(), "b".to_string(), "b".to_string());
+/// let mut coordinator = Entity::new("Mixer".to_string(), 0.0, Vec::new(), "m".to_string(), "m".to_string());
+///
+/// // Alice and Bob hold UTXOs of different sizes
+/// let a = Transaction::create_utxo_transaction(&mut alice, Vec::new(), vec![Output::new("Alice".to_string(), 12.0)], fee).unwrap();
+/// let b = Transaction::create_utxo_transaction(&mut bob, Vec::new(), vec![Output::new("Bob".to_string(), 8.0)], fee).unwrap();
+/// chain.add_block(vec![a, b], "Miner").unwrap();
+///
+/// // Both mix 5 coins into fresh addresses, paying 0.5 of fee each
+/// let mut join = CoinJoin::new(5.0, 0.5);
+/// join.register(chain.state().get_utxos("Alice"), "A1", "Alice").unwrap();
+/// join.register(chain.state().get_utxos("Bob"), "B1", "Bob").unwrap();
+/// join.sign(&alice).unwrap();
+/// assert!(join.finalize(&mut coordinator).is_err()); // Bob hasn't signed yet
+/// join.sign(&bob).unwrap();
+///
+/// let transaction = join.finalize(&mut coordinator).unwrap();
+/// chain.add_block(vec![transaction], "Miner").unwrap();
+/// assert_eq!(chain.state().get_utxo_balance("A1"), 5.0);
+/// assert_eq!(chain.state().get_utxo_balance("B1"), 5.0);
+/// assert_eq!(chain.state().get_utxo_balance("Alice"), 6.5);
+/// assert_eq!(chain.state().get_utxo_balance("Bob"), 2.5);
+/// assert_eq!(chain.get_balance("Mixer"), 0.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CoinJoin {
+    pub denomination: f64,
+    pub fee_per_participant: f64,
+    registrations: Vec<Registration>,
+    signatures: Vec<String>,
+}
+
+impl CoinJoin {
+    /// Opens a CoinJoin mixing outputs of `denomination` coins.
+    pub fn new(denomination: f64, fee_per_participant: f64) -> Self {
+        CoinJoin {
+            denomination: denomination,
+            fee_per_participant: fee_per_participant,
+            registrations: Vec::new(),
+            signatures: Vec::new(),
+        }
+    }
+
+    /// Returns the registered participants, in registration order.
+    pub fn registrations(&self) -> &[Registration] {
+        &self.registrations
+    }
+
+    /// Registers a participant's inputs and addresses.
+    ///
+    /// Registering changes the join, so every signature collected so far is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - Outputs to spend, all owned by the same address
+    /// * `mix_address` - Address receiving the mixed output
+    /// * `change_address` - Address receiving the change
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the participant was added
+    /// * `Err(String)` - If there are no inputs, they have different owners, one is
+    ///   already registered, or they don't cover the denomination and fee share
+    pub fn register(&mut self, inputs: Vec<(OutPoint, Output)>, mix_address: &str, change_address: &str) -> Result<(), String> {
+        let owner = inputs.first().map(|(_, output)| output.address.clone()).ok_or("A participant needs inputs".to_string())?;
+        if inputs.iter().any(|(_, output)| output.address != owner) {
+            return Err("All inputs of a participant must have the same owner".to_string());
+        }
+        let registered: BTreeSet<&OutPoint> = self.registrations.iter()
+            .flat_map(|registration| registration.inputs.iter().map(|(outpoint, _)| outpoint))
+            .collect();
+        for (i, (outpoint, _)) in inputs.iter().enumerate() {
+            if registered.contains(outpoint) || inputs[..i].iter().any(|(other, _)| other == outpoint) {
+                return Err(format!("Output {}:{} is already registered", outpoint.transaction_id, outpoint.index));
+            }
+        }
+        let registration = Registration {
+            owner: owner,
+            inputs: inputs,
+            mix_address: mix_address.to_string(),
+            change_address: change_address.to_string(),
+        };
+        let needed = self.denomination + self.fee_per_participant;
+        if registration.input_value() < needed {
+            return Err(format!("Inputs hold {}, a participant needs {}", registration.input_value(), needed));
+        }
+        self.registrations.push(registration);
+        self.signatures.clear();
+        Ok(())
+    }
+
+    /// Returns the inputs of the join, sorted by outpoint.
+    pub fn inputs(&self) -> Vec<OutPoint> {
+        let mut inputs: Vec<OutPoint> = self.registrations.iter()
+            .flat_map(|registration| registration.inputs.iter().map(|(outpoint, _)| outpoint.clone()))
+            .collect();
+        inputs.sort();
+        inputs
+    }
+
+    /// Returns the outputs of the join: one `denomination` output per participant
+    /// and the change (if any), sorted by amount then address.
+    pub fn outputs(&self) -> Vec<Output> {
+        let mut outputs = Vec::new();
+        for registration in &self.registrations {
+            outputs.push(Output::new(registration.mix_address.clone(), self.denomination));
+            let change = registration.input_value() - self.denomination - self.fee_per_participant;
+            if change > 0.0 {
+                outputs.push(Output::new(registration.change_address.clone(), change));
+            }
+        }
+        outputs.sort_by(|a, b| a.amount.total_cmp(&b.amount).then_with(|| a.address.cmp(&b.address)));
+        outputs
+    }
+
+    /// Returns `true` once every input of the join is signed.
+    pub fn is_fully_signed(&self) -> bool {
+        let inputs = self.inputs();
+        !inputs.is_empty() && self.signatures.len() == inputs.len() && self.signatures.iter().all(|signature| !signature.is_empty())
+    }
+
+    /// Signs every input `participant` owns.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the inputs were signed
+    /// * `Err(String)` - If `participant` has no registered inputs
+    pub fn sign(&mut self, participant: &Entity) -> Result<(), String> {
+        let registration = self.registrations.iter()
+            .find(|registration| registration.owner == participant.address)
+            .ok_or(format!("{} is not a participant", participant.address))?;
+        let owned: Vec<OutPoint> = registration.inputs.iter().map(|(outpoint, _)| outpoint.clone()).collect();
+        let inputs = self.inputs();
+        let signature = participant.sign(&join_message(&inputs, &self.outputs()));
+        self.signatures.resize(inputs.len(), String::new());
+        for (i, input) in inputs.iter().enumerate() {
+            if owned.contains(input) {
+                self.signatures[i] = signature.clone();
+            }
+        }
+        Ok(())
+    }
+
+    /// Builds the CoinJoin transaction, sent by `coordinator`.
+    ///
+    /// The fee is what the inputs hold beyond the outputs (every participant's share).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed transaction
+    /// * `Err(String)` - If there are fewer than `MIN_COINJOIN_PARTICIPANTS`
+    ///   participants or an input is not signed yet
+    pub fn finalize(&self, coordinator: &mut Entity) -> Result<Transaction, String> {
+        if self.registrations.len() < MIN_COINJOIN_PARTICIPANTS {
+            return Err(format!("A CoinJoin needs at least {} participants", MIN_COINJOIN_PARTICIPANTS));
+        }
+        if !self.is_fully_signed() {
+            return Err("Every input must be signed by its owner".to_string());
+        }
+        let inputs = self.inputs().into_iter().zip(self.signatures.iter())
+            .map(|(outpoint, signature)| JoinInput {
+                outpoint: outpoint,
+                signature: signature.clone(),
+            })
+            .collect();
+        let input_value: f64 = self.registrations.iter().map(|registration| registration.input_value()).sum();
+        Transaction::create_coinjoin(coordinator, inputs, self.outputs(), input_value)
+    }
+}
@@ -0,0 +1,2 @@
+pub mod coinjoin;
+pub mod utxo;
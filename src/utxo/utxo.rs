@@ -0,0 +1,99 @@
+use std::collections::{HashMap, HashSet};
+use crate::transactions::transactions::Output;
+
+// Outpoints are what UTXO transactions spend; they live in `blockc_core`.
+pub use blockc_core::payload::OutPoint;
+
+/// Set of unspent transaction outputs (UTXOs) kept by the chain.
+///
+/// Each UTXO can be spent exactly once. Spent outpoints are remembered, so
+/// spending one twice is reported as a double spend rather than as an unknown
+/// output.
+///
+/// # Fields
+///
+/// * `unspent` - Outputs that can still be spent
+/// * `spent` - Outpoints already consumed
+///
+/// # Example
+///
+/// ```
+/// use blockc::transactions::transactions::Output;
+/// use blockc::utxo::utxo::{OutPoint, UtxoSet};
+///
+/// let mut set = UtxoSet::new();
+/// let coin = OutPoint::new("tx1".to_string(), 0);
+/// set.add(coin.clone(), Output::new("Alice".to_string(), 5.0));
+/// assert_eq!(set.balance_of("Alice"), 5.0);
+///
+/// set.spend(&coin).unwrap();
+/// assert!(set.spend(&coin).unwrap_err().contains("double spend"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UtxoSet {
+    unspent: HashMap<OutPoint, Output>,
+    spent: HashSet<OutPoint>,
+}
+
+impl UtxoSet {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        UtxoSet {
+            unspent: HashMap::new(),
+            spent: HashSet::new(),
+        }
+    }
+
+    /// Returns the unspent output at `outpoint`.
+    pub fn get(&self, outpoint: &OutPoint) -> Option<&Output> {
+        self.unspent.get(outpoint)
+    }
+
+    /// Returns `true` if `outpoint` was already spent.
+    pub fn is_spent(&self, outpoint: &OutPoint) -> bool {
+        self.spent.contains(outpoint)
+    }
+
+    /// Returns the unspent outputs owned by `address`, sorted by outpoint.
+    pub fn owned_by(&self, address: &str) -> Vec<(OutPoint, Output)> {
+        let mut owned: Vec<(OutPoint, Output)> = self.unspent.iter()
+            .filter(|(_, output)| output.address == address)
+            .map(|(outpoint, output)| (outpoint.clone(), output.clone()))
+            .collect();
+        owned.sort_by(|a, b| a.0.cmp(&b.0));
+        owned
+    }
+
+    /// Sum of the unspent outputs owned by `address`.
+    pub fn balance_of(&self, address: &str) -> f64 {
+        self.unspent.values()
+            .filter(|output| output.address == address)
+            .map(|output| output.amount)
+            .sum()
+    }
+
+    /// Checks that `outpoint` can be spent.
+    pub fn check_unspent(&self, outpoint: &OutPoint) -> Result<&Output, String> {
+        if self.is_spent(outpoint) {
+            return Err(format!("Output {}:{} was already spent (double spend)", outpoint.transaction_id, outpoint.index));
+        }
+        self.get(outpoint).ok_or(format!("Unknown output {}:{}", outpoint.transaction_id, outpoint.index))
+    }
+
+    /// Adds a new unspent output.
+    pub fn add(&mut self, outpoint: OutPoint, output: Output) {
+        self.unspent.insert(outpoint, output);
+    }
+
+    /// Spends `outpoint`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Output)` - The output that was spent
+    /// * `Err(String)` - If it is unknown or already spent
+    pub fn spend(&mut self, outpoint: &OutPoint) -> Result<Output, String> {
+        self.check_unspent(outpoint)?;
+        self.spent.insert(outpoint.clone());
+        self.unspent.remove(outpoint).ok_or(format!("Unknown output {}:{}", outpoint.transaction_id, outpoint.index))
+    }
+}
@@ -0,0 +1,41 @@
+use std::collections::BTreeMap;
+use crate::vm::instruction::Instruction;
+
+/// A contract account held by the chain.
+///
+/// The contract's coins are a normal balance under `address`; the code and the
+/// storage live here.
+///
+/// # Fields
+///
+/// * `address` - Contract address (the id of the deploy transaction)
+/// * `creator` - Address that deployed it
+/// * `code` - Program run on every call
+/// * `storage` - Persistent key-value storage
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contract {
+    pub address: String,
+    pub creator: String,
+    pub code: Vec<Instruction>,
+    pub storage: BTreeMap<i64, i64>,
+}
+
+/// Outcome of a call transaction, kept by the chain.
+///
+/// A failed call is still included in its block: the sender pays the fee, but
+/// the coins sent are returned and the contract is left unchanged.
+///
+/// # Fields
+///
+/// * `success` - Whether the code ran to completion
+/// * `return_value` - Top of the stack at the end (successful calls only)
+/// * `error` - Why the call failed
+/// * `gas_used` - Gas charged for the call (intrinsic gas plus execution; the whole
+///   gas limit if the call failed)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallReceipt {
+    pub success: bool,
+    pub return_value: Option<i64>,
+    pub error: Option<String>,
+    pub gas_used: u64,
+}
@@ -0,0 +1,3 @@
+// Contract code is part of the transactions deploying it, so instructions live
+// in `blockc_core` with the transaction types.
+pub use blockc_core::instruction::{encode, parse, validate, Instruction, MAX_CODE_LENGTH};
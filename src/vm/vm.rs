@@ -0,0 +1,199 @@
+use std::collections::BTreeMap;
+use crate::vm::instruction::Instruction;
+
+pub use blockc_core::instruction::MAX_INPUTS;
+
+/// Maximum number of values on the stack.
+pub const MAX_STACK: usize = 1024;
+
+/// What a contract call knows about its caller.
+///
+/// # Fields
+///
+/// * `caller` - Address that sent the call
+/// * `value` - Coins sent with the call (already added to `balance`)
+/// * `balance` - Contract balance at the start of the call
+/// * `input` - Arguments, pushed on the stack (in order) before execution starts
+#[derive(Debug, Clone)]
+pub struct CallContext {
+    pub caller: String,
+    pub value: f64,
+    pub balance: f64,
+    pub input: Vec<i64>,
+}
+
+/// Result of a successful execution.
+///
+/// Nothing is written while the code runs: the caller applies `storage` and
+/// `transfers` only if the execution succeeded.
+///
+/// # Fields
+///
+/// * `storage` - Contract storage after the call
+/// * `transfers` - Coins sent by the contract (recipient, amount), in order
+/// * `return_value` - Top of the stack when execution stopped
+/// * `steps` - Instructions executed
+/// * `gas_used` - Gas charged for the instructions executed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutionResult {
+    pub storage: BTreeMap<i64, i64>,
+    pub transfers: Vec<(String, f64)>,
+    pub return_value: Option<i64>,
+    pub steps: u64,
+    pub gas_used: u64,
+}
+
+/// Runs contract code.
+///
+/// Execution is deterministic: it only depends on `code`, `context` and
+/// `storage`. It stops at `Stop` or at the end of the code, and fails on a bad
+/// instruction (stack underflow, overflow, division by zero, jump out of the code,
+/// transfer above the balance) or when the next instruction would use more than
+/// `gas_limit` (see `Instruction::gas_cost`). A failed execution uses all its gas.
+///
+/// # Arguments
+///
+/// * `code` - The contract's program
+/// * `context` - Caller, coins sent, contract balance and input
+/// * `storage` - Contract storage before the call
+/// * `gas_limit` - Gas available to the code
+///
+/// # Returns
+///
+/// * `Ok(ExecutionResult)` - New storage, transfers and return value
+/// * `Err(String)` - Why execution failed (nothing must be applied)
+///
+/// # Example
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use blockc::vm::instruction::parse;
+/// use blockc::vm::vm::{execute, CallContext};
+///
+/// // counter[0] += input
+/// let code = parse("PUSH 0; LOAD; ADD; PUSH 0; SWAP; STORE; PUSH 0; LOAD").unwrap();
+/// let context = CallContext { caller: "Alice".to_string(), value: 0.0, balance: 0.0, input: vec![5] };
+///
+/// let first = execute(&code, &context, &BTreeMap::new(), 100_000).unwrap();
+/// let second = execute(&code, &context, &first.storage, 100_000).unwrap();
+/// assert_eq!(second.return_value, Some(10));
+///
+/// // An endless loop runs out of gas
+/// let endless = parse("JUMP 0").unwrap();
+/// assert!(execute(&endless, &context, &BTreeMap::new(), 1_000).unwrap_err().contains("gas"));
+/// ```
+pub fn execute(code: &[Instruction], context: &CallContext, storage: &BTreeMap<i64, i64>, gas_limit: u64) -> Result<ExecutionResult, String> {
+    let mut stack: Vec<i64> = context.input.clone();
+    let mut storage = storage.clone();
+    let mut transfers: Vec<(String, f64)> = Vec::new();
+    let mut balance = context.balance;
+    let mut pc = 0;
+    let mut steps = 0;
+    let mut gas_used: u64 = 0;
+
+    while pc < code.len() {
+        gas_used += code[pc].gas_cost();
+        if gas_used > gas_limit {
+            return Err(format!("Out of gas (limit {})", gas_limit));
+        }
+        steps += 1;
+        if stack.len() > MAX_STACK {
+            return Err(format!("Stack exceeded {} values", MAX_STACK));
+        }
+
+        let mut next = pc + 1;
+        match &code[pc] {
+            Instruction::Push(value) => stack.push(*value),
+            Instruction::Pop => {
+                pop(&mut stack)?;
+            }
+            Instruction::Dup => {
+                let top = pop(&mut stack)?;
+                stack.push(top);
+                stack.push(top);
+            }
+            Instruction::Swap => {
+                let b = pop(&mut stack)?;
+                let a = pop(&mut stack)?;
+                stack.push(b);
+                stack.push(a);
+            }
+            Instruction::Add | Instruction::Sub | Instruction::Mul | Instruction::Div | Instruction::Mod => {
+                let b = pop(&mut stack)?;
+                let a = pop(&mut stack)?;
+                let result = match &code[pc] {
+                    Instruction::Add => a.checked_add(b),
+                    Instruction::Sub => a.checked_sub(b),
+                    Instruction::Mul => a.checked_mul(b),
+                    Instruction::Div => a.checked_div(b),
+                    _ => a.checked_rem(b),
+                };
+                stack.push(result.ok_or(format!("Arithmetic error at instruction {}", pc))?);
+            }
+            Instruction::Eq | Instruction::Lt | Instruction::Gt => {
+                let b = pop(&mut stack)?;
+                let a = pop(&mut stack)?;
+                let result = match &code[pc] {
+                    Instruction::Eq => a == b,
+                    Instruction::Lt => a < b,
+                    _ => a > b,
+                };
+                stack.push(result as i64);
+            }
+            Instruction::Not => {
+                let a = pop(&mut stack)?;
+                stack.push((a == 0) as i64);
+            }
+            Instruction::Jump(target) => next = jump_target(code, *target)?,
+            Instruction::JumpIf(target) => {
+                if pop(&mut stack)? != 0 {
+                    next = jump_target(code, *target)?;
+                }
+            }
+            Instruction::Load => {
+                let key = pop(&mut stack)?;
+                stack.push(*storage.get(&key).unwrap_or(&0));
+            }
+            Instruction::Store => {
+                let value = pop(&mut stack)?;
+                let key = pop(&mut stack)?;
+                storage.insert(key, value);
+            }
+            Instruction::CallValue => stack.push(context.value.floor() as i64),
+            Instruction::Balance => stack.push(balance.floor() as i64),
+            Instruction::Transfer(_) | Instruction::TransferToCaller => {
+                let amount = pop(&mut stack)?;
+                if amount < 0 || amount as f64 > balance {
+                    return Err(format!("Contract cannot transfer {} with a balance of {}", amount, balance));
+                }
+                let recipient = match &code[pc] {
+                    Instruction::Transfer(address) => address.clone(),
+                    _ => context.caller.clone(),
+                };
+                balance -= amount as f64;
+                transfers.push((recipient, amount as f64));
+            }
+            Instruction::Stop => break,
+        }
+        pc = next;
+    }
+
+    Ok(ExecutionResult {
+        storage: storage,
+        transfers: transfers,
+        return_value: stack.last().copied(),
+        steps: steps,
+        gas_used: gas_used,
+    })
+}
+
+fn pop(stack: &mut Vec<i64>) -> Result<i64, String> {
+    stack.pop().ok_or("Stack underflow".to_string())
+}
+
+fn jump_target(code: &[Instruction], target: usize) -> Result<usize, String> {
+    if target >= code.len() {
+        return Err(format!("Jump to {} is outside the code", target));
+    }
+    Ok(target)
+}
@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+#[cfg(feature = "fs")]
+use std::fs;
+#[cfg(feature = "fs")]
+use std::path::Path;
+#[cfg(feature = "fs")]
+use crate::codec::json;
+use crate::codec::json::{JsonCodec, JsonValue};
+use crate::wallet::wallet::Address;
+
+/// File holding the contacts, in a store's directory.
+pub const CONTACTS_FILE: &str = "contacts.json";
+
+/// Maximum length of a contact label, in bytes.
+pub const MAX_LABEL_LENGTH: usize = 32;
+
+/// Checks that `label` is a valid contact label: 1 to `MAX_LABEL_LENGTH`
+/// lowercase letters, digits, `-` or `_` (so it can't be mistaken for a
+/// registered name like "bob.bc").
+pub fn check_label(label: &str) -> Result<(), String> {
+    if label.is_empty() || label.len() > MAX_LABEL_LENGTH {
+        return Err(format!("A label is 1 to {} characters", MAX_LABEL_LENGTH));
+    }
+    if !label.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_') {
+        return Err(format!("Label {} may only use lowercase letters, digits, - and _", label));
+    }
+    Ok(())
+}
+
+/// Labels for the addresses a user pays, so they can write `bob` instead of
+/// pasting Bob's address. The CLI saves them in `CONTACTS_FILE`.
+///
+/// Labels are local to the user: they are never put on the chain. When a
+/// label is also an address, the label wins.
+///
+/// # Example
+///
+/// ```
+/// use blockc::wallet::contacts::AddressBook;
+///
+/// let mut contacts = AddressBook::new();
+/// contacts.add("bob", "4f1c9a0b7e").unwrap();
+///
+/// assert_eq!(contacts.resolve("bob"), "4f1c9a0b7e");
+/// assert_eq!(contacts.resolve("carol"), "carol");
+/// assert_eq!(contacts.label("4f1c9a0b7e"), Some("bob"));
+/// assert_eq!(contacts.display("4f1c9a0b7e"), "bob (4f1c9a0b7e)");
+///
+/// assert!(contacts.add("Bob Smith", "x").is_err());
+/// assert!(contacts.add("bob.bc", "x").is_err());
+///
+/// # #[cfg(feature = "fs")] {
+/// let path = std::env::temp_dir().join(format!("blockc-contacts-doc-{}.json", std::process::id()));
+/// contacts.save(&path).unwrap();
+/// assert_eq!(AddressBook::load(&path).unwrap(), contacts);
+/// # std::fs::remove_file(&path).unwrap();
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AddressBook {
+    contacts: BTreeMap<String, Address>,
+}
+
+impl AddressBook {
+    /// Creates an empty address book.
+    pub fn new() -> Self {
+        AddressBook::default()
+    }
+
+    /// Reads the contacts saved at `path` (none if the file doesn't exist yet).
+    #[cfg(feature = "fs")]
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(AddressBook::new());
+        }
+        let text = fs::read_to_string(path).map_err(|error| format!("Cannot read {}: {}", path.display(), error))?;
+        json::parse(&text).and_then(|value| AddressBook::from_json(&value)).map_err(|error| format!("{}: {}", path.display(), error))
+    }
+
+    /// Writes the contacts to `path`.
+    #[cfg(feature = "fs")]
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_json().to_string()).map_err(|error| format!("Cannot write {}: {}", path.display(), error))
+    }
+
+    /// Labels `address`, replacing what the label pointed to before.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Option<Address>)` - The address the label replaced, if any
+    /// * `Err(String)` - If the label is invalid (see `check_label`) or the address empty
+    pub fn add(&mut self, label: &str, address: &str) -> Result<Option<Address>, String> {
+        check_label(label)?;
+        if address.is_empty() {
+            return Err("Address is empty".to_string());
+        }
+        Ok(self.contacts.insert(label.to_string(), address.to_string()))
+    }
+
+    /// Removes a label and returns the address it pointed to.
+    pub fn remove(&mut self, label: &str) -> Result<Address, String> {
+        self.contacts.remove(label).ok_or(format!("No contact {}", label))
+    }
+
+    /// Returns the address of a label.
+    pub fn address(&self, label: &str) -> Option<&str> {
+        self.contacts.get(label).map(|address| address.as_str())
+    }
+
+    /// Returns the first label (alphabetically) of an address.
+    pub fn label(&self, address: &str) -> Option<&str> {
+        self.contacts.iter().find(|(_, contact)| *contact == address).map(|(label, _)| label.as_str())
+    }
+
+    /// Returns the labels and their addresses, sorted by label.
+    pub fn contacts(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.contacts.iter().map(|(label, address)| (label.as_str(), address.as_str()))
+    }
+
+    /// Returns the address of `recipient` if it is a label, `recipient` otherwise.
+    pub fn resolve<'a>(&'a self, recipient: &'a str) -> &'a str {
+        self.address(recipient).unwrap_or(recipient)
+    }
+
+    /// Renders an address for humans: "label (address)" if it has a label,
+    /// the address otherwise.
+    pub fn display(&self, address: &str) -> String {
+        match self.label(address) {
+            Some(label) => format!("{} ({})", label, address),
+            None => address.to_string(),
+        }
+    }
+}
+
+/// `{"label": "address", ...}`
+impl JsonCodec for AddressBook {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Object(self.contacts.iter()
+            .map(|(label, address)| (label.clone(), JsonValue::String(address.clone())))
+            .collect())
+    }
+
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let mut contacts = AddressBook::new();
+        for (label, address) in value.as_object()? {
+            contacts.add(label, address.as_str()?)?;
+        }
+        Ok(contacts)
+    }
+}
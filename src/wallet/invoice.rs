@@ -0,0 +1,351 @@
+use std::collections::BTreeMap;
+#[cfg(feature = "fs")]
+use std::fs;
+#[cfg(feature = "fs")]
+use std::path::Path;
+use crate::blockchain::blockchain::Blockchain;
+use crate::codec::json::{self, JsonCodec, JsonValue};
+use crate::helpers::helper_functions::sha256_hex;
+use crate::transactions::transactions::MAX_MEMO_BYTES;
+use crate::wallet::request::PaymentRequest;
+use crate::wallet::wallet::{Address, Amount};
+
+/// File holding the invoices, in a store's directory.
+pub const INVOICES_FILE: &str = "invoices.json";
+
+/// Where an invoice is in its lifecycle.
+///
+/// An invoice starts `Pending` and ends `Paid` or `Expired`; both are final.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InvoiceStatus {
+    Pending,
+    /// Paid by `transaction_id`, confirmed at `height`
+    Paid { transaction_id: String, height: u64 },
+    Expired,
+}
+
+/// A change of status found by `InvoiceBook::update`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InvoiceEvent {
+    /// `transaction_id` paid `amount` for the invoice in the block at `height`
+    Paid { invoice_id: String, transaction_id: String, height: u64, amount: Amount },
+    /// The chain passed the invoice's expiry height without paying it
+    Expired { invoice_id: String },
+}
+
+/// A request for a payment of at least `amount` to `address`, with `memo`,
+/// confirmed by `expiry_height`.
+///
+/// The memo tells payments of different invoices to the same address apart:
+/// only a payment carrying it (e.g. made from `payment_request`) settles the
+/// invoice.
+///
+/// # Fields
+///
+/// * `id` - Identifier of the invoice
+/// * `address` - Address to pay
+/// * `amount` - Amount due
+/// * `memo` - Memo the payment must carry
+/// * `created_height` - Height of the chain when the invoice was made (only later blocks count)
+/// * `expiry_height` - Last height a payment may be confirmed at
+/// * `status` - Pending, paid or expired
+#[derive(Debug, Clone, PartialEq)]
+pub struct Invoice {
+    pub id: String,
+    pub address: Address,
+    pub amount: Amount,
+    pub memo: String,
+    pub created_height: u64,
+    pub expiry_height: u64,
+    pub status: InvoiceStatus,
+}
+
+impl Invoice {
+    /// Returns the payment request to send to the payer (see `PaymentRequest`).
+    pub fn payment_request(&self) -> PaymentRequest {
+        PaymentRequest {
+            address: self.address.clone(),
+            amount: Some(self.amount),
+            memo: Some(self.memo.clone()),
+        }
+    }
+}
+
+/// The invoices of a merchant, settled by watching the chain.
+///
+/// `create` makes an invoice; `update` then looks for payments in the
+/// blocks added since the invoice was made and reports invoices paid or
+/// expired as events. The CLI saves the book in `INVOICES_FILE`.
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::entity::entity::Entity;
+/// use blockc::wallet::invoice::{InvoiceBook, InvoiceEvent, InvoiceStatus};
+/// use blockc::wallet::wallet::Wallet;
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// chain.add_block(Vec::new(), "Alice").unwrap();
+/// let fee = chain.get_base_fee() * 2.0;
+///
+/// let mut invoices = InvoiceBook::new();
+/// let coffee = invoices.create(&chain, "Shop", 3.0, 10, Some("Coffee".to_string())).unwrap().id.clone();
+/// let tea = invoices.create(&chain, "Shop", 2.0, 1, None).unwrap().id.clone();
+///
+/// // Alice pays the coffee from its payment request; nobody pays the tea
+/// let mut alice = Wallet::new(Entity::new("Alice".to_string(), 0.0, Vec::new(), "a".to_string(), "a".to_string()));
+/// alice.sync(&chain);
+/// let request = invoices.get(&coffee).unwrap().payment_request();
+/// let payment = alice.pay_request(&chain, &request, None, fee).unwrap();
+/// chain.submit_transaction(payment).unwrap();
+/// chain.add_block_from_mempool("Miner").unwrap();
+///
+/// let events = invoices.update(&chain);
+/// assert!(matches!(&events[..], [InvoiceEvent::Paid { invoice_id, height: 2, .. }] if *invoice_id == coffee));
+/// assert_eq!(invoices.get(&tea).unwrap().status, InvoiceStatus::Pending);
+///
+/// chain.add_block(Vec::new(), "Miner").unwrap();
+/// assert_eq!(invoices.update(&chain), vec![InvoiceEvent::Expired { invoice_id: tea.clone() }]);
+/// assert!(invoices.update(&chain).is_empty());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InvoiceBook {
+    invoices: BTreeMap<String, Invoice>,
+}
+
+impl InvoiceBook {
+    /// Creates an empty invoice book.
+    pub fn new() -> Self {
+        InvoiceBook::default()
+    }
+
+    /// Reads the invoices saved at `path` (none if the file doesn't exist yet).
+    #[cfg(feature = "fs")]
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(InvoiceBook::new());
+        }
+        let text = fs::read_to_string(path).map_err(|error| format!("Cannot read {}: {}", path.display(), error))?;
+        json::parse(&text).and_then(|value| InvoiceBook::from_json(&value)).map_err(|error| format!("{}: {}", path.display(), error))
+    }
+
+    /// Writes the invoices to `path`.
+    #[cfg(feature = "fs")]
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_json().to_string()).map_err(|error| format!("Cannot write {}: {}", path.display(), error))
+    }
+
+    /// Creates a pending invoice for `amount` to `address`, payable in the
+    /// next `expires_in` blocks of `chain`.
+    ///
+    /// Without a memo, the invoice uses "invoice <id>".
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(&Invoice)` - The new invoice
+    /// * `Err(String)` - If the address is empty, the amount is not positive,
+    ///   `expires_in` is 0 or past the last height, or the memo is over
+    ///   `MAX_MEMO_BYTES`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::wallet::invoice::InvoiceBook;
+    ///
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// let mut invoices = InvoiceBook::new();
+    /// assert_eq!(invoices.create(&chain, "Shop", 3.0, 10, None).unwrap().expiry_height, 11);
+    /// assert!(invoices.create(&chain, "Shop", 3.0, u64::MAX, None).is_err());
+    /// ```
+    pub fn create(&mut self, chain: &Blockchain, address: &str, amount: Amount, expires_in: u64, memo: Option<String>) -> Result<&Invoice, String> {
+        if address.is_empty() {
+            return Err("An invoice needs an address".to_string());
+        }
+        if !(amount.is_finite() && amount > 0.0) {
+            return Err(format!("Invalid amount {}", amount));
+        }
+        if expires_in == 0 {
+            return Err("An invoice must stay payable for at least one block".to_string());
+        }
+        let created_height = chain.get_blocks().len() as u64 - 1;
+        let expiry_height = created_height.checked_add(expires_in)
+            .ok_or(format!("An invoice can't stay payable for {} blocks", expires_in))?;
+        let id = (0..u64::MAX)
+            .map(|attempt| sha256_hex(&format!("{}|{}|{}|{}", address, amount, created_height, attempt))[..16].to_string())
+            .find(|id| !self.invoices.contains_key(id))
+            .ok_or("No free invoice id".to_string())?;
+        let memo = memo.unwrap_or(format!("invoice {}", id));
+        if memo.len() > MAX_MEMO_BYTES {
+            return Err(format!("Memo is over {} bytes", MAX_MEMO_BYTES));
+        }
+        let invoice = Invoice {
+            id: id.clone(),
+            address: address.to_string(),
+            amount: amount,
+            memo: memo,
+            created_height: created_height,
+            expiry_height: expiry_height,
+            status: InvoiceStatus::Pending,
+        };
+        Ok(self.invoices.entry(id).or_insert(invoice))
+    }
+
+    /// Returns an invoice by id.
+    pub fn get(&self, id: &str) -> Option<&Invoice> {
+        self.invoices.get(id)
+    }
+
+    /// Returns every invoice, by id.
+    pub fn invoices(&self) -> impl Iterator<Item = &Invoice> {
+        self.invoices.values()
+    }
+
+    /// Removes an invoice (e.g. cancelled by the merchant).
+    pub fn remove(&mut self, id: &str) -> Result<Invoice, String> {
+        self.invoices.remove(id).ok_or(format!("No invoice {}", id))
+    }
+
+    /// Settles the pending invoices against `chain`.
+    ///
+    /// A pending invoice is paid by the first confirmed transaction after its
+    /// creation, up to its expiry height, that pays its address at least its
+    /// amount with its memo (one transaction settles one invoice). It expires
+    /// once the chain is past its expiry height without such a payment.
+    ///
+    /// # Returns
+    ///
+    /// The invoices paid or expired since the last update, in block order
+    /// (expirations last).
+    pub fn update(&mut self, chain: &Blockchain) -> Vec<InvoiceEvent> {
+        let tip = chain.get_blocks().len() as u64 - 1;
+        let mut used: Vec<String> = self.invoices.values()
+            .filter_map(|invoice| match &invoice.status {
+                InvoiceStatus::Paid { transaction_id, .. } => Some(transaction_id.clone()),
+                _ => None,
+            })
+            .collect();
+        let mut pending: Vec<&mut Invoice> = self.invoices.values_mut()
+            .filter(|invoice| invoice.status == InvoiceStatus::Pending)
+            .collect();
+        pending.sort_by_key(|invoice| invoice.created_height);
+        let mut events = Vec::new();
+        let Some(from) = pending.iter().map(|invoice| invoice.created_height + 1).min() else {
+            return events;
+        };
+        for (height, block) in chain.get_blocks().iter().enumerate().skip(from as usize) {
+            let height = height as u64;
+            for transaction in &block.transaction {
+                let id = transaction.id();
+                if used.contains(&id) {
+                    continue;
+                }
+                let invoice = pending.iter_mut().find(|invoice| {
+                    invoice.status == InvoiceStatus::Pending
+                        && invoice.created_height < height
+                        && height <= invoice.expiry_height
+                        && transaction.memo.as_deref() == Some(invoice.memo.as_str())
+                        && transaction.amount_paid_to(&invoice.address) >= invoice.amount
+                });
+                if let Some(invoice) = invoice {
+                    invoice.status = InvoiceStatus::Paid {
+                        transaction_id: id.clone(),
+                        height: height,
+                    };
+                    events.push(InvoiceEvent::Paid {
+                        invoice_id: invoice.id.clone(),
+                        transaction_id: id.clone(),
+                        height: height,
+                        amount: transaction.amount_paid_to(&invoice.address),
+                    });
+                    used.push(id);
+                }
+            }
+        }
+        for invoice in pending {
+            if invoice.status == InvoiceStatus::Pending && tip > invoice.expiry_height {
+                invoice.status = InvoiceStatus::Expired;
+                events.push(InvoiceEvent::Expired { invoice_id: invoice.id.clone() });
+            }
+        }
+        events
+    }
+}
+
+/// `{"status": "pending" | "paid" | "expired", "transaction_id": ..., "height": n}`
+/// (`transaction_id` and `height` only when paid)
+impl JsonCodec for InvoiceStatus {
+    fn to_json(&self) -> JsonValue {
+        match self {
+            InvoiceStatus::Pending => JsonValue::Object(vec![("status".to_string(), "pending".to_string().to_json())]),
+            InvoiceStatus::Paid { transaction_id, height } => JsonValue::Object(vec![
+                ("status".to_string(), "paid".to_string().to_json()),
+                ("transaction_id".to_string(), transaction_id.to_json()),
+                ("height".to_string(), height.to_json()),
+            ]),
+            InvoiceStatus::Expired => JsonValue::Object(vec![("status".to_string(), "expired".to_string().to_json())]),
+        }
+    }
+
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let members = value.as_object()?;
+        let status: String = json::field(members, "status")?;
+        match status.as_str() {
+            "pending" => Ok(InvoiceStatus::Pending),
+            "paid" => Ok(InvoiceStatus::Paid {
+                transaction_id: json::field(members, "transaction_id")?,
+                height: json::field(members, "height")?,
+            }),
+            "expired" => Ok(InvoiceStatus::Expired),
+            _ => Err(format!("Unknown invoice status {}", status)),
+        }
+    }
+}
+
+/// `{"id": ..., "address": ..., "amount": x, "memo": ..., "created_height": n,
+/// "expiry_height": n, "status": {...}}`
+impl JsonCodec for Invoice {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Object(vec![
+            ("id".to_string(), self.id.to_json()),
+            ("address".to_string(), self.address.to_json()),
+            ("amount".to_string(), self.amount.to_json()),
+            ("memo".to_string(), self.memo.to_json()),
+            ("created_height".to_string(), self.created_height.to_json()),
+            ("expiry_height".to_string(), self.expiry_height.to_json()),
+            ("status".to_string(), self.status.to_json()),
+        ])
+    }
+
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let members = value.as_object()?;
+        Ok(Invoice {
+            id: json::field(members, "id")?,
+            address: json::field(members, "address")?,
+            amount: json::field(members, "amount")?,
+            memo: json::field(members, "memo")?,
+            created_height: json::field(members, "created_height")?,
+            expiry_height: json::field(members, "expiry_height")?,
+            status: json::field(members, "status")?,
+        })
+    }
+}
+
+/// `[invoice, ...]`
+impl JsonCodec for InvoiceBook {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Array(self.invoices.values().map(JsonCodec::to_json).collect())
+    }
+
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let mut book = InvoiceBook::new();
+        for invoice in Vec::<Invoice>::from_json(value)? {
+            if book.invoices.insert(invoice.id.clone(), invoice).is_some() {
+                return Err("An invoice id is listed twice".to_string());
+            }
+        }
+        Ok(book)
+    }
+}
@@ -0,0 +1,9 @@
+pub mod wallet;
+pub mod contacts;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod vanity;
+pub mod signer;
+pub mod psbt;
+pub mod offline;
+pub mod request;
+pub mod invoice;
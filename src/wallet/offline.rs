@@ -0,0 +1,307 @@
+use blockc_core::sign;
+use crate::blockchain::blockchain::Blockchain;
+use crate::codec::json::{self, JsonCodec, JsonValue};
+use crate::helpers::helper_functions::sha256_hex;
+use crate::transactions::transactions::Transaction;
+use crate::wallet::signer::{confirmation_text, TransactionSigner};
+use crate::wallet::wallet::{Address, Amount};
+
+/// Value of the `format` member of an encoded signing request.
+pub const SIGNING_REQUEST_FORMAT: &str = "blockc-signing-request";
+
+/// Value of the `format` member of an encoded offline signature.
+pub const OFFLINE_SIGNATURE_FORMAT: &str = "blockc-offline-signature";
+
+/// Largest encoded signing request or offline signature `from_json` accepts.
+pub const MAX_OFFLINE_BYTES: usize = 64 * 1024;
+
+/// An unsigned transaction exported by an online node for an air-gapped
+/// wallet to sign, with what the offline machine can't look up itself.
+///
+/// The online node builds it (`Wallet::build_signing_request`) and writes it
+/// to a file; the offline wallet reads it, shows `summary` and signs it
+/// (`sign`), giving back an `OfflineSignature`; the online node `import`s that
+/// signature into the request it kept and submits the transaction.
+///
+/// Signatures of this chain can only be checked with the private key, so the
+/// online node can't verify one by itself. Instead the offline signature
+/// carries the hash of the payload it signed and the id of the signed
+/// transaction, and `import` refuses it unless both match the request's own
+/// payload: a signature made for another transaction (or another version of
+/// this one) is caught before anything is broadcast.
+///
+/// # Fields
+///
+/// * `transaction` - The unsigned transaction
+/// * `genesis_hash` - Hash of the genesis block of the chain it was built for
+/// * `height` - Height of the chain's last block when it was built
+/// * `spendable` - What the sender could spend then (pending transactions deducted)
+///
+/// # Example
+///
+/// ```
+/// use blockc::blockchain::blockchain::Blockchain;
+/// use blockc::entity::entity::Entity;
+/// use blockc::wallet::offline::{OfflineSignature, SigningRequest};
+/// use blockc::wallet::wallet::Wallet;
+///
+/// let mut chain = Blockchain::with_settings(1, 50.0);
+/// chain.add_block(Vec::new(), "ColdVault").unwrap();
+/// let fee = chain.get_base_fee() * 2.0;
+///
+/// // Online: build the request and export it
+/// let mut online = Wallet::watching(Vec::new());
+/// online.watch_address("ColdVault").unwrap();
+/// let request = online.build_signing_request(&chain, "ColdVault", "Bob", 20.0, fee).unwrap();
+/// let exported = request.to_json();
+///
+/// // Offline: check what is being signed, sign it and export the signature
+/// let mut cold = Entity::new("ColdVault".to_string(), 0.0, Vec::new(), "c".to_string(), "cold-key".to_string());
+/// let received = SigningRequest::from_json(&exported).unwrap();
+/// assert!(received.summary().contains("ColdVault -> Bob 20"));
+/// let signature = received.sign(&mut cold).unwrap().to_json();
+///
+/// // Online: import the signature and broadcast
+/// let transaction = request.import(&chain, &OfflineSignature::from_json(&signature).unwrap()).unwrap();
+/// chain.submit_transaction(transaction).unwrap();
+/// chain.add_block_from_mempool("Miner").unwrap();
+/// assert_eq!(chain.get_balance("Bob"), 20.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SigningRequest {
+    pub transaction: Transaction,
+    pub genesis_hash: String,
+    pub height: u64,
+    pub spendable: Amount,
+}
+
+/// A signature made by an offline wallet for a `SigningRequest`.
+///
+/// # Fields
+///
+/// * `signer` - Address whose key signed
+/// * `payload_hash` - `SigningRequest::payload_hash` of the request signed
+/// * `transaction_id` - Id of the transaction once the signature is attached
+/// * `signature` - The signature
+#[derive(Debug, Clone, PartialEq)]
+pub struct OfflineSignature {
+    pub signer: Address,
+    pub payload_hash: String,
+    pub transaction_id: String,
+    pub signature: String,
+}
+
+impl SigningRequest {
+    /// Wraps an unsigned transaction built against `chain`, recording the
+    /// chain's genesis hash and height and what `spendable` the sender has.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SigningRequest)` - The request to export
+    /// * `Err(String)` - If the transaction is signed
+    pub fn new(chain: &Blockchain, transaction: Transaction, spendable: Amount) -> Result<Self, String> {
+        if transaction.is_signed() {
+            return Err("The transaction must be unsigned".to_string());
+        }
+        Ok(SigningRequest {
+            transaction: transaction,
+            genesis_hash: chain.get_blocks()[0].block_hash.clone(),
+            height: chain.get_blocks().len() as u64 - 1,
+            spendable: spendable,
+        })
+    }
+
+    /// Returns the SHA-256 hash of the data the signer signs.
+    ///
+    /// Both machines can show it, for the user to compare before signing.
+    pub fn payload_hash(&self) -> String {
+        sha256_hex(&self.transaction.signing_data())
+    }
+
+    /// Describes the request for the offline wallet to show before signing.
+    pub fn summary(&self) -> String {
+        format!(
+            "{}\nchain {} at height {}, {} spendable\npayload {}",
+            confirmation_text(&self.transaction), self.genesis_hash, self.height, self.spendable, self.payload_hash()
+        )
+    }
+
+    /// Signs the request offline, with the key of its sender.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(OfflineSignature)` - The signature to take back to the online node
+    /// * `Err(String)` - If the transaction costs more than the sender could
+    ///   spend, or the signer refuses it (wrong sender, signed transaction, ...)
+    pub fn sign(&self, signer: &mut dyn TransactionSigner) -> Result<OfflineSignature, String> {
+        if self.transaction.total_cost() > self.spendable {
+            return Err(format!("Transaction costs {} but only {} was spendable", self.transaction.total_cost(), self.spendable));
+        }
+        let signature = signer.sign_transaction(&self.transaction)?;
+        Ok(OfflineSignature {
+            signer: signer.address().to_string(),
+            payload_hash: self.payload_hash(),
+            transaction_id: sign::transaction_id(&self.transaction.signing_data(), &signature),
+            signature: signature,
+        })
+    }
+
+    /// Attaches an offline signature to the transaction, after checking it
+    /// was made for this request on `chain`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed transaction, to submit to the chain
+    /// * `Err(String)` - If `chain` is another network, the signer is not the
+    ///   sender, or the payload hash or transaction id don't match the
+    ///   request's payload
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::wallet::wallet::Wallet;
+    ///
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.add_block(Vec::new(), "ColdVault").unwrap();
+    /// let fee = chain.get_base_fee() * 2.0;
+    /// let mut online = Wallet::watching(Vec::new());
+    /// online.watch_address("ColdVault").unwrap();
+    /// let mut cold = Entity::new("ColdVault".to_string(), 0.0, Vec::new(), "c".to_string(), "cold-key".to_string());
+    ///
+    /// let request = online.build_signing_request(&chain, "ColdVault", "Bob", 20.0, fee).unwrap();
+    /// let other = online.build_signing_request(&chain, "ColdVault", "Mallory", 20.0, fee).unwrap();
+    ///
+    /// // A signature of another payload is refused...
+    /// let signature = other.sign(&mut cold).unwrap();
+    /// assert!(request.import(&chain, &signature).is_err());
+    ///
+    /// // ...even when relabelled as this one
+    /// let mut relabelled = signature.clone();
+    /// relabelled.payload_hash = request.payload_hash();
+    /// assert!(request.import(&chain, &relabelled).is_err());
+    ///
+    /// let signature = request.sign(&mut cold).unwrap();
+    /// assert_eq!(request.import(&chain, &signature).unwrap().receiver_address, "Bob");
+    /// ```
+    pub fn import(&self, chain: &Blockchain, signature: &OfflineSignature) -> Result<Transaction, String> {
+        if chain.get_blocks()[0].block_hash != self.genesis_hash {
+            return Err(format!("The request was built for the chain with genesis {}", self.genesis_hash));
+        }
+        if signature.signer != self.transaction.sender_address {
+            return Err(format!("{} cannot sign a transaction from {}", signature.signer, self.transaction.sender_address));
+        }
+        if signature.payload_hash != self.payload_hash() {
+            return Err(format!("The signature is for payload {}, not {}", signature.payload_hash, self.payload_hash()));
+        }
+        let mut transaction = self.transaction.clone();
+        transaction.attach_signature(signature.signature.clone())?;
+        if transaction.id() != signature.transaction_id {
+            return Err(format!("The signature gives transaction {}, not {}", transaction.id(), signature.transaction_id));
+        }
+        Ok(transaction)
+    }
+
+    /// Encodes the request as JSON, to carry it to the offline wallet.
+    pub fn to_json(&self) -> String {
+        JsonCodec::to_json(self).to_string()
+    }
+
+    /// Decodes a request encoded with `to_json`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SigningRequest)` - The request
+    /// * `Err(String)` - If the text is over `MAX_OFFLINE_BYTES`, is not a
+    ///   signing request or its transaction is already signed
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        if text.len() > MAX_OFFLINE_BYTES {
+            return Err(format!("Signing request is over {} bytes", MAX_OFFLINE_BYTES));
+        }
+        <SigningRequest as JsonCodec>::from_json(&json::parse(text)?)
+    }
+}
+
+impl OfflineSignature {
+    /// Encodes the signature as JSON, to carry it back to the online node.
+    pub fn to_json(&self) -> String {
+        JsonCodec::to_json(self).to_string()
+    }
+
+    /// Decodes a signature encoded with `to_json`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(OfflineSignature)` - The signature
+    /// * `Err(String)` - If the text is over `MAX_OFFLINE_BYTES` or is not an
+    ///   offline signature
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        if text.len() > MAX_OFFLINE_BYTES {
+            return Err(format!("Offline signature is over {} bytes", MAX_OFFLINE_BYTES));
+        }
+        <OfflineSignature as JsonCodec>::from_json(&json::parse(text)?)
+    }
+}
+
+fn check_format(members: &[(String, JsonValue)], expected: &str) -> Result<(), String> {
+    let format: String = json::field(members, "format")?;
+    if format != expected {
+        return Err(format!("Expected a {} (format {})", expected, format));
+    }
+    Ok(())
+}
+
+/// `{"format": "blockc-signing-request", "transaction": {...},
+/// "genesis_hash": ..., "height": n, "spendable": x}`
+impl JsonCodec for SigningRequest {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Object(vec![
+            ("format".to_string(), JsonValue::String(SIGNING_REQUEST_FORMAT.to_string())),
+            ("transaction".to_string(), JsonCodec::to_json(&self.transaction)),
+            ("genesis_hash".to_string(), self.genesis_hash.to_json()),
+            ("height".to_string(), self.height.to_json()),
+            ("spendable".to_string(), self.spendable.to_json()),
+        ])
+    }
+
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let members = value.as_object()?;
+        check_format(members, SIGNING_REQUEST_FORMAT)?;
+        let transaction: Transaction = json::field(members, "transaction")?;
+        if transaction.is_signed() {
+            return Err("The transaction of a signing request must be unsigned".to_string());
+        }
+        Ok(SigningRequest {
+            transaction: transaction,
+            genesis_hash: json::field(members, "genesis_hash")?,
+            height: json::field(members, "height")?,
+            spendable: json::field(members, "spendable")?,
+        })
+    }
+}
+
+/// `{"format": "blockc-offline-signature", "signer": ..., "payload_hash": ...,
+/// "transaction_id": ..., "signature": ...}`
+impl JsonCodec for OfflineSignature {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Object(vec![
+            ("format".to_string(), JsonValue::String(OFFLINE_SIGNATURE_FORMAT.to_string())),
+            ("signer".to_string(), self.signer.to_json()),
+            ("payload_hash".to_string(), self.payload_hash.to_json()),
+            ("transaction_id".to_string(), self.transaction_id.to_json()),
+            ("signature".to_string(), self.signature.to_json()),
+        ])
+    }
+
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let members = value.as_object()?;
+        check_format(members, OFFLINE_SIGNATURE_FORMAT)?;
+        Ok(OfflineSignature {
+            signer: json::field(members, "signer")?,
+            payload_hash: json::field(members, "payload_hash")?,
+            transaction_id: json::field(members, "transaction_id")?,
+            signature: json::field(members, "signature")?,
+        })
+    }
+}
@@ -0,0 +1,307 @@
+use std::collections::{BTreeMap, BTreeSet};
+use crate::codec::json::{self, JsonCodec, JsonValue};
+use crate::entity::entity::Entity;
+use crate::transactions::transactions::Transaction;
+use crate::wallet::signer::TransactionSigner;
+use crate::wallet::wallet::Address;
+
+/// Value of the `format` member of an encoded partially signed transaction.
+pub const PSBT_FORMAT: &str = "blockc-psbt";
+
+/// Version of the encoding `to_json` writes.
+pub const PSBT_VERSION: u64 = 1;
+
+/// Largest encoded partially signed transaction `from_json` accepts.
+pub const MAX_PSBT_BYTES: usize = 1024 * 1024;
+
+/// A transaction on its way between machines, collecting the signatures it
+/// needs before it is broadcast (in the spirit of Bitcoin's PSBT).
+///
+/// It carries the unsigned transaction, who must sign it and the signatures
+/// gathered so far. Each signer signs its copy (`sign`, `sign_with` or
+/// `add_signature` for a signature made elsewhere), copies are combined with
+/// `merge`, and `finalize` returns the transaction to submit once
+/// `threshold` required signers have signed.
+///
+/// The sender is always a required signer and must always sign: its
+/// signature is the one the chain stores. The other signers are co-signers
+/// whose approval the party finalizing requires (e.g. 2 of 3 officers of a
+/// company vault); their signatures stay in the container as the record of it.
+///
+/// # Fields
+///
+/// * `transaction` - The unsigned transaction
+/// * `required_signers` - Addresses that may sign, the sender first
+/// * `threshold` - How many of them must sign (the sender included)
+/// * `signatures` - Signatures collected, by signer
+///
+/// # Example
+///
+/// ```
+/// use blockc::entity::entity::Entity;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+/// use blockc::wallet::psbt::PartiallySignedTransaction;
+///
+/// let vault = Entity::new("Vault".to_string(), 0.0, Vec::new(), "v".to_string(), "vault-key".to_string());
+/// let carol = Entity::new("Carol".to_string(), 0.0, Vec::new(), "c".to_string(), "carol-key".to_string());
+/// let payment = Transaction::create_unsigned_payment("Vault", "Supplier", 30.0, 0.1, 0).unwrap();
+///
+/// // The vault key and one of two officers must sign
+/// let psbt = PartiallySignedTransaction::with_cosigners(payment, vec!["Carol".to_string(), "Dave".to_string()], 2).unwrap();
+///
+/// // Each machine signs its own copy of the file
+/// let mut at_vault = PartiallySignedTransaction::from_json(&psbt.to_json()).unwrap();
+/// at_vault.sign(&vault).unwrap();
+/// assert!(at_vault.finalize().is_err());
+///
+/// let mut at_carol = PartiallySignedTransaction::from_json(&psbt.to_json()).unwrap();
+/// at_carol.sign(&carol).unwrap();
+///
+/// at_vault.merge(&at_carol).unwrap();
+/// assert!(at_vault.is_complete());
+/// let transaction = at_vault.finalize().unwrap();
+/// assert!(transaction.is_signed());
+/// assert_eq!(transaction.signature, vault.sign(&transaction.signing_data()));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartiallySignedTransaction {
+    pub transaction: Transaction,
+    pub required_signers: Vec<Address>,
+    pub threshold: usize,
+    pub signatures: BTreeMap<Address, String>,
+}
+
+impl PartiallySignedTransaction {
+    /// Wraps an unsigned transaction that only its sender has to sign.
+    pub fn new(transaction: Transaction) -> Result<Self, String> {
+        PartiallySignedTransaction::with_cosigners(transaction, Vec::new(), 1)
+    }
+
+    /// Wraps an unsigned transaction that its sender and `threshold - 1` of
+    /// `cosigners` have to sign.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PartiallySignedTransaction)` - The container, without signatures
+    /// * `Err(String)` - If the transaction is signed, a signer is listed twice
+    ///   or the threshold is not between 1 and the number of signers
+    pub fn with_cosigners(transaction: Transaction, cosigners: Vec<Address>, threshold: usize) -> Result<Self, String> {
+        let mut required_signers = vec![transaction.sender_address.clone()];
+        required_signers.extend(cosigners);
+        let psbt = PartiallySignedTransaction {
+            transaction: transaction,
+            required_signers: required_signers,
+            threshold: threshold,
+            signatures: BTreeMap::new(),
+        };
+        psbt.check()?;
+        Ok(psbt)
+    }
+
+    fn check(&self) -> Result<(), String> {
+        if self.transaction.is_signed() {
+            return Err("The transaction must be unsigned".to_string());
+        }
+        if self.required_signers.first() != Some(&self.transaction.sender_address) {
+            return Err("The sender must be the first required signer".to_string());
+        }
+        let unique: BTreeSet<&Address> = self.required_signers.iter().collect();
+        if unique.len() != self.required_signers.len() {
+            return Err("A required signer is listed twice".to_string());
+        }
+        if self.threshold == 0 || self.threshold > self.required_signers.len() {
+            return Err(format!("Threshold must be between 1 and {}", self.required_signers.len()));
+        }
+        for (signer, signature) in &self.signatures {
+            if !self.is_required(signer) {
+                return Err(format!("{} is not a required signer", signer));
+            }
+            check_signature(signature)?;
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if `address` is one of the required signers.
+    pub fn is_required(&self, address: &str) -> bool {
+        self.required_signers.iter().any(|signer| signer == address)
+    }
+
+    /// Signs the transaction as `signer`, a required signer.
+    pub fn sign(&mut self, signer: &Entity) -> Result<(), String> {
+        self.add_signature(&signer.address, signer.sign(&self.transaction.signing_data()))
+    }
+
+    /// Has the sender's `TransactionSigner` (hardware wallet, signing service,
+    /// ...) sign the transaction.
+    pub fn sign_with(&mut self, signer: &mut dyn TransactionSigner) -> Result<(), String> {
+        let signature = signer.sign_transaction(&self.transaction)?;
+        let address = signer.address().to_string();
+        self.add_signature(&address, signature)
+    }
+
+    /// Adds a signature of the transaction's signing data made elsewhere.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The signature is recorded
+    /// * `Err(String)` - If `signer` is not required, already gave a different
+    ///   signature, or the signature is not 64 hexadecimal characters
+    pub fn add_signature(&mut self, signer: &str, signature: String) -> Result<(), String> {
+        if !self.is_required(signer) {
+            return Err(format!("{} is not a required signer", signer));
+        }
+        check_signature(&signature)?;
+        match self.signatures.get(signer) {
+            Some(existing) if *existing != signature => Err(format!("{} already signed with a different signature", signer)),
+            _ => {
+                self.signatures.insert(signer.to_string(), signature);
+                Ok(())
+            }
+        }
+    }
+
+    /// Adds the signatures of another copy of the same transaction.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Every signature of `other` is now in this copy
+    /// * `Err(String)` - If `other` is for another transaction or signers, or
+    ///   gives a different signature for a signer (nothing is merged then)
+    pub fn merge(&mut self, other: &PartiallySignedTransaction) -> Result<(), String> {
+        if other.transaction != self.transaction || other.required_signers != self.required_signers || other.threshold != self.threshold {
+            return Err("Cannot merge copies of different transactions".to_string());
+        }
+        let mut merged = self.signatures.clone();
+        for (signer, signature) in &other.signatures {
+            match merged.get(signer) {
+                Some(existing) if existing != signature => {
+                    return Err(format!("The copies disagree on the signature of {}", signer));
+                }
+                _ => {
+                    merged.insert(signer.clone(), signature.clone());
+                }
+            }
+        }
+        self.signatures = merged;
+        Ok(())
+    }
+
+    /// Returns the required signers that haven't signed yet.
+    pub fn missing_signers(&self) -> Vec<&str> {
+        self.required_signers.iter()
+            .filter(|signer| !self.signatures.contains_key(*signer))
+            .map(|signer| signer.as_str())
+            .collect()
+    }
+
+    /// Returns `true` once the sender and enough co-signers have signed.
+    pub fn is_complete(&self) -> bool {
+        self.signatures.contains_key(&self.transaction.sender_address) && self.signatures.len() >= self.threshold
+    }
+
+    /// Returns the transaction to broadcast, carrying the sender's signature.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed transaction
+    /// * `Err(String)` - If the sender or too few signers have signed
+    pub fn finalize(&self) -> Result<Transaction, String> {
+        let Some(signature) = self.signatures.get(&self.transaction.sender_address) else {
+            return Err(format!("{} (the sender) has not signed", self.transaction.sender_address));
+        };
+        if self.signatures.len() < self.threshold {
+            return Err(format!("{} of {} signatures collected (missing: {})", self.signatures.len(), self.threshold, self.missing_signers().join(", ")));
+        }
+        let mut transaction = self.transaction.clone();
+        transaction.attach_signature(signature.clone())?;
+        Ok(transaction)
+    }
+
+    /// Encodes the container as JSON, to pass it to the next signer.
+    pub fn to_json(&self) -> String {
+        JsonCodec::to_json(self).to_string()
+    }
+
+    /// Decodes a container encoded with `to_json`, checking it is consistent
+    /// (not whether the signatures are right: only their keys can tell).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PartiallySignedTransaction)` - The container
+    /// * `Err(String)` - If the text is over `MAX_PSBT_BYTES`, is not a
+    ///   partially signed transaction of a known version, or is inconsistent
+    ///   (signed transaction, unknown signer, bad threshold, ...)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// use blockc::wallet::psbt::PartiallySignedTransaction;
+    ///
+    /// let payment = Transaction::create_unsigned_payment("Alice", "Bob", 5.0, 0.1, 0).unwrap();
+    /// let psbt = PartiallySignedTransaction::new(payment).unwrap();
+    /// let json = psbt.to_json();
+    /// assert_eq!(PartiallySignedTransaction::from_json(&json).unwrap(), psbt);
+    ///
+    /// assert!(PartiallySignedTransaction::from_json(&json.replace("\"version\":1", "\"version\":9")).is_err());
+    /// assert!(PartiallySignedTransaction::from_json(&json.replace("\"threshold\":1", "\"threshold\":2")).is_err());
+    /// assert!(PartiallySignedTransaction::from_json(&json.replace("\"signatures\":{}", "\"signatures\":{\"Mallory\":\"00\"}")).is_err());
+    /// ```
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        if text.len() > MAX_PSBT_BYTES {
+            return Err(format!("Partially signed transaction is over {} bytes", MAX_PSBT_BYTES));
+        }
+        <PartiallySignedTransaction as JsonCodec>::from_json(&json::parse(text)?)
+    }
+}
+
+fn check_signature(signature: &str) -> Result<(), String> {
+    if signature.len() != 64 || !signature.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("A signature is 64 hexadecimal characters".to_string());
+    }
+    Ok(())
+}
+
+/// `{"format": "blockc-psbt", "version": 1, "transaction": {...},
+/// "required_signers": [...], "threshold": n, "signatures": {"signer": "..."}}`
+impl JsonCodec for PartiallySignedTransaction {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Object(vec![
+            ("format".to_string(), JsonValue::String(PSBT_FORMAT.to_string())),
+            ("version".to_string(), PSBT_VERSION.to_json()),
+            ("transaction".to_string(), JsonCodec::to_json(&self.transaction)),
+            ("required_signers".to_string(), self.required_signers.to_json()),
+            ("threshold".to_string(), self.threshold.to_json()),
+            ("signatures".to_string(), JsonValue::Object(self.signatures.iter()
+                .map(|(signer, signature)| (signer.clone(), signature.to_json()))
+                .collect())),
+        ])
+    }
+
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let members = value.as_object()?;
+        let format: String = json::field(members, "format")?;
+        if format != PSBT_FORMAT {
+            return Err(format!("Not a partially signed transaction (format {})", format));
+        }
+        let version: u64 = json::field(members, "version")?;
+        if version != PSBT_VERSION {
+            return Err(format!("Unsupported partially signed transaction version {}", version));
+        }
+        let (_, signatures) = members.iter().find(|(key, _)| key == "signatures").ok_or("Missing field \"signatures\"")?;
+        let mut collected = BTreeMap::new();
+        for (signer, signature) in signatures.as_object()? {
+            if collected.insert(signer.clone(), signature.as_str()?.to_string()).is_some() {
+                return Err(format!("{} signed twice", signer));
+            }
+        }
+        let psbt = PartiallySignedTransaction {
+            transaction: json::field(members, "transaction")?,
+            required_signers: json::field(members, "required_signers")?,
+            threshold: json::field(members, "threshold")?,
+            signatures: collected,
+        };
+        psbt.check()?;
+        Ok(psbt)
+    }
+}
@@ -0,0 +1,210 @@
+use std::fmt;
+use crate::transactions::transactions::MAX_MEMO_BYTES;
+use crate::wallet::wallet::{Address, Amount};
+
+/// Scheme of payment request URIs (matched without regard to case).
+pub const URI_SCHEME: &str = "blockc";
+
+/// Bytes a QR code of each version (1 to 40) holds in byte mode with error
+/// correction level M (recovers about 15% of damaged modules).
+pub const QR_BYTE_CAPACITY: [usize; 40] = [
+    14, 26, 42, 62, 84, 106, 122, 152, 180, 213,
+    251, 287, 331, 362, 412, 450, 504, 560, 624, 666,
+    711, 779, 857, 911, 997, 1059, 1125, 1190, 1264, 1370,
+    1452, 1538, 1628, 1722, 1809, 1911, 1989, 2099, 2213, 2331,
+];
+
+/// A request to be paid, shared as a `blockc:` URI (in the spirit of
+/// Bitcoin's BIP 21): `blockc:<address>?amount=<amount>&memo=<memo>`.
+///
+/// The address and memo are percent-encoded. Parameters are optional;
+/// unknown ones are ignored, except those starting with `req-`, which a
+/// wallet must understand to pay the request and so are refused.
+///
+/// # Fields
+///
+/// * `address` - Address (or registered name) to pay
+/// * `amount` - Amount asked for, if any (the payer chooses otherwise)
+/// * `memo` - Note for the payment, put in the transaction's memo
+///
+/// # Example
+///
+/// ```
+/// use blockc::wallet::request::PaymentRequest;
+///
+/// let request = PaymentRequest::new("ShopVault", Some(12.5), Some("Order #42".to_string())).unwrap();
+/// let uri = request.to_uri();
+/// assert_eq!(uri, "blockc:ShopVault?amount=12.5&memo=Order%20%2342");
+/// assert_eq!(PaymentRequest::parse(&uri).unwrap(), request);
+///
+/// // Scanned from a QR code, another wallet reads the same request
+/// let payload = request.qr_payload().unwrap();
+/// assert_eq!(payload.version, 4);
+/// assert_eq!(PaymentRequest::parse(&payload.data).unwrap(), request);
+///
+/// assert!(PaymentRequest::parse("BLOCKC:Bob?amount=1&label=Bob").is_ok());
+/// assert!(PaymentRequest::parse("blockc:Bob?req-expiry=100").is_err());
+/// assert!(PaymentRequest::parse("bitcoin:Bob?amount=1").is_err());
+/// assert!(PaymentRequest::parse("blockc:Bob?amount=-1").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaymentRequest {
+    pub address: Address,
+    pub amount: Option<Amount>,
+    pub memo: Option<String>,
+}
+
+/// What to show as a QR code for a payment request.
+///
+/// # Fields
+///
+/// * `data` - Text to encode (the URI)
+/// * `version` - Smallest QR version holding it (see `QR_BYTE_CAPACITY`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct QrPayload {
+    pub data: String,
+    pub version: usize,
+}
+
+impl PaymentRequest {
+    /// Creates a payment request.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PaymentRequest)` - The request
+    /// * `Err(String)` - If the address is empty, the amount is negative or not
+    ///   a number, or the memo is over `MAX_MEMO_BYTES`
+    pub fn new(address: &str, amount: Option<Amount>, memo: Option<String>) -> Result<Self, String> {
+        let request = PaymentRequest {
+            address: address.to_string(),
+            amount: amount,
+            memo: memo,
+        };
+        request.check()?;
+        Ok(request)
+    }
+
+    fn check(&self) -> Result<(), String> {
+        if self.address.is_empty() {
+            return Err("A payment request needs an address".to_string());
+        }
+        if let Some(amount) = self.amount
+            && !(amount.is_finite() && amount >= 0.0) {
+            return Err(format!("Invalid amount {}", amount));
+        }
+        if let Some(memo) = &self.memo
+            && memo.len() > MAX_MEMO_BYTES {
+            return Err(format!("Memo is over {} bytes", MAX_MEMO_BYTES));
+        }
+        Ok(())
+    }
+
+    /// Formats the request as a `blockc:` URI.
+    pub fn to_uri(&self) -> String {
+        let mut parameters = Vec::new();
+        if let Some(amount) = self.amount {
+            parameters.push(format!("amount={}", amount));
+        }
+        if let Some(memo) = &self.memo {
+            parameters.push(format!("memo={}", percent_encode(memo)));
+        }
+        let mut uri = format!("{}:{}", URI_SCHEME, percent_encode(&self.address));
+        if !parameters.is_empty() {
+            uri.push('?');
+            uri.push_str(&parameters.join("&"));
+        }
+        uri
+    }
+
+    /// Parses a `blockc:` URI.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PaymentRequest)` - The request
+    /// * `Err(String)` - If the text is not a `blockc:` URI, has a malformed or
+    ///   repeated parameter, an invalid amount or memo, or a `req-` parameter
+    pub fn parse(uri: &str) -> Result<Self, String> {
+        let uri = uri.trim();
+        let rest = uri.split_once(':')
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case(URI_SCHEME))
+            .map(|(_, rest)| rest)
+            .ok_or(format!("Not a {}: URI", URI_SCHEME))?;
+        let (address, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let mut request = PaymentRequest {
+            address: percent_decode(address)?,
+            amount: None,
+            memo: None,
+        };
+        let mut seen = Vec::new();
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').ok_or(format!("Parameter {} has no value", pair))?;
+            if seen.contains(&key) {
+                return Err(format!("Parameter {} is given twice", key));
+            }
+            seen.push(key);
+            match key {
+                "amount" => request.amount = Some(value.parse().map_err(|_| format!("Invalid amount {}", value))?),
+                "memo" => request.memo = Some(percent_decode(value)?),
+                _ if key.starts_with("req-") => return Err(format!("Unsupported required parameter {}", key)),
+                _ => {}
+            }
+        }
+        request.check()?;
+        Ok(request)
+    }
+
+    /// Returns the URI to show as a QR code, with the smallest QR version
+    /// that holds it.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(QrPayload)` - The payload
+    /// * `Err(String)` - If the URI is too long for any QR code
+    pub fn qr_payload(&self) -> Result<QrPayload, String> {
+        let data = self.to_uri();
+        let version = QR_BYTE_CAPACITY.iter()
+            .position(|capacity| data.len() <= *capacity)
+            .ok_or(format!("Request is {} bytes, over the {} a QR code holds", data.len(), QR_BYTE_CAPACITY[39]))?;
+        Ok(QrPayload {
+            data: data,
+            version: version + 1,
+        })
+    }
+}
+
+impl fmt::Display for PaymentRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_uri())
+    }
+}
+
+/// Encodes everything but unreserved characters (RFC 3986) as `%XX`.
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Decodes `%XX` escapes; unlike a browser, refuses invalid escapes and UTF-8.
+fn percent_decode(text: &str) -> Result<String, String> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let byte = bytes.get(index + 1..index + 3)
+                .and_then(|digits| std::str::from_utf8(digits).ok())
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                .ok_or(format!("Invalid escape in {}", text))?;
+            decoded.push(byte);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| format!("{} is not UTF-8", text))
+}
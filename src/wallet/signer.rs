@@ -0,0 +1,269 @@
+use std::collections::BTreeMap;
+use crate::codec::json::{self, JsonCodec, JsonValue};
+use crate::entity::entity::Entity;
+use crate::transactions::transactions::Transaction;
+
+/// Something holding the private key of an address, asked to sign the
+/// transactions a wallet builds for it.
+///
+/// The key can live in the process (`Entity`), on a device (`HardwareSigner`)
+/// or behind a service (`RemoteSigner`): the wallet only sees the returned
+/// signature (see `Wallet::pay_with_signer`).
+///
+/// # Example
+///
+/// ```
+/// use blockc::entity::entity::Entity;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+/// use blockc::wallet::signer::TransactionSigner;
+///
+/// let mut alice = Entity::new("Alice".to_string(), 0.0, Vec::new(), "a".to_string(), "alice-key".to_string());
+/// let mut payment = Transaction::create_unsigned_payment("Alice", "Bob", 5.0, 0.1, 0).unwrap();
+///
+/// let signature = alice.sign_transaction(&payment).unwrap();
+/// payment.attach_signature(signature).unwrap();
+/// assert_eq!(payment.signature, alice.sign(&payment.signing_data()));
+/// ```
+pub trait TransactionSigner {
+    /// Returns the address whose key the signer holds.
+    fn address(&self) -> &str;
+
+    /// Signs `transaction`, an unsigned transaction sent by `address()`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The signature to attach
+    /// * `Err(String)` - If the signer refuses (wrong sender, rejected by the user, policy, ...)
+    fn sign_transaction(&mut self, transaction: &Transaction) -> Result<String, String>;
+}
+
+/// A key held by the process.
+impl TransactionSigner for Entity {
+    fn address(&self) -> &str {
+        &self.address
+    }
+
+    fn sign_transaction(&mut self, transaction: &Transaction) -> Result<String, String> {
+        check_sender(&self.address, transaction)?;
+        Ok(self.sign(&transaction.signing_data()))
+    }
+}
+
+fn check_sender(address: &str, transaction: &Transaction) -> Result<(), String> {
+    if transaction.sender_address != address {
+        return Err(format!("Signer of {} cannot sign a transaction from {}", address, transaction.sender_address));
+    }
+    if transaction.is_signed() {
+        return Err("Transaction is already signed".to_string());
+    }
+    Ok(())
+}
+
+/// Describes a transaction the way a signing device shows it for approval.
+pub fn confirmation_text(transaction: &Transaction) -> String {
+    format!(
+        "{} {} -> {} {} (fee {}, nonce {})",
+        transaction.kind.name(), transaction.sender_address, transaction.receiver_address, transaction.amount, transaction.fee, transaction.nonce
+    )
+}
+
+/// Stub of a hardware wallet: the key never leaves the "device", which must
+/// be unlocked with its PIN and shows each transaction for the user to
+/// approve.
+///
+/// # Example
+///
+/// ```
+/// use blockc::entity::entity::Entity;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+/// use blockc::wallet::signer::{HardwareSigner, TransactionSigner};
+///
+/// let key = Entity::new("Alice".to_string(), 0.0, Vec::new(), "a".to_string(), "alice-key".to_string());
+/// // The user rejects the 50 coin payment when the device shows it
+/// let mut device = HardwareSigner::new(key, "1234", Box::new(|screen: &str| !screen.contains(" 50 ")));
+/// let small = Transaction::create_unsigned_payment("Alice", "Bob", 5.0, 0.1, 0).unwrap();
+/// let large = Transaction::create_unsigned_payment("Alice", "Bob", 50.0, 0.1, 0).unwrap();
+///
+/// assert!(device.sign_transaction(&small).is_err()); // locked
+/// assert!(device.unlock("0000").is_err());
+/// device.unlock("1234").unwrap();
+/// assert!(device.sign_transaction(&small).is_ok());
+/// assert_eq!(device.sign_transaction(&large), Err("Rejected on the device".to_string()));
+/// assert_eq!(device.screens().len(), 2);
+/// ```
+pub struct HardwareSigner {
+    key: Entity,
+    pin: String,
+    unlocked: bool,
+    approve: Box<dyn FnMut(&str) -> bool + Send>,
+    screens: Vec<String>,
+}
+
+impl HardwareSigner {
+    /// Creates a locked device holding `key`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The account whose private key the device holds
+    /// * `pin` - PIN unlocking the device
+    /// * `approve` - The user's answer to each confirmation screen (see `confirmation_text`)
+    pub fn new(key: Entity, pin: &str, approve: Box<dyn FnMut(&str) -> bool + Send>) -> Self {
+        HardwareSigner {
+            key: key,
+            pin: pin.to_string(),
+            unlocked: false,
+            approve: approve,
+            screens: Vec::new(),
+        }
+    }
+
+    /// Unlocks the device.
+    pub fn unlock(&mut self, pin: &str) -> Result<(), String> {
+        if pin != self.pin {
+            return Err("Wrong PIN".to_string());
+        }
+        self.unlocked = true;
+        Ok(())
+    }
+
+    /// Locks the device again.
+    pub fn lock(&mut self) {
+        self.unlocked = false;
+    }
+
+    /// Returns the confirmation screens shown so far.
+    pub fn screens(&self) -> &[String] {
+        &self.screens
+    }
+}
+
+impl TransactionSigner for HardwareSigner {
+    fn address(&self) -> &str {
+        &self.key.address
+    }
+
+    fn sign_transaction(&mut self, transaction: &Transaction) -> Result<String, String> {
+        if !self.unlocked {
+            return Err("Device is locked".to_string());
+        }
+        check_sender(&self.key.address, transaction)?;
+        let screen = confirmation_text(transaction);
+        let approved = (self.approve)(&screen);
+        self.screens.push(screen);
+        if !approved {
+            return Err("Rejected on the device".to_string());
+        }
+        Ok(self.key.sign(&transaction.signing_data()))
+    }
+}
+
+/// Delivers a request to a signing service and returns its answer.
+pub type SignerTransport = Box<dyn FnMut(&str) -> Result<String, String> + Send>;
+
+/// Signer asking a remote signing service over a transport.
+///
+/// Requests are JSON `{"address": ..., "transaction": {...}}`; the service
+/// answers `{"signature": ...}` or `{"error": ...}`. The transport is any
+/// function delivering a request and returning the answer (an HTTP client,
+/// a socket, or an in-process `SigningService` in tests).
+///
+/// # Example
+///
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// use blockc::entity::entity::Entity;
+/// use blockc::transactions::transactions::{Transaction, TransactionExt};
+/// use blockc::wallet::signer::{RemoteSigner, SigningService, TransactionSigner};
+///
+/// let mut service = SigningService::new();
+/// service.add_key(Entity::new("Treasury".to_string(), 0.0, Vec::new(), "t".to_string(), "treasury-key".to_string()), 100.0);
+/// let service = Arc::new(Mutex::new(service));
+///
+/// let remote = service.clone();
+/// let mut signer = RemoteSigner::new("Treasury", Box::new(move |request: &str| Ok(remote.lock().unwrap().handle(request))));
+///
+/// let payment = Transaction::create_unsigned_payment("Treasury", "Bob", 20.0, 0.1, 0).unwrap();
+/// assert_eq!(signer.sign_transaction(&payment).unwrap().len(), 64);
+///
+/// // Over the key's limit
+/// let payment = Transaction::create_unsigned_payment("Treasury", "Bob", 500.0, 0.1, 1).unwrap();
+/// assert!(signer.sign_transaction(&payment).unwrap_err().contains("limit"));
+/// ```
+pub struct RemoteSigner {
+    address: String,
+    transport: SignerTransport,
+}
+
+impl RemoteSigner {
+    /// Creates a signer for `address` sending its requests through `transport`.
+    pub fn new(address: &str, transport: SignerTransport) -> Self {
+        RemoteSigner {
+            address: address.to_string(),
+            transport: transport,
+        }
+    }
+}
+
+impl TransactionSigner for RemoteSigner {
+    fn address(&self) -> &str {
+        &self.address
+    }
+
+    fn sign_transaction(&mut self, transaction: &Transaction) -> Result<String, String> {
+        check_sender(&self.address, transaction)?;
+        let request = JsonValue::Object(vec![
+            ("address".to_string(), self.address.to_json()),
+            ("transaction".to_string(), JsonCodec::to_json(transaction)),
+        ]);
+        let response = (self.transport)(&request.to_string())?;
+        let response = json::parse(&response).map_err(|error| format!("Invalid answer from the signing service: {}", error))?;
+        let members = response.as_object()?;
+        if let Ok(error) = json::field::<String>(members, "error") {
+            return Err(format!("Signing service refused: {}", error));
+        }
+        json::field(members, "signature")
+    }
+}
+
+/// Mock of a remote signing service or HSM: it keeps keys the caller never
+/// sees and signs requests within a per-key spending limit.
+///
+/// See `RemoteSigner` for an example.
+#[derive(Debug, Clone, Default)]
+pub struct SigningService {
+    keys: BTreeMap<String, (Entity, f64)>,
+}
+
+impl SigningService {
+    /// Creates a service holding no keys.
+    pub fn new() -> Self {
+        SigningService::default()
+    }
+
+    /// Adds a key, which signs transactions costing at most `limit` (amount, fee and gas).
+    pub fn add_key(&mut self, key: Entity, limit: f64) {
+        self.keys.insert(key.address.clone(), (key, limit));
+    }
+
+    /// Answers one request from a `RemoteSigner`.
+    pub fn handle(&mut self, request: &str) -> String {
+        let (name, value) = match self.sign_request(request) {
+            Ok(signature) => ("signature", signature),
+            Err(error) => ("error", error),
+        };
+        JsonValue::Object(vec![(name.to_string(), JsonValue::String(value))]).to_string()
+    }
+
+    fn sign_request(&self, request: &str) -> Result<String, String> {
+        let request = json::parse(request)?;
+        let members = request.as_object()?;
+        let address: String = json::field(members, "address")?;
+        let transaction: Transaction = json::field(members, "transaction")?;
+        let (key, limit) = self.keys.get(&address).ok_or(format!("No key for {}", address))?;
+        check_sender(&key.address, &transaction)?;
+        if transaction.total_cost() > *limit {
+            return Err(format!("{} is over the limit of {} for {}", transaction.total_cost(), limit, address));
+        }
+        Ok(key.sign(&transaction.signing_data()))
+    }
+}
@@ -0,0 +1,268 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use blockc_core::sign::{self, ADDRESS_HASH_LENGTH, ADDRESS_PREFIX};
+use crate::entity::entity::Entity;
+use crate::helpers::random::RandomSource;
+use crate::simulation::rng::SimRng;
+
+/// Default number of attempts between two progress reports.
+pub const DEFAULT_REPORT_EVERY: u64 = 100_000;
+
+/// Keys each thread tries between two looks at the shared counters.
+const BATCH: u64 = 256;
+
+/// What a vanity address should look like, in the hexadecimal part after
+/// `ADDRESS_PREFIX`.
+///
+/// Each extra character makes a match 16 times rarer (see `expected_attempts`).
+///
+/// # Example
+///
+/// ```
+/// use blockc::wallet::vanity::VanityPattern;
+///
+/// assert_eq!(VanityPattern::parse("cafe").unwrap(), VanityPattern::Prefix("cafe".to_string()));
+/// assert_eq!(VanityPattern::parse("*BEEF").unwrap(), VanityPattern::Suffix("beef".to_string()));
+/// assert_eq!(VanityPattern::parse("*00*").unwrap(), VanityPattern::Contains("00".to_string()));
+/// assert!(VanityPattern::parse("xyz").is_err());
+/// assert!(VanityPattern::parse("*").is_err());
+///
+/// let pattern = VanityPattern::parse("cafe").unwrap();
+/// assert!(pattern.matches("bccafe0123456789abcdef0123456789abcdef01"));
+/// assert!(!pattern.matches("cafe"));
+/// assert_eq!(pattern.expected_attempts(), 65536.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum VanityPattern {
+    /// The address starts with these characters (`cafe` or `cafe*`)
+    Prefix(String),
+    /// The address ends with these characters (`*cafe`)
+    Suffix(String),
+    /// The characters appear anywhere (`*cafe*`)
+    Contains(String),
+}
+
+impl VanityPattern {
+    /// Reads a pattern: hexadecimal characters (any case), with a `*` before
+    /// them to match the end of the address, or on both sides to match anywhere.
+    pub fn parse(pattern: &str) -> Result<Self, String> {
+        let leading = pattern.starts_with('*');
+        let body = pattern.strip_prefix('*').unwrap_or(pattern);
+        let trailing = body.ends_with('*');
+        let body = body.strip_suffix('*').unwrap_or(body).to_ascii_lowercase();
+        if body.is_empty() || body.len() > ADDRESS_HASH_LENGTH {
+            return Err(format!("A pattern has 1 to {} hexadecimal characters", ADDRESS_HASH_LENGTH));
+        }
+        if !body.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Pattern {} may only use hexadecimal characters (and *)", pattern));
+        }
+        Ok(match (leading, trailing) {
+            (false, _) => VanityPattern::Prefix(body),
+            (true, false) => VanityPattern::Suffix(body),
+            (true, true) => VanityPattern::Contains(body),
+        })
+    }
+
+    /// Returns `true` if the derived `address` fits the pattern.
+    pub fn matches(&self, address: &str) -> bool {
+        let Some(hash) = address.strip_prefix(ADDRESS_PREFIX) else {
+            return false;
+        };
+        match self {
+            VanityPattern::Prefix(body) => hash.starts_with(body.as_str()),
+            VanityPattern::Suffix(body) => hash.ends_with(body.as_str()),
+            VanityPattern::Contains(body) => hash.contains(body.as_str()),
+        }
+    }
+
+    /// Returns how many keys it takes on average to find a match.
+    pub fn expected_attempts(&self) -> f64 {
+        match self {
+            VanityPattern::Prefix(body) | VanityPattern::Suffix(body) => 16f64.powi(body.len() as i32),
+            // Roughly one chance per position the characters can start at
+            VanityPattern::Contains(body) => 16f64.powi(body.len() as i32) / (ADDRESS_HASH_LENGTH + 1 - body.len()) as f64,
+        }
+    }
+}
+
+/// Progress of a search, passed to the progress callback.
+///
+/// # Fields
+///
+/// * `attempts` - Keys tried so far, by all threads
+/// * `elapsed` - Time since the search started
+/// * `expected_attempts` - Keys it takes on average to find a match
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VanityProgress {
+    pub attempts: u64,
+    pub elapsed: Duration,
+    pub expected_attempts: f64,
+}
+
+impl VanityProgress {
+    /// Returns the keys tried per second.
+    pub fn rate(&self) -> f64 {
+        self.attempts as f64 / self.elapsed.as_secs_f64().max(1e-9)
+    }
+}
+
+/// A key pair whose derived address fits the pattern.
+///
+/// # Fields
+///
+/// * `entity` - The account (derived address and keys, balance 0)
+/// * `attempts` - Keys tried to find it, by all threads
+#[derive(Debug, Clone)]
+pub struct VanityKey {
+    pub entity: Entity,
+    pub attempts: u64,
+}
+
+/// Grinds key pairs on several threads until the address derived from one
+/// (see `sign::address`) fits a pattern.
+///
+/// Keys come from `SimRng`s seeded from the search's seed: they are only fit
+/// for a learning chain.
+///
+/// `wasm32-unknown-unknown` has neither threads nor `Instant`: the module is
+/// left out of wasm builds.
+///
+/// # Fields
+///
+/// * `pattern` - What the address should look like
+/// * `threads` - Threads grinding keys (1 runs on the calling thread)
+/// * `report_every` - Attempts between two progress reports
+///
+/// # Example
+///
+/// ```
+/// use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+/// use blockc::wallet::vanity::{VanityGenerator, VanityPattern};
+/// use blockc_core::sign;
+///
+/// let mut generator = VanityGenerator::new(VanityPattern::parse("ab").unwrap());
+/// generator.threads = 2;
+/// generator.report_every = 64;
+///
+/// let reports = AtomicUsize::new(0);
+/// let cancel = AtomicBool::new(false);
+/// let key = generator.search(7, &cancel, &|_| { reports.fetch_add(1, Ordering::Relaxed); }).unwrap();
+/// assert!(key.entity.address.starts_with("bcab"));
+/// assert_eq!(key.entity.address, sign::address(&sign::public_key(&key.entity.private_key)));
+/// assert!(key.attempts > 0);
+///
+/// // A cancelled search gives up
+/// cancel.store(true, Ordering::Relaxed);
+/// let hopeless = VanityGenerator::new(VanityPattern::parse("0123456789abcdef").unwrap());
+/// assert!(hopeless.search(7, &cancel, &|_| {}).is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct VanityGenerator {
+    pub pattern: VanityPattern,
+    pub threads: usize,
+    pub report_every: u64,
+}
+
+impl VanityGenerator {
+    /// Creates a generator with one thread per available core.
+    pub fn new(pattern: VanityPattern) -> Self {
+        VanityGenerator {
+            pattern: pattern,
+            threads: thread::available_parallelism().map(|threads| threads.get()).unwrap_or(1),
+            report_every: DEFAULT_REPORT_EVERY,
+        }
+    }
+
+    /// Searches until a key fits or `cancel` is set.
+    ///
+    /// With one thread, the same seed always finds the same key; with more,
+    /// whichever thread gets lucky first wins.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - Seed of the threads' generators
+    /// * `cancel` - Set it (e.g. from another thread) to stop the search
+    /// * `progress` - Called about every `report_every` attempts, from the search threads
+    ///
+    /// # Returns
+    ///
+    /// The key found, or `None` if the search was cancelled first.
+    pub fn search(&self, seed: u64, cancel: &AtomicBool, progress: &(dyn Fn(&VanityProgress) + Sync)) -> Option<VanityKey> {
+        let search = Search {
+            pattern: &self.pattern,
+            report_every: self.report_every.max(1),
+            cancel: cancel,
+            progress: progress,
+            started: Instant::now(),
+            attempts: AtomicU64::new(0),
+            found: Mutex::new(None),
+        };
+        let mut seeds = SimRng::new(seed);
+        let seeds: Vec<u64> = (0..self.threads.max(1)).map(|_| seeds.next_u64()).collect();
+        if let [seed] = seeds.as_slice() {
+            search.grind(*seed);
+        } else {
+            thread::scope(|scope| {
+                for seed in seeds {
+                    let search = &search;
+                    scope.spawn(move || search.grind(seed));
+                }
+            });
+        }
+        let attempts = search.attempts.load(Ordering::Relaxed);
+        search.found.into_inner().ok().flatten().map(|entity| VanityKey {
+            entity: entity,
+            attempts: attempts,
+        })
+    }
+}
+
+/// State shared by the threads of one search.
+struct Search<'a> {
+    pattern: &'a VanityPattern,
+    report_every: u64,
+    cancel: &'a AtomicBool,
+    progress: &'a (dyn Fn(&VanityProgress) + Sync),
+    started: Instant,
+    attempts: AtomicU64,
+    found: Mutex<Option<Entity>>,
+}
+
+impl Search<'_> {
+    fn done(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed) || self.found.lock().map(|found| found.is_some()).unwrap_or(true)
+    }
+
+    fn grind(&self, seed: u64) {
+        let mut rng = SimRng::new(seed);
+        while !self.done() {
+            let mut tried = 0;
+            let mut hit = None;
+            while tried < BATCH && hit.is_none() {
+                tried += 1;
+                let private_key = rng.hex(32);
+                let public_key = sign::public_key(&private_key);
+                let address = sign::address(&public_key);
+                if self.pattern.matches(&address) {
+                    hit = Some(Entity::new(address, 0.0, Vec::new(), public_key, private_key));
+                }
+            }
+            let before = self.attempts.fetch_add(tried, Ordering::Relaxed);
+            if let Some(entity) = hit {
+                if let Ok(mut found) = self.found.lock() {
+                    found.get_or_insert(entity);
+                }
+                return;
+            }
+            if before / self.report_every != (before + tried) / self.report_every {
+                (self.progress)(&VanityProgress {
+                    attempts: before + tried,
+                    elapsed: self.started.elapsed(),
+                    expected_attempts: self.pattern.expected_attempts(),
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,690 @@
+use std::collections::HashSet;
+use blockc_core::sign;
+use crate::blockchain::blockchain::Blockchain;
+use crate::codec::json::{self, JsonCodec, JsonValue};
+use crate::entity::entity::Entity;
+use crate::transactions::transactions::{Output, Transaction, TransactionExt};
+use crate::names::registry;
+use crate::wallet::contacts::AddressBook;
+use crate::wallet::offline::SigningRequest;
+use crate::wallet::request::PaymentRequest;
+use crate::wallet::signer::TransactionSigner;
+#[cfg(feature = "privacy")]
+use crate::privacy::memo::{self, decrypt_memo, memo_secret};
+#[cfg(feature = "privacy")]
+use crate::privacy::message;
+#[cfg(feature = "privacy")]
+use crate::privacy::stealth::{StealthKeys, StealthPayment};
+
+/// Address of an account.
+pub type Address = String;
+
+/// Amount of coins.
+pub type Amount = f64;
+
+/// Default policy: maximum number of outputs in one multi-recipient transaction.
+pub const DEFAULT_MAX_OUTPUTS_PER_TRANSACTION: usize = 50;
+
+/// Proof that one payment of a batch was signed.
+///
+/// # Fields
+///
+/// * `recipient` - Address paid
+/// * `amount` - Amount paid
+/// * `transaction_id` - Id of the transaction carrying the payment
+/// * `nonce` - Nonce of that transaction
+/// * `output_index` - Position of the payment among the transaction's outputs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Receipt {
+    pub recipient: Address,
+    pub amount: Amount,
+    pub transaction_id: String,
+    pub nonce: u64,
+    pub output_index: usize,
+}
+
+/// Result of `Wallet::batch_send`.
+///
+/// # Fields
+///
+/// * `transactions` - Signed transactions, in nonce order, to submit to the chain
+/// * `receipts` - One receipt per payment, in the order the payments were given
+#[derive(Debug, Clone)]
+pub struct BatchSend {
+    pub transactions: Vec<Transaction>,
+    pub receipts: Vec<Receipt>,
+}
+
+/// An address a wallet follows without holding its private key (e.g. cold
+/// storage).
+///
+/// # Fields
+///
+/// * `address` - The address followed
+/// * `public_key` - Its public key, if known (needed to check its derived address)
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchOnly {
+    pub address: Address,
+    pub public_key: Option<String>,
+}
+
+/// `{"address": ..., "public_key": ...}` (`public_key` is null when unknown)
+impl JsonCodec for WatchOnly {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Object(vec![
+            ("address".to_string(), self.address.to_json()),
+            ("public_key".to_string(), self.public_key.to_json()),
+        ])
+    }
+
+    fn from_json(value: &JsonValue) -> Result<Self, String> {
+        let members = value.as_object()?;
+        Ok(WatchOnly {
+            address: json::field(members, "address")?,
+            public_key: json::field(members, "public_key")?,
+        })
+    }
+}
+
+/// On-chain position of a watched address.
+///
+/// # Fields
+///
+/// * `address` - The watched address
+/// * `balance` - Confirmed balance
+/// * `spendable` - Balance that is not locked (e.g. by vesting)
+/// * `nonce` - Next nonce on chain
+/// * `pending` - Its transactions waiting in the mempool
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchedBalance {
+    pub address: Address,
+    pub balance: Amount,
+    pub spendable: Amount,
+    pub nonce: u64,
+    pub pending: usize,
+}
+
+/// A wallet around an `Entity`, with higher level payment helpers.
+///
+/// The wallet can also watch addresses it has no private key for: it follows
+/// their balances and history and builds unsigned payments for the key holder
+/// to sign.
+///
+/// # Fields
+///
+/// * `entity` - The account (address, keys, local balance and nonce)
+/// * `max_outputs_per_transaction` - Policy limit on outputs in one transaction
+/// * `watch_only` - Addresses followed without their private keys
+pub struct Wallet {
+    pub entity: Entity,
+    pub max_outputs_per_transaction: usize,
+    pub watch_only: Vec<WatchOnly>,
+}
+
+impl Wallet {
+    /// Creates a wallet for `entity` using the default policy.
+    pub fn new(entity: Entity) -> Self {
+        Wallet {
+            entity: entity,
+            max_outputs_per_transaction: DEFAULT_MAX_OUTPUTS_PER_TRANSACTION,
+            watch_only: Vec::new(),
+        }
+    }
+
+    /// Creates a watch-only wallet: it holds no keys of its own (its entity has
+    /// an empty address), only follows `watch_only`.
+    pub fn watching(watch_only: Vec<WatchOnly>) -> Self {
+        let mut wallet = Wallet::new(Entity::new(String::new(), 0.0, Vec::new(), String::new(), String::new()));
+        wallet.watch_only = watch_only;
+        wallet
+    }
+
+    /// Returns the wallet's address.
+    pub fn address(&self) -> &str {
+        &self.entity.address
+    }
+
+    /// Refreshes the local balance and nonce from the chain.
+    ///
+    /// Call it before signing when payments may have been received or sent
+    /// elsewhere, so the wallet signs with the right nonce.
+    pub fn sync(&mut self, chain: &Blockchain) {
+        self.entity.balance = chain.get_balance(&self.entity.address);
+        self.entity.nonce = chain.get_nonce(&self.entity.address);
+    }
+
+    /// Like `sync`, but also counts the wallet's transactions still waiting in
+    /// the mempool, so the next one gets the following nonce.
+    pub fn sync_pending(&mut self, chain: &Blockchain) {
+        self.sync(chain);
+        for transaction in chain.mempool().transactions() {
+            if transaction.sender_address == self.entity.address {
+                self.entity.balance -= transaction.total_cost();
+                self.entity.nonce = self.entity.nonce.max(transaction.nonce + 1);
+            }
+        }
+    }
+
+    /// Turns a recipient into an address: names (e.g. "bob.bc") are resolved on
+    /// `chain`, anything else is already an address.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Address)` - The address to pay
+    /// * `Err(String)` - If the recipient is a name that doesn't resolve
+    pub fn resolve(&self, chain: &Blockchain, recipient: &str) -> Result<Address, String> {
+        if !registry::is_name(recipient) {
+            return Ok(recipient.to_string());
+        }
+        chain.state().resolve_name(recipient)
+            .map(|address| address.to_string())
+            .ok_or(format!("Name {} is not registered", recipient))
+    }
+
+    /// Like `resolve`, but contact labels (e.g. "bob") are looked up in
+    /// `contacts` first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::wallet::contacts::AddressBook;
+    /// use blockc::wallet::wallet::Wallet;
+    ///
+    /// let chain = Blockchain::with_settings(1, 50.0);
+    /// let alice = Wallet::new(Entity::new("Alice".to_string(), 0.0, Vec::new(), "a".to_string(), "a".to_string()));
+    /// let mut contacts = AddressBook::new();
+    /// contacts.add("bob", "9c2e41d07a").unwrap();
+    ///
+    /// assert_eq!(alice.resolve_contact(&chain, &contacts, "bob").unwrap(), "9c2e41d07a");
+    /// assert_eq!(alice.resolve_contact(&chain, &contacts, "Carol").unwrap(), "Carol");
+    /// assert!(alice.resolve_contact(&chain, &contacts, "nobody.bc").is_err());
+    /// ```
+    pub fn resolve_contact(&self, chain: &Blockchain, contacts: &AddressBook, recipient: &str) -> Result<Address, String> {
+        match contacts.address(recipient) {
+            Some(address) => Ok(address.to_string()),
+            None => self.resolve(chain, recipient),
+        }
+    }
+
+    /// Pays `amount` to a name or an address.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed payment, to submit to the chain
+    /// * `Err(String)` - If the name doesn't resolve or the wallet can't afford it
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::transactions::transactions::{Transaction, TransactionExt};
+    /// use blockc::wallet::wallet::Wallet;
+    ///
+    /// let mut chain = Blockchain::new();
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// let fee = chain.get_base_fee() * 2.0;
+    /// let mut alice = Wallet::new(Entity::new("Alice".to_string(), 0.0, Vec::new(), "a".to_string(), "a".to_string()));
+    /// alice.sync(&chain);
+    ///
+    /// let register = Transaction::register_name(&mut alice.entity, "shop.bc", "ShopVault", fee).unwrap();
+    /// chain.add_block(vec![register], "Miner").unwrap();
+    ///
+    /// let payment = alice.pay(&chain, "shop.bc", 5.0, fee).unwrap();
+    /// assert_eq!(payment.receiver_address, "ShopVault");
+    /// assert!(alice.pay(&chain, "nobody.bc", 5.0, fee).is_err());
+    /// ```
+    pub fn pay(&mut self, chain: &Blockchain, recipient: &str, amount: Amount, fee: f64) -> Result<Transaction, String> {
+        let address = self.resolve(chain, recipient)?;
+        Transaction::create_payment(&mut self.entity, &address, amount, fee)
+    }
+
+    /// Returns a request to be paid `amount` (or what the payer chooses) at
+    /// the wallet's address, to share as a URI or QR code.
+    pub fn payment_request(&self, amount: Option<Amount>, memo: Option<String>) -> Result<PaymentRequest, String> {
+        PaymentRequest::new(&self.entity.address, amount, memo)
+    }
+
+    /// Pays a payment request, with its memo.
+    ///
+    /// `amount` is only needed when the request doesn't ask for one; if both
+    /// give one they must agree.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed payment, to submit to the chain
+    /// * `Err(String)` - If no amount is known or the amounts disagree, the
+    ///   address is a name that doesn't resolve, or the wallet can't afford it
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::wallet::request::PaymentRequest;
+    /// use blockc::wallet::wallet::Wallet;
+    ///
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.add_block(Vec::new(), "Alice").unwrap();
+    /// let fee = chain.get_base_fee() * 2.0;
+    ///
+    /// let shop = Wallet::new(Entity::new("Shop".to_string(), 0.0, Vec::new(), "s".to_string(), "s".to_string()));
+    /// let uri = shop.payment_request(Some(12.0), Some("Order 42".to_string())).unwrap().to_uri();
+    ///
+    /// let mut alice = Wallet::new(Entity::new("Alice".to_string(), 0.0, Vec::new(), "a".to_string(), "a".to_string()));
+    /// alice.sync(&chain);
+    /// let request = PaymentRequest::parse(&uri).unwrap();
+    /// assert!(alice.pay_request(&chain, &request, Some(10.0), fee).is_err());
+    ///
+    /// let payment = alice.pay_request(&chain, &request, None, fee).unwrap();
+    /// assert_eq!(payment.memo.as_deref(), Some("Order 42"));
+    /// chain.submit_transaction(payment).unwrap();
+    /// chain.add_block_from_mempool("Miner").unwrap();
+    /// assert_eq!(chain.get_balance("Shop"), 12.0);
+    /// ```
+    pub fn pay_request(&mut self, chain: &Blockchain, request: &PaymentRequest, amount: Option<Amount>, fee: f64) -> Result<Transaction, String> {
+        let amount = match (request.amount, amount) {
+            (Some(asked), Some(given)) if asked != given => {
+                return Err(format!("The request asks for {}, not {}", asked, given));
+            }
+            (Some(amount), _) | (None, Some(amount)) => amount,
+            (None, None) => return Err("The request has no amount: give one".to_string()),
+        };
+        let address = self.resolve(chain, &request.address)?;
+        Transaction::create_payment_with_memo(&mut self.entity, &address, amount, fee, request.memo.clone())
+    }
+
+    /// Returns the stealth meta-address to publish to receive private payments.
+    #[cfg(feature = "privacy")]
+    pub fn stealth_address(&self) -> String {
+        StealthKeys::from_private_key(&self.entity.private_key).meta_address().encode()
+    }
+
+    /// Scans every block of `chain` for stealth payments to this wallet.
+    ///
+    /// See `StealthKeys` for an example.
+    #[cfg(feature = "privacy")]
+    pub fn scan_stealth(&self, chain: &Blockchain) -> Vec<StealthPayment> {
+        let keys = StealthKeys::from_private_key(&self.entity.private_key);
+        chain.get_blocks().iter().flat_map(|block| keys.scan_block(block)).collect()
+    }
+
+    /// Returns the memo key to publish to receive encrypted memos.
+    #[cfg(feature = "privacy")]
+    pub fn memo_key(&self) -> String {
+        memo::memo_key(&self.entity.private_key)
+    }
+
+    /// Returns the memo of `transaction` as this wallet reads it.
+    ///
+    /// Plaintext memos are returned as they are; encrypted memos are decrypted
+    /// with the wallet's memo secret. See
+    /// `Transaction::create_payment_with_encrypted_memo` for an example.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(String)` - The memo
+    /// * `None` - If there is no memo, or it is encrypted to someone else
+    #[cfg(feature = "privacy")]
+    pub fn read_memo(&self, transaction: &Transaction) -> Option<String> {
+        let memo = transaction.memo.as_deref()?;
+        if !transaction.has_encrypted_memo() {
+            return Some(memo.to_string());
+        }
+        decrypt_memo(&memo_secret(&self.entity.private_key), memo).ok()
+    }
+
+    /// Returns the public key the wallet's message signatures verify against,
+    /// to publish in its DID document.
+    #[cfg(feature = "privacy")]
+    pub fn message_public_key(&self) -> String {
+        message::message_public_key(&self.entity.private_key)
+    }
+
+    /// Signs an off-chain message as `address`, to prove the wallet owns it
+    /// without making a transaction (see `verify_message` and
+    /// `verify_address_message` in `privacy::message`).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The encoded signature
+    /// * `Err(String)` - If `address` is not the wallet's, or the message is
+    ///   over `MAX_MESSAGE_BYTES`
+    #[cfg(feature = "privacy")]
+    pub fn sign_message(&self, address: &str, message: &str) -> Result<String, String> {
+        if address != self.entity.address {
+            return Err(format!("This wallet holds the key of {}, not {}", self.entity.address, address));
+        }
+        if message.len() > message::MAX_MESSAGE_BYTES {
+            return Err(format!("Message is over {} bytes", message::MAX_MESSAGE_BYTES));
+        }
+        Ok(message::sign_message(&self.entity.private_key, address, message))
+    }
+
+    /// Watches an address without its keys (nothing changes if it is watched
+    /// already).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The address is watched
+    /// * `Err(String)` - If the address is empty or the wallet's own
+    pub fn watch_address(&mut self, address: &str) -> Result<(), String> {
+        self.watch(address, None)
+    }
+
+    /// Watches the address derived from a public key (see `sign::address`),
+    /// and returns it.
+    pub fn watch_public_key(&mut self, public_key: &str) -> Result<Address, String> {
+        if public_key.is_empty() {
+            return Err("Public key is empty".to_string());
+        }
+        let address = sign::address(public_key);
+        self.watch(&address, Some(public_key.to_string()))?;
+        Ok(address)
+    }
+
+    fn watch(&mut self, address: &str, public_key: Option<String>) -> Result<(), String> {
+        if address.is_empty() {
+            return Err("Address is empty".to_string());
+        }
+        if address == self.entity.address {
+            return Err(format!("The wallet holds the keys of {}", address));
+        }
+        match self.watch_only.iter_mut().find(|watched| watched.address == address) {
+            Some(watched) => watched.public_key = watched.public_key.take().or(public_key),
+            None => self.watch_only.push(WatchOnly {
+                address: address.to_string(),
+                public_key: public_key,
+            }),
+        }
+        Ok(())
+    }
+
+    /// Stops watching an address.
+    pub fn unwatch(&mut self, address: &str) -> Result<(), String> {
+        let count = self.watch_only.len();
+        self.watch_only.retain(|watched| watched.address != address);
+        if self.watch_only.len() == count {
+            return Err(format!("{} is not watched", address));
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the wallet watches `address` without its keys.
+    pub fn is_watching(&self, address: &str) -> bool {
+        self.watch_only.iter().any(|watched| watched.address == address)
+    }
+
+    /// Returns the balance, nonce and pending transactions of every watched
+    /// address, in the order they were added.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::wallet::wallet::Wallet;
+    ///
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.add_block(Vec::new(), "ColdVault").unwrap();
+    ///
+    /// let mut hot = Wallet::new(Entity::new("Hot".to_string(), 0.0, Vec::new(), "h".to_string(), "h".to_string()));
+    /// hot.watch_address("ColdVault").unwrap();
+    /// let balances = hot.watched_balances(&chain);
+    /// assert_eq!((balances[0].address.as_str(), balances[0].balance), ("ColdVault", chain.get_balance("ColdVault")));
+    /// assert!(balances[0].balance > 0.0);
+    /// assert_eq!(hot.watched_history(&chain).len(), 1);
+    ///
+    /// let address = hot.watch_public_key("02ab").unwrap();
+    /// assert!(hot.is_watching(&address));
+    /// assert!(hot.watch_address("Hot").is_err());
+    /// ```
+    pub fn watched_balances(&self, chain: &Blockchain) -> Vec<WatchedBalance> {
+        self.watch_only.iter()
+            .map(|watched| WatchedBalance {
+                address: watched.address.clone(),
+                balance: chain.get_balance(&watched.address),
+                spendable: chain.state().get_spendable_balance(&watched.address),
+                nonce: chain.get_nonce(&watched.address),
+                pending: chain.mempool().transactions().iter()
+                    .filter(|transaction| transaction.sender_address == watched.address)
+                    .count(),
+            })
+            .collect()
+    }
+
+    /// Returns the confirmed transactions sent or received by any watched
+    /// address, oldest first (a transaction between two of them once).
+    pub fn watched_history<'a>(&self, chain: &'a Blockchain) -> Vec<(u64, &'a Transaction)> {
+        let mut history: Vec<(u64, &Transaction)> = self.watch_only.iter()
+            .flat_map(|watched| chain.get_history(&watched.address))
+            .collect();
+        history.sort_by_key(|(height, _)| *height);
+        let mut seen = HashSet::new();
+        history.retain(|(_, transaction)| seen.insert(transaction.id()));
+        history
+    }
+
+    /// Builds an unsigned payment from a watched address, for its key holder
+    /// to sign (`Transaction::sign`) and anyone to submit.
+    ///
+    /// The nonce follows the address's confirmed and pending transactions, and
+    /// the payment must fit in what it can spend after them.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The unsigned payment
+    /// * `Err(String)` - If `from` is not watched, `to` doesn't resolve, an
+    ///   amount is negative or the address can't afford it
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::wallet::wallet::Wallet;
+    /// use blockc::transactions::transactions::TransactionExt;
+    ///
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.add_block(Vec::new(), "ColdVault").unwrap();
+    /// let fee = chain.get_base_fee() * 2.0;
+    ///
+    /// // The online wallet watches the vault and prepares the payment...
+    /// let mut hot = Wallet::new(Entity::new("Hot".to_string(), 0.0, Vec::new(), "h".to_string(), "h".to_string()));
+    /// hot.watch_address("ColdVault").unwrap();
+    /// let mut payment = hot.build_unsigned_payment(&chain, "ColdVault", "Bob", 20.0, fee).unwrap();
+    /// assert!(!payment.is_signed());
+    /// assert!(chain.submit_transaction(payment.clone()).is_err());
+    /// assert!(hot.build_unsigned_payment(&chain, "ColdVault", "Bob", 80.0, fee).is_err());
+    ///
+    /// // ...which only the offline key can sign
+    /// let cold = Entity::new("ColdVault".to_string(), 0.0, Vec::new(), "c".to_string(), "cold-key".to_string());
+    /// assert!(payment.sign(&hot.entity).is_err());
+    /// payment.sign(&cold).unwrap();
+    /// chain.submit_transaction(payment).unwrap();
+    /// chain.add_block_from_mempool("Miner").unwrap();
+    /// assert_eq!(chain.get_balance("Bob"), 20.0);
+    /// ```
+    pub fn build_unsigned_payment(&self, chain: &Blockchain, from: &str, to: &str, amount: Amount, fee: f64) -> Result<Transaction, String> {
+        if !self.is_watching(from) {
+            return Err(format!("{} is not watched by this wallet", from));
+        }
+        self.unsigned_payment(chain, from, to, amount, fee)
+    }
+
+    /// Pays `amount` from the address of `signer`, which holds its key outside
+    /// the wallet (hardware wallet, signing service, ...): the wallet builds the
+    /// unsigned payment, the signer signs it and the wallet attaches the
+    /// signature.
+    ///
+    /// The address must be the wallet's own or a watched one. The payment uses
+    /// its next nonce on `chain` (counting pending transactions).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The signed payment, to submit to the chain
+    /// * `Err(String)` - If the address is not the wallet's, the payment can't be
+    ///   built, or the signer refuses
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::blockchain::blockchain::Blockchain;
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::wallet::signer::HardwareSigner;
+    /// use blockc::wallet::wallet::Wallet;
+    ///
+    /// let mut chain = Blockchain::with_settings(1, 50.0);
+    /// chain.add_block(Vec::new(), "Vault").unwrap();
+    /// let fee = chain.get_base_fee() * 2.0;
+    ///
+    /// let key = Entity::new("Vault".to_string(), 0.0, Vec::new(), "v".to_string(), "vault-key".to_string());
+    /// let mut device = HardwareSigner::new(key, "1234", Box::new(|_: &str| true));
+    /// device.unlock("1234").unwrap();
+    ///
+    /// let mut wallet = Wallet::watching(Vec::new());
+    /// wallet.watch_address("Vault").unwrap();
+    /// let payment = wallet.pay_with_signer(&chain, &mut device, "Bob", 10.0, fee).unwrap();
+    /// chain.submit_transaction(payment).unwrap();
+    /// chain.add_block_from_mempool("Miner").unwrap();
+    /// assert_eq!(chain.get_balance("Bob"), 10.0);
+    ///
+    /// device.lock();
+    /// assert!(wallet.pay_with_signer(&chain, &mut device, "Bob", 10.0, fee).is_err());
+    /// ```
+    pub fn pay_with_signer(&self, chain: &Blockchain, signer: &mut dyn TransactionSigner, to: &str, amount: Amount, fee: f64) -> Result<Transaction, String> {
+        let from = signer.address().to_string();
+        if from != self.entity.address && !self.is_watching(&from) {
+            return Err(format!("{} is not an address of this wallet", from));
+        }
+        let mut transaction = self.unsigned_payment(chain, &from, to, amount, fee)?;
+        let signature = signer.sign_transaction(&transaction)?;
+        transaction.attach_signature(signature)?;
+        Ok(transaction)
+    }
+
+    /// Like `build_unsigned_payment`, but wraps the payment in a
+    /// `SigningRequest` carrying what an air-gapped wallet needs to check it
+    /// (network, height, spendable balance), to export for offline signing.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SigningRequest)` - The request to export
+    /// * `Err(String)` - If the payment can't be built (see `build_unsigned_payment`)
+    pub fn build_signing_request(&self, chain: &Blockchain, from: &str, to: &str, amount: Amount, fee: f64) -> Result<SigningRequest, String> {
+        let transaction = self.build_unsigned_payment(chain, from, to, amount, fee)?;
+        let (spendable, _) = available(chain, from);
+        SigningRequest::new(chain, transaction, spendable)
+    }
+
+    fn unsigned_payment(&self, chain: &Blockchain, from: &str, to: &str, amount: Amount, fee: f64) -> Result<Transaction, String> {
+        let receiver = self.resolve(chain, to)?;
+        let (spendable, nonce) = available(chain, from);
+        let transaction = Transaction::create_unsigned_payment(from, &receiver, amount, fee, nonce)?;
+        if transaction.total_cost() > spendable {
+            return Err(format!("Insufficient balance for {}. Have: {}, Need: {}", from, spendable, transaction.total_cost()));
+        }
+        Ok(transaction)
+    }
+
+    /// Pays many recipients with as few transactions as the policy allows.
+    ///
+    /// Payments are grouped into multi-recipient transactions of at most
+    /// `max_outputs_per_transaction` outputs (a group of one is a plain transfer).
+    /// The transactions use consecutive nonces, so they can all be submitted at
+    /// once. Nothing is signed unless the wallet can afford every payment and fee.
+    ///
+    /// # Arguments
+    ///
+    /// * `payments` - Recipients and amounts
+    /// * `fee` - Fee paid by each transaction
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(BatchSend)` - The transactions to submit and one receipt per payment
+    /// * `Err(String)` - If there are no payments, an amount is negative, or the balance is too low
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use blockc::entity::entity::Entity;
+    /// use blockc::wallet::wallet::Wallet;
+    ///
+    /// let alice = Entity::new("Alice".to_string(), 100.0, Vec::new(), "pub".to_string(), "priv".to_string());
+    /// let mut wallet = Wallet::new(alice);
+    /// wallet.max_outputs_per_transaction = 2;
+    ///
+    /// let payments = vec![
+    ///     ("Bob".to_string(), 10.0),
+    ///     ("Carol".to_string(), 5.0),
+    ///     ("Dave".to_string(), 1.0),
+    /// ];
+    /// let batch = wallet.batch_send(payments, 0.1).unwrap();
+    ///
+    /// assert_eq!(batch.transactions.len(), 2);
+    /// assert_eq!(batch.receipts[2].recipient, "Dave");
+    /// assert_eq!(batch.receipts[2].nonce, 1);
+    /// assert_eq!(wallet.entity.nonce, 2);
+    /// ```
+    pub fn batch_send(&mut self, payments: Vec<(Address, Amount)>, fee: f64) -> Result<BatchSend, String> {
+        if payments.is_empty() {
+            return Err("No payments to send".to_string());
+        }
+        if fee < 0.0 || payments.iter().any(|(_, amount)| *amount < 0.0) {
+            return Err("Amount and fee cannot be negative".to_string());
+        }
+
+        let group_size = self.max_outputs_per_transaction.max(1);
+        let groups = payments.len().div_ceil(group_size);
+        let total: f64 = payments.iter().map(|(_, amount)| amount).sum::<f64>() + fee * groups as f64;
+        if !self.entity.can_send(total) {
+            return Err(format!("Insufficient balance. Have: {}, Need: {}", self.entity.balance, total));
+        }
+
+        let mut transactions = Vec::new();
+        let mut receipts = Vec::new();
+        for group in payments.chunks(group_size) {
+            let transaction = if group.len() == 1 {
+                Transaction::create_payment(&mut self.entity, &group[0].0, group[0].1, fee)?
+            } else {
+                let outputs = group.iter()
+                    .map(|(address, amount)| Output::new(address.clone(), *amount))
+                    .collect();
+                Transaction::create_multi_payment(&mut self.entity, outputs, fee)?
+            };
+
+            let id = transaction.id();
+            for (index, (recipient, amount)) in group.iter().enumerate() {
+                receipts.push(Receipt {
+                    recipient: recipient.clone(),
+                    amount: *amount,
+                    transaction_id: id.clone(),
+                    nonce: transaction.nonce,
+                    output_index: index,
+                });
+            }
+            transactions.push(transaction);
+        }
+
+        Ok(BatchSend {
+            transactions: transactions,
+            receipts: receipts,
+        })
+    }
+}
+
+/// Returns what `address` can spend on `chain` and its next nonce, counting
+/// its pending transactions.
+fn available(chain: &Blockchain, address: &str) -> (Amount, u64) {
+    let mut spendable = chain.state().get_spendable_balance(address);
+    let mut nonce = chain.get_nonce(address);
+    for transaction in chain.mempool().transactions() {
+        if transaction.sender_address == address {
+            spendable -= transaction.total_cost();
+            nonce = nonce.max(transaction.nonce + 1);
+        }
+    }
+    (spendable, nonce)
+}
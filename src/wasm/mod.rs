@@ -0,0 +1 @@
+pub mod wasm;
\ No newline at end of file
@@ -0,0 +1,228 @@
+use wasm_bindgen::prelude::*;
+use crate::block::block::Block;
+use crate::blockchain::blockchain::{Blockchain, DEFAULT_BLOCK_REWARD};
+use crate::codec::json::{self, JsonCodec, JsonValue};
+use crate::entity::entity::Entity;
+use crate::helpers::random::entropy;
+use crate::simulation::rng::SimRng;
+use crate::transactions::transactions::{Transaction, TransactionExt};
+use crate::wallet::wallet::Wallet;
+
+fn to_js_error(error: String) -> JsError {
+    JsError::new(&error)
+}
+
+/// A signed transaction, as seen from JavaScript.
+#[wasm_bindgen(js_name = Transaction)]
+pub struct WasmTransaction {
+    inner: Transaction,
+}
+
+#[wasm_bindgen(js_class = Transaction)]
+impl WasmTransaction {
+    /// Parses a transaction from its JSON form.
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<WasmTransaction, JsError> {
+        <Transaction as TransactionExt>::from_json(json).map(|inner| WasmTransaction { inner: inner }).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> String {
+        TransactionExt::to_json(&self.inner)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn id(&self) -> String {
+        self.inner.id()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn sender(&self) -> String {
+        self.inner.sender_address.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn receiver(&self) -> String {
+        self.inner.receiver_address.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn amount(&self) -> f64 {
+        self.inner.amount
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn fee(&self) -> f64 {
+        self.inner.fee
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn nonce(&self) -> u64 {
+        self.inner.nonce
+    }
+}
+
+/// A blockchain, as seen from JavaScript.
+#[wasm_bindgen(js_name = Blockchain)]
+pub struct WasmBlockchain {
+    inner: Blockchain,
+}
+
+#[wasm_bindgen(js_class = Blockchain)]
+impl WasmBlockchain {
+    /// Creates a chain with its genesis block (difficulty 3 and the default reward when omitted).
+    #[wasm_bindgen(constructor)]
+    pub fn new(difficulty: Option<u32>, block_reward: Option<f64>) -> WasmBlockchain {
+        WasmBlockchain {
+            inner: Blockchain::with_settings(difficulty.unwrap_or(3), block_reward.unwrap_or(DEFAULT_BLOCK_REWARD)),
+        }
+    }
+
+    /// Rebuilds a chain from the JSON array of its blocks, validating every block.
+    #[wasm_bindgen(js_name = fromBlocksJson)]
+    pub fn from_blocks_json(difficulty: u32, block_reward: f64, blocks: &str) -> Result<WasmBlockchain, JsError> {
+        let blocks = json::parse(blocks).and_then(|value| Vec::<Block>::from_json(&value)).map_err(to_js_error)?;
+        Blockchain::from_blocks(difficulty, block_reward, blocks)
+            .map(|inner| WasmBlockchain { inner: inner })
+            .map_err(to_js_error)
+    }
+
+    /// Returns the blocks as a JSON array (the input of `fromBlocksJson`).
+    #[wasm_bindgen(js_name = blocksJson)]
+    pub fn blocks_json(&self) -> String {
+        JsonValue::Array(self.inner.get_blocks().iter().map(JsonCodec::to_json).collect()).to_string()
+    }
+
+    /// Returns the block at `height` as JSON, if there is one.
+    #[wasm_bindgen(js_name = blockJson)]
+    pub fn block_json(&self, height: usize) -> Option<String> {
+        self.inner.get_blocks().get(height).map(|block| block.to_json().to_string())
+    }
+
+    /// Height of the tip (the genesis block is 0).
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> usize {
+        self.inner.get_blocks().len() - 1
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn difficulty(&self) -> u32 {
+        self.inner.get_difficulty()
+    }
+
+    #[wasm_bindgen(getter, js_name = baseFee)]
+    pub fn base_fee(&self) -> f64 {
+        self.inner.get_base_fee()
+    }
+
+    #[wasm_bindgen(getter, js_name = latestHash)]
+    pub fn latest_hash(&self) -> String {
+        self.inner.get_latest_hash()
+    }
+
+    pub fn balance(&self, address: &str) -> f64 {
+        self.inner.get_balance(address)
+    }
+
+    pub fn nonce(&self, address: &str) -> u64 {
+        self.inner.get_nonce(address)
+    }
+
+    /// Adds a signed transaction to the mempool.
+    pub fn submit(&mut self, transaction: &WasmTransaction) -> Result<(), JsError> {
+        self.inner.submit_transaction(transaction.inner.clone()).map_err(to_js_error)
+    }
+
+    /// Mines a block from the mempool for `miner` and returns how many transactions it took.
+    pub fn mine(&mut self, miner: &str) -> Result<usize, JsError> {
+        self.inner.add_block_from_mempool(miner).map_err(to_js_error)
+    }
+
+    /// Throws an error naming the first problem if the chain is invalid.
+    pub fn validate(&self) -> Result<(), JsError> {
+        self.inner.validate().map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(js_name = isValid)]
+    pub fn is_valid(&self) -> bool {
+        self.inner.is_valid()
+    }
+}
+
+/// A wallet with freshly generated keys, as seen from JavaScript.
+#[wasm_bindgen(js_name = Wallet)]
+pub struct WasmWallet {
+    inner: Wallet,
+}
+
+#[wasm_bindgen(js_class = Wallet)]
+impl WasmWallet {
+    /// Generates the keys of `address`; the same `seed` always gives the same keys.
+    #[wasm_bindgen(constructor)]
+    pub fn new(address: String, seed: Option<u32>) -> WasmWallet {
+        let seed = seed.map(u64::from).unwrap_or_else(entropy);
+        WasmWallet {
+            inner: Wallet::new(Entity::generate(address, 0.0, &mut SimRng::new(seed))),
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn address(&self) -> String {
+        self.inner.address().to_string()
+    }
+
+    /// Balance as of the last `sync`.
+    #[wasm_bindgen(getter)]
+    pub fn balance(&self) -> f64 {
+        self.inner.entity.balance
+    }
+
+    /// Refreshes the balance and nonce from `chain`, counting the wallet's pending transactions.
+    pub fn sync(&mut self, chain: &WasmBlockchain) {
+        self.inner.sync_pending(&chain.inner);
+    }
+
+    /// Signs a payment of `amount` to a name or an address (`fee` defaults to twice the base fee).
+    ///
+    /// The wallet syncs with `chain` first; the transaction still has to be
+    /// given to `Blockchain.submit`.
+    pub fn pay(&mut self, chain: &WasmBlockchain, recipient: &str, amount: f64, fee: Option<f64>) -> Result<WasmTransaction, JsError> {
+        self.inner.sync_pending(&chain.inner);
+        let receiver = self.inner.resolve(&chain.inner, recipient).map_err(to_js_error)?;
+        let fee = fee.unwrap_or(chain.inner.get_base_fee() * 2.0);
+        Transaction::create_payment(&mut self.inner.entity, &receiver, amount, fee)
+            .map(|inner| WasmTransaction { inner: inner })
+            .map_err(to_js_error)
+    }
+}
+
+/// Validates a chain given as the JSON array of its blocks.
+///
+/// Returns `undefined` if every block is valid, the error message otherwise.
+///
+/// # Example
+///
+/// ```
+/// use blockc::wasm::wasm::{validate_chain, WasmBlockchain, WasmWallet};
+///
+/// let mut chain = WasmBlockchain::new(Some(1), None);
+/// chain.mine("Alice").unwrap();
+/// let mut alice = WasmWallet::new("Alice".to_string(), Some(7));
+/// let payment = alice.pay(&chain, "Bob", 10.0, None).unwrap();
+/// chain.submit(&payment).unwrap();
+/// chain.mine("Miner").unwrap();
+/// assert_eq!(chain.balance("Bob"), 10.0);
+///
+/// let blocks = chain.blocks_json();
+/// assert_eq!(validate_chain(1, 50.0, &blocks), None);
+/// let forged = blocks.replacen("\"amount\":10.0", "\"amount\":1000.0", 1);
+/// assert!(validate_chain(1, 50.0, &forged).is_some());
+/// ```
+#[wasm_bindgen(js_name = validateChain)]
+pub fn validate_chain(difficulty: u32, block_reward: f64, blocks: &str) -> Option<String> {
+    let blocks = match json::parse(blocks).and_then(|value| Vec::<Block>::from_json(&value)) {
+        Ok(blocks) => blocks,
+        Err(error) => return Some(error),
+    };
+    Blockchain::from_blocks(difficulty, block_reward, blocks).err()
+}